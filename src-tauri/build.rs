@@ -1,4 +1,5 @@
 fn main() {
+    #[cfg(feature = "gui")]
     tauri_build::build();
 
     // Windows: Embed Common Controls v6 manifest for test binaries
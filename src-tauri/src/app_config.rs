@@ -197,6 +197,69 @@ impl SkillApps {
     }
 }
 
+/// 共享提示词应用状态（标记同步到哪些客户端的提示词文件）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PromptApps {
+    #[serde(default)]
+    pub claude: bool,
+    #[serde(default)]
+    pub codex: bool,
+    #[serde(default)]
+    pub gemini: bool,
+    #[serde(default)]
+    pub grokbuild: bool,
+    #[serde(default)]
+    pub opencode: bool,
+    #[serde(default)]
+    pub openclaw: bool,
+    #[serde(default)]
+    pub hermes: bool,
+}
+
+impl PromptApps {
+    /// 检查指定应用是否启用
+    pub fn is_enabled_for(&self, app: &AppType) -> bool {
+        match app {
+            AppType::Claude => self.claude,
+            AppType::Codex => self.codex,
+            AppType::Gemini => self.gemini,
+            AppType::GrokBuild => self.grokbuild,
+            AppType::OpenCode => self.opencode,
+            AppType::OpenClaw => self.openclaw,
+            AppType::Hermes => self.hermes,
+            AppType::Pi => false, // Pi 的 AGENTS.md 由其原生提示词管理
+            AppType::ClaudeDesktop => false,
+        }
+    }
+
+    /// 设置指定应用的启用状态
+    pub fn set_enabled_for(&mut self, app: &AppType, enabled: bool) {
+        match app {
+            AppType::Claude => self.claude = enabled,
+            AppType::Codex => self.codex = enabled,
+            AppType::Gemini => self.gemini = enabled,
+            AppType::GrokBuild => self.grokbuild = enabled,
+            AppType::OpenCode => self.opencode = enabled,
+            AppType::OpenClaw => self.openclaw = enabled,
+            AppType::Hermes => self.hermes = enabled,
+            AppType::Pi => {}
+            AppType::ClaudeDesktop => {}
+        }
+    }
+
+    /// 获取所有启用的应用列表
+    pub fn enabled_apps(&self) -> Vec<AppType> {
+        AppType::all()
+            .filter(|app| self.is_enabled_for(app))
+            .collect()
+    }
+
+    /// 检查是否所有应用都未启用
+    pub fn is_empty(&self) -> bool {
+        self.enabled_apps().is_empty()
+    }
+}
+
 /// 已安装的 Skill（v3.10.0+ 统一结构）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -268,6 +331,71 @@ pub struct McpServer {
     pub tags: Vec<String>,
 }
 
+/// MCP 服务器探测结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpProbeStatus {
+    /// 握手完成
+    Ok,
+    /// 启动/连接失败或协议错误
+    Failed,
+    /// 握手未在时限内完成
+    Timeout,
+}
+
+impl McpProbeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            McpProbeStatus::Ok => "ok",
+            McpProbeStatus::Failed => "failed",
+            McpProbeStatus::Timeout => "timeout",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "ok" => McpProbeStatus::Ok,
+            "timeout" => McpProbeStatus::Timeout,
+            _ => McpProbeStatus::Failed,
+        }
+    }
+}
+
+/// 最近一次 MCP 服务器探测结果（按服务器 id 与 `McpServer` 并列存储）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProbeResult {
+    pub server_id: String,
+    pub status: McpProbeStatus,
+    /// `initialize` 返回的 serverInfo.name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    /// `initialize` 返回的 serverInfo.version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    /// 服务器协商的协议版本
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default)]
+    pub resources: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub probed_at: i64,
+}
+
+/// 本地密钥库条目（只暴露名称，值永不返回前端）
+///
+/// MCP 服务器定义中的 `${secret:NAME}` 在写入各应用 live 配置时才从这里解析
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSecretInfo {
+    pub name: String,
+    pub updated_at: i64,
+}
+
 /// MCP 配置：单客户端维度（v3.6.x 及以前，保留用于向后兼容）
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct McpConfig {
@@ -828,6 +956,7 @@ impl MultiAppConfig {
             content,
             description: Some("Automatically imported on first launch".to_string()),
             enabled: true, // 自动启用
+            sort_index: None,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
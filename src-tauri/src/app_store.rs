@@ -1,11 +1,15 @@
+#[cfg(feature = "gui")]
 use serde_json::Value;
 use std::path::PathBuf;
 use std::sync::{OnceLock, RwLock};
+#[cfg(feature = "gui")]
 use tauri_plugin_store::StoreExt;
 
+#[cfg(feature = "gui")]
 use crate::error::AppError;
 
 /// Store 中的键名
+#[cfg(feature = "gui")]
 const STORE_KEY_APP_CONFIG_DIR: &str = "app_config_dir_override";
 
 /// 缓存当前的 app_config_dir 覆盖路径，避免存储 AppHandle
@@ -15,6 +19,7 @@ fn override_cache() -> &'static RwLock<Option<PathBuf>> {
     APP_CONFIG_DIR_OVERRIDE.get_or_init(|| RwLock::new(None))
 }
 
+#[cfg(feature = "gui")]
 fn update_cached_override(value: Option<PathBuf>) {
     if let Ok(mut guard) = override_cache().write() {
         *guard = value;
@@ -26,6 +31,7 @@ pub fn get_app_config_dir_override() -> Option<PathBuf> {
     override_cache().read().ok()?.clone()
 }
 
+#[cfg(feature = "gui")]
 fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
     let store = match app.store_builder("app_paths.json").build() {
         Ok(store) => store,
@@ -64,6 +70,7 @@ fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
 }
 
 /// 从 Store 刷新 app_config_dir 覆盖值并更新缓存
+#[cfg(feature = "gui")]
 pub fn refresh_app_config_dir_override(app: &tauri::AppHandle) -> Option<PathBuf> {
     let value = read_override_from_store(app);
     update_cached_override(value.clone());
@@ -71,6 +78,7 @@ pub fn refresh_app_config_dir_override(app: &tauri::AppHandle) -> Option<PathBuf
 }
 
 /// 写入 app_config_dir 到 Tauri Store
+#[cfg(feature = "gui")]
 pub fn set_app_config_dir_to_store(
     app: &tauri::AppHandle,
     path: Option<&str>,
@@ -106,6 +114,7 @@ pub fn set_app_config_dir_to_store(
 }
 
 /// 解析路径，支持 ~ 开头的相对路径
+#[cfg(feature = "gui")]
 fn resolve_path(raw: &str) -> PathBuf {
     if raw == "~" {
         if let Some(home) = dirs::home_dir() {
@@ -125,6 +134,7 @@ fn resolve_path(raw: &str) -> PathBuf {
 }
 
 /// 从旧的 settings.json 迁移 app_config_dir 到 Store
+#[cfg(feature = "gui")]
 pub fn migrate_app_config_dir_from_settings(app: &tauri::AppHandle) -> Result<(), AppError> {
     // app_config_dir 已从 settings.json 移除，此函数保留但不再执行迁移
     // 如果用户在旧版本设置过 app_config_dir，需要在 Store 中手动配置
@@ -0,0 +1,5 @@
+//! 无界面命令行入口，复用桌面应用的数据库与 service 层。
+
+fn main() {
+    std::process::exit(cc_switch_lib::cli::run());
+}
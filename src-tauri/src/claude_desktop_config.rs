@@ -76,6 +76,7 @@ pub const DEFAULT_PROXY_ROUTES: &[ClaudeDesktopDefaultRoute] = &[
 struct ClaudeDesktopPaths {
     normal_config_path: PathBuf,
     threep_config_path: PathBuf,
+    #[cfg(feature = "gui")]
     config_library_path: PathBuf,
     profile_path: PathBuf,
     meta_path: PathBuf,
@@ -93,6 +94,7 @@ struct FileSnapshot {
     content: Option<Vec<u8>>,
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeDesktopStatus {
@@ -130,6 +132,7 @@ pub fn apply_provider(db: &Database, provider: &Provider) -> Result<(), AppError
     apply_provider_to_paths(db, provider, &paths)
 }
 
+#[cfg(feature = "gui")]
 pub fn get_status(db: &Database, proxy_running: bool) -> Result<ClaudeDesktopStatus, AppError> {
     if !is_supported_platform() {
         return Ok(ClaudeDesktopStatus {
@@ -209,14 +212,17 @@ pub fn get_status(db: &Database, proxy_running: bool) -> Result<ClaudeDesktopSta
     })
 }
 
+#[cfg(feature = "gui")]
 pub fn get_config_library_path() -> Result<PathBuf, AppError> {
     Ok(current_platform_paths()?.config_library_path)
 }
 
+#[cfg(feature = "gui")]
 pub fn default_proxy_routes() -> Vec<ClaudeDesktopDefaultRoute> {
     DEFAULT_PROXY_ROUTES.to_vec()
 }
 
+#[cfg(feature = "gui")]
 pub fn is_compatible_direct_provider(provider: &Provider) -> bool {
     validate_direct_provider(provider).is_ok()
 }
@@ -1190,6 +1196,7 @@ fn write_meta(path: &Path, applied_profile_id: Option<&str>) -> Result<(), AppEr
     write_json_file(path, &value)
 }
 
+#[cfg(feature = "gui")]
 fn read_applied_id(path: &Path) -> Option<String> {
     read_json_or_empty(path).ok().and_then(|value| {
         value
@@ -1199,6 +1206,7 @@ fn read_applied_id(path: &Path) -> Option<String> {
     })
 }
 
+#[cfg(feature = "gui")]
 fn meta_has_profile_entry(path: &Path) -> bool {
     read_json_or_empty(path)
         .ok()
@@ -1210,6 +1218,7 @@ fn meta_has_profile_entry(path: &Path) -> bool {
         })
 }
 
+#[cfg(feature = "gui")]
 fn is_supported_platform() -> bool {
     cfg!(any(target_os = "macos", windows))
 }
@@ -1,5 +1,7 @@
+#[cfg(feature = "gui")]
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+#[cfg(feature = "gui")]
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -89,6 +91,7 @@ fn is_wsl_path(_path: &Path) -> bool {
     false
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpStatus {
@@ -119,6 +122,7 @@ fn write_json_value(path: &Path, value: &Value) -> Result<(), AppError> {
     atomic_write(path, json.as_bytes())
 }
 
+#[cfg(feature = "gui")]
 pub fn get_mcp_status() -> Result<McpStatus, AppError> {
     let path = user_config_path();
     let (exists, count) = if path.exists() {
@@ -147,6 +151,7 @@ pub fn read_mcp_json() -> Result<Option<String>, AppError> {
 
 /// 在 ~/.claude.json 根对象写入 hasCompletedOnboarding=true（用于跳过 Claude Code 初次安装确认）
 /// 仅增量写入该字段，其他字段保持不变
+#[cfg(feature = "gui")]
 pub fn set_has_completed_onboarding() -> Result<bool, AppError> {
     let path = user_config_path();
     let mut root = if path.exists() {
@@ -174,6 +179,7 @@ pub fn set_has_completed_onboarding() -> Result<bool, AppError> {
 
 /// 删除 ~/.claude.json 根对象的 hasCompletedOnboarding 字段（恢复 Claude Code 初次安装确认）
 /// 仅增量删除该字段，其他字段保持不变
+#[cfg(feature = "gui")]
 pub fn clear_has_completed_onboarding() -> Result<bool, AppError> {
     let path = user_config_path();
     if !path.exists() {
@@ -194,6 +200,7 @@ pub fn clear_has_completed_onboarding() -> Result<bool, AppError> {
     Ok(true)
 }
 
+#[cfg(feature = "gui")]
 pub fn upsert_mcp_server(id: &str, spec: Value) -> Result<bool, AppError> {
     if id.trim().is_empty() {
         return Err(AppError::InvalidInput("MCP 服务器 ID 不能为空".into()));
@@ -266,6 +273,7 @@ pub fn upsert_mcp_server(id: &str, spec: Value) -> Result<bool, AppError> {
     Ok(true)
 }
 
+#[cfg(feature = "gui")]
 pub fn delete_mcp_server(id: &str) -> Result<bool, AppError> {
     if id.trim().is_empty() {
         return Err(AppError::InvalidInput("MCP 服务器 ID 不能为空".into()));
@@ -286,6 +294,7 @@ pub fn delete_mcp_server(id: &str) -> Result<bool, AppError> {
     Ok(true)
 }
 
+#[cfg(feature = "gui")]
 pub fn validate_command_in_path(cmd: &str) -> Result<bool, AppError> {
     if cmd.trim().is_empty() {
         return Ok(false);
@@ -354,42 +363,12 @@ pub fn set_mcp_servers_map(
 
     // 构建 mcpServers 对象：移除 UI 辅助字段（enabled/source），仅保留实际 MCP 规范
     // 检测目标路径是否为 WSL，若是则跳过 cmd /c 包装
-    let is_wsl_target = is_wsl_path(&path);
-    if is_wsl_target {
+    if is_wsl_path(&path) {
         log::info!("检测到 WSL 路径，跳过 cmd /c 包装: {}", path.display());
     }
     let mut out: Map<String, Value> = Map::new();
     for (id, spec) in servers.iter() {
-        let mut obj = if let Some(map) = spec.as_object() {
-            map.clone()
-        } else {
-            return Err(AppError::McpValidation(format!(
-                "MCP 服务器 '{id}' 不是对象"
-            )));
-        };
-
-        if let Some(server_val) = obj.remove("server") {
-            let server_obj = server_val.as_object().cloned().ok_or_else(|| {
-                AppError::McpValidation(format!("MCP 服务器 '{id}' server 字段不是对象"))
-            })?;
-            obj = server_obj;
-        }
-
-        obj.remove("enabled");
-        obj.remove("source");
-        obj.remove("id");
-        obj.remove("name");
-        obj.remove("description");
-        obj.remove("tags");
-        obj.remove("homepage");
-        obj.remove("docs");
-
-        // Windows 平台自动包装 npx/npm 等命令为 cmd /c 格式（WSL 路径除外）
-        if !is_wsl_target {
-            wrap_command_for_windows(&mut obj);
-        }
-
-        out.insert(id.clone(), Value::Object(obj));
+        out.insert(id.clone(), to_claude_server_entry(id, spec)?);
     }
 
     {
@@ -403,6 +382,42 @@ pub fn set_mcp_servers_map(
     Ok(())
 }
 
+/// 把统一结构的服务器定义转换为 ~/.claude.json 中的 mcpServers 条目
+///
+/// ~/.claude.json 位于 WSL 路径时跳过 Windows 的 `cmd /c` 包装
+pub(crate) fn to_claude_server_entry(id: &str, spec: &Value) -> Result<Value, AppError> {
+    let mut obj = if let Some(map) = spec.as_object() {
+        map.clone()
+    } else {
+        return Err(AppError::McpValidation(format!(
+            "MCP 服务器 '{id}' 不是对象"
+        )));
+    };
+
+    if let Some(server_val) = obj.remove("server") {
+        let server_obj = server_val.as_object().cloned().ok_or_else(|| {
+            AppError::McpValidation(format!("MCP 服务器 '{id}' server 字段不是对象"))
+        })?;
+        obj = server_obj;
+    }
+
+    obj.remove("enabled");
+    obj.remove("source");
+    obj.remove("id");
+    obj.remove("name");
+    obj.remove("description");
+    obj.remove("tags");
+    obj.remove("homepage");
+    obj.remove("docs");
+
+    // Windows 平台自动包装 npx/npm 等命令为 cmd /c 格式（WSL 路径除外）
+    if !is_wsl_path(&user_config_path()) {
+        wrap_command_for_windows(&mut obj);
+    }
+
+    Ok(Value::Object(obj))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
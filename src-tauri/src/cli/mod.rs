@@ -0,0 +1,547 @@
+//! 无界面命令行入口（`cc-switch-cli`）
+//!
+//! 与桌面应用共享同一个数据库（`~/.cc-switch/cc-switch.db`）和 service 层，
+//! 供远程开发机、容器等没有 GUI 的环境使用。所有子命令支持全局 `--json`，
+//! 输出单个 JSON 值便于脚本处理；出错时退出码为 1，错误信息写到 stderr。
+//! `serve` 子命令以前台守护进程方式运行本地代理，见 [`serve`]。
+
+mod serve;
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::profile::{ProfileScope, ProfileService};
+use crate::services::{McpService, PromptService, ProviderService, SkillService};
+use crate::store::AppState;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "cc-switch-cli",
+    version,
+    about = "Headless CC Switch: manage providers, MCP servers, prompts, skills and profiles"
+)]
+pub struct Cli {
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Manage providers
+    #[command(subcommand)]
+    Provider(ProviderCommand),
+    /// Manage MCP servers
+    #[command(subcommand)]
+    Mcp(McpCommand),
+    /// Manage prompts
+    #[command(subcommand)]
+    Prompt(PromptCommand),
+    /// Manage installed skills
+    #[command(subcommand)]
+    Skill(SkillCommand),
+    /// Manage profiles
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+    /// Import a ccswitch:// deep link (provider, prompt, MCP or skill)
+    Import {
+        /// The ccswitch:// URL
+        url: String,
+    },
+    /// Print a usage summary for the last N days
+    Usage {
+        /// Only include one app (claude, codex, gemini, ...)
+        #[arg(long)]
+        app: Option<String>,
+        /// Number of days to include
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+    /// Manage the master key that encrypts API keys and sync credentials
+    #[command(subcommand)]
+    Secrets(SecretsCommand),
+    /// Run the local proxy in the foreground (SIGHUP reloads config, SIGTERM stops)
+    Serve {
+        /// Pidfile path [default: ~/.cc-switch/proxy.pid]
+        #[arg(long)]
+        pidfile: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ProviderCommand {
+    /// List providers of an app (current one is marked with *)
+    List { app: String },
+    /// Print the current provider id of an app
+    Current { app: String },
+    /// Switch the current provider of an app
+    Switch { app: String, id: String },
+    /// Add a provider from a JSON file (same shape as the desktop export)
+    Add {
+        app: String,
+        /// Path to the provider JSON, or `-` to read from stdin
+        file: String,
+        /// Only store the provider, do not write it into the app's live config
+        #[arg(long)]
+        no_live: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SecretsCommand {
+    /// Show whether the master key is unlocked
+    Status,
+    /// Protect the master key with a passphrase read from stdin (empty input removes it)
+    SetPassphrase,
+    /// Set the passphrase that end-to-end encrypts sync snapshots, read from stdin
+    /// (empty input uploads plaintext snapshots again)
+    SyncPassphrase {
+        /// Sync transport: webdav, s3, folder or git
+        transport: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum McpCommand {
+    /// List MCP servers and the apps they are enabled for
+    List,
+    /// Enable an MCP server for an app
+    Enable { id: String, app: String },
+    /// Disable an MCP server for an app
+    Disable { id: String, app: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum PromptCommand {
+    /// List prompts of an app
+    List { app: String },
+    /// Enable a prompt (writes it to the app's prompt file)
+    Enable { app: String, id: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum SkillCommand {
+    /// List installed skills
+    List,
+    /// Enable a skill for an app
+    Enable { id: String, app: String },
+    /// Disable a skill for an app
+    Disable { id: String, app: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum ProfileCommand {
+    /// List profiles
+    List,
+    /// Apply a profile
+    Apply {
+        id: String,
+        /// Profile scope: claude, claude-desktop or codex
+        #[arg(long, default_value = "claude")]
+        scope: String,
+    },
+}
+
+/// 命令结果：JSON 形式 + 面向人的文本形式
+struct Output {
+    json: Value,
+    text: String,
+}
+
+impl Output {
+    fn new(json: Value, text: impl Into<String>) -> Self {
+        Self {
+            json,
+            text: text.into(),
+        }
+    }
+}
+
+/// 解析命令行并执行，返回进程退出码
+pub fn run() -> i32 {
+    let cli = Cli::parse();
+    let as_json = cli.json;
+
+    let result = open_state().and_then(|state| execute(&state, cli.command));
+    match result {
+        Ok(output) => {
+            if as_json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&output.json).unwrap_or_default()
+                );
+            } else if !output.text.is_empty() {
+                println!("{}", output.text);
+            }
+            0
+        }
+        Err(e) => {
+            if as_json {
+                eprintln!("{}", json!({ "error": e.to_string() }));
+            } else {
+                eprintln!("error: {e}");
+            }
+            1
+        }
+    }
+}
+
+fn open_state() -> Result<AppState, AppError> {
+    let db_path = crate::config::get_app_config_dir().join("cc-switch.db");
+    if let Some(version) = Database::stored_user_version_exceeds_supported(&db_path)? {
+        return Err(AppError::Database(format!(
+            "database schema v{version} is newer than supported v{}; upgrade cc-switch first",
+            crate::database::SCHEMA_VERSION
+        )));
+    }
+    let db = Arc::new(Database::init()?);
+    if let Err(e) = db.encrypt_plaintext_provider_secrets() {
+        eprintln!("warning: failed to encrypt plaintext provider secrets: {e}");
+    }
+    Ok(AppState::new(db))
+}
+
+fn parse_app(app: &str) -> Result<AppType, AppError> {
+    AppType::from_str(app)
+}
+
+fn execute(state: &AppState, command: Command) -> Result<Output, AppError> {
+    match command {
+        Command::Provider(cmd) => provider_command(state, cmd),
+        Command::Mcp(cmd) => mcp_command(state, cmd),
+        Command::Prompt(cmd) => prompt_command(state, cmd),
+        Command::Skill(cmd) => skill_command(state, cmd),
+        Command::Profile(cmd) => profile_command(state, cmd),
+        Command::Import { url } => {
+            let request = crate::deeplink::parse_deeplink_url(&url)?;
+            let result = crate::deeplink::import_resource_from_deeplink(state, request)?;
+            let text = format!(
+                "Imported {} {}",
+                result["type"].as_str().unwrap_or("resource"),
+                result
+                    .get("id")
+                    .or_else(|| result.get("key"))
+                    .or_else(|| result.get("importedIds"))
+                    .map(|v| v.to_string())
+                    .unwrap_or_default()
+            );
+            Ok(Output::new(result, text))
+        }
+        Command::Usage { app, days } => usage_command(state, app, days),
+        Command::Secrets(cmd) => secrets_command(cmd),
+        Command::Serve { pidfile } => serve::run(state.clone(), pidfile),
+    }
+}
+
+fn provider_command(state: &AppState, cmd: ProviderCommand) -> Result<Output, AppError> {
+    match cmd {
+        ProviderCommand::List { app } => {
+            let app_type = parse_app(&app)?;
+            let providers = ProviderService::list(state, app_type.clone())?;
+            let current = ProviderService::current(state, app_type)?;
+            let text = providers
+                .values()
+                .map(|p| {
+                    let marker = if p.id == current { "*" } else { " " };
+                    format!("{marker} {}\t{}", p.id, p.name)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let list: Vec<&Provider> = providers.values().collect();
+            Ok(Output::new(
+                json!({ "current": current, "providers": list }),
+                text,
+            ))
+        }
+        ProviderCommand::Current { app } => {
+            let current = ProviderService::current(state, parse_app(&app)?)?;
+            Ok(Output::new(json!({ "current": current }), current))
+        }
+        ProviderCommand::Switch { app, id } => {
+            let result = ProviderService::switch(state, parse_app(&app)?, &id)?;
+            let mut text = format!("Switched {app} to {id}");
+            for warning in &result.warnings {
+                text.push_str(&format!("\nwarning: {warning}"));
+            }
+            Ok(Output::new(
+                json!({ "id": id, "warnings": result.warnings }),
+                text,
+            ))
+        }
+        ProviderCommand::Add { app, file, no_live } => {
+            let raw = if file == "-" {
+                let mut buf = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buf)
+                    .map_err(|e| AppError::Message(format!("read stdin: {e}")))?;
+                buf
+            } else {
+                std::fs::read_to_string(&file).map_err(|e| AppError::io(&file, e))?
+            };
+            let provider: Provider = serde_json::from_str(&raw)
+                .map_err(|e| AppError::InvalidInput(format!("invalid provider JSON: {e}")))?;
+            let id = provider.id.clone();
+            ProviderService::add(state, parse_app(&app)?, provider, !no_live)?;
+            Ok(Output::new(
+                json!({ "id": id }),
+                format!("Added provider {id}"),
+            ))
+        }
+    }
+}
+
+fn mcp_command(state: &AppState, cmd: McpCommand) -> Result<Output, AppError> {
+    let (id, app, enabled) = match cmd {
+        McpCommand::List => {
+            let servers = McpService::get_all_servers(state)?;
+            let text = servers
+                .values()
+                .map(|s| {
+                    let apps: Vec<String> = s
+                        .apps
+                        .enabled_apps()
+                        .iter()
+                        .map(|a| a.as_str().to_string())
+                        .collect();
+                    format!("{}\t{}\t[{}]", s.id, s.name, apps.join(","))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let list: Vec<_> = servers.values().collect();
+            return Ok(Output::new(json!(list), text));
+        }
+        McpCommand::Enable { id, app } => (id, app, true),
+        McpCommand::Disable { id, app } => (id, app, false),
+    };
+    McpService::toggle_app(state, &id, parse_app(&app)?, enabled)?;
+    Ok(toggle_output("MCP server", &id, &app, enabled))
+}
+
+fn prompt_command(state: &AppState, cmd: PromptCommand) -> Result<Output, AppError> {
+    match cmd {
+        PromptCommand::List { app } => {
+            let prompts = PromptService::get_prompts(state, parse_app(&app)?)?;
+            let text = prompts
+                .values()
+                .map(|p| {
+                    let marker = if p.enabled { "*" } else { " " };
+                    format!("{marker} {}\t{}", p.id, p.name)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let list: Vec<_> = prompts.values().collect();
+            Ok(Output::new(json!(list), text))
+        }
+        PromptCommand::Enable { app, id } => {
+            PromptService::enable_prompt(state, parse_app(&app)?, &id)?;
+            Ok(toggle_output("prompt", &id, &app, true))
+        }
+    }
+}
+
+fn skill_command(state: &AppState, cmd: SkillCommand) -> Result<Output, AppError> {
+    let (id, app, enabled) = match cmd {
+        SkillCommand::List => {
+            let skills = SkillService::get_all_installed(&state.db)
+                .map_err(|e| AppError::Message(e.to_string()))?;
+            let text = skills
+                .iter()
+                .map(|s| {
+                    let apps: Vec<String> = s
+                        .apps
+                        .enabled_apps()
+                        .iter()
+                        .map(|a| a.as_str().to_string())
+                        .collect();
+                    format!("{}\t{}\t[{}]", s.id, s.name, apps.join(","))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            return Ok(Output::new(json!(skills), text));
+        }
+        SkillCommand::Enable { id, app } => (id, app, true),
+        SkillCommand::Disable { id, app } => (id, app, false),
+    };
+    SkillService::toggle_app(&state.db, &id, &parse_app(&app)?, enabled)
+        .map_err(|e| AppError::Message(e.to_string()))?;
+    Ok(toggle_output("skill", &id, &app, enabled))
+}
+
+fn secrets_command(cmd: SecretsCommand) -> Result<Output, AppError> {
+    match cmd {
+        SecretsCommand::Status => {
+            let unlocked = crate::secrets::is_available();
+            let text = if unlocked {
+                "Master key: unlocked".to_string()
+            } else {
+                format!(
+                    "Master key: locked (set {} to unlock)",
+                    crate::secrets::PASSPHRASE_ENV
+                )
+            };
+            Ok(Output::new(json!({ "unlocked": unlocked }), text))
+        }
+        SecretsCommand::SetPassphrase => {
+            let line = read_stdin_line()?;
+            let passphrase = (!line.is_empty()).then_some(line.as_str());
+            crate::secrets::set_passphrase(passphrase)?;
+            let protected = passphrase.is_some();
+            let text = if protected {
+                format!(
+                    "Master key is now passphrase-protected; export {} before starting cc-switch",
+                    crate::secrets::PASSPHRASE_ENV
+                )
+            } else {
+                "Master key passphrase removed".to_string()
+            };
+            Ok(Output::new(
+                json!({ "passphraseProtected": protected }),
+                text,
+            ))
+        }
+        SecretsCommand::SyncPassphrase { transport } => {
+            let passphrase = read_stdin_line()?;
+            let updated = match transport.as_str() {
+                "webdav" => crate::settings::set_webdav_sync_passphrase(&passphrase)?,
+                "s3" => crate::settings::set_s3_sync_passphrase(&passphrase)?,
+                "folder" => crate::settings::set_folder_sync_passphrase(&passphrase)?,
+                "git" => crate::settings::set_git_sync_passphrase(&passphrase)?,
+                other => {
+                    return Err(AppError::InvalidInput(format!(
+                        "unknown sync transport: {other} (expected webdav, s3, folder or git)"
+                    )))
+                }
+            };
+            if !updated {
+                return Err(AppError::Message(format!(
+                    "{transport} sync is not configured yet"
+                )));
+            }
+            let encrypted = !passphrase.is_empty();
+            let text = if encrypted {
+                format!("{transport} snapshots will be end-to-end encrypted from the next upload")
+            } else {
+                format!("{transport} snapshots will be uploaded unencrypted")
+            };
+            Ok(Output::new(
+                json!({ "transport": transport, "encrypted": encrypted }),
+                text,
+            ))
+        }
+    }
+}
+
+fn read_stdin_line() -> Result<String, AppError> {
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .map_err(|e| AppError::Message(format!("read stdin: {e}")))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn toggle_output(kind: &str, id: &str, app: &str, enabled: bool) -> Output {
+    let verb = if enabled { "Enabled" } else { "Disabled" };
+    Output::new(
+        json!({ "id": id, "app": app, "enabled": enabled }),
+        format!("{verb} {kind} {id} for {app}"),
+    )
+}
+
+fn profile_command(state: &AppState, cmd: ProfileCommand) -> Result<Output, AppError> {
+    match cmd {
+        ProfileCommand::List => {
+            let profiles: Vec<crate::services::profile::ProfileDto> = ProfileService::list(state)?
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            let mut current = serde_json::Map::new();
+            for scope in ProfileScope::ALL {
+                current.insert(
+                    scope.as_str().to_string(),
+                    json!(state.db.get_current_profile_id(scope.as_str())?),
+                );
+            }
+            let text = profiles
+                .iter()
+                .map(|p| format!("{}\t{}", p.id, p.name))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok(Output::new(
+                json!({ "profiles": profiles, "currentIds": current }),
+                text,
+            ))
+        }
+        ProfileCommand::Apply { id, scope } => {
+            let scope = ProfileScope::parse(&scope)?;
+            let (warnings, _) = ProfileService::apply(state, &id, scope)?;
+            let mut text = format!("Applied profile {id} ({})", scope.as_str());
+            for warning in &warnings {
+                text.push_str(&format!("\nwarning: {warning}"));
+            }
+            Ok(Output::new(
+                json!({ "id": id, "scope": scope.as_str(), "warnings": warnings }),
+                text,
+            ))
+        }
+    }
+}
+
+fn usage_command(state: &AppState, app: Option<String>, days: u32) -> Result<Output, AppError> {
+    let app_type = app.as_deref().map(parse_app).transpose()?;
+    let start = chrono::Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60;
+    let summary = state.db.get_usage_summary(
+        Some(start),
+        None,
+        app_type.as_ref().map(|a| a.as_str()),
+        None,
+        None,
+    )?;
+    let text = format!(
+        "Last {days} day(s){}\n  requests:      {}\n  cost (USD):    {}\n  input tokens:  {}\n  output tokens: {}\n  cache read:    {}\n  success rate:  {:.1}%",
+        app_type
+            .as_ref()
+            .map(|a| format!(" [{}]", a.as_str()))
+            .unwrap_or_default(),
+        summary.total_requests,
+        summary.total_cost,
+        summary.total_input_tokens,
+        summary.total_output_tokens,
+        summary.total_cache_read_tokens,
+        summary.success_rate,
+    );
+    Ok(Output::new(json!(summary), text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_global_json_flag_after_subcommand() {
+        let cli =
+            Cli::try_parse_from(["cc-switch-cli", "provider", "list", "claude", "--json"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(
+            cli.command,
+            Command::Provider(ProviderCommand::List { ref app }) if app == "claude"
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+//! `cc-switch-cli serve`：无界面代理守护进程
+//!
+//! 不依赖 Tauri 窗口生命周期：直接启动 `Database` → `ProxyService`
+//!（内含 `ProviderRouter` 与 `ProxyServer`，请求用量随代理处理写入数据库）。
+//! SIGTERM/SIGINT 优雅停止，SIGHUP 重新加载代理配置；启动时写 pidfile、退出时删除，
+//! 便于作为 systemd 用户服务运行（`Type=simple`，日志输出到 stderr 交给 journald）。
+
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use super::Output;
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 默认 pidfile 位置：`~/.cc-switch/proxy.pid`
+fn default_pidfile() -> PathBuf {
+    crate::config::get_app_config_dir().join("proxy.pid")
+}
+
+pub(super) fn run(state: AppState, pidfile: Option<PathBuf>) -> Result<Output, AppError> {
+    init_logger(&state);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| AppError::Message(format!("创建异步运行时失败: {e}")))?;
+
+    runtime.block_on(async {
+        // 先注册信号处理再占用 pidfile：接管后收到的 SIGTERM 不会走默认处理直接杀进程，
+        // 从而保证 pidfile 总能在退出时被清理
+        let mut signals = ShutdownSignals::install()?;
+        let pidfile = PidFile::acquire(pidfile.unwrap_or_else(default_pidfile))?;
+
+        let info = state
+            .proxy_service
+            .start()
+            .await
+            .map_err(AppError::Message)?;
+        log::info!(
+            "代理守护进程已启动: {}:{} (pid {}, pidfile {})",
+            info.address,
+            info.port,
+            std::process::id(),
+            pidfile.path.display()
+        );
+
+        signals.wait(&state).await;
+
+        log::info!("收到停止信号，正在关闭代理服务器");
+        if let Err(e) = state.proxy_service.stop().await {
+            log::warn!("停止代理服务器失败: {e}");
+        }
+        Ok::<_, AppError>(())
+    })?;
+
+    Ok(Output::new(json!({ "stopped": true }), String::new()))
+}
+
+/// 已注册的停止 / 重载信号
+#[cfg(unix)]
+struct ShutdownSignals {
+    terminate: tokio::signal::unix::Signal,
+    interrupt: tokio::signal::unix::Signal,
+    hangup: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl ShutdownSignals {
+    fn install() -> Result<Self, AppError> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let install = |kind: SignalKind| {
+            signal(kind).map_err(|e| AppError::Message(format!("注册信号处理失败: {e}")))
+        };
+        Ok(Self {
+            terminate: install(SignalKind::terminate())?,
+            interrupt: install(SignalKind::interrupt())?,
+            hangup: install(SignalKind::hangup())?,
+        })
+    }
+
+    /// 阻塞直到收到停止信号；期间的 SIGHUP 触发配置重载
+    async fn wait(&mut self, state: &AppState) {
+        loop {
+            tokio::select! {
+                _ = self.terminate.recv() => return,
+                _ = self.interrupt.recv() => return,
+                _ = self.hangup.recv() => {
+                    log::info!("收到 SIGHUP，重新加载代理配置");
+                    if let Err(e) = state.proxy_service.reload_runtime_config().await {
+                        log::warn!("重新加载代理配置失败: {e}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct ShutdownSignals;
+
+#[cfg(not(unix))]
+impl ShutdownSignals {
+    fn install() -> Result<Self, AppError> {
+        Ok(Self)
+    }
+
+    async fn wait(&mut self, _state: &AppState) {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::warn!("等待 Ctrl+C 失败: {e}");
+        }
+    }
+}
+
+/// 进程存活期间持有的 pidfile，Drop 时删除
+///
+/// Unix 下对 pidfile 持有 `flock` 排他锁：锁随进程退出自动释放，
+/// 残留文件无需按 pid 探测存活，并发启动也只有一个进程能拿到锁。
+struct PidFile {
+    path: PathBuf,
+    _file: std::fs::File,
+}
+
+impl PidFile {
+    /// 加锁并写入当前进程 pid；锁被其他进程持有时拒绝启动
+    #[cfg(unix)]
+    fn acquire(path: PathBuf) -> Result<Self, AppError> {
+        use std::io::Write;
+        use std::os::fd::AsRawFd;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|e| AppError::io(&path, e))?;
+
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::WouldBlock {
+                return Err(AppError::io(&path, err));
+            }
+            let pid = read_pid(&path).map_or_else(|| "?".to_string(), |pid| pid.to_string());
+            return Err(AppError::Conflict(format!(
+                "代理守护进程已在运行 (pid {pid}, pidfile {})",
+                path.display()
+            )));
+        }
+
+        // 持锁后再覆盖内容：残留文件中的旧 pid 在这里被替换
+        file.set_len(0).map_err(|e| AppError::io(&path, e))?;
+        writeln!(file, "{}", std::process::id()).map_err(|e| AppError::io(&path, e))?;
+        Ok(Self { path, _file: file })
+    }
+
+    /// 无 `flock` 的平台以 `create_new` 原子创建；已存在时视为残留清理后重试一次，
+    /// 由端口占用兜底拦截重复启动
+    #[cfg(not(unix))]
+    fn acquire(path: PathBuf) -> Result<Self, AppError> {
+        use std::io::Write;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        for _ in 0..2 {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id()).map_err(|e| AppError::io(&path, e))?;
+                    return Ok(Self { path, _file: file });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    log::warn!("清理残留 pidfile {}", path.display());
+                    match std::fs::remove_file(&path) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(AppError::io(&path, e)),
+                    }
+                }
+                Err(e) => return Err(AppError::io(&path, e)),
+            }
+        }
+        Err(AppError::Conflict(format!(
+            "pidfile {} 正被另一个启动中的守护进程占用",
+            path.display()
+        )))
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // 仅删除仍属于本进程的 pidfile，避免误删后来者写入的文件
+        if read_pid(&self.path) == Some(std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// 守护进程模式下没有 tauri-plugin-log，日志直接写 stderr
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "{} [{}] {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                record.level(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+fn init_logger(state: &AppState) {
+    if log::set_logger(&LOGGER).is_err() {
+        return;
+    }
+    let level = state
+        .db
+        .get_log_config()
+        .map(|config| config.to_level_filter())
+        .unwrap_or(log::LevelFilter::Info);
+    log::set_max_level(level);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pidfile_is_written_and_removed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("proxy.pid");
+
+        let pidfile = PidFile::acquire(path.clone()).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+
+        // 锁仍被持有：第二次获取被拒绝
+        #[cfg(unix)]
+        assert!(PidFile::acquire(path.clone()).is_err());
+
+        drop(pidfile);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn stale_pidfile_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proxy.pid");
+        std::fs::write(&path, "not-a-pid\n").unwrap();
+
+        let _pidfile = PidFile::acquire(path.clone()).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn concurrent_acquire_admits_a_single_holder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proxy.pid");
+        // 残留文件中是一个更长的旧 pid，接管后不应留下尾部字符
+        std::fs::write(&path, "4294967295\n").unwrap();
+
+        let barrier = std::sync::Barrier::new(8);
+        let acquired = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        PidFile::acquire(path.clone())
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let held: Vec<_> = acquired.iter().filter(|result| result.is_ok()).collect();
+        assert_eq!(held.len(), 1);
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+    }
+}
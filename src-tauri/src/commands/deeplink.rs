@@ -1,6 +1,6 @@
 use crate::deeplink::{
-    import_mcp_from_deeplink, import_prompt_from_deeplink, import_provider_from_deeplink,
-    import_skill_from_deeplink, parse_deeplink_url, DeepLinkImportRequest,
+    import_provider_from_deeplink, import_resource_from_deeplink, parse_deeplink_url,
+    DeepLinkImportRequest,
 };
 use crate::store::AppState;
 use tauri::State;
@@ -49,41 +49,5 @@ pub async fn import_from_deeplink_unified(
 ) -> Result<serde_json::Value, String> {
     log::info!("Importing {} resource from deep link", request.resource);
 
-    match request.resource.as_str() {
-        "provider" => {
-            let provider_id =
-                import_provider_from_deeplink(&state, request).map_err(|e| e.to_string())?;
-            Ok(serde_json::json!({
-                "type": "provider",
-                "id": provider_id
-            }))
-        }
-        "prompt" => {
-            let prompt_id =
-                import_prompt_from_deeplink(&state, request).map_err(|e| e.to_string())?;
-            Ok(serde_json::json!({
-                "type": "prompt",
-                "id": prompt_id
-            }))
-        }
-        "mcp" => {
-            let result = import_mcp_from_deeplink(&state, request).map_err(|e| e.to_string())?;
-            // Add type field to the result
-            Ok(serde_json::json!({
-                "type": "mcp",
-                "importedCount": result.imported_count,
-                "importedIds": result.imported_ids,
-                "failed": result.failed
-            }))
-        }
-        "skill" => {
-            let skill_key =
-                import_skill_from_deeplink(&state, request).map_err(|e| e.to_string())?;
-            Ok(serde_json::json!({
-                "type": "skill",
-                "key": skill_key
-            }))
-        }
-        _ => Err(format!("Unsupported resource type: {}", request.resource)),
-    }
+    import_resource_from_deeplink(&state, request).map_err(|e| e.to_string())
 }
@@ -67,6 +67,45 @@ mod tests {
 
         assert!(require_failover_provider(&db, "codex", &official.id).is_err());
     }
+
+    #[test]
+    fn routing_weight_is_stored_in_provider_meta_and_listed_in_queue() {
+        let db = Database::memory().expect("memory db");
+        let mut provider = Provider::with_id(
+            "relay-a".to_string(),
+            "Relay A".to_string(),
+            json!({}),
+            None,
+        );
+        provider.meta = Some(ProviderMeta {
+            limit_daily_usd: Some("5".to_string()),
+            ..Default::default()
+        });
+        db.save_provider("claude", &provider)
+            .expect("save provider");
+        db.add_to_failover_queue("claude", "relay-a")
+            .expect("add to queue");
+
+        db.set_provider_routing_weight("claude", "relay-a", Some(3))
+            .expect("set weight");
+        let queue = db.get_failover_queue("claude").expect("queue");
+        assert_eq!(queue[0].routing_weight, Some(3));
+        let meta = db
+            .get_provider_by_id("relay-a", "claude")
+            .expect("load")
+            .and_then(|p| p.meta)
+            .expect("meta");
+        assert_eq!(meta.routing_weight, Some(3));
+        assert_eq!(meta.limit_daily_usd.as_deref(), Some("5"));
+
+        db.set_provider_routing_weight("claude", "relay-a", None)
+            .expect("clear weight");
+        let queue = db.get_failover_queue("claude").expect("queue");
+        assert_eq!(queue[0].routing_weight, None);
+        assert!(db
+            .set_provider_routing_weight("claude", "missing", Some(1))
+            .is_err());
+    }
 }
 
 /// 获取故障转移队列
@@ -145,6 +184,22 @@ pub async fn remove_from_failover_queue(
         .map_err(|e| e.to_string())
 }
 
+/// 设置队列中供应商在加权路由策略下的权重（`None` 恢复默认权重 1）
+#[tauri::command]
+pub async fn set_failover_routing_weight(
+    state: tauri::State<'_, AppState>,
+    app_type: String,
+    provider_id: String,
+    weight: Option<u32>,
+) -> Result<(), String> {
+    require_failover_app(&app_type)?;
+    require_failover_provider(&state.db, &app_type, &provider_id)?;
+    state
+        .db
+        .set_provider_routing_weight(&app_type, &provider_id, weight)
+        .map_err(|e| e.to_string())
+}
+
 /// 获取指定应用的自动故障转移开关状态（从 proxy_config 表读取）
 #[tauri::command]
 pub async fn get_auto_failover_enabled(
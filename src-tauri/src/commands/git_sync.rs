@@ -0,0 +1,247 @@
+#![allow(non_snake_case)]
+
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::commands::sync_support::{
+    attach_warning, post_sync_warning_from_result, run_post_import_sync,
+};
+use crate::error::AppError;
+use crate::services::auto_sync::{AutoSyncSuppressionGuard, GitTransport};
+use crate::services::git_sync as git_sync_service;
+use crate::settings::{self, GitSyncSettings};
+use crate::store::AppState;
+
+fn persist_sync_error(settings: &mut GitSyncSettings, error: &AppError, source: &str) {
+    settings.status.last_error = Some(error.to_string());
+    settings.status.last_error_source = Some(source.to_string());
+    let _ = settings::update_git_sync_status(settings.status.clone());
+}
+
+fn git_not_configured_error() -> String {
+    AppError::localized(
+        "git_sync.not_configured",
+        "未配置 Git 同步",
+        "Git sync is not configured.",
+    )
+    .to_string()
+}
+
+fn git_sync_disabled_error() -> String {
+    AppError::localized(
+        "git_sync.disabled",
+        "Git 同步未启用",
+        "Git sync is disabled.",
+    )
+    .to_string()
+}
+
+fn require_enabled_git_settings() -> Result<GitSyncSettings, String> {
+    let settings = settings::get_git_sync_settings().ok_or_else(git_not_configured_error)?;
+    if !settings.enabled {
+        return Err(git_sync_disabled_error());
+    }
+    Ok(settings)
+}
+
+async fn run_with_git_lock<T, Fut>(operation: Fut) -> Result<T, AppError>
+where
+    Fut: std::future::Future<Output = Result<T, AppError>>,
+{
+    git_sync_service::run_with_sync_lock(operation).await
+}
+
+async fn run_download_with_git_lock<T, U, DownloadFut, Project, ProjectFut>(
+    download: DownloadFut,
+    project: Project,
+) -> Result<U, AppError>
+where
+    DownloadFut: std::future::Future<Output = Result<T, AppError>>,
+    Project: FnOnce(T) -> ProjectFut,
+    ProjectFut: std::future::Future<Output = Result<U, AppError>>,
+{
+    run_with_git_lock(async {
+        let result = {
+            let _auto_sync_suppression = AutoSyncSuppressionGuard::new::<GitTransport>();
+            download.await?
+        };
+        project(result).await
+    })
+    .await
+}
+
+fn map_sync_result<T, F>(result: Result<T, AppError>, on_error: F) -> Result<T, String>
+where
+    F: FnOnce(&AppError),
+{
+    match result {
+        Ok(value) => Ok(value),
+        Err(err) => {
+            on_error(&err);
+            Err(err.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn git_sync_test_connection(settings: GitSyncSettings) -> Result<Value, String> {
+    let mut settings = settings;
+    settings.normalize();
+    git_sync_service::check_connection(&settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(json!({
+        "success": true,
+        "message": "Git repository reachable"
+    }))
+}
+
+#[tauri::command]
+pub async fn git_sync_upload(state: State<'_, AppState>) -> Result<Value, String> {
+    let db = state.db.clone();
+    let mut settings = require_enabled_git_settings()?;
+
+    let result = run_with_git_lock(git_sync_service::upload(&db, &mut settings)).await;
+    map_sync_result(result, |error| {
+        persist_sync_error(&mut settings, error, "manual")
+    })
+}
+
+#[tauri::command]
+pub async fn git_sync_download(state: State<'_, AppState>) -> Result<Value, String> {
+    let db = state.db.clone();
+    let app_state_for_sync = state.inner().clone();
+    let mut settings = require_enabled_git_settings()?;
+
+    // Keep the live configuration refresh inside the same global sync operation
+    // so no other restore can interleave between DB apply and projection.
+    let sync_result = run_download_with_git_lock(
+        git_sync_service::download(&db, &mut settings),
+        |result| async move {
+            let post_sync_result = tauri::async_runtime::spawn_blocking(move || {
+                run_post_import_sync(&app_state_for_sync)
+            })
+            .await
+            .map_err(|e| e.to_string());
+            Ok((result, post_sync_result))
+        },
+    )
+    .await;
+    let (mut result, post_sync_result) = map_sync_result(sync_result, |error| {
+        persist_sync_error(&mut settings, error, "manual")
+    })?;
+
+    // Post-download sync is best-effort: snapshot restore has already succeeded.
+    let warning = post_sync_warning_from_result(post_sync_result);
+    if let Some(msg) = warning.as_ref() {
+        log::warn!("[Git] post-download sync warning: {msg}");
+    }
+    result = attach_warning(result, warning);
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn git_sync_save_settings(
+    settings: GitSyncSettings,
+    passphraseTouched: Option<bool>,
+) -> Result<Value, String> {
+    let mut sync_settings = settings;
+
+    // Preserve server-owned fields that the frontend does not manage. The
+    // passphrase is never sent to the frontend, so an untouched field means
+    // "keep the current one"; a touched empty field turns encryption off.
+    if let Some(existing_settings) = settings::get_git_sync_settings() {
+        sync_settings.status = existing_settings.status;
+        if !passphraseTouched.unwrap_or(false) {
+            sync_settings.encryption_passphrase = existing_settings.encryption_passphrase;
+        }
+    }
+
+    sync_settings.normalize();
+    sync_settings.validate().map_err(|e| e.to_string())?;
+    settings::set_git_sync_settings(Some(sync_settings)).map_err(|e| e.to_string())?;
+    Ok(json!({ "success": true }))
+}
+
+#[tauri::command]
+pub async fn git_sync_fetch_remote_info() -> Result<Value, String> {
+    let settings = require_enabled_git_settings()?;
+    let info = git_sync_service::fetch_remote_info(&settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(info.unwrap_or(json!({ "empty": true })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{git_sync_save_settings, require_enabled_git_settings};
+    use crate::settings::{AppSettings, GitSyncSettings};
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn require_enabled_git_settings_rejects_disabled_config() {
+        let test_home = std::env::temp_dir().join("cc-switch-git-sync-disabled-test");
+        let _ = std::fs::remove_dir_all(&test_home);
+        std::fs::create_dir_all(&test_home).expect("create test home");
+        std::env::set_var("CC_SWITCH_TEST_HOME", &test_home);
+
+        crate::settings::update_settings(AppSettings::default()).expect("reset settings");
+        crate::settings::set_git_sync_settings(Some(GitSyncSettings {
+            enabled: false,
+            repo_url: test_home.join("remote.git").display().to_string(),
+            ..GitSyncSettings::default()
+        }))
+        .expect("seed disabled git settings");
+
+        let err = require_enabled_git_settings().expect_err("disabled settings should fail");
+        assert!(
+            err.contains("disabled") || err.contains("未启用"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn save_settings_applies_passphrase_only_when_touched() {
+        let test_home = std::env::temp_dir().join("cc-switch-git-sync-passphrase-test");
+        let _ = std::fs::remove_dir_all(&test_home);
+        std::fs::create_dir_all(&test_home).expect("create test home");
+        std::env::set_var("CC_SWITCH_TEST_HOME", &test_home);
+
+        crate::settings::update_settings(AppSettings::default()).expect("reset settings");
+        let form = |passphrase: &str| GitSyncSettings {
+            repo_url: test_home.join("remote.git").display().to_string(),
+            encryption_passphrase: passphrase.to_string(),
+            ..GitSyncSettings::default()
+        };
+        crate::settings::set_git_sync_settings(Some(form("hunter2"))).expect("seed git settings");
+        let saved_passphrase = || {
+            crate::settings::get_git_sync_settings()
+                .expect("git settings")
+                .encryption_passphrase
+        };
+
+        git_sync_save_settings(form(""), None)
+            .await
+            .expect("save untouched");
+        assert_eq!(saved_passphrase(), "hunter2");
+        assert!(
+            crate::settings::get_settings_for_frontend()
+                .git_sync
+                .expect("git settings")
+                .encryption_configured
+        );
+
+        git_sync_save_settings(form("correct horse"), Some(true))
+            .await
+            .expect("save new passphrase");
+        assert_eq!(saved_passphrase(), "correct horse");
+
+        git_sync_save_settings(form(""), Some(true))
+            .await
+            .expect("clear passphrase");
+        assert_eq!(saved_passphrase(), "");
+    }
+}
@@ -14,6 +14,7 @@ use crate::error::AppError;
 use crate::services::provider::ProviderService;
 use crate::services::skill::skill_state_write_guard;
 use crate::services::sync_protocol::sync_mutex;
+use crate::services::usage_export::UsageExportFormat;
 use crate::store::AppState;
 
 async fn run_with_database_restore_lock<T, Start, Fut>(start_operation: Start) -> T
@@ -112,6 +113,28 @@ pub async fn save_file_dialog<R: tauri::Runtime>(
     Ok(result.map(|p| p.to_string()))
 }
 
+/// 用量导出的保存对话框：按导出格式设置扩展名过滤
+#[tauri::command]
+pub async fn save_usage_export_dialog<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    #[allow(non_snake_case)] defaultName: String,
+    format: UsageExportFormat,
+) -> Result<Option<String>, String> {
+    let (label, extension) = match format {
+        UsageExportFormat::Csv => ("CSV", "csv"),
+        UsageExportFormat::Jsonl => ("JSON Lines", "jsonl"),
+        UsageExportFormat::Parquet => ("Parquet", "parquet"),
+    };
+    let dialog = app.dialog();
+    let result = dialog
+        .file()
+        .add_filter(label, &[extension])
+        .set_file_name(&defaultName)
+        .blocking_save_file();
+
+    Ok(result.map(|p| p.to_string()))
+}
+
 /// 打开文件对话框
 #[tauri::command]
 pub async fn open_file_dialog<R: tauri::Runtime>(
@@ -2,6 +2,7 @@
 
 use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::time::Duration;
 
 use serde::Serialize;
 use tauri::State;
@@ -157,7 +158,7 @@ pub async fn set_mcp_enabled(
 // v3.7.0 新增：统一 MCP 管理命令
 // ============================================================================
 
-use crate::app_config::McpServer;
+use crate::app_config::{McpProbeResult, McpSecretInfo, McpServer};
 
 /// 获取所有 MCP 服务器（统一结构）
 #[tauri::command]
@@ -199,3 +200,92 @@ pub async fn toggle_mcp_app(
 pub async fn import_mcp_from_apps(state: State<'_, AppState>) -> Result<usize, String> {
     McpService::import_from_all_apps(&state).map_err(|e| e.to_string())
 }
+
+/// 获取所有 MCP 服务器最近一次的探测结果
+#[tauri::command]
+pub async fn get_mcp_probe_results(
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, McpProbeResult>, String> {
+    McpService::get_probe_results(&state).map_err(|e| e.to_string())
+}
+
+/// 探测 MCP 服务器：完成 initialize/tools/list/resources/list 握手并保存结果
+#[tauri::command]
+pub async fn probe_mcp_server(
+    state: State<'_, AppState>,
+    id: String,
+    timeout_secs: Option<u64>,
+) -> Result<McpProbeResult, String> {
+    McpService::probe_server(
+        &state,
+        &id,
+        timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 探测全部 MCP 服务器
+#[tauri::command]
+pub async fn probe_all_mcp_servers(
+    state: State<'_, AppState>,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<McpProbeResult>, String> {
+    McpService::probe_all_servers(
+        &state,
+        timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 获取 MCP 网关模式开关
+#[tauri::command]
+pub async fn get_mcp_gateway_enabled() -> Result<bool, String> {
+    Ok(crate::settings::get_mcp_gateway_enabled())
+}
+
+/// 切换 MCP 网关模式：开启后各应用只保留一个指向本地代理的 http 条目（代理未运行时自动启动）
+#[tauri::command]
+pub async fn set_mcp_gateway_enabled(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    McpService::set_gateway_enabled(&state, enabled)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 列出本地密钥库中的密钥名称（值不会返回前端）
+#[tauri::command]
+pub async fn list_mcp_secrets(state: State<'_, AppState>) -> Result<Vec<McpSecretInfo>, String> {
+    McpService::list_secrets(&state).map_err(|e| e.to_string())
+}
+
+/// 保存本地密钥，供 MCP 服务器定义以 `${secret:NAME}` 引用
+#[tauri::command]
+pub async fn set_mcp_secret(
+    state: State<'_, AppState>,
+    name: String,
+    value: String,
+) -> Result<(), String> {
+    McpService::set_secret(&state, name.trim(), &value).map_err(|e| e.to_string())
+}
+
+/// 获取引用了本机缺失密钥的服务器（id -> 缺失的密钥名称）
+#[tauri::command]
+pub async fn get_mcp_servers_needing_config(
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    McpService::get_servers_needing_config(&state).map_err(|e| e.to_string())
+}
+
+/// 删除本地密钥
+#[tauri::command]
+pub async fn delete_mcp_secret(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    McpService::delete_secret(&state, &name).map_err(|e| e.to_string())
+}
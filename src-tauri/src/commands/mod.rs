@@ -9,6 +9,8 @@ mod copilot;
 mod deeplink;
 mod env;
 mod failover;
+mod folder_sync;
+mod git_sync;
 mod global_proxy;
 mod hermes;
 mod import_export;
@@ -20,6 +22,7 @@ mod openclaw;
 mod pi;
 mod plugin;
 mod profile;
+mod project_binding;
 mod prompt;
 mod provider;
 mod proxy;
@@ -28,6 +31,7 @@ mod settings;
 pub mod skill;
 mod stream_check;
 mod subscription;
+mod sync_conflicts;
 mod sync_support;
 mod xai_oauth;
 
@@ -46,6 +50,8 @@ pub use copilot::*;
 pub use deeplink::*;
 pub use env::*;
 pub use failover::*;
+pub use folder_sync::*;
+pub use git_sync::*;
 pub use global_proxy::*;
 pub use hermes::*;
 pub use import_export::*;
@@ -57,6 +63,7 @@ pub use openclaw::*;
 pub(crate) use pi::*;
 pub use plugin::*;
 pub use profile::*;
+pub use project_binding::*;
 pub use prompt::*;
 pub use provider::*;
 pub use proxy::*;
@@ -65,6 +72,7 @@ pub use settings::*;
 pub use skill::*;
 pub use stream_check::*;
 pub use subscription::*;
+pub use sync_conflicts::*;
 pub use xai_oauth::*;
 
 pub use lightweight::*;
@@ -3,42 +3,10 @@
 use serde::Serialize;
 use tauri::{Emitter, Manager, State};
 
-use crate::database::Profile;
-use crate::services::profile::{ProfilePayload, ProfileScope, ProfileService};
+pub use crate::services::profile::ProfileDto;
+use crate::services::profile::{ProfileScope, ProfileService};
 use crate::store::AppState;
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ProfileDto {
-    pub id: String,
-    pub name: String,
-    pub payload: ProfilePayload,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub created_at: Option<i64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub updated_at: Option<i64>,
-}
-
-impl From<Profile> for ProfileDto {
-    fn from(profile: Profile) -> Self {
-        // 单条 payload 损坏不应拖垮整个列表：降级为默认值并记日志
-        let payload = serde_json::from_str(&profile.payload).unwrap_or_else(|e| {
-            log::warn!(
-                "解析 profile '{}' payload 失败，使用默认值: {e}",
-                profile.id
-            );
-            ProfilePayload::default()
-        });
-        Self {
-            id: profile.id,
-            name: profile.name,
-            payload,
-            created_at: profile.created_at,
-            updated_at: profile.updated_at,
-        }
-    }
-}
-
 /// 每个分组当前激活的项目 id（未使用项目时为 null）
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,43 @@
+//! 项目绑定命令：目录 × 应用 → 供应商 / MCP 集合
+
+use std::str::FromStr;
+
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::database::ProjectBinding;
+use crate::services::project_binding::ProjectBindingService;
+use crate::store::AppState;
+
+/// 列出所有项目绑定
+#[tauri::command]
+pub async fn list_project_bindings(
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectBinding>, String> {
+    ProjectBindingService::list(&state).map_err(|e| e.to_string())
+}
+
+/// 绑定目录并写入项目级配置；同一目录与应用重复调用即为更新
+#[tauri::command]
+pub async fn set_project_binding(
+    state: State<'_, AppState>,
+    path: String,
+    app: String,
+    provider_id: Option<String>,
+    mcp_server_ids: Vec<String>,
+) -> Result<ProjectBinding, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProjectBindingService::bind(&state, &path, app_type, provider_id, mcp_server_ids)
+        .map_err(|e| e.to_string())
+}
+
+/// 解绑并撤销写入的项目级配置
+#[tauri::command]
+pub async fn remove_project_binding(
+    state: State<'_, AppState>,
+    path: String,
+    app: String,
+) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProjectBindingService::unbind(&state, &path, app_type).map_err(|e| e.to_string())
+}
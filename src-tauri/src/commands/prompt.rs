@@ -1,10 +1,11 @@
 use indexmap::IndexMap;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use tauri::State;
 
 use crate::app_config::AppType;
-use crate::prompt::Prompt;
+use crate::prompt::{Prompt, SharedPrompt};
 use crate::services::pi_prompt_files::{
     PiPromptFileKind, PiPromptFileService, PiPromptFileSnapshot, PiPromptTemplate,
     PiPromptTemplateService,
@@ -52,6 +53,68 @@ pub async fn enable_prompt(
     PromptService::enable_prompt(&state, app_type, &id).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn enable_prompt_fragment(
+    app: String,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PromptService::enable_fragment(&state, app_type, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reorder_prompts(
+    app: String,
+    ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    PromptService::reorder_prompts(&state, app_type, &ids).map_err(|e| e.to_string())
+}
+
+/// 返回自定义变量；`app` 指定时附带该应用的内置变量，供编辑时预览
+#[tauri::command]
+pub async fn get_prompt_variables(app: Option<String>) -> Result<BTreeMap<String, String>, String> {
+    match app {
+        Some(app) => {
+            let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+            Ok(crate::services::prompt_fragments::effective_variables(
+                &app_type,
+            ))
+        }
+        None => Ok(crate::settings::get_prompt_variables()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_prompt_variables(
+    variables: BTreeMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    PromptService::set_variables(&state, variables).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_shared_prompts(
+    state: State<'_, AppState>,
+) -> Result<IndexMap<String, SharedPrompt>, String> {
+    PromptService::get_shared_prompts(&state).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn upsert_shared_prompt(
+    prompt: SharedPrompt,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    PromptService::upsert_shared_prompt(&state, prompt).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_shared_prompt(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    PromptService::delete_shared_prompt(&state, &id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn import_prompt_from_file(
     app: String,
@@ -505,42 +505,6 @@ fn resolve_native_credentials(app_type: &AppType, provider: Option<&Provider>) -
         .unwrap_or_default()
 }
 
-fn resolve_coding_plan_credentials(
-    app_type: &AppType,
-    provider: Option<&Provider>,
-    usage_script: Option<&crate::provider::UsageScript>,
-) -> (String, String) {
-    let is_zenmux = usage_script
-        .and_then(|s| s.coding_plan_provider.as_deref())
-        .map(|provider| provider.eq_ignore_ascii_case("zenmux"))
-        .unwrap_or(false);
-
-    if !is_zenmux {
-        return resolve_native_credentials(app_type, provider);
-    }
-
-    let script_base_url = usage_script
-        .and_then(|s| s.base_url.as_deref())
-        .unwrap_or("")
-        .trim_end_matches('/')
-        .to_string();
-    let script_api_key = usage_script
-        .and_then(|s| s.api_key.as_deref())
-        .unwrap_or("")
-        .to_string();
-
-    if !script_base_url.is_empty() && !script_api_key.is_empty() {
-        return (script_base_url, script_api_key);
-    }
-
-    let native = resolve_native_credentials(app_type, provider);
-    if !native.0.is_empty() && !native.1.is_empty() {
-        native
-    } else {
-        (script_base_url, script_api_key)
-    }
-}
-
 async fn query_provider_usage_inner(
     state: &AppState,
     copilot_state: &CopilotAuthState,
@@ -599,30 +563,10 @@ async fn query_provider_usage_inner(
 
     // ── Coding Plan 专用路径 ──
     if template_type == TEMPLATE_TYPE_TOKEN_PLAN {
-        let (base_url, api_key) =
-            resolve_coding_plan_credentials(&app_type, provider, usage_script);
-
-        // 火山方舟用账号 AK/SK 签名查询用量（存于 usage_script，与推理 api_key 分离）；
-        // 其他供应商为 None，service 层沿用 api_key。
-        let access_key_id = usage_script.and_then(|s| s.access_key_id.clone());
-        let secret_access_key = usage_script.and_then(|s| s.secret_access_key.clone());
-        // 智谱团队版：显式 provider 标识 + 组织/项目 ID（与个人版智谱 base_url 相同，
-        // 靠 coding_plan_provider == "zhipu_team" 在 service 层路由）。
-        let coding_plan_provider = usage_script.and_then(|s| s.coding_plan_provider.clone());
-        let team_organization_id = usage_script.and_then(|s| s.team_organization_id.clone());
-        let team_project_id = usage_script.and_then(|s| s.team_project_id.clone());
-
-        let quota = crate::services::coding_plan::get_coding_plan_quota(
-            &base_url,
-            &api_key,
-            access_key_id.as_deref(),
-            secret_access_key.as_deref(),
-            coding_plan_provider.as_deref(),
-            team_organization_id.as_deref(),
-            team_project_id.as_deref(),
-        )
-        .await
-        .map_err(|e| format!("Failed to query coding plan: {e}"))?;
+        let quota =
+            crate::services::coding_plan::get_provider_coding_plan_quota(&app_type, provider)
+                .await
+                .map_err(|e| format!("Failed to query coding plan: {e}"))?;
 
         // 将 SubscriptionQuota 转换为 UsageResult
         if !quota.success {
@@ -1163,9 +1107,10 @@ mod import_claude_desktop_tests {
 
 #[cfg(test)]
 mod native_query_credentials_tests {
-    use super::{resolve_coding_plan_credentials, resolve_native_credentials};
+    use super::resolve_native_credentials;
     use crate::app_config::AppType;
     use crate::provider::{Provider, UsageScript};
+    use crate::services::coding_plan::resolve_coding_plan_credentials;
     use serde_json::json;
 
     fn usage_script(
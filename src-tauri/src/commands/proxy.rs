@@ -3,6 +3,7 @@
 //! 提供前端调用的 API 接口
 
 use crate::error::AppError;
+use crate::proxy::capture::{CaptureReplayResult, ProxyCapture, ProxyCaptureSummary};
 use crate::proxy::types::*;
 use crate::proxy::{CircuitBreakerConfig, CircuitBreakerStats};
 use crate::store::AppState;
@@ -147,6 +148,10 @@ pub async fn update_proxy_config_for_app(
     let db = &state.db;
     let app_type = config.app_type.clone();
     require_proxy_app(&app_type)?;
+    crate::proxy::model_routes::validate_routes(&config.model_routes)?;
+    if config.quota_threshold > 100 {
+        return Err("额度阈值必须在 0-100 之间".to_string());
+    }
     let circuit_config = CircuitBreakerConfig::from(&config);
 
     db.update_proxy_config_for_app(config)
@@ -466,3 +471,74 @@ pub async fn get_circuit_breaker_stats(
     let _ = (state, provider_id, app_type);
     Ok(None)
 }
+
+// ==================== 抓包与重放 ====================
+
+/// 获取代理抓包开关
+#[tauri::command]
+pub async fn get_proxy_capture_enabled() -> Result<bool, String> {
+    Ok(crate::settings::get_proxy_capture_enabled())
+}
+
+/// 开关代理抓包（立即生效，无需重启代理）
+#[tauri::command]
+pub async fn set_proxy_capture_enabled(enabled: bool) -> Result<(), String> {
+    crate::settings::set_proxy_capture_enabled(enabled).map_err(|e| e.to_string())
+}
+
+/// 按时间倒序列出抓包摘要
+#[tauri::command]
+pub async fn list_proxy_captures(
+    state: tauri::State<'_, AppState>,
+    limit: Option<u32>,
+) -> Result<Vec<ProxyCaptureSummary>, String> {
+    state
+        .db
+        .list_proxy_captures(limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+/// 获取完整抓包记录
+#[tauri::command]
+pub async fn get_proxy_capture(
+    state: tauri::State<'_, AppState>,
+    id: String,
+) -> Result<Option<ProxyCapture>, String> {
+    state.db.get_proxy_capture(&id).map_err(|e| e.to_string())
+}
+
+/// 清空抓包
+#[tauri::command]
+pub async fn clear_proxy_captures(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    state.db.clear_proxy_captures().map_err(|e| e.to_string())
+}
+
+/// 重放抓包；`provider_id` 为空时按正常路由选择供应商
+#[tauri::command]
+pub async fn replay_proxy_capture(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    provider_id: Option<String>,
+) -> Result<CaptureReplayResult, String> {
+    state.proxy_service.replay_capture(&id, provider_id).await
+}
+
+// ==================== OpenTelemetry 导出 ====================
+
+/// 获取 OTLP/HTTP 采集端点
+#[tauri::command]
+pub async fn get_otlp_endpoint() -> Result<Option<String>, String> {
+    Ok(crate::settings::get_otlp_endpoint())
+}
+
+/// 设置 OTLP/HTTP 采集端点；传空关闭导出（立即生效，无需重启代理）
+#[tauri::command]
+pub async fn set_otlp_endpoint(endpoint: Option<String>) -> Result<(), String> {
+    if let Some(endpoint) = endpoint.as_deref().map(str::trim).filter(|e| !e.is_empty()) {
+        let url = url::Url::parse(endpoint).map_err(|e| format!("OTLP 端点无效: {e}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("OTLP 端点必须是 http 或 https 地址".to_string());
+        }
+    }
+    crate::settings::set_otlp_endpoint(endpoint).map_err(|e| e.to_string())
+}
@@ -7,6 +7,7 @@ use crate::commands::sync_support::{
     attach_warning, post_sync_warning_from_result, run_post_import_sync,
 };
 use crate::error::AppError;
+use crate::services::auto_sync::{AutoSyncSuppressionGuard, S3Transport};
 use crate::services::s3_sync as s3_sync_service;
 use crate::settings::{self, S3SyncSettings};
 use crate::store::AppState;
@@ -74,8 +75,7 @@ where
 {
     run_with_s3_lock(async {
         let result = {
-            let _auto_sync_suppression =
-                crate::services::s3_auto_sync::AutoSyncSuppressionGuard::new();
+            let _auto_sync_suppression = AutoSyncSuppressionGuard::new::<S3Transport>();
             download.await?
         };
         project(result).await
@@ -163,15 +163,22 @@ pub async fn s3_sync_download(state: State<'_, AppState>) -> Result<Value, Strin
 pub async fn s3_sync_save_settings(
     settings: S3SyncSettings,
     #[allow(non_snake_case)] passwordTouched: Option<bool>,
+    #[allow(non_snake_case)] passphraseTouched: Option<bool>,
 ) -> Result<Value, String> {
     let password_touched = passwordTouched.unwrap_or(false);
+    let passphrase_touched = passphraseTouched.unwrap_or(false);
     let existing = settings::get_s3_sync_settings();
     let mut sync_settings =
         resolve_secret_for_request(settings, existing.clone(), !password_touched);
 
-    // Preserve server-owned fields that the frontend does not manage
+    // Preserve server-owned fields that the frontend does not manage. The
+    // passphrase is never sent to the frontend, so an untouched field means
+    // "keep the current one"; a touched empty field turns encryption off.
     if let Some(existing_settings) = existing {
         sync_settings.status = existing_settings.status;
+        if !passphrase_touched {
+            sync_settings.encryption_passphrase = existing_settings.encryption_passphrase;
+        }
     }
 
     sync_settings.normalize();
@@ -196,6 +203,7 @@ mod tests {
         resolve_secret_for_request, run_download_with_s3_lock, run_with_s3_lock, s3_sync_mutex,
     };
     use crate::error::AppError;
+    use crate::services::auto_sync::{is_auto_sync_suppressed, S3Transport};
     use crate::settings::{AppSettings, S3SyncSettings};
     use serial_test::serial;
     use std::sync::atomic::{AtomicBool, Ordering};
@@ -236,19 +244,19 @@ mod tests {
     #[tokio::test]
     #[serial]
     async fn download_suppression_starts_after_s3_lock_acquisition() {
-        assert!(!crate::services::s3_auto_sync::is_auto_sync_suppressed());
+        assert!(!is_auto_sync_suppressed::<S3Transport>());
         let guard = s3_sync_mutex().lock().await;
         let download_entered = AtomicBool::new(false);
         let projection_entered = AtomicBool::new(false);
         let download = run_download_with_s3_lock(
             async {
                 download_entered.store(true, Ordering::SeqCst);
-                assert!(crate::services::s3_auto_sync::is_auto_sync_suppressed());
+                assert!(is_auto_sync_suppressed::<S3Transport>());
                 Ok::<(), AppError>(())
             },
             |_| async {
                 projection_entered.store(true, Ordering::SeqCst);
-                assert!(!crate::services::s3_auto_sync::is_auto_sync_suppressed());
+                assert!(!is_auto_sync_suppressed::<S3Transport>());
                 Ok::<(), AppError>(())
             },
         );
@@ -263,7 +271,7 @@ mod tests {
         assert!(!download_entered.load(Ordering::SeqCst));
         assert!(!projection_entered.load(Ordering::SeqCst));
         assert!(
-            !crate::services::s3_auto_sync::is_auto_sync_suppressed(),
+            !is_auto_sync_suppressed::<S3Transport>(),
             "local changes must remain observable while the download waits for the global lock"
         );
 
@@ -275,7 +283,7 @@ mod tests {
 
         assert!(download_entered.load(Ordering::SeqCst));
         assert!(projection_entered.load(Ordering::SeqCst));
-        assert!(!crate::services::s3_auto_sync::is_auto_sync_suppressed());
+        assert!(!is_auto_sync_suppressed::<S3Transport>());
     }
 
     #[tokio::test]
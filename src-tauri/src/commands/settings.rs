@@ -43,6 +43,52 @@ fn merge_settings_for_save(
         }
         _ => {}
     }
+    // 快照加密口令从不回传前端：incoming 为空表示"保持现有"，非空表示在设置页输入了新口令
+    // （清除口令走 webdav/s3_sync_save_settings 的 passphraseTouched）
+    if let (Some(incoming_sync), Some(existing_sync)) =
+        (&mut incoming.webdav_sync, &existing.webdav_sync)
+    {
+        if incoming_sync.encryption_passphrase.is_empty() {
+            incoming_sync.encryption_passphrase = existing_sync.encryption_passphrase.clone();
+        }
+    }
+    if let (Some(incoming_sync), Some(existing_sync)) = (&mut incoming.s3_sync, &existing.s3_sync) {
+        if incoming_sync.encryption_passphrase.is_empty() {
+            incoming_sync.encryption_passphrase = existing_sync.encryption_passphrase.clone();
+        }
+    }
+    // 提示词变量由提示词面板单独管理，前端设置页未传时保留现有值
+    if incoming.prompt_variables.is_none() {
+        incoming.prompt_variables = existing.prompt_variables.clone();
+    }
+    // MCP 网关开关由 MCP 面板单独管理（切换时需要重写各应用配置）
+    if incoming.mcp_gateway_enabled.is_none() {
+        incoming.mcp_gateway_enabled = existing.mcp_gateway_enabled;
+    }
+    // 代理抓包开关由代理面板单独管理
+    if incoming.proxy_capture_enabled.is_none() {
+        incoming.proxy_capture_enabled = existing.proxy_capture_enabled;
+    }
+    // OTLP 导出端点同样由代理面板单独管理
+    if incoming.otlp_endpoint.is_none() {
+        incoming.otlp_endpoint = existing.otlp_endpoint.clone();
+    }
+    // 本地目录 / Git 同步没有凭据，前端未传时同样保留现有配置
+    if incoming.folder_sync.is_none() {
+        incoming.folder_sync = existing.folder_sync.clone();
+    }
+    if let (Some(incoming_sync), Some(existing_sync)) =
+        (&mut incoming.folder_sync, &existing.folder_sync)
+    {
+        incoming_sync.encryption_passphrase = existing_sync.encryption_passphrase.clone();
+    }
+    if incoming.git_sync.is_none() {
+        incoming.git_sync = existing.git_sync.clone();
+    }
+    if let (Some(incoming_sync), Some(existing_sync)) = (&mut incoming.git_sync, &existing.git_sync)
+    {
+        incoming_sync.encryption_passphrase = existing_sync.encryption_passphrase.clone();
+    }
     // local_migrations 是纯后端状态（迁移完成标记），前端没有合法的修改场景，
     // 无条件取现有值。若按 incoming 透传：后端清掉 marker（如关闭统一会话
     // 开关）后、前端 query 缓存刷新前的一次全量保存会把旧 marker 重放回来，
@@ -409,6 +455,57 @@ mod tests {
         );
     }
 
+    /// The sync passphrase is never echoed back: a blank field keeps the
+    /// existing one, a typed value from the settings page replaces it.
+    #[test]
+    fn save_settings_should_keep_blank_passphrase_and_accept_new_one() {
+        let existing = AppSettings {
+            webdav_sync: Some(WebDavSyncSettings {
+                base_url: "https://dav.example.com".to_string(),
+                username: "alice".to_string(),
+                encryption_passphrase: "hunter2".to_string(),
+                ..WebDavSyncSettings::default()
+            }),
+            s3_sync: Some(S3SyncSettings {
+                bucket: "bucket".to_string(),
+                encryption_passphrase: "hunter2".to_string(),
+                ..S3SyncSettings::default()
+            }),
+            ..AppSettings::default()
+        };
+
+        let incoming = AppSettings {
+            webdav_sync: Some(WebDavSyncSettings {
+                base_url: "https://dav.example.com".to_string(),
+                username: "alice".to_string(),
+                ..WebDavSyncSettings::default()
+            }),
+            s3_sync: Some(S3SyncSettings {
+                bucket: "bucket".to_string(),
+                encryption_passphrase: "correct horse".to_string(),
+                ..S3SyncSettings::default()
+            }),
+            ..AppSettings::default()
+        };
+
+        let merged = merge_settings_for_save(incoming, &existing);
+
+        assert_eq!(
+            merged
+                .webdav_sync
+                .as_ref()
+                .map(|v| v.encryption_passphrase.as_str()),
+            Some("hunter2")
+        );
+        assert_eq!(
+            merged
+                .s3_sync
+                .as_ref()
+                .map(|v| v.encryption_passphrase.as_str()),
+            Some("correct horse")
+        );
+    }
+
     /// When both incoming and existing have no password, merge should
     /// work without panicking and keep the empty state.
     #[test]
@@ -0,0 +1,48 @@
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::commands::sync_support::{
+    attach_warning, post_sync_warning_from_result, run_post_import_sync,
+};
+use crate::services::sync_protocol;
+use crate::store::AppState;
+
+/// 最近一次同步下载（三方合并）留下的冲突
+#[tauri::command]
+pub async fn sync_list_conflicts() -> Result<Value, String> {
+    Ok(json!(sync_protocol::load_sync_conflicts()))
+}
+
+/// 解决一条同步冲突：`resolution` 为 `local`（保留本地）或 `remote`（采用远端）
+#[tauri::command]
+pub async fn sync_resolve_conflict(
+    state: State<'_, AppState>,
+    id: String,
+    resolution: String,
+) -> Result<Value, String> {
+    let take_remote = match resolution.as_str() {
+        "local" => false,
+        "remote" => true,
+        other => return Err(format!("未知的冲突处理方式: {other}")),
+    };
+
+    let db = state.db.clone();
+    sync_protocol::run_with_sync_lock(async {
+        sync_protocol::resolve_sync_conflict(&db, &id, take_remote)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut result =
+        json!({ "success": true, "remaining": sync_protocol::load_sync_conflicts().len() });
+    if take_remote {
+        // 远端行可能是当前供应商或提示词，需要重新写入 live 配置
+        let app_state = state.inner().clone();
+        let post_sync_result =
+            tauri::async_runtime::spawn_blocking(move || run_post_import_sync(&app_state))
+                .await
+                .map_err(|e| e.to_string());
+        result = attach_warning(result, post_sync_warning_from_result(post_sync_result));
+    }
+    Ok(result)
+}
@@ -2,6 +2,7 @@
 
 use crate::error::AppError;
 use crate::services::model_pricing::{ModelPricingInfo, ModelsDevSyncConfig, ModelsDevSyncState};
+use crate::services::usage_export::{UsageExportFormat, UsageExportSummary};
 use crate::services::usage_stats::*;
 use crate::store::AppState;
 use tauri::State;
@@ -99,6 +100,38 @@ pub fn get_model_stats(
     )
 }
 
+/// 获取按项目目录归属的统计
+#[tauri::command]
+pub fn get_project_stats(
+    state: State<'_, AppState>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    app_type: Option<String>,
+) -> Result<Vec<ProjectStats>, AppError> {
+    state
+        .db
+        .get_project_stats(start_date, end_date, app_type.as_deref())
+}
+
+/// 获取成本最高的会话列表
+#[tauri::command]
+pub fn get_session_stats(
+    state: State<'_, AppState>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    app_type: Option<String>,
+    project_dir: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<SessionStats>, AppError> {
+    state.db.get_session_stats(
+        start_date,
+        end_date,
+        app_type.as_deref(),
+        project_dir.as_deref(),
+        limit.unwrap_or(20),
+    )
+}
+
 /// 获取请求日志列表
 #[tauri::command]
 pub fn get_request_logs(
@@ -291,6 +324,27 @@ pub async fn rebuild_codex_usage(
     .map_err(|error| AppError::Message(format!("Codex 用量重建任务失败: {error}")))?
 }
 
+/// 按筛选条件导出用量数据（日聚合 + 请求明细）到文件
+#[tauri::command]
+pub async fn export_usage_data(
+    state: State<'_, AppState>,
+    filters: LogFilters,
+    format: UsageExportFormat,
+    file_path: String,
+) -> Result<UsageExportSummary, AppError> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::services::usage_export::export_usage(
+            &db,
+            &filters,
+            format,
+            std::path::Path::new(&file_path),
+        )
+    })
+    .await
+    .map_err(|error| AppError::Message(format!("用量导出任务失败: {error}")))?
+}
+
 /// 获取数据来源分布
 #[tauri::command]
 pub fn get_usage_data_sources(
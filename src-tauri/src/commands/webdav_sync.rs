@@ -7,6 +7,7 @@ use crate::commands::sync_support::{
     attach_warning, post_sync_warning_from_result, run_post_import_sync,
 };
 use crate::error::AppError;
+use crate::services::auto_sync::{AutoSyncSuppressionGuard, WebDavTransport};
 use crate::services::webdav_sync as webdav_sync_service;
 use crate::settings::{self, WebDavSyncSettings};
 use crate::store::AppState;
@@ -79,8 +80,7 @@ where
 {
     run_with_webdav_lock(async {
         let result = {
-            let _auto_sync_suppression =
-                crate::services::webdav_auto_sync::AutoSyncSuppressionGuard::new();
+            let _auto_sync_suppression = AutoSyncSuppressionGuard::new::<WebDavTransport>();
             download.await?
         };
         project(result).await
@@ -171,15 +171,22 @@ pub async fn webdav_sync_download(state: State<'_, AppState>) -> Result<Value, S
 pub async fn webdav_sync_save_settings(
     settings: WebDavSyncSettings,
     #[allow(non_snake_case)] passwordTouched: Option<bool>,
+    #[allow(non_snake_case)] passphraseTouched: Option<bool>,
 ) -> Result<Value, String> {
     let password_touched = passwordTouched.unwrap_or(false);
+    let passphrase_touched = passphraseTouched.unwrap_or(false);
     let existing = settings::get_webdav_sync_settings();
     let mut sync_settings =
         resolve_password_for_request(settings, existing.clone(), !password_touched);
 
-    // Preserve server-owned fields that the frontend does not manage
+    // Preserve server-owned fields that the frontend does not manage. The
+    // passphrase is never sent to the frontend, so an untouched field means
+    // "keep the current one"; a touched empty field turns encryption off.
     if let Some(existing_settings) = existing {
         sync_settings.status = existing_settings.status;
+        if !passphrase_touched {
+            sync_settings.encryption_passphrase = existing_settings.encryption_passphrase;
+        }
     }
 
     sync_settings.normalize();
@@ -202,9 +209,10 @@ mod tests {
     use super::{
         map_sync_result, persist_sync_error, require_enabled_webdav_settings,
         resolve_password_for_request, run_download_with_webdav_lock, run_with_webdav_lock,
-        webdav_sync_mutex,
+        webdav_sync_mutex, webdav_sync_save_settings,
     };
     use crate::error::AppError;
+    use crate::services::auto_sync::{is_auto_sync_suppressed, WebDavTransport};
     use crate::settings::{AppSettings, WebDavSyncSettings};
     use serial_test::serial;
     use std::sync::atomic::{AtomicBool, Ordering};
@@ -245,19 +253,19 @@ mod tests {
     #[tokio::test]
     #[serial]
     async fn download_suppression_starts_after_webdav_lock_acquisition() {
-        assert!(!crate::services::webdav_auto_sync::is_auto_sync_suppressed());
+        assert!(!is_auto_sync_suppressed::<WebDavTransport>());
         let guard = webdav_sync_mutex().lock().await;
         let download_entered = AtomicBool::new(false);
         let projection_entered = AtomicBool::new(false);
         let download = run_download_with_webdav_lock(
             async {
                 download_entered.store(true, Ordering::SeqCst);
-                assert!(crate::services::webdav_auto_sync::is_auto_sync_suppressed());
+                assert!(is_auto_sync_suppressed::<WebDavTransport>());
                 Ok::<(), AppError>(())
             },
             |_| async {
                 projection_entered.store(true, Ordering::SeqCst);
-                assert!(!crate::services::webdav_auto_sync::is_auto_sync_suppressed());
+                assert!(!is_auto_sync_suppressed::<WebDavTransport>());
                 Ok::<(), AppError>(())
             },
         );
@@ -272,7 +280,7 @@ mod tests {
         assert!(!download_entered.load(Ordering::SeqCst));
         assert!(!projection_entered.load(Ordering::SeqCst));
         assert!(
-            !crate::services::webdav_auto_sync::is_auto_sync_suppressed(),
+            !is_auto_sync_suppressed::<WebDavTransport>(),
             "local changes must remain observable while the download waits for the global lock"
         );
 
@@ -284,7 +292,7 @@ mod tests {
 
         assert!(download_entered.load(Ordering::SeqCst));
         assert!(projection_entered.load(Ordering::SeqCst));
-        assert!(!crate::services::webdav_auto_sync::is_auto_sync_suppressed());
+        assert!(!is_auto_sync_suppressed::<WebDavTransport>());
     }
 
     #[tokio::test]
@@ -429,4 +437,64 @@ mod tests {
         assert!(settings.enabled);
         assert_eq!(settings.base_url, "https://dav.example.com/dav/");
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn save_settings_applies_passphrase_only_when_touched() {
+        let test_home = std::env::temp_dir().join("cc-switch-sync-save-passphrase-test");
+        let _ = std::fs::remove_dir_all(&test_home);
+        std::fs::create_dir_all(&test_home).expect("create test home");
+        std::env::set_var("CC_SWITCH_TEST_HOME", &test_home);
+
+        crate::settings::update_settings(AppSettings::default()).expect("reset settings");
+        crate::settings::set_webdav_sync_settings(Some(WebDavSyncSettings {
+            base_url: "https://dav.example.com/dav/".to_string(),
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+            encryption_passphrase: "hunter2".to_string(),
+            ..WebDavSyncSettings::default()
+        }))
+        .expect("seed webdav settings");
+        let form = || WebDavSyncSettings {
+            base_url: "https://dav.example.com/dav/".to_string(),
+            username: "alice".to_string(),
+            ..WebDavSyncSettings::default()
+        };
+        let saved_passphrase = || {
+            crate::settings::get_webdav_sync_settings()
+                .expect("webdav settings")
+                .encryption_passphrase
+        };
+
+        webdav_sync_save_settings(form(), None, None)
+            .await
+            .expect("save untouched");
+        assert_eq!(saved_passphrase(), "hunter2");
+
+        let frontend = crate::settings::get_settings_for_frontend()
+            .webdav_sync
+            .expect("webdav settings");
+        assert!(frontend.encryption_configured);
+        assert!(frontend.encryption_passphrase.is_empty());
+
+        let changed = WebDavSyncSettings {
+            encryption_passphrase: "correct horse".to_string(),
+            ..form()
+        };
+        webdav_sync_save_settings(changed, None, Some(true))
+            .await
+            .expect("save new passphrase");
+        assert_eq!(saved_passphrase(), "correct horse");
+
+        webdav_sync_save_settings(form(), None, Some(true))
+            .await
+            .expect("clear passphrase");
+        assert_eq!(saved_passphrase(), "");
+        assert!(
+            !crate::settings::get_settings_for_frontend()
+                .webdav_sync
+                .expect("webdav settings")
+                .encryption_configured
+        );
+    }
 }
@@ -771,6 +771,7 @@ pub fn delete_file(path: &Path) -> Result<(), AppError> {
 }
 
 /// 检查 Claude Code 配置状态
+#[cfg(feature = "gui")]
 #[derive(Serialize, Deserialize)]
 pub struct ConfigStatus {
     pub exists: bool,
@@ -778,6 +779,7 @@ pub struct ConfigStatus {
 }
 
 /// 获取 Claude Code 配置状态
+#[cfg(feature = "gui")]
 pub fn get_claude_config_status() -> ConfigStatus {
     let path = get_claude_settings_path();
     ConfigStatus {
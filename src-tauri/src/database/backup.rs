@@ -26,7 +26,7 @@ const INSERT_BATCH_MAX_BYTES: usize = 1024 * 1024;
 static BACKUP_FILE_OPERATION_LOCK: Mutex<()> = Mutex::new(());
 type BackupFileOperationGuard = MutexGuard<'static, ()>;
 
-fn lock_backup_file_operations() -> Result<BackupFileOperationGuard, AppError> {
+pub(super) fn lock_backup_file_operations() -> Result<BackupFileOperationGuard, AppError> {
     BACKUP_FILE_OPERATION_LOCK
         .lock()
         .map_err(|e| AppError::Database(format!("Backup file operation lock failed: {e}")))
@@ -85,23 +85,33 @@ fn import_authorizer(context: rusqlite::hooks::AuthContext<'_>) -> rusqlite::hoo
 /// Tables whose data rows are skipped when exporting for WebDAV sync.
 const SYNC_SKIP_TABLES: &[&str] = &[
     "proxy_request_logs",
+    "proxy_captures",
     "stream_check_logs",
     "provider_health",
+    "mcp_probe_results",
+    "mcp_secrets",
+    "project_bindings",
     "proxy_live_backup",
     "usage_daily_rollups",
     "session_log_sync",
     "session_usage_dedup",
+    "session_projects",
 ];
 
 /// Tables whose local data is preserved from the live database during WebDAV import.
 /// Excludes ephemeral tables like provider_health that can safely rebuild at runtime.
-const SYNC_PRESERVE_TABLES: &[&str] = &[
+#[cfg(feature = "gui")]
+pub(super) const SYNC_PRESERVE_TABLES: &[&str] = &[
     "proxy_request_logs",
+    "proxy_captures",
+    "mcp_secrets",
+    "project_bindings",
     "stream_check_logs",
     "proxy_live_backup",
     "usage_daily_rollups",
     "session_log_sync",
     "session_usage_dedup",
+    "session_projects",
 ];
 
 /// A database backup entry for the UI
@@ -121,11 +131,25 @@ impl Database {
     }
 
     /// Export SQL for sync (WebDAV), skipping local-only tables' data
+    ///
+    /// Provider secrets stay encrypted under this device's master key, so only
+    /// devices sharing that key can import the result.
     pub fn export_sql_string_for_sync(&self) -> Result<String, AppError> {
         let snapshot = self.snapshot_to_memory()?;
         Self::dump_sql(&snapshot, SYNC_SKIP_TABLES)
     }
 
+    /// Export SQL for a sync snapshot that is sealed end to end as a whole.
+    ///
+    /// Provider secrets are decrypted so any device holding the sync passphrase
+    /// can read them; the caller must never upload this text unsealed.
+    #[cfg(feature = "gui")]
+    pub(crate) fn export_sql_string_for_sealed_sync(&self) -> Result<String, AppError> {
+        let snapshot = self.snapshot_to_memory()?;
+        Self::open_provider_secrets(&snapshot)?;
+        Self::dump_sql(&snapshot, SYNC_SKIP_TABLES)
+    }
+
     /// 导出为 SQLite 兼容的 SQL 文本
     pub fn export_sql(&self, target_path: &Path) -> Result<(), AppError> {
         let dump = self.export_sql_string()?;
@@ -158,6 +182,7 @@ impl Database {
 
     /// Import SQL generated for sync, then restore local-only tables from the
     /// current live database before replacing it.
+    #[cfg(feature = "gui")]
     pub(crate) fn import_sql_string_for_sync(&self, sql_raw: &str) -> Result<String, AppError> {
         self.import_sql_string_inner(sql_raw, SYNC_PRESERVE_TABLES)
     }
@@ -179,6 +204,42 @@ impl Database {
     where
         F: FnOnce() -> Result<(), AppError>,
     {
+        let (_temp_file, temp_conn) = Self::stage_sql_import(sql_raw)?;
+        if !preserve_tables.is_empty() {
+            // 同步快照里的供应商密钥可能是明文（端到端加密快照），落库前用本机主密钥加密；
+            // 别的设备主密钥加密、本机无法解密的值直接拒绝导入
+            Self::seal_provider_secrets(&temp_conn)?;
+        }
+        on_staging_ready()?;
+
+        let backup_file_guard = lock_backup_file_operations()?;
+        // Keep one main-DB guard across the safety snapshot, local-table read,
+        // and final replacement so neither the rollback point nor preserved
+        // device-local rows can miss writes that arrived during staging.
+        let backup_path = {
+            let mut main_conn = lock_conn!(self.conn);
+            let backup_path =
+                Self::backup_database_file_from_conn(&backup_file_guard, &main_conn, &[])?;
+            if !preserve_tables.is_empty() {
+                Self::restore_tables(&main_conn, &temp_conn, preserve_tables)?;
+            }
+            let backup = Backup::new(&temp_conn, &mut main_conn)
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            Self::complete_backup(&backup, "替换主数据库")?;
+            backup_path
+        };
+
+        let backup_id = backup_path
+            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .unwrap_or_default();
+
+        Ok(backup_id)
+    }
+
+    /// 在一次性临时数据库中执行外部 SQL 并补齐 schema，返回暂存库
+    ///
+    /// 临时文件随返回值一起交给调用方持有，暂存库用完即删。
+    pub(super) fn stage_sql_import(sql_raw: &str) -> Result<(NamedTempFile, Connection), AppError> {
         let sql_content = sql_raw.trim_start_matches('\u{feff}');
         Self::validate_cc_switch_sql_export(sql_content)?;
 
@@ -222,30 +283,8 @@ impl Database {
         // 补齐缺失表/索引并执行迁移
         Self::create_tables_on_conn(&temp_conn)?;
         Self::apply_schema_migrations_on_conn(&temp_conn)?;
-        on_staging_ready()?;
-
-        let backup_file_guard = lock_backup_file_operations()?;
-        // Keep one main-DB guard across the safety snapshot, local-table read,
-        // and final replacement so neither the rollback point nor preserved
-        // device-local rows can miss writes that arrived during staging.
-        let backup_path = {
-            let mut main_conn = lock_conn!(self.conn);
-            let backup_path =
-                Self::backup_database_file_from_conn(&backup_file_guard, &main_conn, &[])?;
-            if !preserve_tables.is_empty() {
-                Self::restore_tables(&main_conn, &temp_conn, preserve_tables)?;
-            }
-            let backup = Backup::new(&temp_conn, &mut main_conn)
-                .map_err(|e| AppError::Database(e.to_string()))?;
-            Self::complete_backup(&backup, "替换主数据库")?;
-            backup_path
-        };
-
-        let backup_id = backup_path
-            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
-            .unwrap_or_default();
 
-        Ok(backup_id)
+        Ok((temp_file, temp_conn))
     }
 
     /// 创建内存快照以避免长时间持有数据库锁
@@ -263,7 +302,7 @@ impl Database {
         Ok(snapshot)
     }
 
-    fn complete_backup(backup: &Backup<'_, '_>, context: &str) -> Result<(), AppError> {
+    pub(super) fn complete_backup(backup: &Backup<'_, '_>, context: &str) -> Result<(), AppError> {
         let result = backup
             .step(-1)
             .map_err(|e| AppError::Database(format!("{context}失败: {e}")))?;
@@ -291,7 +330,7 @@ impl Database {
         ))
     }
 
-    fn restore_tables(
+    pub(super) fn restore_tables(
         source_conn: &Connection,
         target_conn: &Connection,
         tables: &[&str],
@@ -410,6 +449,7 @@ impl Database {
     }
 
     /// Periodic backup: create a new backup if the latest one is older than the configured interval
+    #[cfg(feature = "gui")]
     pub(crate) fn periodic_backup_if_needed(&self) -> Result<(), AppError> {
         let interval_hours = crate::settings::effective_backup_interval_hours();
         if interval_hours > 0 {
@@ -488,7 +528,7 @@ impl Database {
 
     /// Create a safety backup from a connection whose caller already owns both
     /// the backup-file operation guard and the appropriate database guard.
-    fn backup_database_file_from_conn(
+    pub(super) fn backup_database_file_from_conn(
         backup_file_guard: &BackupFileOperationGuard,
         source_conn: &Connection,
         protected_paths: &[&Path],
@@ -885,12 +925,15 @@ impl Database {
         Ok(())
     }
 
-    fn quote_identifier(identifier: &str) -> String {
+    pub(super) fn quote_identifier(identifier: &str) -> String {
         format!("\"{}\"", identifier.replace('"', "\"\""))
     }
 
     /// 获取表的列名列表
-    fn get_table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, AppError> {
+    pub(super) fn get_table_columns(
+        conn: &Connection,
+        table: &str,
+    ) -> Result<Vec<String>, AppError> {
         let quoted_table = Self::quote_identifier(table);
         let mut stmt = conn
             .prepare(&format!("PRAGMA table_info({quoted_table})"))
@@ -2538,6 +2581,106 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[serial]
+    fn provider_secrets_round_trip_between_devices_with_different_master_keys(
+    ) -> Result<(), AppError> {
+        let _test_home = TestHomeGuard::new();
+        let device_a_key = crate::secrets::random_bytes::<{ crate::secrets::KEY_LEN }>();
+        let provider = crate::provider::Provider::with_id(
+            "shared".to_string(),
+            "Shared".to_string(),
+            serde_json::json!({ "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-from-device-a",
+                "ANTHROPIC_BASE_URL": "https://api.example.com"
+            }}),
+            None,
+        );
+
+        let device_a = Database::memory()?;
+        let (sealed_sql, unsealed_sql) =
+            crate::secrets::with_test_master_key(device_a_key, || {
+                device_a.save_provider("claude", &provider)?;
+                Ok::<_, AppError>((
+                    device_a.export_sql_string_for_sealed_sync()?,
+                    device_a.export_sql_string_for_sync()?,
+                ))
+            })?;
+        assert!(sealed_sql.contains("sk-from-device-a"));
+        assert!(!unsealed_sql.contains("sk-from-device-a"));
+
+        // 设备 B 使用自己的主密钥：未整体加密的快照只有 A 的密文，必须拒绝导入
+        let device_b = Database::memory()?;
+        assert!(device_b.import_sql_string_for_sync(&unsealed_sql).is_err());
+        assert!(device_b.get_provider_by_id("shared", "claude")?.is_none());
+
+        device_b.import_sql_string_for_sync(&sealed_sql)?;
+        let imported = device_b
+            .get_provider_by_id("shared", "claude")?
+            .expect("provider imported on device B");
+        assert_eq!(
+            imported.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-from-device-a"
+        );
+        let raw: String = crate::database::lock_conn!(device_b.conn).query_row(
+            "SELECT settings_config FROM providers WHERE id = 'shared'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert!(
+            !raw.contains("sk-from-device-a"),
+            "导入后应以 B 的主密钥加密"
+        );
+
+        // 回传给 A 同样可以解密
+        let back_sql = device_b.export_sql_string_for_sealed_sync()?;
+        let round_trip = crate::secrets::with_test_master_key(device_a_key, || {
+            device_a.import_sql_string_for_sync(&back_sql)?;
+            device_a.get_provider_by_id("shared", "claude")
+        })?
+        .expect("provider present on device A");
+        assert_eq!(
+            round_trip.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-from-device-a"
+        );
+
+        // 别的主密钥加密的值读取时清空并标记锁定，而不是把密文当作密钥返回；
+        // 其余供应商照常列出
+        device_b.save_provider(
+            "claude",
+            &crate::provider::Provider::with_id(
+                "plain".to_string(),
+                "Plain".to_string(),
+                serde_json::json!({ "env": { "ANTHROPIC_BASE_URL": "https://plain.example.com" } }),
+                None,
+            ),
+        )?;
+        let listed = crate::secrets::with_test_master_key(
+            crate::secrets::random_bytes::<{ crate::secrets::KEY_LEN }>(),
+            || device_b.get_all_providers("claude"),
+        )?;
+        let locked = &listed["shared"];
+        assert!(locked.secrets_locked());
+        assert_eq!(locked.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+        assert!(!listed["plain"].secrets_locked());
+        assert!(crate::services::provider::ensure_secrets_unlocked(locked).is_err());
+
+        // 未重新填写密钥就保存被锁定的供应商时保留原密文
+        crate::secrets::with_test_master_key(
+            crate::secrets::random_bytes::<{ crate::secrets::KEY_LEN }>(),
+            || device_b.save_provider("claude", locked),
+        )?;
+        let kept = device_b
+            .get_provider_by_id("shared", "claude")?
+            .expect("provider still present");
+        assert!(!kept.secrets_locked());
+        assert_eq!(
+            kept.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-from-device-a"
+        );
+        Ok(())
+    }
+
     #[test]
     #[serial]
     fn sync_import_keeps_local_writes_that_arrive_after_staging() -> Result<(), AppError> {
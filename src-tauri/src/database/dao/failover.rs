@@ -16,6 +16,9 @@ pub struct FailoverQueueItem {
     pub sort_index: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider_notes: Option<String>,
+    /// 加权路由策略下的权重（供应商 meta.routingWeight，缺省为 1）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routing_weight: Option<u32>,
 }
 
 impl Database {
@@ -25,7 +28,7 @@ impl Database {
 
         let mut stmt = conn
             .prepare(
-                "SELECT id, name, sort_index, notes
+                "SELECT id, name, sort_index, notes, meta
                  FROM providers
                  WHERE app_type = ?1 AND in_failover_queue = 1
                  ORDER BY COALESCE(sort_index, 999999), id ASC",
@@ -39,6 +42,12 @@ impl Database {
                     provider_name: row.get(1)?,
                     sort_index: row.get(2)?,
                     provider_notes: row.get(3)?,
+                    routing_weight: serde_json::from_str::<serde_json::Value>(
+                        &row.get::<_, String>(4)?,
+                    )
+                    .ok()
+                    .and_then(|meta| meta.get("routingWeight")?.as_u64())
+                    .and_then(|weight| u32::try_from(weight).ok()),
                 })
             })
             .map_err(|e| AppError::Database(e.to_string()))?
@@ -60,6 +69,49 @@ impl Database {
         Ok(result)
     }
 
+    /// 设置供应商在加权路由策略下的权重（`None` 恢复默认权重）
+    pub fn set_provider_routing_weight(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        weight: Option<u32>,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+
+        let meta: String = conn
+            .query_row(
+                "SELECT meta FROM providers WHERE id = ?1 AND app_type = ?2",
+                rusqlite::params![provider_id, app_type],
+                |row| row.get(0),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::Message(format!("供应商不存在: {provider_id}"))
+                }
+                other => AppError::Database(other.to_string()),
+            })?;
+        let mut meta = serde_json::from_str::<serde_json::Value>(&meta)
+            .ok()
+            .filter(serde_json::Value::is_object)
+            .unwrap_or_else(|| serde_json::json!({}));
+        match weight {
+            Some(weight) => meta["routingWeight"] = weight.into(),
+            None => {
+                if let Some(object) = meta.as_object_mut() {
+                    object.remove("routingWeight");
+                }
+            }
+        }
+
+        conn.execute(
+            "UPDATE providers SET meta = ?1 WHERE id = ?2 AND app_type = ?3",
+            rusqlite::params![meta.to_string(), provider_id, app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// 添加供应商到故障转移队列
     pub fn add_to_failover_queue(&self, app_type: &str, provider_id: &str) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
@@ -2,11 +2,14 @@
 //!
 //! 提供 MCP 服务器的 CRUD 操作。
 
-use crate::app_config::{AppType, McpApps, McpServer};
+use crate::app_config::{
+    AppType, McpApps, McpProbeResult, McpProbeStatus, McpSecretInfo, McpServer,
+};
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use indexmap::IndexMap;
 use rusqlite::{params, OptionalExtension, Row};
+use std::collections::HashMap;
 
 const MCP_SERVER_SELECT: &str =
     "SELECT id, name, server_config, description, homepage, docs, tags, enabled_claude, enabled_codex, enabled_gemini, enabled_grokbuild, enabled_opencode, enabled_hermes FROM mcp_servers";
@@ -150,8 +153,151 @@ impl Database {
         let conn = lock_conn!(self.conn);
         conn.execute("DELETE FROM mcp_servers WHERE id = ?1", params![id])
             .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM mcp_probe_results WHERE server_id = ?1",
+            params![id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取所有 MCP 服务器的最近一次探测结果（以服务器 id 为键）
+    pub fn get_mcp_probe_results(&self) -> Result<HashMap<String, McpProbeResult>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT server_id, status, server_name, server_version, protocol_version,
+                        tools, resources, error, duration_ms, probed_at
+                 FROM mcp_probe_results",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let status: String = row.get(1)?;
+                let tools: String = row.get(5)?;
+                let resources: String = row.get(6)?;
+                let duration_ms: i64 = row.get(8)?;
+                Ok(McpProbeResult {
+                    server_id: row.get(0)?,
+                    status: McpProbeStatus::parse(&status),
+                    server_name: row.get(2)?,
+                    server_version: row.get(3)?,
+                    protocol_version: row.get(4)?,
+                    tools: serde_json::from_str(&tools).unwrap_or_default(),
+                    resources: serde_json::from_str(&resources).unwrap_or_default(),
+                    error: row.get(7)?,
+                    duration_ms: duration_ms.max(0) as u64,
+                    probed_at: row.get(9)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut results = HashMap::new();
+        for row in rows {
+            let result = row.map_err(|e| AppError::Database(e.to_string()))?;
+            results.insert(result.server_id.clone(), result);
+        }
+        Ok(results)
+    }
+
+    /// 保存探测结果，覆盖该服务器之前的记录
+    pub fn save_mcp_probe_result(&self, result: &McpProbeResult) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO mcp_probe_results (
+                server_id, status, server_name, server_version, protocol_version,
+                tools, resources, error, duration_ms, probed_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                result.server_id,
+                result.status.as_str(),
+                result.server_name,
+                result.server_version,
+                result.protocol_version,
+                serde_json::to_string(&result.tools)
+                    .map_err(|e| AppError::Database(format!("Failed to serialize tools: {e}")))?,
+                serde_json::to_string(&result.resources).map_err(|e| AppError::Database(
+                    format!("Failed to serialize resources: {e}")
+                ))?,
+                result.error,
+                result.duration_ms as i64,
+                result.probed_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 清除探测结果（服务器连接定义变化后旧结论不再可信）
+    pub fn delete_mcp_probe_result(&self, server_id: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM mcp_probe_results WHERE server_id = ?1",
+            params![server_id],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 列出本地密钥库中的密钥名称（不含值）
+    pub fn list_mcp_secrets(&self) -> Result<Vec<McpSecretInfo>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare("SELECT name, updated_at FROM mcp_secrets ORDER BY name")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(McpSecretInfo {
+                    name: row.get(0)?,
+                    updated_at: row.get(1)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 读取全部密钥（已解密，以名称为键），仅供写入 live 配置前解析占位符
+    pub fn get_mcp_secret_values(&self) -> Result<HashMap<String, String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare("SELECT name, value FROM mcp_secrets")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut secrets = HashMap::new();
+        for row in rows {
+            let (name, value) = row.map_err(|e| AppError::Database(e.to_string()))?;
+            secrets.insert(name, crate::secrets::decrypt_str(&value)?);
+        }
+        Ok(secrets)
+    }
+
+    /// 保存密钥，值经主密钥加密后落库
+    pub fn save_mcp_secret(&self, name: &str, value: &str) -> Result<(), AppError> {
+        let encrypted = crate::secrets::encrypt_str(value)?;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO mcp_secrets (name, value, updated_at) VALUES (?1, ?2, ?3)",
+            params![name, encrypted, chrono::Utc::now().timestamp()],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
+
+    /// 删除密钥，返回是否存在
+    pub fn delete_mcp_secret(&self, name: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM mcp_secrets WHERE name = ?1", params![name])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
 }
 
 #[cfg(test)]
@@ -265,4 +411,62 @@ mod tests {
             assert_eq!(returned.apps, original.apps);
         }
     }
+
+    #[test]
+    fn probe_results_round_trip_and_follow_server_deletion() {
+        let db = Database::memory().expect("create memory db");
+        db.save_mcp_server(&test_server()).expect("seed server");
+        let result = McpProbeResult {
+            server_id: "shared-server".to_string(),
+            status: McpProbeStatus::Ok,
+            server_name: Some("echo".to_string()),
+            server_version: Some("1.0.0".to_string()),
+            protocol_version: Some("2025-06-18".to_string()),
+            tools: vec!["say".to_string()],
+            resources: Vec::new(),
+            error: None,
+            duration_ms: 42,
+            probed_at: 1_700_000_000,
+        };
+        db.save_mcp_probe_result(&result).expect("save probe");
+
+        let stored = db.get_mcp_probe_results().expect("read probes");
+        assert_eq!(stored.get("shared-server"), Some(&result));
+
+        db.delete_mcp_server("shared-server")
+            .expect("delete server");
+        assert!(db.get_mcp_probe_results().expect("read probes").is_empty());
+    }
+
+    #[test]
+    fn secrets_are_encrypted_at_rest_and_listed_without_values() {
+        let db = Database::memory().expect("memory db");
+        db.save_mcp_secret("GITHUB_TOKEN", "ghp_secret")
+            .expect("save secret");
+
+        let raw: String = {
+            let conn = db.conn.lock().expect("lock conn");
+            conn.query_row(
+                "SELECT value FROM mcp_secrets WHERE name = 'GITHUB_TOKEN'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("raw value")
+        };
+        assert!(crate::secrets::is_encrypted(&raw), "{raw}");
+
+        let listed = db.list_mcp_secrets().expect("list secrets");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "GITHUB_TOKEN");
+        assert_eq!(
+            db.get_mcp_secret_values()
+                .expect("secret values")
+                .get("GITHUB_TOKEN")
+                .map(String::as_str),
+            Some("ghp_secret")
+        );
+
+        assert!(db.delete_mcp_secret("GITHUB_TOKEN").expect("delete"));
+        assert!(!db.delete_mcp_secret("GITHUB_TOKEN").expect("delete again"));
+    }
 }
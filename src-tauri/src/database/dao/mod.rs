@@ -5,10 +5,12 @@
 pub mod failover;
 pub mod mcp;
 pub mod profiles;
+pub mod project_bindings;
 pub mod prompts;
 pub mod providers;
 pub mod providers_seed;
 pub mod proxy;
+pub mod proxy_captures;
 pub mod settings;
 pub mod skills;
 pub mod stream_check;
@@ -16,6 +18,8 @@ pub mod universal_providers;
 pub mod usage_rollup;
 
 // 所有 DAO 方法都通过 Database impl 提供，无需单独导出
-// 导出 FailoverQueueItem / Profile 供外部使用
+// 导出 FailoverQueueItem / Profile / ProjectBinding 供外部使用
+#[cfg(feature = "gui")]
 pub use failover::FailoverQueueItem;
 pub use profiles::Profile;
+pub use project_bindings::ProjectBinding;
@@ -0,0 +1,189 @@
+//! 项目绑定数据访问对象
+//!
+//! project_bindings 表以（目录, 应用）为键，记录该目录绑定的供应商与 MCP 服务器集合，
+//! 以及上次写入项目级配置的片段（`applied`，加密后的原始 JSON 文本，解析在 service 层）。
+//! 路径是本机路径，因此整张表只在本地保存，不参与同步。
+
+use serde::Serialize;
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::{params, OptionalExtension, Row};
+
+/// 项目绑定记录
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBinding {
+    /// 规范化后的项目绝对路径
+    pub path: String,
+    pub app_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    pub mcp_server_ids: Vec<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+const PROJECT_BINDING_SELECT: &str =
+    "SELECT path, app_type, provider_id, mcp_server_ids, created_at, updated_at FROM project_bindings";
+
+fn row_to_binding(row: &Row<'_>) -> rusqlite::Result<ProjectBinding> {
+    let mcp_server_ids: String = row.get(3)?;
+    Ok(ProjectBinding {
+        path: row.get(0)?,
+        app_type: row.get(1)?,
+        provider_id: row.get(2)?,
+        mcp_server_ids: serde_json::from_str(&mcp_server_ids).unwrap_or_default(),
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+impl Database {
+    /// 获取所有项目绑定（按路径、应用排序）
+    pub fn get_project_bindings(&self) -> Result<Vec<ProjectBinding>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(&format!("{PROJECT_BINDING_SELECT} ORDER BY path, app_type"))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], row_to_binding)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取单个绑定及其已写入片段（已解密）
+    pub fn get_project_binding(
+        &self,
+        path: &str,
+        app_type: &str,
+    ) -> Result<Option<(ProjectBinding, String)>, AppError> {
+        let row = {
+            let conn = lock_conn!(self.conn);
+            conn.query_row(
+                "SELECT path, app_type, provider_id, mcp_server_ids, created_at, updated_at, applied
+                 FROM project_bindings WHERE path = ?1 AND app_type = ?2",
+                params![path, app_type],
+                |row| Ok((row_to_binding(row)?, row.get::<_, String>(6)?)),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        row.map(|(binding, applied)| Ok((binding, open_applied(&applied)?)))
+            .transpose()
+    }
+
+    /// 保存绑定；`applied` 可能包含 API Key，落库前加密
+    pub fn save_project_binding(
+        &self,
+        binding: &ProjectBinding,
+        applied: &str,
+    ) -> Result<(), AppError> {
+        let applied = crate::secrets::encrypt_str(applied)?;
+        let mcp_server_ids = serde_json::to_string(&binding.mcp_server_ids)
+            .map_err(|e| AppError::Database(format!("Failed to serialize mcp_server_ids: {e}")))?;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO project_bindings (
+                path, app_type, provider_id, mcp_server_ids, applied, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                binding.path,
+                binding.app_type,
+                binding.provider_id,
+                mcp_server_ids,
+                applied,
+                binding.created_at,
+                binding.updated_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除绑定，返回是否存在
+    pub fn delete_project_binding(&self, path: &str, app_type: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute(
+                "DELETE FROM project_bindings WHERE path = ?1 AND app_type = ?2",
+                params![path, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+}
+
+/// 解密 `applied`；该列只由 [`Database::save_project_binding`] 加密写入，
+/// 出现非空明文说明被绕过写入，按损坏拒绝而不是原样信任
+fn open_applied(applied: &str) -> Result<String, AppError> {
+    if !applied.is_empty() && !crate::secrets::is_encrypted(applied) {
+        return Err(AppError::Database(
+            "project_bindings.applied 不是密文，拒绝读取".to_string(),
+        ));
+    }
+    crate::secrets::decrypt_str(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding() -> ProjectBinding {
+        ProjectBinding {
+            path: "/work/demo".to_string(),
+            app_type: "claude".to_string(),
+            provider_id: Some("p1".to_string()),
+            mcp_server_ids: vec!["fs".to_string()],
+            created_at: 1,
+            updated_at: 1,
+        }
+    }
+
+    fn raw_applied(db: &Database) -> String {
+        let conn = db.conn.lock().expect("lock conn");
+        conn.query_row(
+            "SELECT applied FROM project_bindings WHERE path = '/work/demo'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("raw applied")
+    }
+
+    #[test]
+    fn applied_fragments_are_encrypted_at_rest() {
+        let db = Database::memory().expect("memory db");
+        let applied = r#"[{"file":".mcp.json","value":{"env":{"API_KEY":"sk-secret"}}}]"#;
+        db.save_project_binding(&binding(), applied)
+            .expect("save binding");
+
+        let raw = raw_applied(&db);
+        assert!(crate::secrets::is_encrypted(&raw), "{raw}");
+        assert!(!raw.contains("sk-secret"));
+
+        let (stored, decrypted) = db
+            .get_project_binding("/work/demo", "claude")
+            .expect("read binding")
+            .expect("binding exists");
+        assert_eq!(stored, binding());
+        assert_eq!(decrypted, applied);
+    }
+
+    #[test]
+    fn plaintext_applied_is_rejected() {
+        let db = Database::memory().expect("memory db");
+        db.save_project_binding(&binding(), "[]")
+            .expect("save binding");
+        {
+            let conn = db.conn.lock().expect("lock conn");
+            conn.execute(
+                "UPDATE project_bindings SET applied = '[{\"file\":\".mcp.json\"}]'",
+                [],
+            )
+            .expect("tamper applied");
+        }
+
+        assert!(db.get_project_binding("/work/demo", "claude").is_err());
+    }
+}
@@ -1,10 +1,10 @@
 //! 提示词数据访问对象
 //!
-//! 提供提示词（Prompt）的 CRUD 操作。
+//! 提供提示词（Prompt）与共享提示词（SharedPrompt）的 CRUD 操作。
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use crate::prompt::Prompt;
+use crate::prompt::{Prompt, SharedPrompt};
 use indexmap::IndexMap;
 use rusqlite::params;
 
@@ -14,7 +14,7 @@ impl Database {
         let conn = lock_conn!(self.conn);
         let mut stmt = conn
             .prepare(
-                "SELECT id, name, content, description, enabled, created_at, updated_at
+                "SELECT id, name, content, description, enabled, sort_index, created_at, updated_at
              FROM prompts WHERE app_type = ?1
              ORDER BY created_at ASC, id ASC",
             )
@@ -27,8 +27,9 @@ impl Database {
                 let content: String = row.get(2)?;
                 let description: Option<String> = row.get(3)?;
                 let enabled: bool = row.get(4)?;
-                let created_at: Option<i64> = row.get(5)?;
-                let updated_at: Option<i64> = row.get(6)?;
+                let sort_index: Option<i64> = row.get(5)?;
+                let created_at: Option<i64> = row.get(6)?;
+                let updated_at: Option<i64> = row.get(7)?;
 
                 Ok((
                     id.clone(),
@@ -38,6 +39,7 @@ impl Database {
                         content,
                         description,
                         enabled,
+                        sort_index,
                         created_at,
                         updated_at,
                     },
@@ -58,8 +60,8 @@ impl Database {
         let conn = lock_conn!(self.conn);
         conn.execute(
             "INSERT OR REPLACE INTO prompts (
-                id, app_type, name, content, description, enabled, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                id, app_type, name, content, description, enabled, sort_index, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 prompt.id,
                 app_type,
@@ -67,6 +69,7 @@ impl Database {
                 prompt.content,
                 prompt.description,
                 prompt.enabled,
+                prompt.sort_index,
                 prompt.created_at,
                 prompt.updated_at,
             ],
@@ -85,4 +88,76 @@ impl Database {
         .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
+
+    /// 获取所有共享提示词（按 sort_index 升序，未设置的排在最后）
+    pub fn get_shared_prompts(&self) -> Result<IndexMap<String, SharedPrompt>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, content, description, apps, appendices, sort_index, created_at, updated_at
+             FROM shared_prompts
+             ORDER BY sort_index IS NULL, sort_index ASC, created_at ASC, id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let prompt_iter = stmt
+            .query_map([], |row| {
+                let apps: String = row.get(4)?;
+                let appendices: String = row.get(5)?;
+                Ok(SharedPrompt {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    content: row.get(2)?,
+                    description: row.get(3)?,
+                    apps: serde_json::from_str(&apps).unwrap_or_default(),
+                    appendices: serde_json::from_str(&appendices).unwrap_or_default(),
+                    sort_index: row.get(6)?,
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut prompts = IndexMap::new();
+        for prompt_res in prompt_iter {
+            let prompt = prompt_res.map_err(|e| AppError::Database(e.to_string()))?;
+            prompts.insert(prompt.id.clone(), prompt);
+        }
+        Ok(prompts)
+    }
+
+    /// 保存共享提示词
+    pub fn save_shared_prompt(&self, prompt: &SharedPrompt) -> Result<(), AppError> {
+        let apps = serde_json::to_string(&prompt.apps)
+            .map_err(|e| AppError::Database(format!("Failed to serialize apps: {e}")))?;
+        let appendices = serde_json::to_string(&prompt.appendices)
+            .map_err(|e| AppError::Database(format!("Failed to serialize appendices: {e}")))?;
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO shared_prompts (
+                id, name, content, description, apps, appendices, sort_index, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                prompt.id,
+                prompt.name,
+                prompt.content,
+                prompt.description,
+                apps,
+                appendices,
+                prompt.sort_index,
+                prompt.created_at,
+                prompt.updated_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 删除共享提示词
+    pub fn delete_shared_prompt(&self, id: &str) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM shared_prompts WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
 }
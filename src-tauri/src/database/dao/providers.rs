@@ -2,7 +2,7 @@ use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use crate::provider::{Provider, ProviderMeta};
 use indexmap::IndexMap;
-use rusqlite::{params, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::collections::{HashMap, HashSet};
 
 type OmoProviderRow = (
@@ -16,7 +16,122 @@ type OmoProviderRow = (
     String,
 );
 
+/// 序列化 settings_config 用于落库：敏感字段加密后再写入；加密失败时拒绝保存
+fn settings_config_for_storage(settings_config: &serde_json::Value) -> Result<String, AppError> {
+    let mut stored = settings_config.clone();
+    crate::secrets::encrypt_secret_fields(&mut stored).map_err(|e| {
+        AppError::localized(
+            "provider.secret_encrypt_failed",
+            format!("供应商密钥加密失败，未保存: {e}。若主密钥设置了口令，请先设置 CC_SWITCH_SECRETS_PASSPHRASE"),
+            format!(
+                "Failed to encrypt provider secrets, nothing was saved: {e}. If the master key is passphrase-protected, set CC_SWITCH_SECRETS_PASSPHRASE first"
+            ),
+        )
+    })?;
+    serde_json::to_string(&stored)
+        .map_err(|e| AppError::Database(format!("Failed to serialize settings_config: {e}")))
+}
+
+/// 更新已有供应商时的落库序列化：保留本机仍无法解密、且这次没有重新填写的密钥
+fn settings_config_for_update(
+    conn: &Connection,
+    app_type: &str,
+    provider_id: &str,
+    settings_config: &serde_json::Value,
+) -> Result<String, AppError> {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT settings_config FROM providers WHERE id = ?1 AND app_type = ?2",
+            params![provider_id, app_type],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let Some(stored) = stored.and_then(|raw| serde_json::from_str(&raw).ok()) else {
+        return settings_config_for_storage(settings_config);
+    };
+    let mut merged = settings_config.clone();
+    crate::secrets::keep_locked_secret_fields(&mut merged, &stored);
+    settings_config_for_storage(&merged)
+}
+
+/// 解密读出的 settings_config，返回密钥是否被锁定
+///
+/// 无法用本机主密钥解密的字段被清空，不能把 `enc:v1:` 串当作 API Key 写进 live 配置
+/// 或转发给上游；单个供应商解密失败不影响其余供应商的读取
+fn open_settings_config(provider_id: &str, settings_config: &mut serde_json::Value) -> bool {
+    match crate::secrets::decrypt_secret_fields_lossy(settings_config) {
+        Ok(()) => false,
+        Err(e) => {
+            log::warn!("供应商 {provider_id} 的密钥无法用本机主密钥解密，已标记为锁定: {e}");
+            true
+        }
+    }
+}
+
+/// 对 providers 表的每个 settings_config 执行 `transform` 并写回（同步快照的导入导出用）
+fn rewrite_settings_configs(
+    conn: &Connection,
+    transform: impl Fn(&mut serde_json::Value) -> Result<(), AppError>,
+) -> Result<(), AppError> {
+    if !Database::table_exists(conn, "providers")? {
+        return Ok(());
+    }
+    let rows: Vec<(String, String, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT id, app_type, settings_config FROM providers")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?
+    };
+
+    for (id, app_type, raw) in rows {
+        let Ok(mut settings_config) = serde_json::from_str::<serde_json::Value>(&raw) else {
+            continue;
+        };
+        transform(&mut settings_config).map_err(|e| {
+            AppError::localized(
+                "sync.provider_secret_undecryptable",
+                format!(
+                    "供应商 {id}（{app_type}）的密钥无法用本机主密钥解密: {e}。若数据来自另一台设备，请在所有设备上配置相同的同步加密口令后重新上传"
+                ),
+                format!(
+                    "The secrets of provider {id} ({app_type}) cannot be decrypted with this device's master key: {e}. If the data came from another device, configure the same sync passphrase on every device and upload again"
+                ),
+            )
+        })?;
+        let updated = serde_json::to_string(&settings_config)
+            .map_err(|e| AppError::Database(format!("Failed to serialize settings_config: {e}")))?;
+        if updated != raw {
+            conn.execute(
+                "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
+                params![updated, id, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
 impl Database {
+    /// 把 `conn` 中供应商密钥解密为明文（端到端加密的同步快照、三方合并比较用）
+    ///
+    /// 任何一个 `enc:v1:` 值无法用本机主密钥解密都会报错。
+    #[cfg(feature = "gui")]
+    pub(crate) fn open_provider_secrets(conn: &Connection) -> Result<(), AppError> {
+        rewrite_settings_configs(conn, crate::secrets::decrypt_secret_fields)
+    }
+
+    /// 让 `conn` 中供应商密钥都成为本机主密钥下的密文（同步快照落到主库前调用）
+    ///
+    /// 明文就地加密；别的设备主密钥加密、本机无法解密的值报错。
+    pub(crate) fn seal_provider_secrets(conn: &Connection) -> Result<(), AppError> {
+        rewrite_settings_configs(conn, crate::secrets::seal_secret_fields)
+    }
+
     pub fn get_all_providers(
         &self,
         app_type: &str,
@@ -71,6 +186,11 @@ impl Database {
         for provider_res in provider_iter {
             let (id, mut provider) = provider_res.map_err(|e| AppError::Database(e.to_string()))?;
             provider.id = id.clone();
+            if open_settings_config(&id, &mut provider.settings_config) {
+                if let Some(meta) = &mut provider.meta {
+                    meta.secrets_locked = Some(true);
+                }
+            }
 
             let mut stmt_endpoints = conn.prepare(
                 "SELECT url, added_at FROM provider_endpoints WHERE provider_id = ?1 AND app_type = ?2 ORDER BY added_at ASC, url ASC"
@@ -171,7 +291,14 @@ impl Database {
         );
 
         match result {
-            Ok(provider) => Ok(Some(provider)),
+            Ok(mut provider) => {
+                if open_settings_config(id, &mut provider.settings_config) {
+                    if let Some(meta) = &mut provider.meta {
+                        meta.secrets_locked = Some(true);
+                    }
+                }
+                Ok(Some(provider))
+            }
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(AppError::Database(e.to_string())),
         }
@@ -185,6 +312,8 @@ impl Database {
 
         let mut meta_clone = provider.meta.clone().unwrap_or_default();
         let endpoints = std::mem::take(&mut meta_clone.custom_endpoints);
+        // 锁定标记由读取时的解密结果决定，不落库
+        meta_clone.secrets_locked = None;
 
         let existing: Option<(bool, bool)> = tx
             .query_row(
@@ -216,9 +345,12 @@ impl Database {
                 WHERE id = ?13 AND app_type = ?14",
                 params![
                     provider.name,
-                    serde_json::to_string(&provider.settings_config).map_err(|e| {
-                        AppError::Database(format!("Failed to serialize settings_config: {e}"))
-                    })?,
+                    settings_config_for_update(
+                        &tx,
+                        app_type,
+                        &provider.id,
+                        &provider.settings_config
+                    )?,
                     provider.website_url,
                     provider.category,
                     provider.created_at,
@@ -246,8 +378,7 @@ impl Database {
                     provider.id,
                     app_type,
                     provider.name,
-                    serde_json::to_string(&provider.settings_config)
-                        .map_err(|e| AppError::Database(format!("Failed to serialize settings_config: {e}")))?,
+                    settings_config_for_storage(&provider.settings_config)?,
                     provider.website_url,
                     provider.category,
                     provider.created_at,
@@ -327,6 +458,7 @@ impl Database {
 
         let mut meta = provider.meta.clone().unwrap_or_default();
         meta.custom_endpoints.clear();
+        meta.secrets_locked = None;
         tx.execute(
             "INSERT INTO providers (
                 id, app_type, name, settings_config, website_url, category,
@@ -337,9 +469,7 @@ impl Database {
                 provider.id,
                 app_type,
                 provider.name,
-                serde_json::to_string(&provider.settings_config).map_err(|e| {
-                    AppError::Database(format!("Failed to serialize settings_config: {e}"))
-                })?,
+                settings_config_for_storage(&provider.settings_config)?,
                 provider.website_url,
                 provider.category,
                 provider.created_at,
@@ -418,9 +548,7 @@ impl Database {
         conn.execute(
             "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
             params![
-                serde_json::to_string(settings_config).map_err(|e| AppError::Database(format!(
-                    "Failed to serialize settings_config: {e}"
-                )))?,
+                settings_config_for_update(&conn, app_type, provider_id, settings_config)?,
                 provider_id,
                 app_type
             ],
@@ -570,12 +698,13 @@ impl Database {
                 Err(e) => return Err(AppError::Database(e.to_string())),
             };
 
-        let settings_config = serde_json::from_str(&settings_config_str).map_err(|e| {
+        let mut settings_config = serde_json::from_str(&settings_config_str).map_err(|e| {
             AppError::Database(format!(
                 "Failed to parse {category} provider settings_config (provider_id={id}): {e}"
             ))
         })?;
-        let meta: crate::provider::ProviderMeta = if meta_str.trim().is_empty() {
+        let secrets_locked = open_settings_config(&id, &mut settings_config);
+        let mut meta: crate::provider::ProviderMeta = if meta_str.trim().is_empty() {
             crate::provider::ProviderMeta::default()
         } else {
             serde_json::from_str(&meta_str).map_err(|e| {
@@ -584,6 +713,9 @@ impl Database {
                 ))
             })?
         };
+        if secrets_locked {
+            meta.secrets_locked = Some(true);
+        }
 
         Ok(Some(Provider {
             id,
@@ -601,6 +733,49 @@ impl Database {
         }))
     }
 
+    /// 加密旧版以明文保存的供应商密钥（启动时调用，幂等）
+    ///
+    /// 返回本次加密的供应商数量；主密钥不可用时不做任何修改。
+    pub fn encrypt_plaintext_provider_secrets(&self) -> Result<usize, AppError> {
+        if !crate::secrets::is_available() {
+            return Ok(0);
+        }
+
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows: Vec<(String, String, String)> = {
+            let mut stmt = tx
+                .prepare("SELECT id, app_type, settings_config FROM providers")
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            rows.collect::<Result<_, _>>()
+                .map_err(|e| AppError::Database(e.to_string()))?
+        };
+
+        let mut encrypted = 0;
+        for (id, app_type, raw) in rows {
+            let Ok(mut settings_config) = serde_json::from_str::<serde_json::Value>(&raw) else {
+                continue;
+            };
+            if !crate::secrets::has_plaintext_secrets(&mut settings_config) {
+                continue;
+            }
+            tx.execute(
+                "UPDATE providers SET settings_config = ?1 WHERE id = ?2 AND app_type = ?3",
+                params![settings_config_for_storage(&settings_config)?, id, app_type],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+            encrypted += 1;
+        }
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(encrypted)
+    }
+
     /// 判断 providers 表是否为空（全 app_type 一起算）。
     ///
     /// 用于区分"全新安装"和"升级用户"：在启动流程 import/seed 之前调用。
@@ -925,3 +1100,83 @@ mod ensure_official_seed_tests {
         assert!(result.is_err(), "(id, app_type) mismatch should be Err");
     }
 }
+
+#[cfg(test)]
+mod secret_storage_tests {
+    use crate::database::Database;
+    use crate::provider::Provider;
+    use serde_json::json;
+
+    fn raw_settings_config(db: &Database, id: &str) -> String {
+        db.conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT settings_config FROM providers WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn api_keys_are_encrypted_at_rest() {
+        let db = Database::memory().unwrap();
+        let provider = Provider::with_id(
+            "p1".to_string(),
+            "P1".to_string(),
+            json!({ "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-secret",
+                "ANTHROPIC_BASE_URL": "https://api.example.com"
+            }}),
+            None,
+        );
+        db.save_provider("claude", &provider).unwrap();
+
+        let raw = raw_settings_config(&db, "p1");
+        assert!(!raw.contains("sk-secret"));
+        assert!(raw.contains(crate::secrets::ENCRYPTED_PREFIX));
+        assert!(raw.contains("https://api.example.com"));
+
+        let loaded = db.get_provider_by_id("p1", "claude").unwrap().unwrap();
+        assert_eq!(
+            loaded.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-secret"
+        );
+        let all = db.get_all_providers("claude").unwrap();
+        assert_eq!(
+            all["p1"].settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+            "sk-secret"
+        );
+    }
+
+    #[test]
+    fn legacy_plaintext_rows_are_readable_and_migrated() {
+        let db = Database::memory().unwrap();
+        db.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO providers (id, app_type, name, settings_config, meta)
+                 VALUES ('legacy', 'codex', 'Legacy', ?1, '{}')",
+                [r#"{"auth":{"OPENAI_API_KEY":"sk-legacy"},"config":""}"#],
+            )
+            .unwrap();
+
+        let loaded = db.get_provider_by_id("legacy", "codex").unwrap().unwrap();
+        assert_eq!(
+            loaded.settings_config["auth"]["OPENAI_API_KEY"],
+            "sk-legacy"
+        );
+
+        assert_eq!(db.encrypt_plaintext_provider_secrets().unwrap(), 1);
+        assert!(!raw_settings_config(&db, "legacy").contains("sk-legacy"));
+        assert_eq!(db.encrypt_plaintext_provider_secrets().unwrap(), 0);
+
+        let loaded = db.get_provider_by_id("legacy", "codex").unwrap().unwrap();
+        assert_eq!(
+            loaded.settings_config["auth"]["OPENAI_API_KEY"],
+            "sk-legacy"
+        );
+    }
+}
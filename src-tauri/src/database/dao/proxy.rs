@@ -224,7 +224,8 @@ impl Database {
                 "SELECT app_type, enabled, auto_failover_enabled,
                         max_retries, streaming_first_byte_timeout, streaming_idle_timeout, non_streaming_timeout,
                         circuit_failure_threshold, circuit_success_threshold, circuit_timeout_seconds,
-                        circuit_error_rate_threshold, circuit_min_requests
+                        circuit_error_rate_threshold, circuit_min_requests, routing_strategy,
+                        model_routes, quota_threshold
                  FROM proxy_config WHERE app_type = ?1",
                 [app_type],
                 |row| {
@@ -241,6 +242,13 @@ impl Database {
                         circuit_timeout_seconds: row.get::<_, i32>(9)? as u32,
                         circuit_error_rate_threshold: row.get(10)?,
                         circuit_min_requests: row.get::<_, i32>(11)? as u32,
+                        routing_strategy: row
+                            .get::<_, String>(12)?
+                            .parse()
+                            .unwrap_or_default(),
+                        model_routes: serde_json::from_str(&row.get::<_, String>(13)?)
+                            .unwrap_or_default(),
+                        quota_threshold: row.get::<_, i32>(14)? as u32,
                     })
                 },
             )
@@ -265,6 +273,9 @@ impl Database {
                     circuit_timeout_seconds: 60,
                     circuit_error_rate_threshold: 0.6,
                     circuit_min_requests: 10,
+                    routing_strategy: RoutingStrategy::default(),
+                    model_routes: Vec::new(),
+                    quota_threshold: 0,
                 })
             }
             Err(e) => Err(AppError::Database(e.to_string())),
@@ -276,6 +287,8 @@ impl Database {
         &self,
         config: AppProxyConfig,
     ) -> Result<(), AppError> {
+        let model_routes = serde_json::to_string(&config.model_routes)
+            .map_err(|e| AppError::Database(format!("Failed to serialize model_routes: {e}")))?;
         let conn = lock_conn!(self.conn);
 
         conn.execute(
@@ -291,6 +304,9 @@ impl Database {
                 circuit_timeout_seconds = ?10,
                 circuit_error_rate_threshold = ?11,
                 circuit_min_requests = ?12,
+                routing_strategy = ?13,
+                model_routes = ?14,
+                quota_threshold = ?15,
                 updated_at = datetime('now')
              WHERE app_type = ?1",
             rusqlite::params![
@@ -306,6 +322,9 @@ impl Database {
                 config.circuit_timeout_seconds as i32,
                 config.circuit_error_rate_threshold,
                 config.circuit_min_requests as i32,
+                config.routing_strategy.as_str(),
+                model_routes,
+                config.quota_threshold as i32,
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -526,6 +545,37 @@ impl Database {
 
     // ==================== Provider Health ====================
 
+    /// 获取各供应商近期成功请求的平均延迟（毫秒）
+    ///
+    /// 仅统计代理自身写入的成功请求（`data_source = 'proxy'`，2xx），
+    /// 窗口内没有样本的供应商不会出现在结果中。
+    pub fn get_recent_provider_latencies(
+        &self,
+        app_type: &str,
+        window_secs: i64,
+    ) -> Result<std::collections::HashMap<String, f64>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let since = chrono::Utc::now().timestamp() - window_secs;
+        let mut stmt = conn.prepare(
+            "SELECT provider_id, AVG(latency_ms)
+             FROM proxy_request_logs
+             WHERE app_type = ?1 AND created_at >= ?2
+               AND data_source = 'proxy'
+               AND status_code >= 200 AND status_code < 300
+             GROUP BY provider_id",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![app_type, since], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+        })?;
+
+        let mut latencies = std::collections::HashMap::new();
+        for row in rows {
+            let (provider_id, latency) = row?;
+            latencies.insert(provider_id, latency);
+        }
+        Ok(latencies)
+    }
+
     /// 获取Provider健康状态
     pub async fn get_provider_health(
         &self,
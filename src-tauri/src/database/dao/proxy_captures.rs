@@ -0,0 +1,143 @@
+//! 代理抓包数据访问对象
+//!
+//! proxy_captures 表只保留最近 [`MAX_PROXY_CAPTURES`] 条记录，写入新记录时清理更早的。
+//! 抓包包含完整的提示词与响应，只在本地保存，不参与同步。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::proxy::capture::{ProxyCapture, ProxyCaptureSummary};
+use rusqlite::{params, OptionalExtension};
+
+/// 最多保留的抓包条数
+pub const MAX_PROXY_CAPTURES: i64 = 200;
+
+impl Database {
+    /// 保存抓包记录并清理超出保留条数的旧记录
+    pub fn save_proxy_capture(&self, capture: &ProxyCapture) -> Result<(), AppError> {
+        let data = serde_json::to_string(capture).map_err(|e| AppError::Database(e.to_string()))?;
+        let path = capture
+            .inbound
+            .url
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO proxy_captures
+                (id, app_type, provider_id, method, path, status_code, replay_of, created_at, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                capture.id,
+                capture.app_type,
+                capture.provider_id(),
+                capture.inbound.method,
+                path,
+                capture.status_code(),
+                capture.replay_of,
+                capture.created_at,
+                data,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM proxy_captures WHERE id NOT IN
+                (SELECT id FROM proxy_captures ORDER BY created_at DESC LIMIT ?1)",
+            params![MAX_PROXY_CAPTURES],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 获取完整抓包记录
+    pub fn get_proxy_capture(&self, id: &str) -> Result<Option<ProxyCapture>, AppError> {
+        let data: Option<String> = {
+            let conn = lock_conn!(self.conn);
+            conn.query_row(
+                "SELECT data FROM proxy_captures WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Database(e.to_string()))?
+        };
+        data.map(|data| {
+            serde_json::from_str(&data)
+                .map_err(|e| AppError::Database(format!("解析抓包 {id} 失败: {e}")))
+        })
+        .transpose()
+    }
+
+    /// 按时间倒序列出抓包摘要
+    pub fn list_proxy_captures(&self, limit: u32) -> Result<Vec<ProxyCaptureSummary>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, app_type, provider_id, method, path, status_code, replay_of, created_at
+                 FROM proxy_captures ORDER BY created_at DESC LIMIT ?1",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![limit], |row| {
+                Ok(ProxyCaptureSummary {
+                    id: row.get(0)?,
+                    app_type: row.get(1)?,
+                    provider_id: row.get(2)?,
+                    method: row.get(3)?,
+                    path: row.get(4)?,
+                    status_code: row.get(5)?,
+                    replay_of: row.get(6)?,
+                    created_at: row.get(7)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 清空所有抓包
+    pub fn clear_proxy_captures(&self) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute("DELETE FROM proxy_captures", [])
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(id: &str, created_at: i64) -> ProxyCapture {
+        ProxyCapture {
+            id: id.to_string(),
+            created_at,
+            inbound: crate::proxy::capture::CapturedRequest {
+                method: "POST".to_string(),
+                url: "/v1/messages?beta=true".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn captures_round_trip_and_keep_only_the_newest() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        for i in 0..(MAX_PROXY_CAPTURES + 5) {
+            db.save_proxy_capture(&capture(&format!("cap-{i}"), i))?;
+        }
+
+        let list = db.list_proxy_captures(1000)?;
+        assert_eq!(list.len() as i64, MAX_PROXY_CAPTURES);
+        assert_eq!(list[0].id, format!("cap-{}", MAX_PROXY_CAPTURES + 4));
+        assert_eq!(list[0].path, "/v1/messages");
+        assert!(db.get_proxy_capture("cap-0")?.is_none());
+
+        let newest = db.get_proxy_capture(&list[0].id)?.expect("capture");
+        assert_eq!(newest.inbound.url, "/v1/messages?beta=true");
+
+        assert_eq!(db.clear_proxy_captures()? as i64, MAX_PROXY_CAPTURES);
+        assert!(db.list_proxy_captures(10)?.is_empty());
+        Ok(())
+    }
+}
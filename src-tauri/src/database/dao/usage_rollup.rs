@@ -119,17 +119,18 @@ impl Database {
         let fresh_detail_input = fresh_input_sql("l");
         let fresh_old_input = fresh_input_sql("old");
         // request_model 维度保留路由接管的「客户端别名 → 真实模型」映射，
-        // pricing_model 维度保留写入时的计价基准（request 计价模式下与 model 分叉）；
-        // 明细行的这两列可能为 NULL（历史/手工数据），归一为 ''。
+        // pricing_model 维度保留写入时的计价基准（request 计价模式下与 model 分叉），
+        // project_dir 维度保留按项目归属的成本；明细行的这几列可能为 NULL
+        // （历史/手工数据/未归属会话），归一为 ''。
         let aggregation_sql = format!(
             "INSERT OR REPLACE INTO usage_daily_rollups
-                (date, app_type, provider_id, model, request_model, pricing_model,
+                (date, app_type, provider_id, model, request_model, pricing_model, project_dir,
                  request_count, success_count,
                  input_tokens, output_tokens,
                  cache_read_tokens, cache_creation_tokens,
                  input_token_semantics, total_cost_usd, avg_latency_ms)
             SELECT
-                d, a, p, m, rm, pm,
+                d, a, p, m, rm, pm, pd,
                 COALESCE(old.request_count, 0) + new_req,
                 COALESCE(old.success_count, 0) + new_succ,
                 COALESCE({fresh_old_input}, 0) + new_in,
@@ -149,6 +150,7 @@ impl Database {
                     l.app_type as a, l.provider_id as p, l.model as m,
                     COALESCE(l.request_model, '') as rm,
                     COALESCE(l.pricing_model, '') as pm,
+                    COALESCE(l.project_dir, '') as pd,
                     COUNT(*) as new_req,
                     SUM(CASE WHEN l.status_code >= 200 AND l.status_code < 300 THEN 1 ELSE 0 END) as new_succ,
                     COALESCE(SUM({fresh_detail_input}), 0) as new_in,
@@ -159,12 +161,13 @@ impl Database {
                     COALESCE(AVG(l.latency_ms), 0) as new_lat
                 FROM proxy_request_logs l
                 WHERE l.created_at < ?1 AND {effective_filter}
-                GROUP BY d, a, p, m, rm, pm
+                GROUP BY d, a, p, m, rm, pm, pd
             ) agg
             LEFT JOIN usage_daily_rollups old
                 ON old.date = agg.d AND old.app_type = agg.a
                 AND old.provider_id = agg.p AND old.model = agg.m
-                AND old.request_model = agg.rm AND old.pricing_model = agg.pm"
+                AND old.request_model = agg.rm AND old.pricing_model = agg.pm
+                AND old.project_dir = agg.pd"
         );
 
         conn.execute(&aggregation_sql, [cutoff])
@@ -477,6 +480,47 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_rollup_preserves_project_dimension() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let now = chrono::Utc::now().timestamp();
+        let old_ts = now - 40 * 86400;
+
+        {
+            let conn = crate::database::lock_conn!(db.conn);
+            for (i, project_dir) in [
+                ("a", Some("/work/app")),
+                ("b", Some("/work/app")),
+                ("c", None),
+            ] {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model,
+                        input_tokens, output_tokens, total_cost_usd,
+                        latency_ms, status_code, created_at, project_dir
+                    ) VALUES (?1, 'p1', 'claude', 'claude-sonnet-4-6', 100, 50, '0.01', 100, 200, ?2, ?3)",
+                    rusqlite::params![format!("project-{i}"), old_ts, project_dir],
+                )?;
+            }
+        }
+
+        let deleted = db.rollup_and_prune(30)?;
+        assert_eq!(deleted, 3);
+
+        let conn = crate::database::lock_conn!(db.conn);
+        let mut stmt = conn.prepare(
+            "SELECT project_dir, request_count FROM usage_daily_rollups ORDER BY project_dir",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(rows, vec![(String::new(), 1), ("/work/app".to_string(), 2)]);
+        Ok(())
+    }
+
     #[test]
     fn test_rollup_backfills_costs_before_pruning() -> Result<(), AppError> {
         let db = Database::memory()?;
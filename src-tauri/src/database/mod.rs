@@ -14,6 +14,7 @@
 //! ├── mod.rs        - Database 结构体 + 初始化
 //! ├── schema.rs     - 表结构定义 + Schema 迁移
 //! ├── backup.rs     - SQL 导入导出 + 快照备份
+//! ├── sync_merge.rs - 同步下载的三方合并
 //! ├── migration.rs  - JSON → SQLite 数据迁移
 //! └── dao/          - 数据访问对象
 //!     ├── providers.rs
@@ -27,25 +28,34 @@ pub(crate) mod backup;
 mod dao;
 mod migration;
 mod schema;
+#[cfg(feature = "gui")]
+mod sync_merge;
 
 #[cfg(test)]
 mod tests;
 
 // DAO 类型导出供外部使用
+#[cfg(feature = "gui")]
+pub(crate) use dao::providers_seed::is_official_seed_id;
 pub(crate) use dao::providers_seed::{
-    is_official_seed_id, CLAUDE_DESKTOP_OFFICIAL_PROVIDER_ID, CODEX_OFFICIAL_PROVIDER_ID,
-    GROKBUILD_OFFICIAL_PROVIDER_ID,
+    CLAUDE_DESKTOP_OFFICIAL_PROVIDER_ID, CODEX_OFFICIAL_PROVIDER_ID, GROKBUILD_OFFICIAL_PROVIDER_ID,
 };
 pub(crate) use dao::proxy::{
     validate_cost_multiplier, validate_pricing_source, PRICING_SOURCE_REQUEST,
     PRICING_SOURCE_RESPONSE,
 };
+#[cfg(feature = "gui")]
 pub use dao::FailoverQueueItem;
 pub use dao::Profile;
+pub use dao::ProjectBinding;
+#[cfg(feature = "gui")]
+pub use sync_merge::{SyncConflict, SyncMergeReport};
 
 use crate::config::get_app_config_dir;
 use crate::error::AppError;
-use rusqlite::{hooks::Action, Connection};
+#[cfg(feature = "gui")]
+use rusqlite::hooks::Action;
+use rusqlite::Connection;
 use serde::Serialize;
 use std::sync::Mutex;
 
@@ -53,7 +63,7 @@ use std::sync::Mutex;
 
 /// 当前 Schema 版本号
 /// 每次修改表结构时递增，并在 schema.rs 中添加相应的迁移逻辑
-pub(crate) const SCHEMA_VERSION: i32 = 17;
+pub(crate) const SCHEMA_VERSION: i32 = 27;
 
 /// 安全地序列化 JSON，避免 unwrap panic
 pub(crate) fn to_json_string<T: Serialize>(value: &T) -> Result<String, AppError> {
@@ -81,12 +91,13 @@ pub struct Database {
     pub(crate) conn: Mutex<Connection>,
 }
 
+/// 数据变更时通知自动同步；自动同步只在桌面端运行
+#[cfg(feature = "gui")]
 fn register_db_change_hook(conn: &Connection) {
     conn.update_hook(Some(
         |action: Action, _database: &str, table: &str, _row_id: i64| match action {
             Action::SQLITE_INSERT | Action::SQLITE_UPDATE | Action::SQLITE_DELETE => {
-                crate::services::webdav_auto_sync::notify_db_changed(table);
-                crate::services::s3_auto_sync::notify_db_changed(table);
+                crate::services::auto_sync::notify_db_changed(table);
             }
             _ => {}
         },
@@ -117,6 +128,7 @@ impl Database {
             conn.execute("PRAGMA auto_vacuum = INCREMENTAL;", [])
                 .map_err(|e| AppError::Database(e.to_string()))?;
         }
+        #[cfg(feature = "gui")]
         register_db_change_hook(&conn);
 
         let db = Self {
@@ -191,6 +203,7 @@ impl Database {
             .map_err(|e| AppError::Database(e.to_string()))?;
         conn.execute("PRAGMA auto_vacuum = INCREMENTAL;", [])
             .map_err(|e| AppError::Database(e.to_string()))?;
+        #[cfg(feature = "gui")]
         register_db_change_hook(&conn);
 
         let db = Self {
@@ -72,13 +72,17 @@ impl Database {
             [],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        Self::create_mcp_probe_results_table(conn)?;
+        Self::create_mcp_secrets_table(conn)?;
+        Self::create_project_bindings_table(conn)?;
 
         // 4. Prompts 表
         conn.execute("CREATE TABLE IF NOT EXISTS prompts (
             id TEXT NOT NULL, app_type TEXT NOT NULL, name TEXT NOT NULL, content TEXT NOT NULL,
-            description TEXT, enabled BOOLEAN NOT NULL DEFAULT 1, created_at INTEGER, updated_at INTEGER,
+            description TEXT, enabled BOOLEAN NOT NULL DEFAULT 1, sort_index INTEGER, created_at INTEGER, updated_at INTEGER,
             PRIMARY KEY (id, app_type)
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
+        Self::create_shared_prompts_table(conn)?;
 
         // 5. Skills 表（v3.10.0+ 统一结构）
         conn.execute(
@@ -135,6 +139,9 @@ impl Database {
             circuit_min_requests INTEGER NOT NULL DEFAULT 10,
             default_cost_multiplier TEXT NOT NULL DEFAULT '1',
             pricing_model_source TEXT NOT NULL DEFAULT 'response',
+            routing_strategy TEXT NOT NULL DEFAULT 'priority',
+            model_routes TEXT NOT NULL DEFAULT '[]',
+            quota_threshold INTEGER NOT NULL DEFAULT 0,
             created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now'))
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -194,6 +201,7 @@ impl Database {
         // 10. Proxy Request Logs 表
         // pricing_model = 写入时实际用于计价的模型名（pricing_model_source 解析结果），
         // 回填按它重算；NULL 表示 v11 之前的历史行，'' 表示未计价的错误行。
+        // project_dir = 会话所属项目目录（来自 session_projects），NULL 表示尚未归属。
         conn.execute("CREATE TABLE IF NOT EXISTS proxy_request_logs (
             request_id TEXT PRIMARY KEY, provider_id TEXT NOT NULL, app_type TEXT NOT NULL, model TEXT NOT NULL,
             request_model TEXT,
@@ -207,7 +215,8 @@ impl Database {
             duration_ms INTEGER, status_code INTEGER NOT NULL, error_message TEXT, session_id TEXT,
             provider_type TEXT, is_streaming INTEGER NOT NULL DEFAULT 0,
             cost_multiplier TEXT NOT NULL DEFAULT '1.0', created_at INTEGER NOT NULL,
-            data_source TEXT NOT NULL DEFAULT 'proxy'
+            data_source TEXT NOT NULL DEFAULT 'proxy',
+            project_dir TEXT
         )", []).map_err(|e| AppError::Database(e.to_string()))?;
 
         conn.execute("CREATE INDEX IF NOT EXISTS idx_request_logs_provider ON proxy_request_logs(provider_id, app_type)", [])
@@ -230,6 +239,7 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
         Self::create_request_logs_usage_indexes_if_supported(conn)?;
+        Self::create_proxy_captures_table(conn)?;
 
         // 11. Model Pricing 表
         conn.execute(
@@ -273,6 +283,7 @@ impl Database {
         // request_model 保留路由接管的「客户端别名 → 真实模型」映射维度，
         // pricing_model 保留写入时的计价基准（request 计价模式下与 model 分叉），
         // 否则明细被 prune 后接管计费不可审计；历史行迁移时填 ''（未知）。
+        // project_dir 保留按项目归属的成本维度，'' 表示未归属。
         conn.execute(
             "CREATE TABLE IF NOT EXISTS usage_daily_rollups (
                 date TEXT NOT NULL,
@@ -281,6 +292,7 @@ impl Database {
                 model TEXT NOT NULL,
                 request_model TEXT NOT NULL DEFAULT '',
                 pricing_model TEXT NOT NULL DEFAULT '',
+                project_dir TEXT NOT NULL DEFAULT '',
                 request_count INTEGER NOT NULL DEFAULT 0,
                 success_count INTEGER NOT NULL DEFAULT 0,
                 input_tokens INTEGER NOT NULL DEFAULT 0,
@@ -290,7 +302,7 @@ impl Database {
                 input_token_semantics INTEGER NOT NULL DEFAULT 0,
                 total_cost_usd TEXT NOT NULL DEFAULT '0',
                 avg_latency_ms INTEGER NOT NULL DEFAULT 0,
-                PRIMARY KEY (date, app_type, provider_id, model, request_model, pricing_model)
+                PRIMARY KEY (date, app_type, provider_id, model, request_model, pricing_model, project_dir)
             )",
             [],
         )
@@ -327,6 +339,7 @@ impl Database {
             [],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        Self::create_session_projects_table(conn)?;
 
         // 19. Profiles 表（全应用共享的项目实体，payload 按 app 分槽快照
         //     供应商/MCP/Skills/Prompt；各应用分组的 current 标记在 settings 表）
@@ -536,6 +549,56 @@ impl Database {
                         Self::migrate_v16_to_v17(conn)?;
                         Self::set_user_version(conn, 17)?;
                     }
+                    17 => {
+                        log::info!("迁移数据库从 v17 到 v18（故障转移队列路由策略）");
+                        Self::migrate_v17_to_v18(conn)?;
+                        Self::set_user_version(conn, 18)?;
+                    }
+                    18 => {
+                        log::info!("迁移数据库从 v18 到 v19（提示词片段排序）");
+                        Self::migrate_v18_to_v19(conn)?;
+                        Self::set_user_version(conn, 19)?;
+                    }
+                    19 => {
+                        log::info!("迁移数据库从 v19 到 v20（共享提示词）");
+                        Self::create_shared_prompts_table(conn)?;
+                        Self::set_user_version(conn, 20)?;
+                    }
+                    20 => {
+                        log::info!("迁移数据库从 v20 到 v21（MCP 探测结果）");
+                        Self::create_mcp_probe_results_table(conn)?;
+                        Self::set_user_version(conn, 21)?;
+                    }
+                    21 => {
+                        log::info!("迁移数据库从 v21 到 v22（MCP 本地密钥库）");
+                        Self::create_mcp_secrets_table(conn)?;
+                        Self::set_user_version(conn, 22)?;
+                    }
+                    22 => {
+                        log::info!("迁移数据库从 v22 到 v23（项目绑定）");
+                        Self::create_project_bindings_table(conn)?;
+                        Self::set_user_version(conn, 23)?;
+                    }
+                    23 => {
+                        log::info!("迁移数据库从 v23 到 v24（按模型路由规则）");
+                        Self::migrate_v23_to_v24(conn)?;
+                        Self::set_user_version(conn, 24)?;
+                    }
+                    24 => {
+                        log::info!("迁移数据库从 v24 到 v25（代理抓包）");
+                        Self::create_proxy_captures_table(conn)?;
+                        Self::set_user_version(conn, 25)?;
+                    }
+                    25 => {
+                        log::info!("迁移数据库从 v25 到 v26（额度感知的故障转移）");
+                        Self::migrate_v25_to_v26(conn)?;
+                        Self::set_user_version(conn, 26)?;
+                    }
+                    26 => {
+                        log::info!("迁移数据库从 v26 到 v27（按项目/会话归属成本）");
+                        Self::migrate_v26_to_v27(conn)?;
+                        Self::set_user_version(conn, 27)?;
+                    }
                     _ => {
                         return Err(AppError::Database(format!(
                             "未知的数据库版本 {version}，无法迁移到 {SCHEMA_VERSION}"
@@ -1545,7 +1608,67 @@ impl Database {
     /// schema migration already owns the Database connection mutex.
     fn migrate_v15_to_v16(conn: &Connection) -> Result<(), AppError> {
         let codex_dir = crate::codex_config::get_codex_config_dir();
-        crate::services::session_usage_codex::reset_codex_usage_on_conn(conn, &codex_dir)
+        Self::reset_codex_usage_on_conn(conn, &codex_dir)
+    }
+
+    /// 清理 Codex 会话明细、汇总与同步 cursor。迁移与手动重建共用，
+    /// 不依赖桌面端的会话同步模块，无界面构建同样需要它。
+    pub(crate) fn reset_codex_usage_on_conn(
+        conn: &rusqlite::Connection,
+        codex_dir: &std::path::Path,
+    ) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_request_logs")?
+            && Self::has_column(conn, "proxy_request_logs", "data_source")?
+        {
+            conn.execute(
+                "DELETE FROM proxy_request_logs WHERE data_source = 'codex_session'",
+                [],
+            )
+            .map_err(|error| AppError::Database(format!("清理 Codex 会话明细失败: {error}")))?;
+        }
+        if Self::table_exists(conn, "usage_daily_rollups")?
+            && Self::has_column(conn, "usage_daily_rollups", "provider_id")?
+        {
+            conn.execute(
+                "DELETE FROM usage_daily_rollups WHERE provider_id = '_codex_session'",
+                [],
+            )
+            .map_err(|error| AppError::Database(format!("清理 Codex 用量汇总失败: {error}")))?;
+        }
+        if Self::table_exists(conn, "session_log_sync")?
+            && Self::has_column(conn, "session_log_sync", "file_path")?
+        {
+            let paths = {
+                let mut statement = conn
+                    .prepare("SELECT file_path FROM session_log_sync")
+                    .map_err(|error| {
+                        AppError::Database(format!("读取会话同步 cursor 失败: {error}"))
+                    })?;
+                let paths = statement
+                    .query_map([], |row| row.get::<_, String>(0))
+                    .map_err(|error| {
+                        AppError::Database(format!("查询会话同步 cursor 失败: {error}"))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|error| {
+                        AppError::Database(format!("解析会话同步 cursor 失败: {error}"))
+                    })?;
+                paths
+            };
+            for file_path in paths
+                .into_iter()
+                .filter(|path| is_codex_cursor_path(path, codex_dir))
+            {
+                conn.execute(
+                    "DELETE FROM session_log_sync WHERE file_path = ?1",
+                    [file_path],
+                )
+                .map_err(|error| {
+                    AppError::Database(format!("清理 Codex 同步 cursor 失败: {error}"))
+                })?;
+            }
+        }
+        Ok(())
     }
 
     /// v16 -> v17: preserve session request identities after detail rollup.
@@ -1565,6 +1688,238 @@ impl Database {
         Ok(())
     }
 
+    /// v17 -> v18: per-app routing strategy for the failover queue.
+    fn migrate_v17_to_v18(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_config")? {
+            Self::add_column_if_missing(
+                conn,
+                "proxy_config",
+                "routing_strategy",
+                "TEXT NOT NULL DEFAULT 'priority'",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// v18 -> v19: order of prompt fragments enabled together.
+    fn migrate_v18_to_v19(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "prompts")? {
+            Self::add_column_if_missing(conn, "prompts", "sort_index", "INTEGER")?;
+        }
+        Ok(())
+    }
+
+    /// 共享提示词表（v20）：同一份提示词同步到多个应用
+    fn create_shared_prompts_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS shared_prompts (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                description TEXT,
+                apps TEXT NOT NULL DEFAULT '{}',
+                appendices TEXT NOT NULL DEFAULT '{}',
+                sort_index INTEGER,
+                created_at INTEGER,
+                updated_at INTEGER
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 shared_prompts 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// MCP 探测结果表（v21）：每个服务器只保留最近一次探测
+    fn create_mcp_probe_results_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mcp_probe_results (
+                server_id TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                server_name TEXT,
+                server_version TEXT,
+                protocol_version TEXT,
+                tools TEXT NOT NULL DEFAULT '[]',
+                resources TEXT NOT NULL DEFAULT '[]',
+                error TEXT,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                probed_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 mcp_probe_results 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// MCP 本地密钥库（v22）：值经主密钥加密，且不参与同步
+    fn create_mcp_secrets_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mcp_secrets (
+                name TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 mcp_secrets 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// 项目绑定表（v23）：目录 × 应用 → 供应商/MCP 集合
+    ///
+    /// `applied` 记录上次写入项目配置的片段（加密），解绑时据此精确撤销
+    fn create_project_bindings_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_bindings (
+                path TEXT NOT NULL,
+                app_type TEXT NOT NULL,
+                provider_id TEXT,
+                mcp_server_ids TEXT NOT NULL DEFAULT '[]',
+                applied TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (path, app_type)
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 project_bindings 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// v23 -> v24: per-app model routing rules evaluated before the failover queue.
+    fn migrate_v23_to_v24(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_config")? {
+            Self::add_column_if_missing(
+                conn,
+                "proxy_config",
+                "model_routes",
+                "TEXT NOT NULL DEFAULT '[]'",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// v25 -> v26: per-app quota threshold for quota-aware failover.
+    fn migrate_v25_to_v26(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_config")? {
+            Self::add_column_if_missing(
+                conn,
+                "proxy_config",
+                "quota_threshold",
+                "INTEGER NOT NULL DEFAULT 0",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// v26 -> v27：用量按项目目录归属。
+    ///
+    /// proxy_request_logs 增加 project_dir 列；usage_daily_rollups 把 project_dir
+    /// 纳入主键（SQLite 改主键必须重建表，历史行填 '' 表示未归属），否则明细
+    /// prune 后项目维度永久丢失；新建 session_projects 记录会话 → 项目映射。
+    fn migrate_v26_to_v27(conn: &Connection) -> Result<(), AppError> {
+        if Self::table_exists(conn, "proxy_request_logs")? {
+            Self::add_column_if_missing(conn, "proxy_request_logs", "project_dir", "TEXT")?;
+            Self::create_request_logs_usage_indexes_if_supported(conn)?;
+        }
+        Self::create_session_projects_table(conn)?;
+
+        if !Self::table_exists(conn, "usage_daily_rollups")?
+            || Self::has_column(conn, "usage_daily_rollups", "project_dir")?
+        {
+            return Ok(());
+        }
+        // 缺少 v11 主键列的残缺表无法按原形态复制，只补列
+        if !Self::has_column(conn, "usage_daily_rollups", "pricing_model")? {
+            Self::add_column_if_missing(
+                conn,
+                "usage_daily_rollups",
+                "project_dir",
+                "TEXT NOT NULL DEFAULT ''",
+            )?;
+            return Ok(());
+        }
+
+        conn.execute_batch(
+            "ALTER TABLE usage_daily_rollups RENAME TO usage_daily_rollups_v26;
+             CREATE TABLE usage_daily_rollups (
+                 date TEXT NOT NULL,
+                 app_type TEXT NOT NULL,
+                 provider_id TEXT NOT NULL,
+                 model TEXT NOT NULL,
+                 request_model TEXT NOT NULL DEFAULT '',
+                 pricing_model TEXT NOT NULL DEFAULT '',
+                 project_dir TEXT NOT NULL DEFAULT '',
+                 request_count INTEGER NOT NULL DEFAULT 0,
+                 success_count INTEGER NOT NULL DEFAULT 0,
+                 input_tokens INTEGER NOT NULL DEFAULT 0,
+                 output_tokens INTEGER NOT NULL DEFAULT 0,
+                 cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+                 cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+                 input_token_semantics INTEGER NOT NULL DEFAULT 0,
+                 total_cost_usd TEXT NOT NULL DEFAULT '0',
+                 avg_latency_ms INTEGER NOT NULL DEFAULT 0,
+                 PRIMARY KEY (date, app_type, provider_id, model, request_model, pricing_model, project_dir)
+             );
+             INSERT INTO usage_daily_rollups
+                 (date, app_type, provider_id, model, request_model, pricing_model, project_dir,
+                  request_count, success_count, input_tokens, output_tokens,
+                  cache_read_tokens, cache_creation_tokens, input_token_semantics,
+                  total_cost_usd, avg_latency_ms)
+             SELECT date, app_type, provider_id, model, request_model, pricing_model, '',
+                  request_count, success_count, input_tokens, output_tokens,
+                  cache_read_tokens, cache_creation_tokens, input_token_semantics,
+                  total_cost_usd, avg_latency_ms
+             FROM usage_daily_rollups_v26;
+             DROP TABLE usage_daily_rollups_v26;",
+        )
+        .map_err(|e| {
+            AppError::Database(format!("v26 -> v27 重建 usage_daily_rollups 失败: {e}"))
+        })?;
+        Ok(())
+    }
+
+    /// 会话 → 项目目录映射表（v27）：由会话日志同步写入，代理请求落库时
+    /// 按 session_id 查它补 project_dir；source_path 供统计页回链会话消息。
+    fn create_session_projects_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_projects (
+                session_id TEXT PRIMARY KEY,
+                app_type TEXT NOT NULL,
+                project_dir TEXT NOT NULL,
+                source_path TEXT,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 session_projects 表失败: {e}")))?;
+        Ok(())
+    }
+
+    /// 代理抓包表（v25）：摘要列用于列表，完整记录（JSON）放在 `data`
+    fn create_proxy_captures_table(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS proxy_captures (
+                id TEXT PRIMARY KEY,
+                app_type TEXT,
+                provider_id TEXT,
+                method TEXT NOT NULL,
+                path TEXT NOT NULL,
+                status_code INTEGER,
+                replay_of TEXT,
+                created_at INTEGER NOT NULL,
+                data TEXT NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(format!("创建 proxy_captures 表失败: {e}")))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_proxy_captures_created_at ON proxy_captures(created_at)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// 插入默认模型定价数据
     /// 格式: (model_id, display_name, input, output, cache_read, cache_creation)
     /// 注意: model_id 使用短横线格式（如 claude-haiku-4-5），与 API 返回的模型名称标准化后一致
@@ -3116,6 +3471,15 @@ impl Database {
             .map_err(|e| AppError::Database(format!("创建使用量应用时间索引失败: {e}")))?;
         }
 
+        if has_created_at && Self::has_column(conn, "proxy_request_logs", "project_dir")? {
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_request_logs_project
+                 ON proxy_request_logs(project_dir, created_at DESC)",
+                [],
+            )
+            .map_err(|e| AppError::Database(format!("创建使用量项目索引失败: {e}")))?;
+        }
+
         let required_columns = [
             "app_type",
             "data_source",
@@ -3232,6 +3596,36 @@ impl Database {
     }
 }
 
+fn is_rollout_filename(file_name: &str) -> bool {
+    if !file_name.starts_with("rollout-") || !file_name.ends_with(".jsonl") {
+        return false;
+    }
+    let stem = file_name.trim_end_matches(".jsonl");
+    stem.get(stem.len().saturating_sub(36)..)
+        .is_some_and(|candidate| uuid::Uuid::parse_str(candidate).is_ok())
+}
+
+fn is_codex_cursor_path(file_path: &str, codex_dir: &std::path::Path) -> bool {
+    let path = std::path::Path::new(file_path);
+    let file_name = file_path.rsplit(['/', '\\']).next().unwrap_or_default();
+    if !is_rollout_filename(file_name) {
+        return false;
+    }
+
+    if path.starts_with(codex_dir.join("sessions"))
+        || path.starts_with(codex_dir.join("archived_sessions"))
+    {
+        return true;
+    }
+
+    // 兼容用户改过 CODEX_HOME 后遗留、且源文件已不存在的 cursor。只接受
+    // 明确目录段 + Codex rollout UUID 文件名，避免宽 codex_dir 误删其他 importer。
+    file_path
+        .replace('\\', "/")
+        .split('/')
+        .any(|segment| matches!(segment, "sessions" | "archived_sessions"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3409,4 +3803,215 @@ mod tests {
         )?;
         Ok(())
     }
+
+    #[test]
+    fn migrate_v17_to_v18_adds_routing_strategy_with_priority_default() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute(
+            "CREATE TABLE proxy_config (app_type TEXT PRIMARY KEY, max_retries INTEGER)",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO proxy_config (app_type, max_retries) VALUES ('claude', 6)",
+            [],
+        )?;
+        Database::set_user_version(&conn, 17)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        let strategy: String = conn.query_row(
+            "SELECT routing_strategy FROM proxy_config WHERE app_type = 'claude'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(strategy, "priority");
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v18_to_v19_adds_prompt_sort_index() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute(
+            "CREATE TABLE prompts (id TEXT NOT NULL, app_type TEXT NOT NULL, name TEXT NOT NULL,
+             content TEXT NOT NULL, enabled BOOLEAN NOT NULL DEFAULT 1,
+             PRIMARY KEY (id, app_type))",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO prompts (id, app_type, name, content) VALUES ('p', 'claude', 'P', 'x')",
+            [],
+        )?;
+        Database::set_user_version(&conn, 18)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        let sort_index: Option<i64> =
+            conn.query_row("SELECT sort_index FROM prompts WHERE id = 'p'", [], |row| {
+                row.get(0)
+            })?;
+        assert_eq!(sort_index, None);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v19_to_v20_creates_shared_prompts_table() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        Database::set_user_version(&conn, 19)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        assert!(Database::table_exists(&conn, "shared_prompts")?);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v20_to_v21_creates_mcp_probe_results_table() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        Database::set_user_version(&conn, 20)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        assert!(Database::table_exists(&conn, "mcp_probe_results")?);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v21_to_v22_creates_mcp_secrets_table() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        Database::set_user_version(&conn, 21)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        assert!(Database::table_exists(&conn, "mcp_secrets")?);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v22_to_v23_creates_project_bindings_table() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        Database::set_user_version(&conn, 22)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        assert!(Database::table_exists(&conn, "project_bindings")?);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v23_to_v24_adds_empty_model_routes() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute(
+            "CREATE TABLE proxy_config (app_type TEXT PRIMARY KEY, routing_strategy TEXT)",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO proxy_config (app_type, routing_strategy) VALUES ('claude', 'priority')",
+            [],
+        )?;
+        Database::set_user_version(&conn, 23)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        let routes: String = conn.query_row(
+            "SELECT model_routes FROM proxy_config WHERE app_type = 'claude'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(routes, "[]");
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v24_to_v25_creates_proxy_captures_table() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        Database::set_user_version(&conn, 24)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        assert!(Database::table_exists(&conn, "proxy_captures")?);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v25_to_v26_adds_disabled_quota_threshold() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute(
+            "CREATE TABLE proxy_config (app_type TEXT PRIMARY KEY, model_routes TEXT)",
+            [],
+        )?;
+        conn.execute("INSERT INTO proxy_config (app_type) VALUES ('codex')", [])?;
+        Database::set_user_version(&conn, 25)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        let threshold: i64 = conn.query_row(
+            "SELECT quota_threshold FROM proxy_config WHERE app_type = 'codex'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(threshold, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v26_to_v27_adds_project_dimension() -> Result<(), AppError> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            "CREATE TABLE proxy_request_logs (request_id TEXT PRIMARY KEY, session_id TEXT);
+             CREATE TABLE usage_daily_rollups (
+                 date TEXT NOT NULL,
+                 app_type TEXT NOT NULL,
+                 provider_id TEXT NOT NULL,
+                 model TEXT NOT NULL,
+                 request_model TEXT NOT NULL DEFAULT '',
+                 pricing_model TEXT NOT NULL DEFAULT '',
+                 request_count INTEGER NOT NULL DEFAULT 0,
+                 success_count INTEGER NOT NULL DEFAULT 0,
+                 input_tokens INTEGER NOT NULL DEFAULT 0,
+                 output_tokens INTEGER NOT NULL DEFAULT 0,
+                 cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+                 cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+                 input_token_semantics INTEGER NOT NULL DEFAULT 0,
+                 total_cost_usd TEXT NOT NULL DEFAULT '0',
+                 avg_latency_ms INTEGER NOT NULL DEFAULT 0,
+                 PRIMARY KEY (date, app_type, provider_id, model, request_model, pricing_model)
+             );
+             INSERT INTO usage_daily_rollups
+                 (date, app_type, provider_id, model, request_count, input_token_semantics, total_cost_usd)
+             VALUES ('2026-09-01', 'claude', 'p1', 'claude-sonnet-4-5', 7, 2, '1.5');",
+        )?;
+        Database::set_user_version(&conn, 26)?;
+
+        Database::apply_schema_migrations_on_conn(&conn)?;
+
+        assert_eq!(Database::get_user_version(&conn)?, SCHEMA_VERSION);
+        assert!(Database::has_column(
+            &conn,
+            "proxy_request_logs",
+            "project_dir"
+        )?);
+        assert!(Database::table_exists(&conn, "session_projects")?);
+        let migrated: (String, i64, i64, String) = conn.query_row(
+            "SELECT project_dir, request_count, input_token_semantics, total_cost_usd
+             FROM usage_daily_rollups",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        assert_eq!(migrated, (String::new(), 7, 2, "1.5".to_string()));
+        conn.execute(
+            "INSERT INTO usage_daily_rollups (date, app_type, provider_id, model, project_dir)
+             VALUES ('2026-09-01', 'claude', 'p1', 'claude-sonnet-4-5', '/work/app')",
+            [],
+        )?;
+        Ok(())
+    }
 }
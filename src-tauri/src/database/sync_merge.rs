@@ -0,0 +1,678 @@
+//! 同步下载的三方合并
+//!
+//! 以上次同步时双方一致的快照（base）为共同祖先，对配置表逐行比较本地（local）
+//! 与远端（remote）：
+//! - 只有一方相对 base 有改动（含新增、删除）→ 采用改动的一方
+//! - 两方改成了相同内容 → 直接采用
+//! - 两方改成了不同内容 → 冲突：先保留本地行，并把两侧内容记入冲突列表交给 UI 处理
+//!
+//! 合并结果写回远端快照的暂存库，之后沿用 SQL 导入的整库替换流程落到主库，
+//! 安全备份与本地专属表的保留逻辑都与全量导入一致。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rusqlite::backup::Backup;
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+use super::backup::{lock_backup_file_operations, SYNC_PRESERVE_TABLES};
+use super::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::sync_protocol::SYNC_CONFIG_TABLES;
+
+/// 行标识不用主键的表：自增 id 在各设备上独立分配，不能跨设备比较
+const NATURAL_KEYS: &[(&str, &[&str])] =
+    &[("provider_endpoints", &["provider_id", "app_type", "url"])];
+
+/// 一行在两端被改成不同内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    /// `表名:行标识`，用于解决冲突时定位
+    pub id: String,
+    pub table: String,
+    /// 行标识列 → 值
+    pub key: Map<String, JsonValue>,
+    /// 本地当前内容；`None` 表示本地已删除
+    pub local: Option<Map<String, JsonValue>>,
+    /// 远端快照内容；`None` 表示远端已删除
+    pub remote: Option<Map<String, JsonValue>>,
+}
+
+/// 一次合并下载的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncMergeReport {
+    /// 采用远端改动的行数
+    pub applied_remote: usize,
+    /// 保留本地改动的行数（不含冲突）
+    pub kept_local: usize,
+    pub conflicts: Vec<SyncConflict>,
+    /// 合并结果中来自本地的 skill 目录，恢复 skills.zip 时需保留本地文件
+    #[serde(skip)]
+    pub local_skill_dirs: Vec<String>,
+}
+
+/// 一张表按行标识索引的全部行
+#[derive(Default)]
+struct TableRows {
+    columns: Vec<String>,
+    key_columns: Vec<String>,
+    rows: BTreeMap<String, Vec<Value>>,
+}
+
+impl Database {
+    /// 导入同步 SQL，并对配置表做基于 `base_sql` 的三方合并
+    ///
+    /// `before_replace` 在合并结果确定后、替换主库前调用（此时仍持有主库锁），
+    /// 用于让 Skills 文件与合并后的 skill 行保持一致；返回错误则放弃替换。
+    pub(crate) fn import_sql_string_for_sync_merge<F>(
+        &self,
+        sql_raw: &str,
+        base_sql: &str,
+        before_replace: F,
+    ) -> Result<SyncMergeReport, AppError>
+    where
+        F: FnOnce(&SyncMergeReport) -> Result<(), AppError>,
+    {
+        let (_remote_file, staging) = Self::stage_sql_import(sql_raw)?;
+        let (_base_file, base) = Self::stage_sql_import(base_sql)?;
+
+        let backup_file_guard = lock_backup_file_operations()?;
+        let mut main_conn = lock_conn!(self.conn);
+        Self::backup_database_file_from_conn(&backup_file_guard, &main_conn, &[])?;
+        Self::restore_tables(&main_conn, &staging, SYNC_PRESERVE_TABLES)?;
+
+        let report = merge_into_staging(&staging, &main_conn, &base)?;
+        Self::seal_provider_secrets(&staging)?;
+        before_replace(&report)?;
+
+        let backup =
+            Backup::new(&staging, &mut main_conn).map_err(|e| AppError::Database(e.to_string()))?;
+        Self::complete_backup(&backup, "替换主数据库")?;
+        Ok(report)
+    }
+
+    /// 解决一条同步冲突：`take_remote` 时用远端内容覆盖本地行，否则保持本地不变
+    pub(crate) fn resolve_sync_conflict(
+        &self,
+        conflict: &SyncConflict,
+        take_remote: bool,
+    ) -> Result<(), AppError> {
+        if !take_remote {
+            return Ok(());
+        }
+        if !SYNC_CONFIG_TABLES.contains(&conflict.table.as_str()) {
+            return Err(AppError::InvalidInput(format!(
+                "不支持的同步表: {}",
+                conflict.table
+            )));
+        }
+
+        let conn = lock_conn!(self.conn);
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let key_columns: Vec<String> = conflict.key.keys().cloned().collect();
+        let key_values: Vec<Value> = conflict.key.values().map(json_to_sql).collect();
+        delete_row(&tx, &conflict.table, &key_columns, &key_values)?;
+        if let Some(remote) = &conflict.remote {
+            let columns: Vec<String> = remote.keys().cloned().collect();
+            let values: Vec<Value> = remote.values().map(json_to_sql).collect();
+            insert_row(&tx, &conflict.table, &columns, &values)?;
+            if conflict.table == "providers" {
+                // 冲突里保存的是解密后的行，写回前重新加密
+                Self::seal_provider_secrets(&tx)?;
+            }
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))
+    }
+}
+
+/// 把本地与 base 的差异合并进暂存库（暂存库初始内容即远端快照）
+pub(crate) fn merge_into_staging(
+    staging: &Connection,
+    local: &Connection,
+    base: &Connection,
+) -> Result<SyncMergeReport, AppError> {
+    let tx = staging
+        .unchecked_transaction()
+        .map_err(|e| AppError::Database(format!("开启合并事务失败: {e}")))?;
+    let mut report = SyncMergeReport::default();
+
+    for table in SYNC_CONFIG_TABLES {
+        if !Database::table_exists(&tx, table)?
+            || !Database::table_exists(local, table)?
+            || !Database::table_exists(base, table)?
+        {
+            continue;
+        }
+        let Some(mut remote_rows) = read_table(&tx, table, None)? else {
+            log::warn!("[Sync] 表 {table} 没有行标识，跳过合并并采用远端内容");
+            continue;
+        };
+        let mut local_rows = read_table(local, table, Some(&remote_rows))?
+            .unwrap_or_default()
+            .rows;
+        let mut base_rows = read_table(base, table, Some(&remote_rows))?
+            .unwrap_or_default()
+            .rows;
+        if *table == "providers" {
+            // 密文带随机 nonce，且各端可能是不同形态（本机密文 / 快照明文），
+            // 一律解密后再比较，未改动的行才不会被误判为冲突
+            let columns = remote_rows.columns.clone();
+            for rows in [&mut remote_rows.rows, &mut local_rows, &mut base_rows] {
+                open_secret_column(&columns, rows)?;
+            }
+        }
+        merge_table(
+            &tx,
+            table,
+            &remote_rows,
+            &local_rows,
+            &base_rows,
+            &mut report,
+        )?;
+    }
+
+    normalize_current_providers(&tx, local)?;
+    tx.commit()
+        .map_err(|e| AppError::Database(format!("提交合并事务失败: {e}")))?;
+    Ok(report)
+}
+
+/// 把 providers 行里的 settings_config 解密为明文，用于比较与合并
+fn open_secret_column(
+    columns: &[String],
+    rows: &mut BTreeMap<String, Vec<Value>>,
+) -> Result<(), AppError> {
+    let Some(idx) = columns.iter().position(|c| c == "settings_config") else {
+        return Ok(());
+    };
+    for row in rows.values_mut() {
+        let Value::Text(raw) = &row[idx] else {
+            continue;
+        };
+        let Ok(mut settings_config) = serde_json::from_str::<JsonValue>(raw) else {
+            continue;
+        };
+        crate::secrets::decrypt_secret_fields(&mut settings_config).map_err(|e| {
+            AppError::localized(
+                "sync.provider_secret_undecryptable",
+                format!(
+                    "同步快照中的供应商密钥无法用本机主密钥解密: {e}。请在所有设备上配置相同的同步加密口令后重新上传"
+                ),
+                format!(
+                    "Provider secrets in the sync snapshot cannot be decrypted with this device's master key: {e}. Configure the same sync passphrase on every device and upload again"
+                ),
+            )
+        })?;
+        row[idx] = Value::Text(
+            serde_json::to_string(&settings_config)
+                .map_err(|e| AppError::JsonSerialize { source: e })?,
+        );
+    }
+    Ok(())
+}
+
+fn merge_table(
+    staging: &Connection,
+    table: &str,
+    remote: &TableRows,
+    local: &BTreeMap<String, Vec<Value>>,
+    base: &BTreeMap<String, Vec<Value>>,
+    report: &mut SyncMergeReport,
+) -> Result<(), AppError> {
+    let key_indexes: Vec<usize> = remote
+        .key_columns
+        .iter()
+        .filter_map(|key| remote.columns.iter().position(|c| c == key))
+        .collect();
+    let directory_index = (table == "skills")
+        .then(|| remote.columns.iter().position(|c| c == "directory"))
+        .flatten();
+
+    let keys: BTreeSet<&String> = remote
+        .rows
+        .keys()
+        .chain(local.keys())
+        .chain(base.keys())
+        .collect();
+    for key in keys {
+        let (base_row, local_row, remote_row) =
+            (base.get(key), local.get(key), remote.rows.get(key));
+        if local_row == remote_row {
+            continue;
+        }
+        if local_row == base_row {
+            report.applied_remote += 1;
+            continue;
+        }
+        // 本地改动或冲突：暂存库里的远端行换成本地行（本地已删除则删掉）
+        let Some(source_row) = local_row.or(remote_row) else {
+            continue;
+        };
+        let key_values: Vec<Value> = key_indexes
+            .iter()
+            .map(|&idx| source_row[idx].clone())
+            .collect();
+        if remote_row != base_row {
+            report.conflicts.push(SyncConflict {
+                id: format!("{table}:{key}"),
+                table: table.to_string(),
+                key: row_to_json(&remote.key_columns, &key_values),
+                local: local_row.map(|row| row_to_json(&remote.columns, row)),
+                remote: remote_row.map(|row| row_to_json(&remote.columns, row)),
+            });
+        } else {
+            report.kept_local += 1;
+        }
+
+        delete_row(staging, table, &remote.key_columns, &key_values)?;
+        if let Some(row) = local_row {
+            insert_row(staging, table, &remote.columns, row)?;
+            if let Some(Value::Text(dir)) = directory_index.map(|idx| &row[idx]) {
+                report.local_skill_dirs.push(dir.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 两端各自切换了当前供应商时，合并后可能出现多个 `is_current`，以本地为准
+fn normalize_current_providers(staging: &Connection, local: &Connection) -> Result<(), AppError> {
+    let mut stmt = local
+        .prepare("SELECT app_type, id FROM providers WHERE is_current = 1")
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let local_current = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    for (app_type, id) in local_current {
+        let current_count: i64 = staging
+            .query_row(
+                "SELECT COUNT(*) FROM providers WHERE app_type = ?1 AND is_current = 1",
+                [&app_type],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        if current_count > 1 {
+            staging
+                .execute(
+                    "UPDATE providers SET is_current = (id = ?2) WHERE app_type = ?1",
+                    [&app_type, &id],
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+/// 读取整张表；`layout` 为空时按本表 schema 推导列与行标识，否则沿用给定布局。
+/// 表没有主键也没有自然键时返回 `None`。
+fn read_table(
+    conn: &Connection,
+    table: &str,
+    layout: Option<&TableRows>,
+) -> Result<Option<TableRows>, AppError> {
+    let (columns, key_columns) = match layout {
+        Some(layout) => (layout.columns.clone(), layout.key_columns.clone()),
+        None => match table_layout(conn, table)? {
+            Some(layout) => (layout.columns, layout.key_columns),
+            None => return Ok(None),
+        },
+    };
+    let present = Database::get_table_columns(conn, table)?;
+    if columns.iter().any(|column| !present.contains(column)) {
+        return Err(AppError::Database(format!(
+            "表 {table} 的列与远端快照不一致，无法合并"
+        )));
+    }
+
+    let key_indexes: Vec<usize> = key_columns
+        .iter()
+        .filter_map(|key| columns.iter().position(|c| c == key))
+        .collect();
+    let quoted_columns = columns
+        .iter()
+        .map(|c| Database::quote_identifier(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {quoted_columns} FROM {}",
+            Database::quote_identifier(table)
+        ))
+        .map_err(|e| AppError::Database(format!("读取表 {table} 失败: {e}")))?;
+    let mut query = stmt
+        .query([])
+        .map_err(|e| AppError::Database(format!("查询表 {table} 数据失败: {e}")))?;
+
+    let mut rows = BTreeMap::new();
+    while let Some(row) = query
+        .next()
+        .map_err(|e| AppError::Database(e.to_string()))?
+    {
+        let values = (0..columns.len())
+            .map(|idx| row.get::<_, Value>(idx))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let key = row_key(key_indexes.iter().map(|&idx| &values[idx]));
+        rows.insert(key, values);
+    }
+
+    Ok(Some(TableRows {
+        columns,
+        key_columns,
+        rows,
+    }))
+}
+
+/// 参与比较的列与行标识列。自然键表不比较自增 id，写回时交给 SQLite 重新分配
+fn table_layout(conn: &Connection, table: &str) -> Result<Option<TableRows>, AppError> {
+    if let Some((_, keys)) = NATURAL_KEYS.iter().find(|(name, _)| *name == table) {
+        let columns = Database::get_table_columns(conn, table)?
+            .into_iter()
+            .filter(|column| column != "id")
+            .collect();
+        return Ok(Some(TableRows {
+            columns,
+            key_columns: keys.iter().map(|key| key.to_string()).collect(),
+            rows: BTreeMap::new(),
+        }));
+    }
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "PRAGMA table_info({})",
+            Database::quote_identifier(table)
+        ))
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let mut columns = Vec::new();
+    let mut keys = Vec::new();
+    let info = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(1)?, row.get::<_, i64>(5)?))
+        })
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    for entry in info {
+        let (name, pk) = entry.map_err(|e| AppError::Database(e.to_string()))?;
+        if pk > 0 {
+            keys.push((pk, name.clone()));
+        }
+        columns.push(name);
+    }
+    if keys.is_empty() {
+        return Ok(None);
+    }
+    keys.sort();
+    Ok(Some(TableRows {
+        columns,
+        key_columns: keys.into_iter().map(|(_, name)| name).collect(),
+        rows: BTreeMap::new(),
+    }))
+}
+
+fn delete_row(
+    conn: &Connection,
+    table: &str,
+    key_columns: &[String],
+    key_values: &[Value],
+) -> Result<(), AppError> {
+    // NULL 键列用 IS 比较，`=` 永远匹配不到
+    let predicate = key_columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| format!("{} IS ?{}", Database::quote_identifier(column), idx + 1))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    conn.execute(
+        &format!(
+            "DELETE FROM {} WHERE {predicate}",
+            Database::quote_identifier(table)
+        ),
+        rusqlite::params_from_iter(key_values.iter()),
+    )
+    .map_err(|e| AppError::Database(format!("合并表 {table} 删除行失败: {e}")))?;
+    Ok(())
+}
+
+fn insert_row(
+    conn: &Connection,
+    table: &str,
+    columns: &[String],
+    values: &[Value],
+) -> Result<(), AppError> {
+    let quoted_columns = columns
+        .iter()
+        .map(|c| Database::quote_identifier(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = (1..=columns.len())
+        .map(|idx| format!("?{idx}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {} ({quoted_columns}) VALUES ({placeholders})",
+            Database::quote_identifier(table)
+        ),
+        rusqlite::params_from_iter(values.iter()),
+    )
+    .map_err(|e| AppError::Database(format!("合并表 {table} 写入行失败: {e}")))?;
+    Ok(())
+}
+
+fn row_key<'a>(values: impl Iterator<Item = &'a Value>) -> String {
+    JsonValue::Array(values.map(sql_to_json).collect()).to_string()
+}
+
+fn row_to_json(columns: &[String], values: &[Value]) -> Map<String, JsonValue> {
+    columns
+        .iter()
+        .cloned()
+        .zip(values.iter().map(sql_to_json))
+        .collect()
+}
+
+fn sql_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Null => JsonValue::Null,
+        Value::Integer(i) => JsonValue::from(*i),
+        Value::Real(f) => JsonValue::from(*f),
+        Value::Text(text) => JsonValue::String(text.clone()),
+        // 配置表没有 BLOB 列；兜底编码为 base64 文本
+        Value::Blob(bytes) => JsonValue::String(BASE64.encode(bytes)),
+    }
+}
+
+fn json_to_sql(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(b) => Value::Integer(i64::from(*b)),
+        JsonValue::Number(n) => n
+            .as_i64()
+            .map(Value::Integer)
+            .unwrap_or_else(|| Value::Real(n.as_f64().unwrap_or_default())),
+        JsonValue::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn_with_schema() -> Connection {
+        let conn = Connection::open_in_memory().expect("open memory db");
+        Database::create_tables_on_conn(&conn).expect("create tables");
+        // 种子行的时间戳取自 datetime('now')，跨秒建库会被当成改动；固定下来
+        conn.execute(
+            "UPDATE proxy_config SET created_at = '2026-01-01 00:00:00', updated_at = '2026-01-01 00:00:00'",
+            [],
+        )
+        .expect("pin proxy_config timestamps");
+        conn
+    }
+
+    fn put_mcp(conn: &Connection, id: &str, name: &str) {
+        conn.execute(
+            "INSERT OR REPLACE INTO mcp_servers (id, name, server_config) VALUES (?1, ?2, '{}')",
+            [id, name],
+        )
+        .expect("insert mcp server");
+    }
+
+    fn mcp_names(conn: &Connection) -> Vec<(String, String)> {
+        conn.prepare("SELECT id, name FROM mcp_servers ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn put_provider(conn: &Connection, name: &str, settings_config: &str) {
+        conn.execute(
+            "INSERT OR REPLACE INTO providers (id, app_type, name, settings_config, meta)
+             VALUES ('p1', 'claude', ?1, ?2, '{}')",
+            [name, settings_config],
+        )
+        .expect("insert provider");
+    }
+
+    #[test]
+    fn encrypted_provider_secrets_do_not_conflict_with_plaintext_snapshots() {
+        let plain = r#"{"env":{"ANTHROPIC_AUTH_TOKEN":"sk-shared"}}"#;
+        let mut local_config: JsonValue = serde_json::from_str(plain).unwrap();
+        crate::secrets::encrypt_secret_fields(&mut local_config).unwrap();
+        let local_config = local_config.to_string();
+
+        // base 与远端是端到端加密快照里的明文，本地是带随机 nonce 的密文
+        let base = conn_with_schema();
+        put_provider(&base, "Old", plain);
+        let staging = conn_with_schema();
+        put_provider(&staging, "Renamed", plain);
+        let local = conn_with_schema();
+        put_provider(&local, "Old", &local_config);
+
+        let report = merge_into_staging(&staging, &local, &base).unwrap();
+        assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+        assert_eq!(report.applied_remote, 1);
+
+        Database::seal_provider_secrets(&staging).unwrap();
+        let (name, stored): (String, String) = staging
+            .query_row(
+                "SELECT name, settings_config FROM providers WHERE id = 'p1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(name, "Renamed");
+        assert!(!stored.contains("sk-shared"));
+    }
+
+    #[test]
+    fn non_conflicting_row_changes_from_both_sides_are_merged() {
+        let base = conn_with_schema();
+        put_mcp(&base, "shared", "Shared");
+        put_mcp(&base, "doomed", "Doomed");
+
+        // 本地：新增 local-only，修改 shared
+        let local = conn_with_schema();
+        put_mcp(&local, "shared", "Shared (local edit)");
+        put_mcp(&local, "doomed", "Doomed");
+        put_mcp(&local, "local-only", "Local");
+
+        // 远端：新增 remote-only，删除 doomed
+        let staging = conn_with_schema();
+        put_mcp(&staging, "shared", "Shared");
+        put_mcp(&staging, "remote-only", "Remote");
+
+        let report = merge_into_staging(&staging, &local, &base).unwrap();
+        assert!(report.conflicts.is_empty());
+        assert_eq!(report.kept_local, 2);
+        assert_eq!(report.applied_remote, 2);
+        assert_eq!(
+            mcp_names(&staging),
+            vec![
+                ("local-only".to_string(), "Local".to_string()),
+                ("remote-only".to_string(), "Remote".to_string()),
+                ("shared".to_string(), "Shared (local edit)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn same_row_changed_on_both_sides_is_reported_and_keeps_local() {
+        let base = conn_with_schema();
+        put_mcp(&base, "shared", "Shared");
+        let local = conn_with_schema();
+        put_mcp(&local, "shared", "Local name");
+        let staging = conn_with_schema();
+        put_mcp(&staging, "shared", "Remote name");
+
+        let report = merge_into_staging(&staging, &local, &base).unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.table, "mcp_servers");
+        assert_eq!(conflict.key.get("id"), Some(&JsonValue::from("shared")));
+        assert_eq!(
+            conflict.remote.as_ref().and_then(|row| row.get("name")),
+            Some(&JsonValue::from("Remote name"))
+        );
+        assert_eq!(
+            mcp_names(&staging),
+            vec![("shared".to_string(), "Local name".to_string())]
+        );
+    }
+
+    #[test]
+    fn provider_endpoints_merge_by_url_instead_of_autoincrement_id() {
+        let insert_provider = |conn: &Connection| {
+            conn.execute(
+                "INSERT INTO providers (id, app_type, name, settings_config) VALUES ('p', 'claude', 'P', '{}')",
+                [],
+            )
+            .unwrap();
+        };
+        let add_endpoint = |conn: &Connection, url: &str| {
+            conn.execute(
+                "INSERT INTO provider_endpoints (provider_id, app_type, url) VALUES ('p', 'claude', ?1)",
+                [url],
+            )
+            .unwrap();
+        };
+
+        let base = conn_with_schema();
+        insert_provider(&base);
+        let local = conn_with_schema();
+        insert_provider(&local);
+        add_endpoint(&local, "https://local.example.com");
+        let staging = conn_with_schema();
+        insert_provider(&staging);
+        add_endpoint(&staging, "https://remote.example.com");
+
+        let report = merge_into_staging(&staging, &local, &base).unwrap();
+        assert!(report.conflicts.is_empty());
+        let urls: Vec<String> = staging
+            .prepare("SELECT url FROM provider_endpoints ORDER BY url")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            urls,
+            vec!["https://local.example.com", "https://remote.example.com"]
+        );
+    }
+}
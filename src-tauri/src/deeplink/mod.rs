@@ -24,9 +24,59 @@ use serde::{Deserialize, Serialize};
 pub use mcp::import_mcp_from_deeplink;
 pub use parser::parse_deeplink_url;
 pub use prompt::import_prompt_from_deeplink;
-pub use provider::{import_provider_from_deeplink, parse_and_merge_config};
+pub use provider::import_provider_from_deeplink;
+#[cfg(feature = "gui")]
+pub use provider::parse_and_merge_config;
 pub use skill::import_skill_from_deeplink;
 
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 按资源类型分派导入（桌面端统一导入命令与 CLI 共用）
+///
+/// 返回值带 `type` 字段，其余字段随资源类型而定。
+pub fn import_resource_from_deeplink(
+    state: &AppState,
+    request: DeepLinkImportRequest,
+) -> Result<serde_json::Value, AppError> {
+    match request.resource.as_str() {
+        "provider" => {
+            let provider_id = import_provider_from_deeplink(state, request)?;
+            Ok(serde_json::json!({
+                "type": "provider",
+                "id": provider_id
+            }))
+        }
+        "prompt" => {
+            let prompt_id = import_prompt_from_deeplink(state, request)?;
+            Ok(serde_json::json!({
+                "type": "prompt",
+                "id": prompt_id
+            }))
+        }
+        "mcp" => {
+            let result = import_mcp_from_deeplink(state, request)?;
+            // Add type field to the result
+            Ok(serde_json::json!({
+                "type": "mcp",
+                "importedCount": result.imported_count,
+                "importedIds": result.imported_ids,
+                "failed": result.failed
+            }))
+        }
+        "skill" => {
+            let skill_key = import_skill_from_deeplink(state, request)?;
+            Ok(serde_json::json!({
+                "type": "skill",
+                "key": skill_key
+            }))
+        }
+        other => Err(AppError::InvalidInput(format!(
+            "Unsupported resource type: {other}"
+        ))),
+    }
+}
+
 /// Deep link import request model
 ///
 /// Represents a parsed ccswitch:// URL ready for processing.
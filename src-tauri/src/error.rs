@@ -63,6 +63,8 @@ pub enum AppError {
     AllProvidersCircuitOpen,
     #[error("未配置供应商")]
     NoProvidersConfigured,
+    #[error("供应商已超出消费限额: {0}")]
+    ProviderBudgetExceeded(String),
 }
 
 impl AppError {
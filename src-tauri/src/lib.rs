@@ -1,12 +1,18 @@
 mod app_config;
 mod app_store;
+#[cfg(feature = "gui")]
 mod auto_launch;
 mod claude_desktop_config;
 mod claude_mcp;
+#[cfg(feature = "gui")]
 mod claude_plugin;
+pub mod cli;
 mod codex_config;
+#[cfg(feature = "gui")]
 mod codex_history_migration;
+#[cfg(feature = "gui")]
 mod codex_state_db;
+#[cfg(feature = "gui")]
 mod commands;
 mod config;
 mod database;
@@ -16,25 +22,31 @@ mod gemini_config;
 mod gemini_mcp;
 mod grok_config;
 pub mod hermes_config;
+#[cfg(feature = "gui")]
 mod init_status;
+#[cfg(feature = "gui")]
 mod lightweight;
-#[cfg(target_os = "linux")]
+#[cfg(all(feature = "gui", target_os = "linux"))]
 mod linux_fix;
 mod mcp;
 mod model_capabilities;
 mod openclaw_config;
 mod opencode_config;
+#[cfg(feature = "gui")]
 mod panic_hook;
 mod pi_config;
 mod prompt;
 mod prompt_files;
 mod provider;
 mod proxy;
+mod secrets;
 mod services;
+#[cfg(feature = "gui")]
 mod session_manager;
 mod settings;
 mod store;
 
+#[cfg(feature = "gui")]
 mod tray;
 mod usage_events;
 mod usage_script;
@@ -43,7 +55,9 @@ pub use app_config::{AppType, InstalledSkill, McpApps, McpServer, MultiAppConfig
 pub use codex_config::{
     get_codex_auth_path, get_codex_config_path, read_codex_live_settings, write_codex_live_atomic,
 };
+#[cfg(feature = "gui")]
 pub use commands::open_provider_terminal;
+#[cfg(feature = "gui")]
 pub use commands::*;
 pub use config::{get_claude_mcp_path, get_claude_settings_path, read_json_file};
 pub use database::{Database, Profile};
@@ -61,6 +75,7 @@ pub use prompt::Prompt;
 pub use provider::{Provider, ProviderMeta};
 pub use services::{
     profile::{ProfilePayload, ProfileScope, ProfileService},
+    project_binding::ProjectBindingService,
     provider::reapply_current_codex_official_live,
     skill::{migrate_skills_to_ssot, ImportSkillSelection},
     ConfigService, EndpointLatency, McpService, PromptService, ProviderService, ProxyService,
@@ -68,20 +83,28 @@ pub use services::{
 };
 pub use settings::{update_settings, AppSettings};
 pub use store::AppState;
+#[cfg(feature = "gui")]
 use tauri_plugin_deep_link::DeepLinkExt;
+#[cfg(feature = "gui")]
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
+use std::fmt;
 #[cfg(target_os = "windows")]
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::{fmt, sync::Arc};
-#[cfg(target_os = "macos")]
+#[cfg(feature = "gui")]
+use std::sync::Arc;
+#[cfg(all(feature = "gui", target_os = "macos"))]
 use tauri::image::Image;
+#[cfg(feature = "gui")]
 use tauri::tray::{TrayIconBuilder, TrayIconEvent};
+#[cfg(feature = "gui")]
 use tauri::RunEvent;
+#[cfg(feature = "gui")]
 use tauri::{Emitter, Manager};
+#[cfg(feature = "gui")]
 use tauri_plugin_window_state::{AppHandleExt, StateFlags};
 
-#[cfg(target_os = "windows")]
+#[cfg(all(feature = "gui", target_os = "windows"))]
 fn set_windows_app_user_model_id(app: &tauri::AppHandle) {
     let app_id = app.config().identifier.clone();
     let wide_app_id: Vec<u16> = app_id.encode_utf16().chain(std::iter::once(0)).collect();
@@ -233,6 +256,7 @@ pub(crate) fn redact_url_origin_for_log(url_str: &str) -> String {
     }
 }
 
+#[cfg(feature = "gui")]
 fn runtime_log_level_allows(level: log::Level, max_level: log::LevelFilter) -> bool {
     max_level.to_level().is_some_and(|maximum| level <= maximum)
 }
@@ -242,6 +266,7 @@ fn runtime_log_level_allows(level: log::Level, max_level: log::LevelFilter) -> b
 /// - 解析 URL
 /// - 向前端发射 `deeplink-import` / `deeplink-error` 事件
 /// - 可选：在成功时聚焦主窗口
+#[cfg(feature = "gui")]
 fn handle_deeplink_url(
     app: &tauri::AppHandle,
     url_str: &str,
@@ -304,6 +329,7 @@ fn handle_deeplink_url(
 }
 
 /// 更新托盘菜单的Tauri命令
+#[cfg(feature = "gui")]
 #[tauri::command]
 async fn update_tray_menu(
     app: tauri::AppHandle,
@@ -325,7 +351,7 @@ async fn update_tray_menu(
     }
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(all(feature = "gui", target_os = "macos"))]
 fn macos_tray_icon() -> Option<Image<'static>> {
     const ICON_BYTES: &[u8] = include_bytes!("../icons/tray/macos/statusbar_template_3x.png");
 
@@ -338,6 +364,7 @@ fn macos_tray_icon() -> Option<Image<'static>> {
     }
 }
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 设置 panic hook，在应用崩溃时记录日志到 <app_config_dir>/crash.log（默认 ~/.cc-switch/crash.log）
@@ -657,6 +684,15 @@ pub fn run() {
             // 按表独立判断的导入逻辑（各类数据独立检查，互不影响）
             // ============================================================
 
+            // 0. 加密旧版明文保存的供应商密钥（幂等，主密钥不可用时跳过）
+            match app_state.db.encrypt_plaintext_provider_secrets() {
+                Ok(count) if count > 0 => {
+                    log::info!("✓ Encrypted API keys of {count} provider(s) at rest");
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("✗ Failed to encrypt plaintext provider secrets: {e}"),
+            }
+
             // 1. 初始化默认 Skills 仓库（已有内置检查：表非空则跳过）
             match app_state.db.init_default_skill_repos() {
                 Ok(count) if count > 0 => {
@@ -1120,14 +1156,7 @@ pub fn run() {
             }
 
             let _tray = tray_builder.build(app)?;
-            crate::services::webdav_auto_sync::start_worker(
-                app_state.db.clone(),
-                app.handle().clone(),
-            );
-            crate::services::s3_auto_sync::start_worker(
-                app_state.db.clone(),
-                app.handle().clone(),
-            );
+            crate::services::auto_sync::start_workers(app_state.db.clone(), app.handle().clone());
             // 将同一个实例注入到全局状态，避免重复创建导致的不一致
             app.manage(app_state);
 
@@ -1240,6 +1269,14 @@ pub fn run() {
 
                 // 检查 settings 表中的代理状态，自动恢复代理服务
                 restore_proxy_state_on_startup(&state).await;
+                // 网关模式下各应用的 MCP 条目都指向本地代理，代理必须随应用启动
+                if crate::settings::get_mcp_gateway_enabled() {
+                    if let Err(e) =
+                        crate::services::mcp::McpService::ensure_gateway_proxy(&state).await
+                    {
+                        log::error!("启动 MCP 网关所需的本地代理失败: {e}");
+                    }
+                }
 
                 // Periodic backup check (on startup)
                 if let Err(e) = state.db.periodic_backup_if_needed() {
@@ -1445,11 +1482,30 @@ pub fn run() {
             commands::delete_mcp_server,
             commands::toggle_mcp_app,
             commands::import_mcp_from_apps,
+            commands::get_mcp_probe_results,
+            commands::probe_mcp_server,
+            commands::probe_all_mcp_servers,
+            commands::get_mcp_gateway_enabled,
+            commands::set_mcp_gateway_enabled,
+            commands::list_mcp_secrets,
+            commands::get_mcp_servers_needing_config,
+            commands::set_mcp_secret,
+            commands::delete_mcp_secret,
+            commands::list_project_bindings,
+            commands::set_project_binding,
+            commands::remove_project_binding,
             // Prompt management
             commands::get_prompts,
             commands::upsert_prompt,
             commands::delete_prompt,
             commands::enable_prompt,
+            commands::enable_prompt_fragment,
+            commands::reorder_prompts,
+            commands::get_prompt_variables,
+            commands::set_prompt_variables,
+            commands::get_shared_prompts,
+            commands::upsert_shared_prompt,
+            commands::delete_shared_prompt,
             commands::import_prompt_from_file,
             commands::get_current_prompt_file_content,
             commands::get_pi_prompt_file,
@@ -1496,7 +1552,20 @@ pub fn run() {
             commands::s3_sync_download,
             commands::s3_sync_save_settings,
             commands::s3_sync_fetch_remote_info,
+            commands::folder_sync_test_connection,
+            commands::folder_sync_upload,
+            commands::folder_sync_download,
+            commands::folder_sync_save_settings,
+            commands::folder_sync_fetch_remote_info,
+            commands::git_sync_test_connection,
+            commands::git_sync_upload,
+            commands::git_sync_download,
+            commands::git_sync_save_settings,
+            commands::git_sync_fetch_remote_info,
+            commands::sync_list_conflicts,
+            commands::sync_resolve_conflict,
             commands::save_file_dialog,
+            commands::save_usage_export_dialog,
             commands::open_file_dialog,
             commands::open_zip_file_dialog,
             commands::create_db_backup,
@@ -1571,11 +1640,22 @@ pub fn run() {
             commands::get_circuit_breaker_config,
             commands::update_circuit_breaker_config,
             commands::get_circuit_breaker_stats,
+            // Proxy capture & replay
+            commands::get_proxy_capture_enabled,
+            commands::set_proxy_capture_enabled,
+            commands::list_proxy_captures,
+            commands::get_proxy_capture,
+            commands::clear_proxy_captures,
+            commands::replay_proxy_capture,
+            // OpenTelemetry export
+            commands::get_otlp_endpoint,
+            commands::set_otlp_endpoint,
             // Failover queue management
             commands::get_failover_queue,
             commands::get_available_providers_for_failover,
             commands::add_to_failover_queue,
             commands::remove_from_failover_queue,
+            commands::set_failover_routing_weight,
             commands::get_auto_failover_enabled,
             commands::set_auto_failover_enabled,
             // Usage statistics
@@ -1584,6 +1664,8 @@ pub fn run() {
             commands::get_usage_trends,
             commands::get_provider_stats,
             commands::get_model_stats,
+            commands::get_project_stats,
+            commands::get_session_stats,
             commands::get_request_logs,
             commands::get_request_detail,
             commands::get_model_pricing,
@@ -1598,6 +1680,7 @@ pub fn run() {
             commands::sync_session_usage,
             commands::rebuild_codex_usage,
             commands::get_usage_data_sources,
+            commands::export_usage_data,
             // Stream health check
             commands::stream_check_provider,
             commands::stream_check_all_providers,
@@ -1869,6 +1952,7 @@ pub fn run() {
 /// 在应用退出前检查代理服务器状态，如果正在运行则停止代理并恢复 Live 配置。
 /// 确保 Claude Code/Codex/Gemini 的配置不会处于损坏状态。
 /// 使用 stop_with_restore_keep_state 保留 settings 表中的代理状态，下次启动时自动恢复。
+#[cfg(feature = "gui")]
 pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
     if let Some(state) = app_handle.try_state::<store::AppState>() {
         let proxy_service = &state.proxy_service;
@@ -1916,6 +2000,7 @@ pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
 /// 触发 tray-icon 内部的 `remove_tray_icon` → `Shell_NotifyIconW(NIM_DELETE)`，
 /// 在进程结束前干净地把图标摘掉。其它平台 `set_visible(false)` 也是
 /// 正常的隐藏/移除语义，作为跨平台兜底也安全。
+#[cfg(feature = "gui")]
 pub(crate) fn remove_tray_icon_before_exit(app_handle: &tauri::AppHandle) {
     if let Some(tray) = app_handle.tray_by_id(tray::TRAY_ID) {
         if let Err(e) = tray.set_visible(false) {
@@ -1934,8 +2019,10 @@ pub(crate) fn remove_tray_icon_before_exit(app_handle: &tauri::AppHandle) {
 ///
 /// 检查 `proxy_config.enabled` 字段，如果有任一应用的状态为 `true`，
 /// 则自动启动代理服务并接管对应应用的 Live 配置。
+#[cfg(feature = "gui")]
 const PROXY_STARTUP_APP_TYPES: [&str; 4] = ["claude", "codex", "gemini", "grokbuild"];
 
+#[cfg(feature = "gui")]
 async fn enabled_proxy_apps_on_startup(db: &database::Database) -> Vec<&'static str> {
     let mut apps = Vec::new();
     for app_type in PROXY_STARTUP_APP_TYPES {
@@ -1950,6 +2037,7 @@ async fn enabled_proxy_apps_on_startup(db: &database::Database) -> Vec<&'static
     apps
 }
 
+#[cfg(feature = "gui")]
 async fn restore_proxy_state_on_startup(state: &store::AppState) {
     // 收集需要恢复接管的应用列表（从 proxy_config.enabled 读取）
     let apps_to_restore = enabled_proxy_apps_on_startup(&state.db).await;
@@ -1986,6 +2074,7 @@ async fn restore_proxy_state_on_startup(state: &store::AppState) {
     }
 }
 
+#[cfg(feature = "gui")]
 fn initialize_common_config_snippets(state: &store::AppState) {
     // Auto-extract common config snippets from clean live files when snippet is missing.
     // This must run before proxy takeover is restored on startup, otherwise we'd read
@@ -2070,6 +2159,7 @@ fn initialize_common_config_snippets(state: &store::AppState) {
 // ============================================================
 
 /// 检测是否为中文环境
+#[cfg(feature = "gui")]
 fn is_chinese_locale() -> bool {
     std::env::var("LANG")
         .or_else(|_| std::env::var("LC_ALL"))
@@ -2080,6 +2170,7 @@ fn is_chinese_locale() -> bool {
 
 /// 显示迁移错误对话框
 /// 返回 true 表示用户选择重试，false 表示用户选择退出
+#[cfg(feature = "gui")]
 fn show_migration_error_dialog(app: &tauri::AppHandle, error: &str) -> bool {
     let title = if is_chinese_locale() {
         "配置迁移失败"
@@ -2131,6 +2222,7 @@ fn show_migration_error_dialog(app: &tauri::AppHandle, error: &str) -> bool {
 
 /// 显示数据库初始化/Schema 迁移失败对话框
 /// 返回 true 表示用户选择重试，false 表示用户选择退出
+#[cfg(feature = "gui")]
 fn show_database_init_error_dialog(
     app: &tauri::AppHandle,
     db_path: &std::path::Path,
@@ -2204,6 +2296,7 @@ fn show_database_init_error_dialog(
 /// Tauri 静默忽略（见 `ExitRequestApi::prevent_exit` 文档），事件循环必定继续
 /// 退出并触发各插件的 `RunEvent::Exit` 钩子；任何与之并发的自定义清理任务都
 /// 可能与插件退出钩子争用同一状态而死锁。
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ExitRequestAction {
     /// `code` 为 `None`：运行时自动触发（如隐藏窗口的 WebView 被回收导致无存活
@@ -2216,6 +2309,7 @@ enum ExitRequestAction {
     CleanupAndExit,
 }
 
+#[cfg(feature = "gui")]
 fn classify_exit_request(code: Option<i32>) -> ExitRequestAction {
     match code {
         None => ExitRequestAction::StayInTray,
@@ -2228,12 +2322,14 @@ fn classify_exit_request(code: Option<i32>) -> ExitRequestAction {
 // 在应用主动退出前显式持久化窗口状态
 // ============================================================
 
+#[cfg(feature = "gui")]
 fn window_state_flags() -> StateFlags {
     StateFlags::POSITION | StateFlags::SIZE | StateFlags::MAXIMIZED
 }
 
 /// 当前应用的退出路径会拦截 `ExitRequested` 并最终直接 `std::process::exit(0)`，
 /// 这里需要在真正结束进程前手动落盘，避免 window-state 插件的默认退出钩子被绕过。
+#[cfg(feature = "gui")]
 pub fn save_window_state_before_exit(app_handle: &tauri::AppHandle) {
     if let Err(err) = app_handle.save_window_state(window_state_flags()) {
         log::error!("退出前保存窗口状态失败: {err}");
@@ -2247,6 +2343,7 @@ pub fn save_window_state_before_exit(app_handle: &tauri::AppHandle) {
 /// macOS single-instance 使用 `/tmp/{identifier}.sock`。我们有若干路径会直接
 /// `std::process::exit(0)`，不会触发插件挂在 `RunEvent::Exit` 上的清理钩子。
 /// 重启前主动 destroy 可以避免新进程误连旧 listener 后自行退出。
+#[cfg(feature = "gui")]
 pub fn destroy_single_instance_lock(app_handle: &tauri::AppHandle) {
     #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     tauri_plugin_single_instance::destroy(app_handle);
@@ -2262,6 +2359,7 @@ pub fn destroy_single_instance_lock(app_handle: &tauri::AppHandle) {
 /// 有意不调 `AppHandle::cleanup_before_exit()`：它会在调用线程上 Drop 托盘
 /// 图标，而 macOS 的 NSStatusItem 操作要求主线程；`set_visible(false)` 走
 /// `run_item_main_thread` 代理，跨线程安全（见 `remove_tray_icon_before_exit`）。
+#[cfg(feature = "gui")]
 pub fn restart_process(app_handle: &tauri::AppHandle) -> ! {
     remove_tray_icon_before_exit(app_handle);
     destroy_single_instance_lock(app_handle);
@@ -0,0 +1,785 @@
+//! MCP 客户端
+//!
+//! 按服务器定义建立连接并收发 JSON-RPC：stdio 启动子进程（按行分隔的消息）、http 走
+//! Streamable HTTP、sse 走旧版 HTTP+SSE 传输。连接建立后即完成 `initialize` →
+//! `notifications/initialized` 握手。子进程设置了 `kill_on_drop`，客户端被丢弃时随之回收。
+//! 供存活探测（`probe`）与代理上的 MCP 网关共用。
+//!
+//! 同一连接上的请求可以并发：stdio 与 sse 由后台读取任务按 JSON-RPC id 把响应分发给
+//! 等待中的请求，Streamable HTTP 每条消息本就是独立的 POST。
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use crate::proxy::sse::{append_utf8_safe, strip_sse_field, take_sse_block};
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// 客户端声明的协议版本；服务器可协商为自己支持的版本
+pub(crate) const PROTOCOL_VERSION: &str = "2025-06-18";
+/// 列表分页上限，防止服务器返回循环游标
+const MAX_LIST_PAGES: usize = 20;
+/// 失败时附带的 stderr 尾部长度
+const STDERR_TAIL_BYTES: usize = 2048;
+const MCP_SESSION_ID: &str = "mcp-session-id";
+const MCP_PROTOCOL_VERSION: &str = "mcp-protocol-version";
+
+/// 请求失败的两类原因：服务器按协议返回的错误（连接仍可用）与传输层故障（连接应丢弃）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum McpError {
+    Rpc { code: i64, message: String },
+    Transport(String),
+}
+
+impl fmt::Display for McpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            McpError::Rpc { code, message } => write!(f, "JSON-RPC 错误 {code}: {message}"),
+            McpError::Transport(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for McpError {
+    fn from(message: String) -> Self {
+        McpError::Transport(message)
+    }
+}
+
+/// 已完成初始化握手的 MCP 连接
+pub(crate) struct McpClient {
+    transport: Transport,
+    next_id: AtomicU64,
+    init: Value,
+}
+
+impl McpClient {
+    /// 建立连接并完成 `initialize` 握手；调用方负责施加超时
+    pub(crate) async fn connect(spec: &Value) -> Result<Self, McpError> {
+        let mut client = Self {
+            transport: Transport::connect(spec).await?,
+            next_id: AtomicU64::new(0),
+            init: Value::Null,
+        };
+        let init = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "cc-switch", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .await?;
+        client
+            .transport
+            .set_protocol_version(str_field(&init, "protocolVersion"));
+        client.transport.notify("notifications/initialized").await?;
+        client.init = init;
+        Ok(client)
+    }
+
+    /// `initialize` 的 result（protocolVersion / capabilities / serverInfo）
+    pub(crate) fn init_result(&self) -> &Value {
+        &self.init
+    }
+
+    /// 服务器是否声明了某项能力；未声明的能力调用会得到 Method not found
+    pub(crate) fn supports(&self, capability: &str) -> bool {
+        self.init
+            .get("capabilities")
+            .and_then(|c| c.get(capability))
+            .is_some()
+    }
+
+    pub(crate) async fn request(&self, method: &str, params: Value) -> Result<Value, McpError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.transport.request(id, method, params).await
+    }
+
+    /// 跟随 `nextCursor` 取完分页列表，返回 `result[key]` 下的全部条目
+    pub(crate) async fn list_all(&self, method: &str, key: &str) -> Result<Vec<Value>, McpError> {
+        let mut cursor: Option<String> = None;
+        let mut items = Vec::new();
+        for _ in 0..MAX_LIST_PAGES {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let page = self.request(method, params).await?;
+            if let Some(page_items) = page.get(key).and_then(Value::as_array) {
+                items.extend(page_items.iter().cloned());
+            }
+            cursor = str_field(&page, "nextCursor");
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(items)
+    }
+}
+
+pub(crate) fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// 若 `message` 是 `id` 对应的响应，取出 result 或把 error 转为错误信息；
+/// 通知与服务器发起的请求（带 `method`）返回 None
+fn match_response(message: &Value, id: u64) -> Option<Result<Value, McpError>> {
+    if message.get("method").is_some() || message.get("id").and_then(Value::as_u64) != Some(id) {
+        return None;
+    }
+    if let Some(error) = message.get("error") {
+        let code = error
+            .get("code")
+            .and_then(Value::as_i64)
+            .unwrap_or_default();
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error")
+            .to_string();
+        return Some(Err(McpError::Rpc { code, message }));
+    }
+    Some(Ok(message.get("result").cloned().unwrap_or(Value::Null)))
+}
+
+/// 在单条消息或批量数组中查找 `id` 对应的响应
+fn find_response(payload: &Value, id: u64) -> Option<Result<Value, McpError>> {
+    match payload {
+        Value::Array(items) => items.iter().find_map(|item| match_response(item, id)),
+        other => match_response(other, id),
+    }
+}
+
+/// 等待响应的请求表，由读取任务按 id 分发响应
+#[derive(Default)]
+struct Pending {
+    waiters: HashMap<u64, oneshot::Sender<Result<Value, McpError>>>,
+    /// 连接已断开的原因；之后的请求直接失败
+    closed: Option<String>,
+}
+
+type PendingMap = Arc<Mutex<Pending>>;
+
+fn lock_pending(pending: &PendingMap) -> std::sync::MutexGuard<'_, Pending> {
+    pending.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 一个在途请求；被丢弃（如调用方超时）时从请求表中注销
+struct Waiter {
+    id: u64,
+    pending: PendingMap,
+    receiver: oneshot::Receiver<Result<Value, McpError>>,
+}
+
+impl Waiter {
+    fn register(pending: &PendingMap, id: u64) -> Result<Self, McpError> {
+        let (sender, receiver) = oneshot::channel();
+        let mut guard = lock_pending(pending);
+        if let Some(reason) = &guard.closed {
+            return Err(McpError::Transport(reason.clone()));
+        }
+        guard.waiters.insert(id, sender);
+        Ok(Self {
+            id,
+            pending: Arc::clone(pending),
+            receiver,
+        })
+    }
+
+    async fn response(mut self) -> Result<Value, McpError> {
+        match (&mut self.receiver).await {
+            Ok(response) => response,
+            Err(_) => Err(McpError::Transport("连接已关闭".to_string())),
+        }
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        lock_pending(&self.pending).waiters.remove(&self.id);
+    }
+}
+
+/// 把一条消息（或批量数组）中的响应交给对应的等待者；通知与服务器请求忽略
+fn dispatch_responses(pending: &PendingMap, payload: &Value) {
+    let messages = match payload {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+    let mut guard = lock_pending(pending);
+    for message in messages {
+        let Some(id) = message.get("id").and_then(Value::as_u64) else {
+            continue;
+        };
+        let Some(response) = match_response(message, id) else {
+            continue;
+        };
+        if let Some(waiter) = guard.waiters.remove(&id) {
+            let _ = waiter.send(response);
+        }
+    }
+}
+
+/// 连接断开：唤醒全部在途请求并拒绝后续请求
+fn close_pending(pending: &PendingMap, reason: String) {
+    let mut guard = lock_pending(pending);
+    for (_, waiter) in guard.waiters.drain() {
+        let _ = waiter.send(Err(McpError::Transport(reason.clone())));
+    }
+    guard.closed = Some(reason);
+}
+
+fn rpc_request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn rpc_notification(method: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method })
+}
+
+enum Transport {
+    Stdio(StdioTransport),
+    Http(HttpTransport),
+    Sse(SseTransport),
+}
+
+impl Transport {
+    async fn connect(spec: &Value) -> Result<Self, String> {
+        match spec.get("type").and_then(Value::as_str).unwrap_or("stdio") {
+            "http" => Ok(Transport::Http(HttpTransport::new(spec)?)),
+            "sse" => Ok(Transport::Sse(SseTransport::connect(spec).await?)),
+            _ => Ok(Transport::Stdio(StdioTransport::spawn(spec)?)),
+        }
+    }
+
+    fn set_protocol_version(&mut self, version: Option<String>) {
+        if let Transport::Http(http) = self {
+            http.protocol_version = version;
+        }
+    }
+
+    async fn request(&self, id: u64, method: &str, params: Value) -> Result<Value, McpError> {
+        let message = rpc_request(id, method, params);
+        match self {
+            Transport::Stdio(stdio) => stdio.request(&message, id).await,
+            Transport::Http(http) => http.request(&message, id).await,
+            Transport::Sse(sse) => sse.request(&message, id).await,
+        }
+        .map_err(|e| match e {
+            McpError::Transport(e) => McpError::Transport(format!("{method}: {e}")),
+            rpc => rpc,
+        })
+    }
+
+    async fn notify(&self, method: &str) -> Result<(), String> {
+        let message = rpc_notification(method);
+        match self {
+            Transport::Stdio(stdio) => stdio.send(&message).await,
+            Transport::Http(http) => http.post(&message).await.map(|_| ()),
+            Transport::Sse(sse) => sse.post(&message).await,
+        }
+        .map_err(|e| format!("{method}: {e}"))
+    }
+}
+
+// ─── stdio ───────────────────────────────────────────────────
+
+struct StdioTransport {
+    // 持有子进程以便 drop 时终止
+    _child: Child,
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    pending: PendingMap,
+    reader: JoinHandle<()>,
+    stderr_tail: Arc<Mutex<String>>,
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl StdioTransport {
+    fn spawn(spec: &Value) -> Result<Self, String> {
+        let program = spec.get("command").and_then(Value::as_str).unwrap_or("");
+        let args: Vec<String> = spec
+            .get("args")
+            .and_then(Value::as_array)
+            .map(|args| {
+                args.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Windows 上 npx/uvx 等是 .cmd 脚本，必须经由 cmd 解析
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = Command::new("cmd");
+            command.arg("/C").arg(program).args(&args);
+            command.creation_flags(CREATE_NO_WINDOW);
+            command
+        };
+        #[cfg(not(target_os = "windows"))]
+        let mut command = {
+            let mut command = Command::new(program);
+            command.args(&args);
+            command
+        };
+
+        if let Some(env) = spec.get("env").and_then(Value::as_object) {
+            for (key, value) in env {
+                if let Some(value) = value.as_str() {
+                    command.env(key, value);
+                }
+            }
+        }
+        if let Some(cwd) = spec.get("cwd").and_then(Value::as_str) {
+            if !cwd.trim().is_empty() {
+                command.current_dir(cwd);
+            }
+        }
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("启动 {program} 失败: {e}"))?;
+
+        let stdin = child.stdin.take().ok_or("无法获取子进程 stdin")?;
+        let stdout = child.stdout.take().ok_or("无法获取子进程 stdout")?;
+        let stderr_tail = Arc::new(Mutex::new(String::new()));
+        if let Some(mut stderr) = child.stderr.take() {
+            // 持续读走 stderr，避免管道写满阻塞服务器，同时保留尾部用于报错
+            let tail = Arc::clone(&stderr_tail);
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = stderr.read(&mut buf).await {
+                    if n == 0 {
+                        break;
+                    }
+                    if let Ok(mut tail) = tail.lock() {
+                        tail.push_str(&String::from_utf8_lossy(&buf[..n]));
+                        if tail.len() > STDERR_TAIL_BYTES {
+                            let mut cut = tail.len() - STDERR_TAIL_BYTES;
+                            while !tail.is_char_boundary(cut) {
+                                cut += 1;
+                            }
+                            tail.drain(..cut);
+                        }
+                    }
+                }
+            });
+        }
+
+        let pending = PendingMap::default();
+        let reader = tokio::spawn(Self::read_stdout(
+            BufReader::new(stdout).lines(),
+            Arc::clone(&pending),
+            Arc::clone(&stderr_tail),
+        ));
+
+        Ok(Self {
+            _child: child,
+            stdin: tokio::sync::Mutex::new(stdin),
+            pending,
+            reader,
+            stderr_tail,
+        })
+    }
+
+    /// 后台读取 stdout，把响应分发给在途请求；进程退出时让它们全部失败
+    async fn read_stdout(
+        mut stdout: Lines<BufReader<ChildStdout>>,
+        pending: PendingMap,
+        stderr_tail: Arc<Mutex<String>>,
+    ) {
+        let reason = loop {
+            let line = match stdout.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break "服务器进程已退出".to_string(),
+                Err(e) => break format!("读取 stdout 失败: {e}"),
+            };
+            // 部分服务器会往 stdout 打日志，非 JSON 行直接跳过
+            if let Ok(payload) = serde_json::from_str::<Value>(line.trim()) {
+                dispatch_responses(&pending, &payload);
+            }
+        };
+        close_pending(&pending, with_stderr_tail(&stderr_tail, reason));
+    }
+
+    async fn send(&self, message: &Value) -> Result<(), String> {
+        let mut line = message.to_string();
+        line.push('\n');
+        let mut stdin = self.stdin.lock().await;
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| self.with_stderr(format!("写入 stdin 失败: {e}")))?;
+        stdin
+            .flush()
+            .await
+            .map_err(|e| self.with_stderr(format!("写入 stdin 失败: {e}")))
+    }
+
+    async fn request(&self, message: &Value, id: u64) -> Result<Value, McpError> {
+        let waiter = Waiter::register(&self.pending, id)?;
+        self.send(message).await?;
+        waiter.response().await
+    }
+
+    fn with_stderr(&self, message: String) -> String {
+        with_stderr_tail(&self.stderr_tail, message)
+    }
+}
+
+fn with_stderr_tail(stderr_tail: &Mutex<String>, message: String) -> String {
+    let tail = stderr_tail
+        .lock()
+        .map(|tail| tail.trim().to_string())
+        .unwrap_or_default();
+    if tail.is_empty() {
+        message
+    } else {
+        format!("{message}\n{tail}")
+    }
+}
+
+// ─── HTTP / SSE ──────────────────────────────────────────────
+
+fn spec_headers(spec: &Value) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    if let Some(map) = spec.get("headers").and_then(Value::as_object) {
+        for (key, value) in map {
+            let Some(value) = value.as_str() else {
+                continue;
+            };
+            let name = HeaderName::from_bytes(key.as_bytes())
+                .map_err(|e| format!("无效的请求头名称 {key}: {e}"))?;
+            let value =
+                HeaderValue::from_str(value).map_err(|e| format!("无效的请求头 {key}: {e}"))?;
+            headers.insert(name, value);
+        }
+    }
+    Ok(headers)
+}
+
+fn spec_url(spec: &Value) -> String {
+    spec.get("url")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .trim()
+        .to_string()
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SseEvent {
+    event: Option<String>,
+    data: String,
+}
+
+fn parse_sse_block(block: &str) -> Option<SseEvent> {
+    let mut event = SseEvent::default();
+    let mut data = Vec::new();
+    for line in block.lines() {
+        if let Some(value) = strip_sse_field(line, "event") {
+            event.event = Some(value.trim().to_string());
+        } else if let Some(value) = strip_sse_field(line, "data") {
+            data.push(value);
+        }
+    }
+    if data.is_empty() {
+        return None;
+    }
+    event.data = data.join("\n");
+    Some(event)
+}
+
+type ByteStream = Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>;
+
+/// 增量解析 `text/event-stream` 响应体
+struct SseReader {
+    body: ByteStream,
+    buffer: String,
+    remainder: Vec<u8>,
+    pending: VecDeque<SseEvent>,
+}
+
+impl SseReader {
+    fn new(response: reqwest::Response) -> Self {
+        Self {
+            body: Box::pin(response.bytes_stream()),
+            buffer: String::new(),
+            remainder: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    async fn next_event(&mut self) -> Result<Option<SseEvent>, String> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            match self.body.next().await {
+                Some(Ok(chunk)) => {
+                    append_utf8_safe(&mut self.buffer, &mut self.remainder, &chunk);
+                    while let Some(block) = take_sse_block(&mut self.buffer) {
+                        self.pending.extend(parse_sse_block(&block));
+                    }
+                }
+                Some(Err(e)) => return Err(format!("读取事件流失败: {e}")),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// 读到 `id` 对应的 JSON-RPC 响应为止
+    async fn response_for(&mut self, id: u64) -> Result<Value, McpError> {
+        while let Some(event) = self.next_event().await? {
+            if let Some(response) = message_payload(&event)
+                .as_ref()
+                .and_then(|payload| find_response(payload, id))
+            {
+                return response;
+            }
+        }
+        Err("事件流在收到响应前结束".to_string().into())
+    }
+
+    /// 持续读取事件流并分发响应，直到流结束或出错
+    async fn dispatch_all(mut self, pending: PendingMap) {
+        let reason = loop {
+            match self.next_event().await {
+                Ok(Some(event)) => {
+                    if let Some(payload) = message_payload(&event) {
+                        dispatch_responses(&pending, &payload);
+                    }
+                }
+                Ok(None) => break "事件流已结束".to_string(),
+                Err(e) => break e,
+            }
+        };
+        close_pending(&pending, reason);
+    }
+}
+
+/// `message` 事件中的 JSON-RPC 负载
+fn message_payload(event: &SseEvent) -> Option<Value> {
+    if !matches!(event.event.as_deref(), None | Some("message")) {
+        return None;
+    }
+    serde_json::from_str(&event.data).ok()
+}
+
+async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let body: String = body.chars().take(200).collect();
+    Err(format!("HTTP {status}: {}", body.trim()))
+}
+
+/// Streamable HTTP：每条消息一个 POST，响应为 JSON 或事件流
+struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    headers: HeaderMap,
+    session_id: Mutex<Option<HeaderValue>>,
+    protocol_version: Option<String>,
+}
+
+impl HttpTransport {
+    fn new(spec: &Value) -> Result<Self, String> {
+        Ok(Self {
+            client: crate::proxy::http_client::get(),
+            url: spec_url(spec),
+            headers: spec_headers(spec)?,
+            session_id: Mutex::new(None),
+            protocol_version: None,
+        })
+    }
+
+    async fn post(&self, message: &Value) -> Result<reqwest::Response, String> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(message);
+        let session_id = self
+            .session_id
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(session_id) = session_id {
+            request = request.header(MCP_SESSION_ID, session_id);
+        }
+        if let Some(version) = &self.protocol_version {
+            request = request.header(MCP_PROTOCOL_VERSION, version.as_str());
+        }
+        let response = request.send().await.map_err(|e| format!("请求失败: {e}"))?;
+        let response = error_for_status(response).await?;
+        if let Some(session_id) = response.headers().get(MCP_SESSION_ID) {
+            *self.session_id.lock().unwrap_or_else(|e| e.into_inner()) = Some(session_id.clone());
+        }
+        Ok(response)
+    }
+
+    async fn request(&self, message: &Value, id: u64) -> Result<Value, McpError> {
+        let response = self.post(message).await?;
+        let is_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if is_stream {
+            return SseReader::new(response).response_for(id).await;
+        }
+        let payload: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("响应不是有效的 JSON: {e}"))?;
+        find_response(&payload, id).unwrap_or_else(|| Err("响应中缺少对应 id".to_string().into()))
+    }
+}
+
+/// 旧版 HTTP+SSE：GET 建立事件流并拿到 `endpoint`，消息 POST 到该地址，响应从事件流返回
+struct SseTransport {
+    client: reqwest::Client,
+    headers: HeaderMap,
+    endpoint: String,
+    pending: PendingMap,
+    reader: JoinHandle<()>,
+}
+
+impl Drop for SseTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl SseTransport {
+    async fn connect(spec: &Value) -> Result<Self, String> {
+        let client = crate::proxy::http_client::get();
+        let url = spec_url(spec);
+        let headers = spec_headers(spec)?;
+        let base = url::Url::parse(&url).map_err(|e| format!("无效的 url: {e}"))?;
+
+        let response = client
+            .get(base.clone())
+            .headers(headers.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| format!("连接事件流失败: {e}"))?;
+        let mut events = SseReader::new(error_for_status(response).await?);
+
+        let endpoint = loop {
+            match events.next_event().await? {
+                Some(event) if event.event.as_deref() == Some("endpoint") => break event.data,
+                Some(_) => continue,
+                None => return Err("事件流未提供 endpoint".to_string()),
+            }
+        };
+        let endpoint = base
+            .join(endpoint.trim())
+            .map_err(|e| format!("无效的 endpoint: {e}"))?
+            .to_string();
+
+        let pending = PendingMap::default();
+        let reader = tokio::spawn(events.dispatch_all(Arc::clone(&pending)));
+
+        Ok(Self {
+            client,
+            headers,
+            endpoint,
+            pending,
+            reader,
+        })
+    }
+
+    async fn post(&self, message: &Value) -> Result<(), String> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .headers(self.headers.clone())
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| format!("请求失败: {e}"))?;
+        error_for_status(response).await.map(|_| ())
+    }
+
+    async fn request(&self, message: &Value, id: u64) -> Result<Value, McpError> {
+        let waiter = Waiter::register(&self.pending, id)?;
+        self.post(message).await?;
+        waiter.response().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_response_ignores_notifications_and_other_ids() {
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/message" });
+        assert!(match_response(&notification, 1).is_none());
+
+        let server_request = json!({ "jsonrpc": "2.0", "id": 1, "method": "roots/list" });
+        assert!(match_response(&server_request, 1).is_none());
+
+        let other = json!({ "jsonrpc": "2.0", "id": 2, "result": {} });
+        assert!(match_response(&other, 1).is_none());
+
+        let error =
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "nope" } });
+        assert_eq!(
+            match_response(&error, 1),
+            Some(Err(McpError::Rpc {
+                code: -32601,
+                message: "nope".to_string()
+            }))
+        );
+
+        let batch = json!([other, { "jsonrpc": "2.0", "id": 1, "result": { "ok": true } }]);
+        assert_eq!(find_response(&batch, 1), Some(Ok(json!({ "ok": true }))));
+    }
+
+    #[test]
+    fn parse_sse_block_joins_data_lines() {
+        assert_eq!(
+            parse_sse_block("event: endpoint\ndata: /messages?session=1"),
+            Some(SseEvent {
+                event: Some("endpoint".to_string()),
+                data: "/messages?session=1".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_sse_block("data: {\"a\":\ndata: 1}").map(|e| e.data),
+            Some("{\"a\":\n1}".to_string())
+        );
+        assert_eq!(parse_sse_block(": keep-alive"), None);
+    }
+}
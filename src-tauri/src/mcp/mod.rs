@@ -10,13 +10,19 @@
 //! - `gemini` - Gemini MCP 同步和导入
 //! - `opencode` - OpenCode MCP 同步和导入（含 local/remote 格式转换）
 //! - `hermes` - Hermes MCP 同步和导入
+//! - `client` - MCP 客户端（stdio/http/sse 传输与初始化握手）
+//! - `probe` - 服务器存活探测（initialize + tools/list + resources/list 握手）
+//! - `placeholders` - `${secret:NAME}` / `${env:NAME}` 占位符解析
 
 mod claude;
+mod client;
 mod codex;
 mod gemini;
 mod grokbuild;
 mod hermes;
 mod opencode;
+mod placeholders;
+mod probe;
 mod validation;
 
 // 重新导出公共 API
@@ -24,6 +30,8 @@ pub use claude::{
     import_from_claude, remove_server_from_claude, sync_enabled_to_claude,
     sync_single_server_to_claude,
 };
+pub(crate) use client::{McpClient, McpError, PROTOCOL_VERSION};
+pub(crate) use codex::json_server_to_toml_table;
 pub use codex::{
     import_from_codex, remove_server_from_codex, sync_enabled_to_codex, sync_single_server_to_codex,
 };
@@ -38,3 +46,7 @@ pub use hermes::{import_from_hermes, remove_server_from_hermes, sync_single_serv
 pub use opencode::{
     import_from_opencode, remove_server_from_opencode, sync_single_server_to_opencode,
 };
+pub use placeholders::{
+    is_valid_secret_name, missing_secrets, referenced_secrets, resolve_placeholders,
+};
+pub use probe::{probe_failure, probe_server, DEFAULT_PROBE_TIMEOUT};
@@ -0,0 +1,218 @@
+//! MCP 服务器定义中的占位符
+//!
+//! `McpServer.server` 里的任意字符串值都可以写 `${secret:NAME}`（本地密钥库）或
+//! `${env:NAME}`（CC Switch 进程的环境变量）。数据库与同步快照只保存占位符，
+//! 写入各应用 live 配置、探测或经网关连接时才替换为真实值。
+//! 不带前缀的 `${VAR}` 原样保留，交给 Claude Code 等客户端自己展开。
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde_json::Value;
+
+use crate::error::AppError;
+
+const SECRET_PREFIX: &str = "secret:";
+const ENV_PREFIX: &str = "env:";
+
+/// 密钥名称：字母、数字、`_`、`-`、`.`
+pub fn is_valid_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// 返回替换掉所有占位符后的服务器定义；引用的密钥或环境变量不存在时报错
+pub fn resolve_placeholders(
+    spec: &Value,
+    secrets: &HashMap<String, String>,
+) -> Result<Value, AppError> {
+    resolve_with(spec, &|kind, name| match kind {
+        Placeholder::Secret => secrets.get(name).cloned().ok_or_else(|| {
+            AppError::McpValidation(format!("本地密钥库中没有 {name}（${{secret:{name}}}）"))
+        }),
+        Placeholder::Env => std::env::var(name).map_err(|_| {
+            AppError::McpValidation(format!("环境变量 {name} 未设置（${{env:{name}}}）"))
+        }),
+    })
+}
+
+/// 服务器定义引用到的密钥名称
+pub fn referenced_secrets(spec: &Value) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    collect_secrets(spec, &mut names);
+    names
+}
+
+/// 服务器定义引用了、但本地密钥库中没有的密钥名称（如从其他设备同步过来的服务器）
+pub fn missing_secrets(spec: &Value, secrets: &HashMap<String, String>) -> BTreeSet<String> {
+    referenced_secrets(spec)
+        .into_iter()
+        .filter(|name| !secrets.contains_key(name))
+        .collect()
+}
+
+fn collect_secrets(value: &Value, names: &mut BTreeSet<String>) {
+    match value {
+        Value::String(s) => {
+            for (kind, name) in scan(s).into_iter().filter_map(|token| token.placeholder()) {
+                if kind == Placeholder::Secret {
+                    names.insert(name.to_string());
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_secrets(item, names)),
+        Value::Object(map) => map.values().for_each(|child| collect_secrets(child, names)),
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Secret,
+    Env,
+}
+
+fn resolve_with(
+    value: &Value,
+    lookup: &dyn Fn(Placeholder, &str) -> Result<String, AppError>,
+) -> Result<Value, AppError> {
+    Ok(match value {
+        Value::String(s) => Value::String(resolve_str(s, lookup)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| resolve_with(item, lookup))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, child)| Ok((key.clone(), resolve_with(child, lookup)?)))
+                .collect::<Result<_, AppError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn resolve_str(
+    s: &str,
+    lookup: &dyn Fn(Placeholder, &str) -> Result<String, AppError>,
+) -> Result<String, AppError> {
+    let mut out = String::with_capacity(s.len());
+    for token in scan(s) {
+        match token.placeholder() {
+            Some((kind, name)) => out.push_str(&lookup(kind, name)?),
+            None => out.push_str(token.text),
+        }
+    }
+    Ok(out)
+}
+
+/// 字符串切分出的片段：普通文本或 `${...}` 整体
+struct Token<'a> {
+    text: &'a str,
+    braced: bool,
+}
+
+impl<'a> Token<'a> {
+    /// 只有 `secret:`/`env:` 前缀且名称合法的 `${...}` 才算占位符
+    fn placeholder(&self) -> Option<(Placeholder, &'a str)> {
+        if !self.braced {
+            return None;
+        }
+        let inner = &self.text[2..self.text.len() - 1];
+        let (kind, name) = if let Some(name) = inner.strip_prefix(SECRET_PREFIX) {
+            (Placeholder::Secret, name)
+        } else {
+            (Placeholder::Env, inner.strip_prefix(ENV_PREFIX)?)
+        };
+        is_valid_secret_name(name).then_some((kind, name))
+    }
+}
+
+fn scan(s: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token {
+                text: &rest[..start],
+                braced: false,
+            });
+        }
+        tokens.push(Token {
+            text: &rest[start..start + len + 1],
+            braced: true,
+        });
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token {
+            text: rest,
+            braced: false,
+        });
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resolves_secret_and_env_placeholders_in_nested_strings() {
+        std::env::set_var("CC_SWITCH_PLACEHOLDER_TEST_HOST", "mcp.example.com");
+        let secrets = HashMap::from([("GITHUB_TOKEN".to_string(), "ghp_123".to_string())]);
+        let spec = json!({
+            "type": "http",
+            "url": "https://${env:CC_SWITCH_PLACEHOLDER_TEST_HOST}/mcp",
+            "headers": { "Authorization": "Bearer ${secret:GITHUB_TOKEN}" },
+            "args": ["--home", "${HOME}", "${secret:bad name}"],
+            "timeout": 30
+        });
+
+        let resolved = resolve_placeholders(&spec, &secrets).unwrap();
+        assert_eq!(resolved["url"], "https://mcp.example.com/mcp");
+        assert_eq!(resolved["headers"]["Authorization"], "Bearer ghp_123");
+        // 不带前缀或名称不合法的 `${...}` 原样保留
+        assert_eq!(
+            resolved["args"],
+            json!(["--home", "${HOME}", "${secret:bad name}"])
+        );
+        assert_eq!(resolved["timeout"], 30);
+
+        assert_eq!(
+            referenced_secrets(&spec).into_iter().collect::<Vec<_>>(),
+            vec!["GITHUB_TOKEN"]
+        );
+    }
+
+    #[test]
+    fn missing_secret_or_env_is_an_error() {
+        let spec = json!({ "env": { "TOKEN": "${secret:MISSING}" } });
+        let err = resolve_placeholders(&spec, &HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("MISSING"), "{err}");
+
+        let spec = json!({ "env": { "TOKEN": "${env:CC_SWITCH_PLACEHOLDER_TEST_UNSET}" } });
+        assert!(resolve_placeholders(&spec, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn missing_secrets_lists_only_names_absent_from_the_store() {
+        let secrets = HashMap::from([("PRESENT".to_string(), "x".to_string())]);
+        let spec = json!({
+            "env": { "A": "${secret:PRESENT}", "B": "${secret:ABSENT}" },
+            "args": ["${env:HOME}"]
+        });
+        assert_eq!(
+            missing_secrets(&spec, &secrets)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["ABSENT"]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! MCP 服务器存活探测
+//!
+//! 用 [`McpClient`] 真正启动/连接服务器，完成 `initialize` 握手后再查询 `tools/list`、
+//! `resources/list`，记录服务器版本与工具清单。整个握手受同一个超时约束；超时或失败后
+//! 子进程随客户端一并回收，不会残留。
+
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use super::client::{str_field, McpClient, McpError};
+use super::validation::validate_server_spec;
+use crate::app_config::{McpProbeResult, McpProbeStatus};
+
+/// 默认握手时限：npx/uvx 首次启动需要下载依赖，给得宽松一些
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 探测一个 MCP 服务器；任何失败都体现在返回值的 `status`/`error` 中
+pub async fn probe_server(server_id: &str, spec: &Value, timeout: Duration) -> McpProbeResult {
+    let started = Instant::now();
+    let outcome = match validate_server_spec(spec) {
+        Err(e) => Err(e.to_string()),
+        Ok(()) => match tokio::time::timeout(timeout, handshake(spec)).await {
+            Ok(outcome) => outcome.map_err(|e| e.to_string()),
+            Err(_) => {
+                return finish(
+                    server_id,
+                    started,
+                    Handshake::default(),
+                    McpProbeStatus::Timeout,
+                    Some(format!("握手未在 {} 秒内完成", timeout.as_secs())),
+                )
+            }
+        },
+    };
+
+    match outcome {
+        Ok(handshake) => finish(server_id, started, handshake, McpProbeStatus::Ok, None),
+        Err(error) => finish(
+            server_id,
+            started,
+            Handshake::default(),
+            McpProbeStatus::Failed,
+            Some(error),
+        ),
+    }
+}
+
+/// 未能开始探测（如占位符无法解析）时的失败结果
+pub fn probe_failure(server_id: &str, error: String) -> McpProbeResult {
+    finish(
+        server_id,
+        Instant::now(),
+        Handshake::default(),
+        McpProbeStatus::Failed,
+        Some(error),
+    )
+}
+
+fn finish(
+    server_id: &str,
+    started: Instant,
+    handshake: Handshake,
+    status: McpProbeStatus,
+    error: Option<String>,
+) -> McpProbeResult {
+    McpProbeResult {
+        server_id: server_id.to_string(),
+        status,
+        server_name: handshake.server_name,
+        server_version: handshake.server_version,
+        protocol_version: handshake.protocol_version,
+        tools: handshake.tools,
+        resources: handshake.resources,
+        error,
+        duration_ms: started.elapsed().as_millis() as u64,
+        probed_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+#[derive(Debug, Default)]
+struct Handshake {
+    server_name: Option<String>,
+    server_version: Option<String>,
+    protocol_version: Option<String>,
+    tools: Vec<String>,
+    resources: Vec<String>,
+}
+
+async fn handshake(spec: &Value) -> Result<Handshake, McpError> {
+    let client = McpClient::connect(spec).await?;
+    let init = client.init_result();
+    let mut handshake = Handshake {
+        server_name: init.get("serverInfo").and_then(|i| str_field(i, "name")),
+        server_version: init.get("serverInfo").and_then(|i| str_field(i, "version")),
+        protocol_version: str_field(init, "protocolVersion"),
+        ..Handshake::default()
+    };
+
+    if client.supports("tools") {
+        let tools = client.list_all("tools/list", "tools").await?;
+        handshake.tools = item_names(&tools);
+    }
+    if client.supports("resources") {
+        let resources = client.list_all("resources/list", "resources").await?;
+        handshake.resources = item_names(&resources);
+    }
+    Ok(handshake)
+}
+
+/// 工具取 `name`；资源优先 `name`，缺省时退回 `uri`
+fn item_names(items: &[Value]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|item| str_field(item, "name").or_else(|| str_field(item, "uri")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn invalid_spec_fails_without_spawning() {
+        let result = probe_server("bad", &json!({ "type": "stdio" }), DEFAULT_PROBE_TIMEOUT).await;
+        assert_eq!(result.status, McpProbeStatus::Failed);
+        assert!(result.error.is_some());
+    }
+
+    #[cfg(unix)]
+    fn scripted_server(script: &str) -> Value {
+        json!({ "command": "sh", "args": ["-c", script] })
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_probe_collects_version_tools_and_resources() {
+        // 按请求顺序回放固定响应：initialize(1)、initialized 通知、tools/list(2 → 3 分页)、resources/list(4)
+        let script = r#"
+read l
+echo 'starting up'
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{},"resources":{}},"serverInfo":{"name":"fake","version":"1.2.3"}}}'
+read l
+read l
+echo '{"jsonrpc":"2.0","method":"notifications/message","params":{}}'
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo"}],"nextCursor":"p2"}}'
+read l
+echo '{"jsonrpc":"2.0","id":3,"result":{"tools":[{"name":"add"}]}}'
+read l
+echo '{"jsonrpc":"2.0","id":4,"result":{"resources":[{"uri":"file:///a"},{"uri":"file:///b","name":"b"}]}}'
+read l
+"#;
+        let result = probe_server("fake", &scripted_server(script), DEFAULT_PROBE_TIMEOUT).await;
+
+        assert_eq!(result.status, McpProbeStatus::Ok, "{:?}", result.error);
+        assert_eq!(result.server_name.as_deref(), Some("fake"));
+        assert_eq!(result.server_version.as_deref(), Some("1.2.3"));
+        assert_eq!(result.protocol_version.as_deref(), Some("2025-06-18"));
+        assert_eq!(result.tools, vec!["echo", "add"]);
+        assert_eq!(result.resources, vec!["file:///a", "b"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_probe_reports_exit_with_stderr_and_timeout() {
+        let crashed = probe_server(
+            "crash",
+            &scripted_server("echo 'Cannot find module foo' >&2; sleep 0.2; exit 1"),
+            DEFAULT_PROBE_TIMEOUT,
+        )
+        .await;
+        assert_eq!(crashed.status, McpProbeStatus::Failed);
+        let error = crashed.error.unwrap();
+        assert!(error.contains("Cannot find module foo"), "{error}");
+
+        let hung = probe_server(
+            "hung",
+            &scripted_server("sleep 5"),
+            Duration::from_millis(200),
+        )
+        .await;
+        assert_eq!(hung.status, McpProbeStatus::Timeout);
+    }
+}
@@ -7,6 +7,7 @@ use crate::config::{atomic_write, get_app_config_dir};
 use crate::error::AppError;
 use crate::settings::{effective_backup_retain_count, get_openclaw_override_dir};
 use chrono::Local;
+#[cfg(feature = "gui")]
 use indexmap::IndexMap;
 use json_five::rt::parser::{
     from_str as rt_from_str, JSONKeyValuePair as RtJSONKeyValuePair,
@@ -170,6 +171,7 @@ pub struct OpenClawAgents {
 }
 
 /// OpenClaw env 配置（openclaw.json 的 env 节点）
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenClawEnvConfig {
     #[serde(flatten)]
@@ -177,6 +179,7 @@ pub struct OpenClawEnvConfig {
 }
 
 /// OpenClaw tools 配置（openclaw.json 的 tools 节点）
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenClawToolsConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -210,6 +213,7 @@ pub fn read_openclaw_config() -> Result<Value, AppError> {
 /// 对现有 OpenClaw 配置做健康检查。
 ///
 /// 解析失败时返回单条 parse 警告，不抛出错误。
+#[cfg(feature = "gui")]
 pub fn scan_openclaw_config_health() -> Result<Vec<OpenClawHealthWarning>, AppError> {
     let path = get_openclaw_config_path();
     if !path.exists() {
@@ -625,6 +629,7 @@ fn scan_openclaw_health_from_value(config: &Value) -> Vec<OpenClawHealthWarning>
     warnings
 }
 
+#[cfg(feature = "gui")]
 fn remove_legacy_timeout(defaults_value: &mut Value) {
     if let Some(defaults_obj) = defaults_value.as_object_mut() {
         defaults_obj.remove("timeout");
@@ -649,6 +654,7 @@ pub fn get_providers() -> Result<Map<String, Value>, AppError> {
 }
 
 /// 获取单个供应商配置（原始 JSON）
+#[cfg(feature = "gui")]
 pub fn get_provider(id: &str) -> Result<Option<Value>, AppError> {
     Ok(get_providers()?.get(id).cloned())
 }
@@ -710,6 +716,7 @@ pub fn remove_provider(id: &str) -> Result<OpenClawWriteOutcome, AppError> {
 // ============================================================================
 
 /// 获取所有供应商配置（类型化）
+#[cfg(feature = "gui")]
 pub fn get_typed_providers() -> Result<IndexMap<String, OpenClawProviderConfig>, AppError> {
     let providers = get_providers()?;
     let mut result = IndexMap::new();
@@ -742,6 +749,7 @@ pub fn set_typed_provider(
 // ============================================================================
 
 /// 读取默认模型配置（agents.defaults.model）
+#[cfg(feature = "gui")]
 pub fn get_default_model() -> Result<Option<OpenClawDefaultModel>, AppError> {
     let config = read_openclaw_config()?;
 
@@ -759,6 +767,7 @@ pub fn get_default_model() -> Result<Option<OpenClawDefaultModel>, AppError> {
 }
 
 /// 设置默认模型配置（agents.defaults.model）
+#[cfg(feature = "gui")]
 pub fn set_default_model(model: &OpenClawDefaultModel) -> Result<OpenClawWriteOutcome, AppError> {
     let mut config = read_openclaw_config()?;
     let root = ensure_object(&mut config);
@@ -781,6 +790,7 @@ pub fn set_default_model(model: &OpenClawDefaultModel) -> Result<OpenClawWriteOu
 }
 
 /// 读取模型目录/允许列表（agents.defaults.models）
+#[cfg(feature = "gui")]
 pub fn get_model_catalog() -> Result<Option<HashMap<String, OpenClawModelCatalogEntry>>, AppError> {
     let config = read_openclaw_config()?;
 
@@ -798,6 +808,7 @@ pub fn get_model_catalog() -> Result<Option<HashMap<String, OpenClawModelCatalog
 }
 
 /// 设置模型目录/允许列表（agents.defaults.models）
+#[cfg(feature = "gui")]
 pub fn set_model_catalog(
     catalog: &HashMap<String, OpenClawModelCatalogEntry>,
 ) -> Result<OpenClawWriteOutcome, AppError> {
@@ -826,6 +837,7 @@ pub fn set_model_catalog(
 // ============================================================================
 
 /// Read the full agents.defaults config
+#[cfg(feature = "gui")]
 pub fn get_agents_defaults() -> Result<Option<OpenClawAgentsDefaults>, AppError> {
     let config = read_openclaw_config()?;
 
@@ -839,6 +851,7 @@ pub fn get_agents_defaults() -> Result<Option<OpenClawAgentsDefaults>, AppError>
 }
 
 /// Write the full agents.defaults config
+#[cfg(feature = "gui")]
 pub fn set_agents_defaults(
     defaults: &OpenClawAgentsDefaults,
 ) -> Result<OpenClawWriteOutcome, AppError> {
@@ -865,6 +878,7 @@ pub fn set_agents_defaults(
 // ============================================================================
 
 /// Read the env config section
+#[cfg(feature = "gui")]
 pub fn get_env_config() -> Result<OpenClawEnvConfig, AppError> {
     let config = read_openclaw_config()?;
 
@@ -879,6 +893,7 @@ pub fn get_env_config() -> Result<OpenClawEnvConfig, AppError> {
 }
 
 /// Write the env config section
+#[cfg(feature = "gui")]
 pub fn set_env_config(env: &OpenClawEnvConfig) -> Result<OpenClawWriteOutcome, AppError> {
     let value = serde_json::to_value(env).map_err(|e| AppError::JsonSerialize { source: e })?;
     write_root_section("env", &value)
@@ -889,6 +904,7 @@ pub fn set_env_config(env: &OpenClawEnvConfig) -> Result<OpenClawWriteOutcome, A
 // ============================================================================
 
 /// Read the tools config section
+#[cfg(feature = "gui")]
 pub fn get_tools_config() -> Result<OpenClawToolsConfig, AppError> {
     let config = read_openclaw_config()?;
 
@@ -906,6 +922,7 @@ pub fn get_tools_config() -> Result<OpenClawToolsConfig, AppError> {
 }
 
 /// Write the tools config section
+#[cfg(feature = "gui")]
 pub fn set_tools_config(tools: &OpenClawToolsConfig) -> Result<OpenClawWriteOutcome, AppError> {
     let value = serde_json::to_value(tools).map_err(|e| AppError::JsonSerialize { source: e })?;
     write_root_section("tools", &value)
@@ -2,6 +2,7 @@ use crate::config::write_json_file_with_contents;
 use crate::error::AppError;
 use crate::provider::OpenCodeProviderConfig;
 use crate::settings::get_opencode_override_dir;
+#[cfg(feature = "gui")]
 use indexmap::IndexMap;
 use serde_json::{json, Map, Value};
 use std::path::{Path, PathBuf};
@@ -61,6 +62,7 @@ pub fn get_opencode_config_path() -> PathBuf {
 
 /// 获取 OpenCode SQLite 数据库路径
 /// 优先级: OPENCODE_DB 环境变量 > XDG_DATA_HOME > ~/.local/share/opencode
+#[cfg(feature = "gui")]
 pub fn get_opencode_db_path() -> PathBuf {
     // 支持 OPENCODE_DB 环境变量覆盖（忽略空字符串）
     if let Ok(custom_path) = std::env::var("OPENCODE_DB") {
@@ -77,6 +79,7 @@ pub fn get_opencode_db_path() -> PathBuf {
     get_opencode_data_dir().join("opencode.db")
 }
 
+#[cfg(feature = "gui")]
 fn get_opencode_data_dir() -> PathBuf {
     // 尊重 XDG_DATA_HOME（按 XDG 规范，空字符串视为未设置）
     if let Ok(xdg_data) = std::env::var("XDG_DATA_HOME") {
@@ -193,6 +196,7 @@ pub fn remove_provider(id: &str) -> Result<(), AppError> {
     write_opencode_config_to_path_with_contents(&path, &config).map(|_| ())
 }
 
+#[cfg(feature = "gui")]
 pub fn get_typed_providers() -> Result<IndexMap<String, OpenCodeProviderConfig>, AppError> {
     let providers = get_providers()?;
     let mut result = IndexMap::new();
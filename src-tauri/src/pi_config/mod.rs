@@ -6,6 +6,7 @@
 use crate::config::{atomic_write_private, get_home_dir};
 use crate::error::AppError;
 use indexmap::IndexMap;
+#[cfg(feature = "gui")]
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use sha2::{Digest, Sha256};
@@ -20,6 +21,7 @@ static MODELS_FILE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 #[cfg(test)]
 static TEST_AGENT_DIR: LazyLock<Mutex<Option<PathBuf>>> = LazyLock::new(|| Mutex::new(None));
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct PiNativeDefaults {
@@ -76,10 +78,12 @@ pub(crate) fn get_pi_models_path() -> Result<PathBuf, AppError> {
     Ok(get_pi_agent_dir()?.join("models.json"))
 }
 
+#[cfg(feature = "gui")]
 pub(crate) fn get_pi_settings_path() -> Result<PathBuf, AppError> {
     Ok(get_pi_agent_dir()?.join("settings.json"))
 }
 
+#[cfg(feature = "gui")]
 pub(crate) fn read_pi_native_defaults() -> Result<PiNativeDefaults, AppError> {
     let path = get_pi_settings_path()?;
     if !path.exists() {
@@ -308,6 +312,7 @@ fn read_models_document_with_revision(path: &Path) -> Result<(Value, String), Ap
     Ok((document, revision))
 }
 
+#[cfg(feature = "gui")]
 fn read_json5_value(path: &Path, label: &str) -> Result<Value, AppError> {
     parse_json5_value(path, label, read_file_limited(path, label)?)
 }
@@ -450,6 +455,7 @@ fn ensure_private_models_parent(path: &Path) -> Result<(), AppError> {
     Ok(())
 }
 
+#[cfg(feature = "gui")]
 fn optional_string(
     object: &Map<String, Value>,
     key: &str,
@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::app_config::PromptApps;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Prompt {
     pub id: String,
@@ -9,6 +13,31 @@ pub struct Prompt {
     pub description: Option<String>,
     #[serde(default)]
     pub enabled: bool,
+    /// 多个片段同时启用时的拼接顺序（升序）；未设置的排在最后
+    #[serde(rename = "sortIndex", skip_serializing_if = "Option::is_none")]
+    pub sort_index: Option<i64>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+/// 共享提示词：同一份内容同步到多个应用的提示词文件，可按应用追加附录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedPrompt {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub apps: PromptApps,
+    /// 按应用追加在共享内容之后的附录（key 为 app_type）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub appendices: BTreeMap<String, String>,
+    /// 共享提示词之间的顺序；共享提示词整体排在各应用自身的片段之前
+    #[serde(rename = "sortIndex", skip_serializing_if = "Option::is_none")]
+    pub sort_index: Option<i64>,
     #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
     pub created_at: Option<i64>,
     #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
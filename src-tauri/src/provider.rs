@@ -67,6 +67,14 @@ impl Provider {
         }
     }
 
+    /// 密钥是否无法用本机主密钥解密（见 [`ProviderMeta::secrets_locked`]）
+    pub fn secrets_locked(&self) -> bool {
+        self.meta
+            .as_ref()
+            .and_then(|meta| meta.secrets_locked)
+            .unwrap_or(false)
+    }
+
     pub fn is_codex_oauth(&self) -> bool {
         self.provider_type() == Some("codex_oauth")
     }
@@ -478,6 +486,13 @@ pub struct ProviderMeta {
     /// 每月消费限额（USD）
     #[serde(rename = "limitMonthlyUsd", skip_serializing_if = "Option::is_none")]
     pub limit_monthly_usd: Option<String>,
+    /// 加权路由策略下的权重（缺省为 1，0 表示仅作为故障转移备选）
+    #[serde(rename = "routingWeight", skip_serializing_if = "Option::is_none")]
+    pub routing_weight: Option<u32>,
+    /// 本机主密钥无法解密该供应商的密钥（读取时标记，不落库）；此时密钥字段为空，
+    /// 供应商不会写入 live 配置，也不参与代理路由
+    #[serde(rename = "secretsLocked", skip_serializing_if = "Option::is_none")]
+    pub secrets_locked: Option<bool>,
     /// Claude API 格式（仅 Claude 供应商使用）
     /// - "anthropic": 原生 Anthropic Messages API，直接透传
     /// - "openai_chat": OpenAI Chat Completions 格式，需要转换
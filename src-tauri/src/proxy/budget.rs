@@ -0,0 +1,251 @@
+//! 供应商消费限额守卫
+//!
+//! 在代理路由阶段执行 `limitDailyUsd` / `limitMonthlyUsd`：超额的供应商视为不可用。
+//! 花费总额首次从数据库（明细日志 + 日汇总）加载后缓存在内存中，
+//! 之后每条请求日志写入时累加，避免每个请求都查询 SQLite。
+
+use crate::database::Database;
+use crate::provider::Provider;
+use chrono::{Datelike, Local, NaiveDate};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// 缓存的花费总额多久后从数据库重新校准
+///
+/// 内存累加只覆盖经过本进程代理的请求；会话日志导入等其他写入路径
+/// 依赖定期校准来体现。
+const BUDGET_RESYNC_INTERVAL: Duration = Duration::from_secs(300);
+
+/// 超额信息（用于日志和错误响应）
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExceeded {
+    pub provider_id: String,
+    pub provider_name: String,
+    /// "daily" 或 "monthly"
+    pub period: &'static str,
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+}
+
+impl BudgetExceeded {
+    pub fn message(&self) -> String {
+        format!(
+            "Provider '{}' exceeded its {} budget: ${:.4} spent of ${:.2} limit",
+            self.provider_name, self.period, self.spent_usd, self.limit_usd
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SpendEntry {
+    day: NaiveDate,
+    month: (i32, u32),
+    daily_spent: f64,
+    monthly_spent: f64,
+    synced_at: Instant,
+}
+
+impl SpendEntry {
+    fn is_fresh(&self, today: NaiveDate) -> bool {
+        self.day == today && self.synced_at.elapsed() < BUDGET_RESYNC_INTERVAL
+    }
+}
+
+/// 供应商花费缓存 - key 格式: "app_type:provider_id"
+#[derive(Default)]
+pub struct BudgetTracker {
+    spend: RwLock<HashMap<String, SpendEntry>>,
+}
+
+impl BudgetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 检查供应商是否已超出消费限额
+    ///
+    /// 未配置任何限额的供应商直接放行，不触发数据库查询。
+    pub async fn check(
+        &self,
+        db: &Database,
+        app_type: &str,
+        provider: &Provider,
+    ) -> Option<BudgetExceeded> {
+        let (limit_daily, limit_monthly) = provider_limits(provider);
+        if limit_daily.is_none() && limit_monthly.is_none() {
+            return None;
+        }
+
+        let key = format!("{app_type}:{}", provider.id);
+        let today = Local::now().date_naive();
+
+        let cached = {
+            let spend = self.spend.read().await;
+            spend.get(&key).filter(|e| e.is_fresh(today)).cloned()
+        };
+
+        let entry = match cached {
+            Some(entry) => entry,
+            None => {
+                let status = match db.check_provider_limits(&provider.id, app_type) {
+                    Ok(status) => status,
+                    Err(e) => {
+                        log::warn!(
+                            "[{app_type}] 读取供应商 {} 花费失败: {e}，跳过限额检查",
+                            provider.id
+                        );
+                        return None;
+                    }
+                };
+                let entry = SpendEntry {
+                    day: today,
+                    month: (today.year(), today.month()),
+                    daily_spent: status.daily_usage.parse().unwrap_or(0.0),
+                    monthly_spent: status.monthly_usage.parse().unwrap_or(0.0),
+                    synced_at: Instant::now(),
+                };
+                self.spend.write().await.insert(key, entry.clone());
+                entry
+            }
+        };
+
+        if let Some(limit) = limit_daily {
+            if entry.daily_spent >= limit {
+                return Some(BudgetExceeded {
+                    provider_id: provider.id.clone(),
+                    provider_name: provider.name.clone(),
+                    period: "daily",
+                    spent_usd: entry.daily_spent,
+                    limit_usd: limit,
+                });
+            }
+        }
+        if let Some(limit) = limit_monthly {
+            if entry.monthly_spent >= limit {
+                return Some(BudgetExceeded {
+                    provider_id: provider.id.clone(),
+                    provider_name: provider.name.clone(),
+                    period: "monthly",
+                    spent_usd: entry.monthly_spent,
+                    limit_usd: limit,
+                });
+            }
+        }
+        None
+    }
+
+    /// 累加一次请求的花费
+    ///
+    /// 仅更新已缓存的条目；尚未缓存的供应商会在下次检查时从数据库加载，
+    /// 届时已包含本条日志。
+    pub async fn record_spend(&self, app_type: &str, provider_id: &str, cost_usd: f64) {
+        if cost_usd <= 0.0 {
+            return;
+        }
+        let key = format!("{app_type}:{provider_id}");
+        let today = Local::now().date_naive();
+        let mut spend = self.spend.write().await;
+        if let Some(entry) = spend.get_mut(&key) {
+            if entry.day != today {
+                // 跨天：日累计失效，月累计仅在同月时保留，交给下次检查重新校准
+                spend.remove(&key);
+                return;
+            }
+            entry.daily_spent += cost_usd;
+            if entry.month == (today.year(), today.month()) {
+                entry.monthly_spent += cost_usd;
+            }
+        }
+    }
+}
+
+fn provider_limits(provider: &Provider) -> (Option<f64>, Option<f64>) {
+    let Some(meta) = provider.meta.as_ref() else {
+        return (None, None);
+    };
+    let parse = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|limit| *limit > 0.0)
+    };
+    (parse(&meta.limit_daily_usd), parse(&meta.limit_monthly_usd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderMeta;
+    use serde_json::json;
+
+    fn limited_provider(id: &str, daily: Option<&str>, monthly: Option<&str>) -> Provider {
+        let mut provider = Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None);
+        provider.meta = Some(ProviderMeta {
+            limit_daily_usd: daily.map(str::to_string),
+            limit_monthly_usd: monthly.map(str::to_string),
+            ..Default::default()
+        });
+        provider
+    }
+
+    fn insert_cost(db: &Database, provider_id: &str, cost: &str) {
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO proxy_request_logs (
+                request_id, provider_id, app_type, model,
+                input_tokens, output_tokens, total_cost_usd,
+                latency_ms, status_code, created_at
+            ) VALUES (?1, ?2, 'claude', 'm', 0, 0, ?3, 0, 200, ?4)",
+            rusqlite::params![
+                uuid::Uuid::new_v4().to_string(),
+                provider_id,
+                cost,
+                chrono::Utc::now().timestamp()
+            ],
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn provider_without_limits_is_never_blocked() {
+        let db = Database::memory().unwrap();
+        let tracker = BudgetTracker::new();
+        let provider = Provider::with_id("a".to_string(), "A".to_string(), json!({}), None);
+        assert!(tracker.check(&db, "claude", &provider).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn daily_limit_blocks_after_spend_recorded() {
+        let db = Database::memory().unwrap();
+        let provider = limited_provider("a", Some("1.00"), None);
+        db.save_provider("claude", &provider).unwrap();
+        insert_cost(&db, "a", "0.60");
+
+        let tracker = BudgetTracker::new();
+        assert!(tracker.check(&db, "claude", &provider).await.is_none());
+
+        // 内存累加，不依赖数据库重新查询
+        tracker.record_spend("claude", "a", 0.5).await;
+        let exceeded = tracker.check(&db, "claude", &provider).await.unwrap();
+        assert_eq!(exceeded.period, "daily");
+        assert!(exceeded.spent_usd >= 1.0);
+    }
+
+    #[tokio::test]
+    async fn monthly_limit_is_loaded_from_database() {
+        let db = Database::memory().unwrap();
+        let provider = limited_provider("a", None, Some("2"));
+        db.save_provider("claude", &provider).unwrap();
+        insert_cost(&db, "a", "2.5");
+
+        let tracker = BudgetTracker::new();
+        let exceeded = tracker.check(&db, "claude", &provider).await.unwrap();
+        assert_eq!(exceeded.period, "monthly");
+
+        // 限额取自供应商配置本身，调高后立即生效
+        let raised = limited_provider("a", None, Some("10"));
+        assert!(tracker.check(&db, "claude", &raised).await.is_none());
+    }
+}
@@ -0,0 +1,651 @@
+//! 请求抓包与重放
+//!
+//! 开启抓包（或收到重放请求）时，中间件为每个请求生成一个抓包 id，记录四段数据：
+//! 客户端原始请求、每次发往上游的请求（格式转换之后，含故障转移的每一次尝试）、
+//! 上游原始响应（含 SSE 原文）、以及重新编码后返回给客户端的响应。
+//! 认证类请求头一律脱敏；响应头 `x-cc-switch-capture-id` 回传抓包 id。
+//!
+//! 抓包记录由所有持有者（中间件的响应流、转发器的上游流、请求扩展）共享，
+//! 最后一个持有者释放时写入数据库，因此流式响应要等客户端读完才会落库。
+//!
+//! 重放：[`ReplayPins`] 发放一次性 nonce，重放请求带上 `x-cc-switch-replay` 头
+//! 回到本地代理，可指定供应商（跳过路由与故障转移），并再次被抓包。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures::StreamExt;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::content_encoding::{decompress_body_with_limit, get_content_encoding};
+use super::hyper_client::ProxyResponse;
+use super::server::ProxyState;
+use crate::database::Database;
+
+/// 返回给客户端的抓包 id 响应头
+pub const CAPTURE_ID_HEADER: &str = "x-cc-switch-capture-id";
+/// 重放请求携带的一次性 nonce 请求头（不会转发给上游）
+pub const REPLAY_HEADER: &str = "x-cc-switch-replay";
+/// 每段 body 最多保留的字节数，超出部分截断
+pub const MAX_CAPTURE_BODY_BYTES: usize = 2 * 1024 * 1024;
+/// 客户端请求体读取上限（与路由的 DefaultBodyLimit 一致）
+const MAX_INBOUND_BODY_BYTES: usize = 200 * 1024 * 1024;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// 抓包中的一次 HTTP 请求
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedRequest {
+    pub method: String,
+    /// 客户端请求为路径 + 查询；上游请求为脱敏后的目标 URL
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// 抓包中的一次 HTTP 响应
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// 已按 content-encoding 解压（截断时无法解压，保留原始字节的有损文本）
+    pub body: String,
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// 发往上游的一次尝试；`response` 为空表示连接失败或超时，没有拿到响应
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureAttempt {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub request: CapturedRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<CapturedResponse>,
+}
+
+/// 一次完整的抓包记录
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCapture {
+    pub id: String,
+    /// 请求到达转发器前未知（如本地直接返回的端点）时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    /// 被重放的原始抓包 id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<String>,
+    pub created_at: i64,
+    pub inbound: CapturedRequest,
+    #[serde(default)]
+    pub attempts: Vec<CaptureAttempt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_response: Option<CapturedResponse>,
+}
+
+/// 抓包列表项（不含各段 body）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCaptureSummary {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    pub method: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<String>,
+    pub created_at: i64,
+}
+
+/// 重放结果：新抓包 id 与本地代理返回的状态码
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureReplayResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_id: Option<String>,
+    pub status: u16,
+}
+
+impl ProxyCapture {
+    /// 最后一次尝试的供应商（即实际承接请求的供应商）
+    pub fn provider_id(&self) -> Option<&str> {
+        self.attempts.last().map(|a| a.provider_id.as_str())
+    }
+
+    pub fn status_code(&self) -> Option<u16> {
+        self.client_response.as_ref().map(|r| r.status)
+    }
+}
+
+/// 录制中的 body：按原始字节累积，落库时再解压、转文本
+#[derive(Default)]
+struct PendingBody {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+impl PendingBody {
+    fn push(&mut self, chunk: &[u8]) {
+        let room = MAX_CAPTURE_BODY_BYTES.saturating_sub(self.bytes.len());
+        if chunk.len() > room {
+            self.truncated = true;
+        }
+        self.bytes
+            .extend_from_slice(&chunk[..chunk.len().min(room)]);
+    }
+}
+
+struct PendingResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    content_encoding: Option<String>,
+    body: PendingBody,
+}
+
+impl PendingResponse {
+    fn new(status: StatusCode, headers: &HeaderMap) -> Self {
+        Self {
+            status: status.as_u16(),
+            headers: redact_headers(headers),
+            content_encoding: get_content_encoding(headers),
+            body: PendingBody::default(),
+        }
+    }
+
+    fn finish(self) -> CapturedResponse {
+        let decoded = match (&self.content_encoding, self.body.truncated) {
+            (Some(encoding), false) => {
+                decompress_body_with_limit(encoding, &self.body.bytes, MAX_CAPTURE_BODY_BYTES * 4)
+                    .ok()
+                    .flatten()
+            }
+            _ => None,
+        };
+        CapturedResponse {
+            status: self.status,
+            headers: self.headers,
+            body: String::from_utf8_lossy(decoded.as_deref().unwrap_or(&self.body.bytes))
+                .into_owned(),
+            truncated: self.body.truncated,
+        }
+    }
+}
+
+struct PendingAttempt {
+    provider_id: String,
+    provider_name: String,
+    request: CapturedRequest,
+    response: Option<PendingResponse>,
+}
+
+struct Recording {
+    capture: ProxyCapture,
+    attempts: Vec<PendingAttempt>,
+    client_response: Option<PendingResponse>,
+}
+
+struct CaptureRecorder {
+    db: Arc<Database>,
+    recording: Mutex<Option<Recording>>,
+}
+
+impl Drop for CaptureRecorder {
+    fn drop(&mut self) {
+        let Some(recording) = self
+            .recording
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        else {
+            return;
+        };
+        let mut capture = recording.capture;
+        capture.attempts = recording
+            .attempts
+            .into_iter()
+            .map(|attempt| CaptureAttempt {
+                provider_id: attempt.provider_id,
+                provider_name: attempt.provider_name,
+                request: attempt.request,
+                response: attempt.response.map(PendingResponse::finish),
+            })
+            .collect();
+        capture.client_response = recording.client_response.map(PendingResponse::finish);
+        if let Err(e) = self.db.save_proxy_capture(&capture) {
+            log::warn!("[Capture] 保存抓包 {} 失败: {e}", capture.id);
+        }
+    }
+}
+
+/// 抓包句柄，放在请求扩展中供转发器记录上游请求/响应
+#[derive(Clone)]
+pub struct CaptureHandle(Arc<CaptureRecorder>);
+
+impl CaptureHandle {
+    fn new(db: Arc<Database>, inbound: CapturedRequest, replay_of: Option<String>) -> Self {
+        let capture = ProxyCapture {
+            id: uuid::Uuid::new_v4().to_string(),
+            replay_of,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            inbound,
+            ..Default::default()
+        };
+        Self(Arc::new(CaptureRecorder {
+            db,
+            recording: Mutex::new(Some(Recording {
+                capture,
+                attempts: Vec::new(),
+                client_response: None,
+            })),
+        }))
+    }
+
+    fn with_recording<R>(&self, f: impl FnOnce(&mut Recording) -> R) -> Option<R> {
+        let mut guard = self.0.recording.lock().unwrap_or_else(|e| e.into_inner());
+        guard.as_mut().map(f)
+    }
+
+    pub fn id(&self) -> String {
+        self.with_recording(|r| r.capture.id.clone())
+            .unwrap_or_default()
+    }
+
+    /// 记录一次发往上游的请求（格式转换、认证注入之后的最终形态）
+    pub fn record_upstream_request(
+        &self,
+        app_type: &str,
+        provider: &crate::provider::Provider,
+        method: &http::Method,
+        url_for_log: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) {
+        let mut pending = PendingBody::default();
+        pending.push(body);
+        let request = CapturedRequest {
+            method: method.to_string(),
+            url: url_for_log.to_string(),
+            headers: redact_headers(headers),
+            body: String::from_utf8_lossy(&pending.bytes).into_owned(),
+            truncated: pending.truncated,
+        };
+        self.with_recording(|r| {
+            r.capture.app_type = Some(app_type.to_string());
+            r.attempts.push(PendingAttempt {
+                provider_id: provider.id.clone(),
+                provider_name: provider.name.clone(),
+                request,
+                response: None,
+            });
+        });
+    }
+
+    /// 记录上游错误响应（body 已由调用方读出并解压）
+    pub fn record_upstream_error(&self, status: StatusCode, headers: &HeaderMap, body: &[u8]) {
+        self.with_recording(|r| {
+            if let Some(attempt) = r.attempts.last_mut() {
+                let mut response = PendingResponse::new(status, headers);
+                response.content_encoding = None;
+                response.body.push(body);
+                attempt.response = Some(response);
+            }
+        });
+    }
+
+    /// 包装上游成功响应：body 在被下游消费时同步录制，不改变流式行为
+    pub fn tap_upstream_response(&self, response: ProxyResponse) -> ProxyResponse {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let attempt_index = self.with_recording(|r| {
+            let index = r.attempts.len().checked_sub(1)?;
+            r.attempts[index].response = Some(PendingResponse::new(status, &headers));
+            Some(index)
+        });
+        let Some(Some(index)) = attempt_index else {
+            return response;
+        };
+        let handle = self.clone();
+        let stream = response.bytes_stream().inspect(move |chunk| {
+            if let Ok(bytes) = chunk {
+                handle.with_recording(|r| {
+                    if let Some(response) = r.attempts[index].response.as_mut() {
+                        response.body.push(bytes);
+                    }
+                });
+            }
+        });
+        ProxyResponse::streamed(status, headers, stream)
+    }
+
+    fn tap_client_response(&self, response: Response) -> Response {
+        let (mut parts, body) = response.into_parts();
+        self.with_recording(|r| {
+            r.client_response = Some(PendingResponse::new(parts.status, &parts.headers));
+        });
+        if let Ok(id) = HeaderValue::from_str(&self.id()) {
+            parts.headers.insert(CAPTURE_ID_HEADER, id);
+        }
+        let handle = self.clone();
+        let stream = body.into_data_stream().inspect(move |chunk| {
+            if let Ok(bytes) = chunk {
+                handle.with_recording(|r| {
+                    if let Some(response) = r.client_response.as_mut() {
+                        response.body.push(bytes);
+                    }
+                });
+            }
+        });
+        Response::from_parts(parts, Body::from_stream(stream))
+    }
+}
+
+/// 一次性重放授权：nonce → (原抓包 id, 指定供应商)
+#[derive(Default)]
+pub struct ReplayPins {
+    pins: Mutex<HashMap<String, ReplayPin>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayPin {
+    pub capture_id: String,
+    pub provider_id: Option<String>,
+}
+
+impl ReplayPins {
+    /// 发放 nonce；只有持有 nonce 的本地重放请求才能指定供应商
+    pub fn issue(&self, capture_id: &str, provider_id: Option<String>) -> String {
+        let nonce = uuid::Uuid::new_v4().to_string();
+        self.pins.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            nonce.clone(),
+            ReplayPin {
+                capture_id: capture_id.to_string(),
+                provider_id,
+            },
+        );
+        nonce
+    }
+
+    pub fn peek(&self, headers: &HeaderMap) -> Option<ReplayPin> {
+        let nonce = headers.get(REPLAY_HEADER)?.to_str().ok()?;
+        self.pins
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(nonce)
+            .cloned()
+    }
+
+    /// 取出并作废 nonce
+    pub fn take(&self, headers: &HeaderMap) -> Option<ReplayPin> {
+        let nonce = headers.get(REPLAY_HEADER)?.to_str().ok()?;
+        self.pins
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(nonce)
+    }
+}
+
+/// 抓包中间件：未开启抓包且不是重放请求时直接放行
+pub async fn capture_middleware(
+    State(state): State<ProxyState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let replay = state.replay_pins.peek(request.headers());
+    if !should_capture(request.uri().path(), replay.is_some()) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_INBOUND_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Failed to read request body: {e}"),
+            )
+                .into_response()
+        }
+    };
+    let mut pending = PendingBody::default();
+    pending.push(&body);
+    let inbound = CapturedRequest {
+        method: parts.method.to_string(),
+        url: parts
+            .uri
+            .path_and_query()
+            .map(|pq| strip_key_query(pq.as_str()))
+            .unwrap_or_else(|| parts.uri.path().to_string()),
+        headers: redact_headers(&parts.headers),
+        body: String::from_utf8_lossy(&pending.bytes).into_owned(),
+        truncated: pending.truncated,
+    };
+
+    let handle = CaptureHandle::new(state.db.clone(), inbound, replay.map(|p| p.capture_id));
+    parts.extensions.insert(handle.clone());
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    handle.tap_client_response(response)
+}
+
+fn should_capture(path: &str, is_replay: bool) -> bool {
+    if matches!(path, "/health" | "/status" | "/metrics") || path.starts_with("/mcp/") {
+        return false;
+    }
+    is_replay || crate::settings::get_proxy_capture_enabled()
+}
+
+/// 认证、Cookie 及名称里带 key/token/secret 的请求头只保留名称
+pub fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "cookie" | "set-cookie"
+    ) || name.contains("api-key")
+        || name.contains("token")
+        || name.contains("secret")
+        || name == REPLAY_HEADER
+}
+
+/// 去掉 Gemini 风格客户端放在 query 里的 `key=<API_KEY>`，其余参数原样保留供重放
+fn strip_key_query(path_and_query: &str) -> String {
+    let Some((path, query)) = path_and_query.split_once('?') else {
+        return path_and_query.to_string();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| pair.split('=').next() != Some("key"))
+        .collect();
+    if kept.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{}", kept.join("&"))
+    }
+}
+
+fn redact_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive_header(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+/// 重放时可以原样回放的客户端请求头：去掉脱敏值与由 HTTP 客户端重新生成的头
+pub fn replayable_headers(request: &CapturedRequest) -> Vec<(String, String)> {
+    request
+        .headers
+        .iter()
+        .filter(|(name, value)| {
+            value != REDACTED
+                && !matches!(
+                    name.to_ascii_lowercase().as_str(),
+                    "host" | "content-length" | "transfer-encoding" | "connection"
+                )
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[test]
+    fn auth_headers_are_redacted_and_skipped_on_replay() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer sk-live"));
+        headers.insert("x-api-key", HeaderValue::from_static("sk-ant"));
+        headers.insert("x-goog-api-key", HeaderValue::from_static("AIza"));
+        headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
+        headers.insert("host", HeaderValue::from_static("127.0.0.1:15721"));
+
+        let redacted = redact_headers(&headers);
+        assert!(redacted
+            .iter()
+            .filter(|(name, _)| name != "anthropic-version" && name != "host")
+            .all(|(_, value)| value == REDACTED));
+        assert!(!redacted.iter().any(|(_, value)| value.contains("sk-")));
+
+        let request = CapturedRequest {
+            headers: redacted,
+            ..Default::default()
+        };
+        assert_eq!(
+            replayable_headers(&request),
+            vec![("anthropic-version".to_string(), "2023-06-01".to_string())]
+        );
+    }
+
+    #[test]
+    fn api_key_query_param_is_dropped_from_captured_url() {
+        assert_eq!(
+            strip_key_query("/v1beta/models/gemini-pro:streamGenerateContent?alt=sse&key=AIza"),
+            "/v1beta/models/gemini-pro:streamGenerateContent?alt=sse"
+        );
+        assert_eq!(strip_key_query("/v1beta/models?key=AIza"), "/v1beta/models");
+        assert_eq!(
+            strip_key_query("/v1/messages?beta=true&keyword=x"),
+            "/v1/messages?beta=true&keyword=x"
+        );
+        assert_eq!(strip_key_query("/v1/messages"), "/v1/messages");
+    }
+
+    #[test]
+    fn pending_body_truncates_at_limit() {
+        let mut body = PendingBody::default();
+        body.push(&vec![b'a'; MAX_CAPTURE_BODY_BYTES - 1]);
+        assert!(!body.truncated);
+        body.push(b"bc");
+        assert!(body.truncated);
+        assert_eq!(body.bytes.len(), MAX_CAPTURE_BODY_BYTES);
+    }
+
+    #[test]
+    fn replay_nonce_is_single_use() {
+        let pins = ReplayPins::default();
+        let nonce = pins.issue("cap-1", Some("backup".to_string()));
+        let mut headers = HeaderMap::new();
+        headers.insert(REPLAY_HEADER, HeaderValue::from_str(&nonce).unwrap());
+
+        assert_eq!(
+            pins.peek(&headers).map(|p| p.capture_id),
+            Some("cap-1".to_string())
+        );
+        assert_eq!(
+            pins.take(&headers).and_then(|p| p.provider_id),
+            Some("backup".to_string())
+        );
+        assert!(pins.take(&headers).is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_saves_all_segments_when_last_holder_drops() {
+        let db = Arc::new(Database::memory().expect("memory db"));
+        let handle = CaptureHandle::new(
+            db.clone(),
+            CapturedRequest {
+                method: "POST".to_string(),
+                url: "/v1/responses".to_string(),
+                body: r#"{"model":"gpt-5"}"#.to_string(),
+                ..Default::default()
+            },
+            None,
+        );
+        let id = handle.id();
+        let provider = crate::provider::Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            serde_json::json!({}),
+            None,
+        );
+        let mut upstream_headers = HeaderMap::new();
+        upstream_headers.insert("authorization", HeaderValue::from_static("Bearer sk-x"));
+        handle.record_upstream_request(
+            "codex",
+            &provider,
+            &http::Method::POST,
+            "https://relay.example.com/v1/chat/completions",
+            &upstream_headers,
+            br#"{"model":"gpt-5","messages":[]}"#,
+        );
+
+        let upstream = ProxyResponse::streamed(
+            StatusCode::OK,
+            HeaderMap::new(),
+            futures::stream::iter(vec![
+                Ok(Bytes::from_static(b"data: {\"a\":1}\n\n")),
+                Ok(Bytes::from_static(b"data: [DONE]\n\n")),
+            ]),
+        );
+        let upstream_body = handle
+            .tap_upstream_response(upstream)
+            .bytes_with_limit(1024)
+            .await
+            .expect("read upstream");
+        assert_eq!(upstream_body.len(), 29);
+
+        let client = handle.tap_client_response(Response::new(Body::from("event: done\n\n")));
+        assert_eq!(
+            client.headers().get(CAPTURE_ID_HEADER).unwrap(),
+            id.as_str()
+        );
+        axum::body::to_bytes(client.into_body(), 1024)
+            .await
+            .expect("read client body");
+        drop(handle);
+
+        let saved = db.get_proxy_capture(&id).unwrap().expect("capture saved");
+        assert_eq!(saved.app_type.as_deref(), Some("codex"));
+        assert_eq!(saved.provider_id(), Some("relay"));
+        let attempt = &saved.attempts[0];
+        assert_eq!(attempt.request.headers[0].1, REDACTED);
+        assert!(attempt
+            .response
+            .as_ref()
+            .unwrap()
+            .body
+            .ends_with("[DONE]\n\n"));
+        assert_eq!(saved.status_code(), Some(200));
+        assert_eq!(saved.client_response.unwrap().body, "event: done\n\n");
+    }
+}
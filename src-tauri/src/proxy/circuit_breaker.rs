@@ -7,7 +7,7 @@ use super::types::AppProxyConfig;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// 熔断器状态
@@ -90,6 +90,8 @@ pub struct CircuitBreaker {
     config: Arc<RwLock<CircuitBreakerConfig>>,
     /// 半开状态已放行的请求数（用于限流）
     half_open_requests: Arc<AtomicU32>,
+    /// 后台探测打开熔断器时的保持期限：期限前不进入半开
+    probe_hold_until: Arc<RwLock<Option<Instant>>>,
 }
 
 /// 熔断器放行结果
@@ -114,6 +116,7 @@ impl CircuitBreaker {
             last_opened_at: Arc::new(RwLock::new(None)),
             config: Arc::new(RwLock::new(config)),
             half_open_requests: Arc::new(AtomicU32::new(0)),
+            probe_hold_until: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -137,16 +140,15 @@ impl CircuitBreaker {
         match state {
             CircuitState::Closed | CircuitState::HalfOpen => true,
             CircuitState::Open => {
-                if let Some(opened_at) = *self.last_opened_at.read().await {
-                    if opened_at.elapsed().as_secs() >= config.timeout_seconds {
-                        drop(config); // 释放读锁再转换状态
-                        log::info!(
-                            "[{}] 熔断器 Open → HalfOpen (超时恢复)",
-                            log_cb::OPEN_TO_HALF_OPEN
-                        );
-                        self.transition_to_half_open().await;
-                        return true;
-                    }
+                let timeout_seconds = config.timeout_seconds;
+                drop(config); // 释放读锁再转换状态
+                if self.open_timeout_elapsed(timeout_seconds).await {
+                    log::info!(
+                        "[{}] 熔断器 Open → HalfOpen (超时恢复)",
+                        log_cb::OPEN_TO_HALF_OPEN
+                    );
+                    self.transition_to_half_open().await;
+                    return true;
                 }
                 false
             }
@@ -163,31 +165,28 @@ impl CircuitBreaker {
                 used_half_open_permit: false,
             },
             CircuitState::Open => {
-                let config = self.config.read().await;
+                let timeout_seconds = self.config.read().await.timeout_seconds;
                 // 检查是否应该尝试半开
-                if let Some(opened_at) = *self.last_opened_at.read().await {
-                    if opened_at.elapsed().as_secs() >= config.timeout_seconds {
-                        drop(config); // 释放读锁再转换状态
-                        log::info!(
-                            "[{}] 熔断器 Open → HalfOpen (超时恢复)",
-                            log_cb::OPEN_TO_HALF_OPEN
-                        );
-                        self.transition_to_half_open().await;
-
-                        // 转换后按当前状态决定是否需要获取 HalfOpen 探测名额
-                        let current_state = *self.state.read().await;
-                        return match current_state {
-                            CircuitState::Closed => AllowResult {
-                                allowed: true,
-                                used_half_open_permit: false,
-                            },
-                            CircuitState::HalfOpen => self.allow_half_open_probe(),
-                            CircuitState::Open => AllowResult {
-                                allowed: false,
-                                used_half_open_permit: false,
-                            },
-                        };
-                    }
+                if self.open_timeout_elapsed(timeout_seconds).await {
+                    log::info!(
+                        "[{}] 熔断器 Open → HalfOpen (超时恢复)",
+                        log_cb::OPEN_TO_HALF_OPEN
+                    );
+                    self.transition_to_half_open().await;
+
+                    // 转换后按当前状态决定是否需要获取 HalfOpen 探测名额
+                    let current_state = *self.state.read().await;
+                    return match current_state {
+                        CircuitState::Closed => AllowResult {
+                            allowed: true,
+                            used_half_open_permit: false,
+                        },
+                        CircuitState::HalfOpen => self.allow_half_open_probe(),
+                        CircuitState::Open => AllowResult {
+                            allowed: false,
+                            used_half_open_permit: false,
+                        },
+                    };
                 }
 
                 AllowResult {
@@ -312,6 +311,42 @@ impl CircuitBreaker {
         self.transition_to_closed().await;
     }
 
+    /// 后台探测判定不可达：立即打开熔断器，并在 `hold` 内保持打开（不进入半开），
+    /// 让真实请求在下一次探测前都绕开该供应商
+    pub async fn force_open(&self, hold: Duration) {
+        if *self.state.read().await != CircuitState::Open {
+            log::warn!("[{}] 熔断器探测不可达 → Open", log_cb::PROBE_OPENED);
+        }
+        self.transition_to_open().await;
+        *self.probe_hold_until.write().await = Some(Instant::now() + hold);
+    }
+
+    /// 后台探测判定可达：关闭由探测打开的熔断器
+    ///
+    /// 由真实流量打开的熔断器不受影响——可达不代表可用（鉴权或模型错误只有真实流量能发现）。
+    /// 返回是否发生了关闭。
+    pub async fn release_probe_hold(&self) -> bool {
+        if self.probe_hold_until.read().await.is_none() {
+            return false;
+        }
+        log::info!("[{}] 熔断器探测恢复可达 → Closed", log_cb::PROBE_CLOSED);
+        self.transition_to_closed().await;
+        true
+    }
+
+    /// Open 状态是否已满足转入半开的条件（超时已到且不在探测保持期内）
+    async fn open_timeout_elapsed(&self, timeout_seconds: u64) -> bool {
+        if let Some(hold_until) = *self.probe_hold_until.read().await {
+            if Instant::now() < hold_until {
+                return false;
+            }
+        }
+        match *self.last_opened_at.read().await {
+            Some(opened_at) => opened_at.elapsed().as_secs() >= timeout_seconds,
+            None => false,
+        }
+    }
+
     fn allow_half_open_probe(&self) -> AllowResult {
         // 半开状态限流：只允许有限请求通过进行探测
         let max_half_open_requests = 1u32;
@@ -358,6 +393,7 @@ impl CircuitBreaker {
     /// 转换到打开状态
     async fn transition_to_open(&self) {
         *self.state.write().await = CircuitState::Open;
+        *self.probe_hold_until.write().await = None;
         *self.last_opened_at.write().await = Some(Instant::now());
         self.consecutive_failures.store(0, Ordering::SeqCst);
         self.consecutive_successes.store(0, Ordering::SeqCst);
@@ -379,6 +415,7 @@ impl CircuitBreaker {
     /// 转换到关闭状态
     async fn transition_to_closed(&self) {
         *self.state.write().await = CircuitState::Closed;
+        *self.probe_hold_until.write().await = None;
         self.consecutive_failures.store(0, Ordering::SeqCst);
         self.consecutive_successes.store(0, Ordering::SeqCst);
         // 重置计数器
@@ -492,4 +529,38 @@ mod tests {
         assert_eq!(breaker.get_state().await, CircuitState::Closed);
         assert!(breaker.allow_request().await.allowed);
     }
+
+    #[tokio::test]
+    async fn probe_hold_keeps_circuit_open_past_timeout() {
+        let config = CircuitBreakerConfig {
+            timeout_seconds: 0,
+            ..Default::default()
+        };
+        let breaker = CircuitBreaker::new(config);
+
+        breaker.force_open(Duration::from_secs(3600)).await;
+        assert_eq!(breaker.get_state().await, CircuitState::Open);
+        // timeout_seconds=0 本应立即半开，但探测保持期内仍拒绝
+        assert!(!breaker.is_available().await);
+        assert!(!breaker.allow_request().await.allowed);
+
+        assert!(breaker.release_probe_hold().await);
+        assert_eq!(breaker.get_state().await, CircuitState::Closed);
+        assert!(breaker.allow_request().await.allowed);
+    }
+
+    #[tokio::test]
+    async fn reachable_probe_does_not_close_traffic_opened_circuit() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        };
+        let breaker = CircuitBreaker::new(config);
+
+        breaker.record_failure(false).await;
+        assert_eq!(breaker.get_state().await, CircuitState::Open);
+
+        assert!(!breaker.release_probe_hold().await);
+        assert_eq!(breaker.get_state().await, CircuitState::Open);
+    }
 }
@@ -0,0 +1,331 @@
+//! 供应商端点镜像池
+//!
+//! 供应商在 `settings_config` 中的 base URL 与 `provider_endpoints` 中登记的自定义端点
+//! 共同组成镜像池：请求优先发往最快的健康端点，连接失败/超时时先切换到下一个镜像，
+//! 全部镜像失败后才交给供应商级故障转移。
+//!
+//! 端点健康状态独立于供应商熔断器：单个镜像连续失败只会让该镜像短暂冷却，
+//! 不会影响供应商本身的熔断统计。
+
+use crate::provider::Provider;
+use crate::services::speedtest::SpeedtestService;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+/// 连续失败多少次后进入冷却
+const ENDPOINT_FAILURE_THRESHOLD: u32 = 2;
+
+/// 冷却时长，到期后端点重新参与选择
+const ENDPOINT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// 测速结果多久后在后台重新测量
+const ENDPOINT_PROBE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 后台测速超时（秒）
+const ENDPOINT_PROBE_TIMEOUT_SECS: u64 = 5;
+
+/// 延迟指数滑动平均系数（新样本权重）
+const LATENCY_EWMA_ALPHA: f64 = 0.3;
+
+#[derive(Debug, Clone, Default)]
+struct EndpointHealth {
+    consecutive_failures: u32,
+    cooldown_until: Option<Instant>,
+    latency_ms: Option<f64>,
+    measured_at: Option<Instant>,
+}
+
+impl EndpointHealth {
+    fn is_cooling_down(&self) -> bool {
+        self.cooldown_until
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    fn observe_latency(&mut self, latency_ms: f64) {
+        self.latency_ms = Some(match self.latency_ms {
+            Some(previous) => previous + LATENCY_EWMA_ALPHA * (latency_ms - previous),
+            None => latency_ms,
+        });
+        self.measured_at = Some(Instant::now());
+    }
+
+    fn needs_probe(&self) -> bool {
+        self.measured_at
+            .is_none_or(|at| at.elapsed() >= ENDPOINT_PROBE_INTERVAL)
+    }
+}
+
+/// 端点镜像池 - key 格式: "app_type:provider_id" -> base_url -> 健康状态
+#[derive(Default)]
+pub struct EndpointPool {
+    endpoints: RwLock<HashMap<String, HashMap<String, EndpointHealth>>>,
+    /// 正在后台测速的供应商，避免重复发起
+    probing: Mutex<HashSet<String>>,
+}
+
+impl EndpointPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回本次请求应依次尝试的 base URL
+    ///
+    /// `primary` 为 `settings_config` 中的 base URL；未登记自定义端点时只返回它本身。
+    /// 排序规则：未冷却的端点在前，其中已测得延迟的按延迟升序，未测量的保持登记顺序
+    /// （主地址在前）；冷却中的端点排在最后，作为兜底仍会被尝试。
+    pub async fn candidates(
+        self: &Arc<Self>,
+        app_type: &str,
+        provider: &Provider,
+        primary: &str,
+    ) -> Vec<String> {
+        let urls = mirror_urls(provider, primary);
+        if urls.len() < 2 {
+            return urls;
+        }
+
+        let key = format!("{app_type}:{}", provider.id);
+        let (mut healthy, cooling, needs_probe) = {
+            let endpoints = self.endpoints.read().await;
+            let health = endpoints.get(&key);
+            let mut healthy = Vec::new();
+            let mut cooling = Vec::new();
+            let mut needs_probe = false;
+            for url in urls {
+                let state = health.and_then(|h| h.get(&url));
+                needs_probe |= state.is_none_or(EndpointHealth::needs_probe);
+                if state.is_some_and(EndpointHealth::is_cooling_down) {
+                    cooling.push(url);
+                } else {
+                    let latency = state.and_then(|s| s.latency_ms);
+                    healthy.push((url, latency));
+                }
+            }
+            (healthy, cooling, needs_probe)
+        };
+
+        // 稳定排序：已测量的按延迟升序排在前面，未测量的保持登记顺序
+        healthy.sort_by(|(_, a), (_, b)| match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+
+        if needs_probe {
+            let all: Vec<String> = healthy
+                .iter()
+                .map(|(url, _)| url.clone())
+                .chain(cooling.iter().cloned())
+                .collect();
+            self.spawn_probe(key, all).await;
+        }
+
+        healthy
+            .into_iter()
+            .map(|(url, _)| url)
+            .chain(cooling)
+            .collect()
+    }
+
+    /// 记录端点请求成功及其响应延迟
+    pub async fn record_success(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        url: &str,
+        latency: Duration,
+    ) {
+        let key = format!("{app_type}:{provider_id}");
+        let mut endpoints = self.endpoints.write().await;
+        let health = endpoints
+            .entry(key)
+            .or_default()
+            .entry(url.to_string())
+            .or_default();
+        health.consecutive_failures = 0;
+        health.cooldown_until = None;
+        health.observe_latency(latency.as_secs_f64() * 1000.0);
+    }
+
+    /// 记录端点连接失败/超时；连续失败达到阈值后进入冷却
+    pub async fn record_failure(&self, app_type: &str, provider_id: &str, url: &str) {
+        let key = format!("{app_type}:{provider_id}");
+        let mut endpoints = self.endpoints.write().await;
+        let health = endpoints
+            .entry(key)
+            .or_default()
+            .entry(url.to_string())
+            .or_default();
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= ENDPOINT_FAILURE_THRESHOLD {
+            log::warn!(
+                "[{app_type}] 供应商 {provider_id} 的端点 {url} 连续失败 {} 次，冷却 {}s",
+                health.consecutive_failures,
+                ENDPOINT_COOLDOWN.as_secs()
+            );
+            health.cooldown_until = Some(Instant::now() + ENDPOINT_COOLDOWN);
+        }
+    }
+
+    /// 在后台测量镜像延迟，结果用于后续请求排序
+    async fn spawn_probe(self: &Arc<Self>, key: String, urls: Vec<String>) {
+        if !self.probing.lock().await.insert(key.clone()) {
+            return;
+        }
+
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            match SpeedtestService::test_endpoints(urls, Some(ENDPOINT_PROBE_TIMEOUT_SECS)).await {
+                Ok(results) => {
+                    let mut endpoints = pool.endpoints.write().await;
+                    let health = endpoints.entry(key.clone()).or_default();
+                    for result in results {
+                        // 测速失败不计入健康状态，仅由真实请求的连接错误触发冷却
+                        if let Some(latency) = result.latency {
+                            let url = normalize_url(&result.url);
+                            health
+                                .entry(url)
+                                .or_default()
+                                .observe_latency(latency as f64);
+                        }
+                    }
+                }
+                Err(e) => log::debug!("[{key}] 端点镜像测速失败: {e}"),
+            }
+            pool.probing.lock().await.remove(&key);
+        });
+    }
+}
+
+fn normalize_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_string()
+}
+
+/// 主地址 + 自定义端点（规范化去重，保持登记顺序）
+fn mirror_urls(provider: &Provider, primary: &str) -> Vec<String> {
+    let mut urls = vec![normalize_url(primary)];
+    let Some(meta) = provider.meta.as_ref() else {
+        return urls;
+    };
+
+    let mut custom: Vec<_> = meta.custom_endpoints.values().collect();
+    custom.sort_by_key(|endpoint| endpoint.added_at);
+    for endpoint in custom {
+        let url = normalize_url(&endpoint.url);
+        if !url.is_empty() && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderMeta;
+    use crate::settings::CustomEndpoint;
+    use serde_json::json;
+
+    fn provider_with_mirrors(mirrors: &[&str]) -> Provider {
+        let mut provider = Provider::with_id("p".to_string(), "P".to_string(), json!({}), None);
+        let custom_endpoints = mirrors
+            .iter()
+            .enumerate()
+            .map(|(index, url)| {
+                (
+                    url.to_string(),
+                    CustomEndpoint {
+                        url: url.to_string(),
+                        added_at: index as i64,
+                        last_used: None,
+                    },
+                )
+            })
+            .collect();
+        provider.meta = Some(ProviderMeta {
+            custom_endpoints,
+            ..Default::default()
+        });
+        provider
+    }
+
+    #[tokio::test]
+    async fn provider_without_mirrors_uses_primary_only() {
+        let pool = Arc::new(EndpointPool::new());
+        let provider = Provider::with_id("p".to_string(), "P".to_string(), json!({}), None);
+        let urls = pool
+            .candidates("claude", &provider, "https://api.example.com/")
+            .await;
+        assert_eq!(urls, vec!["https://api.example.com"]);
+    }
+
+    #[tokio::test]
+    async fn mirrors_are_deduped_and_ordered_by_latency() {
+        let pool = Arc::new(EndpointPool::new());
+        let provider = provider_with_mirrors(&[
+            "https://a.example.com/",
+            "https://b.example.com",
+            "https://primary.example.com",
+        ]);
+
+        let urls = pool
+            .candidates("claude", &provider, "https://primary.example.com")
+            .await;
+        assert_eq!(
+            urls,
+            vec![
+                "https://primary.example.com",
+                "https://a.example.com",
+                "https://b.example.com"
+            ]
+        );
+
+        pool.record_success(
+            "claude",
+            "p",
+            "https://b.example.com",
+            Duration::from_millis(50),
+        )
+        .await;
+        pool.record_success(
+            "claude",
+            "p",
+            "https://primary.example.com",
+            Duration::from_millis(400),
+        )
+        .await;
+
+        let urls = pool
+            .candidates("claude", &provider, "https://primary.example.com")
+            .await;
+        assert_eq!(urls[0], "https://b.example.com");
+        assert_eq!(urls[1], "https://primary.example.com");
+    }
+
+    #[tokio::test]
+    async fn failing_mirror_cools_down_and_recovers_on_success() {
+        let pool = Arc::new(EndpointPool::new());
+        let provider = provider_with_mirrors(&["https://mirror.example.com"]);
+        let primary = "https://primary.example.com";
+
+        pool.record_failure("claude", "p", primary).await;
+        let urls = pool.candidates("claude", &provider, primary).await;
+        assert_eq!(urls[0], primary, "单次失败不触发冷却");
+
+        pool.record_failure("claude", "p", primary).await;
+        let urls = pool.candidates("claude", &provider, primary).await;
+        assert_eq!(urls, vec!["https://mirror.example.com", primary]);
+
+        // 其他应用的同一供应商不受影响
+        let urls = pool.candidates("codex", &provider, primary).await;
+        assert_eq!(urls[0], primary);
+
+        pool.record_success("claude", "p", primary, Duration::from_millis(10))
+            .await;
+        let urls = pool.candidates("claude", &provider, primary).await;
+        assert_eq!(urls[0], primary);
+    }
+}
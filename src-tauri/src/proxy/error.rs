@@ -38,6 +38,10 @@ pub enum ProxyError {
     #[error("未配置供应商")]
     NoProvidersConfigured,
 
+    /// 所有可用路由均超出消费限额
+    #[error("供应商已超出消费限额: {0}")]
+    BudgetExceeded(String),
+
     #[allow(dead_code)]
     #[error("Provider不健康: {0}")]
     ProviderUnhealthy(String),
@@ -114,6 +118,19 @@ impl IntoResponse for ProxyError {
 
                 (http_status, error_body)
             }
+            ProxyError::BudgetExceeded(message) => {
+                // 同时兼容 Anthropic（type + error.type）与 OpenAI（error.message/code）的错误结构
+                let error_body = json!({
+                    "type": "error",
+                    "error": {
+                        "type": "rate_limit_error",
+                        "code": "budget_exceeded",
+                        "message": message,
+                    }
+                });
+
+                (StatusCode::TOO_MANY_REQUESTS, error_body)
+            }
             _ => {
                 let (http_status, message) = match &self {
                     ProxyError::AlreadyRunning => (StatusCode::CONFLICT, self.to_string()),
@@ -162,7 +179,9 @@ impl IntoResponse for ProxyError {
                     ProxyError::ResponseBodyTooLarge(_) => {
                         (StatusCode::BAD_GATEWAY, self.to_string())
                     }
-                    ProxyError::UpstreamError { .. } => unreachable!(),
+                    ProxyError::UpstreamError { .. } | ProxyError::BudgetExceeded(_) => {
+                        unreachable!()
+                    }
                 };
 
                 let error_body = json!({
@@ -40,6 +40,9 @@ pub fn map_proxy_error_to_status(error: &ProxyError) -> u16 {
         // 未配置供应商：503 Service Unavailable
         ProxyError::NoProvidersConfigured => 503,
 
+        // 消费限额超出：429 Too Many Requests
+        ProxyError::BudgetExceeded(_) => 429,
+
         // 重试耗尽：503 Service Unavailable
         ProxyError::MaxRetriesExceeded => 503,
 
@@ -78,6 +81,7 @@ pub fn get_error_message(error: &ProxyError) -> String {
         ProxyError::NoAvailableProvider => "无可用 Provider".to_string(),
         ProxyError::AllProvidersCircuitOpen => "所有供应商已熔断，无可用渠道".to_string(),
         ProxyError::NoProvidersConfigured => "未配置供应商".to_string(),
+        ProxyError::BudgetExceeded(msg) => format!("供应商已超出消费限额: {msg}"),
         ProxyError::MaxRetriesExceeded => "所有 Provider 都失败，重试耗尽".to_string(),
         ProxyError::ProviderUnhealthy(msg) => format!("Provider 不健康: {msg}"),
         ProxyError::DatabaseError(msg) => format!("数据库错误: {msg}"),
@@ -117,6 +121,12 @@ mod tests {
         assert_eq!(map_proxy_error_to_status(&error), 503);
     }
 
+    #[test]
+    fn test_map_budget_exceeded_error() {
+        let error = ProxyError::BudgetExceeded("daily".to_string());
+        assert_eq!(map_proxy_error_to_status(&error), 429);
+    }
+
     #[test]
     fn test_map_status_matches_proxy_error_response_semantics() {
         assert_eq!(
@@ -7,8 +7,10 @@
 
 use crate::database::Database;
 use crate::error::AppError;
+use crate::proxy::AppHandle;
 use std::collections::HashSet;
 use std::sync::Arc;
+#[cfg(feature = "gui")]
 use tauri::{Emitter, Manager};
 use tokio::sync::RwLock;
 
@@ -40,7 +42,7 @@ impl FailoverSwitchManager {
     /// - `Err(e)` - 切换过程中发生错误
     pub async fn try_switch(
         &self,
-        app_handle: Option<&tauri::AppHandle>,
+        app_handle: Option<&AppHandle>,
         app_type: &str,
         provider_id: &str,
         provider_name: &str,
@@ -73,7 +75,7 @@ impl FailoverSwitchManager {
 
     async fn do_switch(
         &self,
-        app_handle: Option<&tauri::AppHandle>,
+        app_handle: Option<&AppHandle>,
         app_type: &str,
         provider_id: &str,
         provider_name: &str,
@@ -95,41 +97,61 @@ impl FailoverSwitchManager {
 
         log::info!("[FO-001] 切换: {app_type} → {provider_name}");
 
+        match app_handle {
+            Some(app) => Self::switch_in_app(app, app_type, provider_id).await,
+            None => Ok(false),
+        }
+    }
+
+    /// 在桌面端热切换供应商，并同步托盘菜单与前端
+    #[cfg(feature = "gui")]
+    async fn switch_in_app(
+        app: &AppHandle,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Result<bool, AppError> {
         let mut switched = false;
 
-        if let Some(app) = app_handle {
-            if let Some(app_state) = app.try_state::<crate::store::AppState>() {
-                switched = app_state
-                    .proxy_service
-                    .hot_switch_provider(app_type, provider_id)
-                    .await
-                    .map_err(AppError::Message)?
-                    .logical_target_changed;
-
-                if !switched {
-                    return Ok(false);
-                }
+        if let Some(app_state) = app.try_state::<crate::store::AppState>() {
+            switched = app_state
+                .proxy_service
+                .hot_switch_provider(app_type, provider_id)
+                .await
+                .map_err(AppError::Message)?
+                .logical_target_changed;
 
-                if let Ok(new_menu) = crate::tray::create_tray_menu(app, app_state.inner()) {
-                    if let Some(tray) = app.tray_by_id(crate::tray::TRAY_ID) {
-                        if let Err(e) = tray.set_menu(Some(new_menu)) {
-                            log::error!("[Failover] 更新托盘菜单失败: {e}");
-                        }
+            if !switched {
+                return Ok(false);
+            }
+
+            if let Ok(new_menu) = crate::tray::create_tray_menu(app, app_state.inner()) {
+                if let Some(tray) = app.tray_by_id(crate::tray::TRAY_ID) {
+                    if let Err(e) = tray.set_menu(Some(new_menu)) {
+                        log::error!("[Failover] 更新托盘菜单失败: {e}");
                     }
                 }
             }
+        }
 
-            // 发射事件到前端
-            let event_data = serde_json::json!({
-                "appType": app_type,
-                "providerId": provider_id,
-                "source": "failover"  // 标识来源是故障转移
-            });
-            if let Err(e) = app.emit("provider-switched", event_data) {
-                log::error!("[Failover] 发射事件失败: {e}");
-            }
+        // 发射事件到前端
+        let event_data = serde_json::json!({
+            "appType": app_type,
+            "providerId": provider_id,
+            "source": "failover"  // 标识来源是故障转移
+        });
+        if let Err(e) = app.emit("provider-switched", event_data) {
+            log::error!("[Failover] 发射事件失败: {e}");
         }
 
         Ok(switched)
     }
+
+    #[cfg(not(feature = "gui"))]
+    async fn switch_in_app(
+        app: &AppHandle,
+        _app_type: &str,
+        _provider_id: &str,
+    ) -> Result<bool, AppError> {
+        match *app {}
+    }
 }
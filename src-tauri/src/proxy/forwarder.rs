@@ -5,16 +5,19 @@
 use super::hyper_client::{ProxyResponse, MAX_RESPONSE_BODY_BYTES};
 use super::{
     body_filter::filter_private_params_with_whitelist,
+    capture::CaptureHandle,
     content_encoding::{decompress_body_with_limit, get_content_encoding},
     error::*,
     failover_switch::FailoverSwitchManager,
     json_canonical::{canonicalize_value, short_value_hash},
     log_codes::fwd as log_fwd,
+    metrics::ProxyMetrics,
     provider_router::ProviderRouter,
     providers::{
         codex_chat_history::CodexChatHistoryStore, gemini_shadow::GeminiShadowStore, get_adapter,
         AuthInfo, AuthStrategy, ProviderAdapter, ProviderType,
     },
+    telemetry::RequestTrace,
     thinking_budget_rectifier::{rectify_thinking_budget, should_rectify_thinking_budget},
     thinking_rectifier::{
         normalize_thinking_type, rectify_anthropic_request, should_rectify_thinking_signature,
@@ -22,9 +25,12 @@ use super::{
     types::{CopilotOptimizerConfig, OptimizerConfig, ProxyStatus, RectifierConfig},
     ProxyError,
 };
+#[cfg(feature = "gui")]
 use crate::commands::{CodexOAuthState, CopilotAuthState, XaiOAuthState};
+use crate::proxy::providers::codex_oauth_auth::CodexOAuthManager;
 use crate::proxy::providers::copilot_auth::CopilotAuthManager;
 use crate::proxy::providers::xai_oauth_auth::XaiOAuthManager;
+use crate::proxy::AppHandle;
 use crate::{
     app_config::AppType,
     provider::{LocalProxyRequestOverrides, Provider},
@@ -34,6 +40,8 @@ use futures::StreamExt;
 use http::Extensions;
 use serde_json::Value;
 use std::sync::Arc;
+use std::time::Instant;
+#[cfg(feature = "gui")]
 use tauri::Manager;
 use tokio::sync::RwLock;
 
@@ -146,13 +154,17 @@ pub struct RequestForwarder {
     /// 故障转移切换管理器
     failover_manager: Arc<FailoverSwitchManager>,
     /// AppHandle，用于发射事件和更新托盘
-    app_handle: Option<tauri::AppHandle>,
+    app_handle: Option<AppHandle>,
     /// 请求开始时的"当前供应商 ID"（用于判断是否需要同步 UI/托盘）
     current_provider_id_at_start: String,
     /// 代理会话 ID（用于 Gemini Native shadow replay）
     session_id: String,
     /// Session ID 是否由客户端提供；生成值不能作为上游缓存身份。
     session_client_provided: bool,
+    /// 本次请求由模型路由规则选定供应商：成功后不同步"当前供应商"、不绑定会话
+    model_routed: bool,
+    /// Prometheus 指标（记录故障转移）
+    metrics: Arc<ProxyMetrics>,
     /// 整流器配置
     rectifier_config: RectifierConfig,
     /// 优化器配置
@@ -226,7 +238,7 @@ impl RequestForwarder {
         gemini_shadow: Arc<GeminiShadowStore>,
         codex_chat_history: Arc<CodexChatHistoryStore>,
         failover_manager: Arc<FailoverSwitchManager>,
-        app_handle: Option<tauri::AppHandle>,
+        app_handle: Option<AppHandle>,
         current_provider_id_at_start: String,
         session_id: String,
         session_client_provided: bool,
@@ -251,6 +263,8 @@ impl RequestForwarder {
             current_provider_id_at_start,
             session_id,
             session_client_provided,
+            model_routed: false,
+            metrics: Arc::default(),
             rectifier_config,
             optimizer_config,
             copilot_optimizer_config,
@@ -262,12 +276,36 @@ impl RequestForwarder {
         }
     }
 
+    /// 标记本次请求的供应商由模型路由规则（或抓包重放）指定
+    pub fn with_model_route(mut self, routed: bool) -> Self {
+        self.model_routed = routed;
+        self
+    }
+
+    /// 使用代理共享的指标注册表
+    pub fn with_metrics(mut self, metrics: Arc<ProxyMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 实际使用的供应商与请求开始时的当前供应商不同（即发生了故障转移），
+    /// 需要把"当前供应商"同步过去；模型路由规则分流的请求不改变全局选择
+    fn should_sync_current_provider(&self, provider_id: &str) -> bool {
+        !self.model_routed && self.current_provider_id_at_start != provider_id
+    }
+
     async fn record_success_result(
         &self,
         provider_id: &str,
         app_type: &str,
         used_half_open_permit: bool,
     ) {
+        if self.session_client_provided && !self.model_routed {
+            self.router
+                .bind_session(app_type, &self.session_id, provider_id)
+                .await;
+        }
+
         if used_half_open_permit {
             if let Err(e) = self
                 .router
@@ -437,6 +475,7 @@ impl RequestForwarder {
         let mut last_error = None;
         let mut last_provider = None;
         let mut attempted_providers = 0usize;
+        let mut previous_provider: Option<&str> = None;
 
         // 单 Provider 场景下跳过熔断器检查（故障转移关闭时）
         let bypass_circuit_breaker = providers.len() == 1;
@@ -493,6 +532,10 @@ impl RequestForwarder {
                 };
 
             attempted_providers += 1;
+            if let Some(previous) = previous_provider.replace(provider.id.as_str()) {
+                self.metrics
+                    .record_failover(app_type_str, previous, &provider.id);
+            }
 
             // 更新状态中的当前 Provider 信息（per-attempt 维度的标识）
             //
@@ -505,20 +548,72 @@ impl RequestForwarder {
                 status.current_provider_id = Some(provider.id.clone());
             }
 
-            // 转发请求（每个 Provider 只尝试一次，重试由客户端控制）
-            match self
-                .forward(
-                    app_type,
-                    &method,
-                    provider,
-                    endpoint,
-                    &provider_body,
-                    &headers,
-                    &extensions,
-                    adapter.as_ref(),
-                )
-                .await
-            {
+            // 端点镜像：供应商登记了自定义端点时，按健康状态和延迟排序依次尝试；
+            // 未登记时 active_base_url 为 None，由 forward() 自行提取 base_url
+            let mirrors = match adapter.extract_base_url(provider) {
+                Ok(primary) => {
+                    self.router
+                        .endpoint_candidates(app_type_str, provider, &primary)
+                        .await
+                }
+                Err(_) => Vec::new(),
+            };
+            let mut mirror_index = 0usize;
+            let mut active_base_url = (mirrors.len() > 1).then(|| mirrors[0].clone());
+
+            // 转发请求（每个 Provider 只尝试一次，重试由客户端控制；
+            // 连接失败/超时时先切换到同一供应商的下一个镜像）
+            let forward_result = loop {
+                let started = Instant::now();
+                let result = self
+                    .forward(
+                        app_type,
+                        &method,
+                        provider,
+                        endpoint,
+                        &provider_body,
+                        &headers,
+                        &extensions,
+                        adapter.as_ref(),
+                        active_base_url.as_deref(),
+                    )
+                    .await;
+
+                let Some(url) = active_base_url.as_deref() else {
+                    break result;
+                };
+                match &result {
+                    Ok(_) => {
+                        self.router
+                            .record_endpoint_success(
+                                app_type_str,
+                                &provider.id,
+                                url,
+                                started.elapsed(),
+                            )
+                            .await;
+                    }
+                    Err(ProxyError::ForwardFailed(_) | ProxyError::Timeout(_)) => {
+                        self.router
+                            .record_endpoint_failure(app_type_str, &provider.id, url)
+                            .await;
+                        if mirror_index + 1 < mirrors.len() {
+                            mirror_index += 1;
+                            log::warn!(
+                                "[{app_type_str}] 供应商 {} 端点 {url} 请求失败，切换到镜像 {}",
+                                provider.name,
+                                mirrors[mirror_index]
+                            );
+                            active_base_url = Some(mirrors[mirror_index].clone());
+                            continue;
+                        }
+                    }
+                    Err(_) => {}
+                }
+                break result;
+            };
+
+            match forward_result {
                 Ok((response, claude_api_format, outbound_model)) => {
                     // 成功：普通闭合熔断状态异步记录，避免阻塞流式首包返回；
                     // HalfOpen 探测仍同步等待，保证 permit 与熔断状态及时释放。
@@ -539,8 +634,7 @@ impl RequestForwarder {
                         let mut status = self.status.write().await;
                         status.success_requests += 1;
                         status.last_error = None;
-                        let should_switch =
-                            self.current_provider_id_at_start.as_str() != provider.id.as_str();
+                        let should_switch = self.should_sync_current_provider(&provider.id);
                         if should_switch {
                             status.failover_count += 1;
 
@@ -594,6 +688,7 @@ impl RequestForwarder {
 
                         if replaced_images > 0 {
                             let _ = std::mem::replace(&mut media_rectifier_retried, true);
+                            record_rectifier(&extensions, "media_unsupported_image");
                             let model = media_body
                                 .get("model")
                                 .and_then(Value::as_str)
@@ -615,6 +710,7 @@ impl RequestForwarder {
                                     &headers,
                                     &extensions,
                                     adapter.as_ref(),
+                                    active_base_url.as_deref(),
                                 )
                                 .await
                             {
@@ -643,8 +739,7 @@ impl RequestForwarder {
                                         status.success_requests += 1;
                                         status.last_error = None;
                                         let should_switch =
-                                            self.current_provider_id_at_start.as_str()
-                                                != provider.id.as_str();
+                                            self.should_sync_current_provider(&provider.id);
                                         if should_switch {
                                             status.failover_count += 1;
                                             let fm = self.failover_manager.clone();
@@ -749,6 +844,7 @@ impl RequestForwarder {
 
                                 // 标记已重试（当前逻辑下重试后必定 return，保留标记以备将来扩展）
                                 let _ = std::mem::replace(&mut rectifier_retried, true);
+                                record_rectifier(&extensions, "thinking_signature");
 
                                 // 使用同一供应商重试（不计入熔断器）
                                 match self
@@ -761,6 +857,7 @@ impl RequestForwarder {
                                         &headers,
                                         &extensions,
                                         adapter.as_ref(),
+                                        active_base_url.as_deref(),
                                     )
                                     .await
                                 {
@@ -789,8 +886,7 @@ impl RequestForwarder {
                                             status.success_requests += 1;
                                             status.last_error = None;
                                             let should_switch =
-                                                self.current_provider_id_at_start.as_str()
-                                                    != provider.id.as_str();
+                                                self.should_sync_current_provider(&provider.id);
                                             if should_switch {
                                                 status.failover_count += 1;
 
@@ -915,6 +1011,7 @@ impl RequestForwarder {
                             );
 
                             let _ = std::mem::replace(&mut budget_rectifier_retried, true);
+                            record_rectifier(&extensions, "thinking_budget");
 
                             // 使用同一供应商重试（不计入熔断器）
                             match self
@@ -927,6 +1024,7 @@ impl RequestForwarder {
                                     &headers,
                                     &extensions,
                                     adapter.as_ref(),
+                                    active_base_url.as_deref(),
                                 )
                                 .await
                             {
@@ -953,8 +1051,7 @@ impl RequestForwarder {
                                         status.success_requests += 1;
                                         status.last_error = None;
                                         let should_switch =
-                                            self.current_provider_id_at_start.as_str()
-                                                != provider.id.as_str();
+                                            self.should_sync_current_provider(&provider.id);
                                         if should_switch {
                                             status.failover_count += 1;
                                             let fm = self.failover_manager.clone();
@@ -1140,6 +1237,7 @@ impl RequestForwarder {
     ///
     /// 成功时返回 `(response, claude_api_format, outbound_model)`，其中
     /// `outbound_model` 是最终发往上游的模型名（所有映射/改写之后）。
+    /// 请求带 OpenTelemetry trace 时，每次发送记为一个子 span。
     #[allow(clippy::too_many_arguments)]
     async fn forward(
         &self,
@@ -1151,9 +1249,52 @@ impl RequestForwarder {
         headers: &axum::http::HeaderMap,
         extensions: &Extensions,
         adapter: &dyn ProviderAdapter,
+        base_url_override: Option<&str>,
     ) -> Result<(ProxyResponse, Option<String>, Option<String>), ProxyError> {
-        // 使用适配器提取 base_url
-        let mut base_url = adapter.extract_base_url(provider)?;
+        let trace = extensions.get::<RequestTrace>();
+        let attempt = trace.map(|t| t.start_attempt(provider, base_url_override));
+        let result = self
+            .forward_once(
+                app_type,
+                method,
+                provider,
+                endpoint,
+                body,
+                headers,
+                extensions,
+                adapter,
+                base_url_override,
+            )
+            .await;
+        if let (Some(trace), Some(attempt)) = (trace, attempt) {
+            let outcome = match &result {
+                Ok((response, _, _)) => Ok(response.status().as_u16()),
+                Err(ProxyError::UpstreamError { status, .. }) => Ok(*status),
+                Err(e) => Err(e.to_string()),
+            };
+            trace.finish_attempt(attempt, outcome);
+        }
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn forward_once(
+        &self,
+        app_type: &AppType,
+        method: &http::Method,
+        provider: &Provider,
+        endpoint: &str,
+        body: &Value,
+        headers: &axum::http::HeaderMap,
+        extensions: &Extensions,
+        adapter: &dyn ProviderAdapter,
+        base_url_override: Option<&str>,
+    ) -> Result<(ProxyResponse, Option<String>, Option<String>), ProxyError> {
+        // 使用调用方选定的端点镜像，未指定时由适配器提取 base_url
+        let mut base_url = match base_url_override {
+            Some(url) => url.to_string(),
+            None => adapter.extract_base_url(provider)?,
+        };
 
         let is_full_url = provider
             .meta
@@ -1335,9 +1476,8 @@ impl RequestForwarder {
         // GitHub Copilot 动态 endpoint 路由
         // 从 CopilotAuthManager 获取缓存的 API endpoint（支持企业版等非默认 endpoint）
         if is_copilot && !is_full_url {
-            if let Some(app_handle) = &self.app_handle {
-                let copilot_state = app_handle.state::<CopilotAuthState>();
-                let copilot_auth = copilot_state.0.read().await;
+            if let Some(copilot) = self.copilot_auth() {
+                let copilot_auth = copilot.read().await;
 
                 // 从 provider.meta 获取关联的 GitHub 账号 ID
                 let account_id = provider
@@ -1657,10 +1797,9 @@ impl RequestForwarder {
         let mut auth_headers = if let Some(mut auth) = adapter.extract_auth(provider) {
             // GitHub Copilot 特殊处理：从 CopilotAuthManager 获取真实 token
             if auth.strategy == AuthStrategy::GitHubCopilot {
-                if let Some(app_handle) = &self.app_handle {
-                    let copilot_state = app_handle.state::<CopilotAuthState>();
+                if let Some(copilot) = self.copilot_auth() {
                     let copilot_auth: tokio::sync::RwLockReadGuard<'_, CopilotAuthManager> =
-                        copilot_state.0.read().await;
+                        copilot.read().await;
 
                     // 从 provider.meta 获取关联的 GitHub 账号 ID（多账号支持）
                     let account_id = provider
@@ -1708,10 +1847,7 @@ impl RequestForwarder {
 
             // Codex OAuth 特殊处理：从 CodexOAuthManager 获取真实 access_token
             if auth.strategy == AuthStrategy::CodexOAuth {
-                if let Some(app_handle) = &self.app_handle {
-                    let codex_state = app_handle.state::<CodexOAuthState>();
-                    let codex_auth = &codex_state.0;
-
+                if let Some(codex_auth) = self.codex_oauth() {
                     // 从 provider.meta 获取关联的 ChatGPT 账号 ID
                     let account_id = provider
                         .meta
@@ -1762,10 +1898,9 @@ impl RequestForwarder {
             // sending the request. Invalid refresh credentials are persisted as
             // requiring re-authentication by the manager.
             if auth.strategy == AuthStrategy::XaiOAuth {
-                if let Some(app_handle) = &self.app_handle {
-                    let xai_state = app_handle.state::<XaiOAuthState>();
+                if let Some(xai) = self.xai_oauth() {
                     let xai_auth: tokio::sync::RwLockReadGuard<'_, XaiOAuthManager> =
-                        xai_state.0.read().await;
+                        xai.read().await;
                     let account_id = provider
                         .meta
                         .as_ref()
@@ -1993,6 +2128,7 @@ impl RequestForwarder {
                     | "x-b3-sampled"
                     | "traceparent"
                     | "tracestate"
+                    | super::capture::REPLAY_HEADER
             ) {
                 continue;
             }
@@ -2231,6 +2367,19 @@ impl RequestForwarder {
             short_value_hash(Some(&filtered_body))
         );
 
+        // 抓包：记录格式转换、认证注入之后真正发往上游的请求
+        let capture = extensions.get::<CaptureHandle>();
+        if let Some(capture) = capture {
+            capture.record_upstream_request(
+                app_type.as_str(),
+                provider,
+                method,
+                &crate::redact_url_for_log_with_secrets(&url, &log_secrets),
+                &ordered_headers,
+                &body_bytes,
+            );
+        }
+
         // 确定超时
         let timeout = if self.non_streaming_timeout.is_zero() {
             std::time::Duration::from_secs(600) // 默认 600 秒
@@ -2343,9 +2492,13 @@ impl RequestForwarder {
                     response = self.validate_responses_stream_start(response).await?;
                 }
             }
+            if let Some(capture) = capture {
+                response = capture.tap_upstream_response(response);
+            }
             Ok((response, resolved_claude_api_format, outbound_model))
         } else {
             let status_code = status.as_u16();
+            let error_headers = capture.map(|_| response.headers().clone());
             // 错误响应同样可能被上游压缩（content-encoding）。reqwest 未启用任何
             // 自动解压 feature，这里拿到的是原始字节；不解压的话，压缩过的错误体会
             // 在 from_utf8 处变成非 UTF-8 而被丢弃，隐藏掉上游的限流/鉴权等详情。
@@ -2362,6 +2515,9 @@ impl RequestForwarder {
                 }
                 None => raw.to_vec(),
             };
+            if let (Some(capture), Some(headers)) = (capture, &error_headers) {
+                capture.record_upstream_error(status, headers, &decoded);
+            }
             let body_text = String::from_utf8(decoded).ok();
 
             Err(ProxyError::UpstreamError {
@@ -2615,11 +2771,10 @@ impl RequestForwarder {
         };
         let model_id = model_id.to_string();
 
-        let Some(app_handle) = &self.app_handle else {
+        let Some(copilot) = self.copilot_auth() else {
             return;
         };
-        let copilot_state = app_handle.state::<CopilotAuthState>();
-        let copilot_auth = copilot_state.0.read().await;
+        let copilot_auth = copilot.read().await;
         let account_id = provider
             .meta
             .as_ref()
@@ -2647,13 +2802,12 @@ impl RequestForwarder {
     }
 
     async fn is_copilot_openai_vendor_model(&self, provider: &Provider, model_id: &str) -> bool {
-        let Some(app_handle) = &self.app_handle else {
+        let Some(copilot) = self.copilot_auth() else {
             log::debug!("[Copilot] AppHandle unavailable, fallback to chat/completions");
             return false;
         };
 
-        let copilot_state = app_handle.state::<CopilotAuthState>();
-        let copilot_auth = copilot_state.0.read().await;
+        let copilot_auth = copilot.read().await;
         let account_id = provider
             .meta
             .as_ref()
@@ -2685,6 +2839,46 @@ impl RequestForwarder {
         }
     }
 
+    /// 托管账号的认证管理器由桌面端注册在 AppHandle 上；无界面构建中不可用
+    fn copilot_auth(&self) -> Option<Arc<RwLock<CopilotAuthManager>>> {
+        #[cfg(feature = "gui")]
+        {
+            self.app_handle
+                .as_ref()
+                .map(|app| app.state::<CopilotAuthState>().0.clone())
+        }
+        #[cfg(not(feature = "gui"))]
+        {
+            None
+        }
+    }
+
+    fn codex_oauth(&self) -> Option<Arc<CodexOAuthManager>> {
+        #[cfg(feature = "gui")]
+        {
+            self.app_handle
+                .as_ref()
+                .map(|app| app.state::<CodexOAuthState>().0.clone())
+        }
+        #[cfg(not(feature = "gui"))]
+        {
+            None
+        }
+    }
+
+    fn xai_oauth(&self) -> Option<Arc<RwLock<XaiOAuthManager>>> {
+        #[cfg(feature = "gui")]
+        {
+            self.app_handle
+                .as_ref()
+                .map(|app| app.state::<XaiOAuthState>().0.clone())
+        }
+        #[cfg(not(feature = "gui"))]
+        {
+            None
+        }
+    }
+
     fn categorize_proxy_error(&self, error: &ProxyError, provider: &Provider) -> ErrorCategory {
         // Authentication belongs to the Codex client for an official route.
         // Every retry would reuse the selected account's inbound Authorization
@@ -2777,6 +2971,13 @@ fn build_retryable_failure_log(
     }
 }
 
+/// 整流后同一供应商重发前，把整流动作记到请求的 trace 上
+fn record_rectifier(extensions: &Extensions, action: &str) {
+    if let Some(trace) = extensions.get::<RequestTrace>() {
+        trace.record_rectifier(action);
+    }
+}
+
 fn build_terminal_failure_log(
     attempted_providers: usize,
     total_providers: usize,
@@ -3719,6 +3920,8 @@ mod tests {
             current_provider_id_at_start: String::new(),
             session_id: String::new(),
             session_client_provided: false,
+            model_routed: false,
+            metrics: Arc::default(),
             rectifier_config: RectifierConfig::default(),
             optimizer_config: OptimizerConfig::default(),
             copilot_optimizer_config: CopilotOptimizerConfig::default(),
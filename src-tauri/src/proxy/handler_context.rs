@@ -8,6 +8,8 @@ use crate::proxy::{
     extract_session_id,
     forwarder::RequestForwarder,
     server::ProxyState,
+    session::SessionIdSource,
+    telemetry::RequestTrace,
     types::{AppProxyConfig, CopilotOptimizerConfig, OptimizerConfig, RectifierConfig},
     ProxyError,
 };
@@ -31,6 +33,7 @@ pub struct StreamingTimeoutConfig {
 /// - 选中的 Provider 列表（用于故障转移）
 /// - 请求模型名称
 /// - 日志标签
+/// - 命中的模型路由规则
 /// - Session ID（用于日志关联）
 pub struct RequestContext {
     /// 请求开始时间
@@ -48,6 +51,10 @@ pub struct RequestContext {
     pub current_provider_id: String,
     /// 请求中的模型名称
     pub request_model: String,
+    /// 命中的模型路由规则；命中时不同步"当前供应商"，也不绑定会话
+    pub model_route: Option<String>,
+    /// 抓包重放指定了供应商：只尝试该供应商，同样不同步"当前供应商"、不绑定会话
+    pub pinned_provider: bool,
     /// 实际发往上游的模型名（路由接管/模型映射后的真值，forward 成功后回填）。
     ///
     /// usage 归因的兜底顺序：上游响应回显 → outbound_model → request_model。
@@ -64,6 +71,10 @@ pub struct RequestContext {
     pub session_id: String,
     /// Session ID 是否由客户端提供。生成的 UUID 不能作为上游缓存 key，否则每个请求都会换 key。
     pub session_client_provided: bool,
+    /// Session ID 的提取来源
+    pub session_source: SessionIdSource,
+    /// OpenTelemetry trace（未设置采集端点时为 None）
+    pub trace: Option<RequestTrace>,
     /// 整流器配置
     pub rectifier_config: RectifierConfig,
     /// 优化器配置
@@ -92,6 +103,63 @@ impl RequestContext {
         app_type: AppType,
         tag: &'static str,
         app_type_str: &'static str,
+    ) -> Result<Self, ProxyError> {
+        // 从请求体提取模型名称
+        let request_model = body
+            .get("model")
+            .and_then(|m| m.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        Self::with_request_model(
+            state,
+            body,
+            headers,
+            app_type,
+            tag,
+            app_type_str,
+            request_model,
+        )
+        .await
+    }
+
+    /// 创建请求上下文（Gemini 专用：模型名称在 URI 中）
+    ///
+    /// Gemini API 的模型名称在 URI 中，格式如：
+    /// `/v1beta/models/gemini-pro:generateContent`
+    pub async fn new_with_model_from_uri(
+        state: &ProxyState,
+        body: &serde_json::Value,
+        headers: &HeaderMap,
+        uri: &axum::http::Uri,
+        app_type: AppType,
+        tag: &'static str,
+        app_type_str: &'static str,
+    ) -> Result<Self, ProxyError> {
+        // 用 path() 而不是 path_and_query()：模型名必须从路径段中解析，
+        // 否则 GET /v1beta/models/<id>?key=... 会把 query 拼到 request_model 上。
+        let request_model =
+            extract_gemini_model_from_path(uri.path()).unwrap_or_else(|| "unknown".to_string());
+        Self::with_request_model(
+            state,
+            body,
+            headers,
+            app_type,
+            tag,
+            app_type_str,
+            request_model,
+        )
+        .await
+    }
+
+    /// 模型名称确定后创建上下文：模型路由规则需要在选择 Provider 之前知道模型
+    async fn with_request_model(
+        state: &ProxyState,
+        body: &serde_json::Value,
+        headers: &HeaderMap,
+        app_type: AppType,
+        tag: &'static str,
+        app_type_str: &'static str,
+        request_model: String,
     ) -> Result<Self, ProxyError> {
         let start_time = Instant::now();
 
@@ -110,13 +178,6 @@ impl RequestContext {
         let current_provider_id =
             crate::settings::get_current_provider(&app_type).unwrap_or_default();
 
-        // 从请求体提取模型名称
-        let request_model = body
-            .get("model")
-            .and_then(|m| m.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-
         // 提取 Session ID
         let session_result = extract_session_id(headers, body, app_type_str);
         let session_id = session_result.session_id.clone();
@@ -129,19 +190,54 @@ impl RequestContext {
             session_result.client_provided
         );
 
+        // 抓包重放指定的供应商跳过路由与故障转移
+        let pinned = match state
+            .replay_pins
+            .take(headers)
+            .and_then(|pin| pin.provider_id)
+        {
+            Some(provider_id) => Some(
+                state
+                    .db
+                    .get_provider_by_id(&provider_id, app_type_str)
+                    .map_err(|e| ProxyError::DatabaseError(e.to_string()))?
+                    .ok_or(ProxyError::NoAvailableProvider)?,
+            ),
+            None => None,
+        };
+        let pinned_provider = pinned.is_some();
+
         // 使用共享的 ProviderRouter 选择 Provider（熔断器状态跨请求保持）
         // 注意：只在这里调用一次，结果传递给 forwarder，避免重复消耗 HalfOpen 名额
-        let providers = state
-            .provider_router
-            .select_providers(app_type_str)
-            .await
-            .map_err(|e| match e {
-                crate::error::AppError::AllProvidersCircuitOpen => {
-                    ProxyError::AllProvidersCircuitOpen
-                }
-                crate::error::AppError::NoProvidersConfigured => ProxyError::NoProvidersConfigured,
-                _ => ProxyError::DatabaseError(e.to_string()),
-            })?;
+        // 模型路由规则优先；生成的 Session ID 每个请求都不同，不参与会话粘滞
+        let (providers, model_route) = match pinned {
+            Some(provider) => (vec![provider], None),
+            None => {
+                let selection = state
+                    .provider_router
+                    .select_providers_for_model(
+                        app_type_str,
+                        &request_model,
+                        session_result
+                            .client_provided
+                            .then_some(session_id.as_str()),
+                    )
+                    .await
+                    .map_err(|e| match e {
+                        crate::error::AppError::AllProvidersCircuitOpen => {
+                            ProxyError::AllProvidersCircuitOpen
+                        }
+                        crate::error::AppError::NoProvidersConfigured => {
+                            ProxyError::NoProvidersConfigured
+                        }
+                        crate::error::AppError::ProviderBudgetExceeded(msg) => {
+                            ProxyError::BudgetExceeded(msg)
+                        }
+                        _ => ProxyError::DatabaseError(e.to_string()),
+                    })?;
+                (selection.providers, selection.model_route)
+            }
+        };
 
         let provider = providers
             .first()
@@ -149,10 +245,11 @@ impl RequestContext {
             .ok_or(ProxyError::NoAvailableProvider)?;
 
         log::debug!(
-            "[{}] Provider: {}, model: {}, failover chain: {} providers, session: {}",
+            "[{}] Provider: {}, model: {}, route: {}, failover chain: {} providers, session: {}",
             tag,
             provider.name,
             request_model,
+            model_route.as_deref().unwrap_or("default"),
             providers.len(),
             session_id
         );
@@ -164,31 +261,37 @@ impl RequestContext {
             providers,
             current_provider_id,
             request_model,
+            model_route,
+            pinned_provider,
             outbound_model: None,
             tag,
             app_type_str,
             app_type,
             session_id,
             session_client_provided: session_result.client_provided,
+            session_source: session_result.source,
+            trace: None,
             rectifier_config,
             optimizer_config,
             copilot_optimizer_config,
         })
     }
 
-    /// 从 URI 提取模型名称（Gemini 专用）
-    ///
-    /// Gemini API 的模型名称在 URI 中，格式如：
-    /// `/v1beta/models/gemini-pro:generateContent`
-    pub fn with_model_from_uri(mut self, uri: &axum::http::Uri) -> Self {
-        // 用 path() 而不是 path_and_query()：模型名必须从路径段中解析，
-        // 否则 GET /v1beta/models/<id>?key=... 会把 query 拼到 request_model 上。
-        let endpoint = uri.path();
-
-        self.request_model =
-            extract_gemini_model_from_path(endpoint).unwrap_or_else(|| "unknown".to_string());
-
-        self
+    /// 关联 telemetry 中间件创建的 trace，并记录路由结果
+    pub fn attach_trace(&mut self, extensions: &axum::http::Extensions) {
+        let Some(trace) = extensions.get::<RequestTrace>() else {
+            return;
+        };
+        trace.record_request(
+            self.app_type_str,
+            &self.request_model,
+            &self.providers,
+            self.model_route.as_deref(),
+            &self.session_id,
+            self.session_source,
+            self.session_client_provided,
+        );
+        self.trace = Some(trace.clone());
     }
 
     /// 创建 RequestForwarder
@@ -242,6 +345,8 @@ impl RequestContext {
             self.copilot_optimizer_config.clone(),
             max_retries,
         )
+        .with_model_route(self.model_route.is_some() || self.pinned_provider)
+        .with_metrics(state.metrics.clone())
     }
 
     /// 获取 Provider 列表（用于故障转移）
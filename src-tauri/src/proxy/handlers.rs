@@ -16,6 +16,7 @@ use super::{
         CODEX_PARSER_CONFIG, GEMINI_PARSER_CONFIG, OPENAI_PARSER_CONFIG,
     },
     handler_context::RequestContext,
+    model_catalog::{render_model_list, ModelListFormat},
     providers::{
         codex_chat_common::extract_reasoning_field_text,
         codex_chat_history::record_responses_sse_stream,
@@ -41,6 +42,7 @@ use super::{
     },
     server::ProxyState,
     sse::{strip_sse_field, take_sse_block},
+    telemetry::RequestTrace,
     types::*,
     usage::parser::TokenUsage,
     ProxyError,
@@ -74,17 +76,130 @@ pub async fn get_status(State(state): State<ProxyState>) -> Result<Json<ProxySta
     Ok(Json(status))
 }
 
-/// GET /v1/models — Codex model list (reachability check)
+/// Prometheus 指标（文本格式）
+pub async fn get_metrics(State(state): State<ProxyState>) -> axum::response::Response {
+    let circuits = state.provider_router.circuit_breaker_snapshot().await;
+    let status = state.status.read().await;
+    let uptime_seconds = state
+        .start_time
+        .read()
+        .await
+        .map(|start| start.elapsed().as_secs())
+        .unwrap_or(status.uptime_seconds);
+    let body = state
+        .metrics
+        .render(status.active_connections, uptime_seconds, &circuits);
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            super::metrics::CONTENT_TYPE,
+        )],
+        body,
+    )
+        .into_response()
+}
+
+/// MCP 网关：未开启网关模式时按不存在处理，避免向外暴露本地 MCP 服务器
+pub async fn handle_mcp_gateway(
+    State(state): State<ProxyState>,
+    axum::extract::Path(app): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+    body: Bytes,
+) -> axum::response::Response {
+    if !crate::settings::get_mcp_gateway_enabled() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let token = match crate::proxy::mcp_gateway::gateway_token() {
+        Ok(token) => token,
+        Err(e) => {
+            log::error!("[MCP-Gateway] 读取访问令牌失败: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let listen_address = state.config.read().await.listen_address.clone();
+    if let Err(status) = crate::proxy::mcp_gateway::authorize(&headers, &listen_address, &token) {
+        log::warn!("[MCP-Gateway] 拒绝请求 /mcp/{app}: {status}");
+        return status.into_response();
+    }
+    match state.mcp_gateway.handle(&state.db, &app, &body).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// GET /v1/models — 聚合模型列表
 ///
-/// Codex CLI probes this endpoint at startup and deserializes the response as a
-/// catalog with a top-level `models` field.  Return the cc-switch–managed model
-/// catalog file directly so the format always matches what the current version
-/// of Codex expects.
+/// - Codex CLI 启动时带 `client_version` 查询参数探测该端点，并按顶层 `models`
+///   字段的目录格式反序列化，这种请求继续返回 cc-switch 管理的目录文件
+/// - 带 `anthropic-version` 头的请求（Claude Code 等）返回 Claude 供应商的
+///   聚合列表（Anthropic 格式）
+/// - 其余请求返回 Codex 供应商的聚合列表（OpenAI 格式）
+pub async fn handle_models(
+    State(state): State<ProxyState>,
+    headers: axum::http::HeaderMap,
+    uri: axum::http::Uri,
+) -> Result<Json<Value>, ProxyError> {
+    if headers.contains_key("anthropic-version") {
+        return Ok(aggregated_models(&state, AppType::Claude, ModelListFormat::Anthropic).await);
+    }
+    Ok(codex_models(&state, &uri).await)
+}
+
+/// GET /claude/v1/models — Claude 供应商的聚合模型列表（Anthropic 格式）
+pub async fn handle_claude_models(
+    State(state): State<ProxyState>,
+) -> Result<Json<Value>, ProxyError> {
+    Ok(aggregated_models(&state, AppType::Claude, ModelListFormat::Anthropic).await)
+}
+
+/// GET /codex/v1/models — 默认返回 cc-switch 管理的 Codex 模型目录，
+/// 没有生效的目录时退回 Codex 供应商的聚合模型列表（OpenAI 格式）
+pub async fn handle_codex_models(
+    State(state): State<ProxyState>,
+    uri: axum::http::Uri,
+) -> Result<Json<Value>, ProxyError> {
+    Ok(codex_models(&state, &uri).await)
+}
+
+async fn codex_models(state: &ProxyState, uri: &axum::http::Uri) -> Json<Value> {
+    match codex_catalog_response(uri) {
+        Some(catalog) => Json(catalog),
+        None => aggregated_models(state, AppType::Codex, ModelListFormat::OpenAi).await,
+    }
+}
+
+async fn aggregated_models(
+    state: &ProxyState,
+    app_type: AppType,
+    format: ModelListFormat,
+) -> Json<Value> {
+    let models = state.model_catalog.list(&state.db, &app_type).await;
+    Json(render_model_list(&models, format))
+}
+
+/// Codex 路由的目录响应：目录生效时总是返回目录；没有目录时，只有带
+/// `client_version` 的 Codex CLI 探测请求得到空目录（保持其期望的格式），
+/// 其余请求返回 `None` 以退回聚合列表。
+fn codex_catalog_response(uri: &axum::http::Uri) -> Option<Value> {
+    active_codex_model_catalog()
+        .or_else(|| is_codex_catalog_probe(uri).then(|| json!({"models": []})))
+}
+
+fn is_codex_catalog_probe(uri: &axum::http::Uri) -> bool {
+    uri.query().is_some_and(|query| {
+        query
+            .split('&')
+            .any(|pair| pair.split('=').next() == Some("client_version"))
+    })
+}
+
+/// Codex CLI 的模型目录：直接返回 cc-switch 管理的目录文件，保证格式与当前
+/// Codex 版本期望的一致。
 ///
 /// Only serves the catalog when the live config.toml still references the
 /// cc-switch–owned `model_catalog_json`, using the same path ownership rules as
-/// Codex live-setting import.
-pub async fn handle_models() -> Result<Json<Value>, ProxyError> {
+/// Codex live-setting import; returns `None` otherwise.
+fn active_codex_model_catalog() -> Option<Value> {
     let config_dir = crate::codex_config::get_codex_config_dir();
     let active_catalog_path = match crate::codex_config::read_codex_config_text() {
         Ok(config_text) => {
@@ -93,25 +208,19 @@ pub async fn handle_models() -> Result<Json<Value>, ProxyError> {
         Err(_) => None,
     };
 
-    let catalog = if let Some(catalog_path) =
-        active_catalog_path.as_ref().filter(|path| path.exists())
-    {
-        match crate::codex_config::read_codex_model_catalog_text(catalog_path) {
-            Ok(text) => serde_json::from_str(&text).unwrap_or(json!({"models": []})),
-            Err(error) => {
-                log::warn!("[models] 拒绝读取越界或过大的目录文件: {error}");
-                json!({"models": []})
-            }
-        }
-    } else {
-        if active_catalog_path.is_none() {
-            log::debug!(
-                "[models] stale guard: catalog not served (model_catalog_json not set to cc-switch catalog)"
-            );
-        }
-        json!({"models": []})
+    let Some(catalog_path) = active_catalog_path.filter(|path| path.exists()) else {
+        log::debug!(
+            "[models] stale guard: catalog not served (model_catalog_json not set to cc-switch catalog)"
+        );
+        return None;
     };
-    Ok(Json(catalog))
+    match crate::codex_config::read_codex_model_catalog_text(&catalog_path) {
+        Ok(text) => Some(serde_json::from_str(&text).unwrap_or(json!({"models": []}))),
+        Err(error) => {
+            log::warn!("[models] 拒绝读取越界或过大的目录文件: {error}");
+            Some(json!({"models": []}))
+        }
+    }
 }
 
 // ============================================================================
@@ -185,6 +294,7 @@ async fn handle_messages_for_app(
 
     let mut ctx =
         RequestContext::new(&state, &body, &headers, app_type.clone(), tag, app_type_str).await?;
+    ctx.attach_trace(&extensions);
 
     let raw_endpoint = uri
         .path_and_query()
@@ -304,6 +414,7 @@ struct ClaudeUsageLog {
     app_type: &'static str,
     provider_id: String,
     session_id: String,
+    trace: Option<RequestTrace>,
     usage: TokenUsage,
     latency_ms: u64,
     status_code: u16,
@@ -337,6 +448,7 @@ fn prepare_claude_usage_log(
         app_type: ctx.app_type_str,
         provider_id: ctx.provider.id.clone(),
         session_id: ctx.session_id.clone(),
+        trace: ctx.trace.clone(),
         usage,
         latency_ms: ctx.latency_ms(),
         status_code,
@@ -358,6 +470,7 @@ async fn write_claude_usage_log(state: &ProxyState, log: ClaudeUsageLog) {
         log.is_streaming,
         log.status_code,
         Some(log.session_id),
+        log.trace,
     )
     .await;
 }
@@ -464,6 +577,7 @@ async fn handle_claude_transform(
             let status_code = status.as_u16();
             let start_time = ctx.start_time;
             let session_id = ctx.session_id.clone();
+            let trace = ctx.trace.clone();
             // 用 ctx 的 app_type：Claude Desktop 网关也走此转换路径，硬编码
             // "claude" 会把 claude-desktop 的行错记到 claude 名下
             let app_type_str = ctx.app_type_str;
@@ -482,6 +596,7 @@ async fn handle_claude_transform(
                         let state = state.clone();
                         let provider_id = provider_id.clone();
                         let session_id = session_id.clone();
+                        let trace = trace.clone();
                         let request_model = request_model.clone();
                         let outbound_model = fallback_model.clone();
 
@@ -499,6 +614,7 @@ async fn handle_claude_transform(
                                 true,
                                 status_code,
                                 Some(session_id),
+                                trace,
                             )
                             .await;
                         });
@@ -778,6 +894,7 @@ pub async fn handle_chat_completions(
 
     let mut ctx =
         RequestContext::new(&state, &body, &headers, AppType::Codex, "Codex", "codex").await?;
+    ctx.attach_trace(&extensions);
     let endpoint = endpoint_with_query(&uri, "/chat/completions");
 
     let is_stream = body
@@ -868,6 +985,7 @@ async fn handle_responses_for_app(
 
     let mut ctx =
         RequestContext::new(&state, &body, &headers, app_type.clone(), tag, app_type_str).await?;
+    ctx.attach_trace(&extensions);
     let endpoint = endpoint_with_query(&uri, "/responses");
 
     let is_stream = body
@@ -994,6 +1112,7 @@ pub async fn handle_alpha_search(
 
     let mut ctx =
         RequestContext::new(&state, &body, &headers, AppType::Codex, "Codex", "codex").await?;
+    ctx.attach_trace(&extensions);
     let endpoint = endpoint_with_query(&uri, "/alpha/search");
 
     let forwarder = ctx.create_forwarder(&state);
@@ -1070,6 +1189,7 @@ async fn handle_responses_compact_for_app(
 
     let mut ctx =
         RequestContext::new(&state, &body, &headers, app_type.clone(), tag, app_type_str).await?;
+    ctx.attach_trace(&extensions);
     let endpoint = endpoint_with_query(&uri, "/responses/compact");
 
     let is_stream = body
@@ -1252,6 +1372,7 @@ async fn handle_codex_responses_namespace_restore(
                     let state = state.clone();
                     let provider_id = ctx.provider.id.clone();
                     let session_id = ctx.session_id.clone();
+                    let trace = ctx.trace.clone();
                     let latency_ms = ctx.latency_ms();
                     async move {
                         log_usage(
@@ -1267,6 +1388,7 @@ async fn handle_codex_responses_namespace_restore(
                             false,
                             status.as_u16(),
                             Some(session_id),
+                            trace,
                         )
                         .await;
                     }
@@ -1336,6 +1458,7 @@ async fn handle_codex_chat_to_responses_transform(
             let app_type_str = ctx.app_type_str;
             let start_time = ctx.start_time;
             let session_id = ctx.session_id.clone();
+            let trace = ctx.trace.clone();
 
             Some(SseUsageCollector::new(
                 start_time,
@@ -1364,6 +1487,7 @@ async fn handle_codex_chat_to_responses_transform(
                     let request_model = request_model.clone();
                     let outbound_model = fallback_model.clone();
                     let session_id = session_id.clone();
+                    let trace = trace.clone();
 
                     tokio::spawn(async move {
                         log_usage(
@@ -1379,6 +1503,7 @@ async fn handle_codex_chat_to_responses_transform(
                             true,
                             status.as_u16(),
                             Some(session_id),
+                            trace,
                         )
                         .await;
                     });
@@ -1485,6 +1610,7 @@ async fn handle_codex_chat_to_responses_transform(
             let state = state.clone();
             let provider_id = ctx.provider.id.clone();
             let session_id = ctx.session_id.clone();
+            let trace = ctx.trace.clone();
             let latency_ms = ctx.latency_ms();
             async move {
                 log_usage(
@@ -1500,6 +1626,7 @@ async fn handle_codex_chat_to_responses_transform(
                     false,
                     status.as_u16(),
                     Some(session_id),
+                    trace,
                 )
                 .await;
             }
@@ -1650,6 +1777,7 @@ async fn handle_codex_anthropic_to_responses_transform(
             let state = state.clone();
             let provider_id = ctx.provider.id.clone();
             let session_id = ctx.session_id.clone();
+            let trace = ctx.trace.clone();
             let latency_ms = ctx.latency_ms();
             async move {
                 log_usage(
@@ -1665,6 +1793,7 @@ async fn handle_codex_anthropic_to_responses_transform(
                     false,
                     status.as_u16(),
                     Some(session_id),
+                    trace,
                 )
                 .await;
             }
@@ -1715,6 +1844,7 @@ fn build_codex_anthropic_sse_response(
         let app_type_str = ctx.app_type_str;
         let start_time = ctx.start_time;
         let session_id = ctx.session_id.clone();
+        let trace = ctx.trace.clone();
 
         Some(SseUsageCollector::new(
             start_time,
@@ -1737,6 +1867,7 @@ fn build_codex_anthropic_sse_response(
                 let request_model = request_model.clone();
                 let outbound_model = fallback_model.clone();
                 let session_id = session_id.clone();
+                let trace = trace.clone();
 
                 tokio::spawn(async move {
                     log_usage(
@@ -1752,6 +1883,7 @@ fn build_codex_anthropic_sse_response(
                         true,
                         status.as_u16(),
                         Some(session_id),
+                        trace,
                     )
                     .await;
                 });
@@ -2011,6 +2143,7 @@ fn codex_proxy_error_code(error: &ProxyError) -> &'static str {
         ProxyError::NoAvailableProvider => "cc_switch_no_available_provider",
         ProxyError::AllProvidersCircuitOpen => "cc_switch_all_providers_circuit_open",
         ProxyError::NoProvidersConfigured => "cc_switch_no_providers_configured",
+        ProxyError::BudgetExceeded(_) => "cc_switch_budget_exceeded",
         ProxyError::MaxRetriesExceeded => "cc_switch_max_retries_exceeded",
         ProxyError::ProviderUnhealthy(_) => "cc_switch_provider_unhealthy",
         ProxyError::ConfigError(_) => "cc_switch_config_error",
@@ -2063,6 +2196,14 @@ pub async fn handle_gemini(
         .await
         .map_err(|e| ProxyError::Internal(format!("Failed to read request body: {e}")))?
         .to_bytes();
+    // 列模型请求返回所有 Gemini 供应商的聚合列表，而不是只透传当前供应商的
+    if method == axum::http::Method::GET && uri.path().trim_end_matches('/').ends_with("/models") {
+        return Ok(
+            aggregated_models(&state, AppType::Gemini, ModelListFormat::Gemini)
+                .await
+                .into_response(),
+        );
+    }
     // GET 类只读端点（/v1beta/models、/v1beta/models/<model> 等）没有请求体，
     // 不能强制 parse 为 JSON —— 否则空 body 会被拒绝。
     let body: Value = if body_bytes.is_empty() {
@@ -2073,9 +2214,17 @@ pub async fn handle_gemini(
     };
 
     // Gemini 的模型名称在 URI 中
-    let mut ctx = RequestContext::new(&state, &body, &headers, AppType::Gemini, "Gemini", "gemini")
-        .await?
-        .with_model_from_uri(&uri);
+    let mut ctx = RequestContext::new_with_model_from_uri(
+        &state,
+        &body,
+        &headers,
+        &uri,
+        AppType::Gemini,
+        "Gemini",
+        "gemini",
+    )
+    .await?;
+    ctx.attach_trace(&extensions);
 
     // 提取完整的路径和查询参数
     let endpoint = uri
@@ -2753,6 +2902,15 @@ fn log_forward_error(
     let status_code = map_proxy_error_to_status(error);
     let error_message = get_error_message(error);
     let request_id = uuid::Uuid::new_v4().to_string();
+    state.metrics.record_request(
+        ctx.app_type_str,
+        &ctx.provider.id,
+        &ctx.request_model,
+        status_code,
+        ctx.latency_ms(),
+        None,
+        None,
+    );
 
     if let Err(e) = logger.log_error_with_context(
         request_id,
@@ -2788,9 +2946,14 @@ async fn log_usage(
     is_streaming: bool,
     status_code: u16,
     session_id: Option<String>,
+    trace: Option<RequestTrace>,
 ) {
     use super::usage::logger::UsageLogger;
+    use rust_decimal::prelude::ToPrimitive;
 
+    if let Some(trace) = &trace {
+        trace.record_usage(provider_id, model, &usage);
+    }
     if !usage_logging_enabled(state) {
         return;
     }
@@ -2807,8 +2970,17 @@ async fn log_usage(
 
     let dedup_scope = super::usage::parser::dedup_scope_for_app(app_type, provider_id);
     let request_id = usage.dedup_request_id(dedup_scope);
+    state.metrics.record_request(
+        app_type,
+        provider_id,
+        model,
+        status_code,
+        latency_ms,
+        first_token_ms,
+        Some(&usage),
+    );
 
-    if let Err(e) = logger.log_with_calculation(
+    match logger.log_with_calculation(
         request_id,
         provider_id.to_string(),
         app_type.to_string(),
@@ -2824,7 +2996,18 @@ async fn log_usage(
         None, // provider_type
         is_streaming,
     ) {
-        log::warn!("[USG-001] 记录使用量失败: {e}");
+        Ok(Some(cost)) => {
+            let cost = cost.to_f64().unwrap_or(0.0);
+            state
+                .metrics
+                .record_cost(app_type, provider_id, model, cost);
+            state
+                .provider_router
+                .record_spend(provider_id, app_type, cost)
+                .await;
+        }
+        Ok(None) => log::debug!("[{app_type}] 请求已记录过，跳过花费累计: provider={provider_id}"),
+        Err(e) => log::warn!("[USG-001] 记录使用量失败: {e}"),
     }
 }
 
@@ -2832,7 +3015,7 @@ async fn log_usage(
 mod tests {
     use super::{
         body_looks_like_sse, chat_sse_to_response_value, classify_body_for_diagnostics,
-        codex_proxy_error_json, responses_sse_stream_to_anthropic_message,
+        codex_catalog_response, codex_proxy_error_json, responses_sse_stream_to_anthropic_message,
         responses_sse_to_response_value, should_use_claude_transform_streaming, transform,
         upstream_body_parse_error,
     };
@@ -2843,6 +3026,61 @@ mod tests {
         Arc,
     };
 
+    struct TestHomeGuard(Option<std::ffi::OsString>);
+    impl TestHomeGuard {
+        fn set(home: &std::path::Path) -> Self {
+            let guard = Self(std::env::var_os("CC_SWITCH_TEST_HOME"));
+            std::env::set_var("CC_SWITCH_TEST_HOME", home);
+            guard
+        }
+    }
+    impl Drop for TestHomeGuard {
+        fn drop(&mut self) {
+            match self.0.take() {
+                Some(value) => std::env::set_var("CC_SWITCH_TEST_HOME", value),
+                None => std::env::remove_var("CC_SWITCH_TEST_HOME"),
+            }
+        }
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn codex_route_serves_the_catalog_without_client_version() {
+        let home = tempfile::tempdir().expect("tempdir");
+        let _home_guard = TestHomeGuard::set(home.path());
+        crate::settings::update_settings(crate::settings::AppSettings::default())
+            .expect("reset settings");
+        let plain: axum::http::Uri = "/codex/v1/models".parse().unwrap();
+        let probe: axum::http::Uri = "/codex/v1/models?client_version=0.50.0".parse().unwrap();
+
+        // 没有生效的目录：普通请求退回聚合列表，Codex CLI 探测得到空目录
+        assert_eq!(codex_catalog_response(&plain), None);
+        assert_eq!(
+            codex_catalog_response(&probe),
+            Some(serde_json::json!({"models": []}))
+        );
+
+        let catalog = serde_json::json!({"models": [{"slug": "gpt-5-codex"}]});
+        let config_dir = crate::codex_config::get_codex_config_dir();
+        std::fs::create_dir_all(&config_dir).expect("create codex dir");
+        std::fs::write(
+            crate::codex_config::get_codex_model_catalog_path(),
+            catalog.to_string(),
+        )
+        .expect("write catalog");
+        std::fs::write(
+            crate::codex_config::get_codex_config_path(),
+            format!(
+                "model_catalog_json = \"{}\"\n",
+                crate::codex_config::CC_SWITCH_CODEX_MODEL_CATALOG_FILENAME
+            ),
+        )
+        .expect("write config.toml");
+
+        assert_eq!(codex_catalog_response(&plain), Some(catalog.clone()));
+        assert_eq!(codex_catalog_response(&probe), Some(catalog));
+    }
+
     #[test]
     fn body_looks_like_sse_detects_unlabeled_sse_prefixes() {
         assert!(body_looks_like_sse("data: {\"id\":\"1\"}\n\n"));
@@ -0,0 +1,231 @@
+//! 后台健康探测
+//!
+//! 代理运行期间，按 `StreamCheckConfig::probe_interval_secs` 中各应用的间隔，对故障转移
+//! 队列里的每个供应商执行连通性检查（`StreamCheckService::check_with_retry`）。结果写入
+//! `stream_check_logs`，并经 [`ProviderRouter::record_probe_result`] 提前打开或恢复熔断器、
+//! 同步 `provider_health`，让真实请求不必先撞上首字节超时才发现供应商已不可达。
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::provider::Provider;
+use crate::proxy::provider_router::{provider_supports_failover, ProviderRouter};
+use crate::services::stream_check::{
+    is_copilot_provider, HealthStatus, StreamCheckConfig, StreamCheckResult, StreamCheckService,
+};
+
+/// 调度粒度：每个 tick 重新读取配置，间隔修改无需重启代理
+const PROBE_TICK: Duration = Duration::from_secs(15);
+/// 最小探测间隔，避免误配置成对供应商的高频请求
+const MIN_PROBE_INTERVAL_SECS: u64 = 30;
+
+/// 启动探测任务；由 `ProxyServer` 持有句柄并在停止时 abort
+pub(crate) fn spawn(db: Arc<Database>, router: Arc<ProviderRouter>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last_run: HashMap<AppType, Instant> = HashMap::new();
+        let mut ticker = tokio::time::interval(PROBE_TICK);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            let config = match db.get_stream_check_config() {
+                Ok(config) => config,
+                Err(e) => {
+                    log::warn!("[HealthProbe] 读取探测配置失败: {e}");
+                    continue;
+                }
+            };
+
+            let now = Instant::now();
+            for (app_type, interval) in due_apps(&config, &last_run, now) {
+                last_run.insert(app_type.clone(), now);
+                probe_app(&db, &router, &config, &app_type, interval).await;
+            }
+        }
+    })
+}
+
+/// 计算本轮到期的应用及其探测间隔
+fn due_apps(
+    config: &StreamCheckConfig,
+    last_run: &HashMap<AppType, Instant>,
+    now: Instant,
+) -> Vec<(AppType, Duration)> {
+    config
+        .probe_interval_secs
+        .iter()
+        .filter(|(_, secs)| **secs > 0)
+        .filter_map(|(app, secs)| {
+            let Ok(app_type) = AppType::from_str(app) else {
+                log::debug!("[HealthProbe] 忽略未知应用: {app}");
+                return None;
+            };
+            let interval = Duration::from_secs((*secs).max(MIN_PROBE_INTERVAL_SECS));
+            let due = last_run
+                .get(&app_type)
+                .is_none_or(|last| now.saturating_duration_since(*last) >= interval);
+            due.then_some((app_type, interval))
+        })
+        .collect()
+}
+
+async fn probe_app(
+    db: &Database,
+    router: &ProviderRouter,
+    config: &StreamCheckConfig,
+    app_type: &AppType,
+    interval: Duration,
+) {
+    let app = app_type.as_str();
+    let (queue, providers) = match (db.get_failover_queue(app), db.get_all_providers(app)) {
+        (Ok(queue), Ok(providers)) => (queue, providers),
+        (Err(e), _) | (_, Err(e)) => {
+            log::warn!("[HealthProbe] [{app}] 读取故障转移队列失败: {e}");
+            return;
+        }
+    };
+    // 不可达的供应商保持熔断到下一轮探测结束
+    let hold = interval + PROBE_TICK;
+
+    for item in queue {
+        let Some(provider) = providers.get(&item.provider_id) else {
+            continue;
+        };
+        if !is_probe_target(app, provider) {
+            continue;
+        }
+
+        let result = StreamCheckService::check_with_retry(app_type, provider, config, None)
+            .await
+            .unwrap_or_else(|e| failed_result(e.to_string()));
+        log::debug!(
+            "[HealthProbe] [{app}] {} → {:?} ({})",
+            provider.id,
+            result.status,
+            result.message
+        );
+
+        let _ = db.save_stream_check_log(&provider.id, &provider.name, app, &result);
+        let error = (!result.success).then(|| result.message.clone());
+        if let Err(e) = router
+            .record_probe_result(&provider.id, app, result.success, error, hold)
+            .await
+        {
+            log::warn!(
+                "[HealthProbe] [{app}] 记录 {} 探测结果失败: {e}",
+                provider.id
+            );
+        }
+    }
+}
+
+/// 与手动批量检查一致：官方 OAuth 供应商没有用户配置的探测目标，Copilot 端点需 OAuth
+/// 解析（后台无从获取），均跳过；不参与故障转移的供应商探测了也无从影响路由
+fn is_probe_target(app_type: &str, provider: &Provider) -> bool {
+    if provider.category.as_deref() == Some("official") {
+        return false;
+    }
+    if !provider_supports_failover(app_type, provider) {
+        return false;
+    }
+    let is_full_url = provider
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.is_full_url)
+        .unwrap_or(false);
+    !is_copilot_provider(provider) || is_full_url
+}
+
+fn failed_result(message: String) -> StreamCheckResult {
+    StreamCheckResult {
+        status: HealthStatus::Failed,
+        success: false,
+        message,
+        response_time_ms: None,
+        http_status: None,
+        model_used: String::new(),
+        tested_at: chrono::Utc::now().timestamp(),
+        retry_count: 0,
+        error_category: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderMeta;
+    use serde_json::json;
+
+    fn config_with(intervals: &[(&str, u64)]) -> StreamCheckConfig {
+        StreamCheckConfig {
+            probe_interval_secs: intervals
+                .iter()
+                .map(|(app, secs)| (app.to_string(), *secs))
+                .collect(),
+            ..StreamCheckConfig::default()
+        }
+    }
+
+    #[test]
+    fn due_apps_respects_per_app_interval_and_minimum() {
+        let config = config_with(&[("claude", 300), ("codex", 1), ("gemini", 0), ("nope", 60)]);
+        let now = Instant::now();
+
+        let mut first: Vec<_> = due_apps(&config, &HashMap::new(), now)
+            .into_iter()
+            .map(|(app, interval)| (app.as_str().to_string(), interval.as_secs()))
+            .collect();
+        first.sort();
+        assert_eq!(
+            first,
+            vec![
+                ("claude".to_string(), 300),
+                ("codex".to_string(), MIN_PROBE_INTERVAL_SECS)
+            ]
+        );
+
+        let last_run = HashMap::from([
+            (AppType::Claude, now),
+            (
+                AppType::Codex,
+                now - Duration::from_secs(MIN_PROBE_INTERVAL_SECS),
+            ),
+        ]);
+        let second: Vec<_> = due_apps(&config, &last_run, now + Duration::from_secs(1))
+            .into_iter()
+            .map(|(app, _)| app)
+            .collect();
+        assert_eq!(second, vec![AppType::Codex]);
+    }
+
+    #[test]
+    fn probe_targets_skip_official_and_oauth_copilot_providers() {
+        let mut provider = Provider::with_id(
+            "p".to_string(),
+            "P".to_string(),
+            json!({ "env": { "ANTHROPIC_BASE_URL": "https://relay.example.com" } }),
+            None,
+        );
+        assert!(is_probe_target("claude", &provider));
+
+        provider.category = Some("official".to_string());
+        assert!(!is_probe_target("claude", &provider));
+
+        provider.category = None;
+        provider.meta = Some(ProviderMeta {
+            provider_type: Some("github_copilot".to_string()),
+            ..Default::default()
+        });
+        assert!(!is_probe_target("claude", &provider));
+
+        provider.meta.as_mut().unwrap().is_full_url = Some(true);
+        assert!(is_probe_target("claude", &provider));
+    }
+}
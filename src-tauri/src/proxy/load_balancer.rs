@@ -0,0 +1,248 @@
+//! 故障转移队列负载均衡
+//!
+//! 根据应用的 `RoutingStrategy` 对可用供应商重新排序：
+//! 排在首位的供应商承接本次请求，其余保持为故障转移备选。
+
+use crate::database::Database;
+use crate::provider::Provider;
+use crate::proxy::types::RoutingStrategy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 延迟统计窗口（秒）
+const LATENCY_WINDOW_SECS: i64 = 30 * 60;
+
+/// 延迟统计缓存有效期，避免每个请求都聚合 proxy_request_logs
+const LATENCY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// provider_id -> 近期平均延迟（毫秒）
+type LatencyMap = HashMap<String, f64>;
+
+/// 负载均衡状态（跨请求保持，按 app_type 隔离）
+#[derive(Default)]
+pub struct LoadBalancer {
+    /// 轮询计数器
+    round_robin: Mutex<HashMap<String, usize>>,
+    /// 平滑加权轮询的当前权重 - app_type -> provider_id -> current_weight
+    weighted: Mutex<HashMap<String, HashMap<String, i64>>>,
+    /// 近期平均延迟缓存 - app_type -> (加载时间, provider_id -> latency_ms)
+    latencies: Mutex<HashMap<String, (Instant, LatencyMap)>>,
+}
+
+impl LoadBalancer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按策略对已过滤的可用供应商排序
+    pub fn order(
+        &self,
+        db: &Database,
+        app_type: &str,
+        strategy: RoutingStrategy,
+        mut providers: Vec<Provider>,
+    ) -> Vec<Provider> {
+        if providers.len() < 2 {
+            return providers;
+        }
+
+        match strategy {
+            RoutingStrategy::Priority => {}
+            RoutingStrategy::RoundRobin => {
+                let mut counters = self.round_robin.lock().unwrap_or_else(|e| e.into_inner());
+                let counter = counters.entry(app_type.to_string()).or_insert(0);
+                let offset = *counter % providers.len();
+                *counter = counter.wrapping_add(1);
+                providers.rotate_left(offset);
+            }
+            RoutingStrategy::Weighted => {
+                if let Some(index) = self.pick_weighted(app_type, &providers) {
+                    let chosen = providers.remove(index);
+                    providers.insert(0, chosen);
+                }
+            }
+            RoutingStrategy::LeastLatency => {
+                let latencies = self.recent_latencies(db, app_type);
+                // 无样本的供应商视为 0，优先获得一次请求以建立延迟数据；
+                // 稳定排序保证同延迟时仍按队列顺序
+                providers.sort_by(|a, b| {
+                    let la = latencies.get(&a.id).copied().unwrap_or(0.0);
+                    let lb = latencies.get(&b.id).copied().unwrap_or(0.0);
+                    la.total_cmp(&lb)
+                });
+            }
+        }
+
+        providers
+    }
+
+    /// 平滑加权轮询（nginx SWRR）：返回本次首选供应商的下标
+    ///
+    /// 权重为 0 的供应商不参与首选，只作为故障转移备选。
+    fn pick_weighted(&self, app_type: &str, providers: &[Provider]) -> Option<usize> {
+        let weights: Vec<i64> = providers.iter().map(provider_weight).collect();
+        let total: i64 = weights.iter().sum();
+        if total <= 0 {
+            return None;
+        }
+
+        let mut state = self.weighted.lock().unwrap_or_else(|e| e.into_inner());
+        let current = state.entry(app_type.to_string()).or_default();
+        current.retain(|id, _| providers.iter().any(|p| &p.id == id));
+
+        let mut best: Option<(usize, i64)> = None;
+        for (index, (provider, weight)) in providers.iter().zip(&weights).enumerate() {
+            if *weight == 0 {
+                continue;
+            }
+            let value = current.entry(provider.id.clone()).or_insert(0);
+            *value += weight;
+            if best.is_none_or(|(_, best_value)| *value > best_value) {
+                best = Some((index, *value));
+            }
+        }
+
+        let (index, _) = best?;
+        if let Some(value) = current.get_mut(&providers[index].id) {
+            *value -= total;
+        }
+        Some(index)
+    }
+
+    fn recent_latencies(&self, db: &Database, app_type: &str) -> LatencyMap {
+        {
+            let cache = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((loaded_at, latencies)) = cache.get(app_type) {
+                if loaded_at.elapsed() < LATENCY_CACHE_TTL {
+                    return latencies.clone();
+                }
+            }
+        }
+
+        let latencies = db
+            .get_recent_provider_latencies(app_type, LATENCY_WINDOW_SECS)
+            .unwrap_or_else(|e| {
+                log::warn!("[{app_type}] 读取供应商近期延迟失败: {e}，按队列顺序路由");
+                HashMap::new()
+            });
+        self.latencies
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(app_type.to_string(), (Instant::now(), latencies.clone()));
+        latencies
+    }
+}
+
+fn provider_weight(provider: &Provider) -> i64 {
+    provider
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.routing_weight)
+        .map(i64::from)
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderMeta;
+    use serde_json::json;
+
+    fn provider(id: &str, weight: Option<u32>) -> Provider {
+        let mut provider = Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None);
+        provider.meta = Some(ProviderMeta {
+            routing_weight: weight,
+            ..Default::default()
+        });
+        provider
+    }
+
+    fn first_ids(
+        balancer: &LoadBalancer,
+        db: &Database,
+        strategy: RoutingStrategy,
+        providers: &[Provider],
+        rounds: usize,
+    ) -> Vec<String> {
+        (0..rounds)
+            .map(|_| {
+                balancer.order(db, "claude", strategy, providers.to_vec())[0]
+                    .id
+                    .clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_robin_rotates_first_provider() {
+        let db = Database::memory().unwrap();
+        let balancer = LoadBalancer::new();
+        let providers = vec![
+            provider("a", None),
+            provider("b", None),
+            provider("c", None),
+        ];
+
+        let firsts = first_ids(&balancer, &db, RoutingStrategy::RoundRobin, &providers, 4);
+        assert_eq!(firsts, vec!["a", "b", "c", "a"]);
+
+        // 其余供应商仍作为故障转移备选保留
+        let ordered = balancer.order(&db, "claude", RoutingStrategy::RoundRobin, providers);
+        assert_eq!(ordered.len(), 3);
+    }
+
+    #[test]
+    fn weighted_distributes_by_weight_and_skips_zero() {
+        let db = Database::memory().unwrap();
+        let balancer = LoadBalancer::new();
+        let providers = vec![
+            provider("a", Some(3)),
+            provider("b", Some(1)),
+            provider("c", Some(0)),
+        ];
+
+        let firsts = first_ids(&balancer, &db, RoutingStrategy::Weighted, &providers, 8);
+        assert_eq!(firsts.iter().filter(|id| *id == "a").count(), 6);
+        assert_eq!(firsts.iter().filter(|id| *id == "b").count(), 2);
+        assert!(!firsts.iter().any(|id| id == "c"));
+    }
+
+    #[test]
+    fn least_latency_prefers_fastest_and_unsampled() {
+        let db = Database::memory().unwrap();
+        {
+            let conn = db.conn.lock().unwrap();
+            let now = chrono::Utc::now().timestamp();
+            for (id, provider_id, latency) in [("r1", "a", 900), ("r2", "b", 200)] {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model,
+                        latency_ms, status_code, created_at
+                    ) VALUES (?1, ?2, 'claude', 'm', ?3, 200, ?4)",
+                    rusqlite::params![id, provider_id, latency, now],
+                )
+                .unwrap();
+            }
+        }
+
+        let balancer = LoadBalancer::new();
+        let providers = vec![
+            provider("a", None),
+            provider("b", None),
+            provider("c", None),
+        ];
+        let ordered = balancer.order(&db, "claude", RoutingStrategy::LeastLatency, providers);
+        let ids: Vec<&str> = ordered.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn priority_keeps_queue_order() {
+        let db = Database::memory().unwrap();
+        let balancer = LoadBalancer::new();
+        let providers = vec![provider("a", None), provider("b", None)];
+        let firsts = first_ids(&balancer, &db, RoutingStrategy::Priority, &providers, 3);
+        assert_eq!(firsts, vec!["a", "a", "a"]);
+    }
+}
@@ -18,6 +18,8 @@ pub mod cb {
     pub const TRIGGERED_FAILURES: &str = "CB-004";
     pub const TRIGGERED_ERROR_RATE: &str = "CB-005";
     pub const MANUAL_RESET: &str = "CB-006";
+    pub const PROBE_OPENED: &str = "CB-007";
+    pub const PROBE_CLOSED: &str = "CB-008";
 }
 
 /// 服务器日志码
@@ -44,6 +46,8 @@ pub mod fo {
     pub const LIVE_BACKUP_ERROR: &str = "FO-003";
     pub const ALL_CIRCUIT_OPEN: &str = "FO-004";
     pub const NO_PROVIDERS: &str = "FO-005";
+    pub const BUDGET_EXCEEDED: &str = "FO-006";
+    pub const QUOTA_SATURATED: &str = "FO-007";
 }
 
 /// 响应处理日志码
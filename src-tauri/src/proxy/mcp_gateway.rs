@@ -0,0 +1,657 @@
+//! MCP 网关
+//!
+//! 代理在 `/mcp/{app}` 上暴露一个 Streamable HTTP MCP 服务器（只返回 JSON，不开启服务端
+//! 事件流），把该应用在 `mcp_servers` 中启用的全部服务器的工具汇总到一起：工具名加上
+//! `{server_id}__` 前缀，`tools/call` 按前缀转发给对应上游。上游连接按服务器 id 池化、
+//! 跨应用共享，因此每个 stdio 服务器只启动一个进程；连接定义变化或传输出错时重建。
+//! 同一上游的多个调用并发进行，慢调用不会阻塞其它调用。
+//!
+//! 网关会替用户启动本机进程，因此只在回环地址上提供服务，并要求每个请求带上本机安装
+//! 专属的访问令牌（写进各应用的网关条目）、JSON 请求体，且不接受来自外部网页的 Origin。
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::{header, HeaderMap, StatusCode};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::app_config::{AppType, McpServer};
+use crate::database::Database;
+use crate::error::AppError;
+use crate::mcp::{self, McpClient, McpError, PROTOCOL_VERSION};
+
+/// 命名空间分隔符：`{server_id}__{tool}`
+pub(crate) const TOOL_SEPARATOR: &str = "__";
+/// 建立上游连接（含 npx/uvx 首次下载）的时限
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+/// 单次上游请求时限；工具调用可能较慢
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+/// 网关能回应的协议版本；客户端请求其中之一时原样协商
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// 网关访问令牌文件（位于配置目录，权限 0600）
+const TOKEN_FILE: &str = "mcp-gateway.token";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// 一个上游服务器的当前连接
+#[derive(Default)]
+struct Connection {
+    /// 建立连接时使用的定义，用于发现配置变化
+    spec: Value,
+    client: Option<Arc<McpClient>>,
+    /// 当前连接的工具列表缓存（上游原始名称）
+    tools: Option<Vec<Value>>,
+}
+
+/// 上游连接；锁只保护建立/丢弃连接，请求在锁外并发执行，由客户端按 JSON-RPC id 分发响应
+#[derive(Default)]
+struct Upstream {
+    connection: Mutex<Connection>,
+}
+
+impl Upstream {
+    async fn client(&self, spec: &Value) -> Result<Arc<McpClient>, McpError> {
+        let mut connection = self.connection.lock().await;
+        if connection.client.is_some() && &connection.spec != spec {
+            log::info!("[MCP-Gateway] 上游定义已变化，重建连接");
+            *connection = Connection::default();
+        }
+        if let Some(client) = &connection.client {
+            return Ok(Arc::clone(client));
+        }
+        let client = tokio::time::timeout(CONNECT_TIMEOUT, McpClient::connect(spec))
+            .await
+            .map_err(|_| McpError::Transport("连接上游超时".to_string()))??;
+        let client = Arc::new(client);
+        *connection = Connection {
+            spec: spec.clone(),
+            client: Some(Arc::clone(&client)),
+            tools: None,
+        };
+        Ok(client)
+    }
+
+    async fn request(&self, spec: &Value, method: &str, params: Value) -> Result<Value, McpError> {
+        let client = self.client(spec).await?;
+        let result = tokio::time::timeout(REQUEST_TIMEOUT, client.request(method, params))
+            .await
+            .unwrap_or_else(|_| Err(McpError::Transport(format!("{method}: 上游响应超时"))));
+        // 传输层故障后连接状态未知，丢弃以便下次重连；协议错误不影响连接
+        if matches!(result, Err(McpError::Transport(_))) {
+            self.discard(&client).await;
+        }
+        result
+    }
+
+    async fn tools(&self, spec: &Value) -> Result<Vec<Value>, McpError> {
+        let client = self.client(spec).await?;
+        {
+            let connection = self.connection.lock().await;
+            if let Some(tools) = connection.tools.as_ref().filter(|_| {
+                connection
+                    .client
+                    .as_ref()
+                    .is_some_and(|current| Arc::ptr_eq(current, &client))
+            }) {
+                return Ok(tools.clone());
+            }
+        }
+
+        let listed = if client.supports("tools") {
+            tokio::time::timeout(REQUEST_TIMEOUT, client.list_all("tools/list", "tools"))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(McpError::Transport("tools/list: 上游响应超时".to_string()))
+                })
+        } else {
+            Ok(Vec::new())
+        };
+        match &listed {
+            Ok(tools) => {
+                let mut connection = self.connection.lock().await;
+                if connection
+                    .client
+                    .as_ref()
+                    .is_some_and(|current| Arc::ptr_eq(current, &client))
+                {
+                    connection.tools = Some(tools.clone());
+                }
+            }
+            Err(McpError::Transport(_)) => self.discard(&client).await,
+            Err(_) => {}
+        }
+        listed
+    }
+
+    /// 丢弃出错的连接；期间已被别的请求重建的新连接保持不动。
+    /// 最后一个引用释放时即终止 stdio 子进程（kill_on_drop）
+    async fn discard(&self, client: &Arc<McpClient>) {
+        let mut connection = self.connection.lock().await;
+        if connection
+            .client
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, client))
+        {
+            *connection = Connection::default();
+        }
+    }
+}
+
+/// 代理持有的网关状态：上游连接池
+#[derive(Default)]
+pub struct McpGateway {
+    upstreams: std::sync::Mutex<HashMap<String, Arc<Upstream>>>,
+}
+
+impl McpGateway {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 关闭全部上游连接（代理停止时调用）
+    pub fn shutdown(&self) {
+        if let Ok(mut upstreams) = self.upstreams.lock() {
+            upstreams.clear();
+        }
+    }
+
+    fn upstream(&self, server_id: &str) -> Arc<Upstream> {
+        let mut upstreams = self.upstreams.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(upstreams.entry(server_id.to_string()).or_default())
+    }
+
+    /// 处理一个 HTTP 请求体（单条消息或批量数组）；返回 None 表示全是通知，应回 202
+    pub async fn handle(&self, db: &Database, app: &str, body: &[u8]) -> Option<Value> {
+        let Ok(app) = AppType::from_str(app) else {
+            return Some(error_response(
+                Value::Null,
+                INVALID_REQUEST,
+                format!("未知应用: {app}"),
+            ));
+        };
+        let payload: Value = match serde_json::from_slice(body) {
+            Ok(payload) => payload,
+            Err(e) => {
+                return Some(error_response(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("无效的 JSON: {e}"),
+                ))
+            }
+        };
+
+        match payload {
+            Value::Array(messages) => {
+                let mut responses = Vec::new();
+                for message in &messages {
+                    responses.extend(self.dispatch(db, &app, message).await);
+                }
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            message => self.dispatch(db, &app, &message).await,
+        }
+    }
+
+    /// 处理单条 JSON-RPC 消息；通知没有响应
+    async fn dispatch(&self, db: &Database, app: &AppType, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned()?;
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id,
+                INVALID_REQUEST,
+                "缺少 method".to_string(),
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => self.list_tools(db, app).await,
+            "tools/call" => self.call_tool(db, app, &params).await,
+            other => Err((METHOD_NOT_FOUND, format!("不支持的方法: {other}"))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    async fn list_tools(&self, db: &Database, app: &AppType) -> Result<Value, (i64, String)> {
+        let servers = enabled_servers(db, app)?;
+        let listings = futures::future::join_all(servers.iter().map(|server| async move {
+            let upstream = self.upstream(&server.id);
+            (server, upstream.tools(&server.server).await)
+        }))
+        .await;
+
+        let mut tools = Vec::new();
+        for (server, listing) in listings {
+            match listing {
+                Ok(server_tools) => tools.extend(
+                    server_tools
+                        .into_iter()
+                        .filter_map(|tool| namespace_tool(&server.id, tool)),
+                ),
+                // 单个上游不可用不影响其余服务器的工具
+                Err(e) => log::warn!("[MCP-Gateway] [{}] 获取工具列表失败: {e}", server.id),
+            }
+        }
+        Ok(json!({ "tools": tools }))
+    }
+
+    async fn call_tool(
+        &self,
+        db: &Database,
+        app: &AppType,
+        params: &Value,
+    ) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "缺少工具名称".to_string()))?;
+        let servers = enabled_servers(db, app)?;
+        let (server, tool) = resolve_tool(&servers, name)
+            .ok_or_else(|| (INVALID_PARAMS, format!("未知工具: {name}")))?;
+
+        let mut forwarded = params.clone();
+        forwarded["name"] = Value::String(tool.to_string());
+
+        self.upstream(&server.id)
+            .request(&server.server, "tools/call", forwarded)
+            .await
+            .map_err(|e| match e {
+                McpError::Rpc { code, message } => (code, message),
+                McpError::Transport(message) => {
+                    log::warn!("[MCP-Gateway] [{}] 调用 {tool} 失败: {message}", server.id);
+                    (INTERNAL_ERROR, format!("{}: {message}", server.id))
+                }
+            })
+    }
+}
+
+/// 主机名是否指向本机（`localhost` 或回环 IP，IPv6 可带方括号）
+pub(crate) fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim();
+    let host = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn load_or_create_token(path: &Path) -> Result<String, AppError> {
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        let existing = existing.trim();
+        if !existing.is_empty() {
+            return Ok(existing.to_string());
+        }
+    }
+    let token: String = crate::secrets::random_bytes::<32>()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    crate::config::atomic_write_private(path, token.as_bytes())?;
+    Ok(token)
+}
+
+/// 本机安装的网关访问令牌；首次使用时随机生成并保存
+pub(crate) fn gateway_token() -> Result<String, AppError> {
+    static TOKEN: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+    let mut cached = TOKEN.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(token) = cached.as_ref() {
+        return Ok(token.clone());
+    }
+    let token = load_or_create_token(&crate::config::get_app_config_dir().join(TOKEN_FILE))?;
+    *cached = Some(token.clone());
+    Ok(token)
+}
+
+/// 校验一次网关请求，失败时返回应答的状态码
+///
+/// - 代理监听在非回环地址时不提供网关（403）
+/// - 带 Origin 的请求必须来自本机页面，防止网页借浏览器调用本机工具（403）
+/// - 必须携带 `Authorization: Bearer <令牌>`（401）
+/// - 请求体必须声明为 `application/json`（415）
+pub(crate) fn authorize(
+    headers: &HeaderMap,
+    listen_address: &str,
+    token: &str,
+) -> Result<(), StatusCode> {
+    if !is_loopback_host(listen_address) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        let local = origin
+            .to_str()
+            .ok()
+            .and_then(|origin| url::Url::parse(origin).ok())
+            .and_then(|origin| origin.host_str().map(is_loopback_host))
+            .unwrap_or(false);
+        if !local {
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    if !constant_time_eq(presented.as_bytes(), token.as_bytes()) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"));
+    if !is_json {
+        return Err(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+    Ok(())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 该应用启用的服务器，连接定义中的占位符已替换为真实值
+fn enabled_servers(db: &Database, app: &AppType) -> Result<Vec<McpServer>, (i64, String)> {
+    let servers: Vec<McpServer> = db
+        .get_all_mcp_servers()
+        .map_err(|e| (INTERNAL_ERROR, e.to_string()))?
+        .into_values()
+        .filter(|server| server.apps.is_enabled_for(app))
+        .collect();
+    let needs_secrets = servers
+        .iter()
+        .any(|server| !mcp::referenced_secrets(&server.server).is_empty());
+    let secrets = if needs_secrets {
+        db.get_mcp_secret_values()
+            .map_err(|e| (INTERNAL_ERROR, e.to_string()))?
+    } else {
+        HashMap::new()
+    };
+
+    Ok(servers
+        .into_iter()
+        .filter_map(
+            |mut server| match mcp::resolve_placeholders(&server.server, &secrets) {
+                Ok(spec) => {
+                    server.server = spec;
+                    Some(server)
+                }
+                Err(e) => {
+                    log::warn!("[MCP-Gateway] [{}] 跳过：{e}", server.id);
+                    None
+                }
+            },
+        )
+        .collect())
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let protocol_version = requested
+        .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "cc-switch", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// 给工具名加上服务器前缀，避免不同服务器的同名工具冲突
+fn namespace_tool(server_id: &str, mut tool: Value) -> Option<Value> {
+    let name = tool.get("name").and_then(Value::as_str)?.to_string();
+    tool["name"] = Value::String(format!("{server_id}{TOOL_SEPARATOR}{name}"));
+    Some(tool)
+}
+
+/// 按最长匹配的服务器前缀拆分命名空间工具名（服务器 id 本身可能含 `__`）
+fn resolve_tool<'a>(servers: &'a [McpServer], name: &'a str) -> Option<(&'a McpServer, &'a str)> {
+    servers
+        .iter()
+        .filter_map(|server| {
+            let tool = name
+                .strip_prefix(server.id.as_str())?
+                .strip_prefix(TOOL_SEPARATOR)?;
+            (!tool.is_empty()).then_some((server, tool))
+        })
+        .max_by_key(|(server, _)| server.id.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::McpApps;
+
+    fn server(id: &str, spec: Value) -> McpServer {
+        McpServer {
+            id: id.to_string(),
+            name: id.to_string(),
+            server: spec,
+            apps: McpApps {
+                claude: true,
+                ..McpApps::default()
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn resolve_tool_prefers_the_longest_server_prefix() {
+        let servers = vec![server("a", json!({})), server("a__b", json!({}))];
+        let (owner, tool) = resolve_tool(&servers, "a__b__search").unwrap();
+        assert_eq!((owner.id.as_str(), tool), ("a__b", "search"));
+
+        let (owner, tool) = resolve_tool(&servers, "a__search").unwrap();
+        assert_eq!((owner.id.as_str(), tool), ("a", "search"));
+
+        assert!(resolve_tool(&servers, "other__search").is_none());
+        assert!(resolve_tool(&servers, "a__").is_none());
+    }
+
+    fn gateway_headers(pairs: &[(header::HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(name.clone(), value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn authorize_requires_loopback_bind_local_origin_token_and_json() {
+        let ok = [
+            (header::AUTHORIZATION, "Bearer t0ken"),
+            (header::CONTENT_TYPE, "application/json; charset=utf-8"),
+        ];
+        assert_eq!(
+            authorize(&gateway_headers(&ok), "127.0.0.1", "t0ken"),
+            Ok(())
+        );
+        assert_eq!(authorize(&gateway_headers(&ok), "::1", "t0ken"), Ok(()));
+        assert_eq!(
+            authorize(&gateway_headers(&ok), "0.0.0.0", "t0ken"),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            authorize(&gateway_headers(&ok), "192.168.1.5", "t0ken"),
+            Err(StatusCode::FORBIDDEN)
+        );
+
+        let mut local_origin = ok.to_vec();
+        local_origin.push((header::ORIGIN, "http://localhost:3000"));
+        assert_eq!(
+            authorize(&gateway_headers(&local_origin), "127.0.0.1", "t0ken"),
+            Ok(())
+        );
+        for origin in [
+            "https://evil.example",
+            "null",
+            "http://127.0.0.1.evil.example",
+        ] {
+            let mut foreign = ok.to_vec();
+            foreign.push((header::ORIGIN, origin));
+            assert_eq!(
+                authorize(&gateway_headers(&foreign), "127.0.0.1", "t0ken"),
+                Err(StatusCode::FORBIDDEN),
+                "{origin}"
+            );
+        }
+
+        for auth in ["Bearer wrong", "t0ken", ""] {
+            let headers = gateway_headers(&[
+                (header::AUTHORIZATION, auth),
+                (header::CONTENT_TYPE, "application/json"),
+            ]);
+            assert_eq!(
+                authorize(&headers, "127.0.0.1", "t0ken"),
+                Err(StatusCode::UNAUTHORIZED),
+                "{auth}"
+            );
+        }
+
+        let form = gateway_headers(&[
+            (header::AUTHORIZATION, "Bearer t0ken"),
+            (header::CONTENT_TYPE, "text/plain"),
+        ]);
+        assert_eq!(
+            authorize(&form, "127.0.0.1", "t0ken"),
+            Err(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+        );
+    }
+
+    #[test]
+    fn gateway_token_is_generated_once_and_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(TOKEN_FILE);
+        let token = load_or_create_token(&path).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_eq!(load_or_create_token(&path).unwrap(), token);
+    }
+
+    #[tokio::test]
+    async fn notifications_get_no_response_and_unknown_methods_are_rejected() {
+        let db = Database::memory().unwrap();
+        let gateway = McpGateway::new();
+
+        let note = br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert_eq!(gateway.handle(&db, "claude", note).await, None);
+
+        let init = br#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#;
+        let response = gateway.handle(&db, "claude", init).await.unwrap();
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+
+        let unknown = br#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#;
+        let response = gateway.handle(&db, "claude", unknown).await.unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn tools_are_namespaced_and_calls_are_routed_to_one_shared_process() {
+        // 脚本只回放一轮：initialize(1)、initialized 通知、tools/list(2)、tools/call(3)。
+        // 两个应用的 tools/list 与随后的 tools/call 都必须落在同一个进程上才能全部成功
+        let script = r#"
+read l
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"1"}}}'
+read l
+read l
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo","inputSchema":{"type":"object"}}]}}'
+read l
+case "$l" in
+  *'"name":"echo"'*) echo '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"hi"}]}}' ;;
+  *) echo '{"jsonrpc":"2.0","id":3,"error":{"code":-32602,"message":"bad tool"}}' ;;
+esac
+read l
+"#;
+        let db = Database::memory().unwrap();
+        let mut shared = server("fake", json!({ "command": "sh", "args": ["-c", script] }));
+        shared.apps.codex = true;
+        db.save_mcp_server(&shared).unwrap();
+        let gateway = McpGateway::new();
+
+        let list = br#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#;
+        let claude = gateway.handle(&db, "claude", list).await.unwrap();
+        assert_eq!(claude["result"]["tools"][0]["name"], "fake__echo");
+        let codex = gateway.handle(&db, "codex", list).await.unwrap();
+        assert_eq!(codex["result"]["tools"], claude["result"]["tools"]);
+        let gemini = gateway.handle(&db, "gemini", list).await.unwrap();
+        assert_eq!(gemini["result"]["tools"], json!([]));
+
+        let call = br#"{"jsonrpc":"2.0","id":"b","method":"tools/call","params":{"name":"fake__echo","arguments":{}}}"#;
+        let response = gateway.handle(&db, "codex", call).await.unwrap();
+        assert_eq!(response["id"], "b");
+        assert_eq!(response["result"]["content"][0]["text"], "hi");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn concurrent_calls_share_one_upstream_without_waiting_for_each_other() {
+        // 脚本读到两个 tools/call 之后才作答，并且先回后到的那个；
+        // 若第一个调用独占连接直到响应，第二个请求永远发不出去
+        let script = r#"
+read l
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"1"}}}'
+read l
+read a
+read b
+ida=$(echo "$a" | sed 's/.*"id":\([0-9]*\).*/\1/')
+idb=$(echo "$b" | sed 's/.*"id":\([0-9]*\).*/\1/')
+echo "{\"jsonrpc\":\"2.0\",\"id\":$idb,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"second\"}]}}"
+echo "{\"jsonrpc\":\"2.0\",\"id\":$ida,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"first\"}]}}"
+read l
+"#;
+        let db = Database::memory().unwrap();
+        db.save_mcp_server(&server(
+            "slow",
+            json!({ "command": "sh", "args": ["-c", script] }),
+        ))
+        .unwrap();
+        let gateway = McpGateway::new();
+
+        let call_a = br#"{"jsonrpc":"2.0","id":"a","method":"tools/call","params":{"name":"slow__work","arguments":{}}}"#;
+        let call_b = br#"{"jsonrpc":"2.0","id":"b","method":"tools/call","params":{"name":"slow__work","arguments":{}}}"#;
+        let (a, b) = tokio::time::timeout(Duration::from_secs(20), async {
+            tokio::join!(
+                gateway.handle(&db, "claude", call_a),
+                gateway.handle(&db, "claude", call_b)
+            )
+        })
+        .await
+        .expect("calls should not serialize on the upstream");
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert_eq!((&a["id"], &b["id"]), (&json!("a"), &json!("b")));
+        let mut texts = vec![
+            a["result"]["content"][0]["text"].clone(),
+            b["result"]["content"][0]["text"].clone(),
+        ];
+        texts.sort_by_key(|text| text.to_string());
+        assert_eq!(texts, vec![json!("first"), json!("second")]);
+    }
+}
@@ -0,0 +1,485 @@
+//! Prometheus 指标
+//!
+//! 代理运行期间在内存中累计按应用 / 供应商 / 模型分组的请求数、状态码、故障转移、
+//! 总耗时与首字延迟直方图、Token 数与 USD 花费，由 `GET /metrics` 以 Prometheus
+//! 文本格式（0.0.4）输出，熔断器状态在抓取时从 [`ProviderRouter`] 实时读取。
+//!
+//! 请求级指标与请求日志共用记录点：关闭「请求日志」后成功请求的 Token / 花费不再统计。
+//! 计数器随代理重启清零，Prometheus 的 `rate()` / `increase()` 能正确处理重置。
+//!
+//! [`ProviderRouter`]: crate::proxy::provider_router::ProviderRouter
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use crate::proxy::circuit_breaker::{CircuitBreakerStats, CircuitState};
+use crate::proxy::usage::parser::TokenUsage;
+
+/// Prometheus 文本格式的 Content-Type
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// 总耗时直方图桶（秒）
+const DURATION_BUCKETS: &[f64] = &[0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+/// 首字延迟直方图桶（秒）
+const FIRST_TOKEN_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 60.0];
+
+/// (app, provider, model)
+type SeriesKey = (String, String, String);
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    /// 各桶（非累积）计数，最后一个元素对应 +Inf
+    counts: Vec<u64>,
+    sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let index = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[index] += 1;
+        self.sum += value;
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetricsInner {
+    /// (app, provider, model, status) → 次数
+    requests: BTreeMap<(String, String, String, u16), u64>,
+    /// (app, from_provider, to_provider) → 次数
+    failovers: BTreeMap<(String, String, String), u64>,
+    duration: BTreeMap<SeriesKey, Histogram>,
+    first_token: BTreeMap<SeriesKey, Histogram>,
+    /// (app, provider, model, token 类型) → 数量
+    tokens: BTreeMap<(String, String, String, &'static str), u64>,
+    cost_usd: BTreeMap<SeriesKey, f64>,
+}
+
+/// 单个累计计数序列（供 OTLP 指标导出使用）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterPoint {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub attributes: Vec<(&'static str, String)>,
+    pub value: CounterValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CounterValue {
+    Int(u64),
+    Double(f64),
+}
+
+/// 代理指标注册表（进程内，随代理启停）
+#[derive(Debug, Default)]
+pub struct ProxyMetrics {
+    inner: Mutex<MetricsInner>,
+}
+
+impl ProxyMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MetricsInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录一次已完成的客户端请求（成功或失败）
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_request(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        model: &str,
+        status_code: u16,
+        latency_ms: u64,
+        first_token_ms: Option<u64>,
+        usage: Option<&TokenUsage>,
+    ) {
+        let key = (
+            app_type.to_string(),
+            provider_id.to_string(),
+            model.to_string(),
+        );
+        let mut inner = self.lock();
+        *inner
+            .requests
+            .entry((key.0.clone(), key.1.clone(), key.2.clone(), status_code))
+            .or_default() += 1;
+        inner
+            .duration
+            .entry(key.clone())
+            .or_insert_with(|| Histogram::new(DURATION_BUCKETS))
+            .observe(latency_ms as f64 / 1000.0);
+        if let Some(first_token_ms) = first_token_ms {
+            inner
+                .first_token
+                .entry(key.clone())
+                .or_insert_with(|| Histogram::new(FIRST_TOKEN_BUCKETS))
+                .observe(first_token_ms as f64 / 1000.0);
+        }
+        if let Some(usage) = usage {
+            for (kind, count) in [
+                ("input", usage.input_tokens),
+                ("output", usage.output_tokens),
+                ("cache_read", usage.cache_read_tokens),
+                ("cache_creation", usage.cache_creation_tokens),
+            ] {
+                if count > 0 {
+                    *inner
+                        .tokens
+                        .entry((key.0.clone(), key.1.clone(), key.2.clone(), kind))
+                        .or_default() += u64::from(count);
+                }
+            }
+        }
+    }
+
+    /// 记录请求的计算花费（USD）
+    pub fn record_cost(&self, app_type: &str, provider_id: &str, model: &str, cost_usd: f64) {
+        if !cost_usd.is_finite() || cost_usd <= 0.0 {
+            return;
+        }
+        *self
+            .lock()
+            .cost_usd
+            .entry((
+                app_type.to_string(),
+                provider_id.to_string(),
+                model.to_string(),
+            ))
+            .or_default() += cost_usd;
+    }
+
+    /// 记录一次从 `from` 切换到 `to` 的故障转移
+    pub fn record_failover(&self, app_type: &str, from: &str, to: &str) {
+        *self
+            .lock()
+            .failovers
+            .entry((app_type.to_string(), from.to_string(), to.to_string()))
+            .or_default() += 1;
+    }
+
+    /// 导出全部计数器的当前累计值（直方图与熔断器状态只在 `/metrics` 输出）
+    pub fn counters(&self) -> Vec<CounterPoint> {
+        let inner = self.lock();
+        let series = |app: &str, provider: &str, model: &str| {
+            vec![
+                ("app", app.to_string()),
+                ("provider", provider.to_string()),
+                ("model", model.to_string()),
+            ]
+        };
+        let mut points = Vec::new();
+        for ((app, provider, model, status), count) in &inner.requests {
+            let mut attributes = series(app, provider, model);
+            attributes.push(("status", status.to_string()));
+            points.push(CounterPoint {
+                name: "cc_switch.proxy.requests",
+                unit: "{request}",
+                attributes,
+                value: CounterValue::Int(*count),
+            });
+        }
+        for ((app, from, to), count) in &inner.failovers {
+            points.push(CounterPoint {
+                name: "cc_switch.proxy.failovers",
+                unit: "{failover}",
+                attributes: vec![
+                    ("app", app.clone()),
+                    ("from_provider", from.clone()),
+                    ("to_provider", to.clone()),
+                ],
+                value: CounterValue::Int(*count),
+            });
+        }
+        for ((app, provider, model, kind), count) in &inner.tokens {
+            let mut attributes = series(app, provider, model);
+            attributes.push(("type", kind.to_string()));
+            points.push(CounterPoint {
+                name: "cc_switch.proxy.tokens",
+                unit: "{token}",
+                attributes,
+                value: CounterValue::Int(*count),
+            });
+        }
+        for ((app, provider, model), cost) in &inner.cost_usd {
+            points.push(CounterPoint {
+                name: "cc_switch.proxy.cost",
+                unit: "USD",
+                attributes: series(app, provider, model),
+                value: CounterValue::Double(*cost),
+            });
+        }
+        points
+    }
+
+    /// 以 Prometheus 文本格式输出全部指标
+    ///
+    /// `circuits` 为 (熔断器 key "app_type:provider_id", 统计) 列表。
+    pub fn render(
+        &self,
+        active_connections: usize,
+        uptime_seconds: u64,
+        circuits: &[(String, CircuitBreakerStats)],
+    ) -> String {
+        let inner = self.lock();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "cc_switch_proxy_uptime_seconds",
+            "gauge",
+            "Seconds since the proxy started",
+        );
+        let _ = writeln!(out, "cc_switch_proxy_uptime_seconds {uptime_seconds}");
+        header(
+            &mut out,
+            "cc_switch_proxy_active_connections",
+            "gauge",
+            "Client requests currently in flight",
+        );
+        let _ = writeln!(
+            out,
+            "cc_switch_proxy_active_connections {active_connections}"
+        );
+
+        header(
+            &mut out,
+            "cc_switch_proxy_requests_total",
+            "counter",
+            "Completed client requests by final upstream status code",
+        );
+        for ((app, provider, model, status), count) in &inner.requests {
+            let _ = writeln!(
+                out,
+                "cc_switch_proxy_requests_total{{{},status=\"{status}\"}} {count}",
+                series_labels(app, provider, model)
+            );
+        }
+
+        header(
+            &mut out,
+            "cc_switch_proxy_failovers_total",
+            "counter",
+            "Retries that moved a request to the next provider in the queue",
+        );
+        for ((app, from, to), count) in &inner.failovers {
+            let _ = writeln!(
+                out,
+                "cc_switch_proxy_failovers_total{{app=\"{}\",from_provider=\"{}\",to_provider=\"{}\"}} {count}",
+                escape(app),
+                escape(from),
+                escape(to)
+            );
+        }
+
+        write_histograms(
+            &mut out,
+            "cc_switch_proxy_request_duration_seconds",
+            "Total request latency including streaming",
+            &inner.duration,
+        );
+        write_histograms(
+            &mut out,
+            "cc_switch_proxy_first_token_seconds",
+            "Latency until the first streamed token",
+            &inner.first_token,
+        );
+
+        header(
+            &mut out,
+            "cc_switch_proxy_tokens_total",
+            "counter",
+            "Tokens reported by upstream usage",
+        );
+        for ((app, provider, model, kind), count) in &inner.tokens {
+            let _ = writeln!(
+                out,
+                "cc_switch_proxy_tokens_total{{{},type=\"{kind}\"}} {count}",
+                series_labels(app, provider, model)
+            );
+        }
+
+        header(
+            &mut out,
+            "cc_switch_proxy_cost_usd_total",
+            "counter",
+            "Calculated request cost in USD",
+        );
+        for ((app, provider, model), cost) in &inner.cost_usd {
+            let _ = writeln!(
+                out,
+                "cc_switch_proxy_cost_usd_total{{{}}} {cost}",
+                series_labels(app, provider, model)
+            );
+        }
+
+        header(
+            &mut out,
+            "cc_switch_proxy_circuit_state",
+            "gauge",
+            "Circuit breaker state (0 = closed, 1 = half-open, 2 = open)",
+        );
+        for (key, stats) in circuits {
+            let (app, provider) = key.split_once(':').unwrap_or((key.as_str(), ""));
+            let state = match stats.state {
+                CircuitState::Closed => 0,
+                CircuitState::HalfOpen => 1,
+                CircuitState::Open => 2,
+            };
+            let _ = writeln!(
+                out,
+                "cc_switch_proxy_circuit_state{{app=\"{}\",provider=\"{}\"}} {state}",
+                escape(app),
+                escape(provider)
+            );
+        }
+        header(
+            &mut out,
+            "cc_switch_proxy_circuit_consecutive_failures",
+            "gauge",
+            "Consecutive failures counted by the circuit breaker",
+        );
+        for (key, stats) in circuits {
+            let (app, provider) = key.split_once(':').unwrap_or((key.as_str(), ""));
+            let _ = writeln!(
+                out,
+                "cc_switch_proxy_circuit_consecutive_failures{{app=\"{}\",provider=\"{}\"}} {}",
+                escape(app),
+                escape(provider),
+                stats.consecutive_failures
+            );
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn series_labels(app: &str, provider: &str, model: &str) -> String {
+    format!(
+        "app=\"{}\",provider=\"{}\",model=\"{}\"",
+        escape(app),
+        escape(provider),
+        escape(model)
+    )
+}
+
+fn write_histograms(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    series: &BTreeMap<SeriesKey, Histogram>,
+) {
+    header(out, name, "histogram", help);
+    for ((app, provider, model), histogram) in series {
+        let labels = series_labels(app, provider, model);
+        let mut cumulative = 0u64;
+        for (bound, count) in histogram.bounds.iter().zip(&histogram.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {cumulative}");
+        }
+        cumulative += histogram.counts.last().copied().unwrap_or(0);
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {cumulative}");
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", histogram.sum);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {cumulative}");
+    }
+}
+
+/// 转义标签值中的 `\`、`"` 与换行
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_emits_counters_histograms_and_circuit_state() {
+        let metrics = ProxyMetrics::new();
+        let usage = TokenUsage {
+            input_tokens: 100,
+            output_tokens: 20,
+            ..Default::default()
+        };
+        metrics.record_request(
+            "claude",
+            "p1",
+            "claude-sonnet-4-5",
+            200,
+            1_500,
+            Some(300),
+            Some(&usage),
+        );
+        metrics.record_request("claude", "p1", "claude-sonnet-4-5", 529, 700, None, None);
+        metrics.record_cost("claude", "p1", "claude-sonnet-4-5", 0.25);
+        metrics.record_failover("claude", "p1", "p2");
+
+        let circuits = vec![(
+            "claude:p1".to_string(),
+            CircuitBreakerStats {
+                state: CircuitState::Open,
+                consecutive_failures: 5,
+                consecutive_successes: 0,
+                total_requests: 10,
+                failed_requests: 6,
+            },
+        )];
+        let text = metrics.render(1, 42, &circuits);
+
+        let labels = r#"app="claude",provider="p1",model="claude-sonnet-4-5""#;
+        assert!(text.contains(&format!(
+            "cc_switch_proxy_requests_total{{{labels},status=\"200\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "cc_switch_proxy_requests_total{{{labels},status=\"529\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "cc_switch_proxy_request_duration_seconds_bucket{{{labels},le=\"1\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "cc_switch_proxy_request_duration_seconds_count{{{labels}}} 2"
+        )));
+        assert!(text.contains(&format!(
+            "cc_switch_proxy_first_token_seconds_bucket{{{labels},le=\"0.5\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "cc_switch_proxy_tokens_total{{{labels},type=\"input\"}} 100"
+        )));
+        assert!(!text.contains("type=\"cache_read\""));
+        assert!(text.contains(&format!("cc_switch_proxy_cost_usd_total{{{labels}}} 0.25")));
+        assert!(text.contains(
+            "cc_switch_proxy_failovers_total{app=\"claude\",from_provider=\"p1\",to_provider=\"p2\"} 1"
+        ));
+        assert!(text.contains("cc_switch_proxy_circuit_state{app=\"claude\",provider=\"p1\"} 2"));
+        assert!(text.contains("cc_switch_proxy_uptime_seconds 42"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
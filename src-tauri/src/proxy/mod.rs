@@ -3,10 +3,13 @@
 //! 提供本地HTTP代理服务，支持多Provider故障转移和请求透传
 
 pub mod body_filter;
+pub mod budget;
 pub mod cache_injector;
+pub mod capture;
 pub mod circuit_breaker;
 pub(crate) mod content_encoding;
 pub mod copilot_optimizer;
+pub mod endpoint_pool;
 pub mod error;
 pub mod error_mapper;
 pub(crate) mod failover_switch;
@@ -15,19 +18,28 @@ pub mod gemini_url;
 pub mod handler_config;
 pub mod handler_context;
 mod handlers;
+mod health_prober;
 pub mod http_client;
 pub mod hyper_client;
 pub(crate) mod json_canonical;
+pub mod load_balancer;
 pub mod log_codes;
+pub(crate) mod mcp_gateway;
 pub mod media_sanitizer;
+pub mod metrics;
+pub mod model_catalog;
 pub mod model_mapper;
+pub mod model_routes;
 pub mod provider_router;
 pub mod providers;
+pub mod quota_guard;
 pub mod response_processor;
 pub(crate) mod server;
 pub mod session;
+pub mod session_affinity;
 pub(crate) mod sse;
 pub(crate) mod switch_lock;
+pub mod telemetry;
 pub mod thinking_budget_rectifier;
 pub mod thinking_optimizer;
 pub mod thinking_rectifier;
@@ -35,6 +47,14 @@ pub(crate) mod tool_media;
 pub(crate) mod types;
 pub mod usage;
 
+/// 桌面端的 tauri AppHandle，用于发射事件、更新托盘和读取托管账号状态。
+/// 无界面构建（未启用 `gui`）没有 AppHandle，用不可构造的类型占位，`Option<AppHandle>` 恒为 `None`。
+#[cfg(feature = "gui")]
+pub(crate) type AppHandle = tauri::AppHandle;
+#[cfg(not(feature = "gui"))]
+#[derive(Clone)]
+pub(crate) enum AppHandle {}
+
 // 公开导出给外部使用（commands, services等模块需要）
 #[allow(unused_imports)]
 pub use circuit_breaker::{
@@ -0,0 +1,394 @@
+//! 聚合模型列表
+//!
+//! 代理的 `/v1/models` 等端点不再只返回单个供应商（或 Codex 目录文件）的模型，
+//! 而是合并该应用当前供应商、故障转移队列和模型路由规则涉及的所有供应商：
+//! 各供应商通过 [`crate::services::model_fetch::fetch_models`] 拉取（带缓存），
+//! 再加上供应商配置里显式写的模型（`ANTHROPIC_DEFAULT_*_MODEL`、Codex `model`、
+//! `GEMINI_MODEL`），去重后按路由对应的 OpenAI / Anthropic / Gemini 格式返回。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::provider::Provider;
+use crate::proxy::model_mapper::ModelMapping;
+use crate::proxy::providers::{get_adapter, AuthStrategy};
+use crate::services::model_fetch::{self, FetchedModel};
+
+/// 成功拉取的模型列表缓存时长
+const CATALOG_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 拉取失败后的冷却时长，避免每次列模型都去撞不支持 /models 的上游
+const FAILURE_TTL: Duration = Duration::from_secs(2 * 60);
+
+/// 模型列表的响应格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelListFormat {
+    /// `{"object": "list", "data": [{"id", "object": "model", ...}]}`
+    OpenAi,
+    /// `{"data": [{"type": "model", "id", ...}], "has_more", "first_id", "last_id"}`
+    Anthropic,
+    /// `{"models": [{"name": "models/<id>", ...}]}`
+    Gemini,
+}
+
+struct CacheEntry {
+    /// 供应商配置变化（地址 / 密钥）后缓存失效
+    fingerprint: u64,
+    fetched_at: Instant,
+    ttl: Duration,
+    models: Vec<FetchedModel>,
+}
+
+/// 按 `app_type:provider_id` 缓存各供应商的模型列表（跨请求保持）
+#[derive(Default)]
+pub struct ModelCatalog {
+    cache: Mutex<HashMap<String, CacheEntry>>,
+}
+
+/// 单个供应商拉取模型列表所需的参数
+struct FetchTarget {
+    key: String,
+    fingerprint: u64,
+    base_url: String,
+    api_key: String,
+    is_full_url: bool,
+    models_url: Option<String>,
+    api_format: Option<&'static str>,
+    user_agent: Option<reqwest::header::HeaderValue>,
+}
+
+impl ModelCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回应用的聚合模型列表（已去重，显式配置的模型在前）
+    pub async fn list(&self, db: &Database, app_type: &AppType) -> Vec<FetchedModel> {
+        let providers = catalog_providers(db, app_type).await;
+        let app = app_type.as_str();
+
+        let mut models: Vec<FetchedModel> = Vec::new();
+        for provider in &providers {
+            for id in configured_models(app_type, provider) {
+                models.push(FetchedModel {
+                    id,
+                    owned_by: Some(provider.name.clone()),
+                });
+            }
+        }
+
+        let targets: Vec<FetchTarget> = providers
+            .iter()
+            .filter_map(|provider| fetch_target(app_type, provider))
+            .collect();
+        let (cached, stale): (Vec<_>, Vec<_>) = {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            targets.into_iter().partition(|target| {
+                cache.get(&target.key).is_some_and(|entry| {
+                    entry.fingerprint == target.fingerprint
+                        && entry.fetched_at.elapsed() < entry.ttl
+                })
+            })
+        };
+
+        let fetched = futures::future::join_all(stale.iter().map(|target| async move {
+            let result = model_fetch::fetch_models(
+                &target.base_url,
+                &target.api_key,
+                target.is_full_url,
+                target.models_url.as_deref(),
+                target.user_agent.clone(),
+                target.api_format,
+                None,
+            )
+            .await;
+            (target, result)
+        }))
+        .await;
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        for (target, result) in fetched {
+            let (models, ttl) = match result {
+                Ok(models) => (models, CATALOG_TTL),
+                Err(e) => {
+                    log::debug!("[{app}] 拉取 {} 的模型列表失败: {e}", target.key);
+                    (Vec::new(), FAILURE_TTL)
+                }
+            };
+            cache.insert(
+                target.key.clone(),
+                CacheEntry {
+                    fingerprint: target.fingerprint,
+                    fetched_at: Instant::now(),
+                    ttl,
+                    models,
+                },
+            );
+        }
+        for target in stale.iter().chain(cached.iter()) {
+            if let Some(entry) = cache.get(&target.key) {
+                models.extend(entry.models.iter().cloned());
+            }
+        }
+        drop(cache);
+
+        dedup_models(models)
+    }
+}
+
+/// 参与聚合的供应商：当前供应商 → 故障转移队列 → 模型路由规则引用的供应商
+async fn catalog_providers(db: &Database, app_type: &AppType) -> Vec<Provider> {
+    let app = app_type.as_str();
+    let all = match db.get_all_providers(app) {
+        Ok(all) => all,
+        Err(e) => {
+            log::warn!("[{app}] 读取供应商失败: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut ids: Vec<String> = Vec::new();
+    if let Ok(Some(current)) = crate::settings::get_effective_current_provider(db, app_type) {
+        ids.push(current);
+    }
+    if let Ok(queue) = db.get_failover_queue(app) {
+        ids.extend(queue.into_iter().map(|item| item.provider_id));
+    }
+    if let Ok(config) = db.get_proxy_config_for_app(app).await {
+        for route in config.model_routes.into_iter().filter(|r| r.enabled) {
+            ids.extend(route.provider_ids);
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    ids.into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .filter_map(|id| all.get(&id).cloned())
+        .collect()
+}
+
+/// 供应商配置里显式写出的模型名
+fn configured_models(app_type: &AppType, provider: &Provider) -> Vec<String> {
+    let settings = &provider.settings_config;
+    let models: Vec<String> = match app_type {
+        AppType::Claude => {
+            let mapping = ModelMapping::from_provider(provider);
+            [
+                mapping.default_model,
+                mapping.opus_model,
+                mapping.sonnet_model,
+                mapping.haiku_model,
+                mapping.fable_model,
+                mapping.subagent_model,
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        }
+        AppType::Codex => settings
+            .get("config")
+            .and_then(Value::as_str)
+            .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok())
+            .and_then(|doc| doc.get("model").and_then(|m| m.as_str()).map(String::from))
+            .into_iter()
+            .collect(),
+        AppType::Gemini => settings
+            .pointer("/env/GEMINI_MODEL")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    };
+    models
+        .into_iter()
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// 只有普通 API Key 认证的供应商才能直接拉取；OAuth / Copilot 类跳过
+fn fetch_target(app_type: &AppType, provider: &Provider) -> Option<FetchTarget> {
+    let adapter = get_adapter(app_type)?;
+    let base_url = adapter.extract_base_url(provider).ok()?;
+    let auth = adapter.extract_auth(provider)?;
+    let api_format = match auth.strategy {
+        AuthStrategy::Anthropic => Some("anthropic-messages"),
+        AuthStrategy::Google => Some("google-generative-ai"),
+        AuthStrategy::ClaudeAuth | AuthStrategy::Bearer => None,
+        _ => return None,
+    };
+    let meta = provider.meta.as_ref();
+    // Gemini 原生 API 的模型列表在 /v1beta/models（fetch_models 兼容其响应格式）
+    let models_url = (*app_type == AppType::Gemini).then(|| gemini_models_url(&base_url));
+
+    let fingerprint = {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (&base_url, &auth.api_key, &models_url).hash(&mut hasher);
+        hasher.finish()
+    };
+    Some(FetchTarget {
+        key: format!("{}:{}", app_type.as_str(), provider.id),
+        fingerprint,
+        base_url,
+        api_key: auth.api_key,
+        is_full_url: meta.and_then(|m| m.is_full_url).unwrap_or(false),
+        models_url,
+        api_format,
+        user_agent: meta.and_then(|m| m.custom_user_agent_header().ok().flatten()),
+    })
+}
+
+fn gemini_models_url(base_url: &str) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    if base.ends_with("/v1beta") || base.ends_with("/v1") {
+        format!("{base}/models?pageSize=1000")
+    } else {
+        format!("{base}/v1beta/models?pageSize=1000")
+    }
+}
+
+/// 按模型 ID 去重，保留首次出现的条目
+fn dedup_models(models: Vec<FetchedModel>) -> Vec<FetchedModel> {
+    let mut seen = std::collections::HashSet::new();
+    models
+        .into_iter()
+        .filter(|model| seen.insert(model.id.clone()))
+        .collect()
+}
+
+/// 按格式渲染模型列表响应
+pub fn render_model_list(models: &[FetchedModel], format: ModelListFormat) -> Value {
+    match format {
+        ModelListFormat::OpenAi => json!({
+            "object": "list",
+            "data": models
+                .iter()
+                .map(|model| json!({
+                    "id": model.id,
+                    "object": "model",
+                    "created": 0,
+                    "owned_by": model.owned_by.as_deref().unwrap_or("cc-switch"),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        ModelListFormat::Anthropic => json!({
+            "data": models
+                .iter()
+                .map(|model| json!({
+                    "type": "model",
+                    "id": model.id,
+                    "display_name": model.id,
+                    "created_at": "1970-01-01T00:00:00Z",
+                }))
+                .collect::<Vec<_>>(),
+            "has_more": false,
+            "first_id": models.first().map(|m| m.id.as_str()),
+            "last_id": models.last().map(|m| m.id.as_str()),
+        }),
+        ModelListFormat::Gemini => json!({
+            "models": models
+                .iter()
+                .map(|model| json!({
+                    "name": format!("models/{}", model.id),
+                    "displayName": model.id,
+                    "supportedGenerationMethods": ["generateContent", "countTokens"],
+                }))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str, owner: &str) -> FetchedModel {
+        FetchedModel {
+            id: id.to_string(),
+            owned_by: Some(owner.to_string()),
+        }
+    }
+
+    #[test]
+    fn configured_models_come_from_each_app_config_shape() {
+        let claude = Provider::with_id(
+            "c".to_string(),
+            "Relay".to_string(),
+            json!({ "env": {
+                "ANTHROPIC_MODEL": "glm-4.6",
+                "ANTHROPIC_DEFAULT_HAIKU_MODEL": "glm-4.5-air",
+                "ANTHROPIC_DEFAULT_SONNET_MODEL": ""
+            }}),
+            None,
+        );
+        assert_eq!(
+            configured_models(&AppType::Claude, &claude),
+            vec!["glm-4.6", "glm-4.5-air"]
+        );
+
+        let codex = Provider::with_id(
+            "x".to_string(),
+            "Codex".to_string(),
+            json!({ "config": "model = \"gpt-5-codex\"\nmodel_provider = \"relay\"\n" }),
+            None,
+        );
+        assert_eq!(
+            configured_models(&AppType::Codex, &codex),
+            vec!["gpt-5-codex"]
+        );
+
+        let gemini = Provider::with_id(
+            "g".to_string(),
+            "Gemini".to_string(),
+            json!({ "env": { "GEMINI_MODEL": "gemini-2.5-pro" } }),
+            None,
+        );
+        assert_eq!(
+            configured_models(&AppType::Gemini, &gemini),
+            vec!["gemini-2.5-pro"]
+        );
+    }
+
+    #[test]
+    fn renders_deduplicated_models_in_each_format() {
+        let models = dedup_models(vec![
+            model("claude-sonnet-4-5", "A"),
+            model("claude-haiku-4-5", "A"),
+            model("claude-sonnet-4-5", "B"),
+        ]);
+        assert_eq!(models.len(), 2);
+
+        let openai = render_model_list(&models, ModelListFormat::OpenAi);
+        assert_eq!(openai["object"], "list");
+        assert_eq!(openai["data"][0]["id"], "claude-sonnet-4-5");
+        assert_eq!(openai["data"][0]["owned_by"], "A");
+
+        let anthropic = render_model_list(&models, ModelListFormat::Anthropic);
+        assert_eq!(anthropic["data"][1]["type"], "model");
+        assert_eq!(anthropic["first_id"], "claude-sonnet-4-5");
+        assert_eq!(anthropic["last_id"], "claude-haiku-4-5");
+        assert_eq!(anthropic["has_more"], false);
+
+        let gemini = render_model_list(&models, ModelListFormat::Gemini);
+        assert_eq!(gemini["models"][0]["name"], "models/claude-sonnet-4-5");
+    }
+
+    #[test]
+    fn gemini_models_url_respects_version_suffix() {
+        assert_eq!(
+            gemini_models_url("https://generativelanguage.googleapis.com/"),
+            "https://generativelanguage.googleapis.com/v1beta/models?pageSize=1000"
+        );
+        assert_eq!(
+            gemini_models_url("https://relay.example.com/v1beta"),
+            "https://relay.example.com/v1beta/models?pageSize=1000"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! 按请求模型分流
+//!
+//! 在故障转移队列之前按 `AppProxyConfig.model_routes` 顺序匹配请求模型，
+//! 命中的规则用自己的供应商列表（即该规则的故障转移顺序）承接请求，
+//! 例如把 Claude Code 的 `claude-haiku-*` 子代理/后台请求发往更便宜的中转。
+
+use crate::proxy::types::ModelRoute;
+
+/// 返回第一条启用且匹配 `model` 的规则
+pub fn find_route<'a>(routes: &'a [ModelRoute], model: &str) -> Option<&'a ModelRoute> {
+    routes.iter().find(|route| {
+        route.enabled && !route.provider_ids.is_empty() && pattern_matches(&route.pattern, model)
+    })
+}
+
+/// 校验规则：模式与供应商列表不能为空，同一规则内供应商不重复
+#[cfg(feature = "gui")]
+pub fn validate_routes(routes: &[ModelRoute]) -> Result<(), String> {
+    for route in routes {
+        let pattern = route.pattern.trim();
+        if pattern.is_empty() {
+            return Err("模型路由规则的匹配模式不能为空".to_string());
+        }
+        if route.provider_ids.is_empty() {
+            return Err(format!("模型路由规则 {pattern} 至少需要一个供应商"));
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(dup) = route
+            .provider_ids
+            .iter()
+            .find(|id| !seen.insert(id.as_str()))
+        {
+            return Err(format!("模型路由规则 {pattern} 中供应商 {dup} 重复"));
+        }
+    }
+    Ok(())
+}
+
+/// `*` 匹配任意长度字符，其余字符按字面比较（不区分大小写）
+pub fn pattern_matches(pattern: &str, model: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let model = model.trim().to_ascii_lowercase();
+    if pattern.is_empty() {
+        return false;
+    }
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = model.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    // 没有 `*` 时必须完全相等
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(pattern: &str, providers: &[&str]) -> ModelRoute {
+        ModelRoute {
+            pattern: pattern.to_string(),
+            provider_ids: providers.iter().map(|id| id.to_string()).collect(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn glob_patterns_match_prefix_suffix_and_infix() {
+        assert!(pattern_matches(
+            "claude-haiku-*",
+            "claude-haiku-4-5-20251001"
+        ));
+        assert!(pattern_matches("*-1m", "claude-sonnet-4-5-1M"));
+        assert!(pattern_matches("claude-*-4-*", "claude-opus-4-1"));
+        assert!(pattern_matches("gpt-5", "GPT-5"));
+        assert!(pattern_matches("*", "anything"));
+
+        assert!(!pattern_matches("gpt-5", "gpt-5-codex"));
+        assert!(!pattern_matches("claude-haiku-*", "claude-sonnet-4-5"));
+        assert!(!pattern_matches("*-1m-*", "model-1m"));
+        assert!(!pattern_matches("a*a", "a"));
+        assert!(!pattern_matches("", "claude"));
+    }
+
+    #[test]
+    fn first_enabled_matching_route_wins() {
+        let mut disabled = route("claude-haiku-*", &["disabled"]);
+        disabled.enabled = false;
+        let routes = vec![
+            disabled,
+            route("claude-haiku-*", &["cheap", "official"]),
+            route("claude-*", &["official"]),
+        ];
+
+        let found = find_route(&routes, "claude-haiku-4-5").expect("route");
+        assert_eq!(found.provider_ids, vec!["cheap", "official"]);
+        assert_eq!(
+            find_route(&routes, "claude-opus-4-1").map(|r| r.pattern.as_str()),
+            Some("claude-*")
+        );
+        assert!(find_route(&routes, "gpt-5").is_none());
+    }
+
+    #[test]
+    fn validate_rejects_empty_and_duplicate_entries() {
+        assert!(validate_routes(&[route("claude-*", &["a", "b"])]).is_ok());
+        assert!(validate_routes(&[route("  ", &["a"])]).is_err());
+        assert!(validate_routes(&[route("claude-*", &[])]).is_err());
+        assert!(validate_routes(&[route("claude-*", &["a", "a"])]).is_err());
+    }
+}
@@ -6,7 +6,16 @@ use crate::app_config::AppType;
 use crate::database::Database;
 use crate::error::AppError;
 use crate::provider::Provider;
-use crate::proxy::circuit_breaker::{AllowResult, CircuitBreaker, CircuitBreakerConfig};
+use crate::proxy::budget::BudgetTracker;
+use crate::proxy::circuit_breaker::{
+    AllowResult, CircuitBreaker, CircuitBreakerConfig, CircuitState,
+};
+use crate::proxy::endpoint_pool::EndpointPool;
+use crate::proxy::load_balancer::LoadBalancer;
+use crate::proxy::model_routes;
+use crate::proxy::quota_guard::QuotaGuard;
+use crate::proxy::session_affinity::SessionAffinity;
+use crate::proxy::types::RoutingStrategy;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -20,12 +29,30 @@ pub(crate) fn provider_supports_failover(app_type: &str, provider: &Provider) ->
         || !crate::proxy::providers::is_codex_official_provider(provider)
 }
 
+/// 一次请求的供应商选择结果
+pub struct ProviderSelection {
+    /// 按尝试顺序排列的供应商
+    pub providers: Vec<Provider>,
+    /// 命中的模型路由规则（其 `pattern`）；`None` 表示走默认路由
+    pub model_route: Option<String>,
+}
+
 /// 供应商路由器
 pub struct ProviderRouter {
     /// 数据库连接
     db: Arc<Database>,
     /// 熔断器管理器 - key 格式: "app_type:provider_id"
     circuit_breakers: Arc<RwLock<HashMap<String, Arc<CircuitBreaker>>>>,
+    /// 消费限额守卫（缓存各供应商今日/本月花费）
+    budget: BudgetTracker,
+    /// 故障转移队列负载均衡状态
+    load_balancer: LoadBalancer,
+    /// 会话粘滞表（Session ID → 首个成功响应的供应商）
+    session_affinity: SessionAffinity,
+    /// 供应商端点镜像池（独立于熔断器的端点级健康状态）
+    endpoint_pool: Arc<EndpointPool>,
+    /// 订阅/套餐额度缓存（由后台任务定期刷新）
+    quota_guard: QuotaGuard,
 }
 
 impl ProviderRouter {
@@ -34,6 +61,11 @@ impl ProviderRouter {
         Self {
             db,
             circuit_breakers: Arc::new(RwLock::new(HashMap::new())),
+            budget: BudgetTracker::new(),
+            load_balancer: LoadBalancer::new(),
+            session_affinity: SessionAffinity::new(),
+            endpoint_pool: Arc::new(EndpointPool::new()),
+            quota_guard: QuotaGuard::new(),
         }
     }
 
@@ -41,11 +73,22 @@ impl ProviderRouter {
     ///
     /// 返回按优先级排序的可用供应商列表：
     /// - 故障转移关闭时：仅返回当前供应商
-    /// - 故障转移开启时：仅使用故障转移队列，按队列顺序依次尝试（P1 → P2 → ...）
+    /// - 故障转移开启时：仅使用故障转移队列，按应用的路由策略排序
+    ///   （默认 priority 即队列顺序 P1 → P2 → ...）
+    ///
+    /// 超出每日/每月消费限额的供应商视为不可用；若因此没有任何可用供应商，
+    /// 返回 `AppError::ProviderBudgetExceeded`。额度窗口已达 `quota_threshold` 的供应商
+    /// 仍可用，但排在其余供应商之后。
     pub async fn select_providers(&self, app_type: &str) -> Result<Vec<Provider>, AppError> {
+        Ok(self.select_queue(app_type).await?.0)
+    }
+
+    /// [`Self::select_providers`] 的实现，同时返回生效的额度阈值（故障转移关闭时为 0）
+    async fn select_queue(&self, app_type: &str) -> Result<(Vec<Provider>, u32), AppError> {
         let mut result = Vec::new();
         let mut total_providers = 0usize;
         let mut circuit_open_count = 0usize;
+        let mut budget_exceeded = None;
         let current_id = AppType::from_str(app_type)
             .ok()
             .and_then(|app_enum| {
@@ -61,13 +104,18 @@ impl ProviderRouter {
             .flatten();
 
         // 检查该应用的自动故障转移开关是否开启（从 proxy_config 表读取）
-        let auto_failover_enabled = match self.db.get_proxy_config_for_app(app_type).await {
-            Ok(config) => config.auto_failover_enabled,
-            Err(e) => {
-                log::error!("[{app_type}] 读取 proxy_config 失败: {e}，默认禁用故障转移");
-                false
-            }
-        };
+        let (auto_failover_enabled, routing_strategy, mut quota_threshold) =
+            match self.db.get_proxy_config_for_app(app_type).await {
+                Ok(config) => (
+                    config.auto_failover_enabled,
+                    config.routing_strategy,
+                    config.quota_threshold,
+                ),
+                Err(e) => {
+                    log::error!("[{app_type}] 读取 proxy_config 失败: {e}，默认禁用故障转移");
+                    (false, RoutingStrategy::Priority, 0)
+                }
+            };
 
         if auto_failover_enabled
             && current_provider
@@ -77,8 +125,13 @@ impl ProviderRouter {
             // A selected Codex Official account is an explicit account choice.
             // Keep it as a single route even if an old failover setting remains
             // enabled; retrying would reuse its inbound token for another card.
+            quota_threshold = 0;
             total_providers = 1;
-            result.push(current_provider.expect("checked above"));
+            let current = current_provider.expect("checked above");
+            match self.budget.check(&self.db, app_type, &current).await {
+                Some(exceeded) => budget_exceeded = Some(exceeded),
+                None => result.push(current),
+            }
         } else if auto_failover_enabled {
             // 故障转移开启：仅按队列顺序依次尝试（P1 → P2 → ...）
             let all_providers = self.db.get_all_providers(app_type)?;
@@ -98,8 +151,18 @@ impl ProviderRouter {
                 if !provider_supports_failover(app_type, &provider) {
                     continue;
                 }
+                if provider.secrets_locked() {
+                    log::warn!("[{app_type}] 供应商 {} 的密钥无法解密，跳过", provider.id);
+                    continue;
+                }
                 total_providers += 1;
 
+                if let Some(exceeded) = self.budget.check(&self.db, app_type, &provider).await {
+                    log::info!("[{app_type}] [FO-006] {}，跳过", exceeded.message());
+                    budget_exceeded.get_or_insert(exceeded);
+                    continue;
+                }
+
                 let circuit_key = format!("{app_type}:{}", provider.id);
                 let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
 
@@ -109,15 +172,36 @@ impl ProviderRouter {
                     circuit_open_count += 1;
                 }
             }
+
+            result = self
+                .load_balancer
+                .order(&self.db, app_type, routing_strategy, result);
+            result = self
+                .quota_guard
+                .deprioritize(app_type, quota_threshold, result)
+                .await;
         } else {
             // 故障转移关闭：仅使用当前供应商，跳过熔断器检查
-            if let Some(current) = current_provider {
+            quota_threshold = 0;
+            if let Some(current) = current_provider.filter(|provider| {
+                if provider.secrets_locked() {
+                    log::warn!("[{app_type}] 当前供应商 {} 的密钥无法解密", provider.id);
+                }
+                !provider.secrets_locked()
+            }) {
                 total_providers = 1;
-                result.push(current);
+                match self.budget.check(&self.db, app_type, &current).await {
+                    Some(exceeded) => budget_exceeded = Some(exceeded),
+                    None => result.push(current),
+                }
             }
         }
 
         if result.is_empty() {
+            if let Some(exceeded) = budget_exceeded {
+                log::warn!("[{app_type}] [FO-006] {}", exceeded.message());
+                return Err(AppError::ProviderBudgetExceeded(exceeded.message()));
+            }
             if total_providers > 0 && circuit_open_count == total_providers {
                 log::warn!("[{app_type}] [FO-004] 所有供应商均已熔断");
                 return Err(AppError::AllProvidersCircuitOpen);
@@ -127,7 +211,189 @@ impl ProviderRouter {
             }
         }
 
-        Ok(result)
+        Ok((result, quota_threshold))
+    }
+
+    /// 选择可用的供应商，并按会话粘滞表调整顺序
+    ///
+    /// `session_id` 仅应传入客户端提供的稳定会话 ID；已绑定且仍可用的供应商排到首位，
+    /// 绑定的供应商熔断时回落到 [`Self::select_providers`] 的顺序；
+    /// 绑定的供应商额度窗口已达阈值时同样不再优先。
+    pub async fn select_providers_for_session(
+        &self,
+        app_type: &str,
+        session_id: Option<&str>,
+    ) -> Result<Vec<Provider>, AppError> {
+        let (mut providers, quota_threshold) = self.select_queue(app_type).await?;
+        if let Some(session_id) = session_id {
+            self.session_affinity
+                .apply(app_type, session_id, &mut providers)
+                .await;
+            providers = self
+                .quota_guard
+                .deprioritize(app_type, quota_threshold, providers)
+                .await;
+        }
+        Ok(providers)
+    }
+
+    /// 按请求模型选择供应商
+    ///
+    /// 先按应用的模型路由规则匹配：命中时使用规则自己的供应商列表（列表顺序即故障转移顺序，
+    /// 同样跳过超出限额的供应商、额度已达阈值的供应商排到末尾；故障转移开启时跳过已熔断的
+    /// 供应商），且不参与会话粘滞；
+    /// 未命中或规则内没有可用供应商时回落到 [`Self::select_providers_for_session`]。
+    pub async fn select_providers_for_model(
+        &self,
+        app_type: &str,
+        model: &str,
+        session_id: Option<&str>,
+    ) -> Result<ProviderSelection, AppError> {
+        if let Some((pattern, providers)) = self.select_route_providers(app_type, model).await? {
+            return Ok(ProviderSelection {
+                providers,
+                model_route: Some(pattern),
+            });
+        }
+        Ok(ProviderSelection {
+            providers: self
+                .select_providers_for_session(app_type, session_id)
+                .await?,
+            model_route: None,
+        })
+    }
+
+    /// 命中模型路由规则时返回（规则模式, 可用供应商）
+    async fn select_route_providers(
+        &self,
+        app_type: &str,
+        model: &str,
+    ) -> Result<Option<(String, Vec<Provider>)>, AppError> {
+        let config = match self.db.get_proxy_config_for_app(app_type).await {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!("[{app_type}] 读取 proxy_config 失败: {e}，跳过模型路由");
+                return Ok(None);
+            }
+        };
+        let Some(route) = model_routes::find_route(&config.model_routes, model) else {
+            return Ok(None);
+        };
+
+        let all_providers = self.db.get_all_providers(app_type)?;
+        let mut result = Vec::new();
+        for provider_id in &route.provider_ids {
+            let Some(provider) = all_providers.get(provider_id).cloned() else {
+                log::warn!(
+                    "[{app_type}] 模型路由 {} 引用的供应商 {provider_id} 不存在",
+                    route.pattern
+                );
+                continue;
+            };
+            if provider.secrets_locked() {
+                log::warn!("[{app_type}] 供应商 {provider_id} 的密钥无法解密，跳过");
+                continue;
+            }
+            // Codex Official 账号不能参与重试：只能作为规则里唯一的目标
+            if !provider_supports_failover(app_type, &provider) {
+                if result.is_empty() && route.provider_ids.len() == 1 {
+                    result.push(provider);
+                }
+                continue;
+            }
+            if let Some(exceeded) = self.budget.check(&self.db, app_type, &provider).await {
+                log::info!("[{app_type}] [FO-006] {}，跳过", exceeded.message());
+                continue;
+            }
+            if config.auto_failover_enabled {
+                let circuit_key = format!("{app_type}:{}", provider.id);
+                let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
+                if !breaker.is_available().await {
+                    continue;
+                }
+            }
+            result.push(provider);
+        }
+
+        let result = self
+            .quota_guard
+            .deprioritize(app_type, config.quota_threshold, result)
+            .await;
+        if result.is_empty() {
+            log::warn!(
+                "[{app_type}] 模型 {model} 命中路由 {}，但其中没有可用供应商，回落到默认路由",
+                route.pattern
+            );
+            return Ok(None);
+        }
+        log::debug!(
+            "[{app_type}] 模型 {model} 命中路由 {} → {:?}",
+            route.pattern,
+            result.iter().map(|p| p.id.as_str()).collect::<Vec<_>>()
+        );
+        Ok(Some((route.pattern.clone(), result)))
+    }
+
+    /// 订阅/套餐额度缓存（后台刷新任务写入）
+    pub fn quota_guard(&self) -> &QuotaGuard {
+        &self.quota_guard
+    }
+
+    /// 将会话绑定到成功响应它的供应商
+    ///
+    /// 会话已绑定到其他供应商时，只有原供应商的熔断器已打开（连续失败达到阈值）才改绑；
+    /// 否则视为偶发失败后的一次性故障转移，保留原绑定，原供应商恢复后会话继续由它处理。
+    pub async fn bind_session(&self, app_type: &str, session_id: &str, provider_id: &str) {
+        if let Some(bound) = self
+            .session_affinity
+            .bound_provider(app_type, session_id)
+            .await
+            .filter(|bound| bound != provider_id)
+        {
+            let circuit_key = format!("{app_type}:{bound}");
+            let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
+            if breaker.get_state().await != CircuitState::Open {
+                log::debug!(
+                    "[{app_type}] 会话 {session_id} 由 {provider_id} 临时响应，{bound} 未熔断，保留原绑定"
+                );
+                return;
+            }
+        }
+        self.session_affinity
+            .bind(app_type, session_id, provider_id)
+            .await;
+    }
+
+    /// 返回供应商本次请求应依次尝试的 base URL（主地址 + 自定义端点镜像）
+    pub async fn endpoint_candidates(
+        &self,
+        app_type: &str,
+        provider: &Provider,
+        primary: &str,
+    ) -> Vec<String> {
+        self.endpoint_pool
+            .candidates(app_type, provider, primary)
+            .await
+    }
+
+    /// 记录端点镜像请求成功及延迟
+    pub async fn record_endpoint_success(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        url: &str,
+        latency: std::time::Duration,
+    ) {
+        self.endpoint_pool
+            .record_success(app_type, provider_id, url, latency)
+            .await;
+    }
+
+    /// 记录端点镜像连接失败/超时
+    pub async fn record_endpoint_failure(&self, app_type: &str, provider_id: &str, url: &str) {
+        self.endpoint_pool
+            .record_failure(app_type, provider_id, url)
+            .await;
     }
 
     /// 请求执行前获取熔断器“放行许可”
@@ -183,6 +449,54 @@ impl ProviderRouter {
         Ok(())
     }
 
+    /// 记录后台健康探测结果
+    ///
+    /// - 不可达：立即打开熔断器，并在 `hold` 内保持打开，真实请求不必先撞上首字节超时；
+    /// - 可达：仅关闭此前由探测打开的熔断器，真实流量打开的熔断器仍按超时半开恢复。
+    ///
+    /// 两种情况都会同步 `provider_health`（可达且无需关闭时不写）。
+    pub async fn record_probe_result(
+        &self,
+        provider_id: &str,
+        app_type: &str,
+        reachable: bool,
+        error_msg: Option<String>,
+        hold: std::time::Duration,
+    ) -> Result<(), AppError> {
+        let failure_threshold = match self.db.get_proxy_config_for_app(app_type).await {
+            Ok(app_config) => app_config.circuit_failure_threshold,
+            Err(_) => 5, // 默认值
+        };
+
+        let circuit_key = format!("{app_type}:{provider_id}");
+        let breaker = self.get_or_create_circuit_breaker(&circuit_key).await;
+        if reachable {
+            // 可达只撤销探测自己记下的不健康，不覆盖真实流量得出的健康统计
+            if !breaker.release_probe_hold().await {
+                return Ok(());
+            }
+        } else {
+            breaker.force_open(hold).await;
+        }
+
+        self.db
+            .update_provider_health_with_threshold(
+                provider_id,
+                app_type,
+                reachable,
+                error_msg,
+                failure_threshold,
+            )
+            .await
+    }
+
+    /// 将一次请求的花费计入限额缓存
+    pub async fn record_spend(&self, provider_id: &str, app_type: &str, cost_usd: f64) {
+        self.budget
+            .record_spend(app_type, provider_id, cost_usd)
+            .await;
+    }
+
     /// 重置熔断器（手动恢复）
     pub async fn reset_circuit_breaker(&self, circuit_key: &str) {
         let breakers = self.circuit_breakers.read().await;
@@ -251,6 +565,25 @@ impl ProviderRouter {
         }
     }
 
+    /// 获取全部已创建熔断器的统计（key 格式: "app_type:provider_id"）
+    pub async fn circuit_breaker_snapshot(
+        &self,
+    ) -> Vec<(String, crate::proxy::circuit_breaker::CircuitBreakerStats)> {
+        let breakers: Vec<_> = self
+            .circuit_breakers
+            .read()
+            .await
+            .iter()
+            .map(|(key, breaker)| (key.clone(), breaker.clone()))
+            .collect();
+        let mut snapshot = Vec::with_capacity(breakers.len());
+        for (key, breaker) in breakers {
+            snapshot.push((key, breaker.get_stats().await));
+        }
+        snapshot.sort_by(|a, b| a.0.cmp(&b.0));
+        snapshot
+    }
+
     /// 获取或创建熔断器
     async fn get_or_create_circuit_breaker(&self, key: &str) -> Arc<CircuitBreaker> {
         // 先尝试读锁获取
@@ -296,6 +629,7 @@ mod tests {
     use super::*;
     use crate::database::Database;
     use crate::provider::{AuthBinding, AuthBindingSource, ProviderMeta};
+    use crate::proxy::types::ModelRoute;
     use serde_json::json;
     use serial_test::serial;
     use std::env;
@@ -539,6 +873,253 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn round_robin_strategy_rotates_failover_queue() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        for id in ["a", "b"] {
+            let provider = Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None);
+            db.save_provider("claude", &provider).unwrap();
+            db.add_to_failover_queue("claude", id).unwrap();
+        }
+
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.auto_failover_enabled = true;
+        config.routing_strategy = RoutingStrategy::RoundRobin;
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        let first = router.select_providers("claude").await.unwrap();
+        let second = router.select_providers("claude").await.unwrap();
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+        assert_ne!(first[0].id, second[0].id);
+    }
+
+    fn provider_with_daily_limit(id: &str, limit: &str) -> Provider {
+        let mut provider = Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None);
+        provider.meta = Some(ProviderMeta {
+            limit_daily_usd: Some(limit.to_string()),
+            ..Default::default()
+        });
+        provider
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn over_budget_provider_is_skipped_in_failover_queue() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        db.save_provider("claude", &provider_with_daily_limit("a", "1"))
+            .unwrap();
+        db.save_provider(
+            "claude",
+            &Provider::with_id("b".to_string(), "B".to_string(), json!({}), None),
+        )
+        .unwrap();
+        db.add_to_failover_queue("claude", "a").unwrap();
+        db.add_to_failover_queue("claude", "b").unwrap();
+
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.auto_failover_enabled = true;
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        assert_eq!(router.select_providers("claude").await.unwrap().len(), 2);
+
+        router.record_spend("a", "claude", 1.5).await;
+        let providers = router.select_providers("claude").await.unwrap();
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].id, "b");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn quota_saturated_provider_moves_to_back_until_threshold_disabled() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        for id in ["a", "b"] {
+            let provider = Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None);
+            db.save_provider("claude", &provider).unwrap();
+            db.add_to_failover_queue("claude", id).unwrap();
+        }
+
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.auto_failover_enabled = true;
+        config.quota_threshold = 90;
+        db.update_proxy_config_for_app(config.clone())
+            .await
+            .unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        let quota = crate::services::subscription::SubscriptionQuota {
+            success: true,
+            tiers: vec![crate::services::subscription::QuotaTier {
+                name: "five_hour".to_string(),
+                utilization: 95.0,
+                resets_at: Some((chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339()),
+                used_value_usd: None,
+                max_value_usd: None,
+            }],
+            ..crate::services::subscription::SubscriptionQuota::not_found("claude")
+        };
+        router.quota_guard().record("claude", "a", Ok(&quota)).await;
+
+        let providers = router.select_providers("claude").await.unwrap();
+        let ids: Vec<_> = providers.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a"]);
+
+        router.bind_session("claude", "session-1", "a").await;
+        let providers = router
+            .select_providers_for_session("claude", Some("session-1"))
+            .await
+            .unwrap();
+        assert_eq!(providers[0].id, "b");
+
+        config.quota_threshold = 0;
+        db.update_proxy_config_for_app(config).await.unwrap();
+        let providers = router.select_providers("claude").await.unwrap();
+        assert_eq!(providers[0].id, "a");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn over_budget_current_provider_returns_budget_error() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        db.save_provider("claude", &provider_with_daily_limit("a", "0.5"))
+            .unwrap();
+        db.set_current_provider("claude", "a").unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        assert!(router.select_providers("claude").await.is_ok());
+
+        router.record_spend("a", "claude", 0.75).await;
+        let err = router.select_providers("claude").await.unwrap_err();
+        assert!(matches!(err, AppError::ProviderBudgetExceeded(_)));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn model_routes_pick_their_own_provider_list_before_default_routing() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        for id in ["official", "cheap", "long"] {
+            db.save_provider(
+                "claude",
+                &Provider::with_id(id.to_string(), id.to_string(), json!({}), None),
+            )
+            .unwrap();
+        }
+        db.set_current_provider("claude", "official").unwrap();
+
+        let route = |pattern: &str, providers: &[&str]| ModelRoute {
+            pattern: pattern.to_string(),
+            provider_ids: providers.iter().map(|id| id.to_string()).collect(),
+            enabled: true,
+        };
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.model_routes = vec![
+            route("claude-haiku-*", &["cheap", "official"]),
+            route("*-1m", &["deleted", "long"]),
+            route("claude-opus-*", &["deleted"]),
+        ];
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        let ids = |selection: &ProviderSelection| {
+            selection
+                .providers
+                .iter()
+                .map(|p| p.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let haiku = router
+            .select_providers_for_model("claude", "claude-haiku-4-5", None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&haiku), vec!["cheap", "official"]);
+        assert_eq!(haiku.model_route.as_deref(), Some("claude-haiku-*"));
+
+        // 规则引用的已删除供应商被跳过
+        let long = router
+            .select_providers_for_model("claude", "claude-sonnet-4-5-1m", None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&long), vec!["long"]);
+
+        // 规则内没有可用供应商时回落到默认路由
+        let opus = router
+            .select_providers_for_model("claude", "claude-opus-4-1", None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&opus), vec!["official"]);
+        assert!(opus.model_route.is_none());
+
+        let sonnet = router
+            .select_providers_for_model("claude", "claude-sonnet-4-5", None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&sonnet), vec!["official"]);
+        assert!(sonnet.model_route.is_none());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn session_rebinds_only_after_the_bound_provider_circuit_opens() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        for id in ["a", "b"] {
+            db.save_provider(
+                "claude",
+                &Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None),
+            )
+            .unwrap();
+            db.add_to_failover_queue("claude", id).unwrap();
+        }
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.auto_failover_enabled = true;
+        config.circuit_failure_threshold = 2;
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        router.bind_session("claude", "s1", "a").await;
+
+        // a 偶发失败一次、b 接手成功：a 未熔断，会话仍优先 a
+        router
+            .record_result("a", "claude", false, false, Some("fail".to_string()))
+            .await
+            .unwrap();
+        router.bind_session("claude", "s1", "b").await;
+        let providers = router
+            .select_providers_for_session("claude", Some("s1"))
+            .await
+            .unwrap();
+        assert_eq!(providers[0].id, "a");
+
+        // a 连续失败达到阈值熔断后，由 b 成功响应才改绑
+        router
+            .record_result("a", "claude", false, false, Some("fail".to_string()))
+            .await
+            .unwrap();
+        router.bind_session("claude", "s1", "b").await;
+        router.reset_provider_breaker("a", "claude").await;
+        let providers = router
+            .select_providers_for_session("claude", Some("s1"))
+            .await
+            .unwrap();
+        assert_eq!(providers[0].id, "b");
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_select_providers_does_not_consume_half_open_permit() {
@@ -634,4 +1215,42 @@ mod tests {
         assert!(third.allowed);
         assert!(third.used_half_open_permit);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn unreachable_probe_removes_provider_until_probe_recovers() {
+        let _home = TempHome::new();
+        let db = Arc::new(Database::memory().unwrap());
+
+        for id in ["a", "b"] {
+            db.save_provider(
+                "claude",
+                &Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None),
+            )
+            .unwrap();
+            db.add_to_failover_queue("claude", id).unwrap();
+        }
+        let mut config = db.get_proxy_config_for_app("claude").await.unwrap();
+        config.auto_failover_enabled = true;
+        db.update_proxy_config_for_app(config).await.unwrap();
+
+        let router = ProviderRouter::new(db.clone());
+        let hold = std::time::Duration::from_secs(600);
+
+        router
+            .record_probe_result("a", "claude", false, Some("dns".to_string()), hold)
+            .await
+            .unwrap();
+        let providers = router.select_providers("claude").await.unwrap();
+        assert_eq!(
+            providers.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(),
+            vec!["b"]
+        );
+
+        router
+            .record_probe_result("a", "claude", true, None, hold)
+            .await
+            .unwrap();
+        assert_eq!(router.select_providers("claude").await.unwrap().len(), 2);
+    }
 }
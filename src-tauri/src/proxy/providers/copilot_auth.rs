@@ -24,15 +24,18 @@ use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
 /// GitHub OAuth 客户端 ID（VS Code）- 用于 github.com
+#[cfg(feature = "gui")]
 const GITHUB_CLIENT_ID: &str = "Iv1.b507a08c87ecfe98";
 
 /// GitHub OAuth 客户端 ID（与 OpenCode 相同）- 在所有 GHES Copilot 实例上预注册
+#[cfg(feature = "gui")]
 const GITHUB_CLIENT_ID_GHES: &str = "Ov23li8tweQw6odWQebz";
 
 /// 默认 GitHub 域名
 const DEFAULT_GITHUB_DOMAIN: &str = "github.com";
 
 /// 根据域名选择 OAuth 客户端 ID
+#[cfg(feature = "gui")]
 fn github_client_id(domain: &str) -> &'static str {
     if domain == DEFAULT_GITHUB_DOMAIN {
         GITHUB_CLIENT_ID
@@ -46,11 +49,13 @@ fn default_github_domain() -> String {
 }
 
 /// GitHub 设备码 URL
+#[cfg(feature = "gui")]
 fn github_device_code_url(domain: &str) -> String {
     format!("https://{domain}/login/device/code")
 }
 
 /// GitHub OAuth Token URL
+#[cfg(feature = "gui")]
 fn github_oauth_token_url(domain: &str) -> String {
     format!("https://{domain}/login/oauth/access_token")
 }
@@ -102,6 +107,7 @@ fn is_ghes(domain: &str) -> bool {
 /// - 剥离尾斜杠、path、query、fragment
 /// - 拒绝包含 userinfo（@）的输入
 /// - 保留端口号（如有）
+#[cfg(feature = "gui")]
 fn normalize_github_domain(raw: &str) -> Result<String, CopilotAuthError> {
     let s = raw.trim();
     // 剥离协议
@@ -218,15 +224,19 @@ struct CopilotModelsResponseItem {
 /// Copilot 认证错误
 #[derive(Debug, thiserror::Error)]
 pub enum CopilotAuthError {
+    #[cfg(feature = "gui")]
     #[error("设备码流程未启动")]
     DeviceFlowNotStarted,
 
+    #[cfg(feature = "gui")]
     #[error("等待用户授权中")]
     AuthorizationPending,
 
+    #[cfg(feature = "gui")]
     #[error("用户拒绝授权")]
     AccessDenied,
 
+    #[cfg(feature = "gui")]
     #[error("设备码已过期")]
     ExpiredToken,
 
@@ -251,6 +261,7 @@ pub enum CopilotAuthError {
     #[error("账号不存在: {0}")]
     AccountNotFound(String),
 
+    #[cfg(feature = "gui")]
     #[error("无效的 GitHub 域名: {0}")]
     InvalidDomain(String),
 }
@@ -283,6 +294,7 @@ pub struct GitHubDeviceCodeResponse {
 }
 
 /// GitHub OAuth Token 响应
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct GitHubOAuthResponse {
     access_token: Option<String>,
@@ -360,6 +372,7 @@ impl From<&GitHubAccountData> for GitHubAccount {
 }
 
 /// Copilot 认证状态（支持多账号）
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CopilotAuthStatus {
     /// 所有已认证的账号
@@ -438,6 +451,7 @@ pub struct CopilotAuthManager {
 
 impl CopilotAuthManager {
     /// 创建新的认证管理器
+    #[cfg(feature = "gui")]
     pub fn new(data_dir: PathBuf) -> Self {
         let storage_path = data_dir.join("copilot_auth.json");
 
@@ -465,6 +479,7 @@ impl CopilotAuthManager {
     // ==================== 多账号管理方法 ====================
 
     /// 列出所有已认证的账号
+    #[cfg(feature = "gui")]
     pub async fn list_accounts(&self) -> Vec<GitHubAccount> {
         let accounts = self.accounts.read().await.clone();
         let default_account_id = self.resolve_default_account_id().await;
@@ -472,12 +487,14 @@ impl CopilotAuthManager {
     }
 
     /// 获取指定账号信息
+    #[cfg(feature = "gui")]
     pub async fn get_account(&self, account_id: &str) -> Option<GitHubAccount> {
         let accounts = self.accounts.read().await;
         accounts.get(account_id).map(GitHubAccount::from)
     }
 
     /// 移除指定账号
+    #[cfg(feature = "gui")]
     pub async fn remove_account(&self, account_id: &str) -> Result<(), CopilotAuthError> {
         log::info!("[CopilotAuth] 移除账号: {account_id}");
 
@@ -574,6 +591,7 @@ impl CopilotAuthManager {
     }
 
     /// 设置默认账号
+    #[cfg(feature = "gui")]
     pub async fn set_default_account(&self, account_id: &str) -> Result<(), CopilotAuthError> {
         {
             let accounts = self.accounts.read().await;
@@ -594,6 +612,7 @@ impl CopilotAuthManager {
     // ==================== 设备码流程 ====================
 
     /// 启动设备码流程
+    #[cfg(feature = "gui")]
     pub async fn start_device_flow(
         &self,
         github_domain: Option<&str>,
@@ -637,6 +656,7 @@ impl CopilotAuthManager {
     }
 
     /// 轮询获取 OAuth Token（返回新添加的账号，如果成功）
+    #[cfg(feature = "gui")]
     pub async fn poll_for_token(
         &self,
         device_code: &str,
@@ -904,6 +924,7 @@ impl CopilotAuthManager {
     }
 
     /// 获取指定账号的 Copilot 使用量信息
+    #[cfg(feature = "gui")]
     pub async fn fetch_usage_for_account(
         &self,
         account_id: &str,
@@ -964,6 +985,7 @@ impl CopilotAuthManager {
     }
 
     /// 获取 Copilot 使用量信息（向后兼容：使用第一个账号）
+    #[cfg(feature = "gui")]
     pub async fn fetch_usage(&self) -> Result<CopilotUsageResponse, CopilotAuthError> {
         match self.resolve_default_account_id().await {
             Some(id) => self.fetch_usage_for_account(&id).await,
@@ -1089,6 +1111,7 @@ impl CopilotAuthManager {
     }
 
     /// 获取认证状态（支持多账号）
+    #[cfg(feature = "gui")]
     pub async fn get_status(&self) -> CopilotAuthStatus {
         // 确保迁移完成
         let _ = self.ensure_migration_complete().await;
@@ -1123,12 +1146,14 @@ impl CopilotAuthManager {
     }
 
     /// 检查是否已认证（有任意账号）
+    #[cfg(feature = "gui")]
     pub async fn is_authenticated(&self) -> bool {
         let accounts = self.accounts.read().await;
         !accounts.is_empty()
     }
 
     /// 清除所有认证（登出所有账号）
+    #[cfg(feature = "gui")]
     pub async fn clear_auth(&self) -> Result<(), CopilotAuthError> {
         log::info!("[CopilotAuth] 清除所有认证");
 
@@ -1187,6 +1212,7 @@ impl CopilotAuthManager {
             .map(|(id, _)| id.clone())
     }
 
+    #[cfg(feature = "gui")]
     fn sorted_accounts(
         accounts: &HashMap<String, GitHubAccountData>,
         default_account_id: Option<&str>,
@@ -1391,6 +1417,7 @@ impl CopilotAuthManager {
     // ==================== 存储和迁移 ====================
 
     /// 从磁盘加载（仅加载 token，不发起网络请求）
+    #[cfg(feature = "gui")]
     fn load_from_disk_sync(&self) -> Result<(), CopilotAuthError> {
         if !self.storage_path.exists() {
             return Ok(());
@@ -4,6 +4,7 @@
 //! from xAI's OpenID Connect discovery document so authentication protocol
 //! changes do not require duplicating endpoint constants across the app.
 
+#[cfg(feature = "gui")]
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -13,6 +14,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
+#[cfg(feature = "gui")]
 use super::copilot_auth::GitHubDeviceCodeResponse;
 
 const XAI_ISSUER: &str = "https://auth.x.ai";
@@ -22,17 +24,23 @@ const XAI_SCOPE: &str = "openid profile email offline_access grok-cli:access api
 const XAI_USER_AGENT: &str = "cc-switch-xai-oauth";
 const TOKEN_REFRESH_BUFFER_MS: i64 = 60_000;
 const DEFAULT_TOKEN_LIFETIME_SECS: i64 = 3_600;
+#[cfg(feature = "gui")]
 const POLLING_SAFETY_MARGIN_SECS: u64 = 3;
+#[cfg(feature = "gui")]
 const MAX_DEVICE_CODE_LIFETIME_SECS: u64 = 24 * 60 * 60;
+#[cfg(feature = "gui")]
 const MAX_POLL_INTERVAL_SECS: u64 = 60;
 const MAX_OAUTH_RESPONSE_BYTES: usize = 64 * 1024;
 
 #[derive(Debug, thiserror::Error)]
 pub enum XaiOAuthError {
+    #[cfg(feature = "gui")]
     #[error("等待用户授权中")]
     AuthorizationPending,
+    #[cfg(feature = "gui")]
     #[error("用户拒绝授权")]
     AccessDenied,
+    #[cfg(feature = "gui")]
     #[error("Device Code 已过期")]
     ExpiredToken,
     #[error("OAuth Token 获取失败: {0}")]
@@ -73,9 +81,11 @@ struct DiscoveryDocument {
 #[derive(Debug, Clone)]
 struct OAuthEndpoints {
     token_endpoint: String,
+    #[cfg(feature = "gui")]
     device_authorization_endpoint: String,
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Deserialize)]
 struct DeviceCodeResponse {
     device_code: String,
@@ -93,12 +103,14 @@ struct OAuthTokenResponse {
     access_token: String,
     #[serde(default)]
     refresh_token: Option<String>,
+    #[cfg(feature = "gui")]
     #[serde(default)]
     id_token: Option<String>,
     #[serde(default)]
     expires_in: Option<i64>,
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Default, Deserialize)]
 struct XaiTokenClaims {
     #[serde(default)]
@@ -123,6 +135,7 @@ impl CachedAccessToken {
     }
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone)]
 struct PendingDeviceCode {
     token_endpoint: String,
@@ -142,6 +155,7 @@ struct XaiAccountData {
     requires_reauth: bool,
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct XaiOAuthAccount {
     pub id: String,
@@ -152,6 +166,7 @@ pub struct XaiOAuthAccount {
     pub requires_reauth: bool,
 }
 
+#[cfg(feature = "gui")]
 impl From<&XaiAccountData> for XaiOAuthAccount {
     fn from(data: &XaiAccountData) -> Self {
         let short_id: String = data.account_id.chars().take(12).collect();
@@ -179,6 +194,7 @@ struct XaiOAuthStore {
     default_account_id: Option<String>,
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct XaiOAuthStatus {
     pub accounts: Vec<XaiOAuthAccount>,
@@ -192,6 +208,7 @@ pub struct XaiOAuthManager {
     default_account_id: Arc<RwLock<Option<String>>>,
     access_tokens: Arc<RwLock<HashMap<String, CachedAccessToken>>>,
     refresh_locks: Arc<RwLock<HashMap<String, Arc<Mutex<()>>>>>,
+    #[cfg(feature = "gui")]
     pending_device_codes: Arc<RwLock<HashMap<String, PendingDeviceCode>>>,
     discovered_endpoints: Arc<RwLock<Option<OAuthEndpoints>>>,
     mutation_lock: Arc<Mutex<()>>,
@@ -199,6 +216,7 @@ pub struct XaiOAuthManager {
 }
 
 impl XaiOAuthManager {
+    #[cfg(feature = "gui")]
     pub fn new(data_dir: PathBuf) -> Self {
         let manager = Self {
             accounts: Arc::new(RwLock::new(HashMap::new())),
@@ -217,6 +235,7 @@ impl XaiOAuthManager {
         manager
     }
 
+    #[cfg(feature = "gui")]
     pub async fn start_device_flow(&self) -> Result<GitHubDeviceCodeResponse, XaiOAuthError> {
         let endpoints = self.discover_endpoints().await?;
         let response = crate::proxy::http_client::get()
@@ -270,6 +289,7 @@ impl XaiOAuthManager {
         })
     }
 
+    #[cfg(feature = "gui")]
     pub async fn poll_for_token(
         &self,
         device_code: &str,
@@ -409,10 +429,12 @@ impl XaiOAuthManager {
         }
     }
 
+    #[cfg(feature = "gui")]
     pub async fn default_account_id(&self) -> Option<String> {
         self.resolve_default_account_id().await
     }
 
+    #[cfg(feature = "gui")]
     pub async fn get_status(&self) -> XaiOAuthStatus {
         let accounts = self.accounts.read().await.clone();
         let default_account_id = self.resolve_default_account_id().await;
@@ -429,12 +451,14 @@ impl XaiOAuthManager {
         }
     }
 
+    #[cfg(feature = "gui")]
     pub async fn list_accounts(&self) -> Vec<XaiOAuthAccount> {
         let accounts = self.accounts.read().await.clone();
         let default_account_id = self.resolve_default_account_id().await;
         Self::sorted_accounts(&accounts, default_account_id.as_deref())
     }
 
+    #[cfg(feature = "gui")]
     pub async fn remove_account(&self, account_id: &str) -> Result<(), XaiOAuthError> {
         let _mutation_guard = self.mutation_lock.lock().await;
         let mut accounts = self.accounts.read().await.clone();
@@ -454,6 +478,7 @@ impl XaiOAuthManager {
         Ok(())
     }
 
+    #[cfg(feature = "gui")]
     pub async fn set_default_account(&self, account_id: &str) -> Result<(), XaiOAuthError> {
         let _mutation_guard = self.mutation_lock.lock().await;
         let accounts = self.accounts.read().await.clone();
@@ -467,6 +492,7 @@ impl XaiOAuthManager {
             .await
     }
 
+    #[cfg(feature = "gui")]
     pub async fn clear_auth(&self) -> Result<(), XaiOAuthError> {
         let _mutation_guard = self.mutation_lock.lock().await;
         if self.storage_path.exists() {
@@ -506,6 +532,7 @@ impl XaiOAuthManager {
         validate_xai_endpoint(&document.device_authorization_endpoint)?;
         let endpoints = OAuthEndpoints {
             token_endpoint: document.token_endpoint,
+            #[cfg(feature = "gui")]
             device_authorization_endpoint: document.device_authorization_endpoint,
         };
         *self.discovered_endpoints.write().await = Some(endpoints.clone());
@@ -553,6 +580,7 @@ impl XaiOAuthManager {
         Ok(tokens)
     }
 
+    #[cfg(feature = "gui")]
     async fn add_account_internal(
         &self,
         account_id: String,
@@ -704,6 +732,7 @@ impl XaiOAuthManager {
         self.cached_token(account_id).await
     }
 
+    #[cfg(feature = "gui")]
     async fn schedule_next_poll(&self, device_code: &str, interval_secs: u64) {
         if let Some(entry) = self.pending_device_codes.write().await.get_mut(device_code) {
             entry.next_poll_at_ms = chrono::Utc::now().timestamp_millis().saturating_add(
@@ -714,6 +743,7 @@ impl XaiOAuthManager {
         }
     }
 
+    #[cfg(feature = "gui")]
     async fn increase_poll_interval(&self, device_code: &str) {
         if let Some(entry) = self.pending_device_codes.write().await.get_mut(device_code) {
             entry.interval_secs = entry
@@ -769,6 +799,7 @@ impl XaiOAuthManager {
             .is_some_and(|account| !account.requires_reauth)
     }
 
+    #[cfg(feature = "gui")]
     fn sorted_accounts(
         accounts: &HashMap<String, XaiAccountData>,
         default_account_id: Option<&str>,
@@ -786,6 +817,7 @@ impl XaiOAuthManager {
         result
     }
 
+    #[cfg(feature = "gui")]
     fn load_from_disk_sync(&self) -> Result<(), XaiOAuthError> {
         if !self.storage_path.exists() {
             return Ok(());
@@ -864,6 +896,7 @@ impl XaiOAuthManager {
     }
 }
 
+#[cfg(feature = "gui")]
 fn default_poll_interval() -> u64 {
     5
 }
@@ -886,6 +919,7 @@ fn validate_access_token(access_token: &str) -> Result<(), XaiOAuthError> {
     Ok(())
 }
 
+#[cfg(feature = "gui")]
 fn parse_device_code_response(
     value: serde_json::Value,
 ) -> Result<DeviceCodeResponse, XaiOAuthError> {
@@ -913,12 +947,14 @@ fn refresh_response_requires_reauth(
     ) || (status == reqwest::StatusCode::BAD_REQUEST && response_body_is_invalid)
 }
 
+#[cfg(feature = "gui")]
 fn parse_jwt_claims(token: &str) -> Option<XaiTokenClaims> {
     let payload = token.split('.').nth(1)?;
     let decoded = URL_SAFE_NO_PAD.decode(payload).ok()?;
     serde_json::from_slice(&decoded).ok()
 }
 
+#[cfg(feature = "gui")]
 fn extract_identity_from_tokens(tokens: &OAuthTokenResponse) -> Option<(String, Option<String>)> {
     let claims = tokens
         .id_token
@@ -0,0 +1,384 @@
+//! 额度感知的故障转移
+//!
+//! 代理运行期间定期查询故障转移队列（及模型路由规则）中供应商的订阅/套餐额度
+//! （`services::subscription` 的官方订阅、`services::coding_plan` 的 Token Plan），
+//! 缓存各窗口的用量与重置时间。任一窗口用量达到应用配置的 `quota_threshold` 的供应商，
+//! 在该窗口 `resets_at` 之前排到队列末尾，而不必等上游返回 429 触发熔断。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::provider::Provider;
+use crate::proxy::log_codes::fo as log_fo;
+use crate::proxy::provider_router::{provider_supports_failover, ProviderRouter};
+use crate::services::subscription::SubscriptionQuota;
+
+/// 调度粒度：每个 tick 重新读取配置，阈值修改无需重启代理
+const QUOTA_TICK: Duration = Duration::from_secs(60);
+/// 同一供应商两次额度查询的最小间隔（查询失败同样等待该间隔再重试）
+const QUOTA_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+const TEMPLATE_TYPE_TOKEN_PLAN: &str = "token_plan";
+const TEMPLATE_TYPE_OFFICIAL_SUBSCRIPTION: &str = "official_subscription";
+
+/// 一个额度窗口（如 five_hour / seven_day）的快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaWindow {
+    pub name: String,
+    /// 使用百分比 0–100
+    pub utilization: f64,
+    /// 窗口重置时间；未知时视为直到下次刷新前一直有效
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl QuotaWindow {
+    fn is_saturated(&self, threshold: u32, now: DateTime<Utc>) -> bool {
+        self.utilization >= f64::from(threshold) && self.resets_at.is_none_or(|at| at > now)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QuotaSnapshot {
+    windows: Vec<QuotaWindow>,
+    fetched_at: Instant,
+}
+
+/// 供应商额度缓存 - key 格式: "app_type:provider_id"
+#[derive(Default)]
+pub struct QuotaGuard {
+    snapshots: RwLock<HashMap<String, QuotaSnapshot>>,
+}
+
+impl QuotaGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次额度查询结果
+    ///
+    /// 查询失败时保留上次成功的窗口（其 `resets_at` 仍然有效），只更新查询时间，
+    /// 避免每个 tick 都重试失败的供应商。
+    pub async fn record(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        quota: Result<&SubscriptionQuota, &str>,
+    ) {
+        let key = format!("{app_type}:{provider_id}");
+        let mut snapshots = self.snapshots.write().await;
+        let windows = match quota {
+            Ok(quota) if quota.success => quota
+                .tiers
+                .iter()
+                .map(|tier| QuotaWindow {
+                    name: tier.name.clone(),
+                    utilization: tier.utilization,
+                    resets_at: tier
+                        .resets_at
+                        .as_deref()
+                        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                        .map(|at| at.with_timezone(&Utc)),
+                })
+                .collect(),
+            Ok(quota) => {
+                log::debug!(
+                    "[QuotaGuard] [{app_type}] {provider_id} 额度查询未成功: {}",
+                    quota
+                        .error
+                        .as_deref()
+                        .or(quota.credential_message.as_deref())
+                        .unwrap_or("unknown")
+                );
+                snapshots
+                    .get(&key)
+                    .map(|s| s.windows.clone())
+                    .unwrap_or_default()
+            }
+            Err(e) => {
+                log::debug!("[QuotaGuard] [{app_type}] {provider_id} 额度查询失败: {e}");
+                snapshots
+                    .get(&key)
+                    .map(|s| s.windows.clone())
+                    .unwrap_or_default()
+            }
+        };
+        snapshots.insert(
+            key,
+            QuotaSnapshot {
+                windows,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    /// 距上次查询是否已超过刷新间隔
+    pub async fn is_due(&self, app_type: &str, provider_id: &str) -> bool {
+        let key = format!("{app_type}:{provider_id}");
+        self.snapshots
+            .read()
+            .await
+            .get(&key)
+            .is_none_or(|s| s.fetched_at.elapsed() >= QUOTA_REFRESH_INTERVAL)
+    }
+
+    /// 返回用量最高的已达阈值且尚未重置的窗口；`threshold` 为 0 表示关闭
+    pub async fn saturated_window(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        threshold: u32,
+    ) -> Option<QuotaWindow> {
+        if threshold == 0 {
+            return None;
+        }
+        let key = format!("{app_type}:{provider_id}");
+        let now = Utc::now();
+        self.snapshots
+            .read()
+            .await
+            .get(&key)?
+            .windows
+            .iter()
+            .filter(|w| w.is_saturated(threshold, now))
+            .max_by(|a, b| a.utilization.total_cmp(&b.utilization))
+            .cloned()
+    }
+
+    /// 把额度窗口已达阈值的供应商稳定地移到列表末尾（相对顺序不变）
+    pub async fn deprioritize(
+        &self,
+        app_type: &str,
+        threshold: u32,
+        providers: Vec<Provider>,
+    ) -> Vec<Provider> {
+        if threshold == 0 || providers.len() < 2 {
+            return providers;
+        }
+        let mut available = Vec::with_capacity(providers.len());
+        let mut saturated = Vec::new();
+        for provider in providers {
+            match self
+                .saturated_window(app_type, &provider.id, threshold)
+                .await
+            {
+                Some(window) => {
+                    log::info!(
+                        "[{app_type}] [{}] 供应商 {} 的 {} 窗口已用 {:.0}%（阈值 {threshold}%），重置前降为末位",
+                        log_fo::QUOTA_SATURATED,
+                        provider.id,
+                        window.name,
+                        window.utilization
+                    );
+                    saturated.push(provider);
+                }
+                None => available.push(provider),
+            }
+        }
+        available.extend(saturated);
+        available
+    }
+}
+
+/// 启动额度刷新任务；由 `ProxyServer` 持有句柄并在停止时 abort
+pub(crate) fn spawn(db: Arc<Database>, router: Arc<ProviderRouter>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(QUOTA_TICK);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            for app_type in AppType::all().filter(AppType::supports_local_proxy) {
+                refresh_app(&db, &router, &app_type).await;
+            }
+        }
+    })
+}
+
+async fn refresh_app(db: &Database, router: &ProviderRouter, app_type: &AppType) {
+    let app = app_type.as_str();
+    let config = match db.get_proxy_config_for_app(app).await {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("[QuotaGuard] [{app}] 读取 proxy_config 失败: {e}");
+            return;
+        }
+    };
+    if config.quota_threshold == 0 {
+        return;
+    }
+
+    let (queue, providers) = match (db.get_failover_queue(app), db.get_all_providers(app)) {
+        (Ok(queue), Ok(providers)) => (queue, providers),
+        (Err(e), _) | (_, Err(e)) => {
+            log::warn!("[QuotaGuard] [{app}] 读取故障转移队列失败: {e}");
+            return;
+        }
+    };
+
+    let mut seen = HashSet::new();
+    let queued = config
+        .auto_failover_enabled
+        .then_some(queue.into_iter().map(|item| item.provider_id))
+        .into_iter()
+        .flatten();
+    let routed = config
+        .model_routes
+        .iter()
+        .filter(|route| route.enabled)
+        .flat_map(|route| route.provider_ids.iter().cloned());
+    for provider_id in queued.chain(routed) {
+        if !seen.insert(provider_id.clone()) {
+            continue;
+        }
+        let Some(provider) = providers.get(&provider_id) else {
+            continue;
+        };
+        if !provider_supports_failover(app, provider) {
+            continue;
+        }
+        let guard = router.quota_guard();
+        if !guard.is_due(app, &provider.id).await {
+            continue;
+        }
+        let Some(quota) = fetch_provider_quota(app_type, provider).await else {
+            continue;
+        };
+        guard
+            .record(app, &provider.id, quota.as_ref().map_err(String::as_str))
+            .await;
+    }
+}
+
+/// 按供应商的用量脚本模板查询额度；未启用额度查询的供应商返回 `None`
+async fn fetch_provider_quota(
+    app_type: &AppType,
+    provider: &Provider,
+) -> Option<Result<SubscriptionQuota, String>> {
+    let script = provider.meta.as_ref()?.usage_script.as_ref()?;
+    if !script.enabled {
+        return None;
+    }
+    match script.template_type.as_deref()? {
+        TEMPLATE_TYPE_TOKEN_PLAN => Some(
+            crate::services::coding_plan::get_provider_coding_plan_quota(app_type, Some(provider))
+                .await,
+        ),
+        // xAI OAuth 托管供应商的额度属绑定的 SuperGrok 账号，需要命令层的 OAuth 状态，
+        // 后台无从获取，跳过
+        TEMPLATE_TYPE_OFFICIAL_SUBSCRIPTION if !provider.is_xai_oauth() => {
+            Some(crate::services::subscription::get_subscription_quota(app_type.as_str()).await)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::subscription::{CredentialStatus, QuotaTier};
+    use serde_json::json;
+
+    fn quota(tiers: &[(&str, f64, Option<String>)]) -> SubscriptionQuota {
+        SubscriptionQuota {
+            tool: "claude".to_string(),
+            credential_status: CredentialStatus::Valid,
+            credential_message: None,
+            success: true,
+            tiers: tiers
+                .iter()
+                .map(|(name, utilization, resets_at)| QuotaTier {
+                    name: name.to_string(),
+                    utilization: *utilization,
+                    resets_at: resets_at.clone(),
+                    used_value_usd: None,
+                    max_value_usd: None,
+                })
+                .collect(),
+            extra_usage: None,
+            error: None,
+            queried_at: None,
+        }
+    }
+
+    fn provider(id: &str) -> Provider {
+        Provider::with_id(id.to_string(), id.to_string(), json!({}), None)
+    }
+
+    fn in_hours(hours: i64) -> Option<String> {
+        Some((Utc::now() + chrono::Duration::hours(hours)).to_rfc3339())
+    }
+
+    #[tokio::test]
+    async fn saturated_window_respects_threshold_and_reset_time() {
+        let guard = QuotaGuard::new();
+        guard
+            .record(
+                "claude",
+                "a",
+                Ok(&quota(&[
+                    ("five_hour", 97.0, in_hours(2)),
+                    ("seven_day", 99.0, in_hours(-1)),
+                    ("seven_day_opus", 40.0, in_hours(48)),
+                ])),
+            )
+            .await;
+
+        let window = guard
+            .saturated_window("claude", "a", 90)
+            .await
+            .expect("five_hour is above threshold");
+        assert_eq!(window.name, "five_hour");
+        assert!(guard.saturated_window("claude", "a", 98).await.is_none());
+        assert!(guard.saturated_window("claude", "a", 0).await.is_none());
+        assert!(guard.saturated_window("codex", "a", 90).await.is_none());
+        assert!(!guard.is_due("claude", "a").await);
+        assert!(guard.is_due("claude", "b").await);
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_keeps_previous_windows() {
+        let guard = QuotaGuard::new();
+        guard
+            .record(
+                "claude",
+                "a",
+                Ok(&quota(&[("five_hour", 95.0, in_hours(1))])),
+            )
+            .await;
+        guard.record("claude", "a", Err("timeout")).await;
+
+        assert!(guard.saturated_window("claude", "a", 90).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn deprioritize_moves_saturated_providers_to_the_back_in_order() {
+        let guard = QuotaGuard::new();
+        for id in ["a", "c"] {
+            guard
+                .record(
+                    "claude",
+                    id,
+                    Ok(&quota(&[("seven_day", 100.0, in_hours(24))])),
+                )
+                .await;
+        }
+
+        let ordered = guard
+            .deprioritize(
+                "claude",
+                80,
+                vec![provider("a"), provider("b"), provider("c"), provider("d")],
+            )
+            .await;
+        let ids: Vec<_> = ordered.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "d", "a", "c"]);
+    }
+}
@@ -10,6 +10,7 @@ use super::{
     hyper_client::{ProxyResponse, MAX_RESPONSE_BODY_BYTES},
     server::ProxyState,
     sse::{strip_sse_field, take_sse_block},
+    telemetry::RequestTrace,
     usage::parser::TokenUsage,
     ProxyError,
 };
@@ -494,6 +495,7 @@ pub(crate) fn create_usage_collector(
     let stream_parser = parser_config.stream_parser;
     let model_extractor = parser_config.model_extractor;
     let session_id = ctx.session_id.clone();
+    let trace = ctx.trace.clone();
 
     Some(SseUsageCollector::new(
         start_time,
@@ -506,6 +508,7 @@ pub(crate) fn create_usage_collector(
                 let state = state.clone();
                 let provider_id = provider_id.clone();
                 let session_id = session_id.clone();
+                let trace = trace.clone();
                 let request_model = request_model.clone();
                 let outbound_model = fallback_model.clone();
 
@@ -523,6 +526,7 @@ pub(crate) fn create_usage_collector(
                         true, // is_streaming
                         status_code,
                         Some(session_id),
+                        trace,
                     )
                     .await;
                 });
@@ -532,6 +536,7 @@ pub(crate) fn create_usage_collector(
                 let state = state.clone();
                 let provider_id = provider_id.clone();
                 let session_id = session_id.clone();
+                let trace = trace.clone();
                 let request_model = request_model.clone();
                 let outbound_model = fallback_model.clone();
 
@@ -549,6 +554,7 @@ pub(crate) fn create_usage_collector(
                         true, // is_streaming
                         status_code,
                         Some(session_id),
+                        trace,
                     )
                     .await;
                 });
@@ -587,6 +593,7 @@ fn spawn_log_usage(
         .unwrap_or_else(|| ctx.request_model.clone());
     let latency_ms = ctx.latency_ms();
     let session_id = ctx.session_id.clone();
+    let trace = ctx.trace.clone();
 
     tokio::spawn(async move {
         log_usage_internal(
@@ -602,6 +609,7 @@ fn spawn_log_usage(
             is_streaming,
             status_code,
             Some(session_id),
+            trace,
         )
         .await;
     });
@@ -635,9 +643,14 @@ async fn log_usage_internal(
     is_streaming: bool,
     status_code: u16,
     session_id: Option<String>,
+    trace: Option<RequestTrace>,
 ) {
     use super::usage::logger::UsageLogger;
+    use rust_decimal::prelude::ToPrimitive;
 
+    if let Some(trace) = &trace {
+        trace.record_usage(provider_id, model, &usage);
+    }
     let logger = UsageLogger::new(&state.db);
     let (multiplier, pricing_model_source) =
         logger.resolve_pricing_config(provider_id, app_type).await;
@@ -649,6 +662,15 @@ async fn log_usage_internal(
 
     let dedup_scope = super::usage::parser::dedup_scope_for_app(app_type, provider_id);
     let request_id = usage.dedup_request_id(dedup_scope);
+    state.metrics.record_request(
+        app_type,
+        provider_id,
+        model,
+        status_code,
+        latency_ms,
+        first_token_ms,
+        Some(&usage),
+    );
 
     log::debug!(
         "[{app_type}] 记录请求日志: id={request_id}, provider={provider_id}, model={model}, streaming={is_streaming}, status={status_code}, latency_ms={latency_ms}, first_token_ms={first_token_ms:?}, session={}, input={}, output={}, cache_read={}, cache_creation={}",
@@ -659,7 +681,7 @@ async fn log_usage_internal(
         usage.cache_creation_tokens
     );
 
-    if let Err(e) = logger.log_with_calculation(
+    match logger.log_with_calculation(
         request_id,
         provider_id.to_string(),
         app_type.to_string(),
@@ -675,7 +697,18 @@ async fn log_usage_internal(
         None, // provider_type
         is_streaming,
     ) {
-        log::warn!("[USG-001] 记录使用量失败: {e}");
+        Ok(Some(cost)) => {
+            let cost = cost.to_f64().unwrap_or(0.0);
+            state
+                .metrics
+                .record_cost(app_type, provider_id, model, cost);
+            state
+                .provider_router
+                .record_spend(provider_id, app_type, cost)
+                .await;
+        }
+        Ok(None) => log::debug!("[{app_type}] 请求已记录过，跳过花费累计: provider={provider_id}"),
+        Err(e) => log::warn!("[USG-001] 记录使用量失败: {e}"),
     }
 }
 
@@ -859,6 +892,7 @@ mod tests {
     use crate::error::AppError;
     use crate::provider::ProviderMeta;
     use crate::proxy::failover_switch::FailoverSwitchManager;
+    use crate::proxy::metrics::CounterValue;
     use crate::proxy::provider_router::ProviderRouter;
     use crate::proxy::providers::{
         codex_chat_history::CodexChatHistoryStore, gemini_shadow::GeminiShadowStore,
@@ -1030,6 +1064,11 @@ mod tests {
             codex_chat_history: Arc::new(CodexChatHistoryStore::default()),
             app_handle: None,
             failover_manager: Arc::new(FailoverSwitchManager::new(db)),
+            mcp_gateway: Arc::default(),
+            model_catalog: Arc::default(),
+            replay_pins: Arc::default(),
+            metrics: Arc::default(),
+            telemetry: Arc::default(),
         }
     }
 
@@ -1107,6 +1146,7 @@ mod tests {
             false,
             200,
             None,
+            None,
         )
         .await;
 
@@ -1133,6 +1173,71 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_log_usage_duplicate_request_charges_cost_once() -> Result<(), AppError> {
+        let db = Arc::new(Database::memory()?);
+        let app_type = "claude";
+        seed_pricing(&db)?;
+        insert_provider(&db, "provider-1", app_type, ProviderMeta::default())?;
+
+        let state = build_state(db.clone());
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+            model: None,
+            message_id: Some("msg-dup".to_string()),
+        };
+
+        // 同一上游消息被记录两次（如客户端重试），花费只能累计一次
+        for _ in 0..2 {
+            log_usage_internal(
+                &state,
+                "provider-1",
+                app_type,
+                "resp-model",
+                "resp-model",
+                "resp-model",
+                usage.clone(),
+                10,
+                None,
+                false,
+                200,
+                None,
+                None,
+            )
+            .await;
+        }
+
+        let recorded_cost: f64 = state
+            .metrics
+            .counters()
+            .into_iter()
+            .filter(|point| point.name == "cc_switch.proxy.cost")
+            .map(|point| match point.value {
+                CounterValue::Double(value) => value,
+                CounterValue::Int(value) => value as f64,
+            })
+            .sum();
+
+        let conn = crate::database::lock_conn!(db.conn);
+        let (rows, total_cost): (i64, String) = conn
+            .query_row(
+                "SELECT COUNT(*), MAX(total_cost_usd) FROM proxy_request_logs
+                 WHERE provider_id = ?1",
+                ["provider-1"],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        assert_eq!(rows, 1);
+        let total_cost: f64 = total_cost.parse().unwrap();
+        assert!(total_cost > 0.0);
+        assert!((recorded_cost - total_cost).abs() < 1e-9);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_request_pricing_mode_anchors_to_outbound_model() -> Result<(), AppError> {
         let db = Arc::new(Database::memory()?);
@@ -1177,6 +1282,7 @@ mod tests {
             false,
             200,
             None,
+            None,
         )
         .await;
 
@@ -1257,6 +1363,7 @@ mod tests {
             false,
             200,
             None,
+            None,
         )
         .await;
 
@@ -9,11 +9,17 @@
 //! a direct (non-proxied) CLI request.
 
 use super::{
+    capture::{self, ReplayPins},
     failover_switch::FailoverSwitchManager,
-    handlers,
+    handlers, health_prober,
     log_codes::srv as log_srv,
+    mcp_gateway::McpGateway,
+    metrics::ProxyMetrics,
+    model_catalog::ModelCatalog,
     provider_router::ProviderRouter,
     providers::{codex_chat_history::CodexChatHistoryStore, gemini_shadow::GeminiShadowStore},
+    quota_guard,
+    telemetry::{self, TelemetryExporter},
     types::*,
     ProxyError,
 };
@@ -45,9 +51,19 @@ pub struct ProxyState {
     /// Codex Chat bridge history，用于恢复 previous_response_id 指向的 tool call
     pub codex_chat_history: Arc<CodexChatHistoryStore>,
     /// AppHandle，用于发射事件和更新托盘菜单
-    pub app_handle: Option<tauri::AppHandle>,
+    pub app_handle: Option<super::AppHandle>,
     /// 故障转移切换管理器
     pub failover_manager: Arc<FailoverSwitchManager>,
+    /// MCP 网关的上游连接池（跨应用共享 stdio 进程）
+    pub mcp_gateway: Arc<McpGateway>,
+    /// 聚合模型列表缓存（/v1/models 等端点）
+    pub model_catalog: Arc<ModelCatalog>,
+    /// 抓包重放的一次性授权
+    pub replay_pins: Arc<ReplayPins>,
+    /// Prometheus 指标（`GET /metrics`）
+    pub metrics: Arc<ProxyMetrics>,
+    /// OpenTelemetry 待导出 span 队列
+    pub telemetry: Arc<TelemetryExporter>,
 }
 
 /// 代理HTTP服务器
@@ -57,13 +73,19 @@ pub struct ProxyServer {
     shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
     /// 服务器任务句柄，用于等待服务器实际关闭
     server_handle: Arc<RwLock<Option<JoinHandle<()>>>>,
+    /// 后台健康探测任务句柄，随服务器启停
+    health_prober: Arc<RwLock<Option<JoinHandle<()>>>>,
+    /// 后台额度刷新任务句柄，随服务器启停
+    quota_refresher: Arc<RwLock<Option<JoinHandle<()>>>>,
+    /// 后台 OTLP 导出任务句柄，随服务器启停
+    telemetry_exporter: Arc<RwLock<Option<JoinHandle<()>>>>,
 }
 
 impl ProxyServer {
     pub fn new(
         config: ProxyConfig,
         db: Arc<Database>,
-        app_handle: Option<tauri::AppHandle>,
+        app_handle: Option<super::AppHandle>,
     ) -> Self {
         // 创建共享的 ProviderRouter（熔断器状态将跨所有请求保持）
         let provider_router = Arc::new(ProviderRouter::new(db.clone()));
@@ -81,6 +103,11 @@ impl ProxyServer {
             codex_chat_history: Arc::new(CodexChatHistoryStore::default()),
             app_handle,
             failover_manager,
+            mcp_gateway: Arc::new(McpGateway::new()),
+            model_catalog: Arc::new(ModelCatalog::new()),
+            replay_pins: Arc::new(ReplayPins::default()),
+            metrics: Arc::new(ProxyMetrics::new()),
+            telemetry: Arc::new(TelemetryExporter::new()),
         };
 
         Self {
@@ -88,6 +115,9 @@ impl ProxyServer {
             state,
             shutdown_tx: Arc::new(RwLock::new(None)),
             server_handle: Arc::new(RwLock::new(None)),
+            health_prober: Arc::new(RwLock::new(None)),
+            quota_refresher: Arc::new(RwLock::new(None)),
+            telemetry_exporter: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -214,6 +244,18 @@ impl ProxyServer {
 
         // 保存服务器任务句柄
         *self.server_handle.write().await = Some(handle);
+        *self.health_prober.write().await = Some(health_prober::spawn(
+            self.state.db.clone(),
+            self.state.provider_router.clone(),
+        ));
+        *self.quota_refresher.write().await = Some(quota_guard::spawn(
+            self.state.db.clone(),
+            self.state.provider_router.clone(),
+        ));
+        *self.telemetry_exporter.write().await = Some(telemetry::spawn(
+            self.state.telemetry.clone(),
+            self.state.metrics.clone(),
+        ));
 
         Ok(ProxyServerInfo {
             address: self.config.listen_address.clone(),
@@ -229,6 +271,16 @@ impl ProxyServer {
         } else {
             return Err(ProxyError::NotRunning);
         }
+        if let Some(prober) = self.health_prober.write().await.take() {
+            prober.abort();
+        }
+        if let Some(refresher) = self.quota_refresher.write().await.take() {
+            refresher.abort();
+        }
+        if let Some(exporter) = self.telemetry_exporter.write().await.take() {
+            exporter.abort();
+        }
+        self.state.mcp_gateway.shutdown();
 
         // 2. 等待服务器任务结束（带 5 秒超时保护）
         if let Some(handle) = self.server_handle.write().await.take() {
@@ -254,6 +306,11 @@ impl ProxyServer {
         }
     }
 
+    /// 为抓包重放发放一次性 nonce（可指定供应商）
+    pub fn issue_replay(&self, capture_id: &str, provider_id: Option<String>) -> String {
+        self.state.replay_pins.issue(capture_id, provider_id)
+    }
+
     pub async fn get_status(&self) -> ProxyStatus {
         let mut status = self.state.status.read().await.clone();
 
@@ -293,9 +350,11 @@ impl ProxyServer {
             // 健康检查
             .route("/health", get(handlers::health_check))
             .route("/status", get(handlers::get_status))
+            .route("/metrics", get(handlers::get_metrics))
             // Claude API (支持带前缀和不带前缀两种格式)
             .route("/v1/messages", post(handlers::handle_messages))
             .route("/claude/v1/messages", post(handlers::handle_messages))
+            .route("/claude/v1/models", get(handlers::handle_claude_models))
             // Claude Desktop 3P 本地 gateway（独立 provider namespace）
             .route(
                 "/claude-desktop/v1/models",
@@ -319,9 +378,10 @@ impl ProxyServer {
                 "/codex/v1/chat/completions",
                 post(handlers::handle_chat_completions),
             )
-            // OpenAI Models API (Codex CLI reachability check)
+            // 聚合模型列表（Codex CLI 的目录探测也走这里）
             .route("/models", get(handlers::handle_models))
             .route("/v1/models", get(handlers::handle_models))
+            .route("/codex/v1/models", get(handlers::handle_codex_models))
             // OpenAI Responses API (Codex CLI，支持带前缀和不带前缀)
             .route("/responses", post(handlers::handle_responses))
             .route("/v1/responses", post(handlers::handle_responses))
@@ -373,6 +433,18 @@ impl ProxyServer {
             .route("/gemini/v1beta/*path", any(handlers::handle_gemini))
             // Gemini 的 GA 版本也叫 /v1，给原 SDK 留一条出口
             .route("/gemini/v1/*path", any(handlers::handle_gemini))
+            // MCP 网关（Streamable HTTP，仅 POST；不提供服务端事件流）
+            .route("/mcp/:app", post(handlers::handle_mcp_gateway))
+            // 抓包（未开启时直接放行）
+            .layer(axum::middleware::from_fn_with_state(
+                self.state.clone(),
+                capture::capture_middleware,
+            ))
+            // OpenTelemetry trace（未设置采集端点时直接放行）
+            .layer(axum::middleware::from_fn_with_state(
+                self.state.clone(),
+                telemetry::telemetry_middleware,
+            ))
             // 提高默认请求体大小限制（避免 413 Payload Too Large）
             .layer(DefaultBodyLimit::max(200 * 1024 * 1024))
             .with_state(self.state.clone())
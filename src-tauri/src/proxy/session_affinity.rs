@@ -0,0 +1,153 @@
+//! 会话粘滞路由
+//!
+//! 将客户端提供的 Session ID 绑定到首个成功响应它的供应商，后续请求优先路由到同一家，
+//! 避免负载均衡或故障转移在对话中途换供应商导致 prompt cache 失效、thinking 签名失配。
+//! 绑定在空闲超过 TTL 后过期；绑定的供应商熔断（或超出限额）时本次请求回落到正常顺序。
+//! 只有绑定的供应商熔断器已打开时才改绑到实际成功的供应商（由 `ProviderRouter` 判断），
+//! 偶发失败导致的一次性故障转移不会让会话离开原供应商。
+
+use crate::provider::Provider;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// 绑定空闲过期时间
+const SESSION_AFFINITY_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// 绑定数量超过该值时清理过期条目
+const SESSION_AFFINITY_SWEEP_THRESHOLD: usize = 1024;
+
+struct SessionBinding {
+    provider_id: String,
+    last_seen: Instant,
+}
+
+/// 会话粘滞表 - key 格式: "app_type:session_id"
+#[derive(Default)]
+pub struct SessionAffinity {
+    bindings: RwLock<HashMap<String, SessionBinding>>,
+}
+
+impl SessionAffinity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 若会话已绑定且绑定的供应商仍在可用列表中，将其移到首位
+    pub async fn apply(&self, app_type: &str, session_id: &str, providers: &mut Vec<Provider>) {
+        let key = format!("{app_type}:{session_id}");
+        let pinned = {
+            let mut bindings = self.bindings.write().await;
+            match bindings.get_mut(&key) {
+                Some(binding) if binding.last_seen.elapsed() < SESSION_AFFINITY_TTL => {
+                    binding.last_seen = Instant::now();
+                    Some(binding.provider_id.clone())
+                }
+                Some(_) => {
+                    bindings.remove(&key);
+                    None
+                }
+                None => None,
+            }
+        };
+
+        let Some(pinned) = pinned else {
+            return;
+        };
+        match providers.iter().position(|p| p.id == pinned) {
+            Some(0) => {}
+            Some(index) => {
+                let provider = providers.remove(index);
+                providers.insert(0, provider);
+            }
+            None => {
+                log::debug!(
+                    "[{app_type}] 会话 {session_id} 绑定的供应商 {pinned} 当前不可用，回落到路由顺序"
+                );
+            }
+        }
+    }
+
+    /// 会话当前（未过期）绑定的供应商
+    pub async fn bound_provider(&self, app_type: &str, session_id: &str) -> Option<String> {
+        let key = format!("{app_type}:{session_id}");
+        self.bindings
+            .read()
+            .await
+            .get(&key)
+            .filter(|binding| binding.last_seen.elapsed() < SESSION_AFFINITY_TTL)
+            .map(|binding| binding.provider_id.clone())
+    }
+
+    /// 记录会话由指定供应商成功响应
+    ///
+    /// 首次成功即建立绑定；已绑定其他供应商时直接改绑，是否允许改绑由调用方判断。
+    pub async fn bind(&self, app_type: &str, session_id: &str, provider_id: &str) {
+        let key = format!("{app_type}:{session_id}");
+        let mut bindings = self.bindings.write().await;
+        if bindings.len() >= SESSION_AFFINITY_SWEEP_THRESHOLD {
+            bindings.retain(|_, binding| binding.last_seen.elapsed() < SESSION_AFFINITY_TTL);
+        }
+        let binding = bindings.entry(key).or_insert_with(|| SessionBinding {
+            provider_id: provider_id.to_string(),
+            last_seen: Instant::now(),
+        });
+        if binding.provider_id != provider_id {
+            log::info!(
+                "[{app_type}] 会话 {session_id} 改绑供应商: {} → {provider_id}",
+                binding.provider_id
+            );
+            binding.provider_id = provider_id.to_string();
+        }
+        binding.last_seen = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn providers(ids: &[&str]) -> Vec<Provider> {
+        ids.iter()
+            .map(|id| Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None))
+            .collect()
+    }
+
+    fn ids(providers: &[Provider]) -> Vec<&str> {
+        providers.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn bound_session_moves_provider_to_front() {
+        let affinity = SessionAffinity::new();
+        affinity.bind("claude", "s1", "b").await;
+
+        let mut list = providers(&["a", "b", "c"]);
+        affinity.apply("claude", "s1", &mut list).await;
+        assert_eq!(ids(&list), vec!["b", "a", "c"]);
+
+        // 其他会话和其他应用不受影响
+        let mut other = providers(&["a", "b", "c"]);
+        affinity.apply("claude", "s2", &mut other).await;
+        affinity.apply("codex", "s1", &mut other).await;
+        assert_eq!(ids(&other), vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn unavailable_pinned_provider_falls_through_and_rebinds() {
+        let affinity = SessionAffinity::new();
+        affinity.bind("claude", "s1", "b").await;
+
+        // b 熔断：不在可用列表中，保持原顺序
+        let mut list = providers(&["a", "c"]);
+        affinity.apply("claude", "s1", &mut list).await;
+        assert_eq!(ids(&list), vec!["a", "c"]);
+
+        // a 成功响应后改绑
+        affinity.bind("claude", "s1", "a").await;
+        let mut list = providers(&["c", "b", "a"]);
+        affinity.apply("claude", "s1", &mut list).await;
+        assert_eq!(ids(&list), vec!["a", "c", "b"]);
+    }
+}
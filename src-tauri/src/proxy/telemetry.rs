@@ -0,0 +1,746 @@
+//! OpenTelemetry 导出
+//!
+//! 设置了 OTLP/HTTP 采集端点（`AppSettings.otlp_endpoint`）时，每个客户端请求生成一个
+//! SERVER span，`RequestForwarder` 每次向上游发送（含镜像切换、整流重试与故障转移）
+//! 生成一个 CLIENT 子 span。span 上带供应商、模型、Session ID 提取结果、整流器动作
+//! 以及上游返回的 Token 用量；客户端响应体（含流式）发送完毕后整条 trace 进入导出队列。
+//!
+//! 导出使用 OTLP/HTTP 的 JSON 编码：后台任务定期把 span 批量 POST 到
+//! `{endpoint}/v1/traces`，并把 [`ProxyMetrics`] 的累计计数 POST 到 `{endpoint}/v1/metrics`。
+//! 客户端带 W3C `traceparent` 头时沿用其 trace ID，代理 span 挂在调用方 span 之下。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use futures::StreamExt;
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use crate::provider::Provider;
+use crate::proxy::metrics::{CounterValue, ProxyMetrics};
+use crate::proxy::server::ProxyState;
+use crate::proxy::session::SessionIdSource;
+use crate::proxy::usage::parser::TokenUsage;
+
+/// W3C Trace Context 请求头
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+const SCOPE_NAME: &str = "cc-switch.proxy";
+const SERVICE_NAME: &str = "cc-switch-proxy";
+/// span 批量导出间隔
+const TRACE_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// 指标导出间隔（按 trace 导出 tick 的倍数计）
+const METRICS_EVERY_TICKS: u32 = 6;
+/// 采集端点不可达时最多缓存的 span 数，超出丢弃最旧的
+const MAX_PENDING_SPANS: usize = 4096;
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// OTLP 枚举值
+const SPAN_KIND_SERVER: u8 = 2;
+const SPAN_KIND_CLIENT: u8 = 3;
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+const AGGREGATION_CUMULATIVE: u8 = 2;
+
+fn now_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+fn new_trace_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn new_span_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// 解析 `traceparent`（`00-<trace-id>-<parent-id>-<flags>`），返回 (trace ID, 父 span ID)
+fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let mut parts = value.trim().split('-');
+    let (version, trace_id, parent_id) = (parts.next()?, parts.next()?, parts.next()?);
+    parts.next()?;
+    let is_hex = |s: &str, len: usize| {
+        s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit()) && s.bytes().any(|b| b != b'0')
+    };
+    (version == "00" && is_hex(trace_id, 32) && is_hex(parent_id, 16)).then(|| {
+        (
+            trace_id.to_ascii_lowercase(),
+            parent_id.to_ascii_lowercase(),
+        )
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AttrValue {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl AttrValue {
+    fn to_otlp(&self) -> Value {
+        match self {
+            Self::Str(v) => json!({ "stringValue": v }),
+            // OTLP JSON 中 64 位整数编码为字符串
+            Self::Int(v) => json!({ "intValue": v.to_string() }),
+            Self::Bool(v) => json!({ "boolValue": v }),
+        }
+    }
+}
+
+type Attributes = Vec<(String, AttrValue)>;
+
+fn set_attr(attributes: &mut Attributes, key: &str, value: AttrValue) {
+    match attributes.iter_mut().find(|(k, _)| k == key) {
+        Some((_, existing)) => *existing = value,
+        None => attributes.push((key.to_string(), value)),
+    }
+}
+
+fn attributes_to_otlp(attributes: &[(String, AttrValue)]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": value.to_otlp() }))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+struct SpanRecording {
+    span_id: String,
+    name: String,
+    kind: u8,
+    start: u64,
+    end: Option<u64>,
+    attributes: Attributes,
+    /// (时间, 事件名, 属性)
+    events: Vec<(u64, String, Attributes)>,
+    error: Option<String>,
+}
+
+impl SpanRecording {
+    fn new(name: &str, kind: u8) -> Self {
+        Self {
+            span_id: new_span_id(),
+            name: name.to_string(),
+            kind,
+            start: now_nanos(),
+            end: None,
+            attributes: Vec::new(),
+            events: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn to_otlp(&self, trace_id: &str, parent_span_id: Option<&str>, end: u64) -> Value {
+        let status = match &self.error {
+            Some(message) => json!({ "code": STATUS_ERROR, "message": message }),
+            None => json!({ "code": STATUS_OK }),
+        };
+        let mut span = json!({
+            "traceId": trace_id,
+            "spanId": self.span_id,
+            "name": self.name,
+            "kind": self.kind,
+            "startTimeUnixNano": self.start.to_string(),
+            "endTimeUnixNano": self.end.unwrap_or(end).to_string(),
+            "attributes": attributes_to_otlp(&self.attributes),
+            "events": self
+                .events
+                .iter()
+                .map(|(time, name, attributes)| json!({
+                    "timeUnixNano": time.to_string(),
+                    "name": name,
+                    "attributes": attributes_to_otlp(attributes),
+                }))
+                .collect::<Vec<_>>(),
+            "status": status,
+        });
+        if let Some(parent) = parent_span_id {
+            span["parentSpanId"] = json!(parent);
+        }
+        span
+    }
+}
+
+#[derive(Debug)]
+struct TraceRecording {
+    trace_id: String,
+    /// 调用方（`traceparent`）的 span
+    remote_parent: Option<String>,
+    root: SpanRecording,
+    attempts: Vec<SpanRecording>,
+    rectifier_actions: Vec<String>,
+    /// 已整流、尚未重发的动作
+    pending_rectifier: Option<String>,
+}
+
+struct TraceInner {
+    exporter: Arc<TelemetryExporter>,
+    recording: Mutex<TraceRecording>,
+}
+
+impl Drop for TraceInner {
+    fn drop(&mut self) {
+        let recording = self.recording.get_mut().unwrap_or_else(|e| e.into_inner());
+        let end = now_nanos();
+        let mut spans = Vec::with_capacity(recording.attempts.len() + 1);
+        spans.push(recording.root.to_otlp(
+            &recording.trace_id,
+            recording.remote_parent.as_deref(),
+            end,
+        ));
+        for attempt in &recording.attempts {
+            spans.push(attempt.to_otlp(&recording.trace_id, Some(&recording.root.span_id), end));
+        }
+        self.exporter.push(spans);
+    }
+}
+
+/// 一个客户端请求的 trace 记录器
+///
+/// 由 [`telemetry_middleware`] 放入请求 extensions；最后一个克隆释放时
+/// （客户端响应体发送完毕）把整条 trace 放入导出队列。
+#[derive(Clone)]
+pub struct RequestTrace {
+    inner: Arc<TraceInner>,
+}
+
+impl RequestTrace {
+    fn new(
+        exporter: Arc<TelemetryExporter>,
+        method: &str,
+        path: &str,
+        traceparent: Option<&str>,
+    ) -> Self {
+        let (trace_id, remote_parent) = match traceparent.and_then(parse_traceparent) {
+            Some((trace_id, parent)) => (trace_id, Some(parent)),
+            None => (new_trace_id(), None),
+        };
+        let mut root = SpanRecording::new(&format!("{method} {path}"), SPAN_KIND_SERVER);
+        root.attributes
+            .push(("http.request.method".into(), AttrValue::Str(method.into())));
+        root.attributes
+            .push(("url.path".into(), AttrValue::Str(path.into())));
+        Self {
+            inner: Arc::new(TraceInner {
+                exporter,
+                recording: Mutex::new(TraceRecording {
+                    trace_id,
+                    remote_parent,
+                    root,
+                    attempts: Vec::new(),
+                    rectifier_actions: Vec::new(),
+                    pending_rectifier: None,
+                }),
+            }),
+        }
+    }
+
+    fn with_recording<R>(&self, f: impl FnOnce(&mut TraceRecording) -> R) -> R {
+        let mut recording = self
+            .inner
+            .recording
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        f(&mut recording)
+    }
+
+    /// 记录路由结果：应用、请求模型、故障转移链与 Session ID 提取结果
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_request(
+        &self,
+        app_type: &str,
+        request_model: &str,
+        providers: &[Provider],
+        model_route: Option<&str>,
+        session_id: &str,
+        session_source: SessionIdSource,
+        session_client_provided: bool,
+    ) {
+        self.with_recording(|r| {
+            let attrs = &mut r.root.attributes;
+            set_attr(attrs, "cc_switch.app", AttrValue::Str(app_type.into()));
+            set_attr(
+                attrs,
+                "gen_ai.request.model",
+                AttrValue::Str(request_model.into()),
+            );
+            set_attr(
+                attrs,
+                "cc_switch.failover.chain",
+                AttrValue::Str(
+                    providers
+                        .iter()
+                        .map(|p| p.id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                ),
+            );
+            if let Some(route) = model_route {
+                set_attr(attrs, "cc_switch.model_route", AttrValue::Str(route.into()));
+            }
+            set_attr(attrs, "session.id", AttrValue::Str(session_id.into()));
+            set_attr(
+                attrs,
+                "cc_switch.session.source",
+                AttrValue::Str(session_source_name(session_source).into()),
+            );
+            set_attr(
+                attrs,
+                "cc_switch.session.client_provided",
+                AttrValue::Bool(session_client_provided),
+            );
+        });
+    }
+
+    /// 开始一次上游发送，返回该子 span 的序号
+    pub fn start_attempt(&self, provider: &Provider, base_url: Option<&str>) -> usize {
+        self.with_recording(|r| {
+            let mut span = SpanRecording::new("upstream", SPAN_KIND_CLIENT);
+            span.attributes.push((
+                "cc_switch.provider.id".into(),
+                AttrValue::Str(provider.id.clone()),
+            ));
+            span.attributes.push((
+                "cc_switch.provider.name".into(),
+                AttrValue::Str(provider.name.clone()),
+            ));
+            span.attributes.push((
+                "cc_switch.attempt".into(),
+                AttrValue::Int(r.attempts.len() as i64 + 1),
+            ));
+            if let Some(url) = base_url {
+                span.attributes
+                    .push(("server.address".into(), AttrValue::Str(url.into())));
+            }
+            // 紧跟在整流之后的发送即整流重试
+            if let Some(action) = r.pending_rectifier.take() {
+                span.attributes
+                    .push(("cc_switch.rectifier.retry".into(), AttrValue::Str(action)));
+            }
+            r.attempts.push(span);
+            r.attempts.len() - 1
+        })
+    }
+
+    /// 结束一次上游发送：`Ok(状态码)` 或 `Err(错误信息)`
+    pub fn finish_attempt(&self, index: usize, outcome: Result<u16, String>) {
+        self.with_recording(|r| {
+            let Some(span) = r.attempts.get_mut(index) else {
+                return;
+            };
+            span.end = Some(now_nanos());
+            match outcome {
+                Ok(status) => {
+                    span.attributes.push((
+                        "http.response.status_code".into(),
+                        AttrValue::Int(i64::from(status)),
+                    ));
+                    if status >= 400 {
+                        span.error = Some(format!("HTTP {status}"));
+                    }
+                }
+                Err(message) => span.error = Some(message),
+            }
+        });
+    }
+
+    /// 记录整流器动作（如 thinking 签名整流、budget 整流、图片降级）
+    pub fn record_rectifier(&self, action: &str) {
+        self.with_recording(|r| {
+            let time = now_nanos();
+            if let Some(span) = r.attempts.last_mut() {
+                span.events.push((
+                    time,
+                    "rectifier".into(),
+                    vec![(
+                        "cc_switch.rectifier.action".into(),
+                        AttrValue::Str(action.into()),
+                    )],
+                ));
+            }
+            r.pending_rectifier = Some(action.to_string());
+            r.rectifier_actions.push(action.to_string());
+            let actions = r.rectifier_actions.join(",");
+            set_attr(
+                &mut r.root.attributes,
+                "cc_switch.rectifier.actions",
+                AttrValue::Str(actions),
+            );
+        });
+    }
+
+    /// 记录最终响应的模型与 Token 用量
+    pub fn record_usage(&self, provider_id: &str, model: &str, usage: &TokenUsage) {
+        self.with_recording(|r| {
+            let attrs = &mut r.root.attributes;
+            set_attr(
+                attrs,
+                "cc_switch.provider.id",
+                AttrValue::Str(provider_id.into()),
+            );
+            set_attr(attrs, "gen_ai.response.model", AttrValue::Str(model.into()));
+            for (key, value) in [
+                ("gen_ai.usage.input_tokens", usage.input_tokens),
+                ("gen_ai.usage.output_tokens", usage.output_tokens),
+                ("cc_switch.usage.cache_read_tokens", usage.cache_read_tokens),
+                (
+                    "cc_switch.usage.cache_creation_tokens",
+                    usage.cache_creation_tokens,
+                ),
+            ] {
+                set_attr(attrs, key, AttrValue::Int(i64::from(value)));
+            }
+        });
+    }
+
+    fn record_response(&self, status: u16) {
+        self.with_recording(|r| {
+            set_attr(
+                &mut r.root.attributes,
+                "http.response.status_code",
+                AttrValue::Int(i64::from(status)),
+            );
+            if status >= 500 {
+                r.root.error = Some(format!("HTTP {status}"));
+            }
+        });
+    }
+}
+
+fn session_source_name(source: SessionIdSource) -> &'static str {
+    match source {
+        SessionIdSource::MetadataUserId => "metadata_user_id",
+        SessionIdSource::MetadataSessionId => "metadata_session_id",
+        SessionIdSource::Header => "header",
+        SessionIdSource::Generated => "generated",
+    }
+}
+
+/// 待导出 span 队列
+#[derive(Default)]
+pub struct TelemetryExporter {
+    pending: Mutex<Vec<Value>>,
+}
+
+impl TelemetryExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&self, spans: Vec<Value>) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.extend(spans);
+        if pending.len() > MAX_PENDING_SPANS {
+            let overflow = pending.len() - MAX_PENDING_SPANS;
+            pending.drain(..overflow);
+        }
+    }
+
+    fn take(&self) -> Vec<Value> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// 为客户端请求创建 trace；未设置 OTLP 端点时直接放行
+pub async fn telemetry_middleware(
+    State(state): State<ProxyState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    if !should_trace(&path) || crate::settings::get_otlp_endpoint().is_none() {
+        return next.run(request).await;
+    }
+
+    let traceparent = request
+        .headers()
+        .get(TRACEPARENT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let trace = RequestTrace::new(
+        state.telemetry.clone(),
+        request.method().as_str(),
+        &path,
+        traceparent.as_deref(),
+    );
+    request.extensions_mut().insert(trace.clone());
+
+    let response = next.run(request).await;
+    trace.record_response(response.status().as_u16());
+    let (parts, body) = response.into_parts();
+    // 闭包持有 trace，响应体结束或客户端断开时随流一起释放
+    let stream = body.into_data_stream().inspect(move |chunk| {
+        if chunk.is_err() {
+            trace.with_recording(|r| {
+                r.root
+                    .error
+                    .get_or_insert_with(|| "response stream error".into());
+            });
+        }
+    });
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+fn should_trace(path: &str) -> bool {
+    !(matches!(path, "/health" | "/status" | "/metrics") || path.starts_with("/mcp/"))
+}
+
+/// 启动导出任务；由 `ProxyServer` 持有句柄并在停止时 abort
+pub(crate) fn spawn(
+    exporter: Arc<TelemetryExporter>,
+    metrics: Arc<ProxyMetrics>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        // 采集端点通常在本机，不经过用户配置的上游代理
+        let client = match reqwest::Client::builder()
+            .no_proxy()
+            .timeout(EXPORT_TIMEOUT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("[OTLP] 创建导出客户端失败: {e}");
+                return;
+            }
+        };
+        let start_time = now_nanos();
+        let mut ticker = tokio::time::interval(TRACE_FLUSH_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut tick = 0u32;
+
+        loop {
+            ticker.tick().await;
+            tick = tick.wrapping_add(1);
+            let Some(endpoint) = crate::settings::get_otlp_endpoint() else {
+                // 关闭导出后丢弃已排队的 span
+                exporter.take();
+                continue;
+            };
+
+            let spans = exporter.take();
+            if !spans.is_empty() {
+                post(&client, &endpoint, "v1/traces", traces_payload(spans)).await;
+            }
+            if tick % METRICS_EVERY_TICKS == 0 {
+                let payload = metrics_payload(&metrics, start_time, now_nanos());
+                post(&client, &endpoint, "v1/metrics", payload).await;
+            }
+        }
+    })
+}
+
+async fn post(client: &reqwest::Client, endpoint: &str, signal: &str, payload: Value) {
+    let url = format!("{}/{signal}", endpoint.trim_end_matches('/'));
+    match client.post(&url).json(&payload).send().await {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => log::warn!("[OTLP] 导出到 {url} 失败: HTTP {}", response.status()),
+        Err(e) => log::debug!("[OTLP] 导出到 {url} 失败: {e}"),
+    }
+}
+
+fn resource() -> Value {
+    json!({
+        "attributes": [
+            { "key": "service.name", "value": { "stringValue": SERVICE_NAME } },
+            { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+        ]
+    })
+}
+
+fn scope() -> Value {
+    json!({ "name": SCOPE_NAME, "version": env!("CARGO_PKG_VERSION") })
+}
+
+fn traces_payload(spans: Vec<Value>) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": resource(),
+            "scopeSpans": [{ "scope": scope(), "spans": spans }],
+        }]
+    })
+}
+
+fn metrics_payload(metrics: &ProxyMetrics, start_time: u64, now: u64) -> Value {
+    let mut by_name: Vec<(&'static str, &'static str, Vec<Value>)> = Vec::new();
+    for point in metrics.counters() {
+        let attributes: Vec<Value> = point
+            .attributes
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+            .collect();
+        let mut data_point = json!({
+            "attributes": attributes,
+            "startTimeUnixNano": start_time.to_string(),
+            "timeUnixNano": now.to_string(),
+        });
+        match point.value {
+            CounterValue::Int(v) => data_point["asInt"] = json!(v.to_string()),
+            CounterValue::Double(v) => data_point["asDouble"] = json!(v),
+        }
+        match by_name.iter_mut().find(|(name, _, _)| *name == point.name) {
+            Some((_, _, points)) => points.push(data_point),
+            None => by_name.push((point.name, point.unit, vec![data_point])),
+        }
+    }
+    let metrics: Vec<Value> = by_name
+        .into_iter()
+        .map(|(name, unit, data_points)| {
+            json!({
+                "name": name,
+                "unit": unit,
+                "sum": {
+                    "aggregationTemporality": AGGREGATION_CUMULATIVE,
+                    "isMonotonic": true,
+                    "dataPoints": data_points,
+                },
+            })
+        })
+        .collect();
+    json!({
+        "resourceMetrics": [{
+            "resource": resource(),
+            "scopeMetrics": [{ "scope": scope(), "metrics": metrics }],
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn provider(id: &str) -> Provider {
+        Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None)
+    }
+
+    #[test]
+    fn traceparent_is_validated() {
+        assert_eq!(
+            parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+            Some((
+                "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+                "00f067aa0ba902b7".to_string()
+            ))
+        );
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(
+            parse_traceparent("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(parse_traceparent("garbage").is_none());
+    }
+
+    #[test]
+    fn dropped_trace_exports_root_and_attempt_spans() {
+        let exporter = Arc::new(TelemetryExporter::new());
+        let trace = RequestTrace::new(
+            exporter.clone(),
+            "POST",
+            "/v1/messages",
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        trace.record_request(
+            "claude",
+            "claude-sonnet-4-5",
+            &[provider("a"), provider("b")],
+            None,
+            "session-1",
+            SessionIdSource::MetadataUserId,
+            true,
+        );
+        let first = trace.start_attempt(&provider("a"), None);
+        trace.finish_attempt(first, Ok(400));
+        trace.record_rectifier("thinking_signature");
+        let second = trace.start_attempt(&provider("a"), None);
+        trace.finish_attempt(second, Ok(200));
+        trace.record_usage(
+            "a",
+            "claude-sonnet-4-5-20250929",
+            &TokenUsage {
+                input_tokens: 12,
+                output_tokens: 34,
+                ..Default::default()
+            },
+        );
+        trace.record_response(200);
+
+        let clone = trace.clone();
+        drop(trace);
+        assert!(
+            exporter.take().is_empty(),
+            "exported before last clone dropped"
+        );
+        drop(clone);
+
+        let spans = exporter.take();
+        assert_eq!(spans.len(), 3);
+        let root = &spans[0];
+        assert_eq!(root["traceId"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(root["parentSpanId"], "00f067aa0ba902b7");
+        assert_eq!(root["kind"], SPAN_KIND_SERVER);
+        let attr = |span: &Value, key: &str| {
+            span["attributes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|a| a["key"] == key)
+                .map(|a| a["value"].clone())
+        };
+        assert_eq!(
+            attr(root, "cc_switch.session.source"),
+            Some(json!({ "stringValue": "metadata_user_id" }))
+        );
+        assert_eq!(
+            attr(root, "gen_ai.usage.output_tokens"),
+            Some(json!({ "intValue": "34" }))
+        );
+        assert_eq!(
+            attr(root, "cc_switch.rectifier.actions"),
+            Some(json!({ "stringValue": "thinking_signature" }))
+        );
+
+        let (failed, retried) = (&spans[1], &spans[2]);
+        assert_eq!(failed["parentSpanId"], root["spanId"]);
+        assert_eq!(failed["status"]["code"], STATUS_ERROR);
+        assert_eq!(failed["events"][0]["name"], "rectifier");
+        assert_eq!(retried["status"]["code"], STATUS_OK);
+        assert_eq!(
+            attr(retried, "cc_switch.rectifier.retry"),
+            Some(json!({ "stringValue": "thinking_signature" }))
+        );
+    }
+
+    #[test]
+    fn metrics_payload_groups_points_by_name() {
+        let metrics = ProxyMetrics::new();
+        metrics.record_request("claude", "a", "m", 200, 10, None, None);
+        metrics.record_request("claude", "a", "m", 500, 10, None, None);
+        metrics.record_cost("claude", "a", "m", 0.5);
+
+        let payload = metrics_payload(&metrics, 1, 2);
+        let list = payload["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap();
+        let requests = list
+            .iter()
+            .find(|m| m["name"] == "cc_switch.proxy.requests")
+            .unwrap();
+        assert_eq!(requests["sum"]["dataPoints"].as_array().unwrap().len(), 2);
+        assert_eq!(requests["sum"]["dataPoints"][0]["asInt"], "1");
+        let cost = list
+            .iter()
+            .find(|m| m["name"] == "cc_switch.proxy.cost")
+            .unwrap();
+        assert_eq!(cost["sum"]["dataPoints"][0]["asDouble"], 0.5);
+    }
+}
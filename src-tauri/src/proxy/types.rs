@@ -184,6 +184,71 @@ pub struct AppProxyConfig {
     pub circuit_error_rate_threshold: f64,
     /// 计算错误率的最小请求数
     pub circuit_min_requests: u32,
+    /// 故障转移队列的路由策略
+    #[serde(default)]
+    pub routing_strategy: RoutingStrategy,
+    /// 按请求模型分流的规则（按顺序匹配，先于故障转移队列生效）
+    #[serde(default)]
+    pub model_routes: Vec<ModelRoute>,
+    /// 订阅/套餐额度阈值（百分比，0 表示关闭）：任一窗口用量达到阈值的供应商
+    /// 在窗口重置前排到队列末尾
+    #[serde(default)]
+    pub quota_threshold: u32,
+}
+
+/// 模型路由规则
+///
+/// 请求模型匹配 `pattern`（`*` 通配，不区分大小写）时改走 `provider_ids`，
+/// 列表顺序即该规则自己的故障转移顺序。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRoute {
+    pub pattern: String,
+    pub provider_ids: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 故障转移队列路由策略
+///
+/// 决定可用供应商的尝试顺序；排在首位的供应商承接请求，其余作为故障转移备选。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingStrategy {
+    /// 严格按队列顺序（P1 → P2 → ...）
+    #[default]
+    Priority,
+    /// 每个请求轮换首选供应商
+    RoundRobin,
+    /// 按供应商权重（`routingWeight`）平滑加权轮询
+    Weighted,
+    /// 按近期请求平均延迟从低到高
+    LeastLatency,
+}
+
+impl RoutingStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoutingStrategy::Priority => "priority",
+            RoutingStrategy::RoundRobin => "round_robin",
+            RoutingStrategy::Weighted => "weighted",
+            RoutingStrategy::LeastLatency => "least_latency",
+        }
+    }
+}
+
+impl std::str::FromStr for RoutingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "priority" => Ok(RoutingStrategy::Priority),
+            "round_robin" => Ok(RoutingStrategy::RoundRobin),
+            "weighted" => Ok(RoutingStrategy::Weighted),
+            "least_latency" => Ok(RoutingStrategy::LeastLatency),
+            other => Err(format!("unknown routing strategy: {other}")),
+        }
+    }
 }
 
 /// 整流器配置
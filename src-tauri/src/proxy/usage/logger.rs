@@ -98,7 +98,10 @@ impl<'a> UsageLogger<'a> {
     }
 
     /// 记录成功的请求
-    pub fn log_request(&self, log: &RequestLog) -> Result<(), AppError> {
+    ///
+    /// 返回是否真正写入了一行；同一 request_id 已由代理记录过（上游重放、
+    /// 客户端重试）时返回 false，调用方据此避免重复累计花费。
+    pub fn log_request(&self, log: &RequestLog) -> Result<bool, AppError> {
         let conn = crate::database::lock_conn!(self.db.conn);
 
         let (input_cost, output_cost, cache_read_cost, cache_creation_cost, total_cost) =
@@ -141,7 +144,7 @@ impl<'a> UsageLogger<'a> {
                 if data_source.as_deref().unwrap_or("proxy") == "proxy"
                     && existing_semantic == semantic =>
             {
-                return Ok(());
+                return Ok(false);
             }
             Some(_) => {
                 let fallback = format!("{}:collision:{}", log.request_id, semantic.sha256());
@@ -151,7 +154,7 @@ impl<'a> UsageLogger<'a> {
                     if data_source.as_deref().unwrap_or("proxy") == "proxy"
                         && existing_semantic == semantic
                     {
-                        return Ok(());
+                        return Ok(false);
                     }
                     return Err(AppError::Database(format!(
                         "usage collision fallback 主键发生 SHA-256 冲突: {fallback}"
@@ -161,6 +164,9 @@ impl<'a> UsageLogger<'a> {
             }
         };
 
+        // project_dir 按会话日志同步记下的 session → 项目映射反查；Responses 客户端的
+        // session_id 带 `<app>_` 前缀，去掉后再匹配一次。映射尚未同步时留 NULL，
+        // 由 `record_session_project_on_conn` 事后回填。
         let insert_verb = if replace_session_log {
             "INSERT OR REPLACE"
         } else {
@@ -173,8 +179,10 @@ impl<'a> UsageLogger<'a> {
                 input_token_semantics,
                 input_cost_usd, output_cost_usd, cache_read_cost_usd, cache_creation_cost_usd, total_cost_usd,
                 latency_ms, first_token_ms, status_code, error_message, session_id,
-                provider_type, is_streaming, cost_multiplier, created_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)"
+                provider_type, is_streaming, cost_multiplier, created_at, project_dir
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25,
+                (SELECT project_dir FROM session_projects
+                 WHERE session_id IN (?21, substr(?21, length(?3) + 2))))"
         );
         let affected_rows = conn
             .execute(
@@ -219,7 +227,7 @@ impl<'a> UsageLogger<'a> {
             crate::usage_events::notify_log_recorded();
         }
 
-        Ok(())
+        Ok(affected_rows > 0)
     }
 
     fn load_existing_semantic(
@@ -288,7 +296,7 @@ impl<'a> UsageLogger<'a> {
             cost_multiplier: "1.0".to_string(),
         };
 
-        self.log_request(&log)
+        self.log_request(&log).map(|_| ())
     }
 
     /// 记录失败的请求（带更多上下文信息）
@@ -329,7 +337,7 @@ impl<'a> UsageLogger<'a> {
             cost_multiplier: "1.0".to_string(),
         };
 
-        self.log_request(&log)
+        self.log_request(&log).map(|_| ())
     }
 
     /// 获取模型定价
@@ -442,6 +450,9 @@ impl<'a> UsageLogger<'a> {
     }
 
     /// 计算并记录请求
+    ///
+    /// 返回本次新写入请求的总花费（USD），未找到定价时为 0；请求已记录过
+    /// （重复/重试）时返回 None，调用方不得再次累计花费与指标。
     #[allow(clippy::too_many_arguments)]
     pub fn log_with_calculation(
         &self,
@@ -459,7 +470,7 @@ impl<'a> UsageLogger<'a> {
         session_id: Option<String>,
         provider_type: Option<String>,
         is_streaming: bool,
-    ) -> Result<(), AppError> {
+    ) -> Result<Option<Decimal>, AppError> {
         let pricing = self.get_model_pricing(&pricing_model)?;
 
         let has_usage = usage.input_tokens > 0
@@ -497,7 +508,15 @@ impl<'a> UsageLogger<'a> {
             cost_multiplier: cost_multiplier.to_string(),
         };
 
-        self.log_request(&log)
+        if !self.log_request(&log)? {
+            return Ok(None);
+        }
+        Ok(Some(
+            log.cost
+                .as_ref()
+                .map(|cost| cost.total_cost)
+                .unwrap_or(Decimal::ZERO),
+        ))
     }
 }
 
@@ -590,6 +609,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn duplicate_request_id_returns_cost_only_once() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let logger = UsageLogger::new(&db);
+        let usage = TokenUsage {
+            input_tokens: 1000,
+            output_tokens: 500,
+            ..TokenUsage::default()
+        };
+        let log_once = || {
+            logger.log_with_calculation(
+                "req-dup".to_string(),
+                "provider-1".to_string(),
+                "claude".to_string(),
+                "test-model".to_string(),
+                "test-model".to_string(),
+                "test-model".to_string(),
+                usage.clone(),
+                Decimal::from(1),
+                100,
+                None,
+                200,
+                None,
+                Some("claude".to_string()),
+                false,
+            )
+        };
+
+        assert!(log_once()?.is_some());
+        assert_eq!(log_once()?, None);
+
+        let conn = crate::database::lock_conn!(db.conn);
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM proxy_request_logs WHERE request_id = 'req-dup'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+        Ok(())
+    }
+
     #[test]
     fn identical_replay_writes_and_notifies_once() -> Result<(), AppError> {
         let db = Database::memory()?;
@@ -738,6 +800,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn proxy_log_inherits_project_from_synced_session() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = crate::database::lock_conn!(db.conn);
+            crate::services::session_usage::record_session_project_on_conn(
+                &conn,
+                "codex",
+                "0199a1b2-thread",
+                "/work/app",
+                None,
+            )?;
+        }
+        let mut log = request_log("codex-project", 10);
+        log.session_id = Some("codex_0199a1b2-thread".to_string());
+        UsageLogger::new(&db).log_request(&log)?;
+
+        let conn = crate::database::lock_conn!(db.conn);
+        let project_dir: Option<String> = conn.query_row(
+            "SELECT project_dir FROM proxy_request_logs WHERE request_id = 'codex-project'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(project_dir.as_deref(), Some("/work/app"));
+        Ok(())
+    }
+
     #[test]
     fn test_log_error() -> Result<(), AppError> {
         let db = Database::memory()?;
@@ -0,0 +1,584 @@
+//! 敏感字段加密（本地主密钥 + AES-256-GCM）
+//!
+//! 供应商 `settings_config` 中的 API Key / Token，以及 settings.json 里的 WebDAV 密码、
+//! S3 Secret Access Key 在落盘前加密为 `enc:v1:<base64(nonce || ciphertext)>`，
+//! 读取时解密；没有前缀的旧明文照常读取，并在启动迁移或下次写入时加密。
+//! 带前缀却无法解密的值一律报错，绝不把密文当作密钥继续使用。
+//!
+//! 同步快照 `db.sql`：配置了同步口令时，供应商密钥以明文导出、由整个快照的端到端加密
+//! 保护（见 `services::sync_protocol`），接收端导入时再用本机主密钥加密；未配置口令时
+//! 远端只拿到本机密文，仅共用同一份 master.key 的设备能够导入。
+//!
+//! 主密钥保存在 `~/.cc-switch/master.key`（权限 0600），首次使用时随机生成。
+//! 可选口令：设置后密钥文件只保存经 Argon2id(口令) 包裹的主密钥，进程启动时从环境变量
+//! `CC_SWITCH_SECRETS_PASSPHRASE` 读取口令解锁。
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// 密文前缀（带版本，便于将来更换算法）
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// 解锁口令保护的主密钥时读取的环境变量
+pub const PASSPHRASE_ENV: &str = "CC_SWITCH_SECRETS_PASSPHRASE";
+
+pub(crate) const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+pub(crate) const SALT_LEN: usize = 16;
+const KEY_FILE_VERSION: u32 = 1;
+
+/// 主密钥文件
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyFile {
+    version: u32,
+    /// 未设置口令时直接保存的主密钥（base64）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    /// 设置口令后保存的包裹密钥
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wrapped: Option<WrappedKey>,
+}
+
+/// Argon2id(口令, salt) 派生的密钥包裹的主密钥
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WrappedKey {
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    /// base64(nonce || ciphertext)
+    ciphertext: String,
+}
+
+fn key_file_path() -> PathBuf {
+    crate::config::get_app_config_dir().join("master.key")
+}
+
+fn passphrase_from_env() -> Option<String> {
+    std::env::var(PASSPHRASE_ENV)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+pub(crate) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+pub(crate) fn derive_passphrase_key(
+    passphrase: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<[u8; KEY_LEN], AppError> {
+    let params = argon2::Params::new(m_cost, t_cost, p_cost, Some(KEY_LEN))
+        .map_err(|e| AppError::Config(format!("无效的 Argon2 参数: {e}")))?;
+    let argon = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let mut key = [0u8; KEY_LEN];
+    argon
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Config(format!("口令派生密钥失败: {e}")))?;
+    Ok(key)
+}
+
+pub(crate) fn seal(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+    let cipher = Aes256Gcm::new(key.into());
+    let nonce = random_bytes::<NONCE_LEN>();
+    let mut out = nonce.to_vec();
+    out.extend(
+        cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| AppError::Message("加密失败".to_string()))?,
+    );
+    Ok(out)
+}
+
+pub(crate) fn open(key: &[u8; KEY_LEN], sealed: &[u8]) -> Result<Vec<u8>, AppError> {
+    if sealed.len() < NONCE_LEN {
+        return Err(AppError::Message("密文长度无效".to_string()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::Message("解密失败：主密钥不匹配或数据已损坏".to_string()))
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; KEY_LEN], AppError> {
+    BASE64
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+        .ok_or_else(|| AppError::Config("主密钥文件格式无效".to_string()))
+}
+
+fn build_key_file(key: &[u8; KEY_LEN], passphrase: Option<&str>) -> Result<KeyFile, AppError> {
+    let Some(passphrase) = passphrase else {
+        return Ok(KeyFile {
+            version: KEY_FILE_VERSION,
+            key: Some(BASE64.encode(key)),
+            wrapped: None,
+        });
+    };
+
+    let defaults = argon2::Params::default();
+    let (m_cost, t_cost, p_cost) = (defaults.m_cost(), defaults.t_cost(), defaults.p_cost());
+    let salt = random_bytes::<SALT_LEN>();
+    let wrapping_key = derive_passphrase_key(passphrase, &salt, m_cost, t_cost, p_cost)?;
+    Ok(KeyFile {
+        version: KEY_FILE_VERSION,
+        key: None,
+        wrapped: Some(WrappedKey {
+            salt: BASE64.encode(salt),
+            m_cost,
+            t_cost,
+            p_cost,
+            ciphertext: BASE64.encode(seal(&wrapping_key, key)?),
+        }),
+    })
+}
+
+fn write_key_file(path: &Path, file: &KeyFile) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let json =
+        serde_json::to_string_pretty(file).map_err(|e| AppError::JsonSerialize { source: e })?;
+
+    #[cfg(unix)]
+    {
+        use std::fs::OpenOptions;
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut handle = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|e| AppError::io(path, e))?;
+        handle
+            .write_all(json.as_bytes())
+            .map_err(|e| AppError::io(path, e))?;
+    }
+
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, json).map_err(|e| AppError::io(path, e))?;
+    }
+
+    Ok(())
+}
+
+/// 读取（不存在时生成）主密钥
+fn load_or_create_key(path: &Path, passphrase: Option<&str>) -> Result<[u8; KEY_LEN], AppError> {
+    if !path.exists() {
+        let key = random_bytes::<KEY_LEN>();
+        write_key_file(path, &build_key_file(&key, passphrase)?)?;
+        log::info!("已生成主密钥: {}", path.display());
+        return Ok(key);
+    }
+
+    let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    let file: KeyFile = serde_json::from_str(&content)
+        .map_err(|e| AppError::Config(format!("主密钥文件格式无效: {e}")))?;
+
+    match (file.key, file.wrapped) {
+        (Some(key), _) => decode_key(&key),
+        (None, Some(wrapped)) => {
+            let passphrase = passphrase.ok_or_else(|| {
+                AppError::Config(format!("主密钥受口令保护，请设置环境变量 {PASSPHRASE_ENV}"))
+            })?;
+            let salt = BASE64
+                .decode(&wrapped.salt)
+                .map_err(|e| AppError::Config(format!("主密钥文件格式无效: {e}")))?;
+            let wrapping_key = derive_passphrase_key(
+                passphrase,
+                &salt,
+                wrapped.m_cost,
+                wrapped.t_cost,
+                wrapped.p_cost,
+            )?;
+            let sealed = BASE64
+                .decode(&wrapped.ciphertext)
+                .map_err(|e| AppError::Config(format!("主密钥文件格式无效: {e}")))?;
+            let key = open(&wrapping_key, &sealed)
+                .map_err(|_| AppError::Config("口令错误，无法解锁主密钥".to_string()))?;
+            <[u8; KEY_LEN]>::try_from(key)
+                .map_err(|_| AppError::Config("主密钥文件格式无效".to_string()))
+        }
+        (None, None) => Err(AppError::Config("主密钥文件格式无效".to_string())),
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_MASTER_KEY: std::cell::Cell<Option<[u8; KEY_LEN]>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// 在当前线程内以指定主密钥执行 `f`，模拟另一台设备
+#[cfg(test)]
+pub(crate) fn with_test_master_key<T>(key: [u8; KEY_LEN], f: impl FnOnce() -> T) -> T {
+    let previous = TEST_MASTER_KEY.with(|cell| cell.replace(Some(key)));
+    let result = f();
+    TEST_MASTER_KEY.with(|cell| cell.set(previous));
+    result
+}
+
+/// 进程内缓存的主密钥；加载失败时为 None（加密停用，密文原样保留）
+fn master_key() -> Option<[u8; KEY_LEN]> {
+    #[cfg(test)]
+    if let Some(key) = TEST_MASTER_KEY.with(|cell| cell.get()) {
+        return Some(key);
+    }
+
+    static MASTER_KEY: OnceLock<Option<[u8; KEY_LEN]>> = OnceLock::new();
+    *MASTER_KEY.get_or_init(|| {
+        // 单元测试使用进程内临时密钥，避免在真实用户目录生成密钥文件
+        if cfg!(test) {
+            return Some(random_bytes::<KEY_LEN>());
+        }
+        match load_or_create_key(&key_file_path(), passphrase_from_env().as_deref()) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                log::error!("加载主密钥失败，敏感字段将不会加密: {e}");
+                None
+            }
+        }
+    })
+}
+
+/// 主密钥是否可用
+pub fn is_available() -> bool {
+    master_key().is_some()
+}
+
+/// 为主密钥设置（`Some`）或移除（`None`）口令，重写 master.key
+pub fn set_passphrase(passphrase: Option<&str>) -> Result<(), AppError> {
+    let key = master_key().ok_or_else(|| AppError::Config("主密钥不可用".to_string()))?;
+    write_key_file(&key_file_path(), &build_key_file(&key, passphrase)?)
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// 加密字符串；空串与已加密的值原样返回
+pub fn encrypt_str(plaintext: &str) -> Result<String, AppError> {
+    if plaintext.is_empty() || is_encrypted(plaintext) {
+        return Ok(plaintext.to_string());
+    }
+    let key = master_key().ok_or_else(|| AppError::Config("主密钥不可用".to_string()))?;
+    Ok(format!(
+        "{ENCRYPTED_PREFIX}{}",
+        BASE64.encode(seal(&key, plaintext.as_bytes())?)
+    ))
+}
+
+/// 解密字符串；未加密的旧明文原样返回
+pub fn decrypt_str(value: &str) -> Result<String, AppError> {
+    let Some(encoded) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let key = master_key().ok_or_else(|| AppError::Config("主密钥不可用".to_string()))?;
+    let sealed = BASE64
+        .decode(encoded)
+        .map_err(|e| AppError::Message(format!("密文格式无效: {e}")))?;
+    String::from_utf8(open(&key, &sealed)?)
+        .map_err(|e| AppError::Message(format!("密文内容不是有效的 UTF-8: {e}")))
+}
+
+/// 原地加密；失败时保持原值不变并返回错误，调用方不能再把明文落盘
+pub fn encrypt_in_place(value: &mut String) -> Result<(), AppError> {
+    *value = encrypt_str(value)?;
+    Ok(())
+}
+
+/// 原地解密；失败时保持原值不变并返回错误
+pub fn decrypt_in_place(value: &mut String) -> Result<(), AppError> {
+    *value = decrypt_str(value)?;
+    Ok(())
+}
+
+/// 按字段名判断是否为敏感字段（API Key、Token、密码等）
+fn is_secret_key(name: &str) -> bool {
+    let normalized: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    [
+        "apikey",
+        "authtoken",
+        "accesstoken",
+        "refreshtoken",
+        "bearertoken",
+        "secret",
+        "password",
+    ]
+    .iter()
+    .any(|marker| normalized.contains(marker))
+}
+
+fn walk_secret_strings(value: &mut Value, visit: &mut impl FnMut(&mut String)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(s) if is_secret_key(key) => visit(s),
+                    _ => walk_secret_strings(child, visit),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                walk_secret_strings(item, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 加密 JSON 中所有敏感字段（供应商 settings_config 落库前调用）
+pub fn encrypt_secret_fields(value: &mut Value) -> Result<(), AppError> {
+    let mut result = Ok(());
+    walk_secret_strings(value, &mut |s: &mut String| {
+        if result.is_ok() {
+            result = encrypt_in_place(s);
+        }
+    });
+    result
+}
+
+/// 解密 JSON 中所有敏感字段（供应商 settings_config 读出后调用）
+#[cfg(feature = "gui")]
+pub fn decrypt_secret_fields(value: &mut Value) -> Result<(), AppError> {
+    let mut result = Ok(());
+    walk_secret_strings(value, &mut |s: &mut String| {
+        if result.is_ok() {
+            result = decrypt_in_place(s);
+        }
+    });
+    result
+}
+
+/// 尽量解密 JSON 中的敏感字段：无法解密的字段清空，不让密文流向 live 配置或上游；
+/// 其余字段照常解密。有字段被清空时返回第一个错误
+pub fn decrypt_secret_fields_lossy(value: &mut Value) -> Result<(), AppError> {
+    let mut result = Ok(());
+    walk_secret_strings(value, &mut |s: &mut String| {
+        if let Err(e) = decrypt_in_place(s) {
+            s.clear();
+            if result.is_ok() {
+                result = Err(e);
+            }
+        }
+    });
+    result
+}
+
+/// 保存前把 `incoming` 中为空、而 `stored` 同一位置仍是本机解密不了的密文的敏感字段
+/// 还原为该密文，避免编辑被锁定的供应商时把密钥清空（设置口令后仍可解密）
+pub fn keep_locked_secret_fields(incoming: &mut Value, stored: &Value) {
+    match (incoming, stored) {
+        (Value::Object(map), Value::Object(stored_map)) => {
+            for (key, child) in map.iter_mut() {
+                let Some(stored_child) = stored_map.get(key) else {
+                    continue;
+                };
+                match (child, stored_child) {
+                    (Value::String(s), Value::String(stored_s)) if is_secret_key(key) => {
+                        if s.is_empty() && is_encrypted(stored_s) && decrypt_str(stored_s).is_err()
+                        {
+                            *s = stored_s.clone();
+                        }
+                    }
+                    (child, stored_child) => keep_locked_secret_fields(child, stored_child),
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(stored_items)) => {
+            for (item, stored_item) in items.iter_mut().zip(stored_items) {
+                keep_locked_secret_fields(item, stored_item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 确保 JSON 中的敏感字段都是本机主密钥可解密的密文：明文就地加密，
+/// 已有密文只校验不重写（避免随机 nonce 让未改动的行看起来变了）
+pub fn seal_secret_fields(value: &mut Value) -> Result<(), AppError> {
+    let mut result = Ok(());
+    walk_secret_strings(value, &mut |s: &mut String| {
+        if result.is_err() {
+            return;
+        }
+        result = if is_encrypted(s) {
+            decrypt_str(s).map(|_| ())
+        } else {
+            encrypt_in_place(s)
+        };
+    });
+    result
+}
+
+/// JSON 中是否还有未加密的敏感字段
+pub fn has_plaintext_secrets(value: &mut Value) -> bool {
+    let mut found = false;
+    walk_secret_strings(value, &mut |s: &mut String| {
+        found |= !s.is_empty() && !is_encrypted(s);
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_and_passes_through_plaintext() {
+        let encrypted = encrypt_str("sk-test").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_ne!(encrypt_str("sk-test").unwrap(), encrypted, "nonce 应随机");
+        assert_eq!(decrypt_str(&encrypted).unwrap(), "sk-test");
+
+        assert_eq!(decrypt_str("legacy-plain").unwrap(), "legacy-plain");
+        assert_eq!(encrypt_str("").unwrap(), "");
+        assert_eq!(encrypt_str(&encrypted).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn only_secret_fields_are_encrypted() {
+        let mut config = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-ant",
+                "ANTHROPIC_BASE_URL": "https://api.example.com",
+                "ANTHROPIC_MODEL": "claude"
+            },
+            "auth": { "OPENAI_API_KEY": "sk-openai" },
+            "options": { "apiKey": "sk-opencode", "max_tokens": 100 }
+        });
+        assert!(has_plaintext_secrets(&mut config));
+
+        encrypt_secret_fields(&mut config).unwrap();
+        assert!(is_encrypted(
+            config["env"]["ANTHROPIC_AUTH_TOKEN"].as_str().unwrap()
+        ));
+        assert!(is_encrypted(
+            config["auth"]["OPENAI_API_KEY"].as_str().unwrap()
+        ));
+        assert!(is_encrypted(config["options"]["apiKey"].as_str().unwrap()));
+        assert_eq!(
+            config["env"]["ANTHROPIC_BASE_URL"],
+            "https://api.example.com"
+        );
+        assert_eq!(config["options"]["max_tokens"], 100);
+        assert!(!has_plaintext_secrets(&mut config));
+
+        decrypt_secret_fields(&mut config).unwrap();
+        assert_eq!(config["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-ant");
+        assert_eq!(config["auth"]["OPENAI_API_KEY"], "sk-openai");
+    }
+
+    #[test]
+    fn undecryptable_ciphertext_is_an_error() {
+        let foreign_key = random_bytes::<KEY_LEN>();
+        let foreign = format!(
+            "{ENCRYPTED_PREFIX}{}",
+            BASE64.encode(seal(&foreign_key, b"sk-other-device").unwrap())
+        );
+        let mut config = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": foreign.clone() } });
+
+        assert!(decrypt_secret_fields(&mut config).is_err());
+        assert!(seal_secret_fields(&mut config).is_err());
+        assert_eq!(config["env"]["ANTHROPIC_AUTH_TOKEN"], foreign);
+
+        let mut value = foreign.clone();
+        assert!(decrypt_in_place(&mut value).is_err());
+        assert_eq!(value, foreign);
+    }
+
+    #[test]
+    fn lossy_decrypt_blanks_only_undecryptable_fields_and_saves_keep_them() {
+        let foreign_key = random_bytes::<KEY_LEN>();
+        let foreign = format!(
+            "{ENCRYPTED_PREFIX}{}",
+            BASE64.encode(seal(&foreign_key, b"sk-other-device").unwrap())
+        );
+        let stored = json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": foreign.clone() },
+            "auth": { "OPENAI_API_KEY": encrypt_str("sk-local").unwrap() }
+        });
+
+        let mut opened = stored.clone();
+        assert!(decrypt_secret_fields_lossy(&mut opened).is_err());
+        assert_eq!(opened["env"]["ANTHROPIC_AUTH_TOKEN"], "");
+        assert_eq!(opened["auth"]["OPENAI_API_KEY"], "sk-local");
+
+        // 未重新填写的锁定字段保留原密文，新填写的值照常保存
+        keep_locked_secret_fields(&mut opened, &stored);
+        assert_eq!(opened["env"]["ANTHROPIC_AUTH_TOKEN"], foreign);
+        let mut edited = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-new" } });
+        keep_locked_secret_fields(&mut edited, &stored);
+        assert_eq!(edited["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-new");
+    }
+
+    #[test]
+    fn seal_keeps_existing_ciphertext_and_encrypts_plaintext() {
+        let existing = encrypt_str("sk-local").unwrap();
+        let mut config = json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": existing.clone() },
+            "auth": { "OPENAI_API_KEY": "sk-synced" }
+        });
+
+        seal_secret_fields(&mut config).unwrap();
+        assert_eq!(config["env"]["ANTHROPIC_AUTH_TOKEN"], existing);
+        let sealed = config["auth"]["OPENAI_API_KEY"].as_str().unwrap();
+        assert!(is_encrypted(sealed));
+        assert_eq!(decrypt_str(sealed).unwrap(), "sk-synced");
+    }
+
+    #[test]
+    fn key_file_is_created_and_unlocked_with_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain_path = dir.path().join("plain.key");
+        let key = load_or_create_key(&plain_path, None).unwrap();
+        assert_eq!(load_or_create_key(&plain_path, None).unwrap(), key);
+
+        let wrapped_path = dir.path().join("wrapped.key");
+        write_key_file(
+            &wrapped_path,
+            &build_key_file(&key, Some("hunter2")).unwrap(),
+        )
+        .unwrap();
+        let content = std::fs::read_to_string(&wrapped_path).unwrap();
+        assert!(
+            !content.contains(&BASE64.encode(key)),
+            "口令模式不应保存明文密钥"
+        );
+
+        assert_eq!(
+            load_or_create_key(&wrapped_path, Some("hunter2")).unwrap(),
+            key
+        );
+        assert!(load_or_create_key(&wrapped_path, Some("wrong")).is_err());
+        assert!(load_or_create_key(&wrapped_path, None).is_err());
+    }
+}
@@ -0,0 +1,517 @@
+//! Debounced auto-upload shared by every sync transport.
+//!
+//! Database writes to configuration tables mark each transport dirty; a worker
+//! per transport merges bursts of changes and then runs that transport's
+//! `upload` under the global sync lock. Transports only describe how to load
+//! their settings, persist errors and upload, via [`SyncTransport`].
+//!
+//! Workers report status to the desktop UI, so they only run with the `gui`
+//! feature; headless builds still honour suppression but never upload.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "gui")]
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "gui")]
+use serde_json::json;
+use serde_json::Value;
+#[cfg(feature = "gui")]
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::Sender;
+#[cfg(feature = "gui")]
+use tokio::sync::mpsc::{channel, Receiver};
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::sync_protocol::{run_with_sync_lock, should_trigger_auto_sync_for_table};
+use crate::services::{folder_sync, git_sync, s3_sync, webdav_sync};
+use crate::settings::{
+    self, FolderSyncSettings, GitSyncSettings, S3SyncSettings, WebDavSyncSettings, WebDavSyncStatus,
+};
+
+const AUTO_SYNC_DEBOUNCE_MS: u64 = 1000;
+pub(crate) const MAX_AUTO_SYNC_WAIT_MS: u64 = 10_000;
+
+/// A remote the local snapshot can be auto-uploaded to.
+pub(crate) trait SyncTransport: Send + Sync + 'static {
+    type Settings: Send;
+
+    /// Prefix for log lines, e.g. `WebDAV`.
+    const LABEL: &'static str;
+    /// Event the frontend listens on for auto-sync status changes.
+    const STATUS_EVENT: &'static str;
+
+    /// Per-transport worker channel and suppression depth.
+    fn state() -> &'static AutoSyncState;
+
+    fn load_settings() -> Option<Self::Settings>;
+
+    /// Whether the transport is enabled and has auto-sync turned on.
+    fn auto_sync_enabled(settings: &Self::Settings) -> bool;
+
+    fn status_mut(settings: &mut Self::Settings) -> &mut WebDavSyncStatus;
+
+    fn save_status(status: WebDavSyncStatus) -> Result<(), AppError>;
+
+    fn upload<'a>(
+        db: &'a Database,
+        settings: &'a mut Self::Settings,
+    ) -> impl Future<Output = Result<Value, AppError>> + Send + 'a;
+}
+
+pub(crate) struct AutoSyncState {
+    change_tx: OnceLock<Sender<String>>,
+    suppress_depth: AtomicUsize,
+}
+
+impl AutoSyncState {
+    const fn new() -> Self {
+        Self {
+            change_tx: OnceLock::new(),
+            suppress_depth: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// Suppresses auto-upload for one transport while it is applying a download.
+pub(crate) struct AutoSyncSuppressionGuard {
+    state: &'static AutoSyncState,
+}
+
+impl AutoSyncSuppressionGuard {
+    pub fn new<T: SyncTransport>() -> Self {
+        let state = T::state();
+        state.suppress_depth.fetch_add(1, Ordering::SeqCst);
+        Self { state }
+    }
+}
+
+impl Drop for AutoSyncSuppressionGuard {
+    fn drop(&mut self) {
+        let _ =
+            self.state
+                .suppress_depth
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |value| {
+                    Some(value.saturating_sub(1))
+                });
+    }
+}
+
+pub(crate) fn is_auto_sync_suppressed<T: SyncTransport>() -> bool {
+    T::state().suppress_depth.load(Ordering::SeqCst) > 0
+}
+
+pub(crate) fn enqueue_change_signal(tx: &Sender<String>, table: &str) -> bool {
+    match tx.try_send(table.to_string()) {
+        Ok(()) => true,
+        Err(TrySendError::Full(_)) | Err(TrySendError::Closed(_)) => false,
+    }
+}
+
+pub(crate) fn auto_sync_wait_duration(started_at: Instant, now: Instant) -> Option<Duration> {
+    let max_wait = Duration::from_millis(MAX_AUTO_SYNC_WAIT_MS);
+    let debounce = Duration::from_millis(AUTO_SYNC_DEBOUNCE_MS);
+    let elapsed = now.saturating_duration_since(started_at);
+    if elapsed >= max_wait {
+        return None;
+    }
+    Some(debounce.min(max_wait - elapsed))
+}
+
+fn should_run_auto_sync<T: SyncTransport>(settings: Option<&T::Settings>) -> bool {
+    settings.is_some_and(T::auto_sync_enabled)
+}
+
+fn persist_auto_sync_error<T: SyncTransport>(settings: &mut T::Settings, error: &AppError) {
+    let status = T::status_mut(settings);
+    status.last_error = Some(error.to_string());
+    status.last_error_source = Some("auto".to_string());
+    let _ = T::save_status(status.clone());
+}
+
+#[cfg(feature = "gui")]
+fn emit_auto_sync_status_updated<T: SyncTransport>(
+    app: &AppHandle,
+    status: &str,
+    error: Option<&str>,
+) {
+    let payload = match error {
+        Some(message) => json!({
+            "source": "auto",
+            "status": status,
+            "error": message,
+        }),
+        None => json!({
+            "source": "auto",
+            "status": status,
+        }),
+    };
+
+    if let Err(err) = app.emit(T::STATUS_EVENT, payload) {
+        log::debug!(
+            "[{}] failed to emit sync status update event: {err}",
+            T::LABEL
+        );
+    }
+}
+
+#[cfg(feature = "gui")]
+async fn run_auto_sync_upload<T: SyncTransport>(
+    db: &Database,
+    app: &AppHandle,
+) -> Result<(), AppError> {
+    let Some(mut sync_settings) = T::load_settings() else {
+        return Ok(());
+    };
+    if !should_run_auto_sync::<T>(Some(&sync_settings)) {
+        return Ok(());
+    }
+
+    let result = run_with_sync_lock(T::upload(db, &mut sync_settings)).await;
+    match result {
+        Ok(_) => {
+            emit_auto_sync_status_updated::<T>(app, "success", None);
+            Ok(())
+        }
+        Err(err) => {
+            persist_auto_sync_error::<T>(&mut sync_settings, &err);
+            emit_auto_sync_status_updated::<T>(app, "error", Some(&err.to_string()));
+            Err(err)
+        }
+    }
+}
+
+fn notify_transport<T: SyncTransport>(table: &str) {
+    if is_auto_sync_suppressed::<T>() {
+        return;
+    }
+    let Some(tx) = T::state().change_tx.get() else {
+        return;
+    };
+    let _ = enqueue_change_signal(tx, table);
+}
+
+/// Mark every transport dirty after a write to `table`.
+pub fn notify_db_changed(table: &str) {
+    if !should_trigger_auto_sync_for_table(table) {
+        return;
+    }
+    notify_transport::<WebDavTransport>(table);
+    notify_transport::<S3Transport>(table);
+    notify_transport::<FolderTransport>(table);
+    notify_transport::<GitTransport>(table);
+}
+
+/// Start one auto-sync worker per transport; later calls are no-ops.
+#[cfg(feature = "gui")]
+pub fn start_workers(db: Arc<Database>, app: AppHandle) {
+    start_worker::<WebDavTransport>(db.clone(), app.clone());
+    start_worker::<S3Transport>(db.clone(), app.clone());
+    start_worker::<FolderTransport>(db.clone(), app.clone());
+    start_worker::<GitTransport>(db, app);
+}
+
+#[cfg(feature = "gui")]
+fn start_worker<T: SyncTransport>(db: Arc<Database>, app: AppHandle) {
+    let state = T::state();
+    if state.change_tx.get().is_some() {
+        return;
+    }
+
+    // Buffer size 1 is enough: we only need "dirty" signals, not every event.
+    let (tx, rx) = channel::<String>(1);
+    if state.change_tx.set(tx).is_err() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        run_worker_loop::<T>(db, rx, app).await;
+    });
+}
+
+#[cfg(feature = "gui")]
+async fn run_worker_loop<T: SyncTransport>(
+    db: Arc<Database>,
+    mut rx: Receiver<String>,
+    app: AppHandle,
+) {
+    while let Some(first_table) = rx.recv().await {
+        let started_at = Instant::now();
+        let mut merged_count = 1usize;
+
+        while let Some(wait_for) = auto_sync_wait_duration(started_at, Instant::now()) {
+            let timeout = tokio::time::timeout(wait_for, rx.recv()).await;
+
+            match timeout {
+                Ok(Some(_)) => merged_count += 1,
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+
+        log::debug!(
+            "[{}][AutoSync] Triggered by table={first_table}, merged_changes={merged_count}",
+            T::LABEL
+        );
+
+        if let Err(err) = run_auto_sync_upload::<T>(&db, &app).await {
+            log::warn!("[{}][AutoSync] Upload failed: {err}", T::LABEL);
+        }
+    }
+}
+
+// ─── Transports ──────────────────────────────────────────────
+
+pub(crate) struct WebDavTransport;
+
+impl SyncTransport for WebDavTransport {
+    type Settings = WebDavSyncSettings;
+
+    const LABEL: &'static str = "WebDAV";
+    const STATUS_EVENT: &'static str = "webdav-sync-status-updated";
+
+    fn state() -> &'static AutoSyncState {
+        static STATE: AutoSyncState = AutoSyncState::new();
+        &STATE
+    }
+
+    fn load_settings() -> Option<Self::Settings> {
+        settings::get_webdav_sync_settings()
+    }
+
+    fn auto_sync_enabled(settings: &Self::Settings) -> bool {
+        settings.enabled && settings.auto_sync
+    }
+
+    fn status_mut(settings: &mut Self::Settings) -> &mut WebDavSyncStatus {
+        &mut settings.status
+    }
+
+    fn save_status(status: WebDavSyncStatus) -> Result<(), AppError> {
+        settings::update_webdav_sync_status(status)
+    }
+
+    fn upload<'a>(
+        db: &'a Database,
+        settings: &'a mut Self::Settings,
+    ) -> impl Future<Output = Result<Value, AppError>> + Send + 'a {
+        webdav_sync::upload(db, settings)
+    }
+}
+
+pub(crate) struct S3Transport;
+
+impl SyncTransport for S3Transport {
+    type Settings = S3SyncSettings;
+
+    const LABEL: &'static str = "S3";
+    const STATUS_EVENT: &'static str = "s3-sync-status-updated";
+
+    fn state() -> &'static AutoSyncState {
+        static STATE: AutoSyncState = AutoSyncState::new();
+        &STATE
+    }
+
+    fn load_settings() -> Option<Self::Settings> {
+        settings::get_s3_sync_settings()
+    }
+
+    fn auto_sync_enabled(settings: &Self::Settings) -> bool {
+        settings.enabled && settings.auto_sync
+    }
+
+    fn status_mut(settings: &mut Self::Settings) -> &mut WebDavSyncStatus {
+        &mut settings.status
+    }
+
+    fn save_status(status: WebDavSyncStatus) -> Result<(), AppError> {
+        settings::update_s3_sync_status(status)
+    }
+
+    fn upload<'a>(
+        db: &'a Database,
+        settings: &'a mut Self::Settings,
+    ) -> impl Future<Output = Result<Value, AppError>> + Send + 'a {
+        s3_sync::upload(db, settings)
+    }
+}
+
+pub(crate) struct FolderTransport;
+
+impl SyncTransport for FolderTransport {
+    type Settings = FolderSyncSettings;
+
+    const LABEL: &'static str = "Folder";
+    const STATUS_EVENT: &'static str = "folder-sync-status-updated";
+
+    fn state() -> &'static AutoSyncState {
+        static STATE: AutoSyncState = AutoSyncState::new();
+        &STATE
+    }
+
+    fn load_settings() -> Option<Self::Settings> {
+        settings::get_folder_sync_settings()
+    }
+
+    fn auto_sync_enabled(settings: &Self::Settings) -> bool {
+        settings.enabled && settings.auto_sync
+    }
+
+    fn status_mut(settings: &mut Self::Settings) -> &mut WebDavSyncStatus {
+        &mut settings.status
+    }
+
+    fn save_status(status: WebDavSyncStatus) -> Result<(), AppError> {
+        settings::update_folder_sync_status(status)
+    }
+
+    fn upload<'a>(
+        db: &'a Database,
+        settings: &'a mut Self::Settings,
+    ) -> impl Future<Output = Result<Value, AppError>> + Send + 'a {
+        folder_sync::upload(db, settings)
+    }
+}
+
+pub(crate) struct GitTransport;
+
+impl SyncTransport for GitTransport {
+    type Settings = GitSyncSettings;
+
+    const LABEL: &'static str = "Git";
+    const STATUS_EVENT: &'static str = "git-sync-status-updated";
+
+    fn state() -> &'static AutoSyncState {
+        static STATE: AutoSyncState = AutoSyncState::new();
+        &STATE
+    }
+
+    fn load_settings() -> Option<Self::Settings> {
+        settings::get_git_sync_settings()
+    }
+
+    fn auto_sync_enabled(settings: &Self::Settings) -> bool {
+        settings.enabled && settings.auto_sync
+    }
+
+    fn status_mut(settings: &mut Self::Settings) -> &mut WebDavSyncStatus {
+        &mut settings.status
+    }
+
+    fn save_status(status: WebDavSyncStatus) -> Result<(), AppError> {
+        settings::update_git_sync_status(status)
+    }
+
+    fn upload<'a>(
+        db: &'a Database,
+        settings: &'a mut Self::Settings,
+    ) -> impl Future<Output = Result<Value, AppError>> + Send + 'a {
+        git_sync::upload(db, settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        auto_sync_wait_duration, enqueue_change_signal, is_auto_sync_suppressed,
+        should_run_auto_sync, AutoSyncSuppressionGuard, FolderTransport, GitTransport, S3Transport,
+        SyncTransport, WebDavTransport, MAX_AUTO_SYNC_WAIT_MS,
+    };
+    use crate::services::sync_protocol::should_trigger_auto_sync_for_table;
+    use crate::settings::{
+        FolderSyncSettings, GitSyncSettings, S3SyncSettings, WebDavSyncSettings,
+    };
+    use serial_test::serial;
+    use std::time::{Duration, Instant};
+    use tokio::sync::mpsc::channel;
+
+    #[test]
+    fn should_trigger_sync_for_config_tables_only() {
+        assert!(should_trigger_auto_sync_for_table("providers"));
+        assert!(should_trigger_auto_sync_for_table("profiles"));
+        assert!(should_trigger_auto_sync_for_table("settings"));
+        assert!(!should_trigger_auto_sync_for_table("proxy_request_logs"));
+        assert!(!should_trigger_auto_sync_for_table("provider_health"));
+    }
+
+    #[test]
+    #[serial]
+    fn suppression_guard_is_scoped_to_one_transport() {
+        assert!(!is_auto_sync_suppressed::<WebDavTransport>());
+        {
+            let _guard = AutoSyncSuppressionGuard::new::<WebDavTransport>();
+            assert!(is_auto_sync_suppressed::<WebDavTransport>());
+            assert!(!is_auto_sync_suppressed::<S3Transport>());
+            assert!(!is_auto_sync_suppressed::<FolderTransport>());
+            assert!(!is_auto_sync_suppressed::<GitTransport>());
+        }
+        assert!(!is_auto_sync_suppressed::<WebDavTransport>());
+    }
+
+    #[test]
+    fn max_wait_caps_flush_latency_for_continuous_events() {
+        let started = Instant::now();
+        let later = started + Duration::from_millis(MAX_AUTO_SYNC_WAIT_MS + 1);
+        assert!(auto_sync_wait_duration(started, later).is_none());
+    }
+
+    #[tokio::test]
+    async fn enqueue_change_signal_drops_when_channel_is_full() {
+        let (tx, _rx) = channel::<String>(1);
+        assert!(enqueue_change_signal(&tx, "providers"));
+        assert!(!enqueue_change_signal(&tx, "providers"));
+    }
+
+    fn assert_requires_enabled_and_auto_sync<T: SyncTransport>(
+        build: impl Fn(bool, bool) -> T::Settings,
+    ) {
+        assert!(!should_run_auto_sync::<T>(None));
+        assert!(!should_run_auto_sync::<T>(Some(&build(false, true))));
+        assert!(!should_run_auto_sync::<T>(Some(&build(true, false))));
+        assert!(should_run_auto_sync::<T>(Some(&build(true, true))));
+    }
+
+    #[test]
+    fn should_run_auto_sync_requires_enabled_and_auto_sync_flag() {
+        assert_requires_enabled_and_auto_sync::<WebDavTransport>(|enabled, auto_sync| {
+            WebDavSyncSettings {
+                enabled,
+                auto_sync,
+                ..WebDavSyncSettings::default()
+            }
+        });
+        assert_requires_enabled_and_auto_sync::<S3Transport>(|enabled, auto_sync| S3SyncSettings {
+            enabled,
+            auto_sync,
+            ..S3SyncSettings::default()
+        });
+        assert_requires_enabled_and_auto_sync::<FolderTransport>(|enabled, auto_sync| {
+            FolderSyncSettings {
+                enabled,
+                auto_sync,
+                ..FolderSyncSettings::default()
+            }
+        });
+        assert_requires_enabled_and_auto_sync::<GitTransport>(|enabled, auto_sync| {
+            GitSyncSettings {
+                enabled,
+                auto_sync,
+                ..GitSyncSettings::default()
+            }
+        });
+    }
+
+    #[test]
+    fn service_layer_does_not_depend_on_commands_layer() {
+        let source = include_str!("auto_sync.rs");
+        let needle = ["crate", "commands", ""].join("::");
+        assert!(
+            !source.contains(&needle),
+            "services layer should not depend on commands layer"
+        );
+    }
+}
@@ -6,6 +6,8 @@
 use super::subscription::{
     CredentialStatus, QuotaTier, SubscriptionQuota, TIER_FIVE_HOUR, TIER_MONTHLY, TIER_WEEKLY_LIMIT,
 };
+use crate::app_config::AppType;
+use crate::provider::{Provider, UsageScript};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // ── 供应商检测 ──────────────────────────────────────────────
@@ -1267,6 +1269,79 @@ async fn query_zhipu_team_at(
     Ok(zhipu_quota_from_body(&body))
 }
 
+/// 解析 Token Plan 额度查询用的 `(base_url, api_key)`。
+///
+/// ZenMux 的用量接口与推理地址不同，优先使用用量脚本里填写的地址与密钥；
+/// 其余供应商直接沿用供应商自身的凭据。
+pub(crate) fn resolve_coding_plan_credentials(
+    app_type: &AppType,
+    provider: Option<&Provider>,
+    usage_script: Option<&UsageScript>,
+) -> (String, String) {
+    let native = || {
+        provider
+            .map(|p| p.resolve_usage_credentials(app_type))
+            .unwrap_or_default()
+    };
+    let is_zenmux = usage_script
+        .and_then(|s| s.coding_plan_provider.as_deref())
+        .map(|provider| provider.eq_ignore_ascii_case("zenmux"))
+        .unwrap_or(false);
+
+    if !is_zenmux {
+        return native();
+    }
+
+    let script_base_url = usage_script
+        .and_then(|s| s.base_url.as_deref())
+        .unwrap_or("")
+        .trim_end_matches('/')
+        .to_string();
+    let script_api_key = usage_script
+        .and_then(|s| s.api_key.as_deref())
+        .unwrap_or("")
+        .to_string();
+
+    if !script_base_url.is_empty() && !script_api_key.is_empty() {
+        return (script_base_url, script_api_key);
+    }
+
+    let native = native();
+    if !native.0.is_empty() && !native.1.is_empty() {
+        native
+    } else {
+        (script_base_url, script_api_key)
+    }
+}
+
+/// 按供应商用量脚本（`token_plan` 模板）的配置查询其编程套餐额度
+///
+/// 用量查询命令与代理的额度感知故障转移共用此入口。
+pub(crate) async fn get_provider_coding_plan_quota(
+    app_type: &AppType,
+    provider: Option<&Provider>,
+) -> Result<SubscriptionQuota, String> {
+    let usage_script = provider
+        .and_then(|p| p.meta.as_ref())
+        .and_then(|m| m.usage_script.as_ref());
+    let (base_url, api_key) = resolve_coding_plan_credentials(app_type, provider, usage_script);
+
+    // 火山方舟用账号 AK/SK 签名查询用量（存于 usage_script，与推理 api_key 分离）；
+    // 其他供应商为 None，沿用 api_key。
+    // 智谱团队版：显式 provider 标识 + 组织/项目 ID（与个人版智谱 base_url 相同，
+    // 靠 coding_plan_provider == "zhipu_team" 路由）。
+    get_coding_plan_quota(
+        &base_url,
+        &api_key,
+        usage_script.and_then(|s| s.access_key_id.as_deref()),
+        usage_script.and_then(|s| s.secret_access_key.as_deref()),
+        usage_script.and_then(|s| s.coding_plan_provider.as_deref()),
+        usage_script.and_then(|s| s.team_organization_id.as_deref()),
+        usage_script.and_then(|s| s.team_project_id.as_deref()),
+    )
+    .await
+}
+
 /// 查询编程套餐额度。瞬时传输失败（网络/超时/读体中断）返回 `Err`（前端 reject →
 /// retry + 保留上次成功值）；确定性失败（凭据缺失/未知域名/鉴权/非 2xx/业务错误）
 /// 返回 `Ok(success:false)` 立即透出文案。判定按 reqwest 错误种类在折叠点完成。
@@ -0,0 +1,367 @@
+//! Local folder sync transport.
+//!
+//! Writes the same manifest-based snapshot as the WebDAV/S3 transports into a
+//! plain directory, typically one kept in sync by Syncthing or Dropbox.
+//! The directory helpers are shared with [`super::git_sync`], which commits
+//! the same layout into a Git working tree.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::config::atomic_write_private;
+use crate::error::AppError;
+use crate::settings::{update_folder_sync_status, FolderSyncSettings, WebDavSyncStatus};
+
+pub(crate) use super::sync_protocol::run_with_sync_lock;
+use super::sync_protocol::{
+    apply_snapshot, build_local_snapshot, io_context_localized, load_sync_base, localized,
+    open_artifact, persist_sync_success_best_effort, record_merge_outcome, sha256_hex,
+    store_sync_base, unlock_snapshot, validate_artifact_size_limit, validate_manifest_compat,
+    verify_artifact, ArtifactMeta, LocalSnapshot, RemoteLayout, SyncManifest, DB_COMPAT_VERSION,
+    MAX_MANIFEST_BYTES, MAX_SYNC_ARTIFACT_BYTES, REMOTE_DB_SQL, REMOTE_LAYOUT_VERSION,
+    REMOTE_MANIFEST, REMOTE_SKILLS_ZIP,
+};
+
+// ─── Public API ──────────────────────────────────────────────
+
+/// Check that the sync folder exists and the snapshot directory is writable.
+pub async fn check_connection(settings: &FolderSyncSettings) -> Result<(), AppError> {
+    settings.validate()?;
+    let dir = ensure_snapshot_dir(settings)?;
+    let probe = dir.join(".cc-switch-probe");
+    fs::write(&probe, b"ok").map_err(|e| AppError::io(&probe, e))?;
+    fs::remove_file(&probe).map_err(|e| AppError::io(&probe, e))
+}
+
+/// Write the local snapshot (db + skills) into the sync folder.
+pub async fn upload(
+    db: &crate::database::Database,
+    settings: &mut FolderSyncSettings,
+) -> Result<Value, AppError> {
+    settings.validate()?;
+    let dir = ensure_snapshot_dir(settings)?;
+
+    let snapshot = build_local_snapshot(db, settings.sync_passphrase())?;
+    write_snapshot_dir(&dir, &snapshot)?;
+
+    store_sync_base(&remote_identity(settings), &snapshot.plain_db_sql);
+    let _persisted = persist_sync_success_best_effort(
+        settings,
+        snapshot.manifest_hash,
+        None,
+        persist_sync_success,
+    );
+    Ok(serde_json::json!({ "status": "uploaded" }))
+}
+
+/// Read the snapshot from the sync folder and apply it to the local database + skills.
+pub async fn download(
+    db: &crate::database::Database,
+    settings: &mut FolderSyncSettings,
+) -> Result<Value, AppError> {
+    settings.validate()?;
+    let dir = snapshot_dir(settings);
+
+    let (manifest_bytes, manifest) = read_manifest_dir(&dir)?.ok_or_else(remote_empty_error)?;
+    let merge = apply_snapshot_dir(
+        db,
+        &dir,
+        &manifest,
+        settings.sync_passphrase(),
+        &remote_identity(settings),
+    )?;
+
+    let manifest_hash = sha256_hex(&manifest_bytes);
+    let _persisted =
+        persist_sync_success_best_effort(settings, manifest_hash, None, persist_sync_success);
+    Ok(serde_json::json!({ "status": "downloaded", "merge": merge }))
+}
+
+/// Read the manifest in the sync folder without touching the artifacts.
+pub async fn fetch_remote_info(settings: &FolderSyncSettings) -> Result<Option<Value>, AppError> {
+    settings.validate()?;
+    let dir = snapshot_dir(settings);
+    let Some((_, manifest)) = read_manifest_dir(&dir)? else {
+        return Ok(None);
+    };
+    Ok(Some(remote_info_payload(
+        &manifest,
+        dir.display().to_string(),
+    )))
+}
+
+// ─── Sync status persistence ─────────────────────────────────
+
+fn persist_sync_success(
+    settings: &mut FolderSyncSettings,
+    manifest_hash: String,
+    etag: Option<String>,
+) -> Result<(), AppError> {
+    let status = WebDavSyncStatus {
+        last_sync_at: Some(Utc::now().timestamp()),
+        last_error: None,
+        last_error_source: None,
+        last_local_manifest_hash: Some(manifest_hash.clone()),
+        last_remote_manifest_hash: Some(manifest_hash),
+        last_remote_etag: etag,
+    };
+    settings.status = status.clone();
+    update_folder_sync_status(status)
+}
+
+// ─── Directory layout (shared with git_sync) ─────────────────
+
+/// Relative snapshot directory inside a sync root.
+///
+/// Format: `{remote_root}/v{REMOTE_LAYOUT_VERSION}/db-v{DB_COMPAT_VERSION}/{profile}`
+pub(super) fn snapshot_rel_dir(remote_root: &str, profile: &str) -> PathBuf {
+    PathBuf::from(remote_root)
+        .join(format!("v{REMOTE_LAYOUT_VERSION}"))
+        .join(format!("db-v{DB_COMPAT_VERSION}"))
+        .join(profile)
+}
+
+/// Write artifacts first and the manifest last, each via an atomic rename, so
+/// a folder synced mid-write never exposes a manifest for missing artifacts.
+pub(super) fn write_snapshot_dir(dir: &Path, snapshot: &LocalSnapshot) -> Result<(), AppError> {
+    fs::create_dir_all(dir).map_err(|e| AppError::io(dir, e))?;
+    atomic_write_private(&dir.join(REMOTE_DB_SQL), &snapshot.db_sql)?;
+    atomic_write_private(&dir.join(REMOTE_SKILLS_ZIP), &snapshot.skills_zip)?;
+    atomic_write_private(&dir.join(REMOTE_MANIFEST), &snapshot.manifest_bytes)
+}
+
+/// Read and parse the manifest; `None` when the directory holds no snapshot yet.
+pub(super) fn read_manifest_dir(dir: &Path) -> Result<Option<(Vec<u8>, SyncManifest)>, AppError> {
+    let Some(bytes) = read_limited(&dir.join(REMOTE_MANIFEST), MAX_MANIFEST_BYTES as u64)? else {
+        return Ok(None);
+    };
+    let manifest: SyncManifest = serde_json::from_slice(&bytes).map_err(|e| AppError::Json {
+        path: REMOTE_MANIFEST.to_string(),
+        source: e,
+    })?;
+    Ok(Some((bytes, manifest)))
+}
+
+/// Verify, decrypt and apply the snapshot described by `manifest`, merging
+/// against the last shared snapshot when one exists. Returns the merge outcome.
+pub(super) fn apply_snapshot_dir(
+    db: &crate::database::Database,
+    dir: &Path,
+    manifest: &SyncManifest,
+    passphrase: Option<&str>,
+    identity: &str,
+) -> Result<Value, AppError> {
+    validate_manifest_compat(manifest, RemoteLayout::Current)?;
+    let key = unlock_snapshot(manifest, passphrase)?;
+
+    let db_sql = read_artifact_dir(dir, REMOTE_DB_SQL, &manifest.artifacts)?;
+    let skills_zip = read_artifact_dir(dir, REMOTE_SKILLS_ZIP, &manifest.artifacts)?;
+    let db_sql = open_artifact(key.as_ref(), REMOTE_DB_SQL, db_sql)?;
+    let skills_zip = open_artifact(key.as_ref(), REMOTE_SKILLS_ZIP, skills_zip)?;
+
+    let base_sql = load_sync_base(identity);
+    let report = apply_snapshot(db, &db_sql, &skills_zip, base_sql.as_deref())?;
+    store_sync_base(identity, &db_sql);
+    Ok(record_merge_outcome(report.as_ref()))
+}
+
+pub(super) fn remote_info_payload(manifest: &SyncManifest, remote_path: String) -> Value {
+    let compatible = validate_manifest_compat(manifest, RemoteLayout::Current).is_ok();
+    serde_json::json!({
+        "deviceName": manifest.device_name,
+        "createdAt": manifest.created_at,
+        "snapshotId": manifest.snapshot_id,
+        "version": manifest.version,
+        "protocolVersion": manifest.version,
+        "dbCompatVersion": manifest.db_compat_version,
+        "compatible": compatible,
+        "encrypted": manifest.encryption.is_some(),
+        "artifacts": manifest.artifacts.keys().collect::<Vec<_>>(),
+        "layout": RemoteLayout::Current.as_str(),
+        "remotePath": remote_path,
+    })
+}
+
+pub(super) fn remote_empty_error() -> AppError {
+    localized(
+        "sync.remote_empty",
+        "远端没有可下载的同步数据",
+        "No downloadable sync data found on the remote.",
+    )
+}
+
+fn read_artifact_dir(
+    dir: &Path,
+    artifact_name: &str,
+    artifacts: &BTreeMap<String, ArtifactMeta>,
+) -> Result<Vec<u8>, AppError> {
+    let meta = artifacts.get(artifact_name).ok_or_else(|| {
+        localized(
+            "sync.manifest_missing_artifact",
+            format!("manifest 中缺少 artifact: {artifact_name}"),
+            format!("Manifest missing artifact: {artifact_name}"),
+        )
+    })?;
+    validate_artifact_size_limit(artifact_name, meta.size)?;
+
+    let bytes =
+        read_limited(&dir.join(artifact_name), MAX_SYNC_ARTIFACT_BYTES)?.ok_or_else(|| {
+            localized(
+                "sync.remote_missing_artifact",
+                format!("远端缺少 artifact 文件: {artifact_name}"),
+                format!("Remote artifact file missing: {artifact_name}"),
+            )
+        })?;
+    verify_artifact(&bytes, artifact_name, meta)?;
+    Ok(bytes)
+}
+
+/// Read at most `limit` bytes; a missing file yields `None`.
+fn read_limited(path: &Path, limit: u64) -> Result<Option<Vec<u8>>, AppError> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::io(path, e)),
+    };
+    let mut bytes = Vec::new();
+    file.take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| AppError::io(path, e))?;
+    if bytes.len() as u64 > limit {
+        return Err(localized(
+            "sync.remote_file_too_large",
+            format!("同步文件超过大小限制: {}", path.display()),
+            format!("Sync file exceeds the size limit: {}", path.display()),
+        ));
+    }
+    Ok(Some(bytes))
+}
+
+// ─── Folder helpers ──────────────────────────────────────────
+
+fn snapshot_dir(settings: &FolderSyncSettings) -> PathBuf {
+    Path::new(&settings.path).join(snapshot_rel_dir(&settings.remote_root, &settings.profile))
+}
+
+/// The configured folder must already exist (a missing mount or a typo should
+/// not silently create a fresh tree); the snapshot directory below it may not.
+fn ensure_snapshot_dir(settings: &FolderSyncSettings) -> Result<PathBuf, AppError> {
+    let root = Path::new(&settings.path);
+    if !root.is_dir() {
+        return Err(localized(
+            "folder_sync.path.missing",
+            format!("同步目录不存在: {}", root.display()),
+            format!("Sync folder does not exist: {}", root.display()),
+        ));
+    }
+    let dir = snapshot_dir(settings);
+    fs::create_dir_all(&dir).map_err(|e| {
+        io_context_localized(
+            "folder_sync.create_dir_failed",
+            format!("创建同步目录失败: {}", dir.display()),
+            format!("Failed to create sync directory: {}", dir.display()),
+            e,
+        )
+    })?;
+    Ok(dir)
+}
+
+/// Identity of the snapshot location, used to key the merge base.
+fn remote_identity(settings: &FolderSyncSettings) -> String {
+    format!("folder:{}", snapshot_dir(settings).display())
+}
+
+// ─── Tests ───────────────────────────────────────────────────
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::services::sync_protocol::{PROTOCOL_FORMAT, PROTOCOL_VERSION};
+
+    pub(crate) fn test_snapshot() -> LocalSnapshot {
+        let db_sql = b"CREATE TABLE t(x);".to_vec();
+        let skills_zip = b"PK\x05\x06".to_vec();
+        let artifacts = BTreeMap::from([
+            (
+                REMOTE_DB_SQL.to_string(),
+                ArtifactMeta {
+                    sha256: sha256_hex(&db_sql),
+                    size: db_sql.len() as u64,
+                },
+            ),
+            (
+                REMOTE_SKILLS_ZIP.to_string(),
+                ArtifactMeta {
+                    sha256: sha256_hex(&skills_zip),
+                    size: skills_zip.len() as u64,
+                },
+            ),
+        ]);
+        let manifest = SyncManifest {
+            format: PROTOCOL_FORMAT.to_string(),
+            version: PROTOCOL_VERSION,
+            db_compat_version: Some(DB_COMPAT_VERSION),
+            device_name: "test".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            artifacts,
+            snapshot_id: "snap".to_string(),
+            encryption: None,
+        };
+        let manifest_bytes = serde_json::to_vec(&manifest).unwrap();
+        LocalSnapshot {
+            plain_db_sql: db_sql.clone(),
+            db_sql,
+            skills_zip,
+            manifest_hash: sha256_hex(&manifest_bytes),
+            manifest_bytes,
+        }
+    }
+
+    #[test]
+    fn snapshot_dir_follows_remote_layout() {
+        let settings = FolderSyncSettings {
+            path: "/sync".to_string(),
+            profile: "work".to_string(),
+            ..FolderSyncSettings::default()
+        };
+        assert_eq!(
+            snapshot_dir(&settings),
+            PathBuf::from("/sync/cc-switch-sync/v2/db-v6/work")
+        );
+    }
+
+    #[test]
+    fn written_snapshot_reads_back_and_verifies() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("snap");
+        let snapshot = test_snapshot();
+
+        assert!(read_manifest_dir(&dir).unwrap().is_none());
+        write_snapshot_dir(&dir, &snapshot).unwrap();
+
+        let (bytes, manifest) = read_manifest_dir(&dir).unwrap().unwrap();
+        assert_eq!(bytes, snapshot.manifest_bytes);
+        let db_sql = read_artifact_dir(&dir, REMOTE_DB_SQL, &manifest.artifacts).unwrap();
+        assert_eq!(db_sql, snapshot.db_sql);
+
+        // A tampered artifact is rejected by the manifest hash
+        fs::write(dir.join(REMOTE_SKILLS_ZIP), b"tampered").unwrap();
+        assert!(read_artifact_dir(&dir, REMOTE_SKILLS_ZIP, &manifest.artifacts).is_err());
+    }
+
+    #[test]
+    fn missing_sync_folder_is_not_created() {
+        let temp = tempfile::tempdir().unwrap();
+        let settings = FolderSyncSettings {
+            path: temp.path().join("unmounted").display().to_string(),
+            ..FolderSyncSettings::default()
+        };
+        assert!(ensure_snapshot_dir(&settings).is_err());
+        assert!(!temp.path().join("unmounted").exists());
+    }
+}
@@ -0,0 +1,389 @@
+//! Git sync transport.
+//!
+//! Commits the folder-transport layout (see [`super::folder_sync`]) into a Git
+//! repository, so every upload becomes a commit and the history doubles as a
+//! snapshot log. Only local repositories (an absolute path to a bare repo or a
+//! `file://` URL) are supported; each operation works in a throwaway shallow
+//! checkout driven by the `git` CLI.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::Utc;
+use serde_json::Value;
+use tempfile::TempDir;
+
+use crate::error::AppError;
+use crate::settings::{update_git_sync_status, GitSyncSettings, WebDavSyncStatus};
+
+use super::folder_sync::{
+    apply_snapshot_dir, read_manifest_dir, remote_empty_error, remote_info_payload,
+    snapshot_rel_dir, write_snapshot_dir,
+};
+pub(crate) use super::sync_protocol::run_with_sync_lock;
+use super::sync_protocol::{
+    build_local_snapshot, io_context_localized, localized, persist_sync_success_best_effort,
+    sha256_hex, store_sync_base, LocalSnapshot,
+};
+
+const COMMIT_AUTHOR_NAME: &str = "cc-switch";
+const COMMIT_AUTHOR_EMAIL: &str = "cc-switch@localhost";
+
+// ─── Public API ──────────────────────────────────────────────
+
+/// Check that the repository is reachable with `git ls-remote`.
+pub async fn check_connection(settings: &GitSyncSettings) -> Result<(), AppError> {
+    settings.validate()?;
+    let repo_url = settings.repo_url.clone();
+    run_blocking(move || {
+        let cwd = std::env::temp_dir();
+        git(&cwd, &["ls-remote", "--heads", "--", &repo_url]).map(|_| ())
+    })
+    .await
+}
+
+/// Commit the local snapshot (db + skills) and push it to the configured branch.
+pub async fn upload(
+    db: &crate::database::Database,
+    settings: &mut GitSyncSettings,
+) -> Result<Value, AppError> {
+    settings.validate()?;
+    let snapshot = build_local_snapshot(db, settings.sync_passphrase())?;
+
+    let (snapshot, commit) = {
+        let settings = settings.clone();
+        run_blocking(move || {
+            let commit = commit_snapshot(&settings, &snapshot)?;
+            Ok((snapshot, commit))
+        })
+        .await?
+    };
+
+    store_sync_base(&remote_identity(settings), &snapshot.plain_db_sql);
+    let _persisted = persist_sync_success_best_effort(
+        settings,
+        snapshot.manifest_hash,
+        Some(commit.clone()),
+        persist_sync_success,
+    );
+    Ok(serde_json::json!({ "status": "uploaded", "commit": commit }))
+}
+
+/// Fetch the branch head and apply its snapshot to the local database + skills.
+pub async fn download(
+    db: &crate::database::Database,
+    settings: &mut GitSyncSettings,
+) -> Result<Value, AppError> {
+    settings.validate()?;
+    let checkout = {
+        let settings = settings.clone();
+        run_blocking(move || Checkout::fetch(&settings)).await?
+    };
+    let commit = checkout.head.clone().ok_or_else(remote_empty_error)?;
+
+    let dir = checkout.snapshot_dir(settings);
+    let (manifest_bytes, manifest) = read_manifest_dir(&dir)?.ok_or_else(remote_empty_error)?;
+    let merge = apply_snapshot_dir(
+        db,
+        &dir,
+        &manifest,
+        settings.sync_passphrase(),
+        &remote_identity(settings),
+    )?;
+
+    let manifest_hash = sha256_hex(&manifest_bytes);
+    let _persisted = persist_sync_success_best_effort(
+        settings,
+        manifest_hash,
+        Some(commit),
+        persist_sync_success,
+    );
+    Ok(serde_json::json!({ "status": "downloaded", "merge": merge }))
+}
+
+/// Read the manifest at the branch head without applying the artifacts.
+pub async fn fetch_remote_info(settings: &GitSyncSettings) -> Result<Option<Value>, AppError> {
+    settings.validate()?;
+    let checkout = {
+        let settings = settings.clone();
+        run_blocking(move || Checkout::fetch(&settings)).await?
+    };
+    let Some(commit) = checkout.head.clone() else {
+        return Ok(None);
+    };
+    let Some((_, manifest)) = read_manifest_dir(&checkout.snapshot_dir(settings))? else {
+        return Ok(None);
+    };
+
+    let mut payload = remote_info_payload(&manifest, remote_dir_display(settings));
+    payload["commit"] = Value::String(commit);
+    Ok(Some(payload))
+}
+
+// ─── Sync status persistence ─────────────────────────────────
+
+fn persist_sync_success(
+    settings: &mut GitSyncSettings,
+    manifest_hash: String,
+    etag: Option<String>,
+) -> Result<(), AppError> {
+    let status = WebDavSyncStatus {
+        last_sync_at: Some(Utc::now().timestamp()),
+        last_error: None,
+        last_error_source: None,
+        last_local_manifest_hash: Some(manifest_hash.clone()),
+        last_remote_manifest_hash: Some(manifest_hash),
+        last_remote_etag: etag,
+    };
+    settings.status = status.clone();
+    update_git_sync_status(status)
+}
+
+// ─── Checkout & commit ───────────────────────────────────────
+
+/// Temporary working tree holding the branch head (or an unborn branch).
+struct Checkout {
+    dir: TempDir,
+    /// Commit id of the fetched branch head; `None` if the branch does not exist yet.
+    head: Option<String>,
+}
+
+impl Checkout {
+    fn fetch(settings: &GitSyncSettings) -> Result<Self, AppError> {
+        let dir = tempfile::tempdir().map_err(|e| {
+            io_context_localized(
+                "git_sync.tempdir_failed",
+                "创建 Git 临时工作区失败",
+                "Failed to create a temporary Git work tree",
+                e,
+            )
+        })?;
+        let work = dir.path();
+        let branch_ref = format!("refs/heads/{}", settings.branch);
+
+        git(work, &["init", "--quiet"])?;
+        let heads = git(
+            work,
+            &[
+                "ls-remote",
+                "--heads",
+                "--",
+                &settings.repo_url,
+                &branch_ref,
+            ],
+        )?;
+        if heads.trim().is_empty() {
+            git(work, &["symbolic-ref", "HEAD", &branch_ref])?;
+            return Ok(Self { dir, head: None });
+        }
+
+        git(
+            work,
+            &[
+                "fetch",
+                "--quiet",
+                "--depth",
+                "1",
+                "--",
+                &settings.repo_url,
+                &branch_ref,
+            ],
+        )?;
+        git(
+            work,
+            &["checkout", "--quiet", "-B", &settings.branch, "FETCH_HEAD"],
+        )?;
+        let head = rev_parse_head(work)?;
+        Ok(Self {
+            dir,
+            head: Some(head),
+        })
+    }
+
+    fn snapshot_dir(&self, settings: &GitSyncSettings) -> PathBuf {
+        self.dir
+            .path()
+            .join(snapshot_rel_dir(&settings.remote_root, &settings.profile))
+    }
+}
+
+/// Commit the snapshot on top of the branch head and push it; returns the new commit id.
+///
+/// A concurrent push from another device makes the push non-fast-forward and
+/// fails the upload instead of overwriting that device's snapshot.
+fn commit_snapshot(
+    settings: &GitSyncSettings,
+    snapshot: &LocalSnapshot,
+) -> Result<String, AppError> {
+    let checkout = Checkout::fetch(settings)?;
+    let work = checkout.dir.path();
+    write_snapshot_dir(&checkout.snapshot_dir(settings), snapshot)?;
+
+    let message = format!(
+        "cc-switch sync: {} ({})",
+        settings.profile,
+        &snapshot.manifest_hash[..snapshot.manifest_hash.len().min(12)]
+    );
+    git(work, &["add", "--all"])?;
+    if let Some(head) = &checkout.head {
+        if git(work, &["status", "--porcelain"])?.trim().is_empty() {
+            return Ok(head.clone());
+        }
+    }
+    git(
+        work,
+        &[
+            "-c",
+            &format!("user.name={COMMIT_AUTHOR_NAME}"),
+            "-c",
+            &format!("user.email={COMMIT_AUTHOR_EMAIL}"),
+            "-c",
+            "commit.gpgsign=false",
+            "commit",
+            "--quiet",
+            "--no-verify",
+            "-m",
+            &message,
+        ],
+    )?;
+    let refspec = format!("HEAD:refs/heads/{}", settings.branch);
+    git(
+        work,
+        &[
+            "push",
+            "--quiet",
+            "--no-verify",
+            "--",
+            &settings.repo_url,
+            &refspec,
+        ],
+    )?;
+    rev_parse_head(work)
+}
+
+fn rev_parse_head(work: &Path) -> Result<String, AppError> {
+    Ok(git(work, &["rev-parse", "HEAD"])?.trim().to_string())
+}
+
+/// Run a git subcommand; non-zero exit surfaces git's stderr.
+fn git(cwd: &Path, args: &[&str]) -> Result<String, AppError> {
+    let output = Command::new("git")
+        .current_dir(cwd)
+        .args(args)
+        // Never block on credential prompts; only local transports are allowed
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_ALLOW_PROTOCOL", "file")
+        .output()
+        .map_err(|e| {
+            io_context_localized(
+                "git_sync.git_unavailable",
+                "无法执行 git 命令，请确认已安装 Git",
+                "Failed to run git; make sure Git is installed",
+                e,
+            )
+        })?;
+
+    if !output.status.success() {
+        let subcommand = args.iter().find(|arg| !arg.starts_with('-')).unwrap_or(&"");
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(localized(
+            "git_sync.command_failed",
+            format!("git {subcommand} 执行失败: {stderr}"),
+            format!("git {subcommand} failed: {stderr}"),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn run_blocking<T, F>(task: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| AppError::Message(format!("Git 同步任务执行失败: {e}")))?
+}
+
+// ─── Path helpers ────────────────────────────────────────────
+
+fn remote_dir_display(settings: &GitSyncSettings) -> String {
+    format!(
+        "{}#{}:{}",
+        settings.repo_url,
+        settings.branch,
+        snapshot_rel_dir(&settings.remote_root, &settings.profile).display()
+    )
+}
+
+/// Identity of the remote snapshot location, used to key the merge base.
+fn remote_identity(settings: &GitSyncSettings) -> String {
+    format!("git:{}", remote_dir_display(settings))
+}
+
+// ─── Tests ───────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::folder_sync::tests::test_snapshot;
+
+    fn git_available() -> bool {
+        Command::new("git").arg("--version").output().is_ok()
+    }
+
+    fn bare_repo(root: &Path) -> GitSyncSettings {
+        let repo = root.join("remote.git");
+        git(
+            root,
+            &["init", "--quiet", "--bare", &repo.display().to_string()],
+        )
+        .unwrap();
+        GitSyncSettings {
+            repo_url: repo.display().to_string(),
+            ..GitSyncSettings::default()
+        }
+    }
+
+    #[test]
+    fn commit_snapshot_pushes_to_empty_bare_repo_and_reads_back() {
+        if !git_available() {
+            return;
+        }
+        let temp = tempfile::tempdir().unwrap();
+        let settings = bare_repo(temp.path());
+        let snapshot = test_snapshot();
+
+        assert!(Checkout::fetch(&settings).unwrap().head.is_none());
+
+        let first = commit_snapshot(&settings, &snapshot).unwrap();
+        // An unchanged snapshot does not create an empty commit
+        let second = commit_snapshot(&settings, &snapshot).unwrap();
+        assert_eq!(first, second);
+
+        let checkout = Checkout::fetch(&settings).unwrap();
+        assert_eq!(checkout.head.as_deref(), Some(second.as_str()));
+        let (bytes, _) = read_manifest_dir(&checkout.snapshot_dir(&settings))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, snapshot.manifest_bytes);
+    }
+
+    #[test]
+    fn validate_rejects_non_local_repositories() {
+        let mut settings = GitSyncSettings {
+            repo_url: "https://example.com/repo.git".to_string(),
+            ..GitSyncSettings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        settings.repo_url = "ext::sh -c touch% /tmp/pwned".to_string();
+        assert!(settings.validate().is_err());
+
+        settings.repo_url = "file:///srv/sync.git".to_string();
+        assert!(settings.validate().is_ok());
+
+        settings.branch = "--upload-pack=evil".to_string();
+        assert!(settings.validate().is_err());
+    }
+}
@@ -1,14 +1,28 @@
+use futures::StreamExt;
 use indexmap::IndexMap;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
 
-use crate::app_config::{AppType, McpServer};
+use serde_json::{json, Value};
+
+use crate::app_config::{AppType, McpProbeResult, McpSecretInfo, McpServer};
 use crate::error::AppError;
 use crate::mcp;
 use crate::store::AppState;
 
+/// 网关模式下写入各应用配置的唯一条目 id
+pub const MCP_GATEWAY_SERVER_ID: &str = "cc-switch-gateway";
+
 /// MCP 相关业务逻辑（v3.7.0 统一结构）
 pub struct McpService;
 
+/// 从应用 live 配置导入的服务器，去掉 CC Switch 自己写入的网关条目
+fn user_servers(servers: &HashMap<String, McpServer>) -> impl Iterator<Item = &McpServer> {
+    servers
+        .values()
+        .filter(|server| server.id != MCP_GATEWAY_SERVER_ID)
+}
+
 impl McpService {
     /// 获取所有 MCP 服务器（统一结构）
     pub fn get_all_servers(state: &AppState) -> Result<IndexMap<String, McpServer>, AppError> {
@@ -18,14 +32,14 @@ impl McpService {
     /// 添加或更新 MCP 服务器
     pub fn upsert_server(state: &AppState, server: McpServer) -> Result<(), AppError> {
         // 读取旧状态：用于处理“编辑时取消勾选某个应用”的场景（需要从对应 live 配置中移除）
-        let prev_apps = state
-            .db
-            .get_all_mcp_servers()?
-            .get(&server.id)
-            .map(|s| s.apps.clone())
-            .unwrap_or_default();
+        let prev = state.db.get_all_mcp_servers()?.shift_remove(&server.id);
+        let prev_apps = prev.as_ref().map(|s| s.apps.clone()).unwrap_or_default();
 
         state.db.save_mcp_server(&server)?;
+        // 连接定义变了，旧的探测结论不再代表当前配置
+        if prev.is_some_and(|prev| prev.server != server.server) {
+            state.db.delete_mcp_probe_result(&server.id)?;
+        }
 
         // 处理禁用：若旧版本启用但新版本取消，则需要从该应用的 live 配置移除
         if prev_apps.claude && !server.apps.claude {
@@ -68,6 +82,62 @@ impl McpService {
         }
     }
 
+    /// 获取所有服务器最近一次的探测结果
+    pub fn get_probe_results(
+        state: &AppState,
+    ) -> Result<HashMap<String, McpProbeResult>, AppError> {
+        state.db.get_mcp_probe_results()
+    }
+
+    /// 探测单个服务器并保存结果
+    pub async fn probe_server(
+        state: &AppState,
+        id: &str,
+        timeout: Option<Duration>,
+    ) -> Result<McpProbeResult, AppError> {
+        let server = state
+            .db
+            .get_all_mcp_servers()?
+            .shift_remove(id)
+            .ok_or_else(|| AppError::InvalidInput(format!("MCP 服务器 {id} 不存在")))?;
+        Self::probe_and_save(state, &server, timeout).await
+    }
+
+    /// 探测所有服务器；同时启动的子进程数受限，避免一次拉起几十个 npx
+    pub async fn probe_all_servers(
+        state: &AppState,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<McpProbeResult>, AppError> {
+        const CONCURRENCY: usize = 4;
+        let probes = state
+            .db
+            .get_all_mcp_servers()?
+            .into_values()
+            .map(|server| async move { Self::probe_and_save(state, &server, timeout).await });
+        let results: Vec<_> = futures::stream::iter(probes)
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+        results.into_iter().collect()
+    }
+
+    async fn probe_and_save(
+        state: &AppState,
+        server: &McpServer,
+        timeout: Option<Duration>,
+    ) -> Result<McpProbeResult, AppError> {
+        let timeout = timeout.unwrap_or(mcp::DEFAULT_PROBE_TIMEOUT);
+        let result = match Self::resolve_spec(state, &server.server) {
+            Ok(spec) => mcp::probe_server(&server.id, &spec, timeout).await,
+            Err(e) => mcp::probe_failure(&server.id, e.to_string()),
+        };
+        if let Some(error) = &result.error {
+            log::info!("[MCP] 探测 {} 未通过: {error}", server.id);
+        }
+        state.db.save_mcp_probe_result(&result)?;
+        Ok(result)
+    }
+
     /// 切换指定应用的启用状态
     pub fn toggle_app(
         state: &AppState,
@@ -91,51 +161,79 @@ impl McpService {
     }
 
     /// 将 MCP 服务器同步到所有启用的应用
-    fn sync_server_to_apps(_state: &AppState, server: &McpServer) -> Result<(), AppError> {
+    fn sync_server_to_apps(state: &AppState, server: &McpServer) -> Result<(), AppError> {
         for app in server.apps.enabled_apps() {
-            Self::sync_server_to_app_no_config(server, &app)?;
+            Self::sync_server_to_app(state, server, &app)?;
         }
 
         Ok(())
     }
 
     /// 将 MCP 服务器同步到指定应用
+    ///
+    /// 网关模式下应用只保留指向代理的网关条目，服务器本身由代理托管
     fn sync_server_to_app(
-        _state: &AppState,
+        state: &AppState,
         server: &McpServer,
         app: &AppType,
     ) -> Result<(), AppError> {
-        Self::sync_server_to_app_no_config(server, app)
+        if crate::settings::get_mcp_gateway_enabled() {
+            Self::remove_entry_from_app(&server.id, app)?;
+            return Self::write_gateway_to_app(state, app);
+        }
+        // 从其他设备同步来的服务器可能引用本机没有的密钥：不写入 live 配置，
+        // 并移除旧条目，避免留下占位符或过期的值；补齐密钥后会自动重新同步
+        let missing = Self::missing_secrets_for(state, &server.server)?;
+        if !missing.is_empty() {
+            log::warn!(
+                "[MCP] {} 缺少本地密钥 {}，跳过写入 {app:?}",
+                server.id,
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            );
+            return Self::remove_entry_from_app(&server.id, app);
+        }
+        let spec = Self::resolve_spec(state, &server.server)?;
+        Self::write_entry_to_app(&server.id, &spec, app)
+    }
+
+    fn missing_secrets_for(state: &AppState, spec: &Value) -> Result<BTreeSet<String>, AppError> {
+        if mcp::referenced_secrets(spec).is_empty() {
+            return Ok(BTreeSet::new());
+        }
+        Ok(mcp::missing_secrets(
+            spec,
+            &state.db.get_mcp_secret_values()?,
+        ))
+    }
+
+    /// 把 `${secret:NAME}` / `${env:NAME}` 替换为真实值；只在写入 live 配置或连接服务器前调用
+    pub(crate) fn resolve_spec(state: &AppState, spec: &Value) -> Result<Value, AppError> {
+        if mcp::referenced_secrets(spec).is_empty() {
+            return mcp::resolve_placeholders(spec, &HashMap::new());
+        }
+        mcp::resolve_placeholders(spec, &state.db.get_mcp_secret_values()?)
     }
 
-    fn sync_server_to_app_no_config(server: &McpServer, app: &AppType) -> Result<(), AppError> {
+    fn write_entry_to_app(id: &str, spec: &Value, app: &AppType) -> Result<(), AppError> {
         match app {
             AppType::Claude => {
-                mcp::sync_single_server_to_claude(&Default::default(), &server.id, &server.server)?;
+                mcp::sync_single_server_to_claude(&Default::default(), id, spec)?;
             }
             AppType::ClaudeDesktop => {
                 log::debug!("Claude Desktop 3P profiles do not use CC Switch MCP sync, skipping");
             }
             AppType::Codex => {
                 // Codex uses TOML format, must use the correct function
-                mcp::sync_single_server_to_codex(&Default::default(), &server.id, &server.server)?;
+                mcp::sync_single_server_to_codex(&Default::default(), id, spec)?;
             }
             AppType::Gemini => {
-                mcp::sync_single_server_to_gemini(&Default::default(), &server.id, &server.server)?;
+                mcp::sync_single_server_to_gemini(&Default::default(), id, spec)?;
             }
             AppType::GrokBuild => {
-                mcp::sync_single_server_to_grokbuild(
-                    &Default::default(),
-                    &server.id,
-                    &server.server,
-                )?;
+                mcp::sync_single_server_to_grokbuild(&Default::default(), id, spec)?;
             }
             AppType::OpenCode => {
-                mcp::sync_single_server_to_opencode(
-                    &Default::default(),
-                    &server.id,
-                    &server.server,
-                )?;
+                mcp::sync_single_server_to_opencode(&Default::default(), id, spec)?;
             }
             AppType::OpenClaw => {
                 // OpenClaw MCP support is still in development (Issue #4834)
@@ -143,7 +241,7 @@ impl McpService {
                 log::debug!("OpenClaw MCP support is still in development, skipping sync");
             }
             AppType::Hermes => {
-                mcp::sync_single_server_to_hermes(&Default::default(), &server.id, &server.server)?;
+                mcp::sync_single_server_to_hermes(&Default::default(), id, spec)?;
             }
             AppType::Pi => {}
         }
@@ -163,7 +261,23 @@ impl McpService {
         Ok(())
     }
 
-    fn remove_server_from_app(_state: &AppState, id: &str, app: &AppType) -> Result<(), AppError> {
+    /// 从应用中移除服务器；网关模式下该应用若已无启用的服务器，网关条目也一并移除
+    fn remove_server_from_app(state: &AppState, id: &str, app: &AppType) -> Result<(), AppError> {
+        Self::remove_entry_from_app(id, app)?;
+        if crate::settings::get_mcp_gateway_enabled() {
+            let still_used = state
+                .db
+                .get_all_mcp_servers()?
+                .values()
+                .any(|server| server.apps.is_enabled_for(app));
+            if !still_used {
+                Self::remove_entry_from_app(MCP_GATEWAY_SERVER_ID, app)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_entry_from_app(id: &str, app: &AppType) -> Result<(), AppError> {
         match app {
             AppType::Claude => mcp::remove_server_from_claude(id)?,
             AppType::ClaudeDesktop => {
@@ -187,6 +301,140 @@ impl McpService {
         Ok(())
     }
 
+    // ========================================================================
+    // MCP 网关模式
+    // ========================================================================
+
+    /// 网关条目：指向本地代理 `/mcp/{app}` 的 http 服务器，携带本机的网关访问令牌
+    fn gateway_spec(state: &AppState, app: &AppType) -> Result<Value, AppError> {
+        let config = futures::executor::block_on(state.db.get_global_proxy_config())?;
+        if config.listen_port == 0 {
+            return Err(AppError::Message(
+                "MCP 网关需要固定的代理监听端口，请先在代理设置中指定端口".to_string(),
+            ));
+        }
+        if !crate::proxy::mcp_gateway::is_loopback_host(&config.listen_address) {
+            return Err(AppError::Message(format!(
+                "MCP 网关只能在代理监听回环地址时使用（当前为 {}），请先把监听地址改为 127.0.0.1",
+                config.listen_address
+            )));
+        }
+        let origin = crate::services::proxy::proxy_connect_origin(
+            &config.listen_address,
+            config.listen_port,
+        );
+        let token = crate::proxy::mcp_gateway::gateway_token()?;
+        Ok(json!({
+            "type": "http",
+            "url": format!("{origin}/mcp/{}", app.as_str()),
+            "headers": { "Authorization": format!("Bearer {token}") },
+        }))
+    }
+
+    fn write_gateway_to_app(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        let spec = Self::gateway_spec(state, app)?;
+        Self::write_entry_to_app(MCP_GATEWAY_SERVER_ID, &spec, app)
+    }
+
+    /// 网关由本地代理提供：代理未运行时自动启动（不接管任何应用）
+    pub async fn ensure_gateway_proxy(state: &AppState) -> Result<(), AppError> {
+        if state.proxy_service.is_running().await {
+            return Ok(());
+        }
+        state
+            .proxy_service
+            .start()
+            .await
+            .map(|_| ())
+            .map_err(|e| AppError::Message(format!("MCP 网关依赖本地代理，启动代理失败: {e}")))
+    }
+
+    /// 切换网关模式并重写各应用的 MCP 配置；开启时确保本地代理在运行，
+    /// 否则各应用的网关条目指向一个无人应答的地址
+    pub async fn set_gateway_enabled(state: &AppState, enabled: bool) -> Result<(), AppError> {
+        if enabled {
+            // 先校验端口与监听地址，避免开关已落盘但各应用写不出网关地址
+            Self::gateway_spec(state, &AppType::Claude)?;
+            Self::ensure_gateway_proxy(state).await?;
+        }
+        crate::settings::set_mcp_gateway_enabled(enabled)?;
+
+        if !enabled {
+            for app in AppType::all() {
+                if let Err(err) = Self::remove_entry_from_app(MCP_GATEWAY_SERVER_ID, &app) {
+                    log::warn!("从 {app:?} 移除 MCP 网关条目失败: {err}");
+                }
+            }
+        }
+        Self::sync_all_enabled(state)
+    }
+
+    // ========================================================================
+    // 本地密钥库
+    // ========================================================================
+
+    /// 列出本地密钥（只含名称）
+    pub fn list_secrets(state: &AppState) -> Result<Vec<McpSecretInfo>, AppError> {
+        state.db.list_mcp_secrets()
+    }
+
+    /// 保存密钥，并重新同步引用了它的服务器
+    pub fn set_secret(state: &AppState, name: &str, value: &str) -> Result<(), AppError> {
+        if !mcp::is_valid_secret_name(name) {
+            return Err(AppError::InvalidInput(format!(
+                "密钥名称 {name} 无效，只能包含字母、数字、_、-、."
+            )));
+        }
+        if value.is_empty() {
+            return Err(AppError::InvalidInput("密钥值不能为空".to_string()));
+        }
+        state.db.save_mcp_secret(name, value)?;
+
+        for server in Self::servers_referencing_secret(state, name)? {
+            Self::sync_server_to_apps(state, &server)?;
+        }
+        Ok(())
+    }
+
+    /// 删除密钥；仍被服务器引用时拒绝，避免下次同步时写不出配置
+    pub fn delete_secret(state: &AppState, name: &str) -> Result<bool, AppError> {
+        let users: Vec<String> = Self::servers_referencing_secret(state, name)?
+            .into_iter()
+            .map(|server| server.id)
+            .collect();
+        if !users.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "密钥 {name} 仍被 MCP 服务器 {} 引用",
+                users.join(", ")
+            )));
+        }
+        state.db.delete_mcp_secret(name)
+    }
+
+    /// 引用了本地缺失密钥、需要先补齐配置的服务器（id -> 缺失的密钥名称）
+    pub fn get_servers_needing_config(
+        state: &AppState,
+    ) -> Result<HashMap<String, Vec<String>>, AppError> {
+        let secrets = state.db.get_mcp_secret_values()?;
+        Ok(Self::get_all_servers(state)?
+            .into_values()
+            .filter_map(|server| {
+                let missing = mcp::missing_secrets(&server.server, &secrets);
+                (!missing.is_empty()).then(|| (server.id, missing.into_iter().collect()))
+            })
+            .collect())
+    }
+
+    fn servers_referencing_secret(
+        state: &AppState,
+        name: &str,
+    ) -> Result<Vec<McpServer>, AppError> {
+        Ok(Self::get_all_servers(state)?
+            .into_values()
+            .filter(|server| mcp::referenced_secrets(&server.server).contains(name))
+            .collect())
+    }
+
     /// 手动同步所有启用的 MCP 服务器到对应的应用。
     ///
     /// Best-effort：单个应用投影失败（如 ~/.claude.json 坏 JSON）不阻断
@@ -234,6 +482,19 @@ impl McpService {
             return Ok(());
         }
 
+        if crate::settings::get_mcp_gateway_enabled() {
+            let mut any_enabled = false;
+            for server in servers.values() {
+                Self::remove_entry_from_app(&server.id, app)?;
+                any_enabled |= server.apps.is_enabled_for(app);
+            }
+            return if any_enabled {
+                Self::write_gateway_to_app(state, app)
+            } else {
+                Self::remove_entry_from_app(MCP_GATEWAY_SERVER_ID, app)
+            };
+        }
+
         for server in servers.values() {
             if server.apps.is_enabled_for(app) {
                 Self::sync_server_to_app(state, server, app)?;
@@ -307,7 +568,7 @@ impl McpService {
         if count > 0 {
             if let Some(servers) = &temp_config.mcp.servers {
                 let mut existing = state.db.get_all_mcp_servers()?;
-                for server in servers.values() {
+                for server in user_servers(servers) {
                     // 已存在：仅启用 Claude，不覆盖其他字段（与导入模块语义保持一致）
                     let to_save = if let Some(existing_server) = existing.get(&server.id) {
                         let mut merged = existing_server.clone();
@@ -345,7 +606,7 @@ impl McpService {
         if count > 0 {
             if let Some(servers) = &temp_config.mcp.servers {
                 let mut existing = state.db.get_all_mcp_servers()?;
-                for server in servers.values() {
+                for server in user_servers(servers) {
                     // 已存在：仅启用 Codex，不覆盖其他字段（与导入模块语义保持一致）
                     let to_save = if let Some(existing_server) = existing.get(&server.id) {
                         let mut merged = existing_server.clone();
@@ -383,7 +644,7 @@ impl McpService {
         if count > 0 {
             if let Some(servers) = &temp_config.mcp.servers {
                 let mut existing = state.db.get_all_mcp_servers()?;
-                for server in servers.values() {
+                for server in user_servers(servers) {
                     // 已存在：仅启用 Gemini，不覆盖其他字段（与导入模块语义保持一致）
                     let to_save = if let Some(existing_server) = existing.get(&server.id) {
                         let mut merged = existing_server.clone();
@@ -416,7 +677,7 @@ impl McpService {
         if count > 0 {
             if let Some(servers) = &temp_config.mcp.servers {
                 let mut existing = state.db.get_all_mcp_servers()?;
-                for server in servers.values() {
+                for server in user_servers(servers) {
                     let to_save = if let Some(existing_server) = existing.get(&server.id) {
                         let mut merged = existing_server.clone();
                         merged.apps.grokbuild = true;
@@ -447,7 +708,7 @@ impl McpService {
         if count > 0 {
             if let Some(servers) = &temp_config.mcp.servers {
                 let mut existing = state.db.get_all_mcp_servers()?;
-                for server in servers.values() {
+                for server in user_servers(servers) {
                     // 已存在：仅启用 OpenCode，不覆盖其他字段（与导入模块语义保持一致）
                     let to_save = if let Some(existing_server) = existing.get(&server.id) {
                         let mut merged = existing_server.clone();
@@ -485,7 +746,7 @@ impl McpService {
         if count > 0 {
             if let Some(servers) = &temp_config.mcp.servers {
                 let mut existing = state.db.get_all_mcp_servers()?;
-                for server in servers.values() {
+                for server in user_servers(servers) {
                     // 已存在：仅启用 Hermes，不覆盖其他字段（与导入模块语义保持一致）
                     let to_save = if let Some(existing_server) = existing.get(&server.id) {
                         let mut merged = existing_server.clone();
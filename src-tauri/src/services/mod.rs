@@ -1,27 +1,47 @@
+#[cfg(feature = "gui")]
+pub mod auto_sync;
+#[cfg(feature = "gui")]
 pub mod balance;
+#[cfg(feature = "gui")]
 pub mod codex_oauth_models;
 pub mod coding_plan;
 pub mod config;
+#[cfg(feature = "gui")]
 pub mod env_checker;
+#[cfg(feature = "gui")]
 pub mod env_manager;
+#[cfg(feature = "gui")]
+pub mod folder_sync;
+#[cfg(feature = "gui")]
+pub mod git_sync;
 pub mod mcp;
 pub mod model_fetch;
 pub mod model_pricing;
 pub mod omo;
 pub mod pi_prompt_files;
+#[cfg(feature = "gui")]
 pub(crate) mod pi_state;
 pub mod profile;
+pub mod project_binding;
 pub mod prompt;
+pub(crate) mod prompt_fragments;
 pub mod provider;
 pub mod proxy;
+#[cfg(feature = "gui")]
 pub mod s3;
-pub mod s3_auto_sync;
+#[cfg(feature = "gui")]
 pub mod s3_sync;
+#[cfg(feature = "gui")]
 pub mod session_usage;
+#[cfg(feature = "gui")]
 pub mod session_usage_codex;
+#[cfg(feature = "gui")]
 pub mod session_usage_gemini;
+#[cfg(feature = "gui")]
 pub mod session_usage_grokbuild;
+#[cfg(feature = "gui")]
 pub mod session_usage_opencode;
+#[cfg(feature = "gui")]
 pub mod session_usage_pi;
 pub mod skill;
 pub mod speedtest;
@@ -29,18 +49,24 @@ pub mod sql_helpers;
 pub mod stream_check;
 pub mod subscription;
 pub mod subscription_grok;
+#[cfg(feature = "gui")]
 pub mod sync_protocol;
 pub mod usage_cache;
+#[cfg(feature = "gui")]
+pub mod usage_export;
 pub mod usage_stats;
+#[cfg(feature = "gui")]
 pub mod webdav;
-pub mod webdav_auto_sync;
+#[cfg(feature = "gui")]
 pub mod webdav_sync;
 
 pub use config::ConfigService;
 pub use mcp::McpService;
 pub use omo::OmoService;
 pub use prompt::PromptService;
-pub use provider::{ProviderService, ProviderSortUpdate, SwitchResult};
+pub use provider::ProviderService;
+#[cfg(feature = "gui")]
+pub use provider::{ProviderSortUpdate, SwitchResult};
 pub use proxy::ProxyService;
 #[allow(unused_imports)]
 pub use skill::{DiscoverableSkill, Skill, SkillRepo, SkillService};
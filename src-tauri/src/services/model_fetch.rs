@@ -18,10 +18,17 @@ pub struct FetchedModel {
     pub owned_by: Option<String>,
 }
 
-/// OpenAI 兼容的 /v1/models 响应格式
+/// OpenAI 兼容的 /v1/models 响应格式（`models` 为 Gemini 原生 /v1beta/models 格式）
 #[derive(Debug, Deserialize)]
 struct ModelsResponse {
     data: Option<Vec<ModelEntry>>,
+    models: Option<Vec<GeminiModelEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiModelEntry {
+    /// 形如 `models/gemini-2.5-pro`
+    name: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -106,6 +113,16 @@ pub async fn fetch_models(
                     id: m.id,
                     owned_by: m.owned_by,
                 })
+                .chain(resp.models.unwrap_or_default().into_iter().map(|m| {
+                    FetchedModel {
+                        id: m
+                            .name
+                            .strip_prefix("models/")
+                            .unwrap_or(&m.name)
+                            .to_string(),
+                        owned_by: None,
+                    }
+                }))
                 .collect();
 
             models.sort_by(|a, b| a.id.cmp(&b.id));
@@ -614,4 +631,13 @@ mod tests {
         let resp: ModelsResponse = serde_json::from_str(json).unwrap();
         assert!(resp.data.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_parse_response_gemini_models() {
+        let json =
+            r#"{"models":[{"name":"models/gemini-2.5-pro","displayName":"Gemini 2.5 Pro"}]}"#;
+        let resp: ModelsResponse = serde_json::from_str(json).unwrap();
+        assert!(resp.data.is_none());
+        assert_eq!(resp.models.unwrap()[0].name, "models/gemini-2.5-pro");
+    }
 }
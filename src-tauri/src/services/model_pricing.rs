@@ -92,6 +92,7 @@ impl Default for ModelPricingFile {
     }
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelsDevSyncState {
@@ -314,6 +315,7 @@ pub fn sync_local_model_pricing(db: &Database) -> Result<usize, AppError> {
     Ok(upserted + deleted)
 }
 
+#[cfg(feature = "gui")]
 pub fn get_models_dev_sync_state(db: &Database) -> Result<ModelsDevSyncState, AppError> {
     sync_local_model_pricing(db)?;
     let _file_guard = file_lock()
@@ -326,6 +328,7 @@ pub fn get_models_dev_sync_state(db: &Database) -> Result<ModelsDevSyncState, Ap
     })
 }
 
+#[cfg(feature = "gui")]
 pub fn save_models_dev_sync_config(
     db: &Database,
     config: ModelsDevSyncConfig,
@@ -342,6 +345,7 @@ pub fn save_models_dev_sync_config(
 /// Persist only the outcome of a models.dev sync. Keeping this separate from
 /// `save_models_dev_sync_config` prevents a slow startup fetch from restoring
 /// stale switches or model selections that the user changed in the meantime.
+#[cfg(feature = "gui")]
 pub fn record_models_dev_sync_result(
     db: &Database,
     synced_at: Option<i64>,
@@ -360,6 +364,7 @@ pub fn record_models_dev_sync_result(
     write_file_unlocked(&file)
 }
 
+#[cfg(feature = "gui")]
 fn update_model_pricing_batch_inner(
     db: &Database,
     entries: Vec<ModelPricingInfo>,
@@ -428,10 +433,12 @@ fn update_model_pricing_batch_inner(
     Ok(changed)
 }
 
+#[cfg(feature = "gui")]
 pub fn update_model_pricing(db: &Database, entry: ModelPricingInfo) -> Result<usize, AppError> {
     update_model_pricing_batch_inner(db, vec![entry], false)
 }
 
+#[cfg(feature = "gui")]
 pub fn update_model_pricing_batch(
     db: &Database,
     entries: Vec<ModelPricingInfo>,
@@ -439,6 +446,7 @@ pub fn update_model_pricing_batch(
     update_model_pricing_batch_inner(db, entries, true)
 }
 
+#[cfg(feature = "gui")]
 pub fn delete_model_pricing(db: &Database, model_id: &str) -> Result<(), AppError> {
     let model_id = model_id.trim();
     if model_id.is_empty() {
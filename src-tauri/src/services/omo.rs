@@ -1,5 +1,6 @@
 use crate::config::{atomic_write, get_home_dir, write_json_file_with_contents};
 use crate::error::AppError;
+#[cfg(feature = "gui")]
 use crate::opencode_config::get_opencode_dir;
 use crate::provider::Provider;
 use crate::store::AppState;
@@ -9,11 +10,13 @@ use json_five::rt::parser::{
     JSONKeyValuePair as RtJSONKeyValuePair, JSONObjectContext as RtJSONObjectContext,
     JSONText as RtJSONText, JSONValue as RtJSONValue, KeyValuePairContext as RtKeyValuePairContext,
 };
+#[cfg(feature = "gui")]
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OmoLocalFileData {
@@ -674,11 +677,13 @@ pub struct OmoVariant {
     pub preferred_filename: &'static str,
     pub config_candidates: &'static [&'static str],
     pub category: &'static str,
+    #[cfg(feature = "gui")]
     pub provider_prefix: &'static str,
     pub plugin_name: &'static str,
     pub plugin_prefixes: &'static [&'static str],
     pub has_categories: bool,
     pub label: &'static str,
+    #[cfg(feature = "gui")]
     pub import_label: &'static str,
 }
 
@@ -691,11 +696,13 @@ pub const STANDARD: OmoVariant = OmoVariant {
         "oh-my-opencode.json",
     ],
     category: "omo",
+    #[cfg(feature = "gui")]
     provider_prefix: "omo-",
     plugin_name: "oh-my-openagent@latest",
     plugin_prefixes: &["oh-my-openagent", "oh-my-opencode"],
     has_categories: true,
     label: "OMO",
+    #[cfg(feature = "gui")]
     import_label: "Imported",
 };
 
@@ -703,11 +710,13 @@ pub const SLIM: OmoVariant = OmoVariant {
     preferred_filename: "oh-my-opencode-slim.jsonc",
     config_candidates: &["oh-my-opencode-slim.jsonc", "oh-my-opencode-slim.json"],
     category: "omo-slim",
+    #[cfg(feature = "gui")]
     provider_prefix: "omo-slim-",
     plugin_name: "oh-my-opencode-slim@latest",
     plugin_prefixes: &["oh-my-opencode-slim"],
     has_categories: false,
     label: "OMO Slim",
+    #[cfg(feature = "gui")]
     import_label: "Imported Slim",
 };
 
@@ -772,11 +781,13 @@ impl OmoService {
             .unwrap_or_else(|| OmoConfigLocation::Legacy(legacy_dir.join(v.preferred_filename))))
     }
 
+    #[cfg(feature = "gui")]
     fn resolve_local_config_location(v: &OmoVariant) -> Result<OmoConfigLocation, AppError> {
         Self::find_config_location(v, &get_home_dir(), &get_opencode_dir())?
             .ok_or(AppError::OmoConfigNotFound)
     }
 
+    #[cfg(feature = "gui")]
     fn read_jsonc_object(path: &Path) -> Result<Map<String, Value>, AppError> {
         let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
         let parsed: Value = json5::from_str(&content)
@@ -787,6 +798,7 @@ impl OmoService {
             .ok_or_else(|| AppError::Config("Expected JSON object".to_string()))
     }
 
+    #[cfg(feature = "gui")]
     fn read_config_object(location: &OmoConfigLocation) -> Result<Map<String, Value>, AppError> {
         let mut root = Self::read_jsonc_object(location.path())?;
         match location {
@@ -814,6 +826,7 @@ impl OmoService {
 
     // ── Field extraction ───────────────────────────────────
 
+    #[cfg(feature = "gui")]
     fn extract_other_fields_with_keys(
         obj: &Map<String, Value>,
         known: &[&str],
@@ -1048,6 +1061,7 @@ impl OmoService {
         Value::Object(result)
     }
 
+    #[cfg(feature = "gui")]
     pub fn import_from_local(
         state: &AppState,
         v: &OmoVariant,
@@ -1102,6 +1116,7 @@ impl OmoService {
         Ok(provider)
     }
 
+    #[cfg(feature = "gui")]
     pub fn read_local_file(v: &OmoVariant) -> Result<OmoLocalFileData, AppError> {
         let location = Self::resolve_local_config_location(v)?;
         let actual_path = location.path().to_path_buf();
@@ -1120,6 +1135,7 @@ impl OmoService {
         ))
     }
 
+    #[cfg(feature = "gui")]
     fn build_local_file_data(
         v: &OmoVariant,
         obj: &Map<String, Value>,
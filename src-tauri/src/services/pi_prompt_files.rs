@@ -3,6 +3,7 @@
 use crate::config::atomic_write;
 use crate::error::AppError;
 use crate::pi_config::get_pi_agent_dir;
+#[cfg(feature = "gui")]
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
@@ -11,6 +12,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, Mutex, MutexGuard};
 
 const MAX_PROMPT_FILE_BYTES: u64 = 1024 * 1024;
+#[cfg(feature = "gui")]
 const MAX_TEMPLATE_SLUG_BYTES: usize = 128;
 const MISSING_REVISION: &str = "missing";
 static PROMPT_FILE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
@@ -79,6 +81,7 @@ impl PiAgentsFileGuard {
     }
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PiPromptFileKind {
@@ -86,6 +89,7 @@ pub enum PiPromptFileKind {
     SystemAppend,
 }
 
+#[cfg(feature = "gui")]
 impl PiPromptFileKind {
     fn filename(self) -> &'static str {
         match self {
@@ -95,6 +99,7 @@ impl PiPromptFileKind {
     }
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PiPromptFileSnapshot {
@@ -103,8 +108,10 @@ pub struct PiPromptFileSnapshot {
     pub content: String,
 }
 
+#[cfg(feature = "gui")]
 pub struct PiPromptFileService;
 
+#[cfg(feature = "gui")]
 impl PiPromptFileService {
     pub fn read(kind: PiPromptFileKind) -> Result<PiPromptFileSnapshot, AppError> {
         let _guard = lock_prompt_files()?;
@@ -138,6 +145,7 @@ impl PiPromptFileService {
     }
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PiPromptTemplate {
@@ -146,8 +154,10 @@ pub struct PiPromptTemplate {
     pub revision: String,
 }
 
+#[cfg(feature = "gui")]
 pub struct PiPromptTemplateService;
 
+#[cfg(feature = "gui")]
 impl PiPromptTemplateService {
     pub fn list() -> Result<Vec<PiPromptTemplate>, AppError> {
         let _guard = lock_prompt_files()?;
@@ -249,6 +259,7 @@ fn lock_prompt_files() -> Result<MutexGuard<'static, ()>, AppError> {
         .map_err(|error| AppError::Config(format!("Pi prompt file lock is poisoned: {error}")))
 }
 
+#[cfg(feature = "gui")]
 fn read_prompt_file(root: &Path, kind: PiPromptFileKind) -> Result<PiPromptFileSnapshot, AppError> {
     let path = root.join(kind.filename());
     let (exists, content, file_revision) = match fs::File::open(&path) {
@@ -291,6 +302,7 @@ fn ensure_revision(path: &Path, expected: &str, label: &str) -> Result<(), AppEr
     }
 }
 
+#[cfg(feature = "gui")]
 fn read_limited(path: &Path, label: &str) -> Result<Vec<u8>, AppError> {
     let file = fs::File::open(path).map_err(|error| AppError::io(path, error))?;
     read_open_file_limited(file, path, label)
@@ -318,6 +330,7 @@ fn read_open_file_limited(file: fs::File, path: &Path, label: &str) -> Result<Ve
     Ok(bytes)
 }
 
+#[cfg(feature = "gui")]
 fn validate_instruction_content(content: &str) -> Result<(), AppError> {
     if content.trim().is_empty() {
         return Err(AppError::InvalidInput(
@@ -340,10 +353,12 @@ fn revision(bytes: &[u8]) -> String {
     format!("{:x}", Sha256::digest(bytes))
 }
 
+#[cfg(feature = "gui")]
 fn template_path(dir: &Path, slug: &str) -> PathBuf {
     dir.join(format!("{slug}.md"))
 }
 
+#[cfg(feature = "gui")]
 fn validate_template_slug(slug: &str) -> Result<(), AppError> {
     let basename = slug.split('.').next().unwrap_or_default();
     let windows_reserved = matches!(
@@ -0,0 +1,559 @@
+//! 项目绑定：把目录绑定到某个应用的供应商 / MCP 服务器集合
+//!
+//! 全局切换会改写 `~/.claude/settings.json`、`~/.codex/config.toml` 等用户级配置；项目绑定
+//! 只写各 CLI 自己支持的项目级配置，在该目录下启动时覆盖全局设置：
+//! - Claude Code：`<dir>/.claude/settings.local.json`（供应商 env 等）；MCP 写入
+//!   `~/.claude.json` 的 `projects.<dir>.mcpServers`（local scope，不进仓库）
+//! - Codex：`<dir>/.codex/config.toml`（model_provider / model_providers / mcp_servers，
+//!   API Key 写为 `experimental_bearer_token`；Codex 只为受信任的项目加载该文件）
+//! - Gemini CLI：`<dir>/.gemini/.env`（env）与 `<dir>/.gemini/settings.json`（config + mcpServers）
+//!
+//! 每次写入的片段都记录在 `project_bindings.applied` 中，重新绑定或解绑时先按记录撤销；
+//! 撤销只删除仍与写入值一致的键，用户之后手改过的值保留。绑定是写入时的快照，供应商或
+//! MCP 定义修改后需重新保存绑定。仓库内写入的文件会加入 `.git/info/exclude`，避免把
+//! 中转地址与密钥提交进仓库。
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::app_config::AppType;
+use crate::config::{atomic_write_private, get_claude_mcp_path};
+use crate::database::ProjectBinding;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::provider::{
+    build_effective_settings_with_common_config, json_deep_merge, json_deep_remove,
+    merge_toml_item, remove_toml_item, sanitize_claude_settings_for_live,
+};
+use crate::services::McpService;
+use crate::store::AppState;
+
+/// 写入某个文件的一段配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+enum Fragment {
+    Json {
+        path: PathBuf,
+        value: Value,
+    },
+    Toml {
+        path: PathBuf,
+        text: String,
+    },
+    Env {
+        path: PathBuf,
+        entries: BTreeMap<String, String>,
+    },
+}
+
+impl Fragment {
+    fn path(&self) -> &Path {
+        match self {
+            Fragment::Json { path, .. }
+            | Fragment::Toml { path, .. }
+            | Fragment::Env { path, .. } => path,
+        }
+    }
+}
+
+pub struct ProjectBindingService;
+
+impl ProjectBindingService {
+    /// 支持项目级配置的应用
+    pub fn supports(app: &AppType) -> bool {
+        matches!(app, AppType::Claude | AppType::Codex | AppType::Gemini)
+    }
+
+    pub fn list(state: &AppState) -> Result<Vec<ProjectBinding>, AppError> {
+        state.db.get_project_bindings()
+    }
+
+    /// 绑定（或重新绑定）目录：先撤销上次写入，再写入新的项目级配置
+    pub fn bind(
+        state: &AppState,
+        path: &str,
+        app: AppType,
+        provider_id: Option<String>,
+        mcp_server_ids: Vec<String>,
+    ) -> Result<ProjectBinding, AppError> {
+        if !Self::supports(&app) {
+            return Err(AppError::InvalidInput(format!(
+                "{} 不支持项目级配置",
+                app.as_str()
+            )));
+        }
+        let provider_id = provider_id.filter(|id| !id.trim().is_empty());
+        if provider_id.is_none() && mcp_server_ids.is_empty() {
+            return Err(AppError::InvalidInput(
+                "请至少选择一个供应商或 MCP 服务器".to_string(),
+            ));
+        }
+        let dir = normalize_project_path(path)?;
+
+        let provider = provider_id
+            .as_deref()
+            .map(|id| {
+                state
+                    .db
+                    .get_provider_by_id(id, app.as_str())?
+                    .ok_or_else(|| AppError::InvalidInput(format!("供应商 {id} 不存在")))
+            })
+            .transpose()?;
+        let all_servers = state.db.get_all_mcp_servers()?;
+        let mut servers = Vec::new();
+        for id in &mcp_server_ids {
+            let server = all_servers
+                .get(id)
+                .ok_or_else(|| AppError::InvalidInput(format!("MCP 服务器 {id} 不存在")))?;
+            servers.push((id.clone(), McpService::resolve_spec(state, &server.server)?));
+        }
+
+        // 先把要写的内容全部算好，失败时不动任何文件
+        let fragments = build_fragments(state, &app, Path::new(&dir), provider.as_ref(), &servers)?;
+        let applied =
+            serde_json::to_string(&fragments).map_err(|e| AppError::JsonSerialize { source: e })?;
+
+        let previous = state.db.get_project_binding(&dir, app.as_str())?;
+        if let Some((_, previous_applied)) = &previous {
+            revert_applied(previous_applied)?;
+        }
+        for fragment in &fragments {
+            apply_fragment(fragment)?;
+        }
+        exclude_from_git(Path::new(&dir), &fragments);
+
+        let now = chrono::Utc::now().timestamp();
+        let binding = ProjectBinding {
+            path: dir,
+            app_type: app.as_str().to_string(),
+            provider_id,
+            mcp_server_ids,
+            created_at: previous.map_or(now, |(binding, _)| binding.created_at),
+            updated_at: now,
+        };
+        state.db.save_project_binding(&binding, &applied)?;
+        log::info!(
+            "已绑定项目 {} ({})：供应商 {:?}，MCP {:?}",
+            binding.path,
+            binding.app_type,
+            binding.provider_id,
+            binding.mcp_server_ids
+        );
+        Ok(binding)
+    }
+
+    /// 解绑并撤销写入的项目级配置；目录已不存在时只删除记录
+    pub fn unbind(state: &AppState, path: &str, app: AppType) -> Result<bool, AppError> {
+        let Some((binding, applied)) = state.db.get_project_binding(path, app.as_str())? else {
+            return Ok(false);
+        };
+        revert_applied(&applied)?;
+        state
+            .db
+            .delete_project_binding(&binding.path, &binding.app_type)
+    }
+}
+
+/// 项目路径统一为规范化的绝对路径，与 CLI 看到的工作目录一致
+fn normalize_project_path(path: &str) -> Result<String, AppError> {
+    let raw = Path::new(path.trim());
+    if !raw.is_absolute() {
+        return Err(AppError::InvalidInput(format!(
+            "项目路径必须是绝对路径: {path}"
+        )));
+    }
+    let canonical = raw.canonicalize().map_err(|e| AppError::io(raw, e))?;
+    if !canonical.is_dir() {
+        return Err(AppError::InvalidInput(format!(
+            "项目路径不是目录: {}",
+            canonical.display()
+        )));
+    }
+    let normalized = canonical.to_string_lossy().to_string();
+    // Windows 的 canonicalize 会带上 `\\?\` 前缀，CLI 记录的路径没有
+    Ok(normalized
+        .strip_prefix(r"\\?\")
+        .map(str::to_string)
+        .unwrap_or(normalized))
+}
+
+fn build_fragments(
+    state: &AppState,
+    app: &AppType,
+    dir: &Path,
+    provider: Option<&Provider>,
+    servers: &[(String, Value)],
+) -> Result<Vec<Fragment>, AppError> {
+    let settings = provider
+        .map(|provider| build_effective_settings_with_common_config(&state.db, app, provider))
+        .transpose()?;
+    let mut fragments = Vec::new();
+
+    match app {
+        AppType::Claude => {
+            if let Some(settings) = settings {
+                fragments.push(Fragment::Json {
+                    path: dir.join(".claude").join("settings.local.json"),
+                    value: sanitize_claude_settings_for_live(&settings),
+                });
+            }
+            if !servers.is_empty() {
+                let mut entries = Map::new();
+                for (id, spec) in servers {
+                    entries.insert(
+                        id.clone(),
+                        crate::claude_mcp::to_claude_server_entry(id, spec)?,
+                    );
+                }
+                fragments.push(Fragment::Json {
+                    path: get_claude_mcp_path(),
+                    value: json!({
+                        "projects": { dir.to_string_lossy(): { "mcpServers": entries } }
+                    }),
+                });
+            }
+        }
+        AppType::Codex => {
+            let doc = codex_fragment(settings.as_ref(), servers)?;
+            if !doc.is_empty() {
+                fragments.push(Fragment::Toml {
+                    path: dir.join(".codex").join("config.toml"),
+                    text: doc.to_string(),
+                });
+            }
+        }
+        AppType::Gemini => {
+            let gemini_dir = dir.join(".gemini");
+            let mut project_settings = json!({});
+            if let Some(settings) = &settings {
+                let entries: BTreeMap<String, String> =
+                    crate::gemini_config::json_to_env(settings)?
+                        .into_iter()
+                        .collect();
+                if !entries.is_empty() {
+                    fragments.push(Fragment::Env {
+                        path: gemini_dir.join(".env"),
+                        entries,
+                    });
+                }
+                if let Some(config) = settings.get("config").filter(|c| c.is_object()) {
+                    json_deep_merge(&mut project_settings, config);
+                }
+            }
+            if !servers.is_empty() {
+                let mut entries = Map::new();
+                for (id, spec) in servers {
+                    entries.insert(
+                        id.clone(),
+                        crate::gemini_mcp::to_gemini_server_entry(id, spec)?,
+                    );
+                }
+                json_deep_merge(&mut project_settings, &json!({ "mcpServers": entries }));
+            }
+            if project_settings
+                .as_object()
+                .is_some_and(|obj| !obj.is_empty())
+            {
+                fragments.push(Fragment::Json {
+                    path: gemini_dir.join("settings.json"),
+                    value: project_settings,
+                });
+            }
+        }
+        _ => {}
+    }
+    Ok(fragments)
+}
+
+/// Codex 项目级 config.toml：供应商配置原样写入，API Key 转为 bearer token
+fn codex_fragment(
+    settings: Option<&Value>,
+    servers: &[(String, Value)],
+) -> Result<toml_edit::DocumentMut, AppError> {
+    let mut doc = settings
+        .and_then(|settings| settings.get("config"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("供应商 config.toml 无效: {e}")))?;
+
+    let api_key = settings
+        .and_then(|settings| settings.get("auth"))
+        .and_then(crate::codex_config::extract_codex_auth_api_key);
+    let model_provider = doc
+        .get("model_provider")
+        .and_then(|item| item.as_str())
+        .map(str::to_string);
+    if let (Some(api_key), Some(model_provider)) = (api_key, model_provider) {
+        if let Some(table) = doc
+            .get_mut("model_providers")
+            .and_then(|item| item.get_mut(&model_provider))
+            .and_then(toml_edit::Item::as_table_like_mut)
+        {
+            if !table.contains_key("env_key") && !table.contains_key("experimental_bearer_token") {
+                table.insert("experimental_bearer_token", toml_edit::value(api_key));
+            }
+        }
+    }
+
+    if !servers.is_empty() {
+        let mut mcp_servers = toml_edit::Table::new();
+        mcp_servers.set_implicit(true);
+        for (id, spec) in servers {
+            mcp_servers.insert(
+                id,
+                toml_edit::Item::Table(crate::mcp::json_server_to_toml_table(spec)?),
+            );
+        }
+        doc.insert("mcp_servers", toml_edit::Item::Table(mcp_servers));
+    }
+    Ok(doc)
+}
+
+fn apply_fragment(fragment: &Fragment) -> Result<(), AppError> {
+    match fragment {
+        Fragment::Json { path, value } => {
+            let mut root = read_json_object(path)?;
+            json_deep_merge(&mut root, value);
+            write_json(path, &root)
+        }
+        Fragment::Toml { path, text } => {
+            let mut doc = read_toml(path)?;
+            for (key, item) in parse_toml(text)?.iter() {
+                match doc.get_mut(key) {
+                    Some(target) => merge_toml_item(target, item),
+                    None => {
+                        doc.insert(key, item.clone());
+                    }
+                }
+            }
+            atomic_write_private(path, doc.to_string().as_bytes())
+        }
+        Fragment::Env { path, entries } => {
+            let content = read_text(path)?;
+            // 同名键以绑定为准：先去掉旧行再追加
+            let mut lines: Vec<&str> = content
+                .lines()
+                .filter(|line| {
+                    line.split_once('=')
+                        .is_none_or(|(key, _)| !entries.contains_key(key.trim()))
+                })
+                .collect();
+            while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                lines.pop();
+            }
+            let mut out: Vec<String> = lines.into_iter().map(str::to_string).collect();
+            out.extend(entries.iter().map(|(key, value)| format!("{key}={value}")));
+            atomic_write_private(path, format!("{}\n", out.join("\n")).as_bytes())
+        }
+    }
+}
+
+fn revert_applied(applied: &str) -> Result<(), AppError> {
+    if applied.trim().is_empty() {
+        return Ok(());
+    }
+    let fragments: Vec<Fragment> = serde_json::from_str(applied)
+        .map_err(|e| AppError::Config(format!("项目绑定记录损坏: {e}")))?;
+    for fragment in &fragments {
+        revert_fragment(fragment)?;
+    }
+    Ok(())
+}
+
+/// 撤销写入：只删除仍与写入值一致的部分；文件因此变空时一并删除
+fn revert_fragment(fragment: &Fragment) -> Result<(), AppError> {
+    let path = fragment.path();
+    if !path.exists() {
+        return Ok(());
+    }
+    match fragment {
+        Fragment::Json { path, value } => {
+            let mut root = read_json_object(path)?;
+            json_deep_remove(&mut root, value);
+            if root.as_object().is_some_and(Map::is_empty) {
+                remove_file(path)
+            } else {
+                write_json(path, &root)
+            }
+        }
+        Fragment::Toml { path, text } => {
+            let mut doc = read_toml(path)?;
+            for (key, item) in parse_toml(text)?.iter() {
+                let Some(target) = doc.get_mut(key) else {
+                    continue;
+                };
+                remove_toml_item(target, item);
+                if target.is_none() || target.as_table_like().is_some_and(|t| t.is_empty()) {
+                    doc.remove(key);
+                }
+            }
+            if doc.is_empty() {
+                remove_file(path)
+            } else {
+                atomic_write_private(path, doc.to_string().as_bytes())
+            }
+        }
+        Fragment::Env { path, entries } => {
+            let content = read_text(path)?;
+            let doomed: HashMap<String, String> = entries.clone().into_iter().collect();
+            match crate::gemini_config::remove_env_entries_preserving_layout(&content, &doomed) {
+                Some(cleaned) if cleaned.trim().is_empty() => remove_file(path),
+                Some(cleaned) => atomic_write_private(path, cleaned.as_bytes()),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+/// 仓库目录下写入的文件加入 `.git/info/exclude`；失败只记日志
+fn exclude_from_git(dir: &Path, fragments: &[Fragment]) {
+    let git_dir = dir.join(".git");
+    if !git_dir.is_dir() {
+        return;
+    }
+    let exclude_path = git_dir.join("info").join("exclude");
+    let mut content = fs::read_to_string(&exclude_path).unwrap_or_default();
+    let mut changed = false;
+    for fragment in fragments {
+        let Ok(relative) = fragment.path().strip_prefix(dir) else {
+            continue;
+        };
+        let pattern = format!("/{}", relative.to_string_lossy().replace('\\', "/"));
+        if content.lines().any(|line| line.trim() == pattern) {
+            continue;
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&pattern);
+        content.push('\n');
+        changed = true;
+    }
+    if changed {
+        if let Err(e) = crate::config::write_text_file(&exclude_path, &content) {
+            log::warn!("写入 {} 失败: {e}", exclude_path.display());
+        }
+    }
+}
+
+fn read_text(path: &Path) -> Result<String, AppError> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(path).map_err(|e| AppError::io(path, e))
+}
+
+fn read_json_object(path: &Path) -> Result<Value, AppError> {
+    let content = read_text(path)?;
+    if content.trim().is_empty() {
+        return Ok(json!({}));
+    }
+    let value: Value = serde_json::from_str(&content).map_err(|e| AppError::json(path, e))?;
+    if !value.is_object() {
+        return Err(AppError::Config(format!("{} 根必须是对象", path.display())));
+    }
+    Ok(value)
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<(), AppError> {
+    let json =
+        serde_json::to_string_pretty(value).map_err(|e| AppError::JsonSerialize { source: e })?;
+    atomic_write_private(path, json.as_bytes())
+}
+
+fn parse_toml(text: &str) -> Result<toml_edit::DocumentMut, AppError> {
+    text.parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("解析 TOML 失败: {e}")))
+}
+
+fn read_toml(path: &Path) -> Result<toml_edit::DocumentMut, AppError> {
+    parse_toml(&read_text(path)?).map_err(|e| AppError::Config(format!("{}: {e}", path.display())))
+}
+
+fn remove_file(path: &Path) -> Result<(), AppError> {
+    fs::remove_file(path).map_err(|e| AppError::io(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codex_fragment_moves_api_key_into_bearer_token_and_adds_mcp_servers() {
+        let settings = json!({
+            "auth": { "OPENAI_API_KEY": "sk-relay" },
+            "config": "model_provider = \"relay\"\nmodel = \"gpt-5\"\n\n[model_providers.relay]\nname = \"relay\"\nbase_url = \"https://relay.example.com/v1\"\n"
+        });
+        let servers = vec![(
+            "docs".to_string(),
+            json!({ "type": "stdio", "command": "docs-mcp" }),
+        )];
+
+        let doc = codex_fragment(Some(&settings), &servers).unwrap();
+        assert_eq!(doc["model_provider"].as_str(), Some("relay"));
+        assert_eq!(
+            doc["model_providers"]["relay"]["experimental_bearer_token"].as_str(),
+            Some("sk-relay")
+        );
+        assert_eq!(
+            doc["mcp_servers"]["docs"]["command"].as_str(),
+            Some("docs-mcp")
+        );
+    }
+
+    #[test]
+    fn revert_keeps_user_edits_and_unrelated_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("settings.local.json");
+        fs::write(&json_path, r#"{"permissions":{"allow":["Bash"]}}"#).unwrap();
+        let env_path = dir.path().join(".env");
+        fs::write(&env_path, "# keep me\nOTHER=1\nGEMINI_API_KEY=old\n").unwrap();
+        let toml_path = dir.path().join("config.toml");
+
+        let fragments = vec![
+            Fragment::Json {
+                path: json_path.clone(),
+                value: json!({ "env": { "ANTHROPIC_BASE_URL": "https://relay", "ANTHROPIC_AUTH_TOKEN": "sk" } }),
+            },
+            Fragment::Env {
+                path: env_path.clone(),
+                entries: BTreeMap::from([("GEMINI_API_KEY".to_string(), "new".to_string())]),
+            },
+            Fragment::Toml {
+                path: toml_path.clone(),
+                text: "model = \"gpt-5\"\n".to_string(),
+            },
+        ];
+        for fragment in &fragments {
+            apply_fragment(fragment).unwrap();
+        }
+        assert_eq!(
+            fs::read_to_string(&env_path).unwrap(),
+            "# keep me\nOTHER=1\nGEMINI_API_KEY=new\n"
+        );
+
+        // 用户在绑定后手改了一个值：撤销时保留
+        let mut edited = read_json_object(&json_path).unwrap();
+        edited["env"]["ANTHROPIC_BASE_URL"] = json!("https://mine");
+        write_json(&json_path, &edited).unwrap();
+
+        revert_applied(&serde_json::to_string(&fragments).unwrap()).unwrap();
+        assert_eq!(
+            read_json_object(&json_path).unwrap(),
+            json!({ "permissions": { "allow": ["Bash"] }, "env": { "ANTHROPIC_BASE_URL": "https://mine" } })
+        );
+        assert_eq!(
+            fs::read_to_string(&env_path).unwrap(),
+            "# keep me\nOTHER=1\n"
+        );
+        assert!(
+            !toml_path.exists(),
+            "file created by the binding is removed"
+        );
+    }
+}
@@ -1,12 +1,17 @@
 use indexmap::IndexMap;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::app_config::AppType;
 use crate::config::write_text_file;
 use crate::error::AppError;
-use crate::prompt::Prompt;
+use crate::prompt::{Prompt, SharedPrompt};
 use crate::prompt_files::prompt_file_path;
 use crate::services::pi_prompt_files::PiAgentsFileGuard;
+use crate::services::prompt_fragments::{
+    compose, effective_variables, enabled_fragments, is_valid_variable_name, live_edits,
+    shared_fragments_for, split_shared_edit, SHARED_FRAGMENT_PREFIX,
+};
 use crate::store::AppState;
 
 /// 安全地获取当前 Unix 时间戳
@@ -22,32 +27,187 @@ pub struct PromptService;
 fn project_prompt_set_to_path(
     prompts: &IndexMap<String, Prompt>,
     target_path: &Path,
-) -> Result<Option<String>, AppError> {
-    let enabled: Vec<(&String, &Prompt)> = prompts
-        .iter()
-        .filter(|(_, prompt)| prompt.enabled)
-        .collect();
-
-    if let Some((_, prompt)) = enabled.first() {
-        write_text_file(target_path, &prompt.content)?;
+    vars: &BTreeMap<String, String>,
+) -> Result<(), AppError> {
+    let fragments = enabled_fragments(prompts.values());
+    if !fragments.is_empty() {
+        write_text_file(target_path, &compose(&fragments, vars))?;
     } else if target_path.exists() {
         // Match the existing "disable the last prompt" behavior without
         // creating an otherwise unused application config directory.
         write_text_file(target_path, "")?;
     }
+    Ok(())
+}
 
-    if enabled.len() <= 1 {
-        return Ok(None);
+/// 应用的全部片段：自身提示词，加上指向该应用的共享提示词
+fn load_fragments(state: &AppState, app: &AppType) -> Result<IndexMap<String, Prompt>, AppError> {
+    let mut prompts = state.db.get_prompts(app.as_str())?;
+    let shared = state.db.get_shared_prompts()?;
+    for fragment in shared_fragments_for(shared.values(), app) {
+        prompts.insert(fragment.id.clone(), fragment);
     }
+    Ok(prompts)
+}
 
-    let ids = enabled
-        .iter()
-        .map(|(id, _)| id.as_str())
-        .collect::<Vec<_>>()
-        .join(", ");
-    Ok(Some(format!(
-        "多个 Prompt 同时启用，已按稳定顺序投影第一个；enabled IDs: {ids}"
-    )))
+/// 按数据库当前状态重新写入应用的提示词文件
+fn project_live(state: &AppState, app: &AppType, target_path: &Path) -> Result<(), AppError> {
+    let prompts = load_fragments(state, app)?;
+    project_prompt_set_to_path(&prompts, target_path, &effective_variables(app))
+}
+
+/// 将 live 文件中的手动修改回填到对应的已启用片段；
+/// 没有已启用片段或内容无法对应时，另存一条备份提示词（避免重复备份）
+fn backfill_live_edits(
+    state: &AppState,
+    app: &AppType,
+    target_path: &Path,
+) -> Result<(), AppError> {
+    backfill_live_edits_skipping(state, app, target_path, &[])
+}
+
+/// 同 [`backfill_live_edits`]，但忽略 `skip` 中片段的修改（这些片段刚由别的应用更新过）
+fn backfill_live_edits_skipping(
+    state: &AppState,
+    app: &AppType,
+    target_path: &Path,
+    skip: &[String],
+) -> Result<(), AppError> {
+    if !target_path.exists() {
+        return Ok(());
+    }
+    let Ok(live_content) = std::fs::read_to_string(target_path) else {
+        return Ok(());
+    };
+    if live_content.trim().is_empty() {
+        return Ok(());
+    }
+
+    let prompts = load_fragments(state, app)?;
+    let vars = effective_variables(app);
+    let fragments = enabled_fragments(prompts.values());
+
+    if let Some(edits) = live_edits(&live_content, &fragments, &vars) {
+        let timestamp = get_unix_timestamp()?;
+        for (id, content) in edits {
+            if skip.contains(&id) {
+                continue;
+            }
+            if let Some(shared_id) = id.strip_prefix(SHARED_FRAGMENT_PREFIX) {
+                backfill_shared_edit(state, app, shared_id, &content, timestamp)?;
+            } else if let Some(existing) = prompts.get(&id) {
+                let mut prompt = existing.clone();
+                prompt.content = content;
+                prompt.updated_at = Some(timestamp);
+                log::info!("回填 live 提示词内容到已启用片段: {id}");
+                state.db.save_prompt(app.as_str(), &prompt)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let content_exists = prompts
+        .values()
+        .any(|p| p.content.trim() == live_content.trim());
+    if !content_exists {
+        save_backup_prompt(state, app, live_content, get_unix_timestamp()?)?;
+    }
+    Ok(())
+}
+
+/// 把无法对应到片段的 live 内容另存为一条未启用的备份提示词
+fn save_backup_prompt(
+    state: &AppState,
+    app: &AppType,
+    content: String,
+    timestamp: i64,
+) -> Result<(), AppError> {
+    let backup_id = format!("backup-{timestamp}");
+    let backup_prompt = Prompt {
+        id: backup_id.clone(),
+        name: format!(
+            "原始提示词 {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        ),
+        content,
+        description: Some("自动备份的原始提示词".to_string()),
+        enabled: false,
+        sort_index: None,
+        created_at: Some(timestamp),
+        updated_at: Some(timestamp),
+    };
+    log::info!("回填 live 提示词内容，创建备份: {backup_id}");
+    state.db.save_prompt(app.as_str(), &backup_prompt)
+}
+
+/// 把某应用中对共享片段的修改写回共享提示词，并同步到其余目标应用
+fn backfill_shared_edit(
+    state: &AppState,
+    app: &AppType,
+    shared_id: &str,
+    edited: &str,
+    timestamp: i64,
+) -> Result<(), AppError> {
+    let Some(mut shared) = state.db.get_shared_prompts()?.shift_remove(shared_id) else {
+        return Ok(());
+    };
+    let Some((content, appendix)) = split_shared_edit(&shared, app, edited) else {
+        log::warn!("共享提示词 {shared_id} 的正文与附录同时被修改，无法拆分，另存为备份");
+        return save_backup_prompt(state, app, edited.to_string(), timestamp);
+    };
+    shared.content = content;
+    match appendix {
+        Some(appendix) => shared.appendices.insert(app.as_str().to_string(), appendix),
+        None => shared.appendices.remove(app.as_str()),
+    };
+    shared.updated_at = Some(timestamp);
+    log::info!("回填 live 提示词内容到共享提示词: {shared_id}");
+    state.db.save_shared_prompt(&shared)?;
+
+    let fragment_id = format!("{SHARED_FRAGMENT_PREFIX}{shared_id}");
+    for other in shared.apps.enabled_apps() {
+        if other == *app {
+            continue;
+        }
+        let synced = prompt_file_path(&other).and_then(|path| {
+            backfill_live_edits_skipping(state, &other, &path, std::slice::from_ref(&fragment_id))?;
+            project_live(state, &other, &path)
+        });
+        if let Err(error) = synced {
+            log::warn!("同步共享提示词 {shared_id} 到 {other:?} 失败: {error}");
+        }
+    }
+    Ok(())
+}
+
+/// 按 `order` 重写已启用片段的 `sort_index`，仅保存发生变化的条目
+fn persist_fragment_order(
+    state: &AppState,
+    app: &AppType,
+    prompts: &mut IndexMap<String, Prompt>,
+    order: &[String],
+) -> Result<(), AppError> {
+    for (index, id) in order.iter().enumerate() {
+        if let Some(prompt) = prompts.get_mut(id) {
+            let sort_index = Some(index as i64);
+            if prompt.sort_index != sort_index {
+                prompt.sort_index = sort_index;
+                state.db.save_prompt(app.as_str(), prompt)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 当前已启用片段的顺序，`appended` 移到末尾（新启用的片段排在最后）
+fn fragment_order_with_appended(prompts: &IndexMap<String, Prompt>, appended: &str) -> Vec<String> {
+    let mut order: Vec<String> = enabled_fragments(prompts.values())
+        .into_iter()
+        .filter(|p| p.id != appended)
+        .map(|p| p.id.clone())
+        .collect();
+    order.push(appended.to_string());
+    order
 }
 
 impl PromptService {
@@ -71,30 +231,30 @@ impl PromptService {
             return upsert_pi_prompt(state, id, prompt);
         }
 
-        // 检查是否为已启用的提示词
-        let is_enabled = prompt.enabled;
+        // 未启用且此前也未启用的片段不参与文件内容，仅更新数据库
+        let was_enabled = state
+            .db
+            .get_prompts(app.as_str())?
+            .get(id)
+            .is_some_and(|existing| existing.enabled);
+        if !prompt.enabled && !was_enabled {
+            return state.db.save_prompt(app.as_str(), &prompt);
+        }
+
+        let target_path = prompt_file_path(&app)?;
+        backfill_live_edits(state, &app, &target_path)?;
 
+        let mut prompts = state.db.get_prompts(app.as_str())?;
+        let newly_enabled = prompt.enabled && !was_enabled;
         state.db.save_prompt(app.as_str(), &prompt)?;
+        prompts.insert(id.to_string(), prompt);
 
-        if is_enabled {
-            // 启用提示词：写入内容到文件
-            let target_path = prompt_file_path(&app)?;
-            write_text_file(&target_path, &prompt.content)?;
-        } else {
-            // 禁用提示词：检查是否还有其他已启用的提示词
-            let prompts = state.db.get_prompts(app.as_str())?;
-            let any_enabled = prompts.values().any(|p| p.enabled);
-
-            if !any_enabled {
-                // 所有提示词都已禁用，清空文件
-                let target_path = prompt_file_path(&app)?;
-                if target_path.exists() {
-                    write_text_file(&target_path, "")?;
-                }
-            }
+        if newly_enabled {
+            let order = fragment_order_with_appended(&prompts, id);
+            persist_fragment_order(state, &app, &mut prompts, &order)?;
         }
 
-        Ok(())
+        project_live(state, &app, &target_path)
     }
 
     pub fn delete_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
@@ -113,81 +273,172 @@ impl PromptService {
         Ok(())
     }
 
+    /// 只启用该提示词（其余片段全部停用）；叠加启用见 [`Self::enable_fragment`]
     pub fn enable_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
         if matches!(app, AppType::Pi) {
             return enable_pi_prompt(state, id);
         }
 
-        // 回填当前 live 文件内容到已启用的提示词，或创建备份
+        // 回填当前 live 文件中的修改到各已启用片段，或创建备份
         let target_path = prompt_file_path(&app)?;
-        if target_path.exists() {
-            if let Ok(live_content) = std::fs::read_to_string(&target_path) {
-                if !live_content.trim().is_empty() {
-                    let mut prompts = state.db.get_prompts(app.as_str())?;
-
-                    // 尝试回填到当前已启用的提示词
-                    if let Some((enabled_id, enabled_prompt)) = prompts
-                        .iter_mut()
-                        .find(|(_, p)| p.enabled)
-                        .map(|(id, p)| (id.clone(), p))
-                    {
-                        let timestamp = get_unix_timestamp()?;
-                        enabled_prompt.content = live_content.clone();
-                        enabled_prompt.updated_at = Some(timestamp);
-                        log::info!("回填 live 提示词内容到已启用项: {enabled_id}");
-                        state.db.save_prompt(app.as_str(), enabled_prompt)?;
-                    } else {
-                        // 没有已启用的提示词，则创建一次备份（避免重复备份）
-                        let content_exists = prompts
-                            .values()
-                            .any(|p| p.content.trim() == live_content.trim());
-                        if !content_exists {
-                            let timestamp = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs() as i64;
-                            let backup_id = format!("backup-{timestamp}");
-                            let backup_prompt = Prompt {
-                                id: backup_id.clone(),
-                                name: format!(
-                                    "原始提示词 {}",
-                                    chrono::Local::now().format("%Y-%m-%d %H:%M")
-                                ),
-                                content: live_content,
-                                description: Some("自动备份的原始提示词".to_string()),
-                                enabled: false,
-                                created_at: Some(timestamp),
-                                updated_at: Some(timestamp),
-                            };
-                            log::info!("回填 live 提示词内容，创建备份: {backup_id}");
-                            state.db.save_prompt(app.as_str(), &backup_prompt)?;
-                        }
-                    }
-                }
+        backfill_live_edits(state, &app, &target_path)?;
+
+        // 仅启用目标提示词并写入文件
+        let mut prompts = state.db.get_prompts(app.as_str())?;
+        if !prompts.contains_key(id) {
+            return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
+        }
+        for (prompt_id, prompt) in prompts.iter_mut() {
+            let enabled = prompt_id == id;
+            let sort_index = enabled.then_some(0);
+            if prompt.enabled != enabled || prompt.sort_index != sort_index {
+                prompt.enabled = enabled;
+                prompt.sort_index = sort_index;
+                state.db.save_prompt(app.as_str(), prompt)?;
             }
         }
 
-        // 启用目标提示词并写入文件
-        let mut prompts = state.db.get_prompts(app.as_str())?;
+        project_live(state, &app, &target_path)
+    }
 
-        for prompt in prompts.values_mut() {
-            prompt.enabled = false;
+    /// 追加启用一个片段（排在已启用片段末尾），与其他已启用片段一起拼接写入文件
+    pub fn enable_fragment(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
+        if matches!(app, AppType::Pi) {
+            return enable_pi_prompt(state, id);
         }
 
-        if let Some(prompt) = prompts.get_mut(id) {
+        let target_path = prompt_file_path(&app)?;
+        backfill_live_edits(state, &app, &target_path)?;
+
+        let mut prompts = state.db.get_prompts(app.as_str())?;
+        let Some(prompt) = prompts.get_mut(id) else {
+            return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
+        };
+        if !prompt.enabled {
             prompt.enabled = true;
-            write_text_file(&target_path, &prompt.content)?; // 原子写入
             state.db.save_prompt(app.as_str(), prompt)?;
-        } else {
-            return Err(AppError::InvalidInput(format!("提示词 {id} 不存在")));
+            let order = fragment_order_with_appended(&prompts, id);
+            persist_fragment_order(state, &app, &mut prompts, &order)?;
         }
 
-        // Save all prompts to disable others
-        for (_, prompt) in prompts.iter() {
-            state.db.save_prompt(app.as_str(), prompt)?;
+        project_live(state, &app, &target_path)
+    }
+
+    /// 调整已启用片段的拼接顺序；`ids` 之外的已启用片段保持原相对顺序排在其后
+    pub fn reorder_prompts(state: &AppState, app: AppType, ids: &[String]) -> Result<(), AppError> {
+        if matches!(app, AppType::Pi) {
+            return Err(AppError::InvalidInput(
+                "Pi prompts cannot be combined".to_string(),
+            ));
         }
 
-        Ok(())
+        let target_path = prompt_file_path(&app)?;
+        backfill_live_edits(state, &app, &target_path)?;
+
+        let mut prompts = state.db.get_prompts(app.as_str())?;
+        if let Some(id) = ids
+            .iter()
+            .find(|id| !prompts.get(id.as_str()).is_some_and(|p| p.enabled))
+        {
+            return Err(AppError::InvalidInput(format!(
+                "提示词 {id} 不存在或未启用"
+            )));
+        }
+        let mut order = ids.to_vec();
+        order.extend(
+            enabled_fragments(prompts.values())
+                .into_iter()
+                .filter(|p| !ids.contains(&p.id))
+                .map(|p| p.id.clone()),
+        );
+        persist_fragment_order(state, &app, &mut prompts, &order)?;
+
+        project_live(state, &app, &target_path)
+    }
+
+    /// 保存提示词自定义变量，并按新变量重新写入各应用的提示词文件。
+    ///
+    /// 先用旧变量回填 live 文件中的手动修改，避免重新渲染时丢失。
+    pub fn set_variables(state: &AppState, vars: BTreeMap<String, String>) -> Result<(), AppError> {
+        if let Some(name) = vars.keys().find(|name| !is_valid_variable_name(name)) {
+            return Err(AppError::InvalidInput(format!(
+                "变量名 {name} 无效：仅允许字母、数字、_、.、-"
+            )));
+        }
+
+        for app in AppType::all() {
+            if matches!(app, AppType::ClaudeDesktop | AppType::Pi) {
+                continue;
+            }
+            let backfilled = prompt_file_path(&app)
+                .and_then(|target_path| backfill_live_edits(state, &app, &target_path));
+            if let Err(error) = backfilled {
+                log::warn!("回填 {app:?} 提示词修改失败: {error}");
+            }
+        }
+
+        crate::settings::set_prompt_variables(vars)?;
+        Self::sync_all_to_live(state)
+    }
+
+    pub fn get_shared_prompts(
+        state: &AppState,
+    ) -> Result<IndexMap<String, SharedPrompt>, AppError> {
+        state.db.get_shared_prompts()
+    }
+
+    /// 保存共享提示词，并重新写入新旧目标应用的提示词文件
+    pub fn upsert_shared_prompt(state: &AppState, prompt: SharedPrompt) -> Result<(), AppError> {
+        if let Some(key) = prompt.appendices.keys().find(|key| {
+            !key.parse::<AppType>()
+                .is_ok_and(|app| prompt.apps.is_enabled_for(&app))
+        }) {
+            return Err(AppError::InvalidInput(format!(
+                "附录 {key} 对应的应用不是该共享提示词的目标"
+            )));
+        }
+
+        let previous = state.db.get_shared_prompts()?.shift_remove(&prompt.id);
+        let affected = affected_apps(previous.as_ref(), Some(&prompt));
+        Self::rewrite_shared_targets(state, &affected, || state.db.save_shared_prompt(&prompt))
+    }
+
+    /// 删除共享提示词，并从所有目标应用的提示词文件中移除
+    pub fn delete_shared_prompt(state: &AppState, id: &str) -> Result<(), AppError> {
+        let previous = state.db.get_shared_prompts()?.shift_remove(id);
+        let affected = affected_apps(previous.as_ref(), None);
+        Self::rewrite_shared_targets(state, &affected, || state.db.delete_shared_prompt(id))
+    }
+
+    /// 先回填各目标应用 live 文件中的修改，再执行 `mutate`，最后重新写入这些应用
+    fn rewrite_shared_targets(
+        state: &AppState,
+        apps: &[AppType],
+        mutate: impl FnOnce() -> Result<(), AppError>,
+    ) -> Result<(), AppError> {
+        for app in apps {
+            let target_path = prompt_file_path(app)?;
+            backfill_live_edits(state, app, &target_path)?;
+        }
+        mutate()?;
+
+        let mut failures = Vec::new();
+        for app in apps {
+            let projected = prompt_file_path(app)
+                .and_then(|target_path| project_live(state, app, &target_path));
+            if let Err(error) = projected {
+                log::warn!("同步共享提示词到 {app:?} 失败: {error}");
+                failures.push(format!("{}: {error}", app.as_str()));
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Message(format!(
+                "部分应用 Prompt 同步失败: {}",
+                failures.join("; ")
+            )))
+        }
     }
 
     pub fn import_from_file(state: &AppState, app: AppType) -> Result<String, AppError> {
@@ -215,6 +466,7 @@ impl PromptService {
             content,
             description: Some("从现有配置文件导入".to_string()),
             enabled: false,
+            sort_index: None,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -247,12 +499,8 @@ impl PromptService {
             return Ok(());
         }
 
-        let prompts = state.db.get_prompts(app.as_str())?;
         let target_path = prompt_file_path(&app)?;
-        if let Some(warning) = project_prompt_set_to_path(&prompts, &target_path)? {
-            return Err(AppError::Message(warning));
-        }
-        Ok(())
+        project_live(state, &app, &target_path)
     }
 
     /// Best-effort projection for every Prompt-capable application.
@@ -338,6 +586,7 @@ impl PromptService {
             // Pi derives active state from AGENTS.md. Other apps retain their
             // established persisted prompt selection.
             enabled: !matches!(app, AppType::Pi),
+            sort_index: None,
             created_at: Some(timestamp),
             updated_at: Some(timestamp),
         };
@@ -350,6 +599,18 @@ impl PromptService {
     }
 }
 
+/// 共享提示词修改前后涉及的所有目标应用
+fn affected_apps(previous: Option<&SharedPrompt>, next: Option<&SharedPrompt>) -> Vec<AppType> {
+    AppType::all()
+        .filter(|app| {
+            [previous, next]
+                .into_iter()
+                .flatten()
+                .any(|prompt| prompt.apps.is_enabled_for(app))
+        })
+        .collect()
+}
+
 fn pi_active_prompt_id(
     prompts: &IndexMap<String, Prompt>,
     live_content: Option<&str>,
@@ -466,6 +727,7 @@ fn enable_pi_prompt(state: &AppState, id: &str) -> Result<(), AppError> {
                 content: content.clone(),
                 description: Some("自动备份的原始提示词".to_string()),
                 enabled: false,
+                sort_index: None,
                 created_at: Some(timestamp),
                 updated_at: Some(timestamp),
             };
@@ -489,9 +751,10 @@ fn delete_pi_prompt(state: &AppState, id: &str) -> Result<(), AppError> {
 
 #[cfg(test)]
 mod tests {
-    use super::project_prompt_set_to_path;
-    use crate::prompt::Prompt;
-    use indexmap::IndexMap;
+    use super::*;
+    use crate::database::Database;
+    use serial_test::serial;
+    use std::sync::Arc;
     use tempfile::tempdir;
 
     fn prompt(id: &str, content: &str, enabled: bool) -> Prompt {
@@ -501,6 +764,7 @@ mod tests {
             content: content.to_string(),
             description: None,
             enabled,
+            sort_index: None,
             created_at: None,
             updated_at: None,
         }
@@ -514,8 +778,7 @@ mod tests {
         prompts.insert("off".to_string(), prompt("off", "old", false));
         prompts.insert("on".to_string(), prompt("on", "restored", true));
 
-        let warning = project_prompt_set_to_path(&prompts, &path).expect("project prompt");
-        assert!(warning.is_none());
+        project_prompt_set_to_path(&prompts, &path, &BTreeMap::new()).expect("project prompt");
         assert_eq!(
             std::fs::read_to_string(path).expect("read prompt"),
             "restored"
@@ -529,27 +792,194 @@ mod tests {
         std::fs::write(&path, "stale").expect("seed stale prompt");
         let prompts = IndexMap::new();
 
-        let warning = project_prompt_set_to_path(&prompts, &path).expect("clear prompt");
-        assert!(warning.is_none());
+        project_prompt_set_to_path(&prompts, &path, &BTreeMap::new()).expect("clear prompt");
         assert_eq!(std::fs::read_to_string(path).expect("read prompt"), "");
     }
 
     #[test]
-    fn restored_prompt_projection_selects_the_first_enabled_prompt_deterministically() {
+    fn restored_prompt_projection_combines_enabled_fragments_in_sort_order() {
         let temp = tempdir().expect("tempdir");
         let path = temp.path().join("AGENTS.md");
         let mut prompts = IndexMap::new();
-        prompts.insert("first".to_string(), prompt("first", "first body", true));
-        prompts.insert("second".to_string(), prompt("second", "second body", true));
+        let mut first = prompt("first", "first body", true);
+        first.sort_index = Some(1);
+        let mut second = prompt("second", "second body for {{user}}", true);
+        second.sort_index = Some(0);
+        prompts.insert("first".to_string(), first);
+        prompts.insert("second".to_string(), second);
+        let vars = BTreeMap::from([("user".to_string(), "alice".to_string())]);
+
+        project_prompt_set_to_path(&prompts, &path, &vars).expect("project prompt");
+        let live = std::fs::read_to_string(path).expect("read prompt");
+        let second_at = live.find("second body for alice").expect("second fragment");
+        let first_at = live.find("first body").expect("first fragment");
+        assert!(second_at < first_at, "unexpected order: {live}");
+    }
 
-        let warning = project_prompt_set_to_path(&prompts, &path)
-            .expect("project prompt")
-            .expect("duplicate enabled prompts should warn");
-        assert!(warning.contains("first, second"));
+    #[test]
+    #[serial]
+    fn live_edits_round_trip_into_enabled_fragments() {
+        let home = tempdir().expect("tempdir");
+        std::env::set_var("CC_SWITCH_TEST_HOME", home.path());
+        crate::settings::update_settings(crate::settings::AppSettings::default())
+            .expect("reset settings");
+        let state = AppState::new(Arc::new(
+            Database::memory().expect("create in-memory database"),
+        ));
+        for (id, content) in [
+            ("base", "Be terse."),
+            ("rust", "Prefer Rust."),
+            ("go", "Go."),
+        ] {
+            state
+                .db
+                .save_prompt(AppType::Claude.as_str(), &prompt(id, content, false))
+                .expect("save prompt");
+        }
+
+        PromptService::enable_prompt(&state, AppType::Claude, "base").expect("enable base");
+        PromptService::enable_fragment(&state, AppType::Claude, "rust").expect("add rust");
+        let path = prompt_file_path(&AppType::Claude).expect("prompt path");
+        let live = std::fs::read_to_string(&path).expect("read CLAUDE.md");
+        assert!(live.find("Be terse.").unwrap() < live.find("Prefer Rust.").unwrap());
+
+        write_text_file(&path, &live.replace("Prefer Rust.", "Prefer Rust 2024."))
+            .expect("edit CLAUDE.md");
+        PromptService::reorder_prompts(&state, AppType::Claude, &["rust".to_string()])
+            .expect("reorder");
+
+        let prompts = state
+            .db
+            .get_prompts(AppType::Claude.as_str())
+            .expect("load");
+        assert_eq!(prompts["rust"].content, "Prefer Rust 2024.");
+        assert_eq!(prompts["rust"].sort_index, Some(0));
+        assert_eq!(prompts["base"].sort_index, Some(1));
+        let live = std::fs::read_to_string(&path).expect("read CLAUDE.md");
+        assert!(live.find("Prefer Rust 2024.").unwrap() < live.find("Be terse.").unwrap());
+
+        PromptService::enable_prompt(&state, AppType::Claude, "go").expect("enable go only");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), "Go.");
+        let prompts = state
+            .db
+            .get_prompts(AppType::Claude.as_str())
+            .expect("load");
+        assert!(!prompts["base"].enabled && !prompts["rust"].enabled);
+    }
+
+    #[test]
+    #[serial]
+    fn shared_prompt_syncs_to_every_target_and_takes_edits_back() {
+        let home = tempdir().expect("tempdir");
+        std::env::set_var("CC_SWITCH_TEST_HOME", home.path());
+        crate::settings::update_settings(crate::settings::AppSettings::default())
+            .expect("reset settings");
+        let state = AppState::new(Arc::new(
+            Database::memory().expect("create in-memory database"),
+        ));
+        let shared = SharedPrompt {
+            id: "team".to_string(),
+            name: "Team".to_string(),
+            content: "Team rules.".to_string(),
+            description: None,
+            apps: crate::app_config::PromptApps {
+                claude: true,
+                codex: true,
+                ..Default::default()
+            },
+            appendices: BTreeMap::from([("codex".to_string(), "Codex only.".to_string())]),
+            sort_index: None,
+            created_at: Some(1),
+            updated_at: Some(1),
+        };
+        PromptService::upsert_shared_prompt(&state, shared.clone()).expect("save shared");
+
+        let claude_path = prompt_file_path(&AppType::Claude).expect("claude path");
+        let codex_path = prompt_file_path(&AppType::Codex).expect("codex path");
         assert_eq!(
-            std::fs::read_to_string(path).expect("read prompt"),
-            "first body"
+            std::fs::read_to_string(&claude_path).expect("read"),
+            "Team rules."
+        );
+        assert_eq!(
+            std::fs::read_to_string(&codex_path).expect("read"),
+            "Team rules.\n\nCodex only."
+        );
+
+        // 在 Claude 中编辑共享内容，下次写入时回填并同步到 Codex
+        write_text_file(&claude_path, "Team rules v2.").expect("edit CLAUDE.md");
+        state
+            .db
+            .save_prompt(AppType::Claude.as_str(), &prompt("own", "Own.", false))
+            .expect("save own prompt");
+        PromptService::enable_fragment(&state, AppType::Claude, "own").expect("enable own");
+
+        let stored = &state.db.get_shared_prompts().expect("load shared")["team"];
+        assert_eq!(stored.content, "Team rules v2.");
+        assert_eq!(
+            std::fs::read_to_string(&codex_path).expect("read"),
+            "Team rules v2.\n\nCodex only."
+        );
+        let claude_live = std::fs::read_to_string(&claude_path).expect("read");
+        assert!(claude_live.find("Team rules v2.").unwrap() < claude_live.find("Own.").unwrap());
+
+        PromptService::delete_shared_prompt(&state, "team").expect("delete shared");
+        assert_eq!(std::fs::read_to_string(&codex_path).expect("read"), "");
+        assert_eq!(std::fs::read_to_string(&claude_path).expect("read"), "Own.");
+
+        let mut invalid = shared;
+        invalid.appendices = BTreeMap::from([("gemini".to_string(), "x".to_string())]);
+        assert!(PromptService::upsert_shared_prompt(&state, invalid).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn shared_edit_touching_content_and_appendix_is_backed_up() {
+        let home = tempdir().expect("tempdir");
+        std::env::set_var("CC_SWITCH_TEST_HOME", home.path());
+        crate::settings::update_settings(crate::settings::AppSettings::default())
+            .expect("reset settings");
+        let state = AppState::new(Arc::new(
+            Database::memory().expect("create in-memory database"),
+        ));
+        let shared = SharedPrompt {
+            id: "team".to_string(),
+            name: "Team".to_string(),
+            content: "Team rules.".to_string(),
+            description: None,
+            apps: crate::app_config::PromptApps {
+                claude: true,
+                codex: true,
+                ..Default::default()
+            },
+            appendices: BTreeMap::from([("codex".to_string(), "Codex only.".to_string())]),
+            sort_index: None,
+            created_at: Some(1),
+            updated_at: Some(1),
+        };
+        PromptService::upsert_shared_prompt(&state, shared).expect("save shared");
+
+        // 正文与附录同时改动时无法拆分：共享提示词与附录保持原样，修改另存为备份
+        let codex_path = prompt_file_path(&AppType::Codex).expect("codex path");
+        write_text_file(&codex_path, "Team rules v2.\n\nCodex only v2.").expect("edit AGENTS.md");
+        state
+            .db
+            .save_prompt(AppType::Codex.as_str(), &prompt("own", "Own.", false))
+            .expect("save own prompt");
+        PromptService::enable_fragment(&state, AppType::Codex, "own").expect("enable own");
+
+        let stored = &state.db.get_shared_prompts().expect("load shared")["team"];
+        assert_eq!(stored.content, "Team rules.");
+        assert_eq!(
+            stored.appendices.get("codex").map(String::as_str),
+            Some("Codex only.")
         );
+        let prompts = state.db.get_prompts(AppType::Codex.as_str()).expect("load");
+        let backup = prompts
+            .values()
+            .find(|p| p.id.starts_with("backup-"))
+            .expect("backup prompt");
+        assert_eq!(backup.content, "Team rules v2.\n\nCodex only v2.");
+        assert!(!backup.enabled);
     }
 }
 
@@ -568,6 +998,7 @@ mod pi_prompt_tests {
             content: "managed content".to_string(),
             description: None,
             enabled,
+            sort_index: None,
             created_at: Some(1),
             updated_at: Some(1),
         }
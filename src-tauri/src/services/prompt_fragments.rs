@@ -0,0 +1,399 @@
+//! 提示词片段组合
+//!
+//! 每个应用可同时启用多个提示词片段，按 `sort_index`（其次按列表顺序）拼接后写入
+//! CLAUDE.md / AGENTS.md / GEMINI.md 等文件。启用多个片段时，每段以 HTML 注释标记包裹，
+//! 用户直接编辑 live 文件后可据此把修改回填到对应片段；只启用一个片段时保持原样输出，
+//! 与旧版单提示词文件完全一致。
+//!
+//! 共享提示词（[`SharedPrompt`]）以 `shared:<id>` 片段的形式加入每个目标应用，整体排在
+//! 应用自身片段之前；各应用的附录接在共享正文之后。
+//!
+//! 写入前会替换 `{{variable}}` 占位符：内置变量（`user`、`os`、`arch`、`home`、`app`）
+//! 与设置中的自定义变量（同名时自定义优先）；未知变量原样保留。
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::app_config::AppType;
+use crate::prompt::{Prompt, SharedPrompt};
+
+/// 共享提示词在各应用片段中的 ID 前缀
+pub(crate) const SHARED_FRAGMENT_PREFIX: &str = "shared:";
+
+const SECTION_BEGIN_PREFIX: &str = "<!-- cc-switch:begin ";
+const SECTION_END_PREFIX: &str = "<!-- cc-switch:end ";
+const SECTION_SUFFIX: &str = " -->";
+
+static VARIABLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}").expect("valid prompt variable regex")
+});
+
+/// 内置变量；`app` 为应用 ID（如 `claude`）
+pub(crate) fn builtin_variables(app: &AppType) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    vars.insert("user".to_string(), user);
+    vars.insert("os".to_string(), std::env::consts::OS.to_string());
+    vars.insert("arch".to_string(), std::env::consts::ARCH.to_string());
+    vars.insert(
+        "home".to_string(),
+        crate::config::get_home_dir().display().to_string(),
+    );
+    vars.insert("app".to_string(), app.as_str().to_string());
+    vars
+}
+
+/// 当前生效的变量表：内置变量 + 设置中的自定义变量
+pub(crate) fn effective_variables(app: &AppType) -> BTreeMap<String, String> {
+    let mut vars = builtin_variables(app);
+    vars.extend(crate::settings::get_prompt_variables());
+    vars
+}
+
+/// 变量名只允许字母、数字与 `_` `.` `-`，与占位符语法一致
+pub(crate) fn is_valid_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// 替换 `{{name}}` 占位符，未定义的变量保持原样
+pub(crate) fn render(template: &str, vars: &BTreeMap<String, String>) -> String {
+    VARIABLE_RE
+        .replace_all(template, |caps: &regex::Captures<'_>| {
+            vars.get(&caps[1])
+                .cloned()
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// 已启用片段的拼接顺序：`sort_index` 升序，未设置的排在最后，其余保持列表顺序
+pub(crate) fn enabled_fragments<'a>(
+    prompts: impl IntoIterator<Item = &'a Prompt>,
+) -> Vec<&'a Prompt> {
+    let mut enabled: Vec<&Prompt> = prompts.into_iter().filter(|p| p.enabled).collect();
+    enabled.sort_by_key(|p| p.sort_index.unwrap_or(i64::MAX));
+    enabled
+}
+
+/// 共享提示词在某应用中的内容：共享正文，加上该应用的附录（如有）
+pub(crate) fn shared_content_for(shared: &SharedPrompt, app: &AppType) -> String {
+    match shared_appendix(shared, app) {
+        Some(appendix) => format!("{}\n\n{appendix}", shared.content),
+        None => shared.content.clone(),
+    }
+}
+
+fn shared_appendix<'a>(shared: &'a SharedPrompt, app: &AppType) -> Option<&'a str> {
+    shared
+        .appendices
+        .get(app.as_str())
+        .map(String::as_str)
+        .filter(|appendix| !appendix.trim().is_empty())
+}
+
+/// 指向 `app` 的共享提示词转换成的片段，按共享提示词顺序排在应用自身片段之前
+pub(crate) fn shared_fragments_for<'a>(
+    shared: impl IntoIterator<Item = &'a SharedPrompt>,
+    app: &AppType,
+) -> Vec<Prompt> {
+    shared
+        .into_iter()
+        .filter(|prompt| prompt.apps.is_enabled_for(app))
+        .enumerate()
+        .map(|(index, prompt)| Prompt {
+            id: format!("{SHARED_FRAGMENT_PREFIX}{}", prompt.id),
+            name: prompt.name.clone(),
+            content: shared_content_for(prompt, app),
+            description: prompt.description.clone(),
+            enabled: true,
+            sort_index: Some(i64::MIN + index as i64),
+            created_at: prompt.created_at,
+            updated_at: prompt.updated_at,
+        })
+        .collect()
+}
+
+/// 把某应用中对共享片段的修改拆回 `(共享正文, 该应用附录)`。
+///
+/// 附录未变时修改归入共享正文；正文未变时修改归入附录；两者都变时无法判断分界，
+/// 返回 `None`，由调用方备份该修改而不改动共享提示词，避免丢失附录。
+pub(crate) fn split_shared_edit(
+    shared: &SharedPrompt,
+    app: &AppType,
+    edited: &str,
+) -> Option<(String, Option<String>)> {
+    let Some(appendix) = shared_appendix(shared, app) else {
+        return Some((edited.to_string(), None));
+    };
+    if let Some(content) = edited.strip_suffix(&format!("\n\n{appendix}")) {
+        return Some((content.to_string(), Some(appendix.to_string())));
+    }
+    if let Some(appendix) = edited.strip_prefix(&format!("{}\n\n", shared.content)) {
+        return Some((shared.content.clone(), Some(appendix.to_string())));
+    }
+    None
+}
+
+/// 生成写入 live 文件的内容
+pub(crate) fn compose(fragments: &[&Prompt], vars: &BTreeMap<String, String>) -> String {
+    match fragments {
+        [] => String::new(),
+        [single] => render(&single.content, vars),
+        many => many
+            .iter()
+            .map(|p| {
+                format!(
+                    "{SECTION_BEGIN_PREFIX}{id}{SECTION_SUFFIX}\n{body}\n{SECTION_END_PREFIX}{id}{SECTION_SUFFIX}",
+                    id = p.id,
+                    body = render(&p.content, vars),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+/// 把 live 文件内容映射回各片段模板。
+///
+/// 返回需要更新的 `(片段 ID, 新模板)`；live 内容无法对应到当前启用的片段
+/// （标记缺失/损坏、段落增删、标记外有内容）时返回 `None`，由调用方另存备份。
+pub(crate) fn live_edits(
+    live: &str,
+    fragments: &[&Prompt],
+    vars: &BTreeMap<String, String>,
+) -> Option<Vec<(String, String)>> {
+    if live == compose(fragments, vars) {
+        return Some(Vec::new());
+    }
+
+    let sections = match fragments {
+        [] => return None,
+        [single] => vec![(single.id.clone(), live.to_string())],
+        _ => parse_sections(live)?,
+    };
+
+    let expected: HashSet<&str> = fragments.iter().map(|p| p.id.as_str()).collect();
+    let seen: HashSet<&str> = sections.iter().map(|(id, _)| id.as_str()).collect();
+    if sections.len() != fragments.len() || seen != expected {
+        return None;
+    }
+
+    Some(
+        sections
+            .into_iter()
+            .filter_map(|(id, body)| {
+                let fragment = fragments.iter().find(|p| p.id == id)?;
+                if body == render(&fragment.content, vars) {
+                    return None;
+                }
+                Some((id, unrender(&body, &fragment.content, vars)))
+            })
+            .collect(),
+    )
+}
+
+/// 解析多片段文件；标记外只允许空白
+fn parse_sections(live: &str) -> Option<Vec<(String, String)>> {
+    let mut sections = Vec::new();
+    let mut rest = live;
+    loop {
+        let trimmed = rest.trim_start();
+        if trimmed.is_empty() {
+            return Some(sections);
+        }
+        let after_begin = trimmed.strip_prefix(SECTION_BEGIN_PREFIX)?;
+        let (id, after_id) = after_begin.split_once(SECTION_SUFFIX)?;
+        let body_start = after_id.strip_prefix('\n')?;
+        let end_marker = format!("\n{SECTION_END_PREFIX}{id}{SECTION_SUFFIX}");
+        let (body, after_end) = body_start.split_once(&end_marker)?;
+        sections.push((id.to_string(), body.to_string()));
+        rest = after_end;
+    }
+}
+
+/// 尽量保留编辑后文本中的占位符：与模板某行渲染结果完全相同的行还原为该模板行
+fn unrender(edited: &str, template: &str, vars: &BTreeMap<String, String>) -> String {
+    let rendered_lines: Vec<(String, &str)> = template
+        .lines()
+        .filter(|line| VARIABLE_RE.is_match(line))
+        .map(|line| (render(line, vars), line))
+        .collect();
+    if rendered_lines.is_empty() {
+        return edited.to_string();
+    }
+
+    edited
+        .split('\n')
+        .map(|line| {
+            rendered_lines
+                .iter()
+                .find(|(rendered, _)| rendered == line)
+                .map(|(_, original)| *original)
+                .unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(id: &str, content: &str, sort_index: Option<i64>) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: content.to_string(),
+            description: None,
+            enabled: true,
+            sort_index,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn vars() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("user".to_string(), "alice".to_string()),
+            ("lang".to_string(), "Rust".to_string()),
+        ])
+    }
+
+    #[test]
+    fn render_substitutes_known_variables_and_keeps_unknown() {
+        assert_eq!(
+            render("Hi {{ user }}, write {{lang}}; {{missing}}", &vars()),
+            "Hi alice, write Rust; {{missing}}"
+        );
+    }
+
+    #[test]
+    fn fragments_are_ordered_by_sort_index_then_list_order() {
+        let mut disabled = fragment("off", "x", Some(0));
+        disabled.enabled = false;
+        let prompts = [
+            fragment("late", "", None),
+            fragment("second", "", Some(2)),
+            disabled,
+            fragment("first", "", Some(1)),
+        ];
+        let ids: Vec<_> = enabled_fragments(&prompts)
+            .into_iter()
+            .map(|p| p.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["first", "second", "late"]);
+    }
+
+    #[test]
+    fn single_fragment_is_written_without_markers() {
+        let base = fragment("base", "Hello {{user}}", None);
+        assert_eq!(compose(&[&base], &vars()), "Hello alice");
+    }
+
+    #[test]
+    fn edits_round_trip_into_the_matching_fragment() {
+        let base = fragment("base", "Be terse.\nUser: {{user}}", Some(0));
+        let lang = fragment("lang", "Prefer {{lang}}.\n", Some(1));
+        let fragments = [&base, &lang];
+        let live = compose(&fragments, &vars());
+        assert!(live.starts_with("<!-- cc-switch:begin base -->\nBe terse."));
+        assert_eq!(live_edits(&live, &fragments, &vars()), Some(Vec::new()));
+
+        let edited = live.replace("Be terse.", "Be terse and kind.");
+        assert_eq!(
+            live_edits(&edited, &fragments, &vars()),
+            Some(vec![(
+                "base".to_string(),
+                "Be terse and kind.\nUser: {{user}}".to_string()
+            )])
+        );
+    }
+
+    fn shared(appendix: Option<&str>) -> SharedPrompt {
+        let apps = crate::app_config::PromptApps {
+            claude: true,
+            codex: true,
+            ..Default::default()
+        };
+        SharedPrompt {
+            id: "team".to_string(),
+            name: "Team".to_string(),
+            content: "Team rules.".to_string(),
+            description: None,
+            apps,
+            appendices: appendix
+                .map(|a| BTreeMap::from([("codex".to_string(), a.to_string())]))
+                .unwrap_or_default(),
+            sort_index: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn shared_prompts_become_leading_fragments_with_per_app_appendix() {
+        let prompt = shared(Some("Codex extra."));
+        let own = fragment("own", "Own.", Some(0));
+
+        let claude = shared_fragments_for([&prompt], &AppType::Claude);
+        assert_eq!(claude[0].id, "shared:team");
+        assert_eq!(claude[0].content, "Team rules.");
+        assert!(shared_fragments_for([&prompt], &AppType::Gemini).is_empty());
+
+        let codex = shared_fragments_for([&prompt], &AppType::Codex);
+        assert_eq!(codex[0].content, "Team rules.\n\nCodex extra.");
+        let ids: Vec<_> = enabled_fragments(codex.iter().chain([&own]))
+            .into_iter()
+            .map(|p| p.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["shared:team", "own"]);
+    }
+
+    #[test]
+    fn shared_edits_split_between_content_and_appendix() {
+        let prompt = shared(Some("Codex extra."));
+        assert_eq!(
+            split_shared_edit(&prompt, &AppType::Codex, "New rules.\n\nCodex extra."),
+            Some(("New rules.".to_string(), Some("Codex extra.".to_string())))
+        );
+        assert_eq!(
+            split_shared_edit(&prompt, &AppType::Codex, "Team rules.\n\nCodex more."),
+            Some(("Team rules.".to_string(), Some("Codex more.".to_string())))
+        );
+        assert_eq!(
+            split_shared_edit(&prompt, &AppType::Codex, "All new."),
+            None
+        );
+        assert_eq!(
+            split_shared_edit(&prompt, &AppType::Codex, "New rules.\n\nCodex more."),
+            None
+        );
+        assert_eq!(
+            split_shared_edit(&prompt, &AppType::Claude, "Claude edit."),
+            Some(("Claude edit.".to_string(), None))
+        );
+    }
+
+    #[test]
+    fn unmappable_live_content_is_reported() {
+        let base = fragment("base", "a", Some(0));
+        let lang = fragment("lang", "b", Some(1));
+        let fragments = [&base, &lang];
+        let live = compose(&fragments, &vars());
+
+        assert_eq!(
+            live_edits(&format!("preamble\n{live}"), &fragments, &vars()),
+            None
+        );
+        let only_base = compose(&[&base, &fragment("other", "c", None)], &vars());
+        assert_eq!(live_edits(&only_base, &fragments, &vars()), None);
+        assert_eq!(live_edits("plain text", &fragments, &vars()), None);
+    }
+}
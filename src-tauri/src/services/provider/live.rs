@@ -241,7 +241,7 @@ fn json_remove_array_items(target_arr: &mut Vec<Value>, source_arr: &[Value]) {
     }
 }
 
-fn json_deep_merge(target: &mut Value, source: &Value) {
+pub(crate) fn json_deep_merge(target: &mut Value, source: &Value) {
     match (target, source) {
         (Value::Object(target_map), Value::Object(source_map)) => {
             for (key, source_value) in source_map {
@@ -259,7 +259,7 @@ fn json_deep_merge(target: &mut Value, source: &Value) {
     }
 }
 
-fn json_deep_remove(target: &mut Value, source: &Value) {
+pub(crate) fn json_deep_remove(target: &mut Value, source: &Value) {
     let (Some(target_map), Some(source_map)) = (target.as_object_mut(), source.as_object()) else {
         return;
     };
@@ -375,7 +375,7 @@ fn toml_item_is_subset(target: &Item, source: &Item) -> bool {
     }
 }
 
-fn merge_toml_item(target: &mut Item, source: &Item) {
+pub(crate) fn merge_toml_item(target: &mut Item, source: &Item) {
     if let Some(source_table) = source.as_table_like() {
         if let Some(target_table) = target.as_table_like_mut() {
             merge_toml_table_like(target_table, source_table);
@@ -397,7 +397,7 @@ fn merge_toml_table_like(target: &mut dyn TableLike, source: &dyn TableLike) {
     }
 }
 
-fn remove_toml_item(target: &mut Item, source: &Item) {
+pub(crate) fn remove_toml_item(target: &mut Item, source: &Item) {
     if let Some(source_table) = source.as_table_like() {
         if let Some(target_table) = target.as_table_like_mut() {
             remove_toml_table_like(target_table, source_table);
@@ -455,6 +455,7 @@ fn remove_toml_table_like(target: &mut dyn TableLike, source: &dyn TableLike) {
 /// 结构化合并/剥离。必须在后端用 toml_edit 做：前端 smol-toml 只能
 /// parse → merge → 整文档重序列化，注释全丢、键序重排，还会生成多余的
 /// 空父表头（如 `[model_providers]`）。
+#[cfg(feature = "gui")]
 pub fn update_toml_common_config_snippet(
     config_toml: &str,
     snippet_toml: &str,
@@ -720,6 +721,7 @@ pub(crate) fn write_live_with_common_config_for_codex_oauth_manager(
     provider: &Provider,
     codex_oauth_manager: &Arc<CodexOAuthManager>,
 ) -> Result<(), AppError> {
+    ensure_secrets_unlocked(provider)?;
     let effective_provider = build_effective_provider_for_live_with_codex_oauth_manager(
         db,
         app_type,
@@ -800,6 +802,27 @@ fn apply_codex_official_auth(
     Ok(())
 }
 
+/// 在调用方开的独立线程里驱动 OAuth manager 的异步调用。
+/// 桌面端复用 tauri 的全局运行时；无界面构建（cc-switch-cli）使用本模块自己的运行时。
+fn block_on_oauth<F: std::future::Future>(future: F) -> F::Output {
+    #[cfg(feature = "gui")]
+    {
+        tauri::async_runtime::block_on(future)
+    }
+    #[cfg(not(feature = "gui"))]
+    {
+        static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+        RUNTIME
+            .get_or_init(|| {
+                tokio::runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .expect("创建 OAuth 异步运行时失败")
+            })
+            .block_on(future)
+    }
+}
+
 /// 构建写入托管 Codex `auth.json` 的完整可刷新 auth（含 refresh_token + last_refresh）。
 ///
 /// 步骤：
@@ -815,7 +838,7 @@ fn get_codex_managed_oauth_live_auth_value(
     account_id: String,
 ) -> Result<Value, AppError> {
     std::thread::spawn(move || {
-        tauri::async_runtime::block_on(async move {
+        block_on_oauth(async move {
             if let Some((refresh_token, id_token, last_refresh_ms)) =
                 crate::codex_config::read_codex_live_auth_refresh_for_account(&account_id)
             {
@@ -874,7 +897,7 @@ pub(crate) fn prepare_codex_managed_oauth_live_auth_switch_away(
     account_id: String,
 ) -> Result<Option<String>, AppError> {
     std::thread::spawn(move || {
-        tauri::async_runtime::block_on(async move {
+        block_on_oauth(async move {
             manager
                 .prepare_live_auth_for_account_switch_away(&account_id)
                 .await
@@ -1239,7 +1262,26 @@ impl LiveSnapshot {
 }
 
 /// Write live configuration snapshot for a provider
+/// 密钥被锁定的供应商不写入 live 配置，以免用空密钥覆盖应用现有配置
+pub(crate) fn ensure_secrets_unlocked(provider: &Provider) -> Result<(), AppError> {
+    if !provider.secrets_locked() {
+        return Ok(());
+    }
+    Err(AppError::localized(
+        "provider.secrets_locked",
+        format!(
+            "供应商 {} 的密钥无法用本机主密钥解密，未写入 live 配置。请设置 CC_SWITCH_SECRETS_PASSPHRASE 或重新填写密钥",
+            provider.id
+        ),
+        format!(
+            "The secrets of provider {} cannot be decrypted with this device's master key, so it was not written to the live config. Set CC_SWITCH_SECRETS_PASSPHRASE or re-enter its keys",
+            provider.id
+        ),
+    ))
+}
+
 pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Result<(), AppError> {
+    ensure_secrets_unlocked(provider)?;
     match app_type {
         AppType::Claude => {
             let path = get_claude_settings_path();
@@ -1969,6 +2011,7 @@ pub(crate) fn remove_opencode_provider_from_live(provider_id: &str) -> Result<()
 /// This imports existing providers from ~/.config/opencode/opencode.json
 /// into the CC Switch database. Each provider found will be added to the
 /// database with is_current set to false.
+#[cfg(feature = "gui")]
 pub fn import_opencode_providers_from_live(state: &AppState) -> Result<usize, AppError> {
     use crate::opencode_config;
 
@@ -2044,6 +2087,7 @@ pub fn import_opencode_providers_from_live(state: &AppState) -> Result<usize, Ap
 /// This imports existing providers from ~/.openclaw/openclaw.json
 /// into the CC Switch database. Each provider found will be added to the
 /// database with is_current set to false.
+#[cfg(feature = "gui")]
 pub fn import_openclaw_providers_from_live(state: &AppState) -> Result<usize, AppError> {
     use crate::openclaw_config;
 
@@ -2132,6 +2176,7 @@ pub fn import_openclaw_providers_from_live(state: &AppState) -> Result<usize, Ap
 /// This imports existing providers from ~/.hermes/config.yaml
 /// into the CC Switch database. Each provider found will be added to the
 /// database with is_current set to false.
+#[cfg(feature = "gui")]
 pub fn import_hermes_providers_from_live(state: &AppState) -> Result<usize, AppError> {
     use crate::hermes_config;
 
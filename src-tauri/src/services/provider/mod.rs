@@ -23,12 +23,16 @@ use crate::store::AppState;
 
 // Re-export sub-module functions for external access
 pub use live::{
-    import_default_config, import_hermes_providers_from_live, import_openclaw_providers_from_live,
-    import_opencode_providers_from_live, read_live_settings,
-    should_import_default_config_on_startup, sync_current_to_live,
-    update_toml_common_config_snippet,
+    import_default_config, read_live_settings, should_import_default_config_on_startup,
+    sync_current_to_live,
+};
+#[cfg(feature = "gui")]
+pub use live::{
+    import_hermes_providers_from_live, import_openclaw_providers_from_live,
+    import_opencode_providers_from_live, update_toml_common_config_snippet,
 };
 
+#[cfg(feature = "gui")]
 pub fn import_pi_providers_from_live(state: &AppState) -> Result<usize, AppError> {
     pi::import_from_live(state)
 }
@@ -37,8 +41,9 @@ pub fn import_pi_providers_from_live(state: &AppState) -> Result<usize, AppError
 pub(crate) use live::sanitize_claude_settings_for_live;
 pub(crate) use live::{
     build_effective_provider_for_live_with_codex_oauth_manager,
-    build_effective_settings_with_common_config, normalize_provider_common_config_for_storage,
-    provider_exists_in_live_config, strip_common_config_from_live_settings,
+    build_effective_settings_with_common_config, ensure_secrets_unlocked, json_deep_merge,
+    json_deep_remove, merge_toml_item, normalize_provider_common_config_for_storage,
+    provider_exists_in_live_config, remove_toml_item, strip_common_config_from_live_settings,
     sync_current_provider_for_app_to_live, write_live_with_common_config_for_codex_oauth_manager,
     write_live_with_common_config_for_state,
 };
@@ -4785,6 +4790,7 @@ impl ProviderService {
         Ok(true)
     }
 
+    #[cfg(feature = "gui")]
     pub(crate) fn update_pi_usage_script(
         state: &AppState,
         id: &str,
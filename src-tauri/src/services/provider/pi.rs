@@ -1,7 +1,9 @@
 use super::{ProviderService, SwitchResult};
 use crate::app_config::AppType;
 use crate::error::AppError;
-use crate::provider::{Provider, ProviderMeta, UsageScript};
+#[cfg(feature = "gui")]
+use crate::provider::UsageScript;
+use crate::provider::{Provider, ProviderMeta};
 use crate::store::AppState;
 use indexmap::IndexMap;
 use serde_json::Value;
@@ -23,6 +25,7 @@ pub(super) fn list(state: &AppState) -> Result<IndexMap<String, Provider>, AppEr
     state.db.get_all_providers(PI_APP)
 }
 
+#[cfg(feature = "gui")]
 pub(super) fn import_from_live(state: &AppState) -> Result<usize, AppError> {
     let _guard = futures::executor::block_on(state.proxy_service.lock_switch_for_app(PI_APP));
     let native = crate::pi_config::read_pi_native_providers()?;
@@ -82,6 +85,7 @@ pub(super) fn add(
     Ok(true)
 }
 
+#[cfg(feature = "gui")]
 pub(super) fn update_usage_script(
     state: &AppState,
     id: &str,
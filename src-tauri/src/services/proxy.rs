@@ -6,18 +6,20 @@ use crate::app_config::AppType;
 use crate::config::{get_claude_settings_path, read_json_file, write_json_file};
 use crate::database::Database;
 use crate::provider::Provider;
+use crate::proxy::capture::CaptureReplayResult;
 use crate::proxy::providers::codex_oauth_auth::CodexOAuthManager;
 use crate::proxy::server::ProxyServer;
 use crate::proxy::switch_lock::SwitchLockManager;
 use crate::proxy::types::*;
 use crate::services::provider::{
     build_effective_provider_for_live_with_codex_oauth_manager,
-    build_effective_settings_with_common_config,
+    build_effective_settings_with_common_config, ensure_secrets_unlocked,
     write_live_with_common_config_for_codex_oauth_manager,
 };
 use serde_json::{json, Map, Value};
 use std::str::FromStr;
 use std::sync::Arc;
+#[cfg(feature = "gui")]
 use tauri::Emitter;
 use tokio::sync::RwLock;
 
@@ -391,7 +393,7 @@ pub struct ProxyService {
     codex_oauth_manager: Arc<CodexOAuthManager>,
     server: Arc<RwLock<Option<ProxyServer>>>,
     /// AppHandle，用于传递给 ProxyServer 以支持故障转移时的 UI 更新
-    app_handle: Arc<RwLock<Option<tauri::AppHandle>>>,
+    app_handle: Arc<RwLock<Option<crate::proxy::AppHandle>>>,
     switch_locks: SwitchLockManager,
 }
 
@@ -400,6 +402,23 @@ pub struct HotSwitchOutcome {
     pub logical_target_changed: bool,
 }
 
+/// 客户端连接本地代理用的 origin。
+///
+/// listen_address 可能是 0.0.0.0（用于监听所有网卡），但客户端无法用 0.0.0.0 连接；
+/// 因此写回到各应用配置时，优先使用本机回环地址。
+pub(crate) fn proxy_connect_origin(listen_address: &str, port: u16) -> String {
+    let connect_host = match listen_address {
+        "0.0.0.0" => "127.0.0.1",
+        "::" => "::1",
+        other => other,
+    };
+    if connect_host.contains(':') && !connect_host.starts_with('[') {
+        format!("http://[{connect_host}]:{port}")
+    } else {
+        format!("http://{connect_host}:{port}")
+    }
+}
+
 impl ProxyService {
     pub fn new(db: Arc<Database>) -> Self {
         let codex_oauth_manager =
@@ -915,6 +934,7 @@ impl ProxyService {
     }
 
     /// 设置 AppHandle（在应用初始化时调用）
+    #[cfg(feature = "gui")]
     pub fn set_app_handle(&self, handle: tauri::AppHandle) {
         futures::executor::block_on(async {
             *self.app_handle.write().await = Some(handle);
@@ -1287,6 +1307,7 @@ impl ProxyService {
                             &app, &provider,
                         )
                     {
+                        #[cfg(feature = "gui")]
                         if let Some(handle) = self.app_handle.read().await.as_ref() {
                             let _ = handle.emit(
                                 "proxy-official-warning",
@@ -1929,19 +1950,6 @@ impl ProxyService {
             .await
             .map_err(|e| format!("获取代理配置失败: {e}"))?;
 
-        // listen_address 可能是 0.0.0.0（用于监听所有网卡），但客户端无法用 0.0.0.0 连接；
-        // 因此写回到各应用配置时，优先使用本机回环地址。
-        let connect_host = match config.listen_address.as_str() {
-            "0.0.0.0" => "127.0.0.1".to_string(),
-            "::" => "::1".to_string(),
-            _ => config.listen_address.clone(),
-        };
-        let connect_host_for_url = if connect_host.contains(':') && !connect_host.starts_with('[') {
-            format!("[{connect_host}]")
-        } else {
-            connect_host
-        };
-
         let mut listen_port = config.listen_port;
         if let Some(server) = self.server.read().await.as_ref() {
             let status = server.get_status().await;
@@ -1953,7 +1961,7 @@ impl ProxyService {
             return Err("代理监听端口为 0，但代理服务器尚未运行，无法生成接管地址".to_string());
         }
 
-        let proxy_origin = format!("http://{}:{}", connect_host_for_url, listen_port);
+        let proxy_origin = proxy_connect_origin(&config.listen_address, listen_port);
         let proxy_url = proxy_origin.clone();
         let proxy_codex_base_url = format!("{}/v1", proxy_origin.trim_end_matches('/'));
 
@@ -2806,6 +2814,7 @@ impl ProxyService {
     ) -> Result<(), String> {
         let app_type_enum =
             AppType::from_str(app_type).map_err(|_| format!("未知的应用类型: {app_type}"))?;
+        ensure_secrets_unlocked(provider).map_err(|e| e.to_string())?;
         let mut effective_settings = if matches!(app_type_enum, AppType::Codex) {
             build_effective_provider_for_live_with_codex_oauth_manager(
                 self.db.as_ref(),
@@ -3941,11 +3950,119 @@ impl ProxyService {
         Ok(())
     }
 
+    /// 从数据库重新加载代理配置并实时应用到运行中的服务器
+    ///
+    /// 供无界面守护进程收到 SIGHUP 时调用：全局代理配置通过 `apply_runtime_config` 生效，
+    /// 各应用的熔断器配置同步热更新。监听地址/端口变更需要重启进程才能生效。
+    pub async fn reload_runtime_config(&self) -> Result<(), String> {
+        let config = self.get_config().await?;
+
+        let server_guard = self.server.read().await;
+        let Some(server) = server_guard.as_ref() else {
+            return Err("代理服务器未运行".to_string());
+        };
+
+        let status = server.get_status().await;
+        if status.address != config.listen_address
+            || (config.listen_port != 0 && status.port != config.listen_port)
+        {
+            log::warn!(
+                "监听地址已变更为 {}:{}，需重启代理进程后生效（当前 {}:{}）",
+                config.listen_address,
+                config.listen_port,
+                status.address,
+                status.port
+            );
+        }
+        server.apply_runtime_config(&config).await;
+
+        for app_type in ["claude", "codex", "gemini", "grokbuild"] {
+            match self.db.get_proxy_config_for_app(app_type).await {
+                Ok(app_config) => {
+                    server
+                        .update_circuit_breaker_config_for_app(
+                            app_type,
+                            crate::proxy::CircuitBreakerConfig::from(&app_config),
+                        )
+                        .await;
+                }
+                Err(e) => log::warn!("读取 {app_type} 代理配置失败，保留原熔断器配置: {e}"),
+            }
+        }
+
+        log::info!("代理配置已重新加载");
+        Ok(())
+    }
+
     /// 检查服务器是否正在运行
     pub async fn is_running(&self) -> bool {
         self.server.read().await.is_some()
     }
 
+    /// 重放抓包：把抓到的客户端请求重新发给本地代理，可指定供应商；重放请求本身也会被抓包
+    pub async fn replay_capture(
+        &self,
+        capture_id: &str,
+        provider_id: Option<String>,
+    ) -> Result<CaptureReplayResult, String> {
+        let capture = self
+            .db
+            .get_proxy_capture(capture_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("抓包不存在: {capture_id}"))?;
+        if capture.inbound.truncated {
+            return Err("抓包的请求体已被截断，无法重放".to_string());
+        }
+
+        let (nonce, status) = {
+            let server_guard = self.server.read().await;
+            let server = server_guard
+                .as_ref()
+                .ok_or_else(|| "代理服务器未运行".to_string())?;
+            (
+                server.issue_replay(capture_id, provider_id),
+                server.get_status().await,
+            )
+        };
+
+        let method = reqwest::Method::from_bytes(capture.inbound.method.as_bytes())
+            .map_err(|e| format!("无效的请求方法: {e}"))?;
+        let url = format!(
+            "{}{}",
+            proxy_connect_origin(&status.address, status.port),
+            capture.inbound.url
+        );
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .timeout(std::time::Duration::from_secs(600))
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))?;
+        let mut request = client
+            .request(method, url)
+            .header(crate::proxy::capture::REPLAY_HEADER, nonce);
+        for (name, value) in crate::proxy::capture::replayable_headers(&capture.inbound) {
+            request = request.header(name, value);
+        }
+        let response = request
+            .body(capture.inbound.body)
+            .send()
+            .await
+            .map_err(|e| format!("重放请求失败: {e}"))?;
+
+        let status = response.status().as_u16();
+        let new_capture_id = response
+            .headers()
+            .get(crate::proxy::capture::CAPTURE_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        // 读完响应体，抓包在响应流结束后才落库
+        let _ = response.bytes().await;
+        Ok(CaptureReplayResult {
+            capture_id: new_capture_id,
+            status,
+        })
+    }
+
     /// 热更新熔断器配置
     ///
     /// 如果代理服务器正在运行，将新配置应用到所有已创建的熔断器实例
@@ -14,10 +14,12 @@ use crate::settings::{update_s3_sync_status, S3SyncSettings, WebDavSyncStatus};
 
 pub(crate) use super::sync_protocol::run_with_sync_lock;
 use super::sync_protocol::{
-    apply_snapshot, build_local_snapshot, localized, persist_sync_success_best_effort, sha256_hex,
-    validate_artifact_size_limit, validate_manifest_compat, verify_artifact, ArtifactMeta,
-    RemoteLayout, SyncManifest, DB_COMPAT_VERSION, MAX_MANIFEST_BYTES, MAX_SYNC_ARTIFACT_BYTES,
-    PROTOCOL_VERSION, REMOTE_DB_SQL, REMOTE_MANIFEST, REMOTE_SKILLS_ZIP,
+    apply_snapshot, build_local_snapshot, load_sync_base, localized, open_artifact,
+    persist_sync_success_best_effort, record_merge_outcome, sha256_hex, store_sync_base,
+    unlock_snapshot, validate_artifact_size_limit, validate_manifest_compat, verify_artifact,
+    ArtifactMeta, RemoteLayout, SyncManifest, DB_COMPAT_VERSION, MAX_MANIFEST_BYTES,
+    MAX_SYNC_ARTIFACT_BYTES, REMOTE_DB_SQL, REMOTE_LAYOUT_VERSION, REMOTE_MANIFEST,
+    REMOTE_SKILLS_ZIP,
 };
 
 #[cfg(test)]
@@ -42,7 +44,7 @@ pub async fn upload(
     settings.validate()?;
     let creds = creds_for(settings);
 
-    let snapshot = build_local_snapshot(db)?;
+    let snapshot = build_local_snapshot(db, settings.sync_passphrase())?;
 
     // Upload order: artifacts first, manifest last (best-effort consistency)
     let db_key = s3_key(settings, REMOTE_DB_SQL);
@@ -69,6 +71,7 @@ pub async fn upload(
         }
     };
 
+    store_sync_base(&remote_identity(settings), &snapshot.plain_db_sql);
     let _persisted = persist_sync_success_best_effort(
         settings,
         snapshot.manifest_hash,
@@ -104,19 +107,26 @@ pub async fn download(
         })?;
 
     validate_manifest_compat(&manifest, RemoteLayout::Current)?;
+    let key = unlock_snapshot(&manifest, settings.sync_passphrase())?;
 
-    // Download and verify artifacts
+    // Download and verify artifacts, then decrypt them if the snapshot is sealed
     let db_sql = download_and_verify(settings, &creds, REMOTE_DB_SQL, &manifest.artifacts).await?;
     let skills_zip =
         download_and_verify(settings, &creds, REMOTE_SKILLS_ZIP, &manifest.artifacts).await?;
+    let db_sql = open_artifact(key.as_ref(), REMOTE_DB_SQL, db_sql)?;
+    let skills_zip = open_artifact(key.as_ref(), REMOTE_SKILLS_ZIP, skills_zip)?;
 
-    // Apply snapshot
-    apply_snapshot(db, &db_sql, &skills_zip)?;
+    // Apply snapshot, merging against the last shared snapshot when one exists
+    let identity = remote_identity(settings);
+    let base_sql = load_sync_base(&identity);
+    let report = apply_snapshot(db, &db_sql, &skills_zip, base_sql.as_deref())?;
+    store_sync_base(&identity, &db_sql);
+    let merge = record_merge_outcome(report.as_ref());
 
     let manifest_hash = sha256_hex(&manifest_bytes);
     let _persisted =
         persist_sync_success_best_effort(settings, manifest_hash, etag, persist_sync_success);
-    Ok(serde_json::json!({ "status": "downloaded" }))
+    Ok(serde_json::json!({ "status": "downloaded", "merge": merge }))
 }
 
 /// Fetch remote manifest info without downloading artifacts.
@@ -144,6 +154,7 @@ pub async fn fetch_remote_info(settings: &S3SyncSettings) -> Result<Option<Value
         "protocolVersion": manifest.version,
         "dbCompatVersion": manifest.db_compat_version,
         "compatible": compatible,
+        "encrypted": manifest.encryption.is_some(),
         "artifacts": manifest.artifacts.keys().collect::<Vec<_>>(),
         "layout": RemoteLayout::Current.as_str(),
         "remotePath": s3_dir_display(settings),
@@ -207,19 +218,29 @@ async fn download_and_verify(
 
 /// Build the S3 object key for a given artifact.
 ///
-/// Format: `{remote_root}/v{PROTOCOL_VERSION}/db-v{DB_COMPAT_VERSION}/{profile}/{artifact}`
+/// Format: `{remote_root}/v{REMOTE_LAYOUT_VERSION}/db-v{DB_COMPAT_VERSION}/{profile}/{artifact}`
 /// Example: `cc-switch-sync/v2/db-v6/default/manifest.json`
 fn s3_key(settings: &S3SyncSettings, artifact: &str) -> String {
     format!(
         "{}/v{}/db-v{}/{}/{}",
-        settings.remote_root, PROTOCOL_VERSION, DB_COMPAT_VERSION, settings.profile, artifact
+        settings.remote_root, REMOTE_LAYOUT_VERSION, DB_COMPAT_VERSION, settings.profile, artifact
     )
 }
 
 fn s3_dir_display(settings: &S3SyncSettings) -> String {
     format!(
         "{}/v{}/db-v{}/{}",
-        settings.remote_root, PROTOCOL_VERSION, DB_COMPAT_VERSION, settings.profile
+        settings.remote_root, REMOTE_LAYOUT_VERSION, DB_COMPAT_VERSION, settings.profile
+    )
+}
+
+/// Identity of the remote snapshot location, used to key the merge base.
+fn remote_identity(settings: &S3SyncSettings) -> String {
+    format!(
+        "s3:{}:{}/{}",
+        settings.endpoint,
+        settings.bucket,
+        s3_dir_display(settings)
     )
 }
 
@@ -279,6 +279,7 @@ fn sync_single_file(db: &Database, file_path: &Path) -> Result<(u32, u32), AppEr
     let mut line_offset: i64 = 0;
     let mut messages: HashMap<String, ParsedAssistantUsage> = HashMap::new();
     let mut current_session_id: Option<String> = None;
+    let mut project_dir: Option<String> = None;
 
     for line_result in reader.lines() {
         line_offset += 1;
@@ -308,6 +309,11 @@ fn sync_single_file(db: &Database, file_path: &Path) -> Result<(u32, u32), AppEr
                 current_session_id = Some(sid.to_string());
             }
         }
+        if project_dir.is_none() {
+            if let Some(cwd) = value.get("cwd").and_then(|v| v.as_str()) {
+                project_dir = Some(cwd.to_string());
+            }
+        }
 
         // 只处理 assistant 类型的消息
         if value.get("type").and_then(|t| t.as_str()) != Some("assistant") {
@@ -426,6 +432,17 @@ fn sync_single_file(db: &Database, file_path: &Path) -> Result<(u32, u32), AppEr
         }
     }
 
+    if let (Some(session_id), Some(project_dir)) = (&current_session_id, &project_dir) {
+        // 子 agent transcript 的 sessionId 指向主会话，回链消息应指向主会话文件
+        let is_subagent = file_path
+            .components()
+            .any(|c| c.as_os_str() == std::ffi::OsStr::new("subagents"));
+        let source_path = (!is_subagent).then_some(file_path_str.as_str());
+        if let Err(e) = record_session_project(db, "claude", session_id, project_dir, source_path) {
+            log::warn!("[SESSION-SYNC] 记录会话项目目录失败 ({session_id}): {e}");
+        }
+    }
+
     // 更新同步状态
     update_sync_state(db, &file_path_str, file_modified, line_offset)?;
 
@@ -493,6 +510,70 @@ pub(crate) fn update_sync_state_on_conn(
     Ok(())
 }
 
+/// 记录会话所属的项目目录，并给该会话已落库、尚未归属项目的用量行补上 project_dir。
+///
+/// 供所有 session_usage_* 解析器共用。
+pub(crate) fn record_session_project(
+    db: &Database,
+    app_type: &str,
+    session_id: &str,
+    project_dir: &str,
+    source_path: Option<&str>,
+) -> Result<(), AppError> {
+    let conn = lock_conn!(db.conn);
+    record_session_project_on_conn(&conn, app_type, session_id, project_dir, source_path)
+}
+
+/// [`record_session_project`] 的免锁版本，供调用方在已持锁的事务内使用。
+///
+/// 代理行的 session_id 对 Responses 客户端带 `<app>_` 前缀（见
+/// `proxy::session::extract_session_id`），回填时两种写法都匹配；
+/// 代理落库时按同样规则反查本表，见 `UsageLogger::log_request`。
+pub(crate) fn record_session_project_on_conn(
+    conn: &rusqlite::Connection,
+    app_type: &str,
+    session_id: &str,
+    project_dir: &str,
+    source_path: Option<&str>,
+) -> Result<(), AppError> {
+    let project_dir = project_dir.trim();
+    if session_id.is_empty() || project_dir.is_empty() {
+        return Ok(());
+    }
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    conn.prepare_cached(
+        "INSERT INTO session_projects (session_id, app_type, project_dir, source_path, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(session_id) DO UPDATE SET
+            app_type = excluded.app_type,
+            project_dir = excluded.project_dir,
+            source_path = COALESCE(excluded.source_path, session_projects.source_path),
+            updated_at = excluded.updated_at",
+    )
+    .and_then(|mut stmt| {
+        stmt.execute(rusqlite::params![
+            session_id,
+            app_type,
+            project_dir,
+            source_path,
+            now
+        ])
+    })
+    .map_err(|e| AppError::Database(format!("记录会话项目目录失败: {e}")))?;
+
+    conn.prepare_cached(
+        "UPDATE proxy_request_logs SET project_dir = ?3
+         WHERE session_id IN (?1, ?2 || '_' || ?1) AND project_dir IS NULL",
+    )
+    .and_then(|mut stmt| stmt.execute(rusqlite::params![session_id, app_type, project_dir]))
+    .map_err(|e| AppError::Database(format!("回填用量项目目录失败: {e}")))?;
+    Ok(())
+}
+
 /// 插入单条会话日志到 proxy_request_logs，返回是否成功插入 (true=新插入, false=已存在)
 fn insert_session_log_entry(
     db: &Database,
@@ -897,4 +978,44 @@ mod tests {
         fs::remove_dir_all(&tmp).ok();
         Ok(())
     }
+
+    #[test]
+    fn test_sync_attributes_session_and_proxy_rows_to_project() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        {
+            let conn = lock_conn!(db.conn);
+            conn.execute(
+                "INSERT INTO proxy_request_logs (
+                    request_id, provider_id, app_type, model, input_tokens, output_tokens,
+                    latency_ms, status_code, session_id, created_at
+                ) VALUES ('proxy-1', 'p1', 'claude', 'claude-haiku-4-5', 1, 1, 0, 200, 'session-p', 1)",
+                [],
+            )?;
+        }
+        let tmp = std::env::temp_dir().join(format!("cc-switch-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&tmp).unwrap();
+        let file = tmp.join("session-p.jsonl");
+        let line = r#"{"type":"assistant","cwd":"/work/app","message":{"id":"msg_p","model":"claude-sonnet-4-5","usage":{"input_tokens":10,"output_tokens":5}},"timestamp":"2026-06-07T13:01:23Z","sessionId":"session-p"}"#;
+        fs::write(&file, format!("{line}\n")).unwrap();
+
+        sync_single_file(&db, &file)?;
+
+        let conn = lock_conn!(db.conn);
+        let attributed: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM proxy_request_logs WHERE project_dir = '/work/app'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(attributed, 2, "会话行与同会话的代理行都应归属到项目");
+        let source_path: String = conn.query_row(
+            "SELECT source_path FROM session_projects WHERE session_id = 'session-p'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(source_path, file.to_string_lossy());
+        drop(conn);
+
+        fs::remove_dir_all(&tmp).ok();
+        Ok(())
+    }
 }
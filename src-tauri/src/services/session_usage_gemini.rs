@@ -19,7 +19,8 @@ use crate::gemini_config::get_gemini_dir;
 use crate::proxy::usage::calculator::{CostCalculator, ModelPricing};
 use crate::proxy::usage::parser::TokenUsage;
 use crate::services::session_usage::{
-    get_sync_state, metadata_modified_nanos, update_sync_state, SessionSyncResult,
+    get_sync_state, metadata_modified_nanos, record_session_project, update_sync_state,
+    SessionSyncResult,
 };
 use crate::services::usage_stats::{find_model_pricing, should_skip_session_insert, DedupKey};
 use rust_decimal::Decimal;
@@ -210,6 +211,19 @@ fn sync_single_gemini_file(db: &Database, file_path: &Path) -> Result<(u32, u32)
         }
     }
 
+    // tmp/<project_hash>/.project_root 记录了该哈希对应的项目目录
+    let project_dir = file_path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(|dir| fs::read_to_string(dir.join(".project_root")).ok());
+    if let (Some(session_id), Some(project_dir)) = (&session_id, &project_dir) {
+        if let Err(e) =
+            record_session_project(db, "gemini", session_id, project_dir, Some(&file_path_str))
+        {
+            log::warn!("[GEMINI-SYNC] 记录会话项目目录失败 ({session_id}): {e}");
+        }
+    }
+
     // 更新同步状态
     update_sync_state(db, &file_path_str, file_modified, gemini_msg_count)?;
 
@@ -8,7 +8,8 @@ use crate::error::AppError;
 use crate::proxy::usage::calculator::CostCalculator;
 use crate::proxy::usage::parser::TokenUsage;
 use crate::services::session_usage::{
-    metadata_modified_nanos, update_sync_state_on_conn, SessionSyncResult,
+    metadata_modified_nanos, record_session_project_on_conn, update_sync_state_on_conn,
+    SessionSyncResult,
 };
 use crate::services::sql_helpers::INPUT_TOKEN_SEMANTICS_FRESH;
 use crate::services::usage_stats::find_model_pricing;
@@ -96,6 +97,8 @@ struct PiUsageRecord {
 #[derive(Debug)]
 struct ParsedPiFile {
     records: Vec<PiUsageRecord>,
+    session_id: Option<String>,
+    project_dir: Option<String>,
     last_complete_line: i64,
     incomplete_tail: bool,
 }
@@ -225,6 +228,16 @@ fn sync_single_pi_file(db: &Database, file_path: &Path) -> Result<SessionSyncRes
         }
     }
 
+    if let (Some(session_id), Some(project_dir)) = (&parsed.session_id, &parsed.project_dir) {
+        record_session_project_on_conn(
+            &tx,
+            APP_TYPE,
+            session_id,
+            project_dir,
+            Some(&file_path_string),
+        )?;
+    }
+
     update_pi_sync_state_on_conn(&tx, &file_path_string, revision, parsed.last_complete_line)?;
     tx.commit()
         .map_err(|error| AppError::Database(format!("提交 Pi 用量导入事务失败: {error}")))?;
@@ -353,6 +366,7 @@ fn parse_pi_file(
     let mut line_number = 0i64;
     let mut bytes_read = 0u64;
     let mut session_id = None;
+    let mut project_dir = None;
     let mut session_timestamp = None;
     let mut records = Vec::new();
     let mut incomplete_tail = false;
@@ -422,6 +436,7 @@ fn parse_pi_file(
             if session_id.is_none() {
                 return Err(AppError::Config("Pi 会话 header 缺少 id".to_string()));
             }
+            project_dir = value.get("cwd").and_then(Value::as_str).map(str::to_string);
             let header_timestamp_millis = value.get("timestamp").and_then(parse_timestamp_millis);
             session_timestamp = header_timestamp_millis.map(|timestamp| timestamp / 1000);
             if let Some(byte_offset) = start_at_byte.filter(|offset| *offset >= bytes_read) {
@@ -448,6 +463,8 @@ fn parse_pi_file(
     }
     Ok(ParsedPiFile {
         records,
+        session_id,
+        project_dir,
         last_complete_line: line_number,
         incomplete_tail,
     })
@@ -12,13 +12,17 @@
 //!
 //! ## 与故障转移的关系（重要不变量）
 //!
-//! 连通性检查 **绝不** 触碰故障转移熔断器：一个返回 403/401 的供应商在本检查里
-//! 算"可达"，但它对真实流量是坏的。熔断器只由 `proxy/forwarder.rs` 转发真实流量
+//! 手动检查 **绝不** 触碰故障转移熔断器：一个返回 403/401 的供应商在本检查里
+//! 算"可达"，但它对真实流量是坏的。熔断器主要由 `proxy/forwarder.rs` 转发真实流量
 //! 的成败驱动（被动）。两者职责分离——可达性回答"能不能到"，真实流量回答"能不能用"。
+//!
+//! 唯一的例外是代理运行期间的后台定时探测（`proxy/health_prober.rs`）：不可达时提前
+//! 打开熔断器，恢复可达时只关闭它自己打开的熔断器，不会把真实流量判定的"不能用"洗白。
 
 use reqwest::header::HeaderValue;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Instant;
 
 use crate::app_config::AppType;
@@ -45,6 +49,9 @@ pub struct StreamCheckConfig {
     pub max_retries: u32,
     /// 降级阈值（毫秒）：可达但 TTFB 超过该值判定为"较慢"
     pub degraded_threshold_ms: u64,
+    /// 后台定时探测间隔（秒），按应用配置（key 为 app_type）；缺省或 0 表示不探测
+    #[serde(default)]
+    pub probe_interval_secs: BTreeMap<String, u64>,
 }
 
 impl Default for StreamCheckConfig {
@@ -56,6 +63,7 @@ impl Default for StreamCheckConfig {
             timeout_secs: 8,
             max_retries: 1,
             degraded_threshold_ms: 6000,
+            probe_interval_secs: BTreeMap::new(),
         }
     }
 }
@@ -378,6 +386,21 @@ impl StreamCheckService {
     }
 }
 
+/// Copilot 供应商的端点随 OAuth 账号动态解析，需由调用方预先取出 `base_url_override`
+pub(crate) fn is_copilot_provider(provider: &Provider) -> bool {
+    provider
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.provider_type.as_deref())
+        == Some("github_copilot")
+        || provider
+            .settings_config
+            .pointer("/env/ANTHROPIC_BASE_URL")
+            .and_then(|value| value.as_str())
+            .map(|url| url.contains("githubcopilot.com"))
+            .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,19 +1,31 @@
 //! Transport-agnostic sync protocol layer.
 //!
 //! Shared by WebDAV, S3, and future transports. Artifact set: `db.sql` + `skills.zip`.
+//!
+//! Protocol v3 adds optional end-to-end encryption: when a sync passphrase is
+//! configured, every artifact is sealed client-side with AES-256-GCM under an
+//! Argon2id-derived key before upload, and the manifest records the KDF
+//! parameters plus a key-check value. v2 (always plaintext) manifests remain
+//! readable.
 
 use std::collections::BTreeMap;
 use std::fs;
 use std::future::Future;
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::OnceLock;
 
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha2::{Digest, Sha256};
 use tempfile::tempdir;
 
+use crate::database::{SyncConflict, SyncMergeReport};
 use crate::error::AppError;
+use crate::secrets::KEY_LEN;
 use crate::services::skill::{skill_state_read_guard, skill_state_write_guard};
 
 // Re-export archive functions for use by transport layers.
@@ -26,7 +38,16 @@ pub(crate) use super::webdav_sync::archive::{
 /// Wire-format identifier stored in remote manifests.
 /// Retains historic "webdav" naming for backward compatibility with existing remotes.
 pub(crate) const PROTOCOL_FORMAT: &str = "cc-switch-webdav-sync";
-pub(crate) const PROTOCOL_VERSION: u32 = 2;
+/// v3: optional end-to-end artifact encryption (`SyncManifest::encryption`).
+pub(crate) const PROTOCOL_VERSION: u32 = 3;
+/// Oldest manifest version this client still reads (v2 snapshots are plaintext).
+pub(crate) const MIN_READABLE_PROTOCOL_VERSION: u32 = 2;
+/// Version segment of the remote directory layout (`{root}/v2/db-v6/{profile}`).
+///
+/// Deliberately frozen at 2 across the v3 bump: existing plaintext snapshots stay
+/// where they are, and older clients that find a v3 manifest reject it on the
+/// version check instead of importing ciphertext.
+pub(crate) const REMOTE_LAYOUT_VERSION: u32 = 2;
 pub(crate) const DB_COMPAT_VERSION: u32 = 6;
 pub(crate) const LEGACY_DB_COMPAT_VERSION: u32 = 5;
 pub(crate) const REMOTE_DB_SQL: &str = "db.sql";
@@ -35,6 +56,10 @@ pub(crate) const REMOTE_MANIFEST: &str = "manifest.json";
 pub(crate) const MAX_DEVICE_NAME_LEN: usize = 64;
 pub(crate) const MAX_MANIFEST_BYTES: usize = 1024 * 1024;
 pub(crate) const MAX_SYNC_ARTIFACT_BYTES: u64 = 512 * 1024 * 1024;
+pub(crate) const ENCRYPTION_KDF: &str = "argon2id";
+pub(crate) const ENCRYPTION_CIPHER: &str = "aes-256-gcm";
+/// Known plaintext sealed into `ManifestEncryption::key_check`.
+const KEY_CHECK_PLAINTEXT: &[u8] = b"cc-switch-sync-key-check";
 
 // ─── Sync operation lock ────────────────────────────────────
 
@@ -56,25 +81,27 @@ where
     operation.await
 }
 
-/// Tables whose changes make the remote configuration snapshot stale.
+/// Configuration tables carried by the snapshot and merged row by row on download.
 ///
 /// Keep this transport-agnostic so WebDAV and S3 cannot silently drift apart.
 /// `model_pricing` is intentionally excluded while its local JSON sidecar is
 /// the user-owned SSOT.
+pub(crate) const SYNC_CONFIG_TABLES: &[&str] = &[
+    "providers",
+    "provider_endpoints",
+    "mcp_servers",
+    "prompts",
+    "skills",
+    "skill_repos",
+    "profiles",
+    "settings",
+    "proxy_config",
+];
+
+/// Tables whose changes make the remote configuration snapshot stale.
 pub(crate) fn should_trigger_auto_sync_for_table(table: &str) -> bool {
     let normalized = table.trim().to_ascii_lowercase();
-    matches!(
-        normalized.as_str(),
-        "providers"
-            | "provider_endpoints"
-            | "mcp_servers"
-            | "prompts"
-            | "skills"
-            | "skill_repos"
-            | "profiles"
-            | "settings"
-            | "proxy_config"
-    )
+    SYNC_CONFIG_TABLES.contains(&normalized.as_str())
 }
 
 // ─── Error helpers ───────────────────────────────────────────
@@ -114,6 +141,25 @@ pub(crate) struct SyncManifest {
     pub created_at: String,
     pub artifacts: BTreeMap<String, ArtifactMeta>,
     pub snapshot_id: String,
+    /// Present when the artifacts are end-to-end encrypted (v3+).
+    /// Artifact hashes and sizes then describe the uploaded ciphertext.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<ManifestEncryption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ManifestEncryption {
+    pub kdf: String,
+    pub cipher: String,
+    /// Base64 KDF salt, regenerated for every upload.
+    pub salt: String,
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+    /// Base64 `nonce || AEAD(key, KEY_CHECK_PLAINTEXT)`; lets a client reject a
+    /// wrong passphrase before downloading any artifact.
+    pub key_check: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -124,6 +170,8 @@ pub(crate) struct ArtifactMeta {
 
 pub(crate) struct LocalSnapshot {
     pub db_sql: Vec<u8>,
+    /// Plaintext `db.sql`, kept as the merge base once the upload succeeds.
+    pub plain_db_sql: Vec<u8>,
     pub skills_zip: Vec<u8>,
     pub manifest_bytes: Vec<u8>,
     pub manifest_hash: String,
@@ -146,16 +194,25 @@ impl RemoteLayout {
 
 // ─── Snapshot building ───────────────────────────────────────
 
+/// Export the local snapshot; with a passphrase, artifacts are sealed before
+/// their hashes are recorded so the manifest describes the uploaded bytes.
 pub(crate) fn build_local_snapshot(
     db: &crate::database::Database,
+    passphrase: Option<&str>,
 ) -> Result<LocalSnapshot, AppError> {
     // Keep the DB's skill rows and the filesystem SSOT at one logical point in
     // time. Skill writers take the matching write guard around both mutations.
     let _skill_state_guard = skill_state_read_guard();
 
-    // Export database to SQL string
-    let sql_string = db.export_sql_string_for_sync()?;
+    // Export database to SQL string. A sealed snapshot carries provider secrets
+    // in plaintext so devices with a different master key can import them; an
+    // unsealed one only ever contains this device's ciphertext.
+    let sql_string = match passphrase {
+        Some(_) => db.export_sql_string_for_sealed_sync()?,
+        None => db.export_sql_string_for_sync()?,
+    };
     let db_sql = sql_string.into_bytes();
+    let plain_db_sql = db_sql.clone();
 
     // Pack skills into deterministic ZIP
     let tmp = tempdir().map_err(|e| {
@@ -170,6 +227,18 @@ pub(crate) fn build_local_snapshot(
     zip_skills_ssot(&skills_zip_path)?;
     let skills_zip = fs::read(&skills_zip_path).map_err(|e| AppError::io(&skills_zip_path, e))?;
 
+    let (db_sql, skills_zip, encryption) = match passphrase {
+        Some(passphrase) => {
+            let (encryption, key) = SnapshotKey::generate(passphrase)?;
+            (
+                key.seal_artifact(&db_sql)?,
+                key.seal_artifact(&skills_zip)?,
+                Some(encryption),
+            )
+        }
+        None => (db_sql, skills_zip, None),
+    };
+
     // Build artifact map and compute hashes
     let mut artifacts = BTreeMap::new();
     artifacts.insert(
@@ -196,6 +265,7 @@ pub(crate) fn build_local_snapshot(
         created_at: Utc::now().to_rfc3339(),
         artifacts,
         snapshot_id,
+        encryption,
     };
     let manifest_bytes =
         serde_json::to_vec_pretty(&manifest).map_err(|e| AppError::JsonSerialize { source: e })?;
@@ -203,6 +273,7 @@ pub(crate) fn build_local_snapshot(
 
     Ok(LocalSnapshot {
         db_sql,
+        plain_db_sql,
         skills_zip,
         manifest_bytes,
         manifest_hash,
@@ -245,7 +316,7 @@ pub(crate) fn validate_manifest_compat(
             ),
         ));
     }
-    if manifest.version != PROTOCOL_VERSION {
+    if !(MIN_READABLE_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&manifest.version) {
         return Err(localized(
             "sync.manifest_version_incompatible",
             format!(
@@ -258,6 +329,24 @@ pub(crate) fn validate_manifest_compat(
             ),
         ));
     }
+    if let Some(encryption) = &manifest.encryption {
+        if manifest.version < 3
+            || encryption.kdf != ENCRYPTION_KDF
+            || encryption.cipher != ENCRYPTION_CIPHER
+        {
+            return Err(localized(
+                "sync.manifest_encryption_unsupported",
+                format!(
+                    "远端快照使用了不支持的加密方式: {}/{}",
+                    encryption.kdf, encryption.cipher
+                ),
+                format!(
+                    "Remote snapshot uses an unsupported encryption scheme: {}/{}",
+                    encryption.kdf, encryption.cipher
+                ),
+            ));
+        }
+    }
     let Some(db_compat_version) = effective_db_compat_version(manifest, layout) else {
         return Err(localized(
             "sync.manifest_db_version_missing",
@@ -352,42 +441,247 @@ pub(crate) fn verify_artifact(
     Ok(())
 }
 
+// ─── Snapshot encryption ─────────────────────────────────────
+
+/// Artifact key derived from the sync passphrase for one snapshot.
+pub(crate) struct SnapshotKey([u8; KEY_LEN]);
+
+impl SnapshotKey {
+    /// Derive a key under a fresh salt for a new upload.
+    fn generate(passphrase: &str) -> Result<(ManifestEncryption, Self), AppError> {
+        let defaults = argon2::Params::default();
+        let (m_cost, t_cost, p_cost) = (defaults.m_cost(), defaults.t_cost(), defaults.p_cost());
+        let salt = crate::secrets::random_bytes::<{ crate::secrets::SALT_LEN }>();
+        let key = Self(crate::secrets::derive_passphrase_key(
+            passphrase, &salt, m_cost, t_cost, p_cost,
+        )?);
+        let encryption = ManifestEncryption {
+            kdf: ENCRYPTION_KDF.to_string(),
+            cipher: ENCRYPTION_CIPHER.to_string(),
+            salt: BASE64.encode(salt),
+            m_cost,
+            t_cost,
+            p_cost,
+            key_check: BASE64.encode(crate::secrets::seal(&key.0, KEY_CHECK_PLAINTEXT)?),
+        };
+        Ok((encryption, key))
+    }
+
+    fn seal_artifact(&self, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        crate::secrets::seal(&self.0, plaintext)
+    }
+
+    fn open_artifact(&self, artifact_name: &str, sealed: &[u8]) -> Result<Vec<u8>, AppError> {
+        crate::secrets::open(&self.0, sealed).map_err(|_| {
+            localized(
+                "sync.artifact_decrypt_failed",
+                format!("artifact {artifact_name} 解密失败"),
+                format!("Failed to decrypt artifact {artifact_name}"),
+            )
+        })
+    }
+}
+
+/// Resolve the artifact key for a remote manifest.
+///
+/// Returns `None` for plaintext snapshots (including every v2 manifest). An
+/// encrypted snapshot requires the passphrase, which is checked against the
+/// manifest's key-check value before any artifact is fetched.
+pub(crate) fn unlock_snapshot(
+    manifest: &SyncManifest,
+    passphrase: Option<&str>,
+) -> Result<Option<SnapshotKey>, AppError> {
+    let Some(encryption) = &manifest.encryption else {
+        if passphrase.is_some() {
+            log::warn!("[Sync] Remote snapshot is not encrypted although a sync passphrase is set");
+        }
+        return Ok(None);
+    };
+    let Some(passphrase) = passphrase else {
+        return Err(localized(
+            "sync.passphrase_required",
+            "远端快照已端到端加密，请先配置同步加密口令",
+            "The remote snapshot is end-to-end encrypted. Configure the sync passphrase first.",
+        ));
+    };
+
+    let malformed = || {
+        localized(
+            "sync.manifest_encryption_invalid",
+            "远端 manifest 的加密参数无效",
+            "Remote manifest has invalid encryption parameters.",
+        )
+    };
+    let salt = BASE64.decode(&encryption.salt).map_err(|_| malformed())?;
+    let key_check = BASE64
+        .decode(&encryption.key_check)
+        .map_err(|_| malformed())?;
+    let key = SnapshotKey(crate::secrets::derive_passphrase_key(
+        passphrase,
+        &salt,
+        encryption.m_cost,
+        encryption.t_cost,
+        encryption.p_cost,
+    )?);
+
+    match crate::secrets::open(&key.0, &key_check) {
+        Ok(plain) if plain == KEY_CHECK_PLAINTEXT => Ok(Some(key)),
+        _ => Err(localized(
+            "sync.passphrase_mismatch",
+            "同步加密口令不正确，无法解密远端快照",
+            "Incorrect sync passphrase; the remote snapshot cannot be decrypted.",
+        )),
+    }
+}
+
+/// Decrypt a verified artifact; plaintext snapshots pass through unchanged.
+pub(crate) fn open_artifact(
+    key: Option<&SnapshotKey>,
+    artifact_name: &str,
+    bytes: Vec<u8>,
+) -> Result<Vec<u8>, AppError> {
+    match key {
+        Some(key) => key.open_artifact(artifact_name, &bytes),
+        None => Ok(bytes),
+    }
+}
+
 // ─── Snapshot application ────────────────────────────────────
 
+/// Apply a downloaded snapshot.
+///
+/// With a merge base (the `db.sql` both sides last agreed on) the configuration
+/// tables are merged row by row and the report is returned; without one (first
+/// sync against this remote) the snapshot replaces local data wholesale.
 pub(crate) fn apply_snapshot(
     db: &crate::database::Database,
     db_sql: &[u8],
     skills_zip: &[u8],
-) -> Result<(), AppError> {
-    let sql_str = std::str::from_utf8(db_sql).map_err(|e| {
+    base_sql: Option<&[u8]>,
+) -> Result<Option<SyncMergeReport>, AppError> {
+    let sql_str = sql_utf8(db_sql)?;
+    let base_str = base_sql.map(sql_utf8).transpose()?;
+    // Exclude installs, uninstalls, updates, and local projection while Skills
+    // are backed up/replaced and the corresponding database snapshot is applied.
+    let _skill_state_guard = skill_state_write_guard();
+    let skills_backup = backup_current_skills()?;
+
+    let result = match base_str {
+        Some(base_str) => db
+            .import_sql_string_for_sync_merge(sql_str, base_str, |report| {
+                restore_skills_zip(skills_zip, &report.local_skill_dirs)
+            })
+            .map(Some),
+        None => {
+            // Replace skills first, then import database; roll back skills on DB failure.
+            restore_skills_zip(skills_zip, &[])?;
+            db.import_sql_string_for_sync(sql_str).map(|_| None)
+        }
+    };
+
+    result.map_err(|db_err| match restore_skills_from_backup(&skills_backup) {
+        Ok(()) => db_err,
+        Err(rollback_err) => localized(
+            "sync.db_import_and_rollback_failed",
+            format!("导入数据库失败: {db_err}; 同时回滚 Skills 失败: {rollback_err}"),
+            format!(
+                "Database import failed: {db_err}; skills rollback also failed: {rollback_err}"
+            ),
+        ),
+    })
+}
+
+fn sql_utf8(bytes: &[u8]) -> Result<&str, AppError> {
+    std::str::from_utf8(bytes).map_err(|e| {
         localized(
             "sync.sql_not_utf8",
             format!("SQL 非 UTF-8: {e}"),
             format!("SQL is not valid UTF-8: {e}"),
         )
-    })?;
-    // Exclude installs, uninstalls, updates, and local projection while Skills
-    // are backed up/replaced and the corresponding database snapshot is applied.
-    let _skill_state_guard = skill_state_write_guard();
-    let skills_backup = backup_current_skills()?;
+    })
+}
 
-    // Replace skills first, then import database; roll back skills on DB failure.
-    restore_skills_zip(skills_zip)?;
+// ─── Merge base & conflicts ──────────────────────────────────
 
-    if let Err(db_err) = db.import_sql_string_for_sync(sql_str) {
-        if let Err(rollback_err) = restore_skills_from_backup(&skills_backup) {
-            return Err(localized(
-                "sync.db_import_and_rollback_failed",
-                format!("导入数据库失败: {db_err}; 同时回滚 Skills 失败: {rollback_err}"),
-                format!(
-                    "Database import failed: {db_err}; skills rollback also failed: {rollback_err}"
-                ),
-            ));
+fn sync_state_dir() -> PathBuf {
+    crate::config::get_app_config_dir().join("sync")
+}
+
+/// Merge base for one remote, keyed by a hash of its location so that pointing
+/// a transport at a different server never merges against an unrelated base.
+fn sync_base_path(remote_identity: &str) -> PathBuf {
+    let digest = sha256_hex(remote_identity.as_bytes());
+    sync_state_dir().join(format!("base-{}.sql", &digest[..16]))
+}
+
+pub(crate) fn load_sync_base(remote_identity: &str) -> Option<Vec<u8>> {
+    fs::read(sync_base_path(remote_identity)).ok()
+}
+
+/// Record the snapshot both sides now share. Best-effort: a missing base only
+/// downgrades the next download to a full replace.
+pub(crate) fn store_sync_base(remote_identity: &str, db_sql: &[u8]) {
+    let path = sync_base_path(remote_identity);
+    if let Err(e) = crate::config::atomic_write_private(&path, db_sql) {
+        log::warn!("[Sync] Failed to store merge base {}: {e}", path.display());
+    }
+}
+
+fn sync_conflicts_path() -> PathBuf {
+    sync_state_dir().join("conflicts.json")
+}
+
+/// Conflicts left by the most recent merge download.
+pub(crate) fn load_sync_conflicts() -> Vec<SyncConflict> {
+    fs::read(sync_conflicts_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save_sync_conflicts(conflicts: &[SyncConflict]) -> Result<(), AppError> {
+    let path = sync_conflicts_path();
+    if conflicts.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| AppError::io(&path, e))?;
         }
-        return Err(db_err);
+        return Ok(());
     }
+    let bytes =
+        serde_json::to_vec_pretty(conflicts).map_err(|e| AppError::JsonSerialize { source: e })?;
+    crate::config::atomic_write_private(&path, &bytes)
+}
 
-    Ok(())
+/// Settle one recorded conflict: keep the local row, or overwrite it with the
+/// remote version. The change then reaches the remote with the next upload.
+pub(crate) fn resolve_sync_conflict(
+    db: &crate::database::Database,
+    conflict_id: &str,
+    take_remote: bool,
+) -> Result<(), AppError> {
+    let mut conflicts = load_sync_conflicts();
+    let Some(index) = conflicts.iter().position(|c| c.id == conflict_id) else {
+        return Err(localized(
+            "sync.conflict_not_found",
+            format!("同步冲突不存在: {conflict_id}"),
+            format!("Sync conflict not found: {conflict_id}"),
+        ));
+    };
+    db.resolve_sync_conflict(&conflicts[index], take_remote)?;
+    conflicts.remove(index);
+    save_sync_conflicts(&conflicts)
+}
+
+/// Persist the outcome of a download and describe it for the frontend.
+pub(crate) fn record_merge_outcome(report: Option<&SyncMergeReport>) -> Value {
+    let conflicts = report.map(|r| r.conflicts.as_slice()).unwrap_or_default();
+    if let Err(e) = save_sync_conflicts(conflicts) {
+        log::warn!("[Sync] Failed to persist sync conflicts: {e}");
+    }
+    match report {
+        Some(report) => serde_json::to_value(report).unwrap_or(Value::Null),
+        None => Value::Null,
+    }
 }
 
 // ─── Utilities ───────────────────────────────────────────────
@@ -592,9 +886,18 @@ mod tests {
             created_at: "2026-02-12T00:00:00Z".to_string(),
             artifacts,
             snapshot_id: "snap-1".to_string(),
+            encryption: None,
         }
     }
 
+    fn encrypted_manifest(passphrase: &str) -> (SyncManifest, SnapshotKey) {
+        let (encryption, key) = SnapshotKey::generate(passphrase).expect("derive key");
+        let mut manifest =
+            manifest_with(PROTOCOL_FORMAT, PROTOCOL_VERSION, Some(DB_COMPAT_VERSION));
+        manifest.encryption = Some(encryption);
+        (manifest, key)
+    }
+
     #[test]
     fn validate_manifest_compat_accepts_supported_manifest() {
         let manifest = manifest_with(PROTOCOL_FORMAT, PROTOCOL_VERSION, Some(DB_COMPAT_VERSION));
@@ -617,6 +920,72 @@ mod tests {
         assert!(validate_manifest_compat(&manifest, RemoteLayout::Current).is_err());
     }
 
+    #[test]
+    fn validate_manifest_compat_accepts_v2_plaintext_manifest() {
+        let manifest = manifest_with(
+            PROTOCOL_FORMAT,
+            MIN_READABLE_PROTOCOL_VERSION,
+            Some(DB_COMPAT_VERSION),
+        );
+        assert!(validate_manifest_compat(&manifest, RemoteLayout::Current).is_ok());
+        assert!(unlock_snapshot(&manifest, Some("ignored"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn validate_manifest_compat_rejects_unknown_encryption_scheme() {
+        let (mut manifest, _) = encrypted_manifest("pass");
+        assert!(validate_manifest_compat(&manifest, RemoteLayout::Current).is_ok());
+
+        manifest.encryption.as_mut().unwrap().kdf = "scrypt".to_string();
+        assert!(validate_manifest_compat(&manifest, RemoteLayout::Current).is_err());
+
+        let (mut manifest, _) = encrypted_manifest("pass");
+        manifest.version = MIN_READABLE_PROTOCOL_VERSION;
+        assert!(validate_manifest_compat(&manifest, RemoteLayout::Current).is_err());
+    }
+
+    #[test]
+    fn encrypted_artifacts_round_trip_with_passphrase() {
+        let (manifest, key) = encrypted_manifest("correct horse");
+        let sealed = key
+            .seal_artifact(b"INSERT INTO providers VALUES (1);")
+            .unwrap();
+        assert!(!sealed
+            .windows(b"providers".len())
+            .any(|w| w == b"providers"));
+
+        let value = serde_json::to_value(&manifest).unwrap();
+        let encryption = value
+            .get("encryption")
+            .expect("manifest records encryption");
+        for field in ["kdf", "salt", "mCost", "tCost", "pCost", "keyCheck"] {
+            assert!(encryption.get(field).is_some(), "missing {field}");
+        }
+
+        let unlocked = unlock_snapshot(&manifest, Some("correct horse"))
+            .unwrap()
+            .expect("encrypted snapshot yields a key");
+        let plain = open_artifact(Some(&unlocked), REMOTE_DB_SQL, sealed).unwrap();
+        assert_eq!(plain, b"INSERT INTO providers VALUES (1);");
+    }
+
+    #[test]
+    fn encrypted_snapshot_rejects_missing_or_wrong_passphrase() {
+        let (manifest, _) = encrypted_manifest("correct horse");
+
+        let err = unlock_snapshot(&manifest, None)
+            .err()
+            .expect("passphrase required");
+        assert!(err.to_string().contains("passphrase") || err.to_string().contains("口令"));
+
+        let err = unlock_snapshot(&manifest, Some("battery staple"))
+            .err()
+            .expect("wrong passphrase rejected");
+        assert!(err.to_string().contains("Incorrect") || err.to_string().contains("不正确"));
+    }
+
     #[test]
     fn validate_manifest_compat_accepts_legacy_manifest_without_db_compat() {
         let manifest = manifest_with(PROTOCOL_FORMAT, PROTOCOL_VERSION, None);
@@ -0,0 +1,502 @@
+//! 用量数据导出服务
+//!
+//! 把 `usage_daily_rollups`（明细清理后保留的按日汇总）与 `proxy_request_logs`
+//! 按时间范围和 [`LogFilters`] 导出为 CSV、JSON Lines 或 Parquet，供按项目/团队分摊费用。
+//!
+//! 两类记录共用同一组列，以 `record_type`（`daily_rollup` / `request`）区分；
+//! 聚合行没有的列（如单次请求的成本拆分、状态码）留空。派生列：
+//! - `effective_model`：实际计价模型，`pricing_model` 为空时回落 `model`（与统计页同口径）
+//! - `fresh_input_tokens`：不含缓存读写的输入 token（跨供应商统一口径）
+//! - `data_source`：`proxy` 或会话日志来源；聚合行为 `rollup`
+//!
+//! 行在读取数据库时逐行写出，不在内存中堆积整表；Parquet 按行组缓冲。
+//! 先写入同目录的临时文件，完成后再重命名，失败不会留下半截文件。
+
+mod parquet;
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::usage_stats::{LogFilters, RequestLogDetail, UsageRollupRow};
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageExportFormat {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageExportSummary {
+    pub file_path: String,
+    pub format: UsageExportFormat,
+    pub rollup_rows: u64,
+    pub request_rows: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Text,
+    Int,
+    Bool,
+}
+
+/// 导出列（顺序即文件中的列顺序）
+const COLUMNS: &[(&str, ColumnType)] = &[
+    ("record_type", ColumnType::Text),
+    ("request_id", ColumnType::Text),
+    ("created_at", ColumnType::Int),
+    ("date", ColumnType::Text),
+    ("app_type", ColumnType::Text),
+    ("provider_id", ColumnType::Text),
+    ("provider_name", ColumnType::Text),
+    ("session_id", ColumnType::Text),
+    ("data_source", ColumnType::Text),
+    ("model", ColumnType::Text),
+    ("request_model", ColumnType::Text),
+    ("pricing_model", ColumnType::Text),
+    ("effective_model", ColumnType::Text),
+    ("request_count", ColumnType::Int),
+    ("success_count", ColumnType::Int),
+    ("input_tokens", ColumnType::Int),
+    ("fresh_input_tokens", ColumnType::Int),
+    ("output_tokens", ColumnType::Int),
+    ("cache_read_tokens", ColumnType::Int),
+    ("cache_creation_tokens", ColumnType::Int),
+    ("cost_multiplier", ColumnType::Text),
+    ("input_cost_usd", ColumnType::Text),
+    ("output_cost_usd", ColumnType::Text),
+    ("cache_read_cost_usd", ColumnType::Text),
+    ("cache_creation_cost_usd", ColumnType::Text),
+    ("total_cost_usd", ColumnType::Text),
+    ("status_code", ColumnType::Int),
+    ("is_streaming", ColumnType::Bool),
+    ("latency_ms", ColumnType::Int),
+    ("first_token_ms", ColumnType::Int),
+    ("duration_ms", ColumnType::Int),
+    ("error_message", ColumnType::Text),
+];
+
+/// 单元格；成本保持数据库中的十进制字符串，避免浮点误差
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Null,
+    Text(String),
+    Int(i64),
+    Bool(bool),
+}
+
+fn text(value: impl Into<String>) -> Cell {
+    Cell::Text(value.into())
+}
+
+fn opt_text(value: Option<String>) -> Cell {
+    value.map_or(Cell::Null, Cell::Text)
+}
+
+fn int(value: u64) -> Cell {
+    Cell::Int(value.min(i64::MAX as u64) as i64)
+}
+
+fn opt_int(value: Option<u64>) -> Cell {
+    value.map_or(Cell::Null, int)
+}
+
+fn effective_model(pricing_model: Option<&str>, model: &str) -> String {
+    pricing_model
+        .filter(|m| !m.is_empty())
+        .unwrap_or(model)
+        .to_string()
+}
+
+fn local_date(timestamp: i64) -> Cell {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map_or(Cell::Null, |dt| text(dt.format("%Y-%m-%d").to_string()))
+}
+
+fn request_cells(log: RequestLogDetail, session_id: Option<String>, fresh_input: u64) -> Vec<Cell> {
+    let success = (200..300).contains(&log.status_code);
+    let effective = effective_model(log.pricing_model.as_deref(), &log.model);
+    vec![
+        text("request"),
+        text(log.request_id),
+        Cell::Int(log.created_at),
+        local_date(log.created_at),
+        text(log.app_type),
+        text(log.provider_id),
+        opt_text(log.provider_name),
+        opt_text(session_id),
+        text(log.data_source.unwrap_or_else(|| "proxy".to_string())),
+        text(log.model),
+        opt_text(log.request_model),
+        opt_text(log.pricing_model),
+        text(effective),
+        Cell::Int(1),
+        Cell::Int(i64::from(success)),
+        int(u64::from(log.input_tokens)),
+        int(fresh_input),
+        int(u64::from(log.output_tokens)),
+        int(u64::from(log.cache_read_tokens)),
+        int(u64::from(log.cache_creation_tokens)),
+        text(log.cost_multiplier),
+        text(log.input_cost_usd),
+        text(log.output_cost_usd),
+        text(log.cache_read_cost_usd),
+        text(log.cache_creation_cost_usd),
+        text(log.total_cost_usd),
+        Cell::Int(i64::from(log.status_code)),
+        Cell::Bool(log.is_streaming),
+        int(log.latency_ms),
+        opt_int(log.first_token_ms),
+        opt_int(log.duration_ms),
+        opt_text(log.error_message),
+    ]
+}
+
+fn rollup_cells(row: UsageRollupRow) -> Vec<Cell> {
+    let effective = effective_model(Some(&row.pricing_model), &row.model);
+    let non_empty = |v: String| {
+        if v.is_empty() {
+            Cell::Null
+        } else {
+            Cell::Text(v)
+        }
+    };
+    vec![
+        text("daily_rollup"),
+        Cell::Null,
+        Cell::Null,
+        text(row.date),
+        text(row.app_type),
+        text(row.provider_id),
+        text(row.provider_name),
+        Cell::Null,
+        text("rollup"),
+        text(row.model),
+        non_empty(row.request_model),
+        non_empty(row.pricing_model),
+        text(effective),
+        int(row.request_count),
+        int(row.success_count),
+        int(row.input_tokens),
+        int(row.fresh_input_tokens),
+        int(row.output_tokens),
+        int(row.cache_read_tokens),
+        int(row.cache_creation_tokens),
+        Cell::Null,
+        Cell::Null,
+        Cell::Null,
+        Cell::Null,
+        Cell::Null,
+        text(row.total_cost_usd),
+        Cell::Null,
+        Cell::Null,
+        int(row.avg_latency_ms),
+        Cell::Null,
+        Cell::Null,
+        Cell::Null,
+    ]
+}
+
+enum RowWriter<W: Write> {
+    Csv(W),
+    Jsonl(W),
+    Parquet(parquet::ParquetWriter<W>),
+}
+
+impl<W: Write> RowWriter<W> {
+    fn new(format: UsageExportFormat, mut out: W) -> Result<Self, std::io::Error> {
+        Ok(match format {
+            UsageExportFormat::Csv => {
+                let header: Vec<&str> = COLUMNS.iter().map(|(name, _)| *name).collect();
+                writeln!(out, "{}", header.join(","))?;
+                Self::Csv(out)
+            }
+            UsageExportFormat::Jsonl => Self::Jsonl(out),
+            UsageExportFormat::Parquet => Self::Parquet(parquet::ParquetWriter::new(out, COLUMNS)?),
+        })
+    }
+
+    fn write_row(&mut self, cells: &[Cell]) -> Result<(), std::io::Error> {
+        debug_assert_eq!(cells.len(), COLUMNS.len());
+        match self {
+            Self::Csv(out) => {
+                let fields: Vec<String> = cells.iter().map(csv_field).collect();
+                writeln!(out, "{}", fields.join(","))
+            }
+            Self::Jsonl(out) => {
+                let object: Map<String, Value> = COLUMNS
+                    .iter()
+                    .zip(cells)
+                    .map(|((name, _), cell)| (name.to_string(), json_value(cell)))
+                    .collect();
+                serde_json::to_writer(&mut *out, &object)?;
+                out.write_all(b"\n")
+            }
+            Self::Parquet(writer) => writer.write_row(cells),
+        }
+    }
+
+    fn finish(self) -> Result<W, std::io::Error> {
+        match self {
+            Self::Csv(out) | Self::Jsonl(out) => Ok(out),
+            Self::Parquet(writer) => writer.finish(),
+        }
+    }
+}
+
+/// RFC 4180：含逗号、引号或换行的字段加引号，内部引号双写
+fn csv_field(cell: &Cell) -> String {
+    match cell {
+        Cell::Null => String::new(),
+        Cell::Int(v) => v.to_string(),
+        Cell::Bool(v) => v.to_string(),
+        Cell::Text(v) if v.contains([',', '"', '\n', '\r']) => {
+            format!("\"{}\"", v.replace('"', "\"\""))
+        }
+        Cell::Text(v) => v.clone(),
+    }
+}
+
+fn json_value(cell: &Cell) -> Value {
+    match cell {
+        Cell::Null => Value::Null,
+        Cell::Text(v) => Value::String(v.clone()),
+        Cell::Int(v) => Value::from(*v),
+        Cell::Bool(v) => Value::Bool(*v),
+    }
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+/// 导出用量数据到 `path`：先写日聚合，再写请求明细，均按时间正序
+pub fn export_usage(
+    db: &Database,
+    filters: &LogFilters,
+    format: UsageExportFormat,
+    path: &Path,
+) -> Result<UsageExportSummary, AppError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let temp_path = partial_path(path);
+    let result = write_export(db, filters, format, &temp_path);
+    match result {
+        Ok((rollup_rows, request_rows)) => {
+            fs::rename(&temp_path, path).map_err(|e| AppError::io(path, e))?;
+            log::info!(
+                "用量导出完成: {} ({rollup_rows} 条日聚合, {request_rows} 条请求)",
+                path.display()
+            );
+            Ok(UsageExportSummary {
+                file_path: path.to_string_lossy().into_owned(),
+                format,
+                rollup_rows,
+                request_rows,
+            })
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
+fn write_export(
+    db: &Database,
+    filters: &LogFilters,
+    format: UsageExportFormat,
+    temp_path: &Path,
+) -> Result<(u64, u64), AppError> {
+    let io_err = |e: std::io::Error| AppError::io(temp_path, e);
+    let file = File::create(temp_path).map_err(io_err)?;
+    let mut writer = RowWriter::new(format, BufWriter::new(file)).map_err(io_err)?;
+
+    let rollup_rows = db.for_each_usage_rollup(filters, |row| {
+        writer.write_row(&rollup_cells(row)).map_err(io_err)
+    })?;
+    let request_rows = db.for_each_request_log(filters, |log, session_id, fresh_input| {
+        writer
+            .write_row(&request_cells(log, session_id, fresh_input))
+            .map_err(io_err)
+    })?;
+
+    let mut out = writer.finish().map_err(io_err)?;
+    out.flush().map_err(io_err)?;
+    out.into_inner()
+        .map_err(|e| io_err(e.into_error()))?
+        .sync_all()
+        .map_err(io_err)?;
+    Ok((rollup_rows, request_rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_log(
+        db: &Database,
+        request_id: &str,
+        created_at: i64,
+        status: u16,
+        error: Option<&str>,
+    ) -> Result<(), AppError> {
+        let conn = crate::database::lock_conn!(db.conn);
+        conn.execute(
+            "INSERT INTO proxy_request_logs (
+                request_id, provider_id, app_type, model, request_model, pricing_model,
+                input_tokens, output_tokens, cache_read_tokens, cache_creation_tokens,
+                input_cost_usd, output_cost_usd, cache_read_cost_usd, cache_creation_cost_usd,
+                total_cost_usd, latency_ms, status_code, error_message, session_id,
+                is_streaming, cost_multiplier, created_at
+            ) VALUES (?1, 'p1', 'claude', 'claude-sonnet-4-5', 'sonnet', 'claude-sonnet-4-5-20250929',
+                100, 20, 5, 0, '0.0003', '0.0003', '0.0000015', '0', '0.0006015',
+                1200, ?2, ?3, 'sess-1', 1, '1.0', ?4)",
+            rusqlite::params![request_id, status, error, created_at],
+        )?;
+        Ok(())
+    }
+
+    fn insert_rollup(db: &Database, date: &str) -> Result<(), AppError> {
+        let conn = crate::database::lock_conn!(db.conn);
+        conn.execute(
+            "INSERT INTO usage_daily_rollups (
+                date, app_type, provider_id, model, request_model, pricing_model,
+                request_count, success_count, input_tokens, output_tokens,
+                cache_read_tokens, cache_creation_tokens, input_token_semantics,
+                total_cost_usd, avg_latency_ms
+            ) VALUES (?1, 'claude', 'p1', 'claude-sonnet-4-5', '', '', 10, 9, 1000, 200, 0, 0, 2, '0.5', 900)",
+            [date],
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn csv_export_writes_rollups_then_requests_with_derived_columns() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        insert_rollup(&db, "2020-01-01")?;
+        insert_log(
+            &db,
+            "req-2",
+            2_000_000_000,
+            500,
+            Some("upstream said \"no\", retry"),
+        )?;
+        insert_log(&db, "req-1", 1_900_000_000, 200, None)?;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("usage.csv");
+        let summary = export_usage(&db, &LogFilters::default(), UsageExportFormat::Csv, &path)?;
+        assert_eq!((summary.rollup_rows, summary.request_rows), (1, 2));
+        assert!(!partial_path(&path).exists());
+
+        let content = fs::read_to_string(&path).expect("read export");
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("record_type,request_id,created_at,date,"));
+        assert!(lines[1].starts_with("daily_rollup,,,2020-01-01,claude,p1,"));
+        assert!(lines[1].contains(",rollup,claude-sonnet-4-5,,,claude-sonnet-4-5,10,9,"));
+        assert!(lines[2].starts_with("request,req-1,1900000000,"));
+        assert!(lines[2].contains(",sess-1,proxy,claude-sonnet-4-5,sonnet,"));
+        assert!(lines[2].contains(",claude-sonnet-4-5-20250929,1,1,100,"));
+        assert!(lines[3].ends_with(",\"upstream said \"\"no\"\", retry\""));
+        Ok(())
+    }
+
+    #[test]
+    fn request_logs_are_read_in_pages_without_holding_the_lock() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        let total = crate::services::usage_stats::EXPORT_PAGE_SIZE * 2 + 1;
+        // 同一秒内的多行靠 request_id 决定顺序，分页游标不能跳过或重复
+        for i in 0..total {
+            insert_log(
+                &db,
+                &format!("req-{i:05}"),
+                1_900_000_000 + (i / 7) as i64,
+                200,
+                None,
+            )?;
+        }
+
+        let mut seen = Vec::new();
+        let count = db.for_each_request_log(&LogFilters::default(), |log, _, _| {
+            assert!(db.conn.try_lock().is_ok(), "回调期间不应持有连接锁");
+            seen.push(log.request_id);
+            Ok(())
+        })?;
+
+        assert_eq!(count as usize, total);
+        let expected: Vec<String> = (0..total).map(|i| format!("req-{i:05}")).collect();
+        assert_eq!(seen, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn jsonl_export_applies_filters_and_skips_rollups_for_status_filter() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        insert_rollup(&db, "2020-01-01")?;
+        insert_log(&db, "req-ok", 1_900_000_000, 200, None)?;
+        insert_log(&db, "req-err", 1_900_000_100, 500, Some("boom"))?;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join("usage.jsonl");
+        let filters = LogFilters {
+            status_code: Some(200),
+            ..Default::default()
+        };
+        let summary = export_usage(&db, &filters, UsageExportFormat::Jsonl, &path)?;
+        assert_eq!((summary.rollup_rows, summary.request_rows), (0, 1));
+
+        let content = fs::read_to_string(&path).expect("read export");
+        let row: Value = serde_json::from_str(content.lines().next().unwrap()).expect("json");
+        assert_eq!(row["request_id"], "req-ok");
+        assert_eq!(row["is_streaming"], true);
+        assert_eq!(row["status_code"], 200);
+        assert_eq!(row["total_cost_usd"], "0.0006015");
+        assert_eq!(row["first_token_ms"], Value::Null);
+        assert_eq!(row.as_object().unwrap().len(), COLUMNS.len());
+        Ok(())
+    }
+
+    #[test]
+    fn parquet_export_is_framed_by_magic_and_footer() -> Result<(), AppError> {
+        let db = Database::memory()?;
+        insert_rollup(&db, "2020-01-01")?;
+        insert_log(&db, "req-1", 1_900_000_000, 200, None)?;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("usage.parquet");
+        export_usage(
+            &db,
+            &LogFilters::default(),
+            UsageExportFormat::Parquet,
+            &path,
+        )?;
+
+        let bytes = fs::read(&path).expect("read export");
+        assert_eq!(&bytes[..4], b"PAR1");
+        assert_eq!(&bytes[bytes.len() - 4..], b"PAR1");
+        let footer_len =
+            u32::from_le_bytes(bytes[bytes.len() - 8..bytes.len() - 4].try_into().unwrap())
+                as usize;
+        assert!(footer_len > 0 && footer_len + 12 < bytes.len());
+        Ok(())
+    }
+}
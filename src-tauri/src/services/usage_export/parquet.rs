@@ -0,0 +1,503 @@
+//! 最小 Parquet 写入器
+//!
+//! 只覆盖导出需要的子集：扁平 schema、全部 OPTIONAL 列、PLAIN 编码、不压缩，
+//! 每个行组每列一个 v1 数据页。元数据按 Thrift compact protocol 手工编码。
+//! 格式参考 apache/parquet-format 的 `parquet.thrift`。
+
+use std::io::{self, Write};
+
+use super::{Cell, ColumnType};
+
+const MAGIC: &[u8; 4] = b"PAR1";
+/// 每个行组缓冲的行数
+const ROW_GROUP_ROWS: usize = 16_384;
+
+// parquet.thrift 枚举值
+const TYPE_BOOLEAN: i32 = 0;
+const TYPE_INT64: i32 = 2;
+const TYPE_BYTE_ARRAY: i32 = 6;
+const REPETITION_OPTIONAL: i32 = 1;
+const CONVERTED_UTF8: i32 = 0;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_DATA: i32 = 0;
+
+fn physical_type(column: ColumnType) -> i32 {
+    match column {
+        ColumnType::Text => TYPE_BYTE_ARRAY,
+        ColumnType::Int => TYPE_INT64,
+        ColumnType::Bool => TYPE_BOOLEAN,
+    }
+}
+
+/// 一列在当前行组内的缓冲
+#[derive(Default)]
+struct ColumnBuffer {
+    /// 定义级别：true = 非空
+    defined: Vec<bool>,
+    /// 非空值的 PLAIN 编码（BOOLEAN 除外）
+    values: Vec<u8>,
+    /// BOOLEAN 非空值，写页时按位打包
+    bools: Vec<bool>,
+}
+
+struct ColumnChunkMeta {
+    column: usize,
+    data_page_offset: u64,
+    size: u64,
+    num_values: usize,
+}
+
+struct RowGroupMeta {
+    columns: Vec<ColumnChunkMeta>,
+    num_rows: usize,
+}
+
+pub(super) struct ParquetWriter<W: Write> {
+    out: W,
+    offset: u64,
+    columns: &'static [(&'static str, ColumnType)],
+    buffers: Vec<ColumnBuffer>,
+    buffered_rows: usize,
+    row_groups: Vec<RowGroupMeta>,
+}
+
+impl<W: Write> ParquetWriter<W> {
+    pub(super) fn new(
+        mut out: W,
+        columns: &'static [(&'static str, ColumnType)],
+    ) -> io::Result<Self> {
+        out.write_all(MAGIC)?;
+        Ok(Self {
+            out,
+            offset: MAGIC.len() as u64,
+            columns,
+            buffers: columns.iter().map(|_| ColumnBuffer::default()).collect(),
+            buffered_rows: 0,
+            row_groups: Vec::new(),
+        })
+    }
+
+    pub(super) fn write_row(&mut self, cells: &[Cell]) -> io::Result<()> {
+        for ((buffer, (name, column)), cell) in self.buffers.iter_mut().zip(self.columns).zip(cells)
+        {
+            match (column, cell) {
+                (_, Cell::Null) => {
+                    buffer.defined.push(false);
+                    continue;
+                }
+                (ColumnType::Text, Cell::Text(v)) => {
+                    buffer
+                        .values
+                        .extend_from_slice(&(v.len() as u32).to_le_bytes());
+                    buffer.values.extend_from_slice(v.as_bytes());
+                }
+                (ColumnType::Int, Cell::Int(v)) => {
+                    buffer.values.extend_from_slice(&v.to_le_bytes())
+                }
+                (ColumnType::Bool, Cell::Bool(v)) => buffer.bools.push(*v),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("column {name} type mismatch: {cell:?}"),
+                    ))
+                }
+            }
+            buffer.defined.push(true);
+        }
+        self.buffered_rows += 1;
+        if self.buffered_rows >= ROW_GROUP_ROWS {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    fn flush_row_group(&mut self) -> io::Result<()> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+        let mut chunks = Vec::with_capacity(self.columns.len());
+        for column in 0..self.columns.len() {
+            let buffer = std::mem::take(&mut self.buffers[column]);
+            let num_values = buffer.defined.len();
+
+            let levels = encode_definition_levels(&buffer.defined);
+            let mut page = Vec::with_capacity(4 + levels.len() + buffer.values.len());
+            page.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+            page.extend_from_slice(&levels);
+            if self.columns[column].1 == ColumnType::Bool {
+                page.extend_from_slice(&pack_bools(&buffer.bools));
+            } else {
+                page.extend_from_slice(&buffer.values);
+            }
+
+            let header = page_header(num_values, page.len());
+            let data_page_offset = self.offset;
+            self.out.write_all(&header)?;
+            self.out.write_all(&page)?;
+            let size = (header.len() + page.len()) as u64;
+            self.offset += size;
+            chunks.push(ColumnChunkMeta {
+                column,
+                data_page_offset,
+                size,
+                num_values,
+            });
+        }
+        self.row_groups.push(RowGroupMeta {
+            columns: chunks,
+            num_rows: self.buffered_rows,
+        });
+        self.buffered_rows = 0;
+        Ok(())
+    }
+
+    /// 写出剩余行组与文件尾（FileMetaData + 长度 + magic）
+    pub(super) fn finish(mut self) -> io::Result<W> {
+        self.flush_row_group()?;
+        let footer = self.file_metadata();
+        self.out.write_all(&footer)?;
+        self.out.write_all(&(footer.len() as u32).to_le_bytes())?;
+        self.out.write_all(MAGIC)?;
+        Ok(self.out)
+    }
+
+    fn file_metadata(&self) -> Vec<u8> {
+        let mut t = CompactWriter::default();
+        t.i32(1, 1);
+        // schema：根节点 + 每列一个叶子
+        t.list(2, CompactType::Struct, self.columns.len() + 1);
+        t.begin_element();
+        t.binary(4, b"schema");
+        t.i32(5, self.columns.len() as i32);
+        t.end_struct();
+        for (name, column) in self.columns {
+            t.begin_element();
+            t.i32(1, physical_type(*column));
+            t.i32(3, REPETITION_OPTIONAL);
+            t.binary(4, name.as_bytes());
+            if *column == ColumnType::Text {
+                t.i32(6, CONVERTED_UTF8);
+            }
+            t.end_struct();
+        }
+        let total_rows: usize = self.row_groups.iter().map(|g| g.num_rows).sum();
+        t.i64(3, total_rows as i64);
+        t.list(4, CompactType::Struct, self.row_groups.len());
+        for group in &self.row_groups {
+            t.begin_element();
+            t.list(1, CompactType::Struct, group.columns.len());
+            for chunk in &group.columns {
+                let (name, column) = self.columns[chunk.column];
+                t.begin_element();
+                t.i64(2, chunk.data_page_offset as i64);
+                t.begin_struct(3);
+                t.i32(1, physical_type(column));
+                t.list(2, CompactType::I32, 2);
+                t.element_i32(ENCODING_PLAIN);
+                t.element_i32(ENCODING_RLE);
+                t.list(3, CompactType::Binary, 1);
+                t.element_binary(name.as_bytes());
+                t.i32(4, CODEC_UNCOMPRESSED);
+                t.i64(5, chunk.num_values as i64);
+                t.i64(6, chunk.size as i64);
+                t.i64(7, chunk.size as i64);
+                t.i64(9, chunk.data_page_offset as i64);
+                t.end_struct();
+                t.end_struct();
+            }
+            let group_size: u64 = group.columns.iter().map(|c| c.size).sum();
+            t.i64(2, group_size as i64);
+            t.i64(3, group.num_rows as i64);
+            t.end_struct();
+        }
+        t.binary(
+            6,
+            format!("cc-switch version {}", env!("CARGO_PKG_VERSION")).as_bytes(),
+        );
+        t.finish()
+    }
+}
+
+fn page_header(num_values: usize, page_size: usize) -> Vec<u8> {
+    let mut t = CompactWriter::default();
+    t.i32(1, PAGE_DATA);
+    t.i32(2, page_size as i32);
+    t.i32(3, page_size as i32);
+    t.begin_struct(5);
+    t.i32(1, num_values as i32);
+    t.i32(2, ENCODING_PLAIN);
+    t.i32(3, ENCODING_RLE);
+    t.i32(4, ENCODING_RLE);
+    t.end_struct();
+    t.finish()
+}
+
+/// 定义级别（位宽 1）的 RLE/bit-packing 混合编码，只使用 RLE run
+fn encode_definition_levels(defined: &[bool]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < defined.len() {
+        let value = defined[i];
+        let run = defined[i..].iter().take_while(|v| **v == value).count();
+        write_uvarint(&mut out, (run as u64) << 1);
+        out.push(u8::from(value));
+        i += run;
+    }
+    out
+}
+
+/// BOOLEAN 的 PLAIN 编码：按位打包，低位在前
+fn pack_bools(values: &[bool]) -> Vec<u8> {
+    values
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (bit, v)| byte | (u8::from(*v) << bit))
+        })
+        .collect()
+}
+
+fn write_uvarint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum CompactType {
+    I32 = 5,
+    I64 = 6,
+    Binary = 8,
+    List = 9,
+    Struct = 12,
+}
+
+/// Thrift compact protocol 写入（只实现用到的类型）
+struct CompactWriter {
+    buf: Vec<u8>,
+    /// 每层结构体上一个字段 ID，用于字段头的增量编码
+    last_field: Vec<i16>,
+}
+
+impl Default for CompactWriter {
+    fn default() -> Self {
+        Self {
+            buf: Vec::new(),
+            last_field: vec![0],
+        }
+    }
+}
+
+impl CompactWriter {
+    fn field(&mut self, id: i16, ty: CompactType) {
+        let last = self.last_field.last_mut().expect("struct stack");
+        let delta = id - *last;
+        if (1..=15).contains(&delta) {
+            self.buf.push(((delta as u8) << 4) | ty as u8);
+        } else {
+            self.buf.push(ty as u8);
+            write_uvarint(&mut self.buf, zigzag(i64::from(id)));
+        }
+        *last = id;
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, CompactType::I32);
+        write_uvarint(&mut self.buf, zigzag(i64::from(value)));
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, CompactType::I64);
+        write_uvarint(&mut self.buf, zigzag(value));
+    }
+
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, CompactType::Binary);
+        self.element_binary(value);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.field(id, CompactType::Struct);
+        self.last_field.push(0);
+    }
+
+    fn end_struct(&mut self) {
+        self.buf.push(0);
+        self.last_field.pop();
+    }
+
+    fn list(&mut self, id: i16, element: CompactType, len: usize) {
+        self.field(id, CompactType::List);
+        if len < 15 {
+            self.buf.push(((len as u8) << 4) | element as u8);
+        } else {
+            self.buf.push(0xf0 | element as u8);
+            write_uvarint(&mut self.buf, len as u64);
+        }
+    }
+
+    /// 列表中的结构体元素没有字段头
+    fn begin_element(&mut self) {
+        self.last_field.push(0);
+    }
+
+    fn element_i32(&mut self, value: i32) {
+        write_uvarint(&mut self.buf, zigzag(i64::from(value)));
+    }
+
+    fn element_binary(&mut self, value: &[u8]) {
+        write_uvarint(&mut self.buf, value.len() as u64);
+        self.buf.extend_from_slice(value);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.buf.push(0);
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_protocol_encodes_field_deltas_and_lists() {
+        let mut t = CompactWriter::default();
+        t.i32(1, 1);
+        t.list(2, CompactType::I32, 2);
+        t.element_i32(0);
+        t.element_i32(3);
+        // 跳号超过 15 时使用长字段头
+        t.i64(20, -1);
+        t.begin_struct(21);
+        t.binary(4, b"ab");
+        t.end_struct();
+        assert_eq!(
+            t.finish(),
+            vec![
+                0x15, 0x02, // field 1 i32 = 1
+                0x19, 0x25, 0x00, 0x06, // field 2 list<i32> [0, 3]
+                0x06, 0x28, 0x01, // field 20 i64 = -1
+                0x1c, // field 21 struct
+                0x48, 0x02, b'a', b'b', 0x00, // field 4 binary "ab", stop
+                0x00, // stop
+            ]
+        );
+    }
+
+    #[test]
+    fn definition_levels_use_rle_runs_and_bools_pack_lsb_first() {
+        assert_eq!(
+            encode_definition_levels(&[true, true, true, false, true]),
+            vec![0x06, 0x01, 0x02, 0x00, 0x02, 0x01]
+        );
+        assert_eq!(
+            pack_bools(&[true, false, true, true, false, false, false, false, true]),
+            vec![0b0000_1101, 0b0000_0001]
+        );
+    }
+
+    /// 用 parquet crate 的标准读取器回读写入结果
+    mod reader {
+        use super::*;
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::Field;
+
+        const COLUMNS: &[(&str, ColumnType)] = &[
+            ("name", ColumnType::Text),
+            ("count", ColumnType::Int),
+            ("ok", ColumnType::Bool),
+        ];
+
+        fn row(i: usize) -> Vec<Cell> {
+            vec![
+                if i % 3 == 0 {
+                    Cell::Null
+                } else {
+                    Cell::Text(format!("行-{i}"))
+                },
+                if i % 5 == 0 {
+                    Cell::Null
+                } else {
+                    Cell::Int(i as i64 - 7)
+                },
+                if i % 2 == 0 {
+                    Cell::Null
+                } else {
+                    Cell::Bool(i % 4 == 1)
+                },
+            ]
+        }
+
+        fn write_and_open(rows: usize) -> SerializedFileReader<std::fs::File> {
+            let file = tempfile::tempfile().expect("tempfile");
+            let mut writer =
+                ParquetWriter::new(file.try_clone().expect("clone"), COLUMNS).expect("writer");
+            for i in 0..rows {
+                writer.write_row(&row(i)).expect("write row");
+            }
+            writer.finish().expect("finish");
+            SerializedFileReader::new(file).expect("standard reader accepts file")
+        }
+
+        #[test]
+        fn nulls_and_multiple_row_groups_round_trip() {
+            let rows = ROW_GROUP_ROWS + 3;
+            let reader = write_and_open(rows);
+
+            let metadata = reader.metadata();
+            assert_eq!(metadata.num_row_groups(), 2);
+            assert_eq!(metadata.row_group(0).num_rows(), ROW_GROUP_ROWS as i64);
+            assert_eq!(metadata.row_group(1).num_rows(), 3);
+            assert_eq!(metadata.file_metadata().num_rows(), rows as i64);
+            let names: Vec<_> = metadata
+                .file_metadata()
+                .schema_descr()
+                .columns()
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect();
+            assert_eq!(names, ["name", "count", "ok"]);
+
+            let mut read = 0;
+            for (i, record) in reader.get_row_iter(None).expect("rows").enumerate() {
+                let record = record.expect("row");
+                let fields: Vec<Field> = record
+                    .get_column_iter()
+                    .map(|(_, field)| field.clone())
+                    .collect();
+                let expected: Vec<Field> = row(i)
+                    .into_iter()
+                    .map(|cell| match cell {
+                        Cell::Null => Field::Null,
+                        Cell::Text(v) => Field::Str(v),
+                        Cell::Int(v) => Field::Long(v),
+                        Cell::Bool(v) => Field::Bool(v),
+                    })
+                    .collect();
+                assert_eq!(fields, expected, "row {i}");
+                read += 1;
+            }
+            assert_eq!(read, rows);
+        }
+
+        #[test]
+        fn empty_export_is_a_valid_file() {
+            let reader = write_and_open(0);
+            assert_eq!(reader.metadata().num_row_groups(), 0);
+            assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+            assert_eq!(reader.get_row_iter(None).expect("rows").count(), 0);
+        }
+    }
+}
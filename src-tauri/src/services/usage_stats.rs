@@ -4,6 +4,7 @@
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
+#[cfg(feature = "gui")]
 use crate::proxy::usage::calculator::ModelPricing;
 use crate::services::sql_helpers::{
     fresh_input_sql, INPUT_TOKEN_SEMANTICS_FRESH, INPUT_TOKEN_SEMANTICS_TOTAL,
@@ -13,6 +14,7 @@ use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
+#[cfg(feature = "gui")]
 use std::sync::LazyLock;
 
 /// 使用量汇总
@@ -99,6 +101,35 @@ pub struct ModelStats {
     pub avg_cost_per_request: String,
 }
 
+/// 项目统计（按会话所属项目目录归属）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    /// None 表示无法归属到项目的用量（代理请求未携带已知会话等）
+    pub project_dir: Option<String>,
+    pub request_count: u64,
+    pub total_tokens: u64,
+    pub total_cost: String,
+}
+
+/// 会话统计（仅覆盖明细保留期，日聚合不含会话维度）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStats {
+    pub session_id: String,
+    pub app_type: String,
+    pub project_dir: Option<String>,
+    pub request_count: u64,
+    pub total_tokens: u64,
+    pub total_cost: String,
+    pub first_request_at: i64,
+    pub last_request_at: i64,
+    /// 会话管理器中的 provider 与会话文件，供 `get_session_messages` 回看消息；
+    /// 会话日志尚未同步到该会话时为 None
+    pub session_provider_id: Option<String>,
+    pub source_path: Option<String>,
+}
+
 /// 请求日志过滤器
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -160,6 +191,29 @@ pub struct RequestLogDetail {
     pub pricing_model: Option<String>,
 }
 
+/// 日聚合行（`usage_daily_rollups`，明细清理后保留的按日汇总）
+#[derive(Debug, Clone)]
+pub struct UsageRollupRow {
+    /// 本地日期 `YYYY-MM-DD`
+    pub date: String,
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub model: String,
+    pub request_model: String,
+    pub pricing_model: String,
+    pub request_count: u64,
+    pub success_count: u64,
+    pub input_tokens: u64,
+    /// 按 [`fresh_input_sql`] 归一后的输入 token（不含缓存读写）
+    pub fresh_input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub total_cost_usd: String,
+    pub avg_latency_ms: u64,
+}
+
 /// 把 26 列的查询结果映射为 `RequestLogDetail`。
 ///
 /// 调用方的 SELECT **必须**按以下顺序返回 26 列：
@@ -223,6 +277,9 @@ fn provider_name_coalesce(log_alias: &str, provider_alias: &str) -> String {
 
 pub(crate) const SESSION_PROXY_DEDUP_WINDOW_SECONDS: i64 = 10 * 60;
 
+/// 导出逐行读取时每页行数；每页之间释放连接锁
+pub(crate) const EXPORT_PAGE_SIZE: usize = 1000;
+
 /// SQL 片段：把指定别名的 `data_source` 包成 COALESCE，NULL 视作 'proxy'。
 ///
 /// 防御 schema v9 之前可能写入的 NULL data_source 行（见
@@ -303,6 +360,45 @@ fn push_provider_model_filters(
     }
 }
 
+/// 请求日志列表/导出共用的 WHERE 条件（跨源去重 + `LogFilters`）。
+///
+/// 条件里引用了 `p.name`，FROM 子句必须 LEFT JOIN providers p。
+fn request_log_conditions(filters: &LogFilters) -> (Vec<String>, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut conditions = vec![effective_usage_log_filter("l")];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(ref app_type) = filters.app_type {
+        // 仅过滤口径折叠 claude-desktop→claude；行投影仍返回原始 app_type，
+        // 详情面板据此展示真实入口（路由接管账单审计需要）。
+        conditions.push(format!("{} = ?", folded_app_type_sql("l.app_type")));
+        params.push(Box::new(app_type.clone()));
+    }
+    // 与 Dashboard 顶部下拉筛选同口径：Provider 按展示名精确匹配（会话占位
+    // 行如 "Claude (Session)" 也能命中），模型按有效计价模型匹配。
+    push_provider_model_filters(
+        &mut conditions,
+        &mut params,
+        "l",
+        "p",
+        filters.provider_name.as_deref(),
+        filters.model.as_deref(),
+    );
+    if let Some(status) = filters.status_code {
+        conditions.push("l.status_code = ?".to_string());
+        params.push(Box::new(status as i64));
+    }
+    if let Some(start) = filters.start_date {
+        conditions.push("l.created_at >= ?".to_string());
+        params.push(Box::new(start));
+    }
+    if let Some(end) = filters.end_date {
+        conditions.push("l.created_at <= ?".to_string());
+        params.push(Box::new(end));
+    }
+
+    (conditions, params)
+}
+
 pub(crate) fn effective_usage_log_filter(log_alias: &str) -> String {
     let data_source = data_source_expr(log_alias);
     let proxy_data_source = data_source_expr("proxy_dedup");
@@ -345,6 +441,7 @@ pub(crate) fn effective_usage_log_filter(log_alias: &str) -> String {
 ///
 /// `cache_creation_tokens`：Codex/Gemini session 日志不暴露该字段，调用方传 0
 /// 表示"未知"，匹配器会放行 proxy 侧任意 cache_creation_tokens 值。
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct DedupKey<'a> {
     pub app_type: &'a str,
@@ -360,6 +457,7 @@ pub(crate) struct DedupKey<'a> {
 ///
 /// 命中以下任一条件即跳过插入：① `request_id` 已存在；② 时间窗口内存在
 /// 与 `key` 匹配的 proxy 日志（指纹去重）。
+#[cfg(feature = "gui")]
 pub(crate) fn should_skip_session_insert(
     conn: &Connection,
     request_id: &str,
@@ -371,6 +469,7 @@ pub(crate) fn should_skip_session_insert(
     has_matching_proxy_usage_log(conn, key)
 }
 
+#[cfg(feature = "gui")]
 fn proxy_request_id_exists(conn: &Connection, request_id: &str) -> Result<bool, AppError> {
     conn.prepare_cached("SELECT EXISTS(SELECT 1 FROM proxy_request_logs WHERE request_id = ?1)")
         .and_then(|mut stmt| stmt.query_row(params![request_id], |row| row.get::<_, bool>(0)))
@@ -379,6 +478,7 @@ fn proxy_request_id_exists(conn: &Connection, request_id: &str) -> Result<bool,
 
 // 会话重导每个 token 事件都要跑一次这条查询；SQL 文本静态化让
 // prepare_cached 稳定命中，也省掉每行的 format! 分配。
+#[cfg(feature = "gui")]
 static MATCHING_PROXY_USAGE_LOG_SQL: LazyLock<String> = LazyLock::new(|| {
     let l_data_source = data_source_expr("l");
     let app_type_match = dedup_app_type_match_sql("l.app_type", "?1");
@@ -404,6 +504,7 @@ static MATCHING_PROXY_USAGE_LOG_SQL: LazyLock<String> = LazyLock::new(|| {
     )
 });
 
+#[cfg(feature = "gui")]
 pub(crate) fn has_matching_proxy_usage_log(
     conn: &Connection,
     key: &DedupKey,
@@ -443,6 +544,7 @@ pub(crate) fn has_matching_proxy_usage_log(
 /// 已知局限（有意取舍，方向保守只漏不双）：窗口不含 session 维度，任一
 /// grokbuild 代理行会给 ±窗口内的全部会话事件投下阴影——接管/官方两态在
 /// 十分钟内交替或并行使用时，官方侧轮次会被跳过（漏记而非双算）。
+#[cfg(feature = "gui")]
 pub(crate) fn has_recent_grokbuild_proxy_activity(
     conn: &Connection,
     created_at: i64,
@@ -465,6 +567,7 @@ pub(crate) fn has_recent_grokbuild_proxy_activity(
     .map_err(|e| AppError::Database(format!("查询 Grok 接管活动失败: {e}")))
 }
 
+#[cfg(feature = "gui")]
 static SUSPECTED_CODEX_DUPLICATE_SQL: LazyLock<String> = LazyLock::new(|| {
     let data_source = data_source_expr("l");
     format!(
@@ -483,6 +586,7 @@ static SUSPECTED_CODEX_DUPLICATE_SQL: LazyLock<String> = LazyLock::new(|| {
     )
 });
 
+#[cfg(feature = "gui")]
 pub(crate) fn has_suspected_codex_session_duplicate(
     conn: &Connection,
     request_id: &str,
@@ -1552,47 +1656,200 @@ impl Database {
         Ok(stats)
     }
 
-    /// 获取请求日志列表（分页）
-    pub fn get_request_logs(
+    /// 按项目目录统计成本与 token（明细 + 日聚合）
+    pub fn get_project_stats(
         &self,
-        filters: &LogFilters,
-        page: u32,
-        page_size: u32,
-    ) -> Result<PaginatedLogs, AppError> {
+        start_date: Option<i64>,
+        end_date: Option<i64>,
+        app_type: Option<&str>,
+    ) -> Result<Vec<ProjectStats>, AppError> {
         let conn = lock_conn!(self.conn);
 
-        let mut conditions = vec![effective_usage_log_filter("l")];
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let mut detail_conditions = vec![effective_usage_log_filter("l")];
+        let mut detail_params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(start) = start_date {
+            detail_conditions.push("l.created_at >= ?".to_string());
+            detail_params.push(Box::new(start));
+        }
+        if let Some(end) = end_date {
+            detail_conditions.push("l.created_at <= ?".to_string());
+            detail_params.push(Box::new(end));
+        }
+        if let Some(at) = app_type {
+            detail_conditions.push(format!("{} = ?", folded_app_type_sql("l.app_type")));
+            detail_params.push(Box::new(at.to_string()));
+        }
+        let detail_where = format!("WHERE {}", detail_conditions.join(" AND "));
 
-        if let Some(ref app_type) = filters.app_type {
-            // 仅过滤口径折叠 claude-desktop→claude；行投影仍返回原始 app_type，
-            // 详情面板据此展示真实入口（路由接管账单审计需要）。
-            conditions.push(format!("{} = ?", folded_app_type_sql("l.app_type")));
-            params.push(Box::new(app_type.clone()));
+        let mut rollup_conditions = Vec::new();
+        let mut rollup_params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let rollup_bounds = compute_rollup_date_bounds(start_date, end_date)?;
+        push_rollup_date_filters(
+            &mut rollup_conditions,
+            &mut rollup_params,
+            "r.date",
+            &rollup_bounds,
+        );
+        if let Some(at) = app_type {
+            rollup_conditions.push(format!("{} = ?", folded_app_type_sql("r.app_type")));
+            rollup_params.push(Box::new(at.to_string()));
         }
-        // 与 Dashboard 顶部下拉筛选同口径：Provider 按展示名精确匹配（会话占位
-        // 行如 "Claude (Session)" 也能命中），模型按有效计价模型匹配。
-        push_provider_model_filters(
-            &mut conditions,
-            &mut params,
-            "l",
-            "p",
-            filters.provider_name.as_deref(),
-            filters.model.as_deref(),
+        let rollup_where = if rollup_conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", rollup_conditions.join(" AND "))
+        };
+
+        let fresh_input_detail = fresh_input_sql("l");
+        let fresh_input_rollup = fresh_input_sql("r");
+        let sql = format!(
+            "SELECT
+                project_dir,
+                SUM(request_count) as request_count,
+                SUM(total_tokens) as total_tokens,
+                SUM(total_cost) as total_cost
+            FROM (
+                SELECT COALESCE(l.project_dir, '') as project_dir,
+                    COUNT(*) as request_count,
+                    COALESCE(SUM({fresh_input_detail} + l.output_tokens), 0) as total_tokens,
+                    COALESCE(SUM(CAST(l.total_cost_usd AS REAL)), 0) as total_cost
+                FROM proxy_request_logs l
+                {detail_where}
+                GROUP BY COALESCE(l.project_dir, '')
+                UNION ALL
+                SELECT r.project_dir,
+                    COALESCE(SUM(r.request_count), 0),
+                    COALESCE(SUM({fresh_input_rollup} + r.output_tokens), 0),
+                    COALESCE(SUM(CAST(r.total_cost_usd AS REAL)), 0)
+                FROM usage_daily_rollups r
+                {rollup_where}
+                GROUP BY r.project_dir
+            )
+            GROUP BY project_dir
+            ORDER BY total_cost DESC"
         );
-        if let Some(status) = filters.status_code {
-            conditions.push("l.status_code = ?".to_string());
-            params.push(Box::new(status as i64));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = detail_params;
+        params.extend(rollup_params);
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(param_refs.as_slice(), |row| {
+            let project_dir: String = row.get(0)?;
+            let total_cost: f64 = row.get(3)?;
+            Ok(ProjectStats {
+                project_dir: (!project_dir.is_empty()).then_some(project_dir),
+                request_count: row.get::<_, i64>(1)? as u64,
+                total_tokens: row.get::<_, i64>(2)? as u64,
+                total_cost: format!("{total_cost:.6}"),
+            })
+        })?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row?);
         }
-        if let Some(start) = filters.start_date {
+
+        Ok(stats)
+    }
+
+    /// 按成本降序返回前 `limit` 个会话
+    ///
+    /// 代理行的 session_id 对 Responses 客户端带 `<app>_` 前缀，这里经
+    /// session_projects 归一到会话日志里的原始 ID，同一会话的代理行与会话日志行
+    /// 合并为一条；`project_dir` 过滤传 "" 表示只看未归属项目的会话。
+    pub fn get_session_stats(
+        &self,
+        start_date: Option<i64>,
+        end_date: Option<i64>,
+        app_type: Option<&str>,
+        project_dir: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<SessionStats>, AppError> {
+        let conn = lock_conn!(self.conn);
+
+        let mut conditions = vec![
+            effective_usage_log_filter("l"),
+            "l.session_id IS NOT NULL AND l.session_id != ''".to_string(),
+        ];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(start) = start_date {
             conditions.push("l.created_at >= ?".to_string());
             params.push(Box::new(start));
         }
-        if let Some(end) = filters.end_date {
+        if let Some(end) = end_date {
             conditions.push("l.created_at <= ?".to_string());
             params.push(Box::new(end));
         }
+        if let Some(at) = app_type {
+            conditions.push(format!("{} = ?", folded_app_type_sql("l.app_type")));
+            params.push(Box::new(at.to_string()));
+        }
+        if let Some(dir) = project_dir {
+            conditions.push("COALESCE(l.project_dir, sp.project_dir, '') = ?".to_string());
+            params.push(Box::new(dir.to_string()));
+        }
+        params.push(Box::new(i64::from(limit.clamp(1, 500))));
+
+        let fresh_input = fresh_input_sql("l");
+        let folded_app_type = folded_app_type_sql("l.app_type");
+        let sql = format!(
+            "SELECT
+                COALESCE(sp.session_id, l.session_id) as session_key,
+                MIN({folded_app_type}),
+                COALESCE(MAX(l.project_dir), MAX(sp.project_dir)),
+                COUNT(*),
+                COALESCE(SUM({fresh_input} + l.output_tokens), 0),
+                COALESCE(SUM(CAST(l.total_cost_usd AS REAL)), 0) as total_cost,
+                MIN(l.created_at),
+                MAX(l.created_at),
+                MAX(sp.app_type),
+                MAX(sp.source_path)
+            FROM proxy_request_logs l
+            LEFT JOIN session_projects sp
+                ON sp.session_id IN (l.session_id, substr(l.session_id, length(l.app_type) + 2))
+            WHERE {}
+            GROUP BY session_key
+            ORDER BY total_cost DESC, MAX(l.created_at) DESC
+            LIMIT ?",
+            conditions.join(" AND ")
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(param_refs.as_slice(), |row| {
+            let total_cost: f64 = row.get(5)?;
+            Ok(SessionStats {
+                session_id: row.get(0)?,
+                app_type: row.get(1)?,
+                project_dir: row.get(2)?,
+                request_count: row.get::<_, i64>(3)? as u64,
+                total_tokens: row.get::<_, i64>(4)? as u64,
+                total_cost: format!("{total_cost:.6}"),
+                first_request_at: row.get(6)?,
+                last_request_at: row.get(7)?,
+                session_provider_id: row.get(8)?,
+                source_path: row.get(9)?,
+            })
+        })?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            stats.push(row?);
+        }
+
+        Ok(stats)
+    }
+
+    /// 获取请求日志列表（分页）
+    pub fn get_request_logs(
+        &self,
+        filters: &LogFilters,
+        page: u32,
+        page_size: u32,
+    ) -> Result<PaginatedLogs, AppError> {
+        let conn = lock_conn!(self.conn);
 
+        let (conditions, mut params) = request_log_conditions(filters);
         let where_clause = if conditions.is_empty() {
             String::new()
         } else {
@@ -1652,6 +1909,165 @@ impl Database {
         })
     }
 
+    /// 按时间正序逐行读取请求日志（导出用）
+    ///
+    /// 与 [`Self::get_request_logs`] 同口径：跨源去重、`LogFilters` 筛选、历史行成本回填。
+    /// 回调额外拿到 session_id 与归一后的 fresh input tokens，返回处理的行数。
+    ///
+    /// 按 `(created_at, request_id)` 键集分页读取，每页读完即释放连接锁再执行回调，
+    /// 大范围导出期间代理仍可写入日志；导出期间新写入的行只要排在游标之后也会被读到。
+    pub fn for_each_request_log(
+        &self,
+        filters: &LogFilters,
+        mut f: impl FnMut(RequestLogDetail, Option<String>, u64) -> Result<(), AppError>,
+    ) -> Result<u64, AppError> {
+        let (conditions, filter_params) = request_log_conditions(filters);
+        let logs_pname = provider_name_coalesce("l", "p");
+        let fresh_input = fresh_input_sql("l");
+        let select = format!(
+            "SELECT l.request_id, l.provider_id, {logs_pname} as provider_name, l.app_type, l.model,
+                    l.request_model, l.cost_multiplier,
+                    l.input_tokens, l.output_tokens, l.cache_read_tokens, l.cache_creation_tokens,
+                    l.input_cost_usd, l.output_cost_usd, l.cache_read_cost_usd, l.cache_creation_cost_usd, l.total_cost_usd,
+                    l.is_streaming, l.latency_ms, l.first_token_ms, l.duration_ms,
+                    l.status_code, l.error_message, l.created_at, l.data_source, l.pricing_model,
+                    l.input_token_semantics, l.session_id, {fresh_input}
+             FROM proxy_request_logs l
+             LEFT JOIN providers p ON l.provider_id = p.id AND l.app_type = p.app_type"
+        );
+        let first_page_sql = format!(
+            "{select} WHERE {} ORDER BY l.created_at ASC, l.request_id ASC LIMIT {EXPORT_PAGE_SIZE}",
+            conditions.join(" AND ")
+        );
+        let next_page_sql = format!(
+            "{select} WHERE {} AND (l.created_at, l.request_id) > (?, ?)
+             ORDER BY l.created_at ASC, l.request_id ASC LIMIT {EXPORT_PAGE_SIZE}",
+            conditions.join(" AND ")
+        );
+
+        let mut pricing_cache = HashMap::new();
+        let mut cursor: Option<(i64, String)> = None;
+        let mut count = 0;
+        loop {
+            let page = {
+                let conn = lock_conn!(self.conn);
+                let mut params_refs: Vec<&dyn rusqlite::ToSql> =
+                    filter_params.iter().map(|p| p.as_ref()).collect();
+                let sql = match &cursor {
+                    Some((created_at, request_id)) => {
+                        params_refs.push(created_at);
+                        params_refs.push(request_id);
+                        &next_page_sql
+                    }
+                    None => &first_page_sql,
+                };
+                let mut stmt = conn.prepare(sql)?;
+                let mut rows = stmt.query(params_refs.as_slice())?;
+                let mut page = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let mut log = row_to_request_log_detail(row)?;
+                    let session_id: Option<String> = row.get(26)?;
+                    let fresh_input_tokens = row.get::<_, i64>(27)?.max(0) as u64;
+                    Self::maybe_backfill_log_costs(&conn, &mut log, &mut pricing_cache)?;
+                    page.push((log, session_id, fresh_input_tokens));
+                }
+                page
+            };
+
+            let full_page = page.len() == EXPORT_PAGE_SIZE;
+            for (log, session_id, fresh_input_tokens) in page {
+                cursor = Some((log.created_at, log.request_id.clone()));
+                f(log, session_id, fresh_input_tokens)?;
+                count += 1;
+            }
+            if !full_page {
+                return Ok(count);
+            }
+        }
+    }
+
+    /// 按日期正序逐行读取日聚合（导出用）
+    ///
+    /// 日期范围与汇总查询一致，只取被 `start_date..=end_date` 完整覆盖的本地自然日。
+    /// 聚合行不保留状态码，`filters.status_code` 存在时不返回任何聚合行。
+    pub fn for_each_usage_rollup(
+        &self,
+        filters: &LogFilters,
+        mut f: impl FnMut(UsageRollupRow) -> Result<(), AppError>,
+    ) -> Result<u64, AppError> {
+        if filters.status_code.is_some() {
+            return Ok(0);
+        }
+        let conn = lock_conn!(self.conn);
+
+        let mut conditions: Vec<String> = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let bounds = compute_rollup_date_bounds(filters.start_date, filters.end_date)?;
+        push_rollup_date_filters(&mut conditions, &mut params, "r.date", &bounds);
+        if let Some(ref app_type) = filters.app_type {
+            conditions.push(format!("{} = ?", folded_app_type_sql("r.app_type")));
+            params.push(Box::new(app_type.clone()));
+        }
+        push_provider_model_filters(
+            &mut conditions,
+            &mut params,
+            "r",
+            "p",
+            filters.provider_name.as_deref(),
+            filters.model.as_deref(),
+        );
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let pname = provider_name_coalesce("r", "p");
+        let join = providers_join("r", "p");
+        let fresh_input = fresh_input_sql("r");
+        let sql = format!(
+            "SELECT r.date, r.app_type, r.provider_id, {pname}, r.model, r.request_model,
+                    r.pricing_model, r.request_count, r.success_count, r.input_tokens,
+                    {fresh_input}, r.output_tokens, r.cache_read_tokens,
+                    r.cache_creation_tokens, r.total_cost_usd, r.avg_latency_ms
+             FROM usage_daily_rollups r
+             {join}
+             {where_clause}
+             ORDER BY r.date ASC, r.app_type ASC, r.provider_id ASC, r.model ASC"
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut rows = stmt.query(params_refs.as_slice())?;
+
+        let count_col = |row: &rusqlite::Row<'_>, idx: usize| -> rusqlite::Result<u64> {
+            Ok(row.get::<_, i64>(idx)?.max(0) as u64)
+        };
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            f(UsageRollupRow {
+                date: row.get(0)?,
+                app_type: row.get(1)?,
+                provider_id: row.get(2)?,
+                provider_name: row.get(3)?,
+                model: row.get(4)?,
+                request_model: row.get(5)?,
+                pricing_model: row.get(6)?,
+                request_count: count_col(row, 7)?,
+                success_count: count_col(row, 8)?,
+                input_tokens: count_col(row, 9)?,
+                fresh_input_tokens: count_col(row, 10)?,
+                output_tokens: count_col(row, 11)?,
+                cache_read_tokens: count_col(row, 12)?,
+                cache_creation_tokens: count_col(row, 13)?,
+                total_cost_usd: row.get(14)?,
+                avg_latency_ms: count_col(row, 15)?,
+            })?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     /// 获取单个请求详情
     pub fn get_request_detail(
         &self,
@@ -1804,6 +2220,7 @@ impl Database {
     }
 
     /// 仅回填指定 model_id 相关的零成本行；用于单条定价更新后的精准回填。
+    #[cfg(feature = "gui")]
     pub(crate) fn backfill_missing_usage_costs_for_model(
         &self,
         model_id: &str,
@@ -2033,6 +2450,7 @@ impl Database {
     }
 }
 
+#[cfg(feature = "gui")]
 pub(crate) fn find_model_pricing(conn: &Connection, model_id: &str) -> Option<ModelPricing> {
     find_model_pricing_row(conn, model_id)
         .ok()
@@ -3814,6 +4232,98 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_project_stats_merges_details_and_rollups() -> Result<(), AppError> {
+        let db = Database::memory()?;
+
+        {
+            let conn = lock_conn!(db.conn);
+            for (request_id, project_dir, cost) in
+                [("req1", Some("/work/app"), "0.20"), ("req2", None, "0.05")]
+            {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model,
+                        input_tokens, output_tokens, total_cost_usd,
+                        latency_ms, status_code, created_at, project_dir
+                    ) VALUES (?1, 'p1', 'claude', 'claude-sonnet-4-5', 100, 50, ?2, 100, 200, 1000, ?3)",
+                    params![request_id, cost, project_dir],
+                )?;
+            }
+            conn.execute(
+                "INSERT INTO usage_daily_rollups (
+                    date, app_type, provider_id, model, project_dir,
+                    request_count, success_count, input_tokens, output_tokens, total_cost_usd
+                ) VALUES ('1970-01-01', 'claude', 'p1', 'claude-sonnet-4-5', '/work/app',
+                    3, 3, 300, 150, '0.60')",
+                [],
+            )?;
+        }
+
+        let stats = db.get_project_stats(None, None, None)?;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].project_dir.as_deref(), Some("/work/app"));
+        assert_eq!(stats[0].request_count, 4);
+        assert_eq!(stats[0].total_tokens, 600);
+        assert_eq!(stats[0].total_cost, "0.800000");
+        assert_eq!(stats[1].project_dir, None);
+        assert_eq!(stats[1].request_count, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_session_stats_folds_prefixed_proxy_sessions() -> Result<(), AppError> {
+        let db = Database::memory()?;
+
+        {
+            let conn = lock_conn!(db.conn);
+            crate::services::session_usage::record_session_project_on_conn(
+                &conn,
+                "codex",
+                "thread-a",
+                "/work/app",
+                Some("/home/u/.codex/sessions/rollout-thread-a.jsonl"),
+            )?;
+            for (request_id, session_id, cost, created_at) in [
+                ("req1", "codex_thread-a", "0.30", 1000),
+                ("req2", "thread-a", "0.20", 2000),
+                ("req3", "codex_thread-b", "0.10", 3000),
+            ] {
+                conn.execute(
+                    "INSERT INTO proxy_request_logs (
+                        request_id, provider_id, app_type, model,
+                        input_tokens, output_tokens, total_cost_usd,
+                        latency_ms, status_code, session_id, created_at
+                    ) VALUES (?1, 'p1', 'codex', 'gpt-5.4', 100, 50, ?2, 100, 200, ?3, ?4)",
+                    params![request_id, cost, session_id, created_at],
+                )?;
+            }
+        }
+
+        let stats = db.get_session_stats(None, None, None, None, 10)?;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].session_id, "thread-a");
+        assert_eq!(stats[0].request_count, 2);
+        assert_eq!(stats[0].total_cost, "0.500000");
+        assert_eq!(stats[0].first_request_at, 1000);
+        assert_eq!(stats[0].last_request_at, 2000);
+        assert_eq!(stats[0].project_dir.as_deref(), Some("/work/app"));
+        assert_eq!(stats[0].session_provider_id.as_deref(), Some("codex"));
+        assert_eq!(
+            stats[0].source_path.as_deref(),
+            Some("/home/u/.codex/sessions/rollout-thread-a.jsonl")
+        );
+        assert_eq!(stats[1].session_id, "codex_thread-b");
+        assert_eq!(stats[1].source_path, None);
+
+        let top = db.get_session_stats(None, None, None, Some("/work/app"), 1)?;
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].session_id, "thread-a");
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_provider_stats_with_time_filter() -> Result<(), AppError> {
         let db = Database::memory()?;
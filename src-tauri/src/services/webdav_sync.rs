@@ -17,11 +17,12 @@ use crate::settings::{update_webdav_sync_status, WebDavSyncSettings, WebDavSyncS
 
 pub(crate) use super::sync_protocol::run_with_sync_lock;
 use super::sync_protocol::{
-    apply_snapshot, build_local_snapshot, effective_db_compat_version, localized,
-    persist_sync_success_best_effort, sha256_hex, validate_artifact_size_limit,
-    validate_manifest_compat, verify_artifact, ArtifactMeta, RemoteLayout, SyncManifest,
-    DB_COMPAT_VERSION, MAX_MANIFEST_BYTES, MAX_SYNC_ARTIFACT_BYTES, PROTOCOL_VERSION,
-    REMOTE_DB_SQL, REMOTE_MANIFEST, REMOTE_SKILLS_ZIP,
+    apply_snapshot, build_local_snapshot, effective_db_compat_version, load_sync_base, localized,
+    open_artifact, persist_sync_success_best_effort, record_merge_outcome, sha256_hex,
+    store_sync_base, unlock_snapshot, validate_artifact_size_limit, validate_manifest_compat,
+    verify_artifact, ArtifactMeta, RemoteLayout, SyncManifest, DB_COMPAT_VERSION,
+    MAX_MANIFEST_BYTES, MAX_SYNC_ARTIFACT_BYTES, REMOTE_DB_SQL, REMOTE_LAYOUT_VERSION,
+    REMOTE_MANIFEST, REMOTE_SKILLS_ZIP,
 };
 
 #[cfg(test)]
@@ -59,7 +60,7 @@ pub async fn upload(
     let dir_segs = remote_dir_segments(settings, RemoteLayout::Current);
     ensure_remote_directories(&settings.base_url, &dir_segs, &auth).await?;
 
-    let snapshot = build_local_snapshot(db)?;
+    let snapshot = build_local_snapshot(db, settings.sync_passphrase())?;
 
     // Upload order: artifacts first, manifest last (best-effort consistency)
     let db_url = remote_file_url(settings, RemoteLayout::Current, REMOTE_DB_SQL)?;
@@ -86,6 +87,7 @@ pub async fn upload(
         }
     };
 
+    store_sync_base(&remote_identity(settings), &snapshot.plain_db_sql);
     let _persisted = persist_sync_success_best_effort(
         settings,
         snapshot.manifest_hash,
@@ -113,8 +115,9 @@ pub async fn download(
         })?;
 
     validate_manifest_compat(&snapshot.manifest, snapshot.layout)?;
+    let key = unlock_snapshot(&snapshot.manifest, settings.sync_passphrase())?;
 
-    // Download and verify artifacts
+    // Download and verify artifacts, then decrypt them if the snapshot is sealed
     let db_sql = download_and_verify(
         settings,
         &auth,
@@ -131,9 +134,15 @@ pub async fn download(
         &snapshot.manifest.artifacts,
     )
     .await?;
+    let db_sql = open_artifact(key.as_ref(), REMOTE_DB_SQL, db_sql)?;
+    let skills_zip = open_artifact(key.as_ref(), REMOTE_SKILLS_ZIP, skills_zip)?;
 
-    // Apply snapshot
-    apply_snapshot(db, &db_sql, &skills_zip)?;
+    // Apply snapshot, merging against the last shared snapshot when one exists
+    let identity = remote_identity(settings);
+    let base_sql = load_sync_base(&identity);
+    let report = apply_snapshot(db, &db_sql, &skills_zip, base_sql.as_deref())?;
+    store_sync_base(&identity, &db_sql);
+    let merge = record_merge_outcome(report.as_ref());
 
     let manifest_hash = sha256_hex(&snapshot.manifest_bytes);
     let _persisted = persist_sync_success_best_effort(
@@ -146,6 +155,7 @@ pub async fn download(
         "status": "downloaded",
         "sourceLayout": snapshot.layout.as_str(),
         "sourcePath": remote_dir_display(settings, snapshot.layout),
+        "merge": merge,
     }))
 }
 
@@ -167,6 +177,7 @@ pub async fn fetch_remote_info(settings: &WebDavSyncSettings) -> Result<Option<V
         "protocolVersion": snapshot.manifest.version,
         "dbCompatVersion": db_compat_version,
         "compatible": compatible,
+        "encrypted": snapshot.manifest.encryption.is_some(),
         "artifacts": snapshot.manifest.artifacts.keys().collect::<Vec<_>>(),
         "layout": snapshot.layout.as_str(),
         "remotePath": remote_dir_display(settings, snapshot.layout),
@@ -267,7 +278,7 @@ async fn download_and_verify(
 fn remote_dir_segments(settings: &WebDavSyncSettings, layout: RemoteLayout) -> Vec<String> {
     let mut segs = Vec::new();
     segs.extend(path_segments(&settings.remote_root).map(str::to_string));
-    segs.push(format!("v{PROTOCOL_VERSION}"));
+    segs.push(format!("v{REMOTE_LAYOUT_VERSION}"));
     if layout == RemoteLayout::Current {
         segs.push(format!("db-v{DB_COMPAT_VERSION}"));
     }
@@ -290,6 +301,15 @@ fn remote_dir_display(settings: &WebDavSyncSettings, layout: RemoteLayout) -> St
     format!("/{}", segs.join("/"))
 }
 
+/// Identity of the remote snapshot location, used to key the merge base.
+fn remote_identity(settings: &WebDavSyncSettings) -> String {
+    format!(
+        "webdav:{}{}",
+        settings.base_url.trim_end_matches('/'),
+        remote_dir_display(settings, RemoteLayout::Current)
+    )
+}
+
 fn auth_for(settings: &WebDavSyncSettings) -> WebDavAuth {
     auth_from_credentials(&settings.username, &settings.password)
 }
@@ -65,7 +65,11 @@ pub(crate) fn zip_skills_ssot(dest_path: &Path) -> Result<(), AppError> {
     Ok(())
 }
 
-pub(crate) fn restore_skills_zip(raw: &[u8]) -> Result<(), AppError> {
+/// Replace the Skills SSOT with the archive contents.
+///
+/// Directories in `keep_local_dirs` (skills whose merged row came from this
+/// device) are carried over from the current SSOT instead of the archive.
+pub(crate) fn restore_skills_zip(raw: &[u8], keep_local_dirs: &[String]) -> Result<(), AppError> {
     let tmp = tempdir().map_err(|e| {
         io_context_localized(
             "webdav.sync.skills_extract_tmpdir_failed",
@@ -140,6 +144,26 @@ pub(crate) fn restore_skills_zip(raw: &[u8]) -> Result<(), AppError> {
             format!("Failed to resolve Skills SSOT directory: {e}"),
         )
     })?;
+    for dir in keep_local_dirs {
+        let relative = Path::new(dir);
+        if relative.as_os_str().is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            continue;
+        }
+        let local_dir = ssot.join(relative);
+        if !local_dir.is_dir() {
+            continue;
+        }
+        let target = extracted.join(relative);
+        if target.exists() {
+            fs::remove_dir_all(&target).map_err(|e| AppError::io(&target, e))?;
+        }
+        copy_dir_recursive(&local_dir, &target)?;
+    }
+
     let bak = ssot.with_extension("bak");
 
     if ssot.exists() {
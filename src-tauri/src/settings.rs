@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{OnceLock, RwLock};
 
 use crate::app_config::AppType;
@@ -126,6 +127,16 @@ pub struct WebDavSyncSettings {
     pub remote_root: String,
     #[serde(default = "default_profile")]
     pub profile: String,
+    /// 快照端到端加密口令；为空时上传明文快照。落盘前用主密钥加密，从不回传前端
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub encryption_passphrase: String,
+    /// 是否已配置加密口令：只在返回前端时填充，前端据此提示"已设置"
+    #[serde(
+        default,
+        skip_deserializing,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub encryption_configured: bool,
     #[serde(default)]
     pub status: WebDavSyncStatus,
 }
@@ -140,6 +151,8 @@ impl Default for WebDavSyncSettings {
             password: String::new(),
             remote_root: default_remote_root(),
             profile: default_profile(),
+            encryption_passphrase: String::new(),
+            encryption_configured: false,
             status: WebDavSyncStatus::default(),
         }
     }
@@ -181,6 +194,11 @@ impl WebDavSyncSettings {
     fn is_empty(&self) -> bool {
         self.base_url.is_empty() && self.username.is_empty() && self.password.is_empty()
     }
+
+    /// 配置了端到端加密口令时返回口令
+    pub fn sync_passphrase(&self) -> Option<&str> {
+        (!self.encryption_passphrase.is_empty()).then_some(self.encryption_passphrase.as_str())
+    }
 }
 
 /// S3 同步设置
@@ -205,6 +223,16 @@ pub struct S3SyncSettings {
     pub remote_root: String,
     #[serde(default = "default_profile")]
     pub profile: String,
+    /// 快照端到端加密口令；为空时上传明文快照。落盘前用主密钥加密，从不回传前端
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub encryption_passphrase: String,
+    /// 是否已配置加密口令：只在返回前端时填充，前端据此提示"已设置"
+    #[serde(
+        default,
+        skip_deserializing,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub encryption_configured: bool,
     #[serde(default)]
     pub status: WebDavSyncStatus,
 }
@@ -221,6 +249,8 @@ impl Default for S3SyncSettings {
             endpoint: String::new(),
             remote_root: default_remote_root(),
             profile: default_profile(),
+            encryption_passphrase: String::new(),
+            encryption_configured: false,
             status: WebDavSyncStatus::default(),
         }
     }
@@ -281,6 +311,212 @@ impl S3SyncSettings {
             && self.access_key_id.is_empty()
             && self.secret_access_key.is_empty()
     }
+
+    /// 配置了端到端加密口令时返回口令
+    pub fn sync_passphrase(&self) -> Option<&str> {
+        (!self.encryption_passphrase.is_empty()).then_some(self.encryption_passphrase.as_str())
+    }
+}
+
+fn default_git_branch() -> String {
+    "main".to_string()
+}
+
+/// 本地目录同步设置（Syncthing / Dropbox 等同步盘目录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub auto_sync: bool,
+    /// 同步目录的绝对路径
+    #[serde(default)]
+    pub path: String,
+    #[serde(default = "default_remote_root")]
+    pub remote_root: String,
+    #[serde(default = "default_profile")]
+    pub profile: String,
+    /// 快照端到端加密口令；为空时写入明文快照。从不回传前端
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub encryption_passphrase: String,
+    /// 是否已配置加密口令：只在返回前端时填充，前端据此提示"已设置"
+    #[serde(
+        default,
+        skip_deserializing,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub encryption_configured: bool,
+    #[serde(default)]
+    pub status: WebDavSyncStatus,
+}
+
+impl Default for FolderSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            auto_sync: false,
+            path: String::new(),
+            remote_root: default_remote_root(),
+            profile: default_profile(),
+            encryption_passphrase: String::new(),
+            encryption_configured: false,
+            status: WebDavSyncStatus::default(),
+        }
+    }
+}
+
+impl FolderSyncSettings {
+    pub fn validate(&self) -> Result<(), crate::error::AppError> {
+        if self.path.trim().is_empty() {
+            return Err(crate::error::AppError::localized(
+                "folder_sync.path.required",
+                "同步目录不能为空",
+                "Sync folder is required.",
+            ));
+        }
+        if !Path::new(self.path.trim()).is_absolute() {
+            return Err(crate::error::AppError::localized(
+                "folder_sync.path.not_absolute",
+                "同步目录必须是绝对路径",
+                "Sync folder must be an absolute path.",
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn normalize(&mut self) {
+        self.path = self.path.trim().to_string();
+        self.remote_root = self.remote_root.trim().to_string();
+        self.profile = self.profile.trim().to_string();
+        if self.remote_root.is_empty() {
+            self.remote_root = default_remote_root();
+        }
+        if self.profile.is_empty() {
+            self.profile = default_profile();
+        }
+    }
+
+    /// Returns true if no folder is configured (no config to persist).
+    fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// 配置了端到端加密口令时返回口令
+    pub fn sync_passphrase(&self) -> Option<&str> {
+        (!self.encryption_passphrase.is_empty()).then_some(self.encryption_passphrase.as_str())
+    }
+}
+
+/// Git 同步设置：快照提交到本地裸仓库或 `file://` 仓库
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub auto_sync: bool,
+    /// 仓库地址：绝对路径或 `file://` URL
+    #[serde(default)]
+    pub repo_url: String,
+    #[serde(default = "default_git_branch")]
+    pub branch: String,
+    #[serde(default = "default_remote_root")]
+    pub remote_root: String,
+    #[serde(default = "default_profile")]
+    pub profile: String,
+    /// 快照端到端加密口令；为空时提交明文快照。从不回传前端
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub encryption_passphrase: String,
+    /// 是否已配置加密口令：只在返回前端时填充，前端据此提示"已设置"
+    #[serde(
+        default,
+        skip_deserializing,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub encryption_configured: bool,
+    #[serde(default)]
+    pub status: WebDavSyncStatus,
+}
+
+impl Default for GitSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            auto_sync: false,
+            repo_url: String::new(),
+            branch: default_git_branch(),
+            remote_root: default_remote_root(),
+            profile: default_profile(),
+            encryption_passphrase: String::new(),
+            encryption_configured: false,
+            status: WebDavSyncStatus::default(),
+        }
+    }
+}
+
+impl GitSyncSettings {
+    pub fn validate(&self) -> Result<(), crate::error::AppError> {
+        let repo_url = self.repo_url.trim();
+        if repo_url.is_empty() {
+            return Err(crate::error::AppError::localized(
+                "git_sync.repo_url.required",
+                "Git 仓库地址不能为空",
+                "Git repository is required.",
+            ));
+        }
+        // 仅支持本地仓库：避免 ssh/https 凭据交互，也挡住 `ext::` 等可执行命令的传输协议
+        if !repo_url.starts_with("file://") && !Path::new(repo_url).is_absolute() {
+            return Err(crate::error::AppError::localized(
+                "git_sync.repo_url.unsupported",
+                "Git 仓库地址必须是绝对路径或 file:// URL",
+                "Git repository must be an absolute path or a file:// URL.",
+            ));
+        }
+        let branch = self.branch.trim();
+        let branch_valid = !branch.is_empty()
+            && !branch.starts_with('-')
+            && !branch.contains("..")
+            && !branch.ends_with(".lock")
+            && !branch.ends_with('/')
+            && branch
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+        if !branch_valid {
+            return Err(crate::error::AppError::localized(
+                "git_sync.branch.invalid",
+                format!("Git 分支名无效: {branch}"),
+                format!("Invalid Git branch name: {branch}"),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn normalize(&mut self) {
+        self.repo_url = self.repo_url.trim().to_string();
+        self.branch = self.branch.trim().to_string();
+        self.remote_root = self.remote_root.trim().to_string();
+        self.profile = self.profile.trim().to_string();
+        if self.branch.is_empty() {
+            self.branch = default_git_branch();
+        }
+        if self.remote_root.is_empty() {
+            self.remote_root = default_remote_root();
+        }
+        if self.profile.is_empty() {
+            self.profile = default_profile();
+        }
+    }
+
+    /// Returns true if no repository is configured (no config to persist).
+    fn is_empty(&self) -> bool {
+        self.repo_url.is_empty()
+    }
+
+    /// 配置了端到端加密口令时返回口令
+    pub fn sync_passphrase(&self) -> Option<&str> {
+        (!self.encryption_passphrase.is_empty()).then_some(self.encryption_passphrase.as_str())
+    }
 }
 
 /// 本机自动迁移状态。
@@ -471,6 +707,14 @@ pub struct AppSettings {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub s3_sync: Option<S3SyncSettings>,
 
+    // ===== 本地目录同步设置 =====
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_sync: Option<FolderSyncSettings>,
+
+    // ===== Git 同步设置 =====
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_sync: Option<GitSyncSettings>,
+
     // ===== WebDAV 备份设置（旧版，保留向后兼容）=====
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub webdav_backup: Option<serde_json::Value>,
@@ -491,6 +735,26 @@ pub struct AppSettings {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub preferred_terminal: Option<String>,
 
+    // ===== 提示词变量 =====
+    /// 提示词 `{{name}}` 自定义变量，写入 CLAUDE.md 等文件时替换；与内置变量同名时覆盖内置值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_variables: Option<BTreeMap<String, String>>,
+
+    // ===== MCP 网关 =====
+    /// 开启后各应用只写入一个指向本地代理 `/mcp/{app}` 的 http 条目，stdio 服务器由代理统一托管
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_gateway_enabled: Option<bool>,
+
+    // ===== 代理抓包 =====
+    /// 开启后代理记录每个请求转换前后的请求与响应，用于排查格式转换问题
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_capture_enabled: Option<bool>,
+
+    // ===== OpenTelemetry 导出 =====
+    /// OTLP/HTTP 采集端点（如 `http://127.0.0.1:4318`）；设置后代理导出请求 trace 与指标
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otlp_endpoint: Option<String>,
+
     // ===== 本机自动迁移状态 =====
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub local_migrations: Option<LocalMigrations>,
@@ -552,10 +816,16 @@ impl Default for AppSettings {
             skill_storage_location: SkillStorageLocation::default(),
             webdav_sync: None,
             s3_sync: None,
+            folder_sync: None,
+            git_sync: None,
             webdav_backup: None,
             backup_interval_hours: None,
             backup_retain_count: None,
             preferred_terminal: None,
+            prompt_variables: None,
+            mcp_gateway_enabled: None,
+            proxy_capture_enabled: None,
+            otlp_endpoint: None,
             local_migrations: None,
         }
     }
@@ -648,6 +918,58 @@ impl AppSettings {
                 self.s3_sync = None;
             }
         }
+
+        if let Some(folder) = &mut self.folder_sync {
+            folder.normalize();
+            if folder.is_empty() {
+                self.folder_sync = None;
+            }
+        }
+
+        if let Some(git) = &mut self.git_sync {
+            git.normalize();
+            if git.is_empty() {
+                self.git_sync = None;
+            }
+        }
+    }
+
+    /// 同步凭据落盘前加密（WebDAV 密码、S3 Secret Access Key）；加密失败时拒绝保存，
+    /// 不把明文写进 settings.json
+    fn encrypt_secrets(&mut self) -> Result<(), AppError> {
+        if let Some(sync) = &mut self.webdav_sync {
+            crate::secrets::encrypt_in_place(&mut sync.password)?;
+            crate::secrets::encrypt_in_place(&mut sync.encryption_passphrase)?;
+        }
+        if let Some(s3) = &mut self.s3_sync {
+            crate::secrets::encrypt_in_place(&mut s3.secret_access_key)?;
+            crate::secrets::encrypt_in_place(&mut s3.encryption_passphrase)?;
+        }
+        if let Some(folder) = &mut self.folder_sync {
+            crate::secrets::encrypt_in_place(&mut folder.encryption_passphrase)?;
+        }
+        if let Some(git) = &mut self.git_sync {
+            crate::secrets::encrypt_in_place(&mut git.encryption_passphrase)?;
+        }
+        Ok(())
+    }
+
+    /// 读取 settings.json 后解密同步凭据；旧版明文原样保留，下次保存时加密
+    fn decrypt_secrets(&mut self) {
+        if let Some(sync) = &mut self.webdav_sync {
+            decrypt_or_clear(&mut sync.password);
+            decrypt_or_clear(&mut sync.encryption_passphrase);
+        }
+        if let Some(s3) = &mut self.s3_sync {
+            decrypt_or_clear(&mut s3.secret_access_key);
+            decrypt_or_clear(&mut s3.encryption_passphrase);
+        }
+        if let Some(folder) = &mut self.folder_sync {
+            decrypt_or_clear(&mut folder.encryption_passphrase);
+        }
+        if let Some(git) = &mut self.git_sync {
+            decrypt_or_clear(&mut git.encryption_passphrase);
+        }
     }
 
     fn load_from_file() -> Self {
@@ -658,6 +980,7 @@ impl AppSettings {
             match serde_json::from_str::<AppSettings>(&content) {
                 Ok(mut settings) => {
                     settings.normalize_paths();
+                    settings.decrypt_secrets();
                     settings
                 }
                 Err(err) => {
@@ -675,9 +998,19 @@ impl AppSettings {
     }
 }
 
+/// 解密单个同步凭据；无法解密（主密钥已更换或丢失）时清空，需用户重新填写，
+/// 避免把密文当作密码发给远端
+fn decrypt_or_clear(value: &mut String) {
+    if let Err(e) = crate::secrets::decrypt_in_place(value) {
+        log::error!("同步凭据解密失败，已清空，请重新填写: {e}");
+        value.clear();
+    }
+}
+
 fn save_settings_file(settings: &AppSettings) -> Result<(), AppError> {
     let mut normalized = settings.clone();
     normalized.normalize_paths();
+    normalized.encrypt_secrets()?;
     let Some(path) = AppSettings::settings_path() else {
         return Err(AppError::Config("无法获取用户主目录".to_string()));
     };
@@ -754,13 +1087,26 @@ pub fn get_settings() -> AppSettings {
         .clone()
 }
 
+#[cfg(feature = "gui")]
 pub fn get_settings_for_frontend() -> AppSettings {
     let mut settings = get_settings();
     if let Some(sync) = &mut settings.webdav_sync {
         sync.password.clear();
+        sync.encryption_configured = !sync.encryption_passphrase.is_empty();
+        sync.encryption_passphrase.clear();
     }
     if let Some(s3) = &mut settings.s3_sync {
         s3.secret_access_key.clear();
+        s3.encryption_configured = !s3.encryption_passphrase.is_empty();
+        s3.encryption_passphrase.clear();
+    }
+    if let Some(folder) = &mut settings.folder_sync {
+        folder.encryption_configured = !folder.encryption_passphrase.is_empty();
+        folder.encryption_passphrase.clear();
+    }
+    if let Some(git) = &mut settings.git_sync {
+        git.encryption_configured = !git.encryption_passphrase.is_empty();
+        git.encryption_passphrase.clear();
     }
     settings.webdav_backup = None;
     settings
@@ -794,6 +1140,7 @@ where
     Ok(())
 }
 
+#[cfg(feature = "gui")]
 pub fn is_codex_third_party_history_provider_bucket_migrated() -> bool {
     get_settings()
         .local_migrations
@@ -806,6 +1153,7 @@ pub fn is_codex_third_party_history_provider_bucket_migrated() -> bool {
         .is_some_and(|m| m.scanned_history_files)
 }
 
+#[cfg(feature = "gui")]
 pub fn mark_codex_third_party_history_provider_bucket_migrated(
     migration: CodexThirdPartyHistoryProviderBucketMigration,
 ) -> Result<(), AppError> {
@@ -817,6 +1165,7 @@ pub fn mark_codex_third_party_history_provider_bucket_migrated(
     })
 }
 
+#[cfg(feature = "gui")]
 pub fn is_codex_provider_template_migrated() -> bool {
     get_settings()
         .local_migrations
@@ -825,6 +1174,7 @@ pub fn is_codex_provider_template_migrated() -> bool {
         .is_some()
 }
 
+#[cfg(feature = "gui")]
 pub fn mark_codex_provider_template_migrated(
     migration: CodexProviderTemplateMigration,
 ) -> Result<(), AppError> {
@@ -838,6 +1188,7 @@ pub fn mark_codex_provider_template_migrated(
 
 /// 统一会话迁移标记是否覆盖指定目录。标记里没记目录（不应出现的旧格式）
 /// 视为不匹配——重跑迁移是幂等的，宁可重迁也不漏迁。
+#[cfg(feature = "gui")]
 pub fn is_codex_official_history_unify_migrated_for_dir(codex_dir: &str) -> bool {
     get_settings()
         .local_migrations
@@ -850,6 +1201,7 @@ pub fn is_codex_official_history_unify_migrated_for_dir(codex_dir: &str) -> bool
 /// 检查与写入在 settings 写锁内原子完成，与关闭开关路径
 /// （`update_settings` / 清标记）串行，消除"迁移线程复查开关后、写标记前
 /// 用户恰好关闭开关"的竞态窗口。返回是否实际写入。
+#[cfg(feature = "gui")]
 pub fn mark_codex_official_history_unify_migrated_if_enabled(
     migration: CodexOfficialHistoryUnifyMigration,
 ) -> Result<bool, AppError> {
@@ -868,6 +1220,7 @@ pub fn mark_codex_official_history_unify_migrated_if_enabled(
     Ok(written)
 }
 
+#[cfg(feature = "gui")]
 pub fn clear_codex_official_history_unify_migration() -> Result<(), AppError> {
     mutate_settings(|settings| {
         if let Some(migrations) = settings.local_migrations.as_mut() {
@@ -876,10 +1229,12 @@ pub fn clear_codex_official_history_unify_migration() -> Result<(), AppError> {
     })
 }
 
+#[cfg(feature = "gui")]
 pub fn unify_codex_migrate_existing_requested() -> bool {
     get_settings().unify_codex_migrate_existing.unwrap_or(false)
 }
 
+#[cfg(feature = "gui")]
 pub fn clear_codex_unify_migrate_existing() -> Result<(), AppError> {
     mutate_settings(|settings| {
         settings.unify_codex_migrate_existing = None;
@@ -888,6 +1243,7 @@ pub fn clear_codex_unify_migrate_existing() -> Result<(), AppError> {
 
 /// 从文件重新加载设置到内存缓存
 /// 用于导入配置等场景，确保内存缓存与文件同步
+#[cfg(feature = "gui")]
 pub fn reload_settings() -> Result<(), AppError> {
     let fresh_settings = AppSettings::load_from_file();
     let mut guard = settings_store().write().unwrap_or_else(|e| {
@@ -1093,6 +1449,7 @@ pub fn set_skill_storage_location(location: SkillStorageLocation) -> Result<(),
 // ===== 备份策略管理函数 =====
 
 /// Get the effective auto-backup interval in hours (default 24)
+#[cfg(feature = "gui")]
 pub fn effective_backup_interval_hours() -> u32 {
     settings_store()
         .read()
@@ -1120,6 +1477,7 @@ pub fn effective_backup_retain_count() -> usize {
 // ===== 终端设置管理函数 =====
 
 /// 获取首选终端应用
+#[cfg(feature = "gui")]
 pub fn get_preferred_terminal() -> Option<String> {
     settings_store()
         .read()
@@ -1131,14 +1489,100 @@ pub fn get_preferred_terminal() -> Option<String> {
         .clone()
 }
 
+// ===== 提示词变量管理函数 =====
+
+/// 获取提示词自定义变量
+pub fn get_prompt_variables() -> BTreeMap<String, String> {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .prompt_variables
+        .clone()
+        .unwrap_or_default()
+}
+
+/// 设置提示词自定义变量（空表即清除）
+pub fn set_prompt_variables(vars: BTreeMap<String, String>) -> Result<(), AppError> {
+    mutate_settings(|s| {
+        s.prompt_variables = (!vars.is_empty()).then_some(vars);
+    })
+}
+
+// ===== MCP 网关 =====
+
+/// 是否启用 MCP 网关模式
+pub fn get_mcp_gateway_enabled() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .mcp_gateway_enabled
+        .unwrap_or(false)
+}
+
+pub fn set_mcp_gateway_enabled(enabled: bool) -> Result<(), AppError> {
+    mutate_settings(|s| {
+        s.mcp_gateway_enabled = enabled.then_some(true);
+    })
+}
+
+// ===== 代理抓包 =====
+
+/// 是否开启代理抓包
+pub fn get_proxy_capture_enabled() -> bool {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .proxy_capture_enabled
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "gui")]
+pub fn set_proxy_capture_enabled(enabled: bool) -> Result<(), AppError> {
+    mutate_settings(|s| {
+        s.proxy_capture_enabled = enabled.then_some(true);
+    })
+}
+
+/// OTLP/HTTP 采集端点；未设置时不导出 trace 与指标
+pub fn get_otlp_endpoint() -> Option<String> {
+    settings_store()
+        .read()
+        .unwrap_or_else(|e| {
+            log::warn!("设置锁已毒化，使用恢复值: {e}");
+            e.into_inner()
+        })
+        .otlp_endpoint
+        .clone()
+}
+
+#[cfg(feature = "gui")]
+pub fn set_otlp_endpoint(endpoint: Option<String>) -> Result<(), AppError> {
+    mutate_settings(|s| {
+        s.otlp_endpoint = endpoint
+            .map(|e| e.trim().trim_end_matches('/').to_string())
+            .filter(|e| !e.is_empty());
+    })
+}
+
 // ===== WebDAV 同步设置管理函数 =====
 
 /// 获取 WebDAV 同步设置
+#[cfg(feature = "gui")]
 pub fn get_webdav_sync_settings() -> Option<WebDavSyncSettings> {
     settings_store().read().ok()?.webdav_sync.clone()
 }
 
 /// 保存 WebDAV 同步设置
+#[cfg(feature = "gui")]
 pub fn set_webdav_sync_settings(settings: Option<WebDavSyncSettings>) -> Result<(), AppError> {
     mutate_settings(|current| {
         current.webdav_sync = settings;
@@ -1146,6 +1590,7 @@ pub fn set_webdav_sync_settings(settings: Option<WebDavSyncSettings>) -> Result<
 }
 
 /// 仅更新 WebDAV 同步状态，避免覆写 credentials/root/profile 等字段
+#[cfg(feature = "gui")]
 pub fn update_webdav_sync_status(status: WebDavSyncStatus) -> Result<(), AppError> {
     mutate_settings(|current| {
         if let Some(sync) = current.webdav_sync.as_mut() {
@@ -1154,18 +1599,45 @@ pub fn update_webdav_sync_status(status: WebDavSyncStatus) -> Result<(), AppErro
     })
 }
 
+/// 设置（或清除）WebDAV 快照加密口令，仅在已有 WebDAV 配置时生效
+pub fn set_webdav_sync_passphrase(passphrase: &str) -> Result<bool, AppError> {
+    let mut updated = false;
+    mutate_settings(|current| {
+        if let Some(sync) = current.webdav_sync.as_mut() {
+            sync.encryption_passphrase = passphrase.to_string();
+            updated = true;
+        }
+    })?;
+    Ok(updated)
+}
+
 // ===== S3 同步设置管理函数 =====
 
+#[cfg(feature = "gui")]
 pub fn get_s3_sync_settings() -> Option<S3SyncSettings> {
     settings_store().read().ok()?.s3_sync.clone()
 }
 
+#[cfg(feature = "gui")]
 pub fn set_s3_sync_settings(settings: Option<S3SyncSettings>) -> Result<(), AppError> {
     mutate_settings(|current| {
         current.s3_sync = settings;
     })
 }
 
+/// 设置（或清除）S3 快照加密口令，仅在已有 S3 配置时生效
+pub fn set_s3_sync_passphrase(passphrase: &str) -> Result<bool, AppError> {
+    let mut updated = false;
+    mutate_settings(|current| {
+        if let Some(s3) = current.s3_sync.as_mut() {
+            s3.encryption_passphrase = passphrase.to_string();
+            updated = true;
+        }
+    })?;
+    Ok(updated)
+}
+
+#[cfg(feature = "gui")]
 pub fn update_s3_sync_status(status: WebDavSyncStatus) -> Result<(), AppError> {
     mutate_settings(|current| {
         if let Some(s3) = current.s3_sync.as_mut() {
@@ -1174,6 +1646,76 @@ pub fn update_s3_sync_status(status: WebDavSyncStatus) -> Result<(), AppError> {
     })
 }
 
+// ===== 本地目录同步设置管理函数 =====
+
+#[cfg(feature = "gui")]
+pub fn get_folder_sync_settings() -> Option<FolderSyncSettings> {
+    settings_store().read().ok()?.folder_sync.clone()
+}
+
+#[cfg(feature = "gui")]
+pub fn set_folder_sync_settings(settings: Option<FolderSyncSettings>) -> Result<(), AppError> {
+    mutate_settings(|current| {
+        current.folder_sync = settings;
+    })
+}
+
+/// 设置（或清除）本地目录快照加密口令，仅在已有目录同步配置时生效
+pub fn set_folder_sync_passphrase(passphrase: &str) -> Result<bool, AppError> {
+    let mut updated = false;
+    mutate_settings(|current| {
+        if let Some(folder) = current.folder_sync.as_mut() {
+            folder.encryption_passphrase = passphrase.to_string();
+            updated = true;
+        }
+    })?;
+    Ok(updated)
+}
+
+#[cfg(feature = "gui")]
+pub fn update_folder_sync_status(status: WebDavSyncStatus) -> Result<(), AppError> {
+    mutate_settings(|current| {
+        if let Some(folder) = current.folder_sync.as_mut() {
+            folder.status = status;
+        }
+    })
+}
+
+// ===== Git 同步设置管理函数 =====
+
+#[cfg(feature = "gui")]
+pub fn get_git_sync_settings() -> Option<GitSyncSettings> {
+    settings_store().read().ok()?.git_sync.clone()
+}
+
+#[cfg(feature = "gui")]
+pub fn set_git_sync_settings(settings: Option<GitSyncSettings>) -> Result<(), AppError> {
+    mutate_settings(|current| {
+        current.git_sync = settings;
+    })
+}
+
+/// 设置（或清除）Git 快照加密口令，仅在已有 Git 同步配置时生效
+pub fn set_git_sync_passphrase(passphrase: &str) -> Result<bool, AppError> {
+    let mut updated = false;
+    mutate_settings(|current| {
+        if let Some(git) = current.git_sync.as_mut() {
+            git.encryption_passphrase = passphrase.to_string();
+            updated = true;
+        }
+    })?;
+    Ok(updated)
+}
+
+#[cfg(feature = "gui")]
+pub fn update_git_sync_status(status: WebDavSyncStatus) -> Result<(), AppError> {
+    mutate_settings(|current| {
+        if let Some(git) = current.git_sync.as_mut() {
+            git.status = status;
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
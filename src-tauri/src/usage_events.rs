@@ -9,28 +9,38 @@
 //! - 200ms 防抖合并：流式响应等场景在短时间内可能写入多条日志，
 //!   合并成一次事件可避免前端连续 invalidate。
 //! - 不阻塞写入：通知失败仅记录 warn 日志，不向上传播错误。
+//! - 无界面构建（未启用 `gui` feature）没有前端可通知，通知直接丢弃。
 
+#[cfg(feature = "gui")]
 use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "gui")]
 use std::sync::OnceLock;
+#[cfg(feature = "gui")]
 use std::time::Duration;
 
+#[cfg(feature = "gui")]
 use tauri::{AppHandle, Emitter};
 
 /// 前端监听的事件名
+#[cfg(feature = "gui")]
 pub const EVENT_USAGE_LOG_RECORDED: &str = "usage-log-recorded";
 
 /// 防抖窗口：合并 200ms 内的多次通知。
+#[cfg(feature = "gui")]
 const DEBOUNCE_WINDOW: Duration = Duration::from_millis(200);
 
+#[cfg(feature = "gui")]
 static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
 
 /// 防抖标记：true 表示已有调度任务在等待 emit，后续通知合并到该任务。
+#[cfg(feature = "gui")]
 static EMIT_SCHEDULED: AtomicBool = AtomicBool::new(false);
 
 /// 在应用 setup 阶段调用一次，注入 AppHandle。
 ///
 /// 重复调用是无害的（OnceLock 仅首次写入生效），但应用启动期只该被
 /// `lib.rs::run` 调一次。
+#[cfg(feature = "gui")]
 pub fn init(handle: AppHandle) {
     if APP_HANDLE.set(handle).is_err() {
         log::debug!("usage_events::init 重复调用，已忽略");
@@ -47,6 +57,12 @@ pub fn notify_log_recorded() {
     #[cfg(test)]
     TEST_NOTIFY_COUNT.with(|count| count.set(count.get().saturating_add(1)));
 
+    #[cfg(feature = "gui")]
+    schedule_emit();
+}
+
+#[cfg(feature = "gui")]
+fn schedule_emit() {
     // AppHandle 未注入（典型出现在单元测试或 setup 之前）：直接放弃。
     let Some(handle) = APP_HANDLE.get() else {
         return;
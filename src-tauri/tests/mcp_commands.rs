@@ -1277,3 +1277,238 @@ fn sync_all_enabled_removes_known_disabled_but_preserves_unknown_live_entries()
         "live entries unknown to DB should be preserved"
     );
 }
+
+// 测试使用 Mutex 进行串行化，跨 await 持锁是预期行为
+#[allow(clippy::await_holding_lock)]
+#[tokio::test]
+async fn gateway_mode_replaces_per_server_entries_with_one_proxy_entry() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    fs::create_dir_all(home.join(".claude")).expect("create ~/.claude dir");
+
+    let state = create_test_state().expect("create test state");
+    // 使用空闲端口，避免与本机正在运行的代理冲突
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("pick a free port")
+        .port();
+    let mut proxy_config = state
+        .db
+        .get_global_proxy_config()
+        .await
+        .expect("read proxy config");
+    proxy_config.listen_port = port;
+    state
+        .db
+        .update_global_proxy_config(proxy_config)
+        .await
+        .expect("save proxy config");
+    McpService::upsert_server(
+        &state,
+        McpServer {
+            id: "echo".to_string(),
+            name: "echo".to_string(),
+            server: json!({ "type": "stdio", "command": "echo" }),
+            apps: McpApps {
+                claude: true,
+                ..McpApps::default()
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        },
+    )
+    .expect("upsert enabled server");
+
+    let read_servers = || {
+        let text = fs::read_to_string(get_claude_mcp_path()).expect("read ~/.claude.json");
+        let value: serde_json::Value = serde_json::from_str(&text).expect("parse ~/.claude.json");
+        value
+            .get("mcpServers")
+            .cloned()
+            .unwrap_or_else(|| json!({}))
+    };
+
+    McpService::set_gateway_enabled(&state, true)
+        .await
+        .expect("enable gateway");
+    assert!(
+        state.proxy_service.is_running().await,
+        "enabling the gateway starts the local proxy"
+    );
+    let servers = read_servers();
+    assert!(
+        servers.get("echo").is_none(),
+        "per-server entry is replaced"
+    );
+    assert_eq!(
+        servers.pointer("/cc-switch-gateway/url"),
+        Some(&json!(format!("http://127.0.0.1:{port}/mcp/claude")))
+    );
+    let authorization = servers
+        .pointer("/cc-switch-gateway/headers/Authorization")
+        .and_then(|value| value.as_str())
+        .expect("gateway entry carries the access token");
+    assert!(authorization.starts_with("Bearer ") && authorization.len() > "Bearer ".len());
+
+    // 网关条目不会被当作用户服务器导回
+    McpService::import_from_claude(&state).expect("import from claude");
+    assert!(!state
+        .db
+        .get_all_mcp_servers()
+        .expect("read servers")
+        .contains_key("cc-switch-gateway"));
+
+    McpService::toggle_app(&state, "echo", AppType::Claude, false).expect("disable last server");
+    assert!(
+        read_servers().get("cc-switch-gateway").is_none(),
+        "gateway entry goes away with the last enabled server"
+    );
+
+    McpService::toggle_app(&state, "echo", AppType::Claude, true).expect("re-enable server");
+    McpService::set_gateway_enabled(&state, false)
+        .await
+        .expect("disable gateway");
+    let servers = read_servers();
+    assert!(servers.get("cc-switch-gateway").is_none());
+    assert!(
+        servers.get("echo").is_some(),
+        "per-server entry is restored"
+    );
+
+    state.proxy_service.stop().await.expect("stop proxy");
+}
+
+#[test]
+fn secret_placeholders_resolve_only_in_live_config() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    fs::create_dir_all(home.join(".claude")).expect("create ~/.claude dir");
+
+    let state = create_test_state().expect("create test state");
+    McpService::set_secret(&state, "GITHUB_TOKEN", "ghp_first").expect("save secret");
+
+    let placeholder_spec = json!({
+        "type": "http",
+        "url": "https://api.githubcopilot.com/mcp/",
+        "headers": { "Authorization": "Bearer ${secret:GITHUB_TOKEN}" }
+    });
+    McpService::upsert_server(
+        &state,
+        McpServer {
+            id: "github".to_string(),
+            name: "GitHub".to_string(),
+            server: placeholder_spec.clone(),
+            apps: McpApps {
+                claude: true,
+                ..McpApps::default()
+            },
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        },
+    )
+    .expect("upsert server with placeholder");
+
+    let live_auth = || {
+        let text = fs::read_to_string(get_claude_mcp_path()).expect("read ~/.claude.json");
+        let value: serde_json::Value = serde_json::from_str(&text).expect("parse ~/.claude.json");
+        value
+            .pointer("/mcpServers/github/headers/Authorization")
+            .cloned()
+    };
+    assert_eq!(live_auth(), Some(json!("Bearer ghp_first")));
+    assert_eq!(
+        state.db.get_all_mcp_servers().expect("read servers")["github"].server,
+        placeholder_spec,
+        "database keeps the placeholder"
+    );
+
+    // 更新密钥会重新同步引用它的服务器
+    McpService::set_secret(&state, "GITHUB_TOKEN", "ghp_second").expect("update secret");
+    assert_eq!(live_auth(), Some(json!("Bearer ghp_second")));
+
+    let listed = McpService::list_secrets(&state).expect("list secrets");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].name, "GITHUB_TOKEN");
+
+    let err = McpService::delete_secret(&state, "GITHUB_TOKEN").expect_err("secret in use");
+    assert!(err.to_string().contains("github"), "{err}");
+    assert!(McpService::set_secret(&state, "bad name", "x").is_err());
+
+    McpService::delete_server(&state, "github").expect("delete server");
+    assert!(McpService::delete_secret(&state, "GITHUB_TOKEN").expect("delete unused secret"));
+}
+
+#[test]
+fn server_with_missing_secret_needs_config_and_is_not_written_to_live() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    fs::create_dir_all(home.join(".claude")).expect("create ~/.claude dir");
+
+    // 模拟从其他设备同步来的服务器：数据库里有定义，本机密钥库里没有密钥
+    let state = create_test_state().expect("create test state");
+    let synced = McpServer {
+        id: "github".to_string(),
+        name: "GitHub".to_string(),
+        server: json!({
+            "type": "http",
+            "url": "https://api.githubcopilot.com/mcp/",
+            "headers": { "Authorization": "Bearer ${secret:GITHUB_TOKEN}" }
+        }),
+        apps: McpApps {
+            claude: true,
+            ..McpApps::default()
+        },
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+    };
+    let plain = McpServer {
+        id: "plain".to_string(),
+        name: "Plain".to_string(),
+        server: json!({ "type": "stdio", "command": "echo" }),
+        ..synced.clone()
+    };
+    state
+        .db
+        .save_mcp_server(&synced)
+        .expect("seed synced server");
+    state.db.save_mcp_server(&plain).expect("seed plain server");
+
+    McpService::sync_all_enabled(&state).expect("missing secret does not fail the sync");
+
+    let live_servers = || {
+        let text = fs::read_to_string(get_claude_mcp_path()).expect("read ~/.claude.json");
+        let value: serde_json::Value = serde_json::from_str(&text).expect("parse ~/.claude.json");
+        value["mcpServers"].clone()
+    };
+    assert!(
+        live_servers().get("github").is_none(),
+        "not written with a placeholder"
+    );
+    assert!(
+        live_servers().get("plain").is_some(),
+        "other servers still sync"
+    );
+
+    let needing = McpService::get_servers_needing_config(&state).expect("servers needing config");
+    assert_eq!(needing.len(), 1);
+    assert_eq!(needing["github"], vec!["GITHUB_TOKEN".to_string()]);
+
+    // 补齐密钥后服务器自动写入 live 配置
+    McpService::set_secret(&state, "GITHUB_TOKEN", "ghp_local").expect("save secret");
+    assert_eq!(
+        live_servers().pointer("/github/headers/Authorization"),
+        Some(&json!("Bearer ghp_local"))
+    );
+    assert!(McpService::get_servers_needing_config(&state)
+        .expect("servers needing config")
+        .is_empty());
+}
@@ -61,6 +61,7 @@ fn prompt(id: &str, enabled: bool) -> Prompt {
         content: format!("# prompt {id}\n"),
         description: None,
         enabled,
+        sort_index: None,
         created_at: Some(1_000),
         updated_at: Some(1_000),
     }
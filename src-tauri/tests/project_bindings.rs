@@ -0,0 +1,137 @@
+use serde_json::json;
+
+use cc_switch_lib::{
+    get_claude_mcp_path, read_json_file, AppType, McpApps, McpServer, ProjectBindingService,
+    Provider,
+};
+
+#[path = "support.rs"]
+mod support;
+use support::{create_test_state, ensure_test_home, reset_test_fs, test_mutex};
+
+#[test]
+fn bind_claude_project_writes_local_settings_and_unbind_reverts() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let project = home.join("projects").join("demo");
+    let _ = std::fs::remove_dir_all(&project);
+    std::fs::create_dir_all(project.join(".git").join("info")).expect("create project dir");
+    let project = project.canonicalize().expect("canonicalize project");
+    let project_key = project.to_string_lossy().to_string();
+
+    // 用户自己的 ~/.claude.json 内容必须保留
+    std::fs::write(
+        get_claude_mcp_path(),
+        serde_json::to_string_pretty(&json!({ "numStartups": 3 })).unwrap(),
+    )
+    .expect("seed claude.json");
+
+    let state = create_test_state().expect("create test state");
+    state
+        .db
+        .save_provider(
+            "claude",
+            &Provider::with_id(
+                "work".to_string(),
+                "Work".to_string(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-work" } }),
+                None,
+            ),
+        )
+        .expect("seed provider");
+    state
+        .db
+        .save_mcp_server(&McpServer {
+            id: "fetch".to_string(),
+            name: "fetch".to_string(),
+            server: json!({ "type": "stdio", "command": "uvx", "args": ["mcp-server-fetch"] }),
+            apps: McpApps::default(),
+            description: None,
+            homepage: None,
+            docs: None,
+            tags: Vec::new(),
+        })
+        .expect("seed mcp server");
+
+    let binding = ProjectBindingService::bind(
+        &state,
+        &project_key,
+        AppType::Claude,
+        Some("work".to_string()),
+        vec!["fetch".to_string()],
+    )
+    .expect("bind project");
+    assert_eq!(binding.path, project_key);
+
+    let local_settings = project.join(".claude").join("settings.local.json");
+    let settings: serde_json::Value = read_json_file(&local_settings).expect("read local settings");
+    assert_eq!(settings["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-work");
+
+    let claude_json: serde_json::Value =
+        read_json_file(&get_claude_mcp_path()).expect("read claude.json");
+    assert_eq!(claude_json["numStartups"], 3);
+    assert_eq!(
+        claude_json["projects"][&project_key]["mcpServers"]["fetch"]["command"],
+        "uvx"
+    );
+
+    let exclude = std::fs::read_to_string(project.join(".git").join("info").join("exclude"))
+        .expect("read git exclude");
+    assert!(
+        exclude.contains("/.claude/settings.local.json"),
+        "{exclude}"
+    );
+
+    assert_eq!(ProjectBindingService::list(&state).unwrap().len(), 1);
+
+    // 用户在绑定后追加的设置在解绑时保留
+    let mut edited = settings.clone();
+    edited["permissions"] = json!({ "allow": ["Bash(ls)"] });
+    std::fs::write(
+        &local_settings,
+        serde_json::to_string_pretty(&edited).unwrap(),
+    )
+    .expect("edit local settings");
+
+    assert!(ProjectBindingService::unbind(&state, &project_key, AppType::Claude).unwrap());
+    assert!(ProjectBindingService::list(&state).unwrap().is_empty());
+
+    let settings: serde_json::Value = read_json_file(&local_settings).expect("read local settings");
+    assert!(settings.get("env").is_none(), "{settings}");
+    assert_eq!(settings["permissions"]["allow"][0], "Bash(ls)");
+
+    let claude_json: serde_json::Value =
+        read_json_file(&get_claude_mcp_path()).expect("read claude.json");
+    assert_eq!(claude_json, json!({ "numStartups": 3 }));
+
+    assert!(!ProjectBindingService::unbind(&state, &project_key, AppType::Claude).unwrap());
+}
+
+#[test]
+fn bind_rejects_unsupported_app_and_relative_path() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let state = create_test_state().expect("create test state");
+
+    let err = ProjectBindingService::bind(
+        &state,
+        &home.to_string_lossy(),
+        AppType::OpenCode,
+        Some("any".to_string()),
+        Vec::new(),
+    )
+    .expect_err("opencode has no project-level config");
+    assert!(err.to_string().contains("opencode"), "{err}");
+
+    assert!(ProjectBindingService::bind(
+        &state,
+        "relative/dir",
+        AppType::Claude,
+        Some("any".to_string()),
+        Vec::new(),
+    )
+    .is_err());
+}
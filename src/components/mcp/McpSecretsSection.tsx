@@ -0,0 +1,130 @@
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { ChevronDown, ChevronRight, KeyRound, Trash2 } from "lucide-react";
+import { toast } from "sonner";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  Collapsible,
+  CollapsibleContent,
+  CollapsibleTrigger,
+} from "@/components/ui/collapsible";
+import {
+  useDeleteMcpSecret,
+  useMcpSecrets,
+  useSetMcpSecret,
+} from "@/hooks/useMcp";
+
+interface McpSecretsSectionProps {
+  disabled?: boolean;
+}
+
+/**
+ * 本地密钥库：MCP 服务器定义中以 ${secret:NAME} 引用，值只保存在本机
+ */
+export function McpSecretsSection({ disabled }: McpSecretsSectionProps) {
+  const { t } = useTranslation();
+  const [open, setOpen] = useState(false);
+  const [name, setName] = useState("");
+  const [value, setValue] = useState("");
+  const { data: secrets = [] } = useMcpSecrets();
+  const setMutation = useSetMcpSecret();
+  const deleteMutation = useDeleteMcpSecret();
+  const busy = disabled || setMutation.isPending || deleteMutation.isPending;
+
+  const handleSave = async () => {
+    const trimmed = name.trim();
+    if (!trimmed || !value) return;
+    try {
+      await setMutation.mutateAsync({ name: trimmed, value });
+      setName("");
+      setValue("");
+      toast.success(t("mcp.secrets.saved", { name: trimmed }), {
+        closeButton: true,
+      });
+    } catch (error) {
+      toast.error(t("common.error"), { description: String(error) });
+    }
+  };
+
+  const handleDelete = async (secretName: string) => {
+    try {
+      await deleteMutation.mutateAsync(secretName);
+    } catch (error) {
+      toast.error(t("common.error"), { description: String(error) });
+    }
+  };
+
+  return (
+    <Collapsible
+      open={open}
+      onOpenChange={setOpen}
+      className="mb-3 rounded-xl border border-border bg-card/50 p-4"
+    >
+      <CollapsibleTrigger asChild>
+        <Button
+          type="button"
+          variant={null}
+          size="sm"
+          className="h-8 w-full justify-start gap-1.5 px-0 text-sm font-medium text-foreground hover:opacity-70"
+        >
+          {open ? (
+            <ChevronDown className="h-4 w-4" />
+          ) : (
+            <ChevronRight className="h-4 w-4" />
+          )}
+          <KeyRound className="h-4 w-4 text-muted-foreground" />
+          {t("mcp.secrets.title", { count: secrets.length })}
+        </Button>
+      </CollapsibleTrigger>
+      <CollapsibleContent className="space-y-3 pt-2">
+        <p className="text-xs text-muted-foreground">
+          {t("mcp.secrets.description")}
+        </p>
+        {secrets.map((secret) => (
+          <div
+            key={secret.name}
+            className="flex items-center justify-between gap-2 text-sm"
+          >
+            <code className="truncate">{`\${secret:${secret.name}}`}</code>
+            <Button
+              type="button"
+              variant="ghost"
+              size="icon"
+              className="h-7 w-7"
+              disabled={busy}
+              onClick={() => handleDelete(secret.name)}
+              aria-label={t("common.delete")}
+            >
+              <Trash2 className="h-3.5 w-3.5" />
+            </Button>
+          </div>
+        ))}
+        <div className="flex gap-2">
+          <Input
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            placeholder={t("mcp.secrets.namePlaceholder")}
+            disabled={busy}
+          />
+          <Input
+            type="password"
+            value={value}
+            onChange={(e) => setValue(e.target.value)}
+            placeholder={t("mcp.secrets.valuePlaceholder")}
+            disabled={busy}
+            autoComplete="off"
+          />
+          <Button
+            type="button"
+            size="sm"
+            onClick={handleSave}
+            disabled={busy || !name.trim() || !value}
+          >
+            {t("common.save")}
+          </Button>
+        </div>
+      </CollapsibleContent>
+    </Collapsible>
+  );
+}
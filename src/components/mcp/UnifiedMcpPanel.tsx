@@ -1,6 +1,15 @@
 import React, { useMemo, useState } from "react";
 import { useTranslation } from "react-i18next";
-import { Edit3, ExternalLink, Search, Server, Trash2 } from "lucide-react";
+import {
+  Activity,
+  Edit3,
+  ExternalLink,
+  Loader2,
+  Network,
+  Search,
+  Server,
+  Trash2,
+} from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { TooltipProvider } from "@/components/ui/tooltip";
@@ -10,10 +19,16 @@ import {
   useToggleMcpApp,
   useDeleteMcpServer,
   useImportMcpFromApps,
+  useMcpProbeResults,
+  useMcpServersNeedingConfig,
+  useProbeMcpServer,
+  useMcpGatewayEnabled,
+  useSetMcpGatewayEnabled,
 } from "@/hooks/useMcp";
-import type { McpServer } from "@/types";
+import type { McpProbeResult, McpServer } from "@/types";
 import type { AppId } from "@/lib/api/types";
 import McpFormModal from "./McpFormModal";
+import { McpSecretsSection } from "./McpSecretsSection";
 import { ConfirmDialog } from "../ConfirmDialog";
 import { settingsApi } from "@/lib/api";
 import { mcpPresets } from "@/config/mcpPresets";
@@ -22,6 +37,7 @@ import { isMcpAppId, MCP_APP_IDS } from "@/config/appConfig";
 import { AppCountBar } from "@/components/common/AppCountBar";
 import { AppToggleGroup } from "@/components/common/AppToggleGroup";
 import { ListItemRow } from "@/components/common/ListItemRow";
+import { ToggleRow } from "@/components/ui/toggle-row";
 import { ManagementListSearch } from "@/components/common/ManagementListSearch";
 
 function getMcpSearchText(id: string, server: McpServer): string {
@@ -82,12 +98,19 @@ const UnifiedMcpPanel = React.forwardRef<
   const bulkToggleAppMutation = useBulkToggleMcpApp();
   const deleteServerMutation = useDeleteMcpServer();
   const importMutation = useImportMcpFromApps();
+  const { data: probeResults } = useMcpProbeResults();
+  const { data: serversNeedingConfig } = useMcpServersNeedingConfig();
+  const probeMutation = useProbeMcpServer();
+  const probingId = probeMutation.isPending ? probeMutation.variables : null;
+  const { data: gatewayEnabled } = useMcpGatewayEnabled();
+  const gatewayMutation = useSetMcpGatewayEnabled();
 
   const mutationPending =
     toggleAppMutation.isPending ||
     bulkToggleAppMutation.isPending ||
     deleteServerMutation.isPending ||
-    importMutation.isPending;
+    importMutation.isPending ||
+    gatewayMutation.isPending;
   const interactionBlocked =
     writePending || mutationPending || isFormOpen || confirmDialog !== null;
 
@@ -267,6 +290,42 @@ const UnifiedMcpPanel = React.forwardRef<
     });
   };
 
+  const handleProbe = async (id: string) => {
+    try {
+      const result = await probeMutation.mutateAsync(id);
+      if (result.status === "ok") {
+        toast.success(
+          t("mcp.probe.success", {
+            version: result.serverVersion ?? "?",
+            count: result.tools.length,
+          }),
+          { closeButton: true },
+        );
+      } else {
+        toast.error(t(`mcp.probe.${result.status}`), {
+          description: result.error,
+        });
+      }
+    } catch (error) {
+      toast.error(t("common.error"), { description: String(error) });
+    }
+  };
+
+  const handleToggleGateway = async (enabled: boolean) => {
+    if (!beginWrite()) return;
+    try {
+      await gatewayMutation.mutateAsync(enabled);
+      toast.success(
+        t(enabled ? "mcp.gateway.enabled" : "mcp.gateway.disabled"),
+        { closeButton: true },
+      );
+    } catch (error) {
+      toast.error(t("common.error"), { description: String(error) });
+    } finally {
+      endWrite();
+    }
+  };
+
   const handleCloseForm = () => {
     setIsFormOpen(false);
     setEditingId(null);
@@ -284,6 +343,19 @@ const UnifiedMcpPanel = React.forwardRef<
         disabled={interactionBlocked}
       />
 
+      <div className="mb-3">
+        <ToggleRow
+          icon={<Network className="h-4 w-4 text-muted-foreground" />}
+          title={t("mcp.gateway.title")}
+          description={t("mcp.gateway.description")}
+          checked={gatewayEnabled ?? false}
+          onCheckedChange={handleToggleGateway}
+          disabled={interactionBlocked}
+        />
+      </div>
+
+      <McpSecretsSection disabled={interactionBlocked} />
+
       <ManagementListSearch
         value={searchQuery}
         onValueChange={setSearchQuery}
@@ -323,6 +395,10 @@ const UnifiedMcpPanel = React.forwardRef<
                     key={id}
                     id={id}
                     server={server}
+                    probe={probeResults?.[id]}
+                    missingSecrets={serversNeedingConfig?.[id]}
+                    probing={probingId === id}
+                    onProbe={handleProbe}
                     onToggleApp={handleToggleApp}
                     onEdit={handleEdit}
                     onDelete={handleDelete}
@@ -371,6 +447,11 @@ UnifiedMcpPanel.displayName = "UnifiedMcpPanel";
 interface UnifiedMcpListItemProps {
   id: string;
   server: McpServer;
+  probe?: McpProbeResult;
+  /** 本机缺失的密钥；非空时该服务器不会写入各应用配置 */
+  missingSecrets?: string[];
+  probing?: boolean;
+  onProbe: (id: string) => void;
   onToggleApp: (serverId: string, app: AppId, enabled: boolean) => void;
   onEdit: (id: string) => void;
   onDelete: (id: string) => void;
@@ -381,6 +462,10 @@ interface UnifiedMcpListItemProps {
 const UnifiedMcpListItem: React.FC<UnifiedMcpListItemProps> = ({
   id,
   server,
+  probe,
+  missingSecrets,
+  probing,
+  onProbe,
   onToggleApp,
   onEdit,
   onDelete,
@@ -396,6 +481,15 @@ const UnifiedMcpListItem: React.FC<UnifiedMcpListItemProps> = ({
   const homepageUrl = server.homepage || meta?.homepage;
   const tags = server.tags || meta?.tags;
 
+  const probeTitle = probe
+    ? probe.status === "ok"
+      ? t("mcp.probe.okTitle", {
+          version: probe.serverVersion ?? "?",
+          tools: probe.tools.join(", ") || "-",
+        })
+      : `${t(`mcp.probe.${probe.status}`)}${probe.error ? `: ${probe.error}` : ""}`
+    : undefined;
+
   const openDocs = async () => {
     const url = docsUrl || homepageUrl;
     if (!url) return;
@@ -413,6 +507,24 @@ const UnifiedMcpListItem: React.FC<UnifiedMcpListItemProps> = ({
           <span className="font-medium text-sm text-foreground truncate">
             {name}
           </span>
+          {probe && (
+            <span
+              className={`h-2 w-2 rounded-full flex-shrink-0 ${
+                probe.status === "ok" ? "bg-green-500" : "bg-red-500"
+              }`}
+              title={probeTitle}
+            />
+          )}
+          {missingSecrets && missingSecrets.length > 0 && (
+            <span
+              className="flex-shrink-0 rounded px-1.5 py-0.5 text-[10px] font-medium bg-amber-500/15 text-amber-600 dark:text-amber-400"
+              title={t("mcp.secrets.needsConfigTitle", {
+                names: missingSecrets.join(", "),
+              })}
+            >
+              {t("mcp.secrets.needsConfig")}
+            </span>
+          )}
           {docsUrl && (
             <button
               type="button"
@@ -447,6 +559,21 @@ const UnifiedMcpListItem: React.FC<UnifiedMcpListItemProps> = ({
       />
 
       <div className="flex items-center gap-0.5 flex-shrink-0 opacity-0 group-hover:opacity-100 transition-opacity">
+        <Button
+          type="button"
+          variant="ghost"
+          size="icon"
+          className="h-7 w-7 disabled:opacity-100"
+          onClick={() => onProbe(id)}
+          disabled={probing}
+          title={t("mcp.probe.action")}
+        >
+          {probing ? (
+            <Loader2 size={14} className="animate-spin" />
+          ) : (
+            <Activity size={14} />
+          )}
+        </Button>
         <Button
           type="button"
           variant="ghost"
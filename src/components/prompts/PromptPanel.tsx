@@ -6,6 +6,7 @@ import { useTauriEvent } from "@/hooks/useTauriEvent";
 import PiPromptPanel, { type PromptPrimaryAction } from "./PiPromptPanel";
 import PromptFormPanel from "./PromptFormPanel";
 import { PromptLibrary } from "./PromptLibrary";
+import { SharedPromptsSection } from "./SharedPromptsSection";
 import { ConfirmDialog } from "../ConfirmDialog";
 
 interface PromptPanelProps {
@@ -50,6 +51,7 @@ const StandardPromptPanel = React.forwardRef<
     } | null>(null);
     const [writePending, setWritePending] = useState(false);
     const [reloadPending, setReloadPending] = useState(false);
+    const [sharedBlocked, setSharedBlocked] = useState(false);
     const writeLockRef = React.useRef(false);
     const reloadLockRef = React.useRef(false);
     const reloadRunGenerationRef = React.useRef(0);
@@ -68,9 +70,11 @@ const StandardPromptPanel = React.forwardRef<
     reloadRef.current = reload;
 
     const dialogOpen = confirmDialog !== null;
-    const interactionBlocked =
+    const libraryBlocked =
       loading || reloadPending || writePending || isFormOpen || dialogOpen;
-    const navigationBlocked = writePending || isFormOpen || dialogOpen;
+    const interactionBlocked = libraryBlocked || sharedBlocked;
+    const navigationBlocked =
+      writePending || isFormOpen || dialogOpen || sharedBlocked;
 
     useEffect(() => {
       onInteractionBlockedChange?.(interactionBlocked);
@@ -258,6 +262,14 @@ const StandardPromptPanel = React.forwardRef<
 
     return (
       <div className="flex flex-col flex-1 min-h-0 px-6">
+        <SharedPromptsSection
+          appId={appId}
+          open={open}
+          disabled={libraryBlocked}
+          onBlockedChange={setSharedBlocked}
+          onChanged={runExternalReload}
+        />
+
         <PromptLibrary
           prompts={prompts}
           loading={loading}
@@ -0,0 +1,219 @@
+import React, { useEffect, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import MarkdownEditor from "@/components/MarkdownEditor";
+import { FullScreenPanel } from "@/components/common/FullScreenPanel";
+import { useDarkMode } from "@/hooks/useDarkMode";
+import type { AppId, PromptApps, SharedPrompt } from "@/lib/api";
+
+export const SHARED_PROMPT_APPS: (keyof PromptApps)[] = [
+  "claude",
+  "codex",
+  "gemini",
+  "grokbuild",
+  "opencode",
+  "openclaw",
+  "hermes",
+];
+
+export function isSharedPromptApp(appId: AppId): appId is keyof PromptApps {
+  return (SHARED_PROMPT_APPS as string[]).includes(appId);
+}
+
+interface SharedPromptFormPanelProps {
+  appId: AppId;
+  initialData?: SharedPrompt;
+  onSave: (prompt: SharedPrompt) => Promise<boolean>;
+  onClose: () => void;
+}
+
+const SharedPromptFormPanel: React.FC<SharedPromptFormPanelProps> = ({
+  appId,
+  initialData,
+  onSave,
+  onClose,
+}) => {
+  const { t } = useTranslation();
+  const appendixApp = isSharedPromptApp(appId) ? appId : null;
+  const [name, setName] = useState("");
+  const [description, setDescription] = useState("");
+  const [content, setContent] = useState("");
+  const [appendix, setAppendix] = useState("");
+  const [apps, setApps] = useState<PromptApps>(() => ({
+    claude: false,
+    codex: false,
+    gemini: false,
+    grokbuild: false,
+    opencode: false,
+    openclaw: false,
+    hermes: false,
+    ...(appendixApp ? { [appendixApp]: true } : {}),
+  }));
+  const [saving, setSaving] = useState(false);
+  const savingRef = useRef(false);
+  const isDarkMode = useDarkMode();
+
+  useEffect(() => {
+    if (initialData) {
+      setName(initialData.name);
+      setDescription(initialData.description || "");
+      setContent(initialData.content);
+      setApps(initialData.apps);
+      setAppendix(
+        (appendixApp && initialData.appendices?.[appendixApp]) || "",
+      );
+    }
+  }, [appendixApp, initialData]);
+
+  const handleSave = async () => {
+    if (savingRef.current || !name.trim()) {
+      return;
+    }
+
+    savingRef.current = true;
+    setSaving(true);
+    try {
+      const timestamp = Math.floor(Date.now() / 1000);
+      // 只改当前应用的附录，其余应用的附录原样保留
+      const appendices = { ...initialData?.appendices };
+      if (appendixApp) {
+        if (appendix.trim()) {
+          appendices[appendixApp] = appendix.trim();
+        } else {
+          delete appendices[appendixApp];
+        }
+      }
+      const prompt: SharedPrompt = {
+        id: initialData?.id || `shared-${Date.now()}`,
+        name: name.trim(),
+        description: description.trim() || undefined,
+        content: content.trim(),
+        apps,
+        appendices,
+        sortIndex: initialData?.sortIndex,
+        createdAt: initialData?.createdAt || timestamp,
+        updatedAt: timestamp,
+      };
+      if (await onSave(prompt)) {
+        onClose();
+      }
+    } finally {
+      savingRef.current = false;
+      setSaving(false);
+    }
+  };
+
+  const handleClose = () => {
+    if (!savingRef.current) onClose();
+  };
+
+  return (
+    <FullScreenPanel
+      isOpen={true}
+      title={
+        initialData
+          ? t("prompts.shared.editTitle")
+          : t("prompts.shared.addTitle")
+      }
+      onClose={handleClose}
+      footer={
+        <Button
+          type="button"
+          onClick={handleSave}
+          disabled={!name.trim() || saving}
+          className="bg-primary text-primary-foreground hover:bg-primary/90 disabled:opacity-50 disabled:cursor-not-allowed"
+        >
+          {saving ? t("common.saving") : t("common.save")}
+        </Button>
+      }
+    >
+      <div className="glass rounded-xl p-6 border border-white/10 space-y-6">
+        <div>
+          <Label htmlFor="shared-name" className="text-foreground">
+            {t("prompts.name")}
+          </Label>
+          <Input
+            id="shared-name"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            disabled={saving}
+            placeholder={t("prompts.namePlaceholder")}
+            className="mt-2"
+          />
+        </div>
+
+        <div>
+          <Label htmlFor="shared-description" className="text-foreground">
+            {t("prompts.description")}
+          </Label>
+          <Input
+            id="shared-description"
+            value={description}
+            onChange={(e) => setDescription(e.target.value)}
+            disabled={saving}
+            placeholder={t("prompts.descriptionPlaceholder")}
+            className="mt-2"
+          />
+        </div>
+
+        <div>
+          <Label className="text-foreground">{t("prompts.shared.apps")}</Label>
+          <div className="mt-2 flex flex-wrap gap-4">
+            {SHARED_PROMPT_APPS.map((app) => (
+              <label
+                key={app}
+                className="flex items-center gap-2 text-sm text-foreground"
+              >
+                <Checkbox
+                  checked={apps[app]}
+                  disabled={saving}
+                  onCheckedChange={(checked) =>
+                    setApps((current) => ({ ...current, [app]: checked }))
+                  }
+                />
+                {t(`apps.${app}`)}
+              </label>
+            ))}
+          </div>
+        </div>
+
+        <div>
+          <Label className="block mb-2 text-foreground">
+            {t("prompts.shared.content")}
+          </Label>
+          <MarkdownEditor
+            value={content}
+            onChange={setContent}
+            placeholder={t("prompts.shared.contentPlaceholder")}
+            darkMode={isDarkMode}
+            readOnly={saving}
+            minHeight="167px"
+          />
+        </div>
+
+        {appendixApp && (
+          <div>
+            <Label className="block mb-2 text-foreground">
+              {t("prompts.shared.appendix", {
+                appName: t(`apps.${appendixApp}`),
+              })}
+            </Label>
+            <MarkdownEditor
+              value={appendix}
+              onChange={setAppendix}
+              placeholder={t("prompts.shared.appendixPlaceholder")}
+              darkMode={isDarkMode}
+              readOnly={saving}
+              minHeight="100px"
+            />
+          </div>
+        )}
+      </div>
+    </FullScreenPanel>
+  );
+};
+
+export default SharedPromptFormPanel;
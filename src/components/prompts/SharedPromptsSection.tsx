@@ -0,0 +1,171 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Edit3, Plus, Share2, Trash2 } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import type { AppId, SharedPrompt } from "@/lib/api";
+import { useSharedPrompts } from "@/hooks/useSharedPrompts";
+import { ConfirmDialog } from "../ConfirmDialog";
+import SharedPromptFormPanel, {
+  SHARED_PROMPT_APPS,
+} from "./SharedPromptFormPanel";
+
+interface SharedPromptsSectionProps {
+  appId: AppId;
+  open: boolean;
+  disabled?: boolean;
+  /** 表单或确认框打开、或正在写入时为 true */
+  onBlockedChange?: (blocked: boolean) => void;
+  /** 共享提示词写入后当前应用的提示词文件可能已变化 */
+  onChanged?: () => void;
+}
+
+export function SharedPromptsSection({
+  appId,
+  open,
+  disabled = false,
+  onBlockedChange,
+  onChanged,
+}: SharedPromptsSectionProps) {
+  const { t } = useTranslation();
+  const { sharedPrompts, reload, saveSharedPrompt, deleteSharedPrompt } =
+    useSharedPrompts();
+  const [editing, setEditing] = useState<SharedPrompt | "new" | null>(null);
+  const [deleting, setDeleting] = useState<SharedPrompt | null>(null);
+  const [pending, setPending] = useState(false);
+
+  const blocked = editing !== null || deleting !== null || pending;
+
+  useEffect(() => {
+    onBlockedChange?.(blocked);
+  }, [blocked, onBlockedChange]);
+
+  useEffect(
+    () => () => {
+      onBlockedChange?.(false);
+    },
+    [onBlockedChange],
+  );
+
+  useEffect(() => {
+    if (open) void reload();
+  }, [open, reload]);
+
+  const handleSave = async (prompt: SharedPrompt) => {
+    setPending(true);
+    try {
+      await saveSharedPrompt(prompt);
+      onChanged?.();
+      return true;
+    } catch {
+      // Error handled by hook
+      return false;
+    } finally {
+      setPending(false);
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!deleting) return;
+    setPending(true);
+    try {
+      await deleteSharedPrompt(deleting.id);
+      onChanged?.();
+      setDeleting(null);
+    } catch {
+      // Error handled by hook
+    } finally {
+      setPending(false);
+    }
+  };
+
+  const entries = Object.values(sharedPrompts);
+
+  return (
+    <div className="mb-4 flex-shrink-0 space-y-2 rounded-xl border border-white/10 px-6 py-4 glass">
+      <div className="flex items-center justify-between gap-2">
+        <div className="flex items-center gap-2 text-sm font-medium text-foreground">
+          <Share2 size={14} className="text-muted-foreground" />
+          {t("prompts.shared.title")}
+        </div>
+        <Button
+          type="button"
+          variant="ghost"
+          size="sm"
+          disabled={disabled || blocked}
+          onClick={() => setEditing("new")}
+        >
+          <Plus size={14} />
+          {t("prompts.shared.add")}
+        </Button>
+      </div>
+
+      {entries.length === 0 ? (
+        <p className="text-xs text-muted-foreground">
+          {t("prompts.shared.empty")}
+        </p>
+      ) : (
+        entries.map((prompt) => (
+          <div
+            key={prompt.id}
+            data-testid={`shared-prompt-${prompt.id}`}
+            className="flex items-center gap-3 rounded-lg border border-border-default bg-muted/50 px-3 py-2"
+          >
+            <div className="min-w-0 flex-1">
+              <div className="truncate text-sm text-foreground">
+                {prompt.name}
+              </div>
+              <div className="truncate text-xs text-muted-foreground">
+                {SHARED_PROMPT_APPS.filter((app) => prompt.apps[app])
+                  .map((app) => t(`apps.${app}`))
+                  .join(", ") || t("prompts.shared.noApps")}
+              </div>
+            </div>
+            <Button
+              type="button"
+              variant="ghost"
+              size="icon"
+              disabled={disabled || blocked}
+              onClick={() => setEditing(prompt)}
+              title={t("prompts.shared.edit")}
+            >
+              <Edit3 size={16} />
+            </Button>
+            <Button
+              type="button"
+              variant="ghost"
+              size="icon"
+              disabled={disabled || blocked}
+              onClick={() => setDeleting(prompt)}
+              className="hover:text-red-500 hover:bg-red-100 dark:hover:text-red-400 dark:hover:bg-red-500/10"
+              title={t("prompts.shared.delete")}
+            >
+              <Trash2 size={16} />
+            </Button>
+          </div>
+        ))
+      )}
+
+      {editing && (
+        <SharedPromptFormPanel
+          appId={appId}
+          initialData={editing === "new" ? undefined : editing}
+          onSave={handleSave}
+          onClose={() => setEditing(null)}
+        />
+      )}
+
+      {deleting && (
+        <ConfirmDialog
+          isOpen={true}
+          title={t("prompts.confirm.deleteTitle")}
+          message={t("prompts.shared.deleteMessage", { name: deleting.name })}
+          pending={pending}
+          onConfirm={handleDelete}
+          onCancel={() => {
+            if (!pending) setDeleting(null);
+          }}
+        />
+      )}
+    </div>
+  );
+}
@@ -474,6 +474,14 @@ export function ProviderCard({
                 />
               )}
 
+              {provider.meta?.secretsLocked && (
+                <ProviderStatusBadge
+                  tone="warning"
+                  label={t("provider.secretsLocked")}
+                  title={t("provider.secretsLockedHint")}
+                />
+              )}
+
               {appId === "claude" && provider.category === "official" && (
                 <ProviderStatusBadge
                   label={t("provider.noRoutingSupport", {
@@ -4,9 +4,24 @@ import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Alert, AlertDescription } from "@/components/ui/alert";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { Save, Loader2, Info } from "lucide-react";
 import { toast } from "sonner";
 import { useAppProxyConfig, useUpdateAppProxyConfig } from "@/lib/query/proxy";
+import type { RoutingStrategy } from "@/types/proxy";
+
+const ROUTING_STRATEGIES: RoutingStrategy[] = [
+  "priority",
+  "round_robin",
+  "weighted",
+  "least_latency",
+];
 
 export interface AutoFailoverConfigPanelProps {
   appType: string;
@@ -33,6 +48,8 @@ export function AutoFailoverConfigPanel({
     circuitTimeoutSeconds: "60",
     circuitErrorRateThreshold: "50", // 存储百分比值
     circuitMinRequests: "10",
+    quotaThreshold: "0",
+    routingStrategy: "priority" as RoutingStrategy,
   });
 
   useEffect(() => {
@@ -50,6 +67,8 @@ export function AutoFailoverConfigPanel({
           Math.round(config.circuitErrorRateThreshold * 100),
         ),
         circuitMinRequests: String(config.circuitMinRequests),
+        quotaThreshold: String(config.quotaThreshold ?? 0),
+        routingStrategy: config.routingStrategy ?? "priority",
       });
     }
   }, [config]);
@@ -75,6 +94,7 @@ export function AutoFailoverConfigPanel({
       circuitTimeoutSeconds: { min: 0, max: 300 },
       circuitErrorRateThreshold: { min: 0, max: 100 },
       circuitMinRequests: { min: 5, max: 100 },
+      quotaThreshold: { min: 0, max: 100 },
     };
 
     // 解析原始值
@@ -88,6 +108,7 @@ export function AutoFailoverConfigPanel({
       circuitTimeoutSeconds: parseNum(formData.circuitTimeoutSeconds),
       circuitErrorRateThreshold: parseNum(formData.circuitErrorRateThreshold),
       circuitMinRequests: parseNum(formData.circuitMinRequests),
+      quotaThreshold: parseNum(formData.quotaThreshold),
     };
 
     // 校验是否超出范围（NaN 也视为无效）
@@ -147,6 +168,11 @@ export function AutoFailoverConfigPanel({
       ranges.circuitMinRequests,
       t("proxy.autoFailover.minRequests", "最小请求数"),
     );
+    checkRange(
+      raw.quotaThreshold,
+      ranges.quotaThreshold,
+      t("proxy.autoFailover.quotaThreshold", "额度阈值"),
+    );
 
     if (errors.length > 0) {
       toast.error(
@@ -172,6 +198,9 @@ export function AutoFailoverConfigPanel({
         circuitTimeoutSeconds: raw.circuitTimeoutSeconds,
         circuitErrorRateThreshold: raw.circuitErrorRateThreshold / 100,
         circuitMinRequests: raw.circuitMinRequests,
+        routingStrategy: formData.routingStrategy,
+        modelRoutes: config.modelRoutes,
+        quotaThreshold: raw.quotaThreshold,
       });
       toast.success(
         t("proxy.autoFailover.configSaved", "自动故障转移配置已保存"),
@@ -199,6 +228,8 @@ export function AutoFailoverConfigPanel({
           Math.round(config.circuitErrorRateThreshold * 100),
         ),
         circuitMinRequests: String(config.circuitMinRequests),
+        quotaThreshold: String(config.quotaThreshold ?? 0),
+        routingStrategy: config.routingStrategy ?? "priority",
       });
     }
   };
@@ -232,6 +263,46 @@ export function AutoFailoverConfigPanel({
           </AlertDescription>
         </Alert>
 
+        {/* 路由策略 */}
+        <div className="space-y-4 rounded-lg border border-white/10 bg-muted/30 p-4">
+          <h4 className="text-sm font-semibold">
+            {t("proxy.autoFailover.routingSettings", "路由策略")}
+          </h4>
+
+          <div className="space-y-2">
+            <Label htmlFor={`routingStrategy-${appType}`}>
+              {t("proxy.autoFailover.routingStrategy", "队列路由策略")}
+            </Label>
+            <Select
+              value={formData.routingStrategy}
+              onValueChange={(value) =>
+                setFormData({
+                  ...formData,
+                  routingStrategy: value as RoutingStrategy,
+                })
+              }
+              disabled={isDisabled}
+            >
+              <SelectTrigger id={`routingStrategy-${appType}`}>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {ROUTING_STRATEGIES.map((strategy) => (
+                  <SelectItem key={strategy} value={strategy}>
+                    {t(`proxy.routingStrategy.${strategy}`)}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <p className="text-xs text-muted-foreground">
+              {t(
+                "proxy.autoFailover.routingStrategyHint",
+                "决定故障转移队列中首选供应商的选择方式；加权策略的权重在故障转移队列中设置",
+              )}
+            </p>
+          </div>
+        </div>
+
         {/* 重试与超时配置 */}
         <div className="space-y-4 rounded-lg border border-white/10 bg-muted/30 p-4">
           <h4 className="text-sm font-semibold">
@@ -287,6 +358,30 @@ export function AutoFailoverConfigPanel({
                 )}
               </p>
             </div>
+
+            <div className="space-y-2">
+              <Label htmlFor={`quotaThreshold-${appType}`}>
+                {t("proxy.autoFailover.quotaThreshold", "额度阈值 (%)")}
+              </Label>
+              <Input
+                id={`quotaThreshold-${appType}`}
+                type="number"
+                min="0"
+                max="100"
+                step="5"
+                value={formData.quotaThreshold}
+                onChange={(e) =>
+                  setFormData({ ...formData, quotaThreshold: e.target.value })
+                }
+                disabled={isDisabled}
+              />
+              <p className="text-xs text-muted-foreground">
+                {t(
+                  "proxy.autoFailover.quotaThresholdHint",
+                  "订阅或套餐的任一额度窗口用量达到该百分比时，该供应商在窗口重置前排到队列末尾；0 表示关闭",
+                )}
+              </p>
+            </div>
           </div>
         </div>
 
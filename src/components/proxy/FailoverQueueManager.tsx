@@ -4,13 +4,15 @@
  * 允许用户管理代理模式下的故障转移队列，支持：
  * - 添加/移除供应商
  * - 队列顺序基于首页供应商列表的 sort_index
+ * - 加权路由策略下设置各供应商的权重
  */
 
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { toast } from "sonner";
 import { Plus, Trash2, Loader2, Info, AlertTriangle } from "lucide-react";
 import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
 import { Switch } from "@/components/ui/switch";
 import { Alert, AlertDescription } from "@/components/ui/alert";
 import {
@@ -30,7 +32,9 @@ import {
   useRemoveFromFailoverQueue,
   useAutoFailoverEnabled,
   useSetAutoFailoverEnabled,
+  useSetFailoverRoutingWeight,
 } from "@/lib/query/failover";
+import { useAppProxyConfig } from "@/lib/query/proxy";
 
 interface FailoverQueueManagerProps {
   appType: ProxyAppId;
@@ -60,6 +64,11 @@ export function FailoverQueueManager({
   // Mutations
   const addToQueue = useAddToFailoverQueue();
   const removeFromQueue = useRemoveFromFailoverQueue();
+  const setRoutingWeight = useSetFailoverRoutingWeight();
+
+  // 加权路由策略下才显示权重
+  const { data: proxyConfig } = useAppProxyConfig(appType);
+  const isWeighted = proxyConfig?.routingStrategy === "weighted";
 
   // 切换故障转移开关
   const handleToggleFailover = (enabled: boolean) => {
@@ -104,6 +113,19 @@ export function FailoverQueueManager({
     }
   };
 
+  // 设置供应商权重
+  const handleWeightChange = async (providerId: string, weight: number) => {
+    try {
+      await setRoutingWeight.mutateAsync({ appType, providerId, weight });
+    } catch (error) {
+      toast.error(
+        t("proxy.failoverQueue.weightFailed", "权重保存失败") +
+          ": " +
+          String(error),
+      );
+    }
+  };
+
   if (isQueueLoading) {
     return (
       <div className="flex items-center justify-center p-8">
@@ -233,6 +255,7 @@ export function FailoverQueueManager({
               disabled={disabled}
               onRemove={handleRemoveProvider}
               isRemoving={removeFromQueue.isPending}
+              onWeightChange={isWeighted ? handleWeightChange : undefined}
             />
           ))}
         </div>
@@ -257,6 +280,8 @@ interface QueueItemProps {
   disabled: boolean;
   onRemove: (providerId: string) => void;
   isRemoving: boolean;
+  /** 仅加权路由策略下传入，显示权重输入框 */
+  onWeightChange?: (providerId: string, weight: number) => void;
 }
 
 function QueueItem({
@@ -265,8 +290,27 @@ function QueueItem({
   disabled,
   onRemove,
   isRemoving,
+  onWeightChange,
 }: QueueItemProps) {
   const { t } = useTranslation();
+  const currentWeight = item.routingWeight ?? 1;
+  const [weight, setWeight] = useState(String(currentWeight));
+
+  useEffect(() => {
+    setWeight(String(currentWeight));
+  }, [currentWeight]);
+
+  // 失焦时提交；非法输入恢复为当前权重
+  const commitWeight = () => {
+    const parsed = Number(weight.trim());
+    if (!Number.isInteger(parsed) || parsed < 0 || parsed > 100) {
+      setWeight(String(currentWeight));
+      return;
+    }
+    if (parsed !== currentWeight) {
+      onWeightChange?.(item.providerId, parsed);
+    }
+  };
 
   return (
     <div
@@ -291,6 +335,29 @@ function QueueItem({
         </span>
       </div>
 
+      {/* 权重 */}
+      {onWeightChange && (
+        <div className="flex items-center gap-2">
+          <span className="text-xs text-muted-foreground">
+            {t("proxy.failoverQueue.weight", "权重")}
+          </span>
+          <Input
+            type="number"
+            min="0"
+            max="100"
+            className="h-8 w-16"
+            value={weight}
+            onChange={(e) => setWeight(e.target.value)}
+            onBlur={commitWeight}
+            disabled={disabled}
+            aria-label={t("proxy.failoverQueue.weightFor", {
+              name: item.providerName,
+              defaultValue: `${item.providerName} 的权重`,
+            })}
+          />
+        </div>
+      )}
+
       {/* 删除按钮 */}
       <Button
         variant="ghost"
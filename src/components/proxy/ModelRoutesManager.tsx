@@ -0,0 +1,184 @@
+/**
+ * 模型路由规则管理组件
+ *
+ * 按请求模型把流量分给不同的供应商（如 claude-haiku-* → 便宜的中转），
+ * 规则按顺序匹配、先于故障转移队列生效，每条规则的供应商顺序即其故障转移顺序。
+ */
+
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Plus, Save, Trash2, X } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Switch } from "@/components/ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import type { ModelRoute } from "@/types/proxy";
+import type { ProxyAppId } from "@/config/appConfig";
+import { useAppProxyConfig, useUpdateAppProxyConfig } from "@/lib/query/proxy";
+import { useProvidersQuery } from "@/lib/query/queries";
+
+interface ModelRoutesManagerProps {
+  appType: ProxyAppId;
+  disabled?: boolean;
+}
+
+export function ModelRoutesManager({
+  appType,
+  disabled = false,
+}: ModelRoutesManagerProps) {
+  const { t } = useTranslation();
+  const { data: config } = useAppProxyConfig(appType);
+  const { data: providersData } = useProvidersQuery(appType);
+  const updateConfig = useUpdateAppProxyConfig();
+  const [routes, setRoutes] = useState<ModelRoute[]>([]);
+
+  useEffect(() => {
+    setRoutes(config?.modelRoutes ?? []);
+  }, [config]);
+
+  const providers = Object.values(providersData?.providers ?? {});
+  const providerName = (id: string) => providersData?.providers[id]?.name ?? id;
+
+  const updateRoute = (index: number, patch: Partial<ModelRoute>) =>
+    setRoutes((prev) =>
+      prev.map((route, i) => (i === index ? { ...route, ...patch } : route)),
+    );
+
+  const handleSave = () => {
+    if (!config) return;
+    updateConfig.mutate({
+      ...config,
+      modelRoutes: routes.map((route) => ({
+        ...route,
+        pattern: route.pattern.trim(),
+      })),
+    });
+  };
+
+  const busy = disabled || updateConfig.isPending;
+
+  return (
+    <div className="space-y-3">
+      {routes.length === 0 && (
+        <p className="text-sm text-muted-foreground">
+          {t("proxy.modelRoutes.empty")}
+        </p>
+      )}
+      {routes.map((route, index) => (
+        <div
+          key={index}
+          className="space-y-2 rounded-lg border border-border/50 bg-muted/30 p-3"
+        >
+          <div className="flex items-center gap-2">
+            <Input
+              value={route.pattern}
+              onChange={(e) =>
+                updateRoute(index, { pattern: e.target.value })
+              }
+              placeholder={t("proxy.modelRoutes.patternPlaceholder")}
+              disabled={busy}
+              className="flex-1 font-mono"
+            />
+            <Switch
+              checked={route.enabled}
+              onCheckedChange={(enabled) => updateRoute(index, { enabled })}
+              disabled={busy}
+              aria-label={t("proxy.failoverQueue.toggleEnabled")}
+            />
+            <Button
+              type="button"
+              variant="ghost"
+              size="icon"
+              disabled={busy}
+              onClick={() =>
+                setRoutes((prev) => prev.filter((_, i) => i !== index))
+              }
+              aria-label={t("common.delete")}
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+          <div className="flex flex-wrap items-center gap-1.5">
+            {route.providerIds.map((id, position) => (
+              <span
+                key={id}
+                className="inline-flex items-center gap-1 rounded-full bg-background px-2 py-0.5 text-xs border border-border/60"
+              >
+                <span className="text-muted-foreground">P{position + 1}</span>
+                {providerName(id)}
+                <button
+                  type="button"
+                  disabled={busy}
+                  onClick={() =>
+                    updateRoute(index, {
+                      providerIds: route.providerIds.filter((p) => p !== id),
+                    })
+                  }
+                  aria-label={t("common.delete")}
+                >
+                  <X className="h-3 w-3" />
+                </button>
+              </span>
+            ))}
+            <Select
+              value=""
+              onValueChange={(id) =>
+                updateRoute(index, {
+                  providerIds: [...route.providerIds, id],
+                })
+              }
+              disabled={busy}
+            >
+              <SelectTrigger className="h-7 w-auto text-xs">
+                <SelectValue placeholder={t("proxy.modelRoutes.addProvider")} />
+              </SelectTrigger>
+              <SelectContent>
+                {providers
+                  .filter(
+                    (provider) => !route.providerIds.includes(provider.id),
+                  )
+                  .map((provider) => (
+                    <SelectItem key={provider.id} value={provider.id}>
+                      {provider.name}
+                    </SelectItem>
+                  ))}
+              </SelectContent>
+            </Select>
+          </div>
+        </div>
+      ))}
+      <div className="flex justify-between">
+        <Button
+          type="button"
+          variant="outline"
+          size="sm"
+          disabled={busy}
+          onClick={() =>
+            setRoutes((prev) => [
+              ...prev,
+              { pattern: "", providerIds: [], enabled: true },
+            ])
+          }
+        >
+          <Plus className="mr-1 h-4 w-4" />
+          {t("proxy.modelRoutes.addRule")}
+        </Button>
+        <Button
+          type="button"
+          size="sm"
+          disabled={busy || !config}
+          onClick={handleSave}
+        >
+          <Save className="mr-1 h-4 w-4" />
+          {t("common.save")}
+        </Button>
+      </div>
+    </div>
+  );
+}
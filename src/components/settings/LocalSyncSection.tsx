@@ -0,0 +1,522 @@
+import { useCallback, useEffect, useState } from "react";
+import {
+  Check,
+  DownloadCloud,
+  FolderOpen,
+  Link2,
+  Save,
+  UploadCloud,
+} from "lucide-react";
+import { useTranslation } from "react-i18next";
+import { useQueryClient } from "@tanstack/react-query";
+import { toast } from "sonner";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Switch } from "@/components/ui/switch";
+import { folderSyncApi, gitSyncApi, settingsApi } from "@/lib/api";
+import { ConfirmDialog } from "@/components/ConfirmDialog";
+import {
+  ActionButton,
+  PassphraseField,
+} from "@/components/settings/WebdavSyncSection";
+import type { ActionState } from "@/components/settings/WebdavSyncSection";
+import type {
+  FolderSyncSettings,
+  GitSyncSettings,
+  RemoteSnapshotInfo,
+} from "@/types";
+
+// ─── Transport specs ────────────────────────────────────────
+
+type SyncSettings = FolderSyncSettings | GitSyncSettings;
+
+interface TransportField {
+  key: "path" | "repoUrl" | "branch";
+  defaultValue?: string;
+  required?: boolean;
+  /** 显示"浏览"按钮，用系统目录选择器填充 */
+  browse?: boolean;
+}
+
+interface TransportApi<T extends SyncSettings> {
+  testConnection(settings: T): Promise<unknown>;
+  saveSettings(settings: T): Promise<unknown>;
+  upload(): Promise<unknown>;
+  download(): Promise<unknown>;
+  fetchRemoteInfo(): Promise<RemoteSnapshotInfo | { empty: true }>;
+}
+
+interface TransportSpec<T extends SyncSettings> {
+  /** i18n 前缀：字段标签、占位符与各项提示均挂在其下 */
+  prefix: "settings.folderSync" | "settings.gitSync";
+  fields: TransportField[];
+  api: TransportApi<T>;
+}
+
+const FOLDER_SPEC: TransportSpec<FolderSyncSettings> = {
+  prefix: "settings.folderSync",
+  fields: [{ key: "path", required: true, browse: true }],
+  api: folderSyncApi,
+};
+
+const GIT_SPEC: TransportSpec<GitSyncSettings> = {
+  prefix: "settings.gitSync",
+  fields: [
+    { key: "repoUrl", required: true },
+    { key: "branch", defaultValue: "main" },
+  ],
+  api: gitSyncApi,
+};
+
+type FieldValues = Partial<Record<TransportField["key"], string>>;
+
+function readFields(fields: TransportField[], config?: SyncSettings) {
+  const values: FieldValues = {};
+  for (const field of fields) {
+    const raw = (config as FieldValues | undefined)?.[field.key];
+    values[field.key] = raw ?? field.defaultValue ?? "";
+  }
+  return values;
+}
+
+function errorMessage(error: unknown): string {
+  return (error as Error)?.message ?? String(error);
+}
+
+// ─── Transport card ─────────────────────────────────────────
+
+function TransportSyncCard<T extends SyncSettings>({
+  spec,
+  config,
+}: {
+  spec: TransportSpec<T>;
+  config?: T;
+}) {
+  const { t } = useTranslation();
+  const queryClient = useQueryClient();
+  const { prefix, fields, api } = spec;
+  const [actionState, setActionState] = useState<ActionState>("idle");
+  const [values, setValues] = useState(() => readFields(fields, config));
+  const [enabled, setEnabled] = useState(config?.enabled ?? false);
+  const [autoSync, setAutoSync] = useState(config?.autoSync ?? false);
+  const [passphrase, setPassphrase] = useState("");
+  const [passphraseTouched, setPassphraseTouched] = useState(false);
+  const [dirty, setDirty] = useState(false);
+  const [justSaved, setJustSaved] = useState(false);
+  const [confirm, setConfirm] = useState<"upload" | "download" | null>(null);
+
+  // 设置异步加载或保存后刷新时，以后端为准重置未修改的表单
+  useEffect(() => {
+    if (!config || dirty) return;
+    setValues(readFields(fields, config));
+    setEnabled(config.enabled ?? false);
+    setAutoSync(config.autoSync ?? false);
+    setPassphrase("");
+    setPassphraseTouched(false);
+  }, [config, dirty, fields]);
+
+  const markDirty = useCallback(() => {
+    setDirty(true);
+    setJustSaved(false);
+  }, []);
+
+  const updateField = useCallback(
+    (key: TransportField["key"], value: string) => {
+      setValues((prev) => ({ ...prev, [key]: value }));
+      markDirty();
+    },
+    [markDirty],
+  );
+
+  const handleBrowse = useCallback(
+    async (key: TransportField["key"]) => {
+      const picked = await settingsApi.pickDirectory(values[key] || undefined);
+      if (picked) updateField(key, picked);
+    },
+    [updateField, values],
+  );
+
+  const buildSettings = useCallback((): T | null => {
+    const trimmed: FieldValues = {};
+    for (const field of fields) {
+      const value = values[field.key]?.trim() ?? "";
+      if (field.required && !value) return null;
+      trimmed[field.key] = value || field.defaultValue || "";
+    }
+    return {
+      ...trimmed,
+      enabled,
+      autoSync,
+      remoteRoot: config?.remoteRoot ?? "cc-switch-sync",
+      profile: config?.profile ?? "default",
+      // 未编辑口令时不提交该字段，后端沿用已保存口令；编辑后清空表示关闭加密
+      encryptionPassphrase: passphraseTouched ? passphrase : undefined,
+    } as T;
+  }, [
+    autoSync,
+    config,
+    enabled,
+    fields,
+    passphrase,
+    passphraseTouched,
+    values,
+  ]);
+
+  const handleTest = useCallback(async () => {
+    const settings = buildSettings();
+    if (!settings) {
+      toast.error(t(`${prefix}.missingRequired`));
+      return;
+    }
+    setActionState("testing");
+    try {
+      await api.testConnection(settings);
+      toast.success(t(`${prefix}.testSuccess`));
+    } catch (error) {
+      toast.error(
+        t("settings.webdavSync.testFailed", { error: errorMessage(error) }),
+      );
+    } finally {
+      setActionState("idle");
+    }
+  }, [api, buildSettings, prefix, t]);
+
+  const handleSave = useCallback(async () => {
+    const settings = buildSettings();
+    if (!settings) {
+      toast.error(t(`${prefix}.missingRequired`));
+      return;
+    }
+    setActionState("saving");
+    try {
+      await api.saveSettings(settings);
+      setDirty(false);
+      setPassphraseTouched(false);
+      setJustSaved(true);
+      await queryClient.invalidateQueries({ queryKey: ["settings"] });
+    } catch (error) {
+      toast.error(
+        t("settings.webdavSync.saveFailed", { error: errorMessage(error) }),
+      );
+      setActionState("idle");
+      return;
+    }
+
+    // 保存后自动测试连接
+    setActionState("testing");
+    try {
+      await api.testConnection(settings);
+      toast.success(t("settings.webdavSync.saveAndTestSuccess"));
+    } catch (error) {
+      toast.warning(
+        t("settings.webdavSync.saveAndTestFailed", {
+          error: errorMessage(error),
+        }),
+      );
+    } finally {
+      setActionState("idle");
+    }
+  }, [api, buildSettings, prefix, queryClient, t]);
+
+  const handleUploadClick = useCallback(() => {
+    if (dirty) {
+      toast.error(t("settings.webdavSync.unsavedChanges"));
+      return;
+    }
+    setConfirm("upload");
+  }, [dirty, t]);
+
+  const handleDownloadClick = useCallback(async () => {
+    if (dirty) {
+      toast.error(t("settings.webdavSync.unsavedChanges"));
+      return;
+    }
+    setActionState("fetching_remote");
+    try {
+      const info = await api.fetchRemoteInfo();
+      if ("empty" in info) {
+        toast.info(t("settings.webdavSync.noRemoteData"));
+        return;
+      }
+      if (!info.compatible) {
+        toast.error(
+          t("settings.webdavSync.incompatibleVersion", {
+            protocolVersion: info.protocolVersion,
+            dbCompatVersion:
+              typeof info.dbCompatVersion === "number"
+                ? `db-v${info.dbCompatVersion}`
+                : t("common.unknown"),
+          }),
+        );
+        return;
+      }
+      setConfirm("download");
+    } catch (error) {
+      toast.error(
+        t("settings.webdavSync.downloadFailed", { error: errorMessage(error) }),
+      );
+    } finally {
+      setActionState("idle");
+    }
+  }, [api, dirty, t]);
+
+  const handleConfirm = useCallback(async () => {
+    const action = confirm;
+    setConfirm(null);
+    if (!action) return;
+    setActionState(action === "upload" ? "uploading" : "downloading");
+    try {
+      if (action === "upload") {
+        await api.upload();
+        toast.success(t(`${prefix}.uploadSuccess`));
+      } else {
+        await api.download();
+        toast.success(t(`${prefix}.downloadSuccess`));
+      }
+      await queryClient.invalidateQueries();
+    } catch (error) {
+      toast.error(
+        t(
+          action === "upload"
+            ? "settings.webdavSync.uploadFailed"
+            : "settings.webdavSync.downloadFailed",
+          { error: errorMessage(error) },
+        ),
+      );
+    } finally {
+      setActionState("idle");
+    }
+  }, [api, confirm, prefix, queryClient, t]);
+
+  const isLoading = actionState !== "idle";
+  const savedFields = readFields(fields, config);
+  const hasSavedConfig = Boolean(
+    config?.enabled &&
+      fields.every((field) => !field.required || savedFields[field.key]),
+  );
+  const lastSyncAt = config?.status?.lastSyncAt;
+  const lastError = config?.status?.lastError?.trim();
+  const showAutoSyncError =
+    !!lastError && config?.status?.lastErrorSource === "auto";
+
+  return (
+    <div className="space-y-4 rounded-lg border border-border bg-muted/40 p-6">
+      <div>
+        <h4 className="text-sm font-medium">{t(`${prefix}.title`)}</h4>
+        <p className="text-xs text-muted-foreground">
+          {t(`${prefix}.description`)}
+        </p>
+      </div>
+
+      <div className="space-y-3">
+        <div className="flex items-center gap-4">
+          <label className="w-40 text-xs font-medium text-foreground shrink-0">
+            {t("settings.localSync.enabled")}
+          </label>
+          <Switch
+            checked={enabled}
+            onCheckedChange={(checked) => {
+              setEnabled(checked);
+              markDirty();
+            }}
+            aria-label={t(`${prefix}.title`)}
+            disabled={isLoading}
+          />
+        </div>
+
+        {fields.map((field) => (
+          <div key={field.key} className="flex items-center gap-4">
+            <label className="w-40 text-xs font-medium text-foreground shrink-0">
+              {t(`${prefix}.${field.key}`)}
+            </label>
+            <Input
+              value={values[field.key] ?? ""}
+              onChange={(e) => updateField(field.key, e.target.value)}
+              placeholder={t(`${prefix}.${field.key}Placeholder`)}
+              aria-label={t(`${prefix}.${field.key}`)}
+              className="text-xs flex-1"
+              disabled={isLoading}
+            />
+            {field.browse && (
+              <Button
+                type="button"
+                variant="outline"
+                size="sm"
+                onClick={() => void handleBrowse(field.key)}
+                disabled={isLoading}
+              >
+                <FolderOpen className="h-3.5 w-3.5" />
+                {t("settings.localSync.browse")}
+              </Button>
+            )}
+          </div>
+        ))}
+
+        <PassphraseField
+          value={passphrase}
+          configured={config?.encryptionConfigured}
+          disabled={isLoading}
+          onChange={(value) => {
+            setPassphrase(value);
+            setPassphraseTouched(true);
+            markDirty();
+          }}
+        />
+
+        <div className="flex items-start gap-4">
+          <label className="w-40 text-xs font-medium text-foreground shrink-0">
+            {t("settings.webdavSync.autoSync")}
+            <span className="block text-[10px] font-normal text-muted-foreground">
+              {t("settings.localSync.autoSyncHint")}
+            </span>
+          </label>
+          <div className="pt-1">
+            <Switch
+              checked={autoSync}
+              onCheckedChange={(checked) => {
+                setAutoSync(checked);
+                markDirty();
+              }}
+              aria-label={t("settings.webdavSync.autoSync")}
+              disabled={isLoading}
+            />
+          </div>
+        </div>
+      </div>
+
+      {lastSyncAt && (
+        <p className="text-xs text-muted-foreground">
+          {t("settings.webdavSync.lastSync", {
+            time: new Date(lastSyncAt * 1000).toLocaleString(),
+          })}
+        </p>
+      )}
+      {showAutoSyncError && (
+        <div className="rounded-lg border border-red-300/70 bg-red-50/80 px-3 py-2 text-xs text-red-900 dark:border-red-500/50 dark:bg-red-950/30 dark:text-red-200">
+          <p className="font-medium">
+            {t("settings.webdavSync.autoSyncLastErrorTitle")}
+          </p>
+          <p className="mt-1 break-all whitespace-pre-wrap">{lastError}</p>
+        </div>
+      )}
+
+      <div className="flex flex-wrap items-center gap-3 pt-2">
+        <ActionButton
+          type="button"
+          variant="outline"
+          size="sm"
+          onClick={handleTest}
+          actionState={actionState}
+          targetState="testing"
+          icon={Link2}
+          activeLabel={t("settings.webdavSync.testing")}
+          idleLabel={t("settings.webdavSync.test")}
+        />
+        <ActionButton
+          type="button"
+          variant="outline"
+          size="sm"
+          onClick={handleSave}
+          actionState={actionState}
+          targetState="saving"
+          icon={Save}
+          activeLabel={t("settings.webdavSync.saving")}
+          idleLabel={t("settings.webdavSync.save")}
+        />
+        {dirty && (
+          <span className="inline-flex items-center gap-1.5 text-xs text-amber-500 dark:text-amber-400">
+            <span className="h-1.5 w-1.5 rounded-full bg-amber-500 dark:bg-amber-400" />
+            {t("settings.webdavSync.unsaved")}
+          </span>
+        )}
+        {!dirty && justSaved && (
+          <span className="inline-flex items-center gap-1.5 text-xs text-emerald-600 dark:text-emerald-400">
+            <Check className="h-3 w-3" />
+            {t("settings.webdavSync.saved")}
+          </span>
+        )}
+      </div>
+
+      <div className="flex flex-wrap items-center gap-3 border-t border-border pt-4">
+        <ActionButton
+          type="button"
+          size="sm"
+          onClick={handleUploadClick}
+          disabled={!hasSavedConfig}
+          actionState={actionState}
+          targetState="uploading"
+          icon={UploadCloud}
+          activeLabel={t("settings.webdavSync.uploading")}
+          idleLabel={t("settings.localSync.upload")}
+        />
+        <ActionButton
+          type="button"
+          variant="secondary"
+          size="sm"
+          onClick={handleDownloadClick}
+          disabled={!hasSavedConfig}
+          actionState={actionState}
+          targetState="downloading"
+          alsoActiveFor={["fetching_remote"]}
+          icon={DownloadCloud}
+          activeLabel={
+            actionState === "fetching_remote"
+              ? t("settings.webdavSync.fetchingRemote")
+              : t("settings.webdavSync.downloading")
+          }
+          idleLabel={t("settings.localSync.download")}
+        />
+      </div>
+      {!hasSavedConfig && (
+        <p className="text-xs text-muted-foreground">
+          {t("settings.webdavSync.saveBeforeSync")}
+        </p>
+      )}
+
+      <ConfirmDialog
+        isOpen={confirm !== null}
+        title={t(
+          confirm === "download"
+            ? "settings.localSync.confirmDownloadTitle"
+            : "settings.localSync.confirmUploadTitle",
+        )}
+        message={t(
+          confirm === "download"
+            ? "settings.localSync.confirmDownloadMessage"
+            : "settings.localSync.confirmUploadMessage",
+        )}
+        onConfirm={() => void handleConfirm()}
+        onCancel={() => setConfirm(null)}
+      />
+    </div>
+  );
+}
+
+// ─── Main component ─────────────────────────────────────────
+
+interface LocalSyncSectionProps {
+  folderConfig?: FolderSyncSettings;
+  gitConfig?: GitSyncSettings;
+}
+
+/** 本地目录与本地 Git 仓库两种同步方式：各自独立启用，互不排斥 */
+export function LocalSyncSection({
+  folderConfig,
+  gitConfig,
+}: LocalSyncSectionProps) {
+  const { t } = useTranslation();
+  return (
+    <section className="space-y-4 pt-6">
+      <header className="space-y-2">
+        <h3 className="text-base font-semibold text-foreground">
+          {t("settings.localSync.title")}
+        </h3>
+        <p className="text-sm text-muted-foreground">
+          {t("settings.localSync.description")}
+        </p>
+      </header>
+      <TransportSyncCard spec={FOLDER_SPEC} config={folderConfig} />
+      <TransportSyncCard spec={GIT_SPEC} config={gitConfig} />
+    </section>
+  );
+}
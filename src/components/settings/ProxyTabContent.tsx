@@ -12,6 +12,7 @@ import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { Badge } from "@/components/ui/badge";
 import { ProxyPanel } from "@/components/proxy";
 import { AutoFailoverConfigPanel } from "@/components/proxy/AutoFailoverConfigPanel";
+import { ModelRoutesManager } from "@/components/proxy/ModelRoutesManager";
 import { FailoverQueueManager } from "@/components/proxy/FailoverQueueManager";
 import { RectifierConfigPanel } from "@/components/settings/RectifierConfigPanel";
 import { GlobalProxySettings } from "@/components/settings/GlobalProxySettings";
@@ -208,6 +209,20 @@ export function ProxyTabContent({
                           disabled={failoverDisabled}
                         />
                       </div>
+                      <div className="space-y-4 border-t border-border/50 pt-6">
+                        <div>
+                          <h4 className="text-sm font-semibold">
+                            {t("proxy.modelRoutes.title")}
+                          </h4>
+                          <p className="text-xs text-muted-foreground">
+                            {t("proxy.modelRoutes.description")}
+                          </p>
+                        </div>
+                        <ModelRoutesManager
+                          appType={appType}
+                          disabled={failoverDisabled}
+                        />
+                      </div>
                       <div className="border-t border-border/50 pt-6">
                         <AutoFailoverConfigPanel
                           appType={appType}
@@ -45,6 +45,8 @@ import { DirectorySettings } from "@/components/settings/DirectorySettings";
 import { ImportExportSection } from "@/components/settings/ImportExportSection";
 import { BackupListSection } from "@/components/settings/BackupListSection";
 import { WebdavSyncSection } from "@/components/settings/WebdavSyncSection";
+import { LocalSyncSection } from "@/components/settings/LocalSyncSection";
+import { SyncConflictsPanel } from "@/components/settings/SyncConflictsPanel";
 import { AboutSection } from "@/components/settings/AboutSection";
 import { ProxyTabContent } from "@/components/settings/ProxyTabContent";
 import { ConnectivityCheckConfigPanel } from "@/components/usage/ConnectivityCheckConfigPanel";
@@ -453,6 +455,11 @@ export function SettingsPage({
                             settings={settings}
                             onAutoSave={handleAutoSave}
                           />
+                          <LocalSyncSection
+                            folderConfig={settings?.folderSync}
+                            gitConfig={settings?.gitSync}
+                          />
+                          <SyncConflictsPanel />
                         </AccordionContent>
                       </AccordionItem>
 
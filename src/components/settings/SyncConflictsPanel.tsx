@@ -0,0 +1,131 @@
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { AlertTriangle } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { useSyncConflicts } from "@/hooks/useSyncConflicts";
+import type { SyncConflict, SyncConflictResolution } from "@/lib/api";
+import { extractErrorMessage } from "@/utils/errorUtils";
+
+function formatValue(value: unknown): string {
+  if (value === undefined || value === null) return "";
+  return typeof value === "string" ? value : JSON.stringify(value);
+}
+
+function formatKey(key: SyncConflict["key"]): string {
+  return Object.values(key).map(formatValue).join(" / ");
+}
+
+/** 两端内容不同的字段；一端已删除时列出另一端的全部字段 */
+function differingFields(conflict: SyncConflict): string[] {
+  const { local, remote } = conflict;
+  const fields = new Set([
+    ...Object.keys(local ?? {}),
+    ...Object.keys(remote ?? {}),
+  ]);
+  return [...fields].filter(
+    (field) =>
+      !local ||
+      !remote ||
+      JSON.stringify(local[field]) !== JSON.stringify(remote[field]),
+  );
+}
+
+export function SyncConflictsPanel() {
+  const { t } = useTranslation();
+  const { conflicts, resolve, isResolving } = useSyncConflicts();
+
+  if (conflicts.length === 0) return null;
+
+  const handleResolve = async (
+    id: string,
+    resolution: SyncConflictResolution,
+  ) => {
+    try {
+      const result = await resolve({ id, resolution });
+      if (result.warning) {
+        toast.warning(result.warning);
+      } else {
+        toast.success(t("settings.syncConflicts.resolved"));
+      }
+    } catch (error) {
+      toast.error(
+        extractErrorMessage(error) || t("settings.syncConflicts.resolveFailed"),
+      );
+    }
+  };
+
+  const renderSide = (row: SyncConflict["local"], field: string) =>
+    row ? (
+      <span className="break-all">{formatValue(row[field])}</span>
+    ) : (
+      <span className="italic text-muted-foreground">
+        {t("settings.syncConflicts.deleted")}
+      </span>
+    );
+
+  return (
+    <div className="mt-6 space-y-3 rounded-lg border border-amber-500/40 bg-amber-500/5 p-4">
+      <div className="flex items-start gap-2">
+        <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0 text-amber-500" />
+        <div>
+          <h4 className="text-sm font-semibold">
+            {t("settings.syncConflicts.title", { count: conflicts.length })}
+          </h4>
+          <p className="text-xs text-muted-foreground">
+            {t("settings.syncConflicts.description")}
+          </p>
+        </div>
+      </div>
+
+      {conflicts.map((conflict) => (
+        <div
+          key={conflict.id}
+          data-testid={`sync-conflict-${conflict.id}`}
+          className="space-y-2 rounded-md border border-border/60 bg-background p-3"
+        >
+          <div className="text-sm font-medium">
+            {conflict.table}
+            <span className="ml-2 font-mono text-xs text-muted-foreground">
+              {formatKey(conflict.key)}
+            </span>
+          </div>
+          <div className="grid grid-cols-[minmax(6rem,auto)_1fr_1fr] gap-x-3 gap-y-1 text-xs">
+            <span className="font-medium text-muted-foreground">
+              {t("settings.syncConflicts.field")}
+            </span>
+            <span className="font-medium text-muted-foreground">
+              {t("settings.syncConflicts.local")}
+            </span>
+            <span className="font-medium text-muted-foreground">
+              {t("settings.syncConflicts.remote")}
+            </span>
+            {differingFields(conflict).map((field) => (
+              <div key={field} className="contents">
+                <span className="font-mono">{field}</span>
+                {renderSide(conflict.local, field)}
+                {renderSide(conflict.remote, field)}
+              </div>
+            ))}
+          </div>
+          <div className="flex justify-end gap-2">
+            <Button
+              size="sm"
+              variant="outline"
+              disabled={isResolving}
+              onClick={() => handleResolve(conflict.id, "local")}
+            >
+              {t("settings.syncConflicts.keepLocal")}
+            </Button>
+            <Button
+              size="sm"
+              disabled={isResolving}
+              onClick={() => handleResolve(conflict.id, "remote")}
+            >
+              {t("settings.syncConflicts.useRemote")}
+            </Button>
+          </div>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -173,7 +173,7 @@ function buildPasswordPreservationKey(values: {
 
 // ─── Types ──────────────────────────────────────────────────
 
-type ActionState =
+export type ActionState =
   | "idle"
   | "testing"
   | "saving"
@@ -195,7 +195,7 @@ interface WebdavSyncSectionProps {
 // ─── ActionButton ───────────────────────────────────────────
 
 /** Reusable button with loading spinner. */
-function ActionButton({
+export function ActionButton({
   actionState,
   targetState,
   alsoActiveFor,
@@ -229,6 +229,47 @@ function ActionButton({
   );
 }
 
+/** 快照加密口令输入：口令从不回传，已设置时以占位提示代替 */
+export function PassphraseField({
+  value,
+  configured,
+  disabled,
+  onChange,
+}: {
+  value: string;
+  configured?: boolean;
+  disabled?: boolean;
+  onChange: (value: string) => void;
+}) {
+  const { t } = useTranslation();
+  return (
+    <>
+      <div className="flex items-center gap-4">
+        <label className="w-40 text-xs font-medium text-foreground shrink-0">
+          {t("settings.syncEncryption.label")}
+        </label>
+        <Input
+          type="password"
+          value={value}
+          onChange={(e) => onChange(e.target.value)}
+          placeholder={t(
+            configured
+              ? "settings.syncEncryption.configuredPlaceholder"
+              : "settings.syncEncryption.placeholder",
+          )}
+          className="text-xs flex-1"
+          autoComplete="new-password"
+          disabled={disabled}
+        />
+      </div>
+      <div className="flex items-start gap-2 pl-44 text-xs text-muted-foreground">
+        <Info className="h-3.5 w-3.5 shrink-0 mt-0.5" />
+        <span>{t("settings.syncEncryption.hint")}</span>
+      </div>
+    </>
+  );
+}
+
 // ─── Main component ─────────────────────────────────────────
 
 export function WebdavSyncSection({
@@ -242,6 +283,7 @@ export function WebdavSyncSection({
   const [actionState, setActionState] = useState<ActionState>("idle");
   const [dirty, setDirty] = useState(false);
   const [passwordTouched, setPasswordTouched] = useState(false);
+  const [passphraseTouched, setPassphraseTouched] = useState(false);
   const [justSaved, setJustSaved] = useState(false);
   const justSavedTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const pendingPasswordPreservationRef = useRef<{
@@ -270,6 +312,7 @@ export function WebdavSyncSection({
     remoteRoot: config?.remoteRoot ?? "cc-switch-sync",
     profile: config?.profile ?? "default",
     autoSync: config?.autoSync ?? false,
+    encryptionPassphrase: "",
   }));
 
   // ─── S3 form state ─────────────────────────────────────────
@@ -290,6 +333,8 @@ export function WebdavSyncSection({
   const [s3AutoSync, setS3AutoSync] = useState(s3Config?.autoSync ?? false);
   const [s3Enabled, setS3Enabled] = useState(s3Config?.enabled ?? false);
   const [s3SecretTouched, setS3SecretTouched] = useState(false);
+  const [s3Passphrase, setS3Passphrase] = useState("");
+  const [s3PassphraseTouched, setS3PassphraseTouched] = useState(false);
   const [s3Dirty, setS3Dirty] = useState(false);
   const [s3JustSaved, setS3JustSaved] = useState(false);
   const s3JustSavedTimerRef = useRef<ReturnType<typeof setTimeout> | null>(
@@ -366,9 +411,11 @@ export function WebdavSyncSection({
         remoteRoot: nextRemoteRoot,
         profile: nextProfile,
         autoSync: config.autoSync ?? false,
+        encryptionPassphrase: "",
       };
     });
     setPasswordTouched(false);
+    setPassphraseTouched(false);
     setPresetId(detectPreset(config.baseUrl ?? ""));
   }, [config, dirty]);
 
@@ -385,6 +432,8 @@ export function WebdavSyncSection({
     setS3AutoSync(s3Config.autoSync ?? false);
     setS3Enabled(s3Config.enabled ?? false);
     setS3SecretTouched(false);
+    setS3Passphrase("");
+    setS3PassphraseTouched(false);
   }, [s3Config, s3Dirty]);
 
   const updateField = useCallback((field: keyof typeof form, value: string) => {
@@ -392,6 +441,9 @@ export function WebdavSyncSection({
     if (field === "password") {
       setPasswordTouched(true);
     }
+    if (field === "encryptionPassphrase") {
+      setPassphraseTouched(true);
+    }
     setDirty(true);
     setJustSaved(false);
     if (justSavedTimerRef.current) {
@@ -464,8 +516,12 @@ export function WebdavSyncSection({
       remoteRoot: form.remoteRoot.trim() || "cc-switch-sync",
       profile: form.profile.trim() || "default",
       autoSync: form.autoSync,
+      // 未编辑口令时不提交该字段，后端沿用已保存口令；编辑后清空表示关闭加密
+      encryptionPassphrase: passphraseTouched
+        ? form.encryptionPassphrase
+        : undefined,
     };
-  }, [form, passwordTouched]);
+  }, [form, passwordTouched, passphraseTouched]);
 
   // ─── Handlers ───────────────────────────────────────────
 
@@ -507,6 +563,7 @@ export function WebdavSyncSection({
       await settingsApi.webdavSyncSaveSettings(settings, passwordTouched);
       setDirty(false);
       setPasswordTouched(false);
+      setPassphraseTouched(false);
       // Show "saved" indicator for 2 seconds
       setJustSaved(true);
       if (justSavedTimerRef.current) clearTimeout(justSavedTimerRef.current);
@@ -679,6 +736,7 @@ export function WebdavSyncSection({
       endpoint: s3Endpoint.trim() || undefined,
       remoteRoot: s3RemoteRoot.trim() || "cc-switch-sync",
       profile: s3Profile.trim() || "default",
+      encryptionPassphrase: s3PassphraseTouched ? s3Passphrase : undefined,
     };
   }, [
     s3Enabled,
@@ -690,6 +748,8 @@ export function WebdavSyncSection({
     s3Endpoint,
     s3RemoteRoot,
     s3Profile,
+    s3Passphrase,
+    s3PassphraseTouched,
   ]);
 
   // ─── S3 Handlers ──────────────────────────────────────────
@@ -726,6 +786,7 @@ export function WebdavSyncSection({
       await settingsApi.s3SyncSaveSettings(s3Settings, s3SecretTouched);
       setS3Dirty(false);
       setS3SecretTouched(false);
+      setS3PassphraseTouched(false);
       setS3JustSaved(true);
       if (s3JustSavedTimerRef.current)
         clearTimeout(s3JustSavedTimerRef.current);
@@ -1064,6 +1125,14 @@ export function WebdavSyncSection({
               />
             </div>
 
+            {/* Snapshot encryption passphrase */}
+            <PassphraseField
+              value={form.encryptionPassphrase}
+              configured={config?.encryptionConfigured}
+              disabled={isLoading}
+              onChange={(value) => updateField("encryptionPassphrase", value)}
+            />
+
             {/* Preset hint */}
             {activePreset?.hint && (
               <div className="flex items-start gap-2 pl-44 text-xs text-muted-foreground">
@@ -1332,6 +1401,18 @@ export function WebdavSyncSection({
               />
             </div>
 
+            {/* Snapshot encryption passphrase */}
+            <PassphraseField
+              value={s3Passphrase}
+              configured={s3Config?.encryptionConfigured}
+              disabled={isS3Loading}
+              onChange={(value) => {
+                setS3Passphrase(value);
+                setS3PassphraseTouched(true);
+                markS3Dirty();
+              }}
+            />
+
             {/* Endpoint (optional) */}
             <div className="flex items-center gap-4">
               <label className="w-40 text-xs font-medium text-foreground shrink-0">
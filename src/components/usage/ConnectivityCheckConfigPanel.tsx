@@ -12,6 +12,10 @@ import {
   type StreamCheckConfig,
 } from "@/lib/api/connectivity-check";
 
+// 后台定时探测仅在代理接管的应用上生效
+const PROBE_APPS = ["claude", "codex", "gemini"] as const;
+type ProbeApp = (typeof PROBE_APPS)[number];
+
 export function ConnectivityCheckConfigPanel() {
   const { t } = useTranslation();
   const [isLoading, setIsLoading] = useState(true);
@@ -23,6 +27,9 @@ export function ConnectivityCheckConfigPanel() {
     maxRetries: "1",
     degradedThresholdMs: "6000",
   });
+  const [probeIntervals, setProbeIntervals] = useState<
+    Record<ProbeApp, string>
+  >({ claude: "0", codex: "0", gemini: "0" });
 
   useEffect(() => {
     loadConfig();
@@ -38,6 +45,11 @@ export function ConnectivityCheckConfigPanel() {
         maxRetries: String(data.maxRetries),
         degradedThresholdMs: String(data.degradedThresholdMs),
       });
+      setProbeIntervals({
+        claude: String(data.probeIntervalSecs?.claude ?? 0),
+        codex: String(data.probeIntervalSecs?.codex ?? 0),
+        gemini: String(data.probeIntervalSecs?.gemini ?? 0),
+      });
     } catch (e) {
       setError(String(e));
     } finally {
@@ -57,6 +69,9 @@ export function ConnectivityCheckConfigPanel() {
         timeoutSecs: parseNum(config.timeoutSecs, 8),
         maxRetries: parseNum(config.maxRetries, 1),
         degradedThresholdMs: parseNum(config.degradedThresholdMs, 6000),
+        probeIntervalSecs: Object.fromEntries(
+          PROBE_APPS.map((app) => [app, parseNum(probeIntervals[app], 0)]),
+        ),
       };
       await saveStreamCheckConfig(parsed);
       toast.success(t("streamCheck.configSaved"), {
@@ -149,6 +164,38 @@ export function ConnectivityCheckConfigPanel() {
         </div>
       </div>
 
+      {/* 后台定时探测：代理运行时按间隔检查故障转移队列并联动熔断器 */}
+      <div className="space-y-4">
+        <h4 className="text-sm font-medium text-muted-foreground">
+          {t("streamCheck.backgroundProbe")}
+        </h4>
+        <p className="text-xs text-muted-foreground">
+          {t("streamCheck.backgroundProbeHint")}
+        </p>
+        <div className="grid grid-cols-1 md:grid-cols-3 gap-4">
+          {PROBE_APPS.map((app) => (
+            <div key={app} className="space-y-2">
+              <Label htmlFor={`probeInterval-${app}`}>
+                {t(`apps.${app}`)}
+              </Label>
+              <Input
+                id={`probeInterval-${app}`}
+                type="number"
+                min={0}
+                step={30}
+                value={probeIntervals[app]}
+                onChange={(e) =>
+                  setProbeIntervals({
+                    ...probeIntervals,
+                    [app]: e.target.value,
+                  })
+                }
+              />
+            </div>
+          ))}
+        </div>
+      </div>
+
       <div className="flex justify-end">
         <Button onClick={handleSave} disabled={isSaving}>
           {isSaving ? (
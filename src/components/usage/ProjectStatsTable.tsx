@@ -0,0 +1,109 @@
+import { useTranslation } from "react-i18next";
+import { ChevronRight } from "lucide-react";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { Button } from "@/components/ui/button";
+import { useProjectStats } from "@/lib/query/usage";
+import { fmtUsd } from "./format";
+import type { UsageRangeSelection } from "@/types/usage";
+
+interface ProjectStatsTableProps {
+  range: UsageRangeSelection;
+  appType?: string;
+  refreshIntervalMs: number;
+  /** 查看该项目下的会话；未归属项目的用量无法按目录筛选，不提供入口 */
+  onSelectProject?: (projectDir: string) => void;
+}
+
+export function ProjectStatsTable({
+  range,
+  appType,
+  refreshIntervalMs,
+  onSelectProject,
+}: ProjectStatsTableProps) {
+  const { t } = useTranslation();
+  const { data: stats, isLoading } = useProjectStats(range, appType, {
+    refetchInterval: refreshIntervalMs > 0 ? refreshIntervalMs : false,
+  });
+
+  if (isLoading) {
+    return <div className="h-[400px] animate-pulse rounded bg-gray-100" />;
+  }
+
+  return (
+    <div className="rounded-lg border border-border/50 bg-card/40 backdrop-blur-sm overflow-hidden">
+      <Table>
+        <TableHeader>
+          <TableRow>
+            <TableHead>{t("usage.project", "项目")}</TableHead>
+            <TableHead className="text-right">
+              {t("usage.requests", "请求数")}
+            </TableHead>
+            <TableHead className="text-right">
+              {t("usage.tokens", "Tokens")}
+            </TableHead>
+            <TableHead className="text-right">
+              {t("usage.totalCost", "总成本")}
+            </TableHead>
+            <TableHead className="w-[120px]" />
+          </TableRow>
+        </TableHeader>
+        <TableBody>
+          {stats?.length === 0 ? (
+            <TableRow>
+              <TableCell
+                colSpan={5}
+                className="text-center text-muted-foreground"
+              >
+                {t("usage.noData", "暂无数据")}
+              </TableCell>
+            </TableRow>
+          ) : (
+            stats?.map((stat) => (
+              <TableRow key={stat.projectDir ?? ""}>
+                <TableCell
+                  className="max-w-[360px] truncate font-mono text-sm"
+                  title={stat.projectDir ?? undefined}
+                >
+                  {stat.projectDir ?? (
+                    <span className="font-sans text-muted-foreground">
+                      {t("usage.unattributedProject", "未归属项目")}
+                    </span>
+                  )}
+                </TableCell>
+                <TableCell className="text-right">
+                  {stat.requestCount.toLocaleString()}
+                </TableCell>
+                <TableCell className="text-right">
+                  {stat.totalTokens.toLocaleString()}
+                </TableCell>
+                <TableCell className="text-right">
+                  {fmtUsd(stat.totalCost, 4)}
+                </TableCell>
+                <TableCell className="text-right">
+                  {stat.projectDir && onSelectProject && (
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      className="h-7 gap-1 text-xs"
+                      onClick={() => onSelectProject(stat.projectDir!)}
+                    >
+                      {t("usage.viewSessions", "查看会话")}
+                      <ChevronRight className="h-3.5 w-3.5" />
+                    </Button>
+                  )}
+                </TableCell>
+              </TableRow>
+            ))
+          )}
+        </TableBody>
+      </Table>
+    </div>
+  );
+}
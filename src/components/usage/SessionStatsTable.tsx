@@ -0,0 +1,278 @@
+import { useCallback, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Loader2, MessageSquareText, X } from "lucide-react";
+import { toast } from "sonner";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { Button } from "@/components/ui/button";
+import { SessionMessageItem } from "@/components/sessions/SessionMessageItem";
+import { useSessionMessagesQuery } from "@/lib/query/queries";
+import { useSessionStats } from "@/lib/query/usage";
+import { extractErrorMessage } from "@/utils/errorUtils";
+import { fmtUsd, getLocaleFromLanguage } from "./format";
+import type { SessionStats, UsageRangeSelection } from "@/types/usage";
+
+/** 只列出成本最高的若干会话，完整明细走导出 */
+const TOP_SESSIONS_LIMIT = 20;
+
+interface SessionStatsTableProps {
+  range: UsageRangeSelection;
+  appType?: string;
+  projectDir?: string;
+  onClearProject?: () => void;
+  refreshIntervalMs: number;
+}
+
+export function SessionStatsTable({
+  range,
+  appType,
+  projectDir,
+  onClearProject,
+  refreshIntervalMs,
+}: SessionStatsTableProps) {
+  const { t, i18n } = useTranslation();
+  const [viewing, setViewing] = useState<SessionStats | null>(null);
+  const { data: stats, isLoading } = useSessionStats(
+    range,
+    { appType, projectDir },
+    TOP_SESSIONS_LIMIT,
+    {
+      refetchInterval: refreshIntervalMs > 0 ? refreshIntervalMs : false,
+    },
+  );
+  const locale = getLocaleFromLanguage(
+    i18n.resolvedLanguage || i18n.language || "en",
+  );
+
+  if (isLoading) {
+    return <div className="h-[400px] animate-pulse rounded bg-gray-100" />;
+  }
+
+  return (
+    <div className="space-y-3">
+      {projectDir && (
+        <div className="flex items-center gap-2 text-sm text-muted-foreground">
+          <span>{t("usage.sessionsInProject", "项目")}:</span>
+          <span
+            className="truncate font-mono text-foreground"
+            title={projectDir}
+          >
+            {projectDir}
+          </span>
+          {onClearProject && (
+            <Button
+              variant="ghost"
+              size="sm"
+              className="h-7 gap-1 text-xs"
+              onClick={onClearProject}
+            >
+              <X className="h-3.5 w-3.5" />
+              {t("usage.clearProjectFilter", "显示全部项目")}
+            </Button>
+          )}
+        </div>
+      )}
+
+      <div className="rounded-lg border border-border/50 bg-card/40 backdrop-blur-sm overflow-hidden">
+        <Table>
+          <TableHeader>
+            <TableRow>
+              <TableHead>{t("usage.session", "会话")}</TableHead>
+              <TableHead>{t("usage.project", "项目")}</TableHead>
+              <TableHead className="text-right">
+                {t("usage.requests", "请求数")}
+              </TableHead>
+              <TableHead className="text-right">
+                {t("usage.tokens", "Tokens")}
+              </TableHead>
+              <TableHead className="text-right">
+                {t("usage.totalCost", "总成本")}
+              </TableHead>
+              <TableHead className="text-right">
+                {t("usage.lastActive", "最近活动")}
+              </TableHead>
+              <TableHead className="w-[60px]" />
+            </TableRow>
+          </TableHeader>
+          <TableBody>
+            {stats?.length === 0 ? (
+              <TableRow>
+                <TableCell
+                  colSpan={7}
+                  className="text-center text-muted-foreground"
+                >
+                  {t("usage.noData", "暂无数据")}
+                </TableCell>
+              </TableRow>
+            ) : (
+              stats?.map((stat) => {
+                const canView = Boolean(
+                  stat.sessionProviderId && stat.sourcePath,
+                );
+                return (
+                  <TableRow key={`${stat.appType}:${stat.sessionId}`}>
+                    <TableCell className="max-w-[220px]">
+                      <div
+                        className="truncate font-mono text-sm"
+                        title={stat.sessionId}
+                      >
+                        {stat.sessionId}
+                      </div>
+                      <div className="text-xs text-muted-foreground">
+                        {t(`usage.appFilter.${stat.appType}`, stat.appType)}
+                      </div>
+                    </TableCell>
+                    <TableCell
+                      className="max-w-[240px] truncate font-mono text-xs text-muted-foreground"
+                      title={stat.projectDir ?? undefined}
+                    >
+                      {stat.projectDir ?? "-"}
+                    </TableCell>
+                    <TableCell className="text-right">
+                      {stat.requestCount.toLocaleString()}
+                    </TableCell>
+                    <TableCell className="text-right">
+                      {stat.totalTokens.toLocaleString()}
+                    </TableCell>
+                    <TableCell className="text-right">
+                      {fmtUsd(stat.totalCost, 4)}
+                    </TableCell>
+                    <TableCell className="text-right text-xs text-muted-foreground">
+                      {new Date(stat.lastRequestAt * 1000).toLocaleString(
+                        locale,
+                      )}
+                    </TableCell>
+                    <TableCell className="text-right">
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        className="h-7 w-7"
+                        disabled={!canView}
+                        onClick={() => setViewing(stat)}
+                        title={
+                          canView
+                            ? t("usage.viewSessionMessages", "查看会话消息")
+                            : t(
+                                "usage.sessionMessagesUnavailable",
+                                "会话日志未同步，无法查看消息",
+                              )
+                        }
+                        aria-label={t(
+                          "usage.viewSessionMessages",
+                          "查看会话消息",
+                        )}
+                      >
+                        <MessageSquareText className="h-4 w-4" />
+                      </Button>
+                    </TableCell>
+                  </TableRow>
+                );
+              })
+            )}
+          </TableBody>
+        </Table>
+      </div>
+
+      <SessionMessagesDialog
+        session={viewing}
+        onClose={() => setViewing(null)}
+      />
+    </div>
+  );
+}
+
+function SessionMessagesDialog({
+  session,
+  onClose,
+}: {
+  session: SessionStats | null;
+  onClose: () => void;
+}) {
+  const { t } = useTranslation();
+  const {
+    data: messages,
+    isLoading,
+    error,
+  } = useSessionMessagesQuery(
+    session?.sessionProviderId ?? undefined,
+    session?.sourcePath ?? undefined,
+  );
+
+  const handleCopy = useCallback(
+    async (content: string) => {
+      try {
+        await navigator.clipboard.writeText(content);
+        toast.success(
+          t("sessionManager.messageCopied", { defaultValue: "已复制消息内容" }),
+        );
+      } catch (copyError) {
+        toast.error(
+          extractErrorMessage(copyError) ||
+            t("common.error", { defaultValue: "Copy failed" }),
+        );
+      }
+    },
+    [t],
+  );
+
+  return (
+    <Dialog
+      open={session !== null}
+      onOpenChange={(nextOpen) => {
+        if (!nextOpen) onClose();
+      }}
+    >
+      <DialogContent zIndex="top" className="max-w-4xl h-[80vh]">
+        <DialogHeader>
+          <DialogTitle>
+            {t("usage.sessionMessagesTitle", "会话消息")}
+          </DialogTitle>
+          <DialogDescription className="truncate font-mono">
+            {session?.projectDir ?? session?.sessionId}
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="flex-1 min-h-0 overflow-y-auto space-y-3 px-6 py-4">
+          {isLoading ? (
+            <div className="flex h-full items-center justify-center">
+              <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+            </div>
+          ) : error ? (
+            <p className="text-sm text-destructive">
+              {t("usage.sessionMessagesLoadFailed", {
+                defaultValue: "加载会话消息失败：{{error}}",
+                error: extractErrorMessage(error) || String(error),
+              })}
+            </p>
+          ) : messages?.length ? (
+            messages.map((message, index) => (
+              <SessionMessageItem
+                key={index}
+                message={message}
+                isActive={false}
+                onCopy={(content) => void handleCopy(content)}
+              />
+            ))
+          ) : (
+            <p className="text-center text-sm text-muted-foreground">
+              {t("usage.noData", "暂无数据")}
+            </p>
+          )}
+        </div>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -5,10 +5,13 @@ import { UsageTrendChart } from "./UsageTrendChart";
 import { RequestLogTable } from "./RequestLogTable";
 import { ProviderStatsTable } from "./ProviderStatsTable";
 import { ModelStatsTable } from "./ModelStatsTable";
+import { ProjectStatsTable } from "./ProjectStatsTable";
+import { SessionStatsTable } from "./SessionStatsTable";
 import {
   KNOWN_APP_TYPES,
   type AppType,
   type AppTypeFilter,
+  type UsageExportFormat,
   type UsageRangeSelection,
 } from "@/types/usage";
 import { motion } from "framer-motion";
@@ -21,6 +24,9 @@ import {
   LayoutGrid,
   DatabaseBackup,
   Loader2,
+  FolderGit2,
+  MessagesSquare,
+  Download,
 } from "lucide-react";
 import { ProviderIcon } from "@/components/ProviderIcon";
 import {
@@ -46,12 +52,20 @@ import { getUsageRangePresetLabel, resolveUsageRange } from "@/lib/usageRange";
 import { UsageDateRangePicker } from "./UsageDateRangePicker";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { Button } from "@/components/ui/button";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
 import { ConfirmDialog } from "@/components/ConfirmDialog";
 import { usageApi } from "@/lib/api/usage";
 import { toast } from "sonner";
 
 const APP_FILTER_OPTIONS: AppTypeFilter[] = ["all", ...KNOWN_APP_TYPES];
 
+const EXPORT_FORMATS: UsageExportFormat[] = ["csv", "jsonl", "parquet"];
+
 const DEFAULT_REFRESH_INTERVAL_MS = 30000;
 const REFRESH_INTERVAL_OPTIONS_MS = [0, 5000, 10000, 30000, 60000] as const;
 type RefreshIntervalOption = (typeof REFRESH_INTERVAL_OPTIONS_MS)[number];
@@ -103,6 +117,11 @@ export function UsageDashboard({
   );
   const [showRebuildConfirm, setShowRebuildConfirm] = useState(false);
   const [rebuildingCodex, setRebuildingCodex] = useState(false);
+  const [activeTab, setActiveTab] = useState("logs");
+  const [sessionProject, setSessionProject] = useState<string | undefined>(
+    undefined,
+  );
+  const [exporting, setExporting] = useState(false);
 
   useEffect(() => {
     setRefreshIntervalMs(normalizeRefreshInterval(savedRefreshIntervalMs));
@@ -115,6 +134,7 @@ export function UsageDashboard({
     if (next !== appType) {
       setProviderName(undefined);
       setModel(undefined);
+      setSessionProject(undefined);
     }
   };
   const changeProviderName = (next: string | undefined) => {
@@ -175,6 +195,48 @@ export function UsageDashboard({
     }
   };
 
+  const showProjectSessions = (projectDir: string) => {
+    setSessionProject(projectDir);
+    setActiveTab("sessions");
+  };
+
+  // 导出沿用顶栏的时间范围与筛选；时间在点击时重新解析，"至今"范围不会截在页面打开时刻
+  const exportUsageData = async (format: UsageExportFormat) => {
+    const stamp = new Date().toISOString().slice(0, 10);
+    const filePath = await usageApi.saveExportDialog(
+      `cc-switch-usage-${stamp}.${format}`,
+      format,
+    );
+    if (!filePath) return;
+    setExporting(true);
+    try {
+      const summary = await usageApi.exportUsageData(
+        {
+          appType: appType === "all" ? undefined : appType,
+          providerName,
+          model,
+          ...resolveUsageRange(range),
+        },
+        format,
+        filePath,
+      );
+      toast.success(
+        t("usage.export.completed", {
+          rows: summary.requestRows + summary.rollupRows,
+          path: summary.filePath,
+        }),
+      );
+    } catch (error) {
+      toast.error(
+        t("usage.export.failed", {
+          error: String(error),
+        }),
+      );
+    } finally {
+      setExporting(false);
+    }
+  };
+
   const language = i18n.resolvedLanguage || i18n.language || "en";
   const locale = getLocaleFromLanguage(language);
   const resolvedRange = useMemo(() => resolveUsageRange(range), [range]);
@@ -386,7 +448,7 @@ export function UsageDashboard({
       />
 
       <div className="space-y-4">
-        <Tabs defaultValue="logs" className="w-full">
+        <Tabs value={activeTab} onValueChange={setActiveTab} className="w-full">
           <div className="flex items-center justify-between mb-4">
             <TabsList className="bg-muted/50">
               <TabsTrigger value="logs" className="gap-2">
@@ -401,7 +463,43 @@ export function UsageDashboard({
                 <BarChart3 className="h-4 w-4" />
                 {t("usage.modelStats")}
               </TabsTrigger>
+              <TabsTrigger value="projects" className="gap-2">
+                <FolderGit2 className="h-4 w-4" />
+                {t("usage.projectStats")}
+              </TabsTrigger>
+              <TabsTrigger value="sessions" className="gap-2">
+                <MessagesSquare className="h-4 w-4" />
+                {t("usage.sessionStats")}
+              </TabsTrigger>
             </TabsList>
+
+            <DropdownMenu>
+              <DropdownMenuTrigger asChild>
+                <Button
+                  variant="outline"
+                  size="sm"
+                  className="h-9 gap-2"
+                  disabled={exporting}
+                >
+                  {exporting ? (
+                    <Loader2 className="h-4 w-4 animate-spin" />
+                  ) : (
+                    <Download className="h-4 w-4" />
+                  )}
+                  {t("usage.export.action")}
+                </Button>
+              </DropdownMenuTrigger>
+              <DropdownMenuContent align="end">
+                {EXPORT_FORMATS.map((format) => (
+                  <DropdownMenuItem
+                    key={format}
+                    onSelect={() => void exportUsageData(format)}
+                  >
+                    {t(`usage.export.format.${format}`)}
+                  </DropdownMenuItem>
+                ))}
+              </DropdownMenuContent>
+            </DropdownMenu>
           </div>
 
           <motion.div
@@ -440,6 +538,25 @@ export function UsageDashboard({
                 refreshIntervalMs={refreshIntervalMs}
               />
             </TabsContent>
+
+            <TabsContent value="projects" className="mt-0">
+              <ProjectStatsTable
+                range={range}
+                appType={appType}
+                refreshIntervalMs={refreshIntervalMs}
+                onSelectProject={showProjectSessions}
+              />
+            </TabsContent>
+
+            <TabsContent value="sessions" className="mt-0">
+              <SessionStatsTable
+                range={range}
+                appType={appType}
+                projectDir={sessionProject}
+                onClearProject={() => setSessionProject(undefined)}
+                refreshIntervalMs={refreshIntervalMs}
+              />
+            </TabsContent>
           </motion.div>
         </Tabs>
       </div>
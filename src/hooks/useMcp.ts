@@ -14,6 +14,94 @@ export function useAllMcpServers() {
   });
 }
 
+/**
+ * 查询所有 MCP 服务器最近一次的探测结果
+ */
+export function useMcpProbeResults() {
+  return useQuery({
+    queryKey: ["mcp", "probes"],
+    queryFn: () => mcpApi.getProbeResults(),
+  });
+}
+
+/**
+ * 探测单个 MCP 服务器（结果会写入数据库）
+ */
+export function useProbeMcpServer() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (id: string) => mcpApi.probeServer(id),
+    onSettled: () =>
+      queryClient.invalidateQueries({ queryKey: ["mcp", "probes"] }),
+  });
+}
+
+/**
+ * 查询 MCP 网关模式开关
+ */
+export function useMcpGatewayEnabled() {
+  return useQuery({
+    queryKey: ["mcp", "gateway"],
+    queryFn: () => mcpApi.getGatewayEnabled(),
+  });
+}
+
+/**
+ * 切换 MCP 网关模式
+ */
+export function useSetMcpGatewayEnabled() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (enabled: boolean) => mcpApi.setGatewayEnabled(enabled),
+    onSettled: () => queryClient.invalidateQueries({ queryKey: ["mcp"] }),
+  });
+}
+
+/**
+ * 查询本地密钥库中的密钥名称
+ */
+export function useMcpSecrets() {
+  return useQuery({
+    queryKey: ["mcp", "secrets"],
+    queryFn: () => mcpApi.listSecrets(),
+  });
+}
+
+/**
+ * 查询引用了本机缺失密钥的服务器（这些服务器不会写入各应用配置）
+ */
+export function useMcpServersNeedingConfig() {
+  return useQuery({
+    queryKey: ["mcp", "secrets", "needingConfig"],
+    queryFn: () => mcpApi.getServersNeedingConfig(),
+  });
+}
+
+/**
+ * 保存本地密钥
+ */
+export function useSetMcpSecret() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: ({ name, value }: { name: string; value: string }) =>
+      mcpApi.setSecret(name, value),
+    onSettled: () =>
+      queryClient.invalidateQueries({ queryKey: ["mcp", "secrets"] }),
+  });
+}
+
+/**
+ * 删除本地密钥
+ */
+export function useDeleteMcpSecret() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (name: string) => mcpApi.deleteSecret(name),
+    onSettled: () =>
+      queryClient.invalidateQueries({ queryKey: ["mcp", "secrets"] }),
+  });
+}
+
 /**
  * 添加或更新 MCP 服务器
  */
@@ -23,8 +111,8 @@ export function useUpsertMcpServer() {
     mutationFn: (server: McpServer) => mcpApi.upsertUnifiedServer(server),
     // The database is updated before live configs are synchronized, so an
     // error can still leave a persisted change that the list must reflect.
-    onSettled: () =>
-      queryClient.invalidateQueries({ queryKey: ["mcp", "all"] }),
+    // Changing the connection spec also clears the stored probe result.
+    onSettled: () => queryClient.invalidateQueries({ queryKey: ["mcp"] }),
   });
 }
 
@@ -80,8 +168,7 @@ export function useDeleteMcpServer() {
     mutationFn: (id: string) => mcpApi.deleteUnifiedServer(id),
     // Deletion reaches the database before live-config cleanup, so refresh
     // after both success and failure to avoid operating on a removed entry.
-    onSettled: () =>
-      queryClient.invalidateQueries({ queryKey: ["mcp", "all"] }),
+    onSettled: () => queryClient.invalidateQueries({ queryKey: ["mcp"] }),
   });
 }
 
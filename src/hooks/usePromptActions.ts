@@ -5,6 +5,20 @@ import { promptsApi, type Prompt, type AppId } from "@/lib/api";
 
 const EMPTY_PROMPTS: Record<string, Prompt> = {};
 
+/**
+ * 后端会叠加启用多个片段的应用；Pi 只能启用一个提示词，Claude Desktop
+ * 不支持提示词，二者都不做乐观更新，以免界面先翻转再被后端结果打回
+ */
+const MULTI_FRAGMENT_APPS: ReadonlySet<AppId> = new Set<AppId>([
+  "claude",
+  "codex",
+  "gemini",
+  "grokbuild",
+  "opencode",
+  "openclaw",
+  "hermes",
+]);
+
 export function usePromptActions(appId: AppId) {
   const { t } = useTranslation();
   const [prompts, setPrompts] = useState<Record<string, Prompt>>({});
@@ -201,32 +215,22 @@ export function usePromptActions(appId: AppId) {
 
       const previousPrompts = visiblePrompts;
       const mutationGeneration = reloadGenerationRef.current;
+      const optimistic = MULTI_FRAGMENT_APPS.has(appId);
 
-      if (enabled) {
-        const updatedPrompts = Object.keys(visiblePrompts).reduce(
-          (acc, key) => {
-            acc[key] = {
-              ...visiblePrompts[key],
-              enabled: key === id,
-            };
-            return acc;
-          },
-          {} as Record<string, Prompt>,
-        );
-        updatePromptsForApp(appId, () => updatedPrompts);
-      } else {
+      // 多片段应用中开关只影响当前片段，可以先行更新界面
+      if (optimistic) {
         updatePromptsForApp(appId, (current) => ({
           ...current,
           [id]: {
             ...current[id],
-            enabled: false,
+            enabled,
           },
         }));
       }
 
       try {
         if (enabled) {
-          await promptsApi.enablePrompt(appId, id);
+          await promptsApi.enablePromptFragment(appId, id);
           toast.success(t("prompts.enableSuccess"), { closeButton: true });
         } else {
           await promptsApi.upsertPrompt(appId, id, {
@@ -238,6 +242,7 @@ export function usePromptActions(appId: AppId) {
         return currentAppIdRef.current === appId ? await reload() : false;
       } catch (error) {
         if (
+          optimistic &&
           currentAppIdRef.current === appId &&
           reloadGenerationRef.current === mutationGeneration
         ) {
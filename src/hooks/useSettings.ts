@@ -204,6 +204,8 @@ export function useSettings(): UseSettingsResult {
         const {
           webdavSync: _ignoredWebdavSync,
           s3Sync: _ignoredS3Sync,
+          folderSync: _ignoredFolderSync,
+          gitSync: _ignoredGitSync,
           ...restSettings
         } = mergedSettings;
 
@@ -347,6 +349,8 @@ export function useSettings(): UseSettingsResult {
         const {
           webdavSync: _ignoredWebdavSync,
           s3Sync: _ignoredS3Sync,
+          folderSync: _ignoredFolderSync,
+          gitSync: _ignoredGitSync,
           ...restSettings
         } = mergedSettings;
 
@@ -0,0 +1,78 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { promptsApi, type SharedPrompt } from "@/lib/api";
+
+/** 共享提示词（同一份内容同步到多个应用）的加载与增删改 */
+export function useSharedPrompts() {
+  const { t } = useTranslation();
+  const [sharedPrompts, setSharedPrompts] = useState<
+    Record<string, SharedPrompt>
+  >({});
+  const [loading, setLoading] = useState(false);
+  const reloadGenerationRef = useRef(0);
+
+  useEffect(
+    () => () => {
+      reloadGenerationRef.current += 1;
+    },
+    [],
+  );
+
+  const reload = useCallback(async () => {
+    const generation = ++reloadGenerationRef.current;
+    setLoading(true);
+    try {
+      const data = await promptsApi.getSharedPrompts();
+      if (reloadGenerationRef.current === generation) setSharedPrompts(data);
+    } catch {
+      if (reloadGenerationRef.current === generation) {
+        toast.error(t("prompts.shared.loadFailed"));
+      }
+    } finally {
+      if (reloadGenerationRef.current === generation) setLoading(false);
+    }
+  }, [t]);
+
+  const saveSharedPrompt = useCallback(
+    async (prompt: SharedPrompt) => {
+      try {
+        await promptsApi.upsertSharedPrompt(prompt);
+        setSharedPrompts((current) => ({ ...current, [prompt.id]: prompt }));
+        toast.success(t("prompts.saveSuccess"), { closeButton: true });
+      } catch (error) {
+        toast.error(t("prompts.saveFailed"));
+        throw error;
+      }
+      await reload();
+    },
+    [reload, t],
+  );
+
+  const deleteSharedPrompt = useCallback(
+    async (id: string) => {
+      try {
+        await promptsApi.deleteSharedPrompt(id);
+        setSharedPrompts((current) => {
+          const next = { ...current };
+          delete next[id];
+          return next;
+        });
+        toast.success(t("prompts.deleteSuccess"), { closeButton: true });
+      } catch (error) {
+        toast.error(t("prompts.deleteFailed"));
+        throw error;
+      }
+      await reload();
+    },
+    [reload, t],
+  );
+
+  return {
+    sharedPrompts,
+    loading,
+    reload,
+    saveSharedPrompt,
+    deleteSharedPrompt,
+  };
+}
@@ -0,0 +1,41 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { syncConflictsApi } from "@/lib/api";
+import type { SyncConflictResolution } from "@/lib/api";
+
+export const SYNC_CONFLICTS_QUERY_KEY = ["sync-conflicts"];
+
+export function useSyncConflicts() {
+  const queryClient = useQueryClient();
+
+  const { data: conflicts = [], isLoading } = useQuery({
+    queryKey: SYNC_CONFLICTS_QUERY_KEY,
+    queryFn: () => syncConflictsApi.list(),
+  });
+
+  const resolveMutation = useMutation({
+    mutationFn: ({
+      id,
+      resolution,
+    }: {
+      id: string;
+      resolution: SyncConflictResolution;
+    }) => syncConflictsApi.resolve(id, resolution),
+    onSuccess: async (_result, { resolution }) => {
+      if (resolution === "remote") {
+        // 采用远端会改写数据库中的供应商/提示词等，整体刷新
+        await queryClient.invalidateQueries();
+      } else {
+        await queryClient.invalidateQueries({
+          queryKey: SYNC_CONFLICTS_QUERY_KEY,
+        });
+      }
+    },
+  });
+
+  return {
+    conflicts,
+    isLoading,
+    resolve: resolveMutation.mutateAsync,
+    isResolving: resolveMutation.isPending,
+  };
+}
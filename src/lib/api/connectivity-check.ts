@@ -2,7 +2,8 @@ import { invoke } from "@tauri-apps/api/core";
 import type { AppId } from "./types";
 
 // ===== 连通性检查类型 =====
-// 注意：本检查只探测 base_url 是否可达，不发真实大模型请求，也不触碰故障转移熔断器。
+// 注意：本检查只探测 base_url 是否可达，不发真实大模型请求；手动检查不触碰故障转移熔断器，
+// 仅代理运行时的后台定时探测（probeIntervalSecs）会据结果打开/恢复熔断器。
 
 export type HealthStatus = "operational" | "degraded" | "failed";
 
@@ -13,6 +14,8 @@ export interface StreamCheckConfig {
   maxRetries: number;
   /** 降级阈值（毫秒）：可达但 TTFB 超过该值判定为"较慢" */
   degradedThresholdMs: number;
+  /** 后台定时探测间隔（秒），key 为应用；缺省或 0 表示不探测 */
+  probeIntervalSecs?: Partial<Record<AppId, number>>;
 }
 
 export interface StreamCheckResult {
@@ -84,6 +84,19 @@ export const failoverApi = {
     return invoke("remove_from_failover_queue", { appType, providerId });
   },
 
+  // 设置供应商在加权路由策略下的权重（null 恢复默认权重）
+  async setFailoverRoutingWeight(
+    appType: string,
+    providerId: string,
+    weight: number | null,
+  ): Promise<void> {
+    return invoke("set_failover_routing_weight", {
+      appType,
+      providerId,
+      weight,
+    });
+  },
+
   // 获取指定应用的自动故障转移开关状态
   async getAutoFailoverEnabled(appType: string): Promise<boolean> {
     return invoke("get_auto_failover_enabled", { appType });
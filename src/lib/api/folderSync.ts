@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { FolderSyncSettings, RemoteSnapshotInfo } from "@/types";
+import type { WebDavSyncResult, WebDavTestResult } from "./settings";
+
+export const folderSyncApi = {
+  async testConnection(
+    settings: FolderSyncSettings,
+  ): Promise<WebDavTestResult> {
+    return await invoke("folder_sync_test_connection", { settings });
+  },
+
+  async upload(): Promise<WebDavSyncResult> {
+    return await invoke("folder_sync_upload");
+  },
+
+  async download(): Promise<WebDavSyncResult> {
+    return await invoke("folder_sync_download");
+  },
+
+  async saveSettings(
+    settings: FolderSyncSettings,
+  ): Promise<{ success: boolean }> {
+    return await invoke("folder_sync_save_settings", {
+      settings,
+      // 口令从不回传前端：只有用户编辑过（含清空）时才随设置提交
+      passphraseTouched: settings.encryptionPassphrase !== undefined,
+    });
+  },
+
+  async fetchRemoteInfo(): Promise<RemoteSnapshotInfo | { empty: true }> {
+    return await invoke("folder_sync_fetch_remote_info");
+  },
+};
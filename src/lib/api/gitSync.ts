@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { GitSyncSettings, RemoteSnapshotInfo } from "@/types";
+import type { WebDavSyncResult, WebDavTestResult } from "./settings";
+
+export const gitSyncApi = {
+  async testConnection(settings: GitSyncSettings): Promise<WebDavTestResult> {
+    return await invoke("git_sync_test_connection", { settings });
+  },
+
+  async upload(): Promise<WebDavSyncResult> {
+    return await invoke("git_sync_upload");
+  },
+
+  async download(): Promise<WebDavSyncResult> {
+    return await invoke("git_sync_download");
+  },
+
+  async saveSettings(settings: GitSyncSettings): Promise<{ success: boolean }> {
+    return await invoke("git_sync_save_settings", {
+      settings,
+      passphraseTouched: settings.encryptionPassphrase !== undefined,
+    });
+  },
+
+  async fetchRemoteInfo(): Promise<RemoteSnapshotInfo | { empty: true }> {
+    return await invoke("git_sync_fetch_remote_info");
+  },
+};
@@ -5,6 +5,10 @@ export { settingsApi } from "./settings";
 export { backupsApi } from "./settings";
 export { mcpApi } from "./mcp";
 export { profilesApi } from "./profiles";
+export { projectBindingsApi } from "./projectBindings";
+export { syncConflictsApi } from "./syncConflicts";
+export { folderSyncApi } from "./folderSync";
+export { gitSyncApi } from "./gitSync";
 export { promptsApi } from "./prompts";
 export { skillsApi } from "./skills";
 export { usageApi } from "./usage";
@@ -18,8 +22,14 @@ export * as configApi from "./config";
 export * as authApi from "./auth";
 export * as copilotApi from "./copilot";
 export type { ProviderSwitchEvent } from "./providers";
-export type { Prompt } from "./prompts";
+export type { Prompt, PromptApps, SharedPrompt } from "./prompts";
 export type { Profile, ProfilePayload, ProfilesResponse } from "./profiles";
+export type { ProjectBinding } from "./projectBindings";
+export type {
+  SyncConflict,
+  SyncConflictResolution,
+  SyncResolveResult,
+} from "./syncConflicts";
 export type {
   CopilotDeviceCodeResponse,
   CopilotAuthStatus,
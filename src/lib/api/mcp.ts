@@ -1,6 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   McpConfigResponse,
+  McpProbeResult,
+  McpSecretInfo,
   McpServer,
   McpServerSpec,
   McpServersMap,
@@ -126,4 +128,70 @@ export const mcpApi = {
   async importFromApps(): Promise<number> {
     return await invoke("import_mcp_from_apps");
   },
+
+  /**
+   * 获取所有 MCP 服务器最近一次的探测结果（id -> 结果）
+   */
+  async getProbeResults(): Promise<Record<string, McpProbeResult>> {
+    return await invoke("get_mcp_probe_results");
+  },
+
+  /**
+   * 启动/连接服务器并完成握手，记录版本与工具清单
+   */
+  async probeServer(
+    id: string,
+    timeoutSecs?: number,
+  ): Promise<McpProbeResult> {
+    return await invoke("probe_mcp_server", { id, timeoutSecs });
+  },
+
+  /**
+   * 探测全部 MCP 服务器
+   */
+  async probeAllServers(timeoutSecs?: number): Promise<McpProbeResult[]> {
+    return await invoke("probe_all_mcp_servers", { timeoutSecs });
+  },
+
+  /**
+   * 是否启用 MCP 网关模式（各应用只写入一个指向本地代理的条目）
+   */
+  async getGatewayEnabled(): Promise<boolean> {
+    return await invoke("get_mcp_gateway_enabled");
+  },
+
+  /**
+   * 切换 MCP 网关模式，并重新同步所有应用的 MCP 配置
+   */
+  async setGatewayEnabled(enabled: boolean): Promise<void> {
+    return await invoke("set_mcp_gateway_enabled", { enabled });
+  },
+
+  /**
+   * 列出本地密钥库中的密钥名称（值不会返回前端）
+   */
+  async listSecrets(): Promise<McpSecretInfo[]> {
+    return await invoke("list_mcp_secrets");
+  },
+
+  /**
+   * 保存本地密钥，并重新同步引用它的服务器
+   */
+  async setSecret(name: string, value: string): Promise<void> {
+    return await invoke("set_mcp_secret", { name, value });
+  },
+
+  /**
+   * 获取引用了本机缺失密钥、需要先补齐配置的服务器（id -> 缺失的密钥名称）
+   */
+  async getServersNeedingConfig(): Promise<Record<string, string[]>> {
+    return await invoke("get_mcp_servers_needing_config");
+  },
+
+  /**
+   * 删除本地密钥（仍被引用时会失败）
+   */
+  async deleteSecret(name: string): Promise<boolean> {
+    return await invoke("delete_mcp_secret", { name });
+  },
 };
@@ -0,0 +1,48 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+
+/**
+ * 项目绑定（与后端 database/dao/project_bindings.rs 的 ProjectBinding 严格对应）
+ *
+ * 目录 × 应用 → 供应商 / MCP 集合，写入该目录的项目级配置。
+ */
+export interface ProjectBinding {
+  path: string;
+  appType: AppId;
+  providerId?: string;
+  mcpServerIds: string[];
+  createdAt: number;
+  updatedAt: number;
+}
+
+export const projectBindingsApi = {
+  /**
+   * 获取所有项目绑定
+   */
+  async list(): Promise<ProjectBinding[]> {
+    return await invoke("list_project_bindings");
+  },
+
+  /**
+   * 绑定目录（同一目录与应用重复调用即为更新），仅支持 claude / codex / gemini
+   */
+  async set(
+    path: string,
+    app: AppId,
+    options: { providerId?: string | null; mcpServerIds?: string[] },
+  ): Promise<ProjectBinding> {
+    return await invoke("set_project_binding", {
+      path,
+      app,
+      providerId: options.providerId ?? null,
+      mcpServerIds: options.mcpServerIds ?? [],
+    });
+  },
+
+  /**
+   * 解绑并撤销写入的项目级配置，返回绑定是否存在
+   */
+  async remove(path: string, app: AppId): Promise<boolean> {
+    return await invoke("remove_project_binding", { path, app });
+  },
+};
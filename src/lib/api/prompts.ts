@@ -7,6 +7,33 @@ export interface Prompt {
   content: string;
   description?: string;
   enabled: boolean;
+  /** 多个片段同时启用时的拼接顺序（升序） */
+  sortIndex?: number;
+  createdAt?: number;
+  updatedAt?: number;
+}
+
+/** 共享提示词可同步的应用 */
+export interface PromptApps {
+  claude: boolean;
+  codex: boolean;
+  gemini: boolean;
+  grokbuild: boolean;
+  opencode: boolean;
+  openclaw: boolean;
+  hermes: boolean;
+}
+
+/** 同一份提示词同步到多个应用，可按应用追加附录 */
+export interface SharedPrompt {
+  id: string;
+  name: string;
+  content: string;
+  description?: string;
+  apps: PromptApps;
+  /** 按应用追加在共享内容之后的附录，key 为应用 ID */
+  appendices?: Partial<Record<keyof PromptApps, string>>;
+  sortIndex?: number;
   createdAt?: number;
   updatedAt?: number;
 }
@@ -42,6 +69,36 @@ export const promptsApi = {
     return await invoke("enable_prompt", { app, id });
   },
 
+  /** 叠加启用一个片段，与其他已启用片段一起写入提示词文件 */
+  async enablePromptFragment(app: AppId, id: string): Promise<void> {
+    return await invoke("enable_prompt_fragment", { app, id });
+  },
+
+  async reorderPrompts(app: AppId, ids: string[]): Promise<void> {
+    return await invoke("reorder_prompts", { app, ids });
+  },
+
+  /** 传入 app 时返回含内置变量（user/os/arch/home/app）的生效变量表 */
+  async getPromptVariables(app?: AppId): Promise<Record<string, string>> {
+    return await invoke("get_prompt_variables", { app: app ?? null });
+  },
+
+  async setPromptVariables(variables: Record<string, string>): Promise<void> {
+    return await invoke("set_prompt_variables", { variables });
+  },
+
+  async getSharedPrompts(): Promise<Record<string, SharedPrompt>> {
+    return await invoke("get_shared_prompts");
+  },
+
+  async upsertSharedPrompt(prompt: SharedPrompt): Promise<void> {
+    return await invoke("upsert_shared_prompt", { prompt });
+  },
+
+  async deleteSharedPrompt(id: string): Promise<void> {
+    return await invoke("delete_shared_prompt", { id });
+  },
+
   async importFromFile(app: AppId): Promise<string> {
     return await invoke("import_prompt_from_file", { app });
   },
@@ -5,6 +5,9 @@ import type {
   ProxyTakeoverStatus,
   GlobalProxyConfig,
   AppProxyConfig,
+  ProxyCapture,
+  ProxyCaptureSummary,
+  CaptureReplayResult,
 } from "@/types/proxy";
 
 export const proxyApi = {
@@ -91,4 +94,45 @@ export const proxyApi = {
   async setPricingModelSource(appType: string, value: string): Promise<void> {
     return invoke("set_pricing_model_source", { appType, value });
   },
+
+  // ========== 抓包与重放 API ==========
+
+  async getCaptureEnabled(): Promise<boolean> {
+    return invoke("get_proxy_capture_enabled");
+  },
+
+  async setCaptureEnabled(enabled: boolean): Promise<void> {
+    return invoke("set_proxy_capture_enabled", { enabled });
+  },
+
+  async listCaptures(limit?: number): Promise<ProxyCaptureSummary[]> {
+    return invoke("list_proxy_captures", { limit });
+  },
+
+  async getCapture(id: string): Promise<ProxyCapture | null> {
+    return invoke("get_proxy_capture", { id });
+  },
+
+  async clearCaptures(): Promise<number> {
+    return invoke("clear_proxy_captures");
+  },
+
+  // providerId 为空时按正常路由选择供应商
+  async replayCapture(
+    id: string,
+    providerId?: string,
+  ): Promise<CaptureReplayResult> {
+    return invoke("replay_proxy_capture", { id, providerId });
+  },
+
+  // ========== OpenTelemetry 导出 API ==========
+
+  async getOtlpEndpoint(): Promise<string | null> {
+    return invoke("get_otlp_endpoint");
+  },
+
+  // endpoint 为空时关闭导出
+  async setOtlpEndpoint(endpoint: string | null): Promise<void> {
+    return invoke("set_otlp_endpoint", { endpoint });
+  },
 };
@@ -159,6 +159,8 @@ export const settingsApi = {
     return await invoke("webdav_sync_save_settings", {
       settings,
       passwordTouched,
+      // 口令从不回传前端：只有用户编辑过（含清空）时才随设置提交
+      passphraseTouched: settings.encryptionPassphrase !== undefined,
     });
   },
 
@@ -195,6 +197,7 @@ export const settingsApi = {
     return await invoke("s3_sync_save_settings", {
       settings,
       passwordTouched,
+      passphraseTouched: settings.encryptionPassphrase !== undefined,
     });
   },
 
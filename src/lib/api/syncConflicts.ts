@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type SyncRow = Record<string, unknown>;
+
+/** 一行在本机与远端被改成不同内容（三方合并后留待用户处理） */
+export interface SyncConflict {
+  /** `表名:行标识`，解决冲突时使用 */
+  id: string;
+  table: string;
+  key: SyncRow;
+  /** 本机当前内容；null 表示本机已删除 */
+  local: SyncRow | null;
+  /** 远端快照内容；null 表示远端已删除 */
+  remote: SyncRow | null;
+}
+
+export type SyncConflictResolution = "local" | "remote";
+
+export interface SyncResolveResult {
+  success: boolean;
+  remaining: number;
+  /** 采用远端后重写 live 配置失败时的提示 */
+  warning?: string;
+}
+
+export const syncConflictsApi = {
+  async list(): Promise<SyncConflict[]> {
+    return await invoke("sync_list_conflicts");
+  },
+
+  async resolve(
+    id: string,
+    resolution: SyncConflictResolution,
+  ): Promise<SyncResolveResult> {
+    return await invoke("sync_resolve_conflict", { id, resolution });
+  },
+};
@@ -5,6 +5,8 @@ import type {
   DailyStats,
   ProviderStats,
   ModelStats,
+  ProjectStats,
+  SessionStats,
   RequestLog,
   LogFilters,
   ModelPricing,
@@ -14,6 +16,8 @@ import type {
   PaginatedLogs,
   SessionSyncResult,
   DataSourceSummary,
+  UsageExportFormat,
+  UsageExportSummary,
 } from "@/types/usage";
 import type { UsageResult } from "@/types";
 import type { AppId } from "./types";
@@ -128,6 +132,30 @@ export const usageApi = {
     });
   },
 
+  getProjectStats: async (
+    startDate?: number,
+    endDate?: number,
+    appType?: string,
+  ): Promise<ProjectStats[]> => {
+    return invoke("get_project_stats", { startDate, endDate, appType });
+  },
+
+  getSessionStats: async (
+    startDate?: number,
+    endDate?: number,
+    appType?: string,
+    projectDir?: string,
+    limit?: number,
+  ): Promise<SessionStats[]> => {
+    return invoke("get_session_stats", {
+      startDate,
+      endDate,
+      appType,
+      projectDir,
+      limit,
+    });
+  },
+
   getRequestLogs: async (
     filters: LogFilters,
     page: number = 0,
@@ -210,4 +238,19 @@ export const usageApi = {
   getDataSourceBreakdown: async (): Promise<DataSourceSummary[]> => {
     return invoke("get_usage_data_sources");
   },
+
+  exportUsageData: async (
+    filters: LogFilters,
+    format: UsageExportFormat,
+    filePath: string,
+  ): Promise<UsageExportSummary> => {
+    return invoke("export_usage_data", { filters, format, filePath });
+  },
+
+  saveExportDialog: async (
+    defaultName: string,
+    format: UsageExportFormat,
+  ): Promise<string | null> => {
+    return invoke("save_usage_export_dialog", { defaultName, format });
+  },
 };
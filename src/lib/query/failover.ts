@@ -187,6 +187,33 @@ export function useRemoveFromFailoverQueue() {
   });
 }
 
+/**
+ * 设置供应商在加权路由策略下的权重
+ */
+export function useSetFailoverRoutingWeight() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      appType,
+      providerId,
+      weight,
+    }: {
+      appType: string;
+      providerId: string;
+      weight: number | null;
+    }) => failoverApi.setFailoverRoutingWeight(appType, providerId, weight),
+    onSuccess: (_, variables) => {
+      queryClient.invalidateQueries({
+        queryKey: ["failoverQueue", variables.appType],
+      });
+      queryClient.invalidateQueries({
+        queryKey: ["providers", variables.appType],
+      });
+    },
+  });
+}
+
 // ========== 自动故障转移开关 Hooks ==========
 
 /**
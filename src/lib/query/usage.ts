@@ -22,6 +22,11 @@ type RequestLogsQueryArgs = {
   options?: UsageQueryOptions;
 };
 
+type SessionStatsFilters = {
+  appType?: string;
+  projectDir?: string;
+};
+
 type RequestLogsKey = {
   preset: UsageRangeSelection["preset"];
   customStartDate?: number;
@@ -125,6 +130,41 @@ export const usageKeys = {
       filters?.providerName ?? null,
       filters?.model ?? null,
     ] as const,
+  projectStats: (
+    preset: UsageRangeSelection["preset"],
+    customStartDate: number | undefined,
+    customEndDate: number | undefined,
+    appType?: string,
+    liveEndTime?: boolean,
+  ) =>
+    [
+      ...usageKeys.all,
+      "project-stats",
+      preset,
+      customStartDate ?? 0,
+      customEndDate ?? 0,
+      liveEndTime ?? false,
+      appType ?? null,
+    ] as const,
+  sessionStats: (
+    preset: UsageRangeSelection["preset"],
+    customStartDate: number | undefined,
+    customEndDate: number | undefined,
+    filters?: SessionStatsFilters,
+    limit?: number,
+    liveEndTime?: boolean,
+  ) =>
+    [
+      ...usageKeys.all,
+      "session-stats",
+      preset,
+      customStartDate ?? 0,
+      customEndDate ?? 0,
+      liveEndTime ?? false,
+      filters?.appType ?? null,
+      filters?.projectDir ?? null,
+      limit ?? null,
+    ] as const,
   logs: (key: RequestLogsKey, page: number, pageSize: number) =>
     [
       ...usageKeys.all,
@@ -302,6 +342,64 @@ export function useModelStats(
   });
 }
 
+// 项目/会话维度只按应用筛选：会话归属与来源、模型无关
+export function useProjectStats(
+  range: UsageRangeSelection,
+  appType?: string,
+  options?: UsageQueryOptions,
+) {
+  const effectiveAppType = appType === "all" ? undefined : appType;
+  return useQuery({
+    queryKey: usageKeys.projectStats(
+      range.preset,
+      range.customStartDate,
+      range.customEndDate,
+      effectiveAppType,
+      range.liveEndTime,
+    ),
+    queryFn: () => {
+      const { startDate, endDate } = resolveUsageRange(range);
+      return usageApi.getProjectStats(startDate, endDate, effectiveAppType);
+    },
+    refetchInterval: options?.refetchInterval ?? DEFAULT_REFETCH_INTERVAL_MS,
+    refetchIntervalInBackground: options?.refetchIntervalInBackground ?? false,
+  });
+}
+
+export function useSessionStats(
+  range: UsageRangeSelection,
+  filters?: SessionStatsFilters,
+  limit?: number,
+  options?: UsageQueryOptions,
+) {
+  const effective: SessionStatsFilters = {
+    appType: filters?.appType === "all" ? undefined : filters?.appType,
+    projectDir: filters?.projectDir,
+  };
+  return useQuery({
+    queryKey: usageKeys.sessionStats(
+      range.preset,
+      range.customStartDate,
+      range.customEndDate,
+      effective,
+      limit,
+      range.liveEndTime,
+    ),
+    queryFn: () => {
+      const { startDate, endDate } = resolveUsageRange(range);
+      return usageApi.getSessionStats(
+        startDate,
+        endDate,
+        effective.appType,
+        effective.projectDir,
+        limit,
+      );
+    },
+    refetchInterval: options?.refetchInterval ?? DEFAULT_REFETCH_INTERVAL_MS,
+    refetchIntervalInBackground: options?.refetchIntervalInBackground ?? false,
+  });
+}
+
 export function useRequestLogs({
   filters,
   range,
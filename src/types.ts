@@ -187,6 +187,8 @@ export interface ProviderMeta {
   endpointAutoSelect?: boolean;
   // 是否为官方合作伙伴
   isPartner?: boolean;
+  // 本机主密钥无法解密该供应商的密钥（只读标记，后端读取时填写）
+  secretsLocked?: boolean;
   // 合作伙伴促销 key（用于后端识别 PackyCode 等）
   partnerPromotionKey?: string;
   // 供应商成本倍率
@@ -319,6 +321,10 @@ export interface WebDavSyncSettings {
   password?: string;
   remoteRoot?: string;
   profile?: string;
+  /** 快照加密口令：只写不读，仅在用户编辑过（含清空）时提交 */
+  encryptionPassphrase?: string;
+  /** 后端是否已保存加密口令（只读） */
+  encryptionConfigured?: boolean;
   status?: WebDavSyncStatus;
 }
 
@@ -333,6 +339,35 @@ export interface S3SyncSettings {
   endpoint?: string;
   remoteRoot?: string;
   profile?: string;
+  encryptionPassphrase?: string;
+  encryptionConfigured?: boolean;
+  status?: WebDavSyncStatus;
+}
+
+// 本地目录同步配置
+export interface FolderSyncSettings {
+  enabled?: boolean;
+  autoSync?: boolean;
+  /** 同步目录的绝对路径 */
+  path?: string;
+  remoteRoot?: string;
+  profile?: string;
+  encryptionPassphrase?: string;
+  encryptionConfigured?: boolean;
+  status?: WebDavSyncStatus;
+}
+
+// Git 同步配置
+export interface GitSyncSettings {
+  enabled?: boolean;
+  autoSync?: boolean;
+  /** 本地仓库：绝对路径或 file:// URL */
+  repoUrl?: string;
+  branch?: string;
+  remoteRoot?: string;
+  profile?: string;
+  encryptionPassphrase?: string;
+  encryptionConfigured?: boolean;
   status?: WebDavSyncStatus;
 }
 
@@ -442,6 +477,10 @@ export interface Settings {
   // ===== S3 同步设置 =====
   s3Sync?: S3SyncSettings;
 
+  // ===== 本地目录 / Git 同步设置 =====
+  folderSync?: FolderSyncSettings;
+  gitSync?: GitSyncSettings;
+
   // ===== 备份策略设置 =====
   // Auto-backup interval in hours (0=disabled, default 24)
   backupIntervalHours?: number;
@@ -532,6 +571,29 @@ export interface McpServer {
 // MCP 服务器映射（id -> McpServer）
 export type McpServersMap = Record<string, McpServer>;
 
+// MCP 服务器探测结论
+export type McpProbeStatus = "ok" | "failed" | "timeout";
+
+// 最近一次 MCP 服务器探测结果（initialize + tools/list + resources/list 握手）
+export interface McpProbeResult {
+  serverId: string;
+  status: McpProbeStatus;
+  serverName?: string;
+  serverVersion?: string;
+  protocolVersion?: string;
+  tools: string[];
+  resources: string[];
+  error?: string;
+  durationMs: number;
+  probedAt: number; // Unix 秒
+}
+
+// 本地密钥库条目（只含名称；MCP 定义中以 ${secret:NAME} 引用）
+export interface McpSecretInfo {
+  name: string;
+  updatedAt: number; // Unix 秒
+}
+
 // MCP 配置状态
 export interface McpStatus {
   userConfigPath: string;
@@ -114,6 +114,8 @@ export interface FailoverQueueItem {
   providerName: string;
   providerNotes?: string;
   sortIndex?: number;
+  /** 加权路由策略下的权重（缺省为 1，0 表示仅作为故障转移备选） */
+  routingWeight?: number;
 }
 
 // 全局代理配置（统一字段，三行镜像）
@@ -138,4 +140,71 @@ export interface AppProxyConfig {
   circuitTimeoutSeconds: number;
   circuitErrorRateThreshold: number;
   circuitMinRequests: number;
+  routingStrategy?: RoutingStrategy;
+  modelRoutes?: ModelRoute[];
+  // 订阅/套餐额度阈值（百分比，0 表示关闭）
+  quotaThreshold?: number;
 }
+
+// 按请求模型分流的规则（pattern 支持 * 通配，providerIds 顺序即该规则的故障转移顺序）
+export interface ModelRoute {
+  pattern: string;
+  providerIds: string[];
+  enabled: boolean;
+}
+
+// 代理抓包：认证类请求头已脱敏，body 超过上限时截断
+export interface CapturedRequest {
+  method: string;
+  url: string;
+  headers: [string, string][];
+  body: string;
+  truncated: boolean;
+}
+
+export interface CapturedResponse {
+  status: number;
+  headers: [string, string][];
+  body: string;
+  truncated: boolean;
+}
+
+export interface CaptureAttempt {
+  providerId: string;
+  providerName: string;
+  request: CapturedRequest;
+  response?: CapturedResponse;
+}
+
+export interface ProxyCapture {
+  id: string;
+  appType?: string;
+  replayOf?: string;
+  createdAt: number;
+  inbound: CapturedRequest;
+  attempts: CaptureAttempt[];
+  clientResponse?: CapturedResponse;
+}
+
+export interface ProxyCaptureSummary {
+  id: string;
+  appType?: string;
+  providerId?: string;
+  method: string;
+  path: string;
+  statusCode?: number;
+  replayOf?: string;
+  createdAt: number;
+}
+
+export interface CaptureReplayResult {
+  captureId?: string;
+  status: number;
+}
+
+// 故障转移队列路由策略
+export type RoutingStrategy =
+  | "priority"
+  | "round_robin"
+  | "weighted"
+  | "least_latency";
@@ -129,6 +129,28 @@ export interface ModelStats {
   avgCostPerRequest: string;
 }
 
+export interface ProjectStats {
+  /** 无法归属到项目的用量为空 */
+  projectDir: string | null;
+  requestCount: number;
+  totalTokens: number;
+  totalCost: string;
+}
+
+export interface SessionStats {
+  sessionId: string;
+  appType: string;
+  projectDir: string | null;
+  requestCount: number;
+  totalTokens: number;
+  totalCost: string;
+  firstRequestAt: number;
+  lastRequestAt: number;
+  /** 传给 sessionsApi.getMessages 回看会话消息；会话日志未同步时为空 */
+  sessionProviderId: string | null;
+  sourcePath: string | null;
+}
+
 export interface LogFilters {
   appType?: string;
   providerName?: string;
@@ -138,6 +160,15 @@ export interface LogFilters {
   endDate?: number;
 }
 
+export type UsageExportFormat = "csv" | "jsonl" | "parquet";
+
+export interface UsageExportSummary {
+  filePath: string;
+  format: UsageExportFormat;
+  rollupRows: number;
+  requestRows: number;
+}
+
 /**
  * Dashboard 顶栏的全局筛选维度，作用于 Hero / 趋势图 / 三个统计 Tab。
  *
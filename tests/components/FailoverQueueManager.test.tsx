@@ -0,0 +1,104 @@
+import { fireEvent, render, screen, waitFor } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const mocks = vi.hoisted(() => ({
+  routingStrategy: "priority" as string,
+  setRoutingWeight: vi.fn(),
+}));
+
+vi.mock("react-i18next", () => ({
+  useTranslation: () => ({
+    t: (key: string, options?: Record<string, unknown> | string) =>
+      typeof options === "object" && options?.name
+        ? `${key}:${options.name}`
+        : key,
+  }),
+}));
+
+vi.mock("@/lib/query/failover", () => ({
+  useFailoverQueue: () => ({
+    data: [
+      { providerId: "a", providerName: "Relay A", routingWeight: 3 },
+      { providerId: "b", providerName: "Relay B" },
+    ],
+    isLoading: false,
+    error: null,
+  }),
+  useAvailableProvidersForFailover: () => ({
+    data: [],
+    isLoading: false,
+  }),
+  useAddToFailoverQueue: () => ({ mutateAsync: vi.fn(), isPending: false }),
+  useRemoveFromFailoverQueue: () => ({
+    mutateAsync: vi.fn(),
+    isPending: false,
+  }),
+  useAutoFailoverEnabled: () => ({ data: true }),
+  useSetAutoFailoverEnabled: () => ({ mutate: vi.fn(), isPending: false }),
+  useSetFailoverRoutingWeight: () => ({
+    mutateAsync: mocks.setRoutingWeight,
+    isPending: false,
+  }),
+}));
+
+vi.mock("@/lib/query/proxy", () => ({
+  useAppProxyConfig: () => ({
+    data: { routingStrategy: mocks.routingStrategy },
+  }),
+}));
+
+import { FailoverQueueManager } from "@/components/proxy/FailoverQueueManager";
+
+describe("FailoverQueueManager routing weights", () => {
+  beforeEach(() => {
+    mocks.routingStrategy = "priority";
+    mocks.setRoutingWeight.mockReset();
+    mocks.setRoutingWeight.mockResolvedValue(undefined);
+  });
+
+  it("hides weights unless the weighted strategy is selected", () => {
+    render(<FailoverQueueManager appType="claude" />);
+
+    expect(
+      screen.queryByLabelText("proxy.failoverQueue.weightFor:Relay A"),
+    ).not.toBeInTheDocument();
+  });
+
+  it("shows the stored weight and saves an edited one on blur", async () => {
+    mocks.routingStrategy = "weighted";
+    render(<FailoverQueueManager appType="claude" />);
+
+    const weightA = screen.getByLabelText(
+      "proxy.failoverQueue.weightFor:Relay A",
+    );
+    expect(weightA).toHaveValue(3);
+    expect(
+      screen.getByLabelText("proxy.failoverQueue.weightFor:Relay B"),
+    ).toHaveValue(1);
+
+    fireEvent.change(weightA, { target: { value: "5" } });
+    fireEvent.blur(weightA);
+
+    await waitFor(() =>
+      expect(mocks.setRoutingWeight).toHaveBeenCalledWith({
+        appType: "claude",
+        providerId: "a",
+        weight: 5,
+      }),
+    );
+  });
+
+  it("discards an out-of-range weight", () => {
+    mocks.routingStrategy = "weighted";
+    render(<FailoverQueueManager appType="claude" />);
+
+    const weightB = screen.getByLabelText(
+      "proxy.failoverQueue.weightFor:Relay B",
+    );
+    fireEvent.change(weightB, { target: { value: "-2" } });
+    fireEvent.blur(weightB);
+
+    expect(mocks.setRoutingWeight).not.toHaveBeenCalled();
+    expect(weightB).toHaveValue(1);
+  });
+});
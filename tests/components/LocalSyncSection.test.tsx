@@ -0,0 +1,301 @@
+import { render, screen, fireEvent, waitFor } from "@testing-library/react";
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import "@testing-library/jest-dom";
+import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
+
+import { LocalSyncSection } from "@/components/settings/LocalSyncSection";
+import type { FolderSyncSettings, GitSyncSettings } from "@/types";
+
+const toastSuccessMock = vi.fn();
+const toastErrorMock = vi.fn();
+const toastWarningMock = vi.fn();
+const toastInfoMock = vi.fn();
+
+vi.mock("sonner", () => ({
+  toast: {
+    success: (...args: unknown[]) => toastSuccessMock(...args),
+    error: (...args: unknown[]) => toastErrorMock(...args),
+    warning: (...args: unknown[]) => toastWarningMock(...args),
+    info: (...args: unknown[]) => toastInfoMock(...args),
+  },
+}));
+
+vi.mock("react-i18next", () => ({
+  useTranslation: () => ({
+    t: (key: string) => key,
+  }),
+}));
+
+vi.mock("@/components/ui/button", () => ({
+  Button: ({ children, ...props }: any) => <button {...props}>{children}</button>,
+}));
+
+vi.mock("@/components/ui/input", () => ({
+  Input: (props: any) => <input {...props} />,
+}));
+
+vi.mock("@/components/ui/switch", () => ({
+  Switch: ({ checked, onCheckedChange, ...props }: any) => (
+    <button
+      role="switch"
+      aria-checked={checked}
+      onClick={() => onCheckedChange?.(!checked)}
+      {...props}
+    />
+  ),
+}));
+
+vi.mock("@/components/ConfirmDialog", () => ({
+  ConfirmDialog: ({
+    isOpen,
+    title,
+    onConfirm,
+  }: {
+    isOpen: boolean;
+    title: string;
+    onConfirm: (checked: boolean) => void;
+  }) =>
+    isOpen ? (
+      <div role="dialog">
+        <span>{title}</span>
+        <button onClick={() => onConfirm(false)}>confirm</button>
+      </div>
+    ) : null,
+}));
+
+const { folderSyncApiMock, gitSyncApiMock, settingsApiMock } = vi.hoisted(
+  () => ({
+    folderSyncApiMock: {
+      testConnection: vi.fn(),
+      saveSettings: vi.fn(),
+      fetchRemoteInfo: vi.fn(),
+      upload: vi.fn(),
+      download: vi.fn(),
+    },
+    gitSyncApiMock: {
+      testConnection: vi.fn(),
+      saveSettings: vi.fn(),
+      fetchRemoteInfo: vi.fn(),
+      upload: vi.fn(),
+      download: vi.fn(),
+    },
+    settingsApiMock: {
+      pickDirectory: vi.fn(),
+    },
+  }),
+);
+
+vi.mock("@/lib/api", () => ({
+  folderSyncApi: folderSyncApiMock,
+  gitSyncApi: gitSyncApiMock,
+  settingsApi: settingsApiMock,
+}));
+
+const folderConfig: FolderSyncSettings = {
+  enabled: true,
+  autoSync: false,
+  path: "/mnt/drive/cc-switch",
+  remoteRoot: "cc-switch-sync",
+  profile: "default",
+  encryptionConfigured: true,
+  status: {},
+};
+
+const gitConfig: GitSyncSettings = {
+  enabled: false,
+  autoSync: false,
+  repoUrl: "",
+  branch: "main",
+  remoteRoot: "cc-switch-sync",
+  profile: "default",
+  status: {},
+};
+
+function renderSection(
+  folder: FolderSyncSettings = folderConfig,
+  git: GitSyncSettings = gitConfig,
+) {
+  const client = new QueryClient({
+    defaultOptions: {
+      queries: { retry: false },
+      mutations: { retry: false },
+    },
+  });
+  return render(
+    <QueryClientProvider client={client}>
+      <LocalSyncSection folderConfig={folder} gitConfig={git} />
+    </QueryClientProvider>,
+  );
+}
+
+function saveButtons() {
+  return screen.getAllByRole("button", { name: "settings.webdavSync.save" });
+}
+
+describe("LocalSyncSection", () => {
+  beforeEach(() => {
+    toastSuccessMock.mockReset();
+    toastErrorMock.mockReset();
+    toastWarningMock.mockReset();
+    toastInfoMock.mockReset();
+    for (const api of [folderSyncApiMock, gitSyncApiMock]) {
+      for (const fn of Object.values(api)) fn.mockReset();
+      api.saveSettings.mockResolvedValue({ success: true });
+      api.testConnection.mockResolvedValue({ success: true });
+      api.upload.mockResolvedValue({ status: "uploaded" });
+      api.download.mockResolvedValue({ status: "downloaded" });
+      api.fetchRemoteInfo.mockResolvedValue({
+        deviceName: "My MacBook",
+        createdAt: "2026-02-01T10:00:00Z",
+        snapshotId: "snapshot-1",
+        version: 2,
+        compatible: true,
+        artifacts: ["db.sql", "skills.zip"],
+      });
+    }
+    settingsApiMock.pickDirectory.mockReset();
+  });
+
+  it("saves folder settings without touching the stored passphrase", async () => {
+    renderSection();
+
+    expect(
+      screen.getByPlaceholderText("settings.syncEncryption.configuredPlaceholder"),
+    ).toBeInTheDocument();
+
+    fireEvent.click(saveButtons()[0]);
+
+    await waitFor(() => {
+      expect(folderSyncApiMock.saveSettings).toHaveBeenCalledTimes(1);
+    });
+    expect(folderSyncApiMock.saveSettings).toHaveBeenCalledWith(
+      expect.objectContaining({
+        enabled: true,
+        path: "/mnt/drive/cc-switch",
+        encryptionPassphrase: undefined,
+      }),
+    );
+    await waitFor(() => {
+      expect(folderSyncApiMock.testConnection).toHaveBeenCalledTimes(1);
+    });
+    expect(gitSyncApiMock.saveSettings).not.toHaveBeenCalled();
+  });
+
+  it("sends an edited folder path and passphrase", async () => {
+    renderSection();
+
+    fireEvent.change(screen.getByLabelText("settings.folderSync.path"), {
+      target: { value: "/srv/sync" },
+    });
+    fireEvent.change(
+      screen.getByPlaceholderText("settings.syncEncryption.configuredPlaceholder"),
+      { target: { value: "hunter2" } },
+    );
+    fireEvent.click(saveButtons()[0]);
+
+    await waitFor(() => {
+      expect(folderSyncApiMock.saveSettings).toHaveBeenCalledWith(
+        expect.objectContaining({
+          path: "/srv/sync",
+          encryptionPassphrase: "hunter2",
+        }),
+      );
+    });
+  });
+
+  it("fills the folder path from the directory picker", async () => {
+    settingsApiMock.pickDirectory.mockResolvedValue("/picked/dir");
+    renderSection();
+
+    fireEvent.click(
+      screen.getByRole("button", { name: "settings.localSync.browse" }),
+    );
+
+    await waitFor(() => {
+      expect(
+        (screen.getByLabelText("settings.folderSync.path") as HTMLInputElement)
+          .value,
+      ).toBe("/picked/dir");
+    });
+    expect(settingsApiMock.pickDirectory).toHaveBeenCalledWith(
+      "/mnt/drive/cc-switch",
+    );
+  });
+
+  it("requires a repository URL before saving git settings", () => {
+    renderSection();
+
+    fireEvent.click(saveButtons()[1]);
+
+    expect(toastErrorMock).toHaveBeenCalledWith(
+      "settings.gitSync.missingRequired",
+    );
+    expect(gitSyncApiMock.saveSettings).not.toHaveBeenCalled();
+  });
+
+  it("saves git repository, branch and auto sync", async () => {
+    renderSection();
+
+    fireEvent.click(
+      screen.getByRole("switch", { name: "settings.gitSync.title" }),
+    );
+    fireEvent.change(screen.getByLabelText("settings.gitSync.repoUrl"), {
+      target: { value: " git@example.com:me/sync.git " },
+    });
+    fireEvent.change(screen.getByLabelText("settings.gitSync.branch"), {
+      target: { value: "sync" },
+    });
+    fireEvent.click(
+      screen.getAllByRole("switch", { name: "settings.webdavSync.autoSync" })[1],
+    );
+    fireEvent.click(saveButtons()[1]);
+
+    await waitFor(() => {
+      expect(gitSyncApiMock.saveSettings).toHaveBeenCalledWith(
+        expect.objectContaining({
+          enabled: true,
+          autoSync: true,
+          repoUrl: "git@example.com:me/sync.git",
+          branch: "sync",
+        }),
+      );
+    });
+    expect(folderSyncApiMock.saveSettings).not.toHaveBeenCalled();
+  });
+
+  it("uploads the folder snapshot after confirmation", async () => {
+    renderSection();
+
+    fireEvent.click(
+      screen.getAllByRole("button", { name: "settings.localSync.upload" })[0],
+    );
+    expect(
+      screen.getByText("settings.localSync.confirmUploadTitle"),
+    ).toBeInTheDocument();
+    fireEvent.click(screen.getByRole("button", { name: "confirm" }));
+
+    await waitFor(() => {
+      expect(folderSyncApiMock.upload).toHaveBeenCalledTimes(1);
+    });
+    expect(toastSuccessMock).toHaveBeenCalledWith(
+      "settings.folderSync.uploadSuccess",
+    );
+  });
+
+  it("shows a hint instead of confirming download when the folder is empty", async () => {
+    folderSyncApiMock.fetchRemoteInfo.mockResolvedValue({ empty: true });
+    renderSection();
+
+    fireEvent.click(
+      screen.getAllByRole("button", { name: "settings.localSync.download" })[0],
+    );
+
+    await waitFor(() => {
+      expect(toastInfoMock).toHaveBeenCalledWith(
+        "settings.webdavSync.noRemoteData",
+      );
+    });
+    expect(folderSyncApiMock.download).not.toHaveBeenCalled();
+    expect(screen.queryByRole("dialog")).not.toBeInTheDocument();
+  });
+});
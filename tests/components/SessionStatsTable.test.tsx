@@ -0,0 +1,117 @@
+import { fireEvent, render, screen } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import { SessionStatsTable } from "@/components/usage/SessionStatsTable";
+import type { SessionStats } from "@/types/usage";
+
+const useSessionStatsMock = vi.hoisted(() => vi.fn());
+const useSessionMessagesQueryMock = vi.hoisted(() => vi.fn());
+
+vi.mock("react-i18next", () => ({
+  useTranslation: () => ({
+    t: (key: string) => key,
+    i18n: {
+      resolvedLanguage: "en",
+      language: "en",
+    },
+  }),
+}));
+
+vi.mock("@/lib/query/usage", () => ({
+  useSessionStats: (...args: unknown[]) => useSessionStatsMock(...args),
+}));
+
+vi.mock("@/lib/query/queries", () => ({
+  useSessionMessagesQuery: (...args: unknown[]) =>
+    useSessionMessagesQueryMock(...args),
+}));
+
+vi.mock("@/components/sessions/SessionMessageItem", () => ({
+  SessionMessageItem: ({ message }: any) => (
+    <div data-testid="session-message">{message.content}</div>
+  ),
+}));
+
+vi.mock("@/components/ui/dialog", () => ({
+  Dialog: ({ open, children }: any) => (open ? <div>{children}</div> : null),
+  DialogContent: ({ children }: any) => <div>{children}</div>,
+  DialogDescription: ({ children }: any) => <div>{children}</div>,
+  DialogHeader: ({ children }: any) => <div>{children}</div>,
+  DialogTitle: ({ children }: any) => <h2>{children}</h2>,
+}));
+
+const syncedSession: SessionStats = {
+  sessionId: "session-synced",
+  appType: "claude",
+  projectDir: "/work/app",
+  requestCount: 12,
+  totalTokens: 34000,
+  totalCost: "1.25",
+  firstRequestAt: 1_770_000_000,
+  lastRequestAt: 1_770_003_600,
+  sessionProviderId: "claude",
+  sourcePath: "/home/me/.claude/projects/app/session-synced.jsonl",
+};
+
+const proxyOnlySession: SessionStats = {
+  ...syncedSession,
+  sessionId: "session-proxy",
+  sessionProviderId: null,
+  sourcePath: null,
+};
+
+describe("SessionStatsTable", () => {
+  beforeEach(() => {
+    useSessionStatsMock.mockReset();
+    useSessionMessagesQueryMock.mockReset();
+    useSessionStatsMock.mockReturnValue({
+      data: [syncedSession, proxyOnlySession],
+      isLoading: false,
+    });
+    useSessionMessagesQueryMock.mockReturnValue({
+      data: [{ role: "user", content: "hello there" }],
+      isLoading: false,
+      error: null,
+    });
+  });
+
+  it("queries top sessions for the selected app and project", () => {
+    render(
+      <SessionStatsTable
+        range={{ preset: "today" }}
+        appType="claude"
+        projectDir="/work/app"
+        refreshIntervalMs={0}
+      />,
+    );
+
+    expect(useSessionStatsMock).toHaveBeenCalledWith(
+      { preset: "today" },
+      { appType: "claude", projectDir: "/work/app" },
+      expect.any(Number),
+      { refetchInterval: false },
+    );
+    expect(screen.getByText("session-synced")).toBeInTheDocument();
+  });
+
+  it("opens the session messages from the row action", () => {
+    render(
+      <SessionStatsTable range={{ preset: "today" }} refreshIntervalMs={0} />,
+    );
+
+    const [viewSynced, viewProxyOnly] = screen.getAllByRole("button", {
+      name: "usage.viewSessionMessages",
+    });
+    expect(viewProxyOnly).toBeDisabled();
+
+    fireEvent.click(viewSynced);
+
+    expect(useSessionMessagesQueryMock).toHaveBeenLastCalledWith(
+      "claude",
+      "/home/me/.claude/projects/app/session-synced.jsonl",
+    );
+    expect(screen.getByText("usage.sessionMessagesTitle")).toBeInTheDocument();
+    expect(screen.getByTestId("session-message")).toHaveTextContent(
+      "hello there",
+    );
+  });
+});
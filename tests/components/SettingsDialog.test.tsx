@@ -236,6 +236,15 @@ vi.mock("@/components/settings/WebdavSyncSection", () => ({
   ),
 }));
 
+vi.mock("@/components/settings/LocalSyncSection", () => ({
+  LocalSyncSection: ({ folderConfig, gitConfig }: any) => (
+    <div>
+      local-sync-section:{folderConfig?.path ?? "none"}:
+      {gitConfig?.repoUrl ?? "none"}
+    </div>
+  ),
+}));
+
 let settingsApi: any;
 
 const renderSettingsPage = (
@@ -0,0 +1,130 @@
+import { fireEvent, render, screen, waitFor } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const { getSharedPrompts, upsertSharedPrompt, deleteSharedPrompt } =
+  vi.hoisted(() => ({
+    getSharedPrompts: vi.fn(),
+    upsertSharedPrompt: vi.fn(),
+    deleteSharedPrompt: vi.fn(),
+  }));
+
+vi.mock("react-i18next", () => ({
+  useTranslation: () => ({
+    t: (key: string) => key,
+  }),
+}));
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn() },
+}));
+
+vi.mock("@/lib/api/prompts", () => ({
+  promptsApi: { getSharedPrompts, upsertSharedPrompt, deleteSharedPrompt },
+}));
+
+vi.mock("@/components/common/FullScreenPanel", () => ({
+  FullScreenPanel: ({
+    footer,
+    children,
+  }: {
+    footer?: React.ReactNode;
+    children: React.ReactNode;
+  }) => (
+    <div data-testid="shared-form">
+      {children}
+      {footer}
+    </div>
+  ),
+}));
+
+vi.mock("@/components/MarkdownEditor", () => ({
+  default: ({
+    value,
+    onChange,
+  }: {
+    value: string;
+    onChange: (value: string) => void;
+  }) => (
+    <textarea
+      aria-label="markdown-editor"
+      value={value}
+      onChange={(event) => onChange(event.target.value)}
+    />
+  ),
+}));
+
+vi.mock("@/components/ConfirmDialog", () => ({
+  ConfirmDialog: ({ onConfirm }: { onConfirm: (checked: boolean) => void }) => (
+    <button type="button" onClick={() => onConfirm(false)}>
+      confirm-dialog
+    </button>
+  ),
+}));
+
+import { SharedPromptsSection } from "@/components/prompts/SharedPromptsSection";
+
+const team = {
+  id: "team",
+  name: "Team",
+  content: "Team rules.",
+  apps: {
+    claude: true,
+    codex: true,
+    gemini: false,
+    grokbuild: false,
+    opencode: false,
+    openclaw: false,
+    hermes: false,
+  },
+  appendices: { claude: "Claude only.", codex: "Codex only." },
+  createdAt: 1,
+};
+
+describe("SharedPromptsSection", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    getSharedPrompts.mockResolvedValue({ team });
+    upsertSharedPrompt.mockResolvedValue(undefined);
+    deleteSharedPrompt.mockResolvedValue(undefined);
+  });
+
+  it("edits the body and this app's appendix while keeping the others", async () => {
+    const onChanged = vi.fn();
+    render(<SharedPromptsSection appId="codex" open onChanged={onChanged} />);
+
+    await screen.findByTestId("shared-prompt-team");
+    fireEvent.click(screen.getByTitle("prompts.shared.edit"));
+
+    const [content, appendix] = screen.getAllByLabelText("markdown-editor");
+    expect(content).toHaveValue("Team rules.");
+    expect(appendix).toHaveValue("Codex only.");
+    fireEvent.change(content, { target: { value: "Team rules v2." } });
+    fireEvent.change(appendix, { target: { value: "Codex v2." } });
+    fireEvent.click(screen.getByRole("button", { name: "common.save" }));
+
+    await waitFor(() => expect(upsertSharedPrompt).toHaveBeenCalledTimes(1));
+    expect(upsertSharedPrompt.mock.calls[0][0]).toMatchObject({
+      id: "team",
+      content: "Team rules v2.",
+      appendices: { claude: "Claude only.", codex: "Codex v2." },
+    });
+    await waitFor(() =>
+      expect(screen.queryByTestId("shared-form")).not.toBeInTheDocument(),
+    );
+    expect(onChanged).toHaveBeenCalled();
+  });
+
+  it("deletes a shared prompt after confirmation", async () => {
+    const onChanged = vi.fn();
+    render(<SharedPromptsSection appId="claude" open onChanged={onChanged} />);
+
+    await screen.findByTestId("shared-prompt-team");
+    fireEvent.click(screen.getByTitle("prompts.shared.delete"));
+    fireEvent.click(screen.getByRole("button", { name: "confirm-dialog" }));
+
+    await waitFor(() =>
+      expect(deleteSharedPrompt).toHaveBeenCalledWith("team"),
+    );
+    expect(onChanged).toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,122 @@
+import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
+import { fireEvent, render, screen, waitFor } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const { list, resolve, toastSuccess, toastWarning } = vi.hoisted(() => ({
+  list: vi.fn(),
+  resolve: vi.fn(),
+  toastSuccess: vi.fn(),
+  toastWarning: vi.fn(),
+}));
+
+vi.mock("react-i18next", () => ({
+  useTranslation: () => ({
+    t: (key: string) => key,
+  }),
+}));
+
+vi.mock("sonner", () => ({
+  toast: { success: toastSuccess, warning: toastWarning, error: vi.fn() },
+}));
+
+vi.mock("@/lib/api/syncConflicts", () => ({
+  syncConflictsApi: { list, resolve },
+}));
+
+import { SyncConflictsPanel } from "@/components/settings/SyncConflictsPanel";
+
+const providerConflict = {
+  id: "providers:claude/p1",
+  table: "providers",
+  key: { id: "p1", app_type: "claude" },
+  local: { id: "p1", app_type: "claude", name: "Local name", sort_index: 1 },
+  remote: { id: "p1", app_type: "claude", name: "Remote name", sort_index: 1 },
+};
+
+const deletedPromptConflict = {
+  id: "prompts:claude/pr1",
+  table: "prompts",
+  key: { id: "pr1", app_type: "claude" },
+  local: null,
+  remote: { id: "pr1", app_type: "claude", content: "remote body" },
+};
+
+function renderPanel() {
+  const client = new QueryClient({
+    defaultOptions: { queries: { retry: false } },
+  });
+  return render(
+    <QueryClientProvider client={client}>
+      <SyncConflictsPanel />
+    </QueryClientProvider>,
+  );
+}
+
+describe("SyncConflictsPanel", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("renders nothing when there are no conflicts", async () => {
+    list.mockResolvedValue([]);
+    const { container } = renderPanel();
+
+    await waitFor(() => expect(list).toHaveBeenCalled());
+    expect(container).toBeEmptyDOMElement();
+  });
+
+  it("shows only the differing fields and marks a deleted side", async () => {
+    list.mockResolvedValue([providerConflict, deletedPromptConflict]);
+    renderPanel();
+
+    const provider = await screen.findByTestId(
+      "sync-conflict-providers:claude/p1",
+    );
+    expect(provider).toHaveTextContent("Local name");
+    expect(provider).toHaveTextContent("Remote name");
+    expect(provider).not.toHaveTextContent("sort_index");
+
+    const prompt = screen.getByTestId("sync-conflict-prompts:claude/pr1");
+    expect(prompt).toHaveTextContent("remote body");
+    expect(prompt).toHaveTextContent("settings.syncConflicts.deleted");
+  });
+
+  it("resolves a conflict and refreshes the list", async () => {
+    list.mockResolvedValueOnce([providerConflict]).mockResolvedValue([]);
+    resolve.mockResolvedValue({ success: true, remaining: 0 });
+    renderPanel();
+
+    await screen.findByTestId("sync-conflict-providers:claude/p1");
+    fireEvent.click(screen.getByText("settings.syncConflicts.useRemote"));
+
+    await waitFor(() =>
+      expect(resolve).toHaveBeenCalledWith("providers:claude/p1", "remote"),
+    );
+    await waitFor(() =>
+      expect(
+        screen.queryByTestId("sync-conflict-providers:claude/p1"),
+      ).not.toBeInTheDocument(),
+    );
+    expect(toastSuccess).toHaveBeenCalledWith(
+      "settings.syncConflicts.resolved",
+    );
+  });
+
+  it("surfaces the post-sync warning returned by the backend", async () => {
+    list.mockResolvedValue([providerConflict]);
+    resolve.mockResolvedValue({
+      success: true,
+      remaining: 0,
+      warning: "live config write failed",
+    });
+    renderPanel();
+
+    await screen.findByTestId("sync-conflict-providers:claude/p1");
+    fireEvent.click(screen.getByText("settings.syncConflicts.keepLocal"));
+
+    await waitFor(() =>
+      expect(toastWarning).toHaveBeenCalledWith("live config write failed"),
+    );
+    expect(resolve).toHaveBeenCalledWith("providers:claude/p1", "local");
+  });
+});
@@ -13,10 +13,33 @@ import { UsageDashboard } from "@/components/usage/UsageDashboard";
 const useProviderStatsMock = vi.hoisted(() => vi.fn());
 const useModelStatsMock = vi.hoisted(() => vi.fn());
 const usageHeroMock = vi.hoisted(() => vi.fn());
+const sessionStatsTableMock = vi.hoisted(() => vi.fn());
+const { usageApiMock, toastSuccessMock, toastErrorMock } = vi.hoisted(() => ({
+  usageApiMock: {
+    saveExportDialog: vi.fn(),
+    exportUsageData: vi.fn(),
+    rebuildCodexUsage: vi.fn(),
+  },
+  toastSuccessMock: vi.fn(),
+  toastErrorMock: vi.fn(),
+}));
+
+vi.mock("sonner", () => ({
+  toast: {
+    success: (...args: unknown[]) => toastSuccessMock(...args),
+    error: (...args: unknown[]) => toastErrorMock(...args),
+    warning: vi.fn(),
+  },
+}));
+
+vi.mock("@/lib/api/usage", () => ({
+  usageApi: usageApiMock,
+}));
 
 vi.mock("react-i18next", () => ({
   useTranslation: () => ({
-    t: (key: string, fallback?: string) => fallback ?? key,
+    t: (key: string, fallback?: unknown) =>
+      typeof fallback === "string" ? fallback : key,
     i18n: {
       resolvedLanguage: "en",
       language: "en",
@@ -69,6 +92,43 @@ vi.mock("@/components/usage/ModelStatsTable", () => ({
   ModelStatsTable: () => <div data-testid="model-stats-table" />,
 }));
 
+vi.mock("@/components/usage/ProjectStatsTable", () => ({
+  ProjectStatsTable: ({
+    onSelectProject,
+  }: {
+    onSelectProject: (projectDir: string) => void;
+  }) => (
+    <button type="button" onClick={() => onSelectProject("/work/app")}>
+      project-row
+    </button>
+  ),
+}));
+
+vi.mock("@/components/usage/SessionStatsTable", () => ({
+  SessionStatsTable: (props: unknown) => {
+    sessionStatsTableMock(props);
+    return <div data-testid="session-stats-table" />;
+  },
+}));
+
+vi.mock("@/components/ui/tabs", () => ({
+  Tabs: ({ value, children }: any) => <div data-tab={value}>{children}</div>,
+  TabsList: ({ children }: any) => <div>{children}</div>,
+  TabsTrigger: ({ children }: any) => <div>{children}</div>,
+  TabsContent: ({ children }: any) => <div>{children}</div>,
+}));
+
+vi.mock("@/components/ui/dropdown-menu", () => ({
+  DropdownMenu: ({ children }: any) => <div>{children}</div>,
+  DropdownMenuTrigger: ({ children }: any) => <>{children}</>,
+  DropdownMenuContent: ({ children }: any) => <div>{children}</div>,
+  DropdownMenuItem: ({ children, onSelect }: any) => (
+    <button type="button" onClick={() => onSelect?.()}>
+      {children}
+    </button>
+  ),
+}));
+
 vi.mock("@/components/usage/PricingConfigPanel", () => ({
   PricingConfigPanel: () => <div data-testid="pricing-config-panel" />,
 }));
@@ -114,6 +174,11 @@ describe("UsageDashboard", () => {
     useProviderStatsMock.mockReset();
     useModelStatsMock.mockReset();
     usageHeroMock.mockReset();
+    sessionStatsTableMock.mockReset();
+    usageApiMock.saveExportDialog.mockReset();
+    usageApiMock.exportUsageData.mockReset();
+    toastSuccessMock.mockReset();
+    toastErrorMock.mockReset();
     useProviderStatsMock.mockReturnValue({ data: [] });
     useModelStatsMock.mockReturnValue({ data: [] });
   });
@@ -179,4 +244,70 @@ describe("UsageDashboard", () => {
       expect(screen.getByTestId("select-30000")).toBeInTheDocument(),
     );
   });
+
+  it("opens the sessions of a project picked in the project view", async () => {
+    renderDashboard();
+
+    fireEvent.click(screen.getByRole("button", { name: "project-row" }));
+
+    await waitFor(() =>
+      expect(sessionStatsTableMock).toHaveBeenLastCalledWith(
+        expect.objectContaining({ projectDir: "/work/app" }),
+      ),
+    );
+    expect(document.querySelector("[data-tab]")).toHaveAttribute(
+      "data-tab",
+      "sessions",
+    );
+  });
+
+  it("exports usage with the dashboard filters", async () => {
+    usageApiMock.saveExportDialog.mockResolvedValue("/tmp/usage.csv");
+    usageApiMock.exportUsageData.mockResolvedValue({
+      filePath: "/tmp/usage.csv",
+      format: "csv",
+      rollupRows: 2,
+      requestRows: 3,
+    });
+    renderDashboard();
+
+    fireEvent.click(screen.getByRole("button", { name: "usage.appFilter.pi" }));
+    fireEvent.click(
+      screen.getByRole("button", { name: "usage.export.format.csv" }),
+    );
+
+    await waitFor(() =>
+      expect(usageApiMock.exportUsageData).toHaveBeenCalledWith(
+        expect.objectContaining({
+          appType: "pi",
+          startDate: expect.any(Number),
+          endDate: expect.any(Number),
+        }),
+        "csv",
+        "/tmp/usage.csv",
+      ),
+    );
+    expect(usageApiMock.saveExportDialog).toHaveBeenCalledWith(
+      expect.stringMatching(/\.csv$/),
+      "csv",
+    );
+    expect(toastSuccessMock).toHaveBeenCalledWith("usage.export.completed");
+  });
+
+  it("does not export when the save dialog is cancelled", async () => {
+    usageApiMock.saveExportDialog.mockResolvedValue(null);
+    renderDashboard();
+
+    fireEvent.click(
+      screen.getByRole("button", { name: "usage.export.format.parquet" }),
+    );
+
+    await waitFor(() =>
+      expect(usageApiMock.saveExportDialog).toHaveBeenCalledWith(
+        expect.stringMatching(/\.parquet$/),
+        "parquet",
+      ),
+    );
+    expect(usageApiMock.exportUsageData).not.toHaveBeenCalled();
+  });
 });
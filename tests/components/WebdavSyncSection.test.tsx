@@ -328,6 +328,38 @@ describe("WebdavSyncSection", () => {
     });
   });
 
+  it("omits the passphrase until it is edited and never prefills it", async () => {
+    renderSection({ ...baseConfig, encryptionConfigured: true });
+
+    const passphrase = screen.getByPlaceholderText(
+      "settings.syncEncryption.configuredPlaceholder",
+    ) as HTMLInputElement;
+    expect(passphrase.value).toBe("");
+
+    fireEvent.click(screen.getByRole("button", { name: "settings.webdavSync.save" }));
+    await waitFor(() => {
+      expect(settingsApiMock.webdavSyncSaveSettings).toHaveBeenCalledTimes(1);
+    });
+    expect(
+      settingsApiMock.webdavSyncSaveSettings.mock.calls[0][0].encryptionPassphrase,
+    ).toBeUndefined();
+    await waitFor(() => {
+      expect(
+        screen.getByRole("button", { name: "settings.webdavSync.save" }),
+      ).not.toBeDisabled();
+    });
+
+    fireEvent.change(passphrase, { target: { value: "correct horse" } });
+    fireEvent.click(screen.getByRole("button", { name: "settings.webdavSync.save" }));
+    await waitFor(() => {
+      expect(settingsApiMock.webdavSyncSaveSettings).toHaveBeenCalledTimes(2);
+    });
+    expect(settingsApiMock.webdavSyncSaveSettings).toHaveBeenLastCalledWith(
+      expect.objectContaining({ encryptionPassphrase: "correct horse" }),
+      false,
+    );
+  });
+
   it("saves auto sync as true after toggle", async () => {
     renderSection(baseConfig);
 
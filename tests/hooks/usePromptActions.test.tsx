@@ -8,6 +8,7 @@ const mocks = vi.hoisted(() => ({
   getPrompts: vi.fn(),
   getCurrentFileContent: vi.fn(),
   enablePrompt: vi.fn(),
+  enablePromptFragment: vi.fn(),
   upsertPrompt: vi.fn(),
   deletePrompt: vi.fn(),
   toastError: vi.fn(),
@@ -19,6 +20,7 @@ vi.mock("@/lib/api", () => ({
     getPrompts: mocks.getPrompts,
     getCurrentFileContent: mocks.getCurrentFileContent,
     enablePrompt: mocks.enablePrompt,
+    enablePromptFragment: mocks.enablePromptFragment,
     upsertPrompt: mocks.upsertPrompt,
     deletePrompt: mocks.deletePrompt,
   },
@@ -77,6 +79,8 @@ describe("usePromptActions reload concurrency", () => {
     mocks.getCurrentFileContent.mockResolvedValue(null);
     mocks.enablePrompt.mockReset();
     mocks.enablePrompt.mockResolvedValue(undefined);
+    mocks.enablePromptFragment.mockReset();
+    mocks.enablePromptFragment.mockResolvedValue(undefined);
     mocks.upsertPrompt.mockReset();
     mocks.upsertPrompt.mockResolvedValue(undefined);
     mocks.deletePrompt.mockReset();
@@ -257,7 +261,7 @@ describe("usePromptActions reload concurrency", () => {
     mocks.getPrompts.mockImplementation(async (appId: AppId) =>
       appId === "claude" ? claudePrompts : codexPrompts,
     );
-    mocks.enablePrompt.mockReturnValueOnce(enableRequest.promise);
+    mocks.enablePromptFragment.mockReturnValueOnce(enableRequest.promise);
 
     const { result, rerender } = renderPromptActions("claude");
     await act(async () => {
@@ -269,7 +273,7 @@ describe("usePromptActions reload concurrency", () => {
       togglePromise = result.current.toggleEnabled("claude-prompt", true);
     });
     await waitFor(() => {
-      expect(mocks.enablePrompt).toHaveBeenCalledWith(
+      expect(mocks.enablePromptFragment).toHaveBeenCalledWith(
         "claude",
         "claude-prompt",
       );
@@ -358,10 +362,40 @@ describe("usePromptActions reload concurrency", () => {
       expect(await result.current.toggleEnabled("toggle", true)).toBe(false);
     });
 
-    expect(mocks.enablePrompt).toHaveBeenCalledWith("claude", "toggle");
+    expect(mocks.enablePromptFragment).toHaveBeenCalledWith("claude", "toggle");
     expect(result.current.prompts.toggle.enabled).toBe(true);
     expect(mocks.toastSuccess).toHaveBeenCalledWith("prompts.enableSuccess", {
       closeButton: true,
     });
   });
+
+  it("does not optimistically enable prompts for apps without fragments", async () => {
+    const enableRequest = createDeferred<void>();
+    mocks.getPrompts.mockResolvedValue(makePrompts("single", "Single Prompt"));
+    mocks.enablePromptFragment.mockReturnValueOnce(enableRequest.promise);
+
+    const { result } = renderPromptActions("claude-desktop");
+    await act(async () => {
+      expect(await result.current.reload()).toBe(true);
+    });
+
+    let togglePromise!: Promise<boolean>;
+    act(() => {
+      togglePromise = result.current.toggleEnabled("single", true);
+    });
+    await waitFor(() =>
+      expect(mocks.enablePromptFragment).toHaveBeenCalledWith(
+        "claude-desktop",
+        "single",
+      ),
+    );
+    expect(result.current.prompts.single.enabled).toBe(false);
+
+    await act(async () => {
+      enableRequest.reject(new Error("unsupported"));
+      await expect(togglePromise).rejects.toThrow("unsupported");
+    });
+    expect(result.current.prompts.single.enabled).toBe(false);
+    expect(mocks.toastError).toHaveBeenCalledWith("prompts.enableFailed");
+  });
 });
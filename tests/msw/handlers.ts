@@ -318,6 +318,12 @@ export const handlers = [
     success({ success: true }),
   ),
 
+  // Shared prompts (none configured)
+  http.post(`${TAURI_ENDPOINT}/get_shared_prompts`, () => success({})),
+
+  // Sync conflicts (none recorded)
+  http.post(`${TAURI_ENDPOINT}/sync_list_conflicts`, () => success([])),
+
   http.post(`${TAURI_ENDPOINT}/get_pi_current_state`, () =>
     success({
       enabledProviderIds: [],